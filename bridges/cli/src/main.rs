@@ -10,14 +10,23 @@
 
 use anyhow::Result;
 use clap::Parser;
-use localgpt_bridge::{BridgeServiceClient, connect};
-use rustyline::DefaultEditor;
+use localgpt_bridge::{BridgeServiceClient, SkillCommand, connect};
+use rustyline::completion::{Completer, Pair};
 use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Editor, Helper};
 use std::io::{self, Write};
 use tarpc::context;
-use tracing::{error, info};
+use tracing::{debug, error, info};
 use uuid::Uuid;
 
+/// Bridge ID this client authenticates as; the daemon gives it the chat
+/// and memory scopes by default.
+const BRIDGE_ID: &str = "bridge-cli";
+
 #[derive(Parser)]
 #[command(name = "localgpt-bridge-cli")]
 #[command(version, about = "Interactive CLI bridge for LocalGPT daemon")]
@@ -69,12 +78,13 @@ async fn main() -> Result<()> {
     };
 
     // 2. Verify protocol version (require major version 1)
-    match client.get_version(context::current()).await {
+    let version = match client.get_version(context::current()).await {
         Ok(v) => {
             let major = v.split('.').next().and_then(|s| s.parse::<u32>().ok());
             match major {
                 Some(1) => {
                     info!("Bridge protocol version: {}", v);
+                    v
                 }
                 Some(m) => {
                     eprintln!(
@@ -94,12 +104,37 @@ async fn main() -> Result<()> {
             eprintln!("Could not retrieve bridge version: {}", e);
             std::process::exit(1);
         }
+    };
+
+    // 3. Authenticate, so the daemon allows the chat and memory RPCs
+    //    (daemons before protocol 1.7 don't check)
+    let minor = version
+        .split('.')
+        .nth(1)
+        .and_then(|s| s.parse::<u32>().ok());
+    if minor.is_some_and(|m| m >= 7) {
+        match client
+            .authenticate(context::current(), BRIDGE_ID.to_string())
+            .await
+        {
+            Ok(Ok(scopes)) => {
+                debug!("Authenticated as '{}' ({})", BRIDGE_ID, scopes.join(", "));
+            }
+            Ok(Err(e)) => {
+                eprintln!("Daemon refused the CLI bridge: {}", e);
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("RPC error authenticating: {}", e);
+                std::process::exit(1);
+            }
+        }
     }
 
-    // 3. Create or use provided session ID
+    // 4. Create or use provided session ID
     let session_id = args.session.unwrap_or_else(|| Uuid::new_v4().to_string());
 
-    // 4. Initialize session
+    // 5. Initialize session
     match client
         .new_session(context::current(), session_id.clone())
         .await
@@ -117,7 +152,7 @@ async fn main() -> Result<()> {
         }
     }
 
-    // 5. Set model if specified
+    // 6. Set model if specified
     if let Some(ref model) = args.model {
         match client
             .set_model(context::current(), session_id.clone(), model.clone())
@@ -133,17 +168,94 @@ async fn main() -> Result<()> {
         "\nLocalGPT CLI Bridge | Session: {}\n",
         &session_id[..session_id.len().min(8)]
     );
-    println!("Type /help for commands, /quit to exit\n");
+    println!("Type /help for commands, /quit to exit (Tab completes commands)\n");
 
-    // 6. Interactive loop
+    // 7. Interactive loop
     run_interactive_loop(&client, &session_id).await?;
 
     println!("Goodbye!");
     Ok(())
 }
 
+/// Built-in command names offered for completion
+const BUILTIN_COMMANDS: &[&str] = &[
+    "help", "quit", "exit", "new", "status", "model", "compact", "clear", "memory", "stats", "ping",
+];
+
+/// Tab-completes `/command` names: built-ins plus the daemon's skills.
+struct SlashCompleter {
+    skills: Vec<SkillCommand>,
+}
+
+impl Completer for SlashCompleter {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let Some(partial) = line[..pos].strip_prefix('/') else {
+            return Ok((0, Vec::new()));
+        };
+        if partial.contains(char::is_whitespace) {
+            return Ok((0, Vec::new()));
+        }
+
+        let candidates = BUILTIN_COMMANDS
+            .iter()
+            .copied()
+            .chain(self.skills.iter().map(|s| s.name.as_str()))
+            .filter(|name| name.starts_with(partial))
+            .map(|name| Pair {
+                display: format!("/{}", name),
+                replacement: format!("/{} ", name),
+            })
+            .collect();
+        Ok((0, candidates))
+    }
+}
+
+impl Hinter for SlashCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for SlashCompleter {}
+
+impl Validator for SlashCompleter {}
+
+impl Helper for SlashCompleter {}
+
+/// Fetch the daemon's skill commands; older daemons without the RPC have none.
+async fn fetch_skills(client: &BridgeServiceClient) -> Vec<SkillCommand> {
+    match client.list_skills(context::current()).await {
+        Ok(Ok(skills)) => skills,
+        Ok(Err(e)) => {
+            debug!("Skills unavailable: {}", e);
+            Vec::new()
+        }
+        Err(e) => {
+            debug!("list_skills RPC failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Whether `/command` names a skill, normalized the way the daemon matches it.
+fn is_skill_command(cmd: &str, skills: &[SkillCommand]) -> bool {
+    let normalized = cmd.trim_start_matches('/').to_lowercase().replace('_', "-");
+    skills
+        .iter()
+        .any(|s| s.name.replace('_', "-") == normalized)
+}
+
 async fn run_interactive_loop(client: &BridgeServiceClient, session_id: &str) -> Result<()> {
-    let mut rl = DefaultEditor::new()?;
+    let mut skills = fetch_skills(client).await;
+    let mut rl: Editor<SlashCompleter, DefaultHistory> = Editor::new()?;
+    rl.set_helper(Some(SlashCompleter {
+        skills: skills.clone(),
+    }));
     let mut stdout = io::stdout();
 
     loop {
@@ -173,9 +285,15 @@ async fn run_interactive_loop(client: &BridgeServiceClient, session_id: &str) ->
 
         // Handle commands
         if input.starts_with('/') {
-            match handle_command(input, client, session_id).await {
+            let result = handle_command(input, client, session_id, &mut skills).await;
+            if let Some(helper) = rl.helper_mut() {
+                helper.skills.clone_from(&skills);
+            }
+            match result {
                 CommandResult::Continue => continue,
                 CommandResult::Quit => break,
+                // Skill invocations go to the daemon, which expands them
+                CommandResult::Send => {}
             }
         }
 
@@ -210,12 +328,14 @@ async fn run_interactive_loop(client: &BridgeServiceClient, session_id: &str) ->
 enum CommandResult {
     Continue,
     Quit,
+    Send,
 }
 
 async fn handle_command(
     input: &str,
     client: &BridgeServiceClient,
     session_id: &str,
+    skills: &mut Vec<SkillCommand>,
 ) -> CommandResult {
     let parts: Vec<&str> = input.split_whitespace().collect();
     let cmd = parts[0];
@@ -235,6 +355,12 @@ async fn handle_command(
             println!("  /memory <query>     - Search memory files");
             println!("  /stats              - Show memory statistics");
             println!("  /ping               - Check daemon connectivity");
+            if !skills.is_empty() {
+                println!("\nSkills:");
+                for skill in skills.iter() {
+                    println!("  {:<20}- {}", skill.usage, skill.description);
+                }
+            }
             println!();
             CommandResult::Continue
         }
@@ -244,7 +370,10 @@ async fn handle_command(
                 .new_session(context::current(), session_id.to_string())
                 .await
             {
-                Ok(Ok(msg)) => println!("\n{}\n", msg),
+                Ok(Ok(msg)) => {
+                    println!("\n{}\n", msg);
+                    *skills = fetch_skills(client).await;
+                }
                 Ok(Err(e)) => eprintln!("\nError: {}\n", e),
                 Err(e) => eprintln!("\nRPC error: {}\n", e),
             }
@@ -356,6 +485,8 @@ async fn handle_command(
             CommandResult::Continue
         }
 
+        _ if is_skill_command(cmd, skills) => CommandResult::Send,
+
         _ => {
             eprintln!(
                 "Unknown command: {}. Type /help for available commands.",
@@ -408,7 +408,10 @@ impl Handler {
             }
             "/skills" => {
                 let workspace_path = self.state.config.workspace_path();
-                match localgpt_core::agent::load_skills(&workspace_path) {
+                match localgpt_core::agent::load_skills(
+                    &workspace_path,
+                    &self.state.config.skills.trust,
+                ) {
                     Ok(skills) => {
                         if skills.is_empty() {
                             let _ = channel_id.say(&ctx.http, "No skills installed.").await;
@@ -480,6 +483,7 @@ impl Handler {
             .await
             {
                 Ok(mut agent) => {
+                    agent.set_channel("discord");
                     if let Err(err) = agent.new_session().await {
                         error!("Failed to create session: {}", err);
                         let _ = thinking_msg
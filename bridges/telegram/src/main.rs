@@ -7,12 +7,15 @@ use std::sync::Arc;
 use std::time::Instant;
 use tarpc::context;
 use teloxide::prelude::*;
-use teloxide::types::{ChatAction, MessageId, ParseMode};
+use teloxide::types::{BotCommand, ChatAction, MessageId, ParseMode};
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 
 use localgpt_bridge::connect;
-use localgpt_core::agent::{Agent, AgentConfig, StreamEvent, extract_tool_detail};
+use localgpt_core::agent::{
+    Agent, AgentConfig, Skill, StreamEvent, extract_tool_detail, load_skills, parse_skill_command,
+    skill_invocation_prompt, slash_command_skills,
+};
 use localgpt_core::concurrency::TurnGate;
 use localgpt_core::config::Config;
 use localgpt_core::memory::MemoryManager;
@@ -26,6 +29,9 @@ const MAX_MESSAGE_LENGTH: usize = 4096;
 /// Debounce interval for streaming edits (seconds)
 const EDIT_DEBOUNCE_SECS: u64 = 2;
 
+/// Maximum number of commands Telegram accepts in the bot menu
+const MAX_BOT_COMMANDS: usize = 100;
+
 #[derive(Debug, Serialize, Deserialize)]
 struct PairedUser {
     user_id: u64,
@@ -157,15 +163,7 @@ async fn main() -> Result<()> {
     });
 
     // 5. Register slash commands so clients show the "/" menu
-    let commands: Vec<teloxide::types::BotCommand> = localgpt_core::commands::COMMANDS
-        .iter()
-        .filter(|c| c.supports(localgpt_core::commands::Interface::Telegram))
-        .map(|c| teloxide::types::BotCommand::new(c.name, c.description))
-        .collect();
-
-    if let Err(e) = bot.set_my_commands(commands).await {
-        warn!("Failed to set bot commands: {}", e);
-    }
+    register_bot_commands(&bot, &config).await;
 
     info!("Telegram bot started.");
 
@@ -478,7 +476,7 @@ async fn handle_command(
         }
         "/skills" => {
             let workspace_path = state.config.workspace_path();
-            match localgpt_core::agent::load_skills(&workspace_path) {
+            match localgpt_core::agent::load_skills(&workspace_path, &state.config.skills.trust) {
                 Ok(skills) => {
                     if skills.is_empty() {
                         bot.send_message(chat_id, "No skills installed.").await?;
@@ -507,17 +505,99 @@ async fn handle_command(
             .await?;
         }
         _ => {
-            bot.send_message(
-                chat_id,
-                "Unknown command. Use /help for available commands.",
-            )
-            .await?;
+            // Skill commands: `/skill_name args` (or `/skill_name@bot args` in groups)
+            let text = text.replacen(&format!("@{}", state.bot_info.username()), "", 1);
+            let skills = load_skills(&state.config.workspace_path(), &state.config.skills.trust)
+                .unwrap_or_default();
+            let invocation = parse_skill_command(&text, &skills).and_then(|inv| {
+                skills
+                    .iter()
+                    .find(|s| s.name == inv.skill_name)
+                    .map(|skill| (skill, inv.args))
+            });
+            match invocation {
+                Some((skill, args)) => match skill_invocation_prompt(skill, &args) {
+                    Ok(prompt) => return handle_chat(bot, chat_id, state, &prompt).await,
+                    Err(e) => {
+                        bot.send_message(chat_id, e.to_string()).await?;
+                    }
+                },
+                None => {
+                    bot.send_message(
+                        chat_id,
+                        "Unknown command. Use /help for available commands.",
+                    )
+                    .await?;
+                }
+            }
         }
     }
 
     Ok(())
 }
 
+/// Telegram command names allow only lowercase letters, digits and
+/// underscores, up to 32 characters.
+fn telegram_command_name(name: &str) -> Option<String> {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c == '-' {
+                '_'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '_')
+        .take(32)
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Core Telegram commands followed by user-invocable skills.
+fn bot_commands(skills: &[Skill]) -> Vec<BotCommand> {
+    let mut commands: Vec<BotCommand> = localgpt_core::commands::COMMANDS
+        .iter()
+        .filter(|c| c.supports(localgpt_core::commands::Interface::Telegram))
+        .map(|c| BotCommand::new(c.name, c.description))
+        .collect();
+
+    for skill in slash_command_skills(skills) {
+        if commands.len() >= MAX_BOT_COMMANDS {
+            warn!(
+                "Telegram command menu full, not registering remaining skills from {}",
+                skill.name
+            );
+            break;
+        }
+        let Some(name) = telegram_command_name(&skill.command_name) else {
+            continue;
+        };
+        if commands.iter().any(|c| c.command == name) {
+            continue;
+        }
+        let description = if skill.description.is_empty() {
+            format!("Run the {} skill", skill.name)
+        } else {
+            truncate_str(&skill.description, 256).to_string()
+        };
+        commands.push(BotCommand::new(name, description));
+    }
+
+    commands
+}
+
+/// Register core commands and the currently loaded skills with Telegram.
+async fn register_bot_commands(bot: &Bot, config: &Config) {
+    let skills = load_skills(&config.workspace_path(), &config.skills.trust).unwrap_or_else(|e| {
+        warn!("Failed to load skills for bot commands: {}", e);
+        Vec::new()
+    });
+    if let Err(e) = bot.set_my_commands(bot_commands(&skills)).await {
+        warn!("Failed to set bot commands: {}", e);
+    }
+}
+
 async fn handle_chat(
     bot: &Bot,
     chat_id: ChatId,
@@ -539,10 +619,13 @@ async fn handle_chat(
 
         match Agent::new(agent_config, &state.config, Arc::new(state.memory.clone())).await {
             Ok(mut agent) => {
+                agent.set_channel("telegram");
                 if let Err(err) = agent.new_session().await {
                     bot.send_message(chat_id, format!("Error: {}", err)).await?;
                     return Ok(());
                 }
+                // Refresh the "/" menu with the skills this session loaded
+                register_bot_commands(bot, &state.config).await;
                 // Send welcome message on first run
                 if agent.is_brand_new() {
                     let html = markdown_to_html(localgpt_core::agent::FIRST_RUN_WELCOME);
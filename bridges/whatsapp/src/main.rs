@@ -169,6 +169,7 @@ async fn process_message(state: Arc<BridgeState>, chat_id: String, text: String)
 
         let mut agent =
             Agent::new(agent_config, &state.config, Arc::new(state.memory.clone())).await?;
+        agent.set_channel("whatsapp");
         agent.new_session().await?;
 
         e.insert(SessionEntry {
@@ -4,7 +4,10 @@ pub mod protocol;
 pub use interprocess::local_socket::tokio::{LocalSocketListener, LocalSocketStream};
 
 // Re-export protocol
-pub use protocol::{BRIDGE_PROTOCOL_VERSION, BridgeError, BridgeService, BridgeServiceClient};
+pub use protocol::{
+    BRIDGE_PROTOCOL_VERSION, BridgeConnection, BridgeError, BridgeService, BridgeServiceClient,
+    BridgeStatusReport, CronJobChanges, CronJobSpec, CronJobStatus, SkillCommand,
+};
 
 use futures::StreamExt;
 use tarpc::server::{BaseChannel, Channel};
@@ -6,10 +6,10 @@ pub struct PeerIdentity {
 }
 
 #[cfg(unix)]
-pub use self::unix::get_peer_identity;
+pub use self::unix::{get_peer_identity, peer_executable};
 
 #[cfg(windows)]
-pub use self::windows::get_peer_identity;
+pub use self::windows::{get_peer_identity, peer_executable};
 
 #[cfg(unix)]
 mod unix {
@@ -35,6 +35,12 @@ mod unix {
         })
     }
 
+    /// Path of the peer's executable, from `/proc/<pid>/exe`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn peer_executable(identity: &PeerIdentity) -> Option<std::path::PathBuf> {
+        std::fs::read_link(format!("/proc/{}/exe", identity.pid?)).ok()
+    }
+
     #[cfg(any(
         target_os = "macos",
         target_os = "ios",
@@ -51,12 +57,51 @@ mod unix {
             return Err(io::Error::last_os_error());
         }
 
+        // The PID is only needed to find the peer's executable, so a
+        // failure here isn't fatal
+        let mut pid: libc::pid_t = 0;
+        let mut len = std::mem::size_of::<libc::pid_t>() as libc::socklen_t;
+        let ret = unsafe {
+            libc::getsockopt(
+                fd,
+                libc::SOL_LOCAL,
+                libc::LOCAL_PEERPID,
+                &mut pid as *mut libc::pid_t as *mut libc::c_void,
+                &mut len,
+            )
+        };
+
         Ok(PeerIdentity {
             uid: Some(uid as u32),
             gid: Some(gid as u32),
-            pid: None,
+            pid: (ret == 0).then_some(pid),
         })
     }
+
+    /// Path of the peer's executable, from `proc_pidpath`.
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "watchos"
+    ))]
+    pub fn peer_executable(identity: &PeerIdentity) -> Option<std::path::PathBuf> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut buf = vec![0u8; libc::PROC_PIDPATHINFO_MAXSIZE as usize];
+        let len = unsafe {
+            libc::proc_pidpath(
+                identity.pid?,
+                buf.as_mut_ptr() as *mut libc::c_void,
+                buf.len() as u32,
+            )
+        };
+        if len <= 0 {
+            return None;
+        }
+        buf.truncate(len as usize);
+        Some(std::ffi::OsStr::from_bytes(&buf).into())
+    }
 }
 
 #[cfg(windows)]
@@ -83,4 +128,32 @@ mod windows {
             Err(io::Error::last_os_error())
         }
     }
+
+    /// Path of the peer's executable, from `QueryFullProcessImageNameW`.
+    pub fn peer_executable(identity: &PeerIdentity) -> Option<std::path::PathBuf> {
+        use std::os::windows::ffi::OsStringExt;
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Threading::{
+            OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+            QueryFullProcessImageNameW,
+        };
+        use windows::core::PWSTR;
+
+        let pid = u32::try_from(identity.pid?).ok()?;
+        let mut buf = vec![0u16; 1024];
+        let mut len = buf.len() as u32;
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let result = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buf.as_mut_ptr()),
+                &mut len,
+            );
+            let _ = CloseHandle(process);
+            result.ok()?;
+        }
+        buf.truncate(len as usize);
+        Some(std::ffi::OsString::from_wide(&buf).into())
+    }
 }
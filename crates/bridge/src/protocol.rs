@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 /// Current bridge protocol version.
 /// Increment the minor version for backward-compatible additions,
 /// and the major version for breaking changes.
-pub const BRIDGE_PROTOCOL_VERSION: &str = "1.1";
+pub const BRIDGE_PROTOCOL_VERSION: &str = "1.8";
 
 #[derive(Debug, thiserror::Error, Serialize, Deserialize)]
 pub enum BridgeError {
@@ -19,6 +19,80 @@ pub enum BridgeError {
     NotSupported(String),
 }
 
+/// A user-invocable skill offered as a slash command (added in 1.2).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillCommand {
+    /// Command name without the leading slash
+    pub name: String,
+    pub description: String,
+    /// Usage line, e.g. `/github-pr title=<string> [base=main]`
+    pub usage: String,
+}
+
+/// A cron job definition (added in 1.3).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronJobSpec {
+    pub name: String,
+    /// Cron expression ("0 */6 * * *") or interval ("every 30m")
+    pub schedule: String,
+    /// Prompt sent to a fresh agent session
+    pub prompt: String,
+    pub channel: Option<String>,
+    pub enabled: bool,
+    /// Job timeout (e.g. "10m")
+    pub timeout: String,
+}
+
+/// A cron job with its source and next run (added in 1.3).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronJobStatus {
+    pub job: CronJobSpec,
+    /// "config" (read-only, from config.toml) or "runtime"
+    pub source: String,
+    /// Next run time (RFC 3339), if enabled and not paused
+    pub next_run: Option<String>,
+    /// Scheduled runs are skipped while paused (added in 1.4)
+    #[serde(default)]
+    pub paused: bool,
+}
+
+/// Changes to a runtime cron job; `None` fields are unchanged (added in 1.3).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CronJobChanges {
+    pub schedule: Option<String>,
+    pub prompt: Option<String>,
+    pub channel: Option<String>,
+    pub enabled: Option<bool>,
+    pub timeout: Option<String>,
+}
+
+/// A client connected to the bridge socket (added in 1.5).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeConnection {
+    pub connection_id: String,
+    /// Bridge ID, once the client has asked for its credentials
+    pub bridge_id: Option<String>,
+    /// When the client connected (RFC 3339)
+    pub connected_at: String,
+    /// Last RPC from the client (RFC 3339)
+    pub last_active: String,
+    pub pid: Option<i32>,
+    pub uid: Option<u32>,
+    /// "healthy", "degraded", or "unhealthy"
+    pub health: String,
+    /// Health checks failed in a row
+    pub consecutive_failures: u32,
+}
+
+/// Bridge connections and stored credentials (added in 1.5).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeStatusReport {
+    /// Other clients connected to the socket, not including the caller
+    pub connections: Vec<BridgeConnection>,
+    /// Bridge IDs with registered credentials
+    pub registered: Vec<String>,
+}
+
 #[tarpc::service]
 pub trait BridgeService {
     /// Returns the server's protocol version string (e.g. "1.1").
@@ -28,7 +102,8 @@ pub trait BridgeService {
     /// Liveness check. Returns `true` if the server is healthy.
     async fn ping() -> bool;
 
-    /// Retrieve encrypted credentials for the given bridge ID.
+    /// Retrieve encrypted credentials for the given bridge ID. Also
+    /// authenticates the connection as that bridge (see `authenticate`).
     async fn get_credentials(bridge_id: String) -> Result<Vec<u8>, BridgeError>;
 
     // -- Agent session RPCs (added in 1.1) --
@@ -57,4 +132,64 @@ pub trait BridgeService {
 
     /// Get memory statistics.
     async fn memory_stats() -> Result<String, BridgeError>;
+
+    // -- Skill RPCs (added in 1.2) --
+
+    /// List skills that can be invoked as slash commands. A `chat` message
+    /// of the form `/name args` invokes the matching skill.
+    async fn list_skills() -> Result<Vec<SkillCommand>, BridgeError>;
+
+    // -- Cron RPCs (added in 1.3) --
+
+    /// List cron jobs from config.toml and the runtime jobs file.
+    async fn list_cron_jobs() -> Result<Vec<CronJobStatus>, BridgeError>;
+
+    /// Add a runtime cron job.
+    async fn add_cron_job(job: CronJobSpec) -> Result<(), BridgeError>;
+
+    /// Modify, enable, or disable a runtime cron job.
+    async fn update_cron_job(
+        name: String,
+        changes: CronJobChanges,
+    ) -> Result<CronJobSpec, BridgeError>;
+
+    /// Remove a runtime cron job.
+    async fn remove_cron_job(name: String) -> Result<(), BridgeError>;
+
+    // -- Cron control RPCs (added in 1.4) --
+
+    /// Pause a cron job (config or runtime) so its scheduled runs are skipped.
+    async fn pause_cron_job(name: String) -> Result<(), BridgeError>;
+
+    /// Resume a paused cron job.
+    async fn resume_cron_job(name: String) -> Result<(), BridgeError>;
+
+    /// Run a cron job on the scheduler's next tick.
+    async fn run_cron_job(name: String) -> Result<(), BridgeError>;
+
+    // -- Status RPCs (added in 1.5) --
+
+    /// Connected bridges with their health, and the registered bridge IDs.
+    async fn bridge_status() -> Result<BridgeStatusReport, BridgeError>;
+
+    // -- Cancellation RPCs (added in 1.6) --
+
+    /// Stop the `chat` running on `session_id`; that call then fails with
+    /// "Turn cancelled". Returns `false` if no chat was running.
+    async fn cancel_chat(session_id: String) -> Result<bool, BridgeError>;
+
+    // -- Scope RPCs (added in 1.7) --
+
+    /// Authenticate the connection as `bridge_id` without fetching
+    /// credentials, for bridges that have none. The bridge's executable
+    /// must be approved for that ID. Other RPCs are then allowed by the
+    /// bridge's scopes, which are returned ("chat", "memory", ...).
+    async fn authenticate(bridge_id: String) -> Result<Vec<String>, BridgeError>;
+
+    // -- Admin RPCs (added in 1.8) --
+
+    /// Give the connection every scope. `token` is the daemon's current
+    /// admin token, which it writes at start to a file only the user can
+    /// read; the `localgpt` CLI presents it.
+    async fn authenticate_admin(token: String) -> Result<(), BridgeError>;
 }
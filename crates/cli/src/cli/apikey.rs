@@ -0,0 +1,121 @@
+//! CLI subcommand: `localgpt apikey`
+//!
+//! Creates, lists, and revokes the API keys accepted by the HTTP server.
+//! Keys are stored hashed in the state directory and carry scopes (`chat`,
+//! `memory-read`, `admin`) that limit which routes they can call. Keys
+//! created with `--user` only see that user's sessions.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+
+use localgpt_core::config::Config;
+use localgpt_core::security::{ApiKeyStore, ApiScope};
+
+#[derive(Args)]
+pub struct ApiKeyArgs {
+    #[command(subcommand)]
+    pub command: ApiKeyCommands,
+}
+
+#[derive(Subcommand)]
+pub enum ApiKeyCommands {
+    /// Create a key; it is printed once and can't be shown again
+    Create {
+        /// Name to identify the key by
+        name: String,
+
+        /// Scope to grant: chat, memory-read, or admin (repeatable)
+        #[arg(short, long = "scope", default_value = "chat")]
+        scopes: Vec<String>,
+
+        /// User the key acts as; users see only their own sessions
+        #[arg(short, long)]
+        user: Option<String>,
+    },
+    /// List keys with their scopes
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Revoke a key by name or prefix
+    Revoke {
+        /// Key name, or the prefix shown by `apikey list`
+        name: String,
+    },
+}
+
+pub async fn run(args: ApiKeyArgs) -> Result<()> {
+    let config = Config::load()?;
+    let store = ApiKeyStore::new(&config.paths.state_dir);
+
+    match args.command {
+        ApiKeyCommands::Create { name, scopes, user } => {
+            let scopes = scopes
+                .iter()
+                .map(|s| ApiScope::parse(s))
+                .collect::<Result<Vec<_>>>()?;
+            let (key, secret) = store.create(&name, scopes, user.as_deref())?;
+            match &key.user {
+                Some(user) => println!(
+                    "Created API key '{}' for user '{}' ({})",
+                    key.name,
+                    user,
+                    scope_list(&key.scopes)
+                ),
+                None => println!(
+                    "Created API key '{}' ({})",
+                    key.name,
+                    scope_list(&key.scopes)
+                ),
+            }
+            println!();
+            println!("  {}", secret);
+            println!();
+            println!("Store it now; it can't be shown again. Send it as:");
+            println!("  Authorization: Bearer {}", secret);
+        }
+        ApiKeyCommands::List { json } => {
+            let keys = store.list()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&keys)?);
+                return Ok(());
+            }
+            if keys.is_empty() {
+                println!("No API keys. Create one with: localgpt apikey create <name>");
+                return Ok(());
+            }
+            println!("API keys:");
+            println!();
+            for key in &keys {
+                let user = key
+                    .user
+                    .as_ref()
+                    .map(|u| format!("  user {}", u))
+                    .unwrap_or_default();
+                println!(
+                    "  {} {}…  [{}]{}  created {}",
+                    key.name,
+                    key.prefix,
+                    scope_list(&key.scopes),
+                    user,
+                    key.created_at.format("%Y-%m-%d %H:%M")
+                );
+            }
+        }
+        ApiKeyCommands::Revoke { name } => {
+            let key = store.revoke(&name)?;
+            println!("Revoked API key '{}'", key.name);
+        }
+    }
+
+    Ok(())
+}
+
+fn scope_list(scopes: &[ApiScope]) -> String {
+    scopes
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use clap::Args;
+use clap_complete::engine::ArgValueCandidates;
 use std::sync::Arc;
 
 use futures::StreamExt;
@@ -11,13 +12,15 @@ use localgpt_core::config::Config;
 use localgpt_core::memory::MemoryManager;
 use std::io::Write;
 
+use super::completions;
+
 #[derive(Args)]
 pub struct AskArgs {
     /// The question or task to perform
     pub question: String,
 
     /// Model to use (overrides config)
-    #[arg(short, long)]
+    #[arg(short, long, add = ArgValueCandidates::new(completions::model_names))]
     pub model: Option<String>,
 
     /// Output format: text (default) or json
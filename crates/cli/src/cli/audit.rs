@@ -0,0 +1,294 @@
+//! CLI subcommand: `localgpt audit`
+//!
+//! `audit list` and `audit search` query the security audit log (policy
+//! signing, blocked writes, approvals, ...), including rotated segments
+//! with `--archives`; `audit rotate` archives the current log.
+//! `audit tools` inspects the tool usage audit log: every tool invocation
+//! made by an agent, with its arguments, duration, outcome, session, and
+//! channel. `audit sampling` totals MCP sampling requests and tokens per
+//! server.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Args, Subcommand};
+
+use localgpt_core::config::{Config, parse_duration};
+use localgpt_core::mcp::sampling::{read_sampling_log, usage_by_server};
+use localgpt_core::security::{
+    self, AuditAction, AuditEntry, AuditQuery, AuditRotation, ToolAuditQuery, ToolOutcome,
+};
+
+#[derive(Args)]
+pub struct AuditArgs {
+    #[command(subcommand)]
+    pub command: AuditCommands,
+}
+
+#[derive(Subcommand)]
+pub enum AuditCommands {
+    /// Show security audit log entries
+    List {
+        #[command(flatten)]
+        filter: AuditFilter,
+    },
+    /// Show security audit log entries mentioning a pattern
+    Search {
+        /// Text to look for in the action, source, or detail (case-insensitive)
+        pattern: String,
+
+        #[command(flatten)]
+        filter: AuditFilter,
+    },
+    /// Archive the security audit log now, or only if due with --if-due
+    Rotate {
+        /// Only rotate if the log is past `[security.audit]` limits
+        #[arg(long)]
+        if_due: bool,
+    },
+    /// Show recorded tool invocations
+    Tools {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Only show calls to this tool (e.g., bash, web_fetch)
+        #[arg(long)]
+        tool: Option<String>,
+
+        /// Only show calls from this session ID
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Only show calls from this channel (cli, http, telegram, ...)
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Only show failed calls
+        #[arg(long)]
+        errors: bool,
+
+        /// Show at most this many of the most recent entries
+        #[arg(short = 'n', long, default_value = "50")]
+        limit: usize,
+    },
+    /// Show MCP sampling requests and token usage per server
+    Sampling {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Args)]
+pub struct AuditFilter {
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Only show entries since a time: a duration ago (24h, 7d), a date
+    /// (2026-01-31), or an RFC 3339 timestamp
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Only show these actions (e.g., write_blocked); repeatable
+    #[arg(long = "action")]
+    actions: Vec<String>,
+
+    /// Only show entries whose source starts with this (e.g., cli, tool:)
+    #[arg(long)]
+    source: Option<String>,
+
+    /// Also read rotated audit log segments
+    #[arg(long)]
+    archives: bool,
+
+    /// Show at most this many of the most recent entries
+    #[arg(short = 'n', long, default_value = "50")]
+    limit: usize,
+}
+
+impl AuditFilter {
+    fn into_query(self, text: Option<String>) -> Result<(bool, AuditQuery)> {
+        let since = self.since.as_deref().map(parse_since).transpose()?;
+        let actions = self
+            .actions
+            .iter()
+            .map(|a| a.parse::<AuditAction>())
+            .collect::<Result<Vec<_>>>()?;
+        let query = AuditQuery {
+            since,
+            actions,
+            source: self.source,
+            text,
+            archives: self.archives,
+            limit: Some(self.limit),
+        };
+        Ok((self.json, query))
+    }
+}
+
+pub async fn run(args: AuditArgs) -> Result<()> {
+    match args.command {
+        AuditCommands::List { filter } => {
+            let (json, query) = filter.into_query(None)?;
+            show_audit(json, &query)
+        }
+        AuditCommands::Search { pattern, filter } => {
+            let (json, query) = filter.into_query(Some(pattern))?;
+            show_audit(json, &query)
+        }
+        AuditCommands::Rotate { if_due } => rotate(if_due),
+        AuditCommands::Tools {
+            json,
+            tool,
+            session,
+            channel,
+            errors,
+            limit,
+        } => {
+            let query = ToolAuditQuery {
+                tool,
+                session,
+                channel,
+                outcome: errors.then_some(ToolOutcome::Error),
+                limit: Some(limit),
+            };
+            show_tools(json, &query)
+        }
+        AuditCommands::Sampling { json } => show_sampling(json),
+    }
+}
+
+/// Parse `--since` as a duration ago, a date (midnight UTC), or a timestamp.
+fn parse_since(since: &str) -> Result<DateTime<Utc>> {
+    if let Ok(ago) = parse_duration(since) {
+        return Ok(Utc::now() - ago);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    DateTime::parse_from_rfc3339(since)
+        .map(|ts| ts.with_timezone(&Utc))
+        .map_err(|_| {
+            anyhow!(
+                "Invalid --since '{}': expected a duration (24h, 7d), a date, or an RFC 3339 timestamp",
+                since
+            )
+        })
+}
+
+fn show_audit(json_output: bool, query: &AuditQuery) -> Result<()> {
+    let config = Config::load()?;
+    let entries = security::query_audit_log(&config.paths.state_dir, query)?;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No matching audit log entries.");
+        return Ok(());
+    }
+
+    println!("Security Audit Log ({} entries):", entries.len());
+    println!();
+    for entry in &entries {
+        print_entry(entry);
+    }
+
+    Ok(())
+}
+
+fn print_entry(entry: &AuditEntry) {
+    let action = serde_json::to_string(&entry.action).unwrap_or_default();
+    let detail = entry
+        .detail
+        .as_deref()
+        .map(|d| format!(" — {}", d))
+        .unwrap_or_default();
+    println!(
+        "  {} {} (source: {}){}",
+        entry.ts,
+        action.trim_matches('"'),
+        entry.source,
+        detail
+    );
+}
+
+fn rotate(if_due: bool) -> Result<()> {
+    let config = Config::load()?;
+    let rotation = AuditRotation::from_config(&config.security.audit)?;
+    match security::rotate_audit_log(&config.paths.state_dir, &rotation, !if_due)? {
+        Some(archive) => println!("Audit log archived to {}", archive.display()),
+        None if if_due => println!("Audit log is not due for rotation."),
+        None => println!("Audit log is empty; nothing to rotate."),
+    }
+    Ok(())
+}
+
+fn show_sampling(json_output: bool) -> Result<()> {
+    let config = Config::load()?;
+    let usage = usage_by_server(&read_sampling_log(&config.paths.state_dir)?);
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&usage)?);
+        return Ok(());
+    }
+
+    if usage.is_empty() {
+        println!("No MCP sampling requests.");
+        return Ok(());
+    }
+
+    println!("MCP Sampling Usage:");
+    println!();
+    for server in &usage {
+        println!(
+            "  {}: {} request(s), {} denied, {} in / {} out tokens",
+            server.server,
+            server.requests,
+            server.denied,
+            server.input_tokens,
+            server.output_tokens
+        );
+    }
+
+    Ok(())
+}
+
+fn show_tools(json_output: bool, query: &ToolAuditQuery) -> Result<()> {
+    let config = Config::load()?;
+    let entries = security::query_tool_audit_log(&config.paths.state_dir, query)?;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No tool audit entries.");
+        if !config.tools.audit_log {
+            println!("  Tool auditing is disabled (tools.audit_log = false).");
+        }
+        return Ok(());
+    }
+
+    println!("Tool Audit Log ({} entries):", entries.len());
+    println!();
+
+    for entry in &entries {
+        let outcome = match entry.outcome {
+            ToolOutcome::Ok => "ok".to_string(),
+            ToolOutcome::Error => format!("ERROR: {}", entry.error.as_deref().unwrap_or("")),
+        };
+        let session: String = entry.session.chars().take(8).collect();
+        println!(
+            "  {} [{}:{}] {} ({}ms) {}",
+            entry.ts, entry.channel, session, entry.tool, entry.duration_ms, outcome
+        );
+        println!("      {}", entry.args);
+    }
+
+    Ok(())
+}
@@ -246,7 +246,7 @@ fn generate_pkce() -> (String, String) {
     (challenge, verifier)
 }
 
-fn wait_for_callback(port: u16, expected_state: &str) -> Result<String> {
+pub(crate) fn wait_for_callback(port: u16, expected_state: &str) -> Result<String> {
     let listener = TcpListener::bind(format!("127.0.0.1:{}", port)).with_context(|| {
         format!(
             "Failed to bind to port {}. Is another instance running?",
@@ -1,6 +1,14 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use clap::{Args, Subcommand};
+use localgpt_bridge::BridgeStatusReport;
+use localgpt_core::paths::Paths;
 use localgpt_server::BridgeManager;
+use localgpt_server::security::{
+    BridgeApprovals, BridgeScope, BridgeScopes, approve_bridge_exe, join_scopes,
+    resolve_bridge_request, revoke_bridge, set_bridge_scopes,
+};
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Args)]
 pub struct BridgeArgs {
@@ -19,19 +27,296 @@ pub enum BridgeCommands {
         /// Secret key/token for the bridge
         #[arg(long)]
         secret: String,
+
+        /// Keep the secret in the OS keyring instead of an encrypted file
+        #[arg(long)]
+        keyring: bool,
+    },
+
+    /// Show the bridges connected to the daemon and the registered credentials
+    Status {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Let a bridge executable that asked for credentials have them
+    Approve {
+        /// Bridge ID (e.g., "telegram")
+        id: String,
+
+        /// Only approve the request with this fingerprint
+        #[arg(long, conflicts_with = "exe")]
+        fingerprint: Option<String>,
+
+        /// Approve this executable ahead of its first connection
+        #[arg(long)]
+        exe: Option<PathBuf>,
+    },
+
+    /// Refuse a bridge executable's pending request
+    Deny {
+        /// Bridge ID
+        id: String,
+
+        /// Only deny the request with this fingerprint
+        #[arg(long)]
+        fingerprint: Option<String>,
+    },
+
+    /// Remove a bridge's approvals; its next connection must be approved again
+    Revoke {
+        /// Bridge ID
+        id: String,
+    },
+
+    /// Show or set which RPCs a bridge may call
+    Scopes {
+        /// Bridge ID
+        id: String,
+
+        /// Scopes to grant, replacing the current ones (credentials, chat,
+        /// memory, cron, status, admin)
+        scopes: Vec<BridgeScope>,
+
+        /// Go back to the default scopes
+        #[arg(long, conflicts_with = "scopes")]
+        reset: bool,
     },
 }
 
 pub async fn run(args: BridgeArgs) -> Result<()> {
     match args.command {
-        BridgeCommands::Register { id, secret } => {
+        BridgeCommands::Register {
+            id,
+            secret,
+            keyring,
+        } => {
             let manager = BridgeManager::new();
-            manager.register_bridge(&id, secret.as_bytes()).await?;
+            if keyring {
+                manager
+                    .register_bridge_in_keyring(&id, secret.as_bytes())
+                    .await?;
+            } else {
+                manager.register_bridge(&id, secret.as_bytes()).await?;
+            }
             // Note: Logging is handled by the core logging system, initialized in main.
             // But we can print to stdout for CLI feedback.
             println!("Bridge '{}' registered successfully.", id);
             println!("You may need to restart the daemon for changes to take effect.");
+            println!(
+                "Approve the bridge when it first connects with 'localgpt bridge approve {}'.",
+                id
+            );
+        }
+        BridgeCommands::Status { json } => {
+            let report = fetch_status().await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_status(&report);
+                print_approvals(&BridgeApprovals::load(&Paths::resolve()?.data_dir)?);
+            }
+        }
+        BridgeCommands::Approve {
+            id, exe: Some(exe), ..
+        } => {
+            let paths = Paths::resolve()?;
+            let exe = std::fs::canonicalize(&exe).unwrap_or(exe);
+            let peer = approve_bridge_exe(&paths, &id, &exe, "cli")?;
+            println!(
+                "Approved bridge '{}' from {} (fingerprint {})",
+                peer.bridge_id,
+                peer.exe_display(),
+                peer.fingerprint
+            );
+        }
+        BridgeCommands::Approve {
+            id,
+            fingerprint,
+            exe: None,
+        } => {
+            let paths = Paths::resolve()?;
+            for peer in resolve_bridge_request(&paths, &id, fingerprint.as_deref(), true, "cli")? {
+                println!(
+                    "Approved bridge '{}' from {} (fingerprint {})",
+                    peer.bridge_id,
+                    peer.exe_display(),
+                    peer.fingerprint
+                );
+            }
+            println!("Start the bridge again if it has exited.");
+        }
+        BridgeCommands::Deny { id, fingerprint } => {
+            let paths = Paths::resolve()?;
+            for peer in resolve_bridge_request(&paths, &id, fingerprint.as_deref(), false, "cli")? {
+                println!(
+                    "Denied bridge '{}' from {} (fingerprint {})",
+                    peer.bridge_id,
+                    peer.exe_display(),
+                    peer.fingerprint
+                );
+            }
+        }
+        BridgeCommands::Revoke { id } => {
+            let removed = revoke_bridge(&Paths::resolve()?, &id, "cli")?;
+            if removed.is_empty() {
+                println!("Bridge '{}' has no approvals.", id);
+            } else {
+                println!(
+                    "Revoked {} approval(s) for bridge '{}'. A running bridge keeps its credentials until it restarts.",
+                    removed.len(),
+                    id
+                );
+            }
+        }
+        BridgeCommands::Scopes { id, scopes, reset } => {
+            let paths = Paths::resolve()?;
+            if reset || !scopes.is_empty() {
+                let scopes = set_bridge_scopes(&paths, &id, (!reset).then_some(scopes), "cli")?;
+                println!("Bridge '{}' may now call: {}", id, join_scopes(&scopes));
+                println!("Connected bridges pick up the change on their next call.");
+            } else {
+                let all = BridgeScopes::load(&paths.data_dir)?;
+                let default = if all.bridges.contains_key(&id) {
+                    ""
+                } else {
+                    " (default)"
+                };
+                println!(
+                    "Bridge '{}' may call: {}{}",
+                    id,
+                    join_scopes(&all.scopes_for(&id)),
+                    default
+                );
+            }
         }
     }
     Ok(())
 }
+
+fn print_approvals(approvals: &BridgeApprovals) {
+    if !approvals.pending.is_empty() {
+        println!("\nWaiting for approval:");
+        for request in &approvals.pending {
+            println!(
+                "  {:<16} {}  fingerprint {}",
+                request.peer.bridge_id,
+                request.peer.exe_display(),
+                request.peer.fingerprint
+            );
+        }
+        println!(
+            "Approve with 'localgpt bridge approve <id>' or refuse with 'localgpt bridge deny <id>'."
+        );
+    }
+    if !approvals.approved.is_empty() {
+        println!("\nApproved executables:");
+        for approved in &approvals.approved {
+            println!(
+                "  {:<16} {}  fingerprint {}",
+                approved.peer.bridge_id,
+                approved.peer.exe_display(),
+                approved.peer.fingerprint
+            );
+        }
+    }
+}
+
+/// Ask the daemon over the bridge socket for its connections.
+pub(crate) async fn fetch_status() -> Result<BridgeStatusReport> {
+    let paths = Paths::resolve()?;
+    let daemon_running = std::fs::read_to_string(paths.pid_file())
+        .is_ok_and(|pid| super::daemon::is_process_running(pid.trim()));
+    if !daemon_running {
+        bail!("Daemon is not running. Start it with 'localgpt daemon start'.");
+    }
+
+    let socket = paths.bridge_socket_name();
+    let connect = localgpt_bridge::connect(&socket);
+    let client = match tokio::time::timeout(Duration::from_secs(2), connect).await {
+        Ok(Ok(client)) => client,
+        Ok(Err(e)) => bail!("Cannot connect to bridge socket {}: {}", socket, e),
+        Err(_) => bail!("Bridge socket {} did not accept a connection", socket),
+    };
+
+    // bridge_status was added in protocol 1.5, authenticate_admin in 1.8
+    let version = client.get_version(tarpc::context::current()).await?;
+    let parsed = version
+        .split_once('.')
+        .and_then(|(major, minor)| Some((major.parse::<u32>().ok()?, minor.parse::<u32>().ok()?)))
+        .unwrap_or((0, 0));
+    if parsed < (1, 5) {
+        bail!(
+            "The daemon speaks bridge protocol {}; restart it to get bridge status",
+            version
+        );
+    }
+    if parsed >= (1, 8) {
+        let token = std::fs::read_to_string(paths.bridge_admin_token())
+            .map_err(|e| anyhow::anyhow!("Cannot read the daemon's bridge admin token: {}", e))?;
+        client
+            .authenticate_admin(tarpc::context::current(), token.trim().to_string())
+            .await?
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+    }
+
+    client
+        .bridge_status(tarpc::context::current())
+        .await?
+        .map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+fn print_status(report: &BridgeStatusReport) {
+    let now = chrono::Utc::now();
+    let ago = |time: &str| {
+        chrono::DateTime::parse_from_rfc3339(time)
+            .map(|t| {
+                let secs = (now - t.with_timezone(&chrono::Utc)).num_seconds().max(0);
+                format!("{} ago", super::status::format_uptime(secs as u64))
+            })
+            .unwrap_or_else(|_| time.to_string())
+    };
+
+    println!("Connected bridges: {}", report.connections.len());
+    for conn in &report.connections {
+        let process = match (conn.pid, conn.uid) {
+            (Some(pid), Some(uid)) => format!("PID {}, UID {}", pid, uid),
+            (Some(pid), None) => format!("PID {}", pid),
+            (None, Some(uid)) => format!("UID {}", uid),
+            (None, None) => "-".to_string(),
+        };
+        print!(
+            "  {:<16} {:<10} active {:<14} connected {:<14} {}",
+            conn.bridge_id.as_deref().unwrap_or("(unidentified)"),
+            conn.health,
+            ago(&conn.last_active),
+            ago(&conn.connected_at),
+            process
+        );
+        if conn.consecutive_failures > 0 {
+            print!(" ({} failed health checks)", conn.consecutive_failures);
+        }
+        println!();
+    }
+
+    if report.registered.is_empty() {
+        println!("\nRegistered credentials: none");
+        return;
+    }
+    println!("\nRegistered credentials: {}", report.registered.join(", "));
+    let disconnected: Vec<&str> = report
+        .registered
+        .iter()
+        .filter(|id| {
+            !report
+                .connections
+                .iter()
+                .any(|c| c.bridge_id.as_deref() == Some(id.as_str()))
+        })
+        .map(String::as_str)
+        .collect();
+    if !disconnected.is_empty() {
+        println!("Not connected: {}", disconnected.join(", "));
+    }
+}
@@ -1,20 +1,97 @@
 use anyhow::Result;
 use clap::Args;
+use clap_complete::engine::ArgValueCandidates;
 use futures::StreamExt;
 use rustyline::DefaultEditor;
 use rustyline::error::ReadlineError;
 use std::io::{self, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::debug;
 
+use localgpt_core::agent::attachments::{attachment_budget, build_message};
 use localgpt_core::agent::{
-    Agent, AgentConfig, ImageAttachment, Skill, create_spawn_agent_tool, extract_tool_detail,
+    Agent, AgentConfig, Attachment, Skill, create_spawn_agent_tool, extract_tool_detail,
     get_last_session_id_for_agent, get_skills_summary, list_sessions_for_agent, load_skills,
-    parse_skill_command, search_sessions_for_agent,
+    parse_skill_command, search_sessions_for_agent, skill_invocation_prompt,
 };
-use localgpt_core::concurrency::WorkspaceLock;
+use localgpt_core::concurrency::{CancellationToken, WorkspaceLock, is_cancelled};
 use localgpt_core::config::Config;
+use localgpt_core::mcp::sampling::{SamplingApprover, set_sampling_approver};
 use localgpt_core::memory::MemoryManager;
+use localgpt_core::security::{PendingWrite, WriteApprover, add_write_approver};
+
+use super::completions;
+
+/// Asks on the terminal before an MCP server with `sampling = "ask"` runs a
+/// completion.
+struct TerminalSamplingApprover;
+
+impl SamplingApprover for TerminalSamplingApprover {
+    fn approve(&self, server: &str, preview: &str) -> bool {
+        println!("\n[MCP server '{}' requests a completion]", server);
+        println!("{}", preview);
+        print!("Allow? [y/N]: ");
+        if io::stdout().flush().is_err() {
+            return false;
+        }
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).is_err() {
+            return false;
+        }
+        matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+}
+
+/// Asks on the terminal before the agent writes to a protected file.
+struct TerminalWriteApprover;
+
+impl WriteApprover for TerminalWriteApprover {
+    fn name(&self) -> &str {
+        "cli"
+    }
+
+    fn ask(&self, write: &PendingWrite) -> Option<bool> {
+        println!(
+            "\n[{} wants to change protected file {}]",
+            write.tool,
+            write.path.display()
+        );
+        println!("{}", write.preview);
+        print!("Allow this write? [y/N]: ");
+        if io::stdout().flush().is_err() {
+            return Some(false);
+        }
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).is_err() {
+            return Some(false);
+        }
+        Some(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+    }
+}
+
+/// Cancels the agent's turn on Ctrl-C until dropped
+struct CtrlCCancel(tokio::task::JoinHandle<()>);
+
+impl CtrlCCancel {
+    /// Give `agent` a fresh cancellation token for the next turn and cancel
+    /// it if Ctrl-C is pressed
+    fn new(agent: &mut Agent) -> Self {
+        let token = CancellationToken::new();
+        agent.set_cancel_token(token.clone());
+        Self(tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                token.cancel();
+            }
+        }))
+    }
+}
+
+impl Drop for CtrlCCancel {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
 
 /// Adjust a byte index to the nearest valid UTF-8 char boundary (searching forward).
 fn floor_char_boundary(s: &str, index: usize) -> usize {
@@ -104,20 +181,34 @@ fn extract_snippet(content: &str, query: &str, max_len: usize) -> String {
 #[derive(Args)]
 pub struct ChatArgs {
     /// Model to use (overrides config)
-    #[arg(short, long)]
+    #[arg(short, long, add = ArgValueCandidates::new(completions::model_names))]
     pub model: Option<String>,
 
     /// Session ID to resume
-    #[arg(short, long)]
+    #[arg(short, long, add = ArgValueCandidates::new(completions::session_ids))]
     pub session: Option<String>,
 
     /// Resume the most recent session
     #[arg(long)]
     pub resume: bool,
+
+    /// Full-screen terminal UI instead of the line REPL
+    #[arg(long)]
+    pub tui: bool,
+
+    /// Attach a file to the first message (repeatable). Images go to the
+    /// model as image inputs; other files are inlined as text.
+    #[arg(long = "attach", value_name = "FILE", conflicts_with = "tui")]
+    pub attach: Vec<PathBuf>,
 }
 
 pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
     let config = Config::load()?;
+    let mut pending_attachments = args
+        .attach
+        .iter()
+        .map(|path| Attachment::load(path))
+        .collect::<Result<Vec<_>>>()?;
     // Embedding provider is automatically created based on config.memory.embedding_provider
     let memory = Arc::new(MemoryManager::new_with_full_config(
         &config.memory,
@@ -131,6 +222,11 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
         reserve_tokens: config.agent.reserve_tokens,
     };
 
+    set_sampling_approver(Arc::new(TerminalSamplingApprover));
+    if !args.tui {
+        // The TUI asks in its own pane
+        add_write_approver(Arc::new(TerminalWriteApprover));
+    }
     let mut agent = Agent::new(agent_config, &config, Arc::clone(&memory)).await?;
     agent.extend_tools(crate::tools::create_cli_tools(&config)?);
     // Add spawn_agent tool for hierarchical delegation
@@ -168,9 +264,13 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
         agent.new_session().await?;
     }
 
+    if args.tui {
+        return super::tui::run(agent, agent_id, workspace_lock).await;
+    }
+
     // Load skills from workspace
     let workspace = config.workspace_path();
-    let skills = load_skills(&workspace).unwrap_or_default();
+    let skills = load_skills(&workspace, &config.skills.trust_policy()).unwrap_or_default();
     let skills_count = skills.iter().filter(|s| s.eligibility.is_ready()).count();
 
     let embedding_status = if agent.has_embeddings() {
@@ -209,12 +309,12 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
     let mut rl = DefaultEditor::new()?;
     let mut stdout = io::stdout();
 
-    // Track pending file attachments (text and images)
-    enum Attachment {
-        Text { name: String, content: String },
-        Image { name: String, data: ImageAttachment },
+    for attachment in &pending_attachments {
+        print_attached(attachment);
+    }
+    if !pending_attachments.is_empty() {
+        println!("Type your message to send with attachment(s), or /attachments to list.\n");
     }
-    let mut pending_attachments: Vec<Attachment> = Vec::new();
 
     loop {
         let readline = rl.readline("You: ");
@@ -253,71 +353,15 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
                 }
                 let file_path = parts[1..].join(" ");
                 let expanded = shellexpand::tilde(&file_path).to_string();
-                let path = std::path::Path::new(&expanded);
-                let filename = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or(&file_path)
-                    .to_string();
-
-                // Check if it's an image file
-                let ext = path
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .map(|e| e.to_lowercase());
-
-                let is_image = matches!(
-                    ext.as_deref(),
-                    Some("png") | Some("jpg") | Some("jpeg") | Some("gif") | Some("webp")
-                );
-
-                if is_image {
-                    // Read as binary and encode as base64
-                    match std::fs::read(&expanded) {
-                        Ok(bytes) => {
-                            use base64::{Engine as _, engine::general_purpose::STANDARD};
-                            let data = STANDARD.encode(&bytes);
-                            let media_type = match ext.as_deref() {
-                                Some("png") => "image/png",
-                                Some("jpg") | Some("jpeg") => "image/jpeg",
-                                Some("gif") => "image/gif",
-                                Some("webp") => "image/webp",
-                                _ => "application/octet-stream",
-                            }
-                            .to_string();
-
-                            let size = bytes.len();
-                            pending_attachments.push(Attachment::Image {
-                                name: filename.clone(),
-                                data: ImageAttachment { data, media_type },
-                            });
-                            println!("Attached image: {} ({} bytes)", filename, size);
-                            println!(
-                                "Type your message to send with attachment(s), or /attachments to list.\n"
-                            );
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to read image file: {}", e);
-                        }
-                    }
-                } else {
-                    // Read as text
-                    match std::fs::read_to_string(&expanded) {
-                        Ok(content) => {
-                            let size = content.len();
-                            pending_attachments.push(Attachment::Text {
-                                name: filename.clone(),
-                                content,
-                            });
-                            println!("Attached: {} ({} bytes)", filename, size);
-                            println!(
-                                "Type your message to send with attachment(s), or /attachments to list.\n"
-                            );
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to read file: {}", e);
-                        }
+                match Attachment::load(std::path::Path::new(&expanded)) {
+                    Ok(attachment) => {
+                        print_attached(&attachment);
+                        pending_attachments.push(attachment);
+                        println!(
+                            "Type your message to send with attachment(s), or /attachments to list.\n"
+                        );
                     }
+                    Err(e) => eprintln!("Failed to attach {}: {}", file_path, e),
                 }
                 continue;
             }
@@ -365,6 +409,7 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
                     print!("\nLocalGPT: ");
                     stdout.flush().ok();
                     let _lock_guard = workspace_lock.acquire()?;
+                    let _ctrl_c = CtrlCCancel::new(&mut agent);
                     match agent.chat(&msg).await {
                         Ok(response) => {
                             println!("{}\n", response);
@@ -372,6 +417,7 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
                                 eprintln!("Warning: Failed to auto-save session: {}", e);
                             }
                         }
+                        Err(e) if is_cancelled(&e) => println!("(cancelled)\n"),
                         Err(e) => {
                             eprintln!("Error: {}\n", e);
                         }
@@ -385,39 +431,27 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
             }
         }
 
-        // Build message with attachments
-        let mut message = input.to_string();
-        let mut images: Vec<ImageAttachment> = Vec::new();
-
-        if !pending_attachments.is_empty() {
-            let mut text_attachments = Vec::new();
-
-            // Separate text and image attachments
-            for att in pending_attachments.drain(..) {
-                match att {
-                    Attachment::Text { name, content } => {
-                        text_attachments.push((name, content));
-                    }
-                    Attachment::Image { data, .. } => {
-                        images.push(data);
-                    }
-                }
-            }
-
-            // Add text attachments to message
-            if !text_attachments.is_empty() {
-                message.push_str("\n\n---\n\n**Attached files:**\n");
-                for (name, content) in &text_attachments {
-                    message.push_str(&format!("\n### {}\n```\n{}\n```\n", name, content));
-                }
-            }
+        // Build message with attachments, fitted to the free context
+        let (used, usable, _) = agent.context_usage();
+        let message = build_message(
+            input,
+            std::mem::take(&mut pending_attachments),
+            attachment_budget(used, usable),
+        );
+        for notice in &message.truncated {
+            eprintln!("Truncated attachment {}", notice);
         }
+        let images = message.images;
+        let message = message.text;
 
         // Send message to agent with streaming
         print!("\nLocalGPT: ");
         stdout.flush()?;
 
         let _lock_guard = workspace_lock.acquire()?;
+        // Ctrl-C stops the turn and drops it from the session
+        let _ctrl_c = CtrlCCancel::new(&mut agent);
+        let before = agent.session_status();
         match agent.chat_stream_with_images(&message, images).await {
             Ok(mut stream) => {
                 let mut full_response = String::new();
@@ -435,12 +469,18 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
                                 pending_tool_calls = chunk.tool_calls;
                             }
                         }
+                        Err(e) if is_cancelled(&e) => break,
                         Err(e) => {
                             eprintln!("\nStream error: {}", e);
                             break;
                         }
                     }
                 }
+                if agent.is_cancelled() {
+                    agent.rewind_session(&before);
+                    println!("\n(cancelled)\n");
+                    continue;
+                }
 
                 // Handle tool calls if any
                 if let Some(tool_calls) = pending_tool_calls {
@@ -514,6 +554,10 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
                                 print!("\nLocalGPT: {}", follow_up);
                                 stdout.flush()?;
                             }
+                            Err(e) if is_cancelled(&e) => {
+                                agent.rewind_session(&before);
+                                println!("\n(cancelled)");
+                            }
                             Err(e) => {
                                 eprintln!("Tool execution error: {}", e);
                             }
@@ -533,6 +577,10 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
                 }
                 println!("\n");
             }
+            Err(e) if is_cancelled(&e) => {
+                agent.rewind_session(&before);
+                println!("(cancelled)\n");
+            }
             Err(e) => {
                 eprintln!("Error: {}\n", e);
             }
@@ -543,6 +591,23 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
     Ok(())
 }
 
+fn print_attached(attachment: &Attachment) {
+    match attachment {
+        Attachment::Text { name, content } => println!(
+            "Attached: {} ({} bytes, ~{} tokens)",
+            name,
+            content.len(),
+            attachment.estimated_tokens()
+        ),
+        Attachment::Image { name, data } => println!(
+            "Attached image: {} ({}, {} bytes encoded)",
+            name,
+            data.media_type,
+            data.data.len()
+        ),
+    }
+}
+
 enum CommandResult {
     Continue,
     Quit,
@@ -899,17 +964,9 @@ async fn handle_command(
             if let Some(invocation) = parse_skill_command(input, skills) {
                 // Find the skill to get its path
                 if let Some(skill) = skills.iter().find(|s| s.name == invocation.skill_name) {
-                    let skill_prompt = if invocation.args.is_empty() {
-                        format!(
-                            "Use the skill at {}. Read it first, then follow its instructions.",
-                            skill.path.display()
-                        )
-                    } else {
-                        format!(
-                            "Use the skill at {} with this request: {}\n\nRead the skill file first, then follow its instructions.",
-                            skill.path.display(),
-                            invocation.args
-                        )
+                    let skill_prompt = match skill_invocation_prompt(skill, &invocation.args) {
+                        Ok(prompt) => prompt,
+                        Err(e) => return CommandResult::Error(e.to_string()),
                     };
                     println!(
                         "\nInvoking skill: {} {}",
@@ -0,0 +1,100 @@
+//! CLI subcommand: `localgpt completions`
+//!
+//! Prints a shell completion script. By default the script calls back into
+//! `localgpt` while completing, so session IDs and model names come from the
+//! current config and session files. `--static` prints a self-contained
+//! script that completes only subcommands and flags.
+
+use std::io;
+
+use anyhow::{Result, anyhow};
+use clap::{Args, CommandFactory};
+use clap_complete::Shell;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::Shells;
+
+use localgpt_core::agent::{DEFAULT_AGENT_ID, list_sessions_for_agent};
+use localgpt_core::config::Config;
+
+/// Environment variable that puts `localgpt` into completion mode
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Short model aliases the providers accept
+const MODEL_ALIASES: &[&str] = &["opus", "sonnet", "gpt", "gpt-mini", "glm", "grok", "codex"];
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for
+    pub shell: Shell,
+
+    /// Complete only subcommands and flags, without calling localgpt
+    #[arg(long = "static")]
+    pub static_only: bool,
+}
+
+pub fn run(args: CompletionsArgs) -> Result<()> {
+    let mut stdout = io::stdout();
+
+    if args.static_only {
+        let mut cmd = super::Cli::command();
+        clap_complete::generate(args.shell, &mut cmd, "localgpt", &mut stdout);
+        return Ok(());
+    }
+
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&args.shell.to_string())
+        .ok_or_else(|| anyhow!("Dynamic completion is not supported for {}", args.shell))?;
+    completer.write_registration(
+        COMPLETE_VAR,
+        "localgpt",
+        "localgpt",
+        "localgpt",
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+/// Saved session IDs of the agent in `LOCALGPT_AGENT` (or the default),
+/// described by their titles
+pub fn session_ids() -> Vec<CompletionCandidate> {
+    let agent_id = std::env::var("LOCALGPT_AGENT").unwrap_or_else(|_| DEFAULT_AGENT_ID.to_string());
+    list_sessions_for_agent(&agent_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| CompletionCandidate::new(s.id).help(s.title.map(Into::into)))
+        .collect()
+}
+
+/// Model aliases plus the models named in the config
+pub fn model_names() -> Vec<CompletionCandidate> {
+    known_models()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Model aliases plus the models named in the config, sorted
+pub fn known_models() -> Vec<String> {
+    let mut models: Vec<String> = MODEL_ALIASES.iter().map(|m| m.to_string()).collect();
+
+    // Don't create a config file just to complete a word
+    let config = Config::config_path()
+        .ok()
+        .filter(|path| path.exists())
+        .and_then(|_| Config::load().ok());
+    if let Some(config) = config {
+        models.push(config.agent.default_model.clone());
+        models.extend(config.agent.fallback_models.iter().cloned());
+        if let Some(ollama) = &config.providers.ollama {
+            models.push(format!("ollama/{}", ollama.model));
+        }
+        if let Some(claude_cli) = &config.providers.claude_cli {
+            models.push(format!("claude-cli/{}", claude_cli.model));
+        }
+    }
+
+    models.sort();
+    models.dedup();
+    models
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
 
-use localgpt_core::config::Config;
+use localgpt_core::config::{Config, set_config_value, validate_config_file};
 
 #[derive(Args)]
 pub struct ConfigArgs {
@@ -18,21 +18,24 @@ pub enum ConfigCommands {
         format: String,
     },
 
-    /// Get a configuration value
+    /// Get a configuration value, or a whole section
     Get {
-        /// Config key (e.g., agent.default_model)
+        /// Config key (e.g., agent.default_model or server)
         key: String,
     },
 
-    /// Set a configuration value
+    /// Set a configuration value, keeping the file's comments
     Set {
         /// Config key (e.g., agent.default_model)
         key: String,
 
-        /// Value to set
+        /// Value to set; parsed as the setting's type (TOML syntax for arrays)
         value: String,
     },
 
+    /// Check the config file for syntax errors, bad values, and unknown keys
+    Validate,
+
     /// Show config file path
     Path,
 
@@ -49,6 +52,7 @@ pub async fn run(args: ConfigArgs) -> Result<()> {
         ConfigCommands::Show { format } => show_config(&format),
         ConfigCommands::Get { key } => get_config(&key),
         ConfigCommands::Set { key, value } => set_config(&key, &value),
+        ConfigCommands::Validate => validate_config(),
         ConfigCommands::Path => show_path(),
         ConfigCommands::Init { force } => init_config(force),
     }
@@ -79,10 +83,45 @@ fn get_config(key: &str) -> Result<()> {
 }
 
 fn set_config(key: &str, value: &str) -> Result<()> {
-    let mut config = Config::load()?;
-    config.set_value(key, value)?;
-    config.save()?;
-    println!("Set {} = {}", key, value);
+    let path = Config::config_path()?;
+    if !path.exists() {
+        // Start from the commented template rather than an empty file
+        Config::load()?;
+    }
+    let written = set_config_value(&path, key, value)?;
+    println!("Set {} = {}", key, written);
+    Ok(())
+}
+
+fn validate_config() -> Result<()> {
+    let path = Config::config_path()?;
+    if !path.exists() {
+        println!("No config file at {}; defaults apply", path.display());
+        return Ok(());
+    }
+
+    let issues = validate_config_file(&path)?;
+    for issue in &issues {
+        let level = if issue.is_error { "error" } else { "warning" };
+        match &issue.key {
+            Some(key) => println!("{}: {}: {}", level, key, issue.message),
+            None => println!("{}: {}", level, issue.message),
+        }
+    }
+
+    let errors = issues.iter().filter(|i| i.is_error).count();
+    if errors > 0 {
+        anyhow::bail!("{} is invalid", path.display());
+    }
+    println!(
+        "{} is valid{}",
+        path.display(),
+        if issues.is_empty() {
+            String::new()
+        } else {
+            format!(" ({} warnings)", issues.len())
+        }
+    );
     Ok(())
 }
 
@@ -114,7 +153,7 @@ fn init_config(force: bool) -> Result<()> {
     Ok(())
 }
 
-const DEFAULT_CONFIG_TEMPLATE: &str = r#"# LocalGPT Configuration
+pub(crate) const DEFAULT_CONFIG_TEMPLATE: &str = r#"# LocalGPT Configuration
 
 [agent]
 # Default model: claude-cli/opus, anthropic/claude-sonnet-4-5, openai/gpt-4o, etc.
@@ -0,0 +1,605 @@
+//! CLI subcommand: `localgpt cron`
+//!
+//! Manages runtime cron jobs in the cron jobs file (see `localgpt paths`).
+//! A running daemon picks up changes on its next scheduler tick. Jobs from
+//! `[[cron.jobs]]` in config.toml are listed but can only be changed there.
+//! Any job can be paused, resumed, or queued to run now with `cron pause`,
+//! `cron resume`, and `cron run`. `cron history` shows recorded runs and
+//! `cron next` the upcoming ones. Jobs with `--trigger` also run on a new
+//! file, a webhook hit, or a memory change. Every subcommand takes `--json`.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::{Args, Subcommand};
+use serde_json::json;
+
+use localgpt_core::config::{CatchUpPolicy, Config, CronJob, CronRetryPolicy, JobTrigger};
+use localgpt_core::cron::history::{CronRunStatus, read_cron_history};
+use localgpt_core::cron::{CronJobStore, CronJobUpdate};
+
+#[derive(Args)]
+pub struct CronArgs {
+    #[command(subcommand)]
+    pub command: CronCommands,
+
+    /// Output as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+}
+
+#[derive(Subcommand)]
+pub enum CronCommands {
+    /// List cron jobs and their next run times
+    List {
+        /// Show one-shot jobs that have already run
+        #[arg(long)]
+        archived: bool,
+    },
+    /// Show upcoming scheduled runs, soonest first
+    Next {
+        /// Only runs of this job (default: all jobs)
+        job: Option<String>,
+
+        /// Number of upcoming runs to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Add a cron job
+    Add {
+        /// Unique job name
+        name: String,
+
+        /// Cron expression ("0 */6 * * *"), interval ("every 30m"), natural
+        /// language ("every weekday at 9am"), or one-shot ("in 45 minutes",
+        /// "at 2025-07-01 09:00"); may be omitted with --trigger
+        #[arg(short, long, default_value = "", required_unless_present = "trigger")]
+        schedule: String,
+
+        /// Prompt to send to a fresh agent session
+        #[arg(short, long)]
+        prompt: String,
+
+        /// Telegram channel/chat to route output to
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Job timeout (e.g. "5m", "1h")
+        #[arg(long, default_value = "10m")]
+        timeout: String,
+
+        /// Add the job disabled
+        #[arg(long)]
+        disabled: bool,
+
+        /// Runs missed while the daemon was down: skip, run-once, run-all
+        #[arg(long, value_parser = parse_catch_up, default_value = "skip")]
+        catch_up: CatchUpPolicy,
+
+        /// Random delay of up to this long before each run (e.g. "2m")
+        #[arg(long)]
+        jitter: Option<String>,
+
+        /// Model for this job (default: agent.default_model)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Only allow this tool (repeatable; "memory_*" matches a prefix)
+        #[arg(long = "tool")]
+        tools: Vec<String>,
+
+        /// Only offer this skill (repeatable)
+        #[arg(long = "skill")]
+        skills: Vec<String>,
+
+        /// Memory namespace (default: "cron-<name>")
+        #[arg(long)]
+        memory_namespace: Option<String>,
+
+        /// Total attempts per run, including the first
+        #[arg(long, default_value_t = 1)]
+        max_attempts: u32,
+
+        /// Delay before the first retry, doubling after each attempt
+        #[arg(long, default_value = "1m")]
+        retry_backoff: String,
+
+        /// Also run on an event: "file:<dir>[/<glob>]", "webhook:<secret>",
+        /// or "memory"
+        #[arg(long, value_parser = parse_trigger)]
+        trigger: Option<JobTrigger>,
+    },
+    /// Modify a cron job
+    Edit {
+        /// Job name
+        name: String,
+
+        /// New schedule
+        #[arg(short, long)]
+        schedule: Option<String>,
+
+        /// New prompt
+        #[arg(short, long)]
+        prompt: Option<String>,
+
+        /// New output channel (empty to clear)
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// New timeout
+        #[arg(long)]
+        timeout: Option<String>,
+
+        /// New catch-up policy: skip, run-once, run-all
+        #[arg(long, value_parser = parse_catch_up)]
+        catch_up: Option<CatchUpPolicy>,
+
+        /// New maximum random start delay (empty to clear)
+        #[arg(long)]
+        jitter: Option<String>,
+
+        /// New model (empty for the default model)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Replace the allowed tools (repeatable)
+        #[arg(long = "tool", conflicts_with = "all_tools")]
+        tools: Vec<String>,
+
+        /// Remove the tool restriction
+        #[arg(long)]
+        all_tools: bool,
+
+        /// Replace the offered skills (repeatable)
+        #[arg(long = "skill", conflicts_with = "all_skills")]
+        skills: Vec<String>,
+
+        /// Offer all skills again
+        #[arg(long)]
+        all_skills: bool,
+
+        /// New memory namespace (empty for the default)
+        #[arg(long)]
+        memory_namespace: Option<String>,
+
+        /// New total attempts per run
+        #[arg(long)]
+        max_attempts: Option<u32>,
+
+        /// New delay before the first retry
+        #[arg(long)]
+        retry_backoff: Option<String>,
+
+        /// New event trigger
+        #[arg(long, value_parser = parse_trigger, conflicts_with = "no_trigger")]
+        trigger: Option<JobTrigger>,
+
+        /// Remove the event trigger
+        #[arg(long)]
+        no_trigger: bool,
+    },
+    /// Enable a cron job
+    Enable {
+        /// Job name
+        name: String,
+    },
+    /// Disable a cron job
+    Disable {
+        /// Job name
+        name: String,
+    },
+    /// Pause a cron job; scheduled runs are skipped until it is resumed
+    Pause {
+        /// Job name
+        name: String,
+    },
+    /// Resume a paused cron job
+    Resume {
+        /// Job name
+        name: String,
+    },
+    /// Run a cron job now (on the daemon's next scheduler tick)
+    Run {
+        /// Job name
+        name: String,
+    },
+    /// Remove a cron job
+    #[command(alias = "remove")]
+    Rm {
+        /// Job name
+        name: String,
+    },
+    /// Show recent runs
+    History {
+        /// Only runs of this job (default: all jobs)
+        job: Option<String>,
+
+        /// Number of most recent runs to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+
+        /// Print full recorded output
+        #[arg(long)]
+        output: bool,
+    },
+}
+
+pub async fn run(args: CronArgs) -> Result<()> {
+    let config = Config::load()?;
+    let store = CronJobStore::from_config(&config)?;
+    let json = args.json;
+
+    match args.command {
+        CronCommands::List { archived: false } => list(&store, json),
+        CronCommands::List { archived: true } => list_archived(&store, json),
+        CronCommands::Next { job, limit } => next(&store, job.as_deref(), limit, json),
+        CronCommands::Add {
+            name,
+            schedule,
+            prompt,
+            channel,
+            timeout,
+            disabled,
+            catch_up,
+            jitter,
+            model,
+            tools,
+            skills,
+            memory_namespace,
+            max_attempts,
+            retry_backoff,
+            trigger,
+        } => {
+            store.add(CronJob {
+                name: name.clone(),
+                schedule,
+                prompt,
+                channel,
+                enabled: !disabled,
+                timeout,
+                catch_up,
+                jitter,
+                model,
+                tools,
+                skills,
+                memory_namespace,
+                retry: CronRetryPolicy {
+                    max_attempts,
+                    backoff: retry_backoff,
+                },
+                trigger,
+            })?;
+            done(json, &name, "added", format!("Added cron job '{}'", name))
+        }
+        CronCommands::Edit {
+            name,
+            schedule,
+            prompt,
+            channel,
+            timeout,
+            catch_up,
+            jitter,
+            model,
+            tools,
+            all_tools,
+            skills,
+            all_skills,
+            memory_namespace,
+            max_attempts,
+            retry_backoff,
+            trigger,
+            no_trigger,
+        } => {
+            let job = store.update(
+                &name,
+                CronJobUpdate {
+                    schedule,
+                    prompt,
+                    channel,
+                    enabled: None,
+                    timeout,
+                    catch_up,
+                    jitter,
+                    model,
+                    tools: list_change(tools, all_tools),
+                    skills: list_change(skills, all_skills),
+                    memory_namespace,
+                    max_attempts,
+                    retry_backoff,
+                    trigger,
+                    remove_trigger: no_trigger,
+                },
+            )?;
+            done(
+                json,
+                &job.name,
+                "updated",
+                format!("Updated cron job '{}'", job.name),
+            )
+        }
+        CronCommands::Enable { name } => {
+            store.set_enabled(&name, true)?;
+            done(
+                json,
+                &name,
+                "enabled",
+                format!("Enabled cron job '{}'", name),
+            )
+        }
+        CronCommands::Disable { name } => {
+            store.set_enabled(&name, false)?;
+            done(
+                json,
+                &name,
+                "disabled",
+                format!("Disabled cron job '{}'", name),
+            )
+        }
+        CronCommands::Pause { name } => {
+            store.pause(&name)?;
+            done(json, &name, "paused", format!("Paused cron job '{}'", name))
+        }
+        CronCommands::Resume { name } => {
+            store.resume(&name)?;
+            done(
+                json,
+                &name,
+                "resumed",
+                format!("Resumed cron job '{}'", name),
+            )
+        }
+        CronCommands::Run { name } => {
+            store.request_run(&name)?;
+            let message = format!(
+                "Queued cron job '{}'; the daemon runs it within 30 seconds",
+                name
+            );
+            done(json, &name, "queued", message)
+        }
+        CronCommands::Rm { name } => {
+            store.remove(&name)?;
+            done(
+                json,
+                &name,
+                "removed",
+                format!("Removed cron job '{}'", name),
+            )
+        }
+        CronCommands::History { job, limit, output } => {
+            history(&config, job.as_deref(), limit, output, json)
+        }
+    }
+}
+
+/// Report a change to a job, as text or as `{"job": ..., "action": ...}`.
+fn done(json: bool, name: &str, action: &str, message: String) -> Result<()> {
+    if json {
+        println!("{}", json!({ "job": name, "action": action }));
+    } else {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+/// Time until `at`, e.g. "in 2h 13m"; "due now" once it has passed.
+fn until(at: DateTime<Local>) -> String {
+    let secs = (at - Local::now()).num_seconds();
+    if secs <= 0 {
+        "due now".to_string()
+    } else {
+        format!("in {}", super::status::format_uptime(secs as u64))
+    }
+}
+
+fn next(store: &CronJobStore, job: Option<&str>, limit: usize, json: bool) -> Result<()> {
+    let runs = store.upcoming(job, limit)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&runs)?);
+        return Ok(());
+    }
+    if runs.is_empty() {
+        match job {
+            Some(job) => println!("Cron job '{}' has no scheduled runs.", job),
+            None => println!("No scheduled cron runs."),
+        }
+        return Ok(());
+    }
+
+    for run in &runs {
+        println!(
+            "{}  {:<12} {}",
+            run.at.format("%Y-%m-%d %H:%M"),
+            until(run.at),
+            run.job
+        );
+    }
+    Ok(())
+}
+
+fn history(
+    config: &Config,
+    job: Option<&str>,
+    limit: usize,
+    output: bool,
+    json: bool,
+) -> Result<()> {
+    let runs = read_cron_history(&config.paths.state_dir, job, Some(limit))?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&runs)?);
+        return Ok(());
+    }
+    if runs.is_empty() {
+        match job {
+            Some(job) => println!("No recorded runs for cron job '{}'.", job),
+            None => println!("No recorded cron runs."),
+        }
+        return Ok(());
+    }
+
+    for run in &runs {
+        let mark = match run.status {
+            CronRunStatus::Ok => "✓",
+            CronRunStatus::Error | CronRunStatus::TimedOut => "✗",
+        };
+        println!(
+            "{} {} {} [{}] {:.1}s, {} in / {} out tokens",
+            mark,
+            run.started_at,
+            run.job,
+            run.status.as_str(),
+            run.duration_ms as f64 / 1000.0,
+            run.input_tokens,
+            run.output_tokens
+        );
+        if run.attempts > 1 {
+            println!("    attempts: {}", run.attempts);
+        }
+        if let Some(event) = &run.event {
+            println!("    event: {}", event);
+        }
+        if let Some(error) = &run.error {
+            println!("    error: {}", error);
+        }
+        if output && !run.output.is_empty() {
+            for line in run.output.lines() {
+                println!("    {}", line);
+            }
+        } else if !run.output.is_empty() {
+            let preview: String = run.output.chars().take(100).collect();
+            println!("    {}", preview.replace('\n', " "));
+        }
+    }
+    Ok(())
+}
+
+/// `Some(list)` to replace a job's list, `Some(empty)` to clear it, `None`
+/// to leave it unchanged.
+fn list_change(values: Vec<String>, clear: bool) -> Option<Vec<String>> {
+    if clear || !values.is_empty() {
+        Some(values)
+    } else {
+        None
+    }
+}
+
+fn parse_catch_up(s: &str) -> Result<CatchUpPolicy, String> {
+    match s {
+        "skip" => Ok(CatchUpPolicy::Skip),
+        "run-once" => Ok(CatchUpPolicy::RunOnce),
+        "run-all" => Ok(CatchUpPolicy::RunAll),
+        _ => Err(format!(
+            "unknown catch-up policy '{}' (use skip, run-once, or run-all)",
+            s
+        )),
+    }
+}
+
+fn parse_trigger(s: &str) -> Result<JobTrigger, String> {
+    s.parse()
+}
+
+fn list(store: &CronJobStore, json: bool) -> Result<()> {
+    let jobs = store.list()?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&jobs)?);
+        return Ok(());
+    }
+    if jobs.is_empty() {
+        println!("No cron jobs. Add one with `localgpt cron add`.");
+        return Ok(());
+    }
+
+    for info in &jobs {
+        let job = &info.job;
+        let status = match (job.enabled, info.paused) {
+            (false, _) => "disabled",
+            (true, true) => "paused",
+            (true, false) => "enabled",
+        };
+        println!("{} [{}, {}]", job.name, info.source.as_str(), status);
+        let trigger_only = job.schedule.trim().is_empty();
+        match &info.interpreted {
+            Some(expr) => println!("    schedule: {} (cron: {})", job.schedule, expr),
+            None if trigger_only => println!("    schedule: (trigger only)"),
+            None => println!("    schedule: {}", job.schedule),
+        }
+        match info.next_run {
+            Some(next) => println!(
+                "    next run: {} ({})",
+                next.format("%Y-%m-%d %H:%M:%S"),
+                until(next)
+            ),
+            None if job.enabled && !info.paused && !trigger_only => {
+                println!("    next run: invalid schedule")
+            }
+            None => {}
+        }
+        match &job.trigger {
+            Some(JobTrigger::File {
+                path,
+                pattern: Some(pattern),
+            }) => println!("    trigger:  new file in {} matching {}", path, pattern),
+            Some(JobTrigger::File { path, .. }) => println!("    trigger:  new file in {}", path),
+            Some(JobTrigger::Webhook { .. }) => {
+                println!("    trigger:  webhook POST /api/cron/hooks/{}", job.name)
+            }
+            Some(JobTrigger::Memory) => println!("    trigger:  memory change"),
+            None => {}
+        }
+        if let Some(channel) = &job.channel {
+            println!("    channel:  {}", channel);
+        }
+        if let Some(jitter) = &job.jitter {
+            println!("    jitter:   up to {}", jitter);
+        }
+        if job.catch_up != CatchUpPolicy::Skip {
+            println!("    catch-up: {}", job.catch_up.as_str());
+        }
+        if let Some(model) = &job.model {
+            println!("    model:    {}", model);
+        }
+        if !job.tools.is_empty() {
+            println!("    tools:    {}", job.tools.join(", "));
+        }
+        if !job.skills.is_empty() {
+            println!("    skills:   {}", job.skills.join(", "));
+        }
+        if let Some(namespace) = &job.memory_namespace {
+            println!("    memory:   {}", namespace);
+        }
+        if job.retry.max_attempts > 1 {
+            println!(
+                "    retry:    {} attempts, backoff {}",
+                job.retry.max_attempts, job.retry.backoff
+            );
+        }
+        let prompt: String = job.prompt.chars().take(80).collect();
+        println!("    prompt:   {}", prompt.replace('\n', " "));
+    }
+    Ok(())
+}
+
+fn list_archived(store: &CronJobStore, json: bool) -> Result<()> {
+    let archived = store.archived()?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&archived)?);
+        return Ok(());
+    }
+    if archived.is_empty() {
+        println!("No archived one-shot jobs.");
+        return Ok(());
+    }
+
+    for entry in &archived {
+        println!(
+            "{} [{}, {}] finished {}",
+            entry.job.name,
+            entry.source.as_str(),
+            entry.status.as_str(),
+            entry.finished_at
+        );
+        println!("    schedule: {}", entry.job.schedule);
+        let prompt: String = entry.job.prompt.chars().take(80).collect();
+        println!("    prompt:   {}", prompt.replace('\n', " "));
+    }
+    Ok(())
+}
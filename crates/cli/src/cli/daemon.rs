@@ -1,6 +1,7 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
 use std::fs;
+use std::io::{IsTerminal, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::task::JoinSet;
@@ -8,11 +9,15 @@ use tokio::task::JoinSet;
 #[cfg(unix)]
 use daemonize::Daemonize;
 
-use localgpt_core::concurrency::TurnGate;
-use localgpt_core::config::Config;
+use localgpt_core::agent::SkillsWatcher;
+use localgpt_core::concurrency::{LockHolder, Shutdown, TurnGate, WorkspaceLock, shutdown_signal};
+use localgpt_core::config::{Config, parse_duration};
 use localgpt_core::heartbeat::HeartbeatRunner;
 use localgpt_core::memory::MemoryManager;
+use localgpt_core::readiness::{self, ComponentState};
+use localgpt_core::security::RedactingWriter;
 use localgpt_server::Server;
+use localgpt_server::security::{BridgeApprover, PendingBridge};
 use std::time::Duration;
 
 /// Agent ID used for bridge CLI sessions.
@@ -139,6 +144,7 @@ async fn run_daemon_server(config: Config, agent_id: &str) -> Result<()> {
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::new("info"))
         .with_ansi(false)
+        .with_writer(|| RedactingWriter::new(std::io::stdout()))
         .init();
 
     let memory = MemoryManager::new_with_full_config(&config.memory, Some(&config), agent_id)?;
@@ -164,30 +170,50 @@ async fn run_daemon_server(config: Config, agent_id: &str) -> Result<()> {
         localgpt_core::config::spawn_sighup_handler(watcher.clone());
     }
 
+    // Reload skills into running agents when SKILL.md files change
+    let _skills_watcher = match SkillsWatcher::start(&config.workspace_path()) {
+        Ok(w) => {
+            println!("  Skills hot-reload: enabled");
+            Some(w)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to start skills watcher: {}", e);
+            None
+        }
+    };
+
     println!("Daemon started successfully");
 
-    run_daemon_services(&config, agent_id, config_watcher).await?;
+    run_daemon_services(&config, agent_id, &memory, config_watcher).await?;
 
-    println!("\nShutting down...");
     let pid_file = get_pid_file()?;
     fs::remove_file(&pid_file).ok();
+    println!("Daemon stopped");
 
     Ok(())
 }
 
-/// Run daemon services (server and/or heartbeat)
+/// Run daemon services (server and/or heartbeat) until SIGTERM or ctrl-c,
+/// then let in-flight work finish before returning
 async fn run_daemon_services(
     config: &Config,
     agent_id: &str,
+    memory: &MemoryManager,
     // Config watcher is available for services that need hot-reload support
     // Services can subscribe to config changes via config_watcher.subscribe()
     _config_watcher: Option<Arc<localgpt_core::config::ConfigWatcher>>,
 ) -> Result<()> {
     // Create shared turn gate for heartbeat + HTTP concurrency control
-    let turn_gate = TurnGate::new();
+    let turn_gate = TurnGate::from_config(&config.server.turns)?;
 
-    // Collect all running JoinHandles
+    let shutdown_timeout = parse_duration(&config.server.shutdown_timeout)
+        .map_err(|e| anyhow::anyhow!("Invalid server.shutdown_timeout: {}", e))?;
+    let shutdown = Shutdown::new(shutdown_timeout);
+
+    // Services stopped as soon as in-flight turns are done
     let mut handles = JoinSet::new();
+    // Services that finish their own work once shutdown is triggered
+    let mut draining = JoinSet::new();
 
     // Note: Services that need hot-reload should subscribe to config_watcher.subscribe()
     // and update their internal state when a new config is received.
@@ -237,6 +263,25 @@ async fn run_daemon_services(
         });
     }
 
+    // Spawn periodic audit log rotation task (every hour, first check at startup)
+    {
+        let rotation = localgpt_core::security::AuditRotation::from_config(&config.security.audit)?;
+        let state_dir = config.paths.state_dir.clone();
+        handles.spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60 * 60));
+            loop {
+                interval.tick().await;
+                match localgpt_core::security::rotate_audit_log(&state_dir, &rotation, false) {
+                    Ok(Some(archive)) => {
+                        tracing::info!("Audit log archived to {}", archive.display());
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Audit log rotation failed: {}", e),
+                }
+            }
+        });
+    }
+
     // Spawn heartbeat in background if enabled
     if config.heartbeat.enabled {
         let heartbeat_config = config.clone();
@@ -300,13 +345,20 @@ async fn run_daemon_services(
         println!("  Telegram: disabled");
     }
 
-    // Spawn cron scheduler if any jobs are configured
-    if !config.cron.jobs.is_empty() {
+    // Spawn cron scheduler; it follows the runtime jobs file, so it runs even
+    // with no jobs yet (`localgpt cron add` can add some later)
+    {
         let cron_config = config.clone();
-        let scheduler = localgpt_core::cron::CronScheduler::new(&config.cron.jobs);
-        let job_count = config.cron.jobs.iter().filter(|j| j.enabled).count();
-        println!("  Cron: {} job(s) scheduled", job_count);
-        handles.spawn(async move {
+        let store = localgpt_core::cron::CronJobStore::from_config(config)?;
+        let scheduler = localgpt_core::cron::CronScheduler::with_store(store)?
+            .with_max_concurrent(config.cron.max_concurrent);
+        println!("  Cron: {} job(s) scheduled", scheduler.job_count().await);
+        let catch_up = scheduler.schedule_catch_up(&config.paths.state_dir).await;
+        if catch_up > 0 {
+            println!("  Cron: catching up {} missed run(s)", catch_up);
+        }
+        let cron_shutdown = shutdown.clone();
+        draining.spawn(async move {
             // Create tool factory that provides CLI tools to cron jobs
             let tool_factory: localgpt_core::cron::ToolFactory =
                 Box::new(|config: &localgpt_core::config::Config| {
@@ -315,35 +367,48 @@ async fn run_daemon_services(
 
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    () = cron_shutdown.wait() => break,
+                }
                 scheduler.tick(&cron_config, Some(&tool_factory)).await;
+                readiness::report(readiness::SCHEDULER, ComponentState::Running);
+            }
+
+            // No new runs start; let the running ones finish
+            while scheduler.running_jobs().await > 0 {
+                tokio::time::sleep(Duration::from_millis(250)).await;
             }
         });
-    } else {
-        println!("  Cron: no jobs configured");
     }
 
     if config.server.enabled {
         let bridge_memory =
             MemoryManager::new_with_full_config(&config.memory, Some(config), BRIDGE_CLI_AGENT_ID)?;
-        let bridge_manager =
-            localgpt_server::BridgeManager::new_with_agent_support(config.clone(), bridge_memory);
+        let mut bridge_manager =
+            localgpt_server::BridgeManager::new_with_agent_support(config.clone(), bridge_memory)?;
+        // A foreground daemon can ask about new bridges itself
+        if std::io::stdin().is_terminal() {
+            bridge_manager.set_approver(Arc::new(TerminalBridgeApprover));
+        }
+        bridge_manager.start_health_checker();
 
         // Spawn Server
         let server_config = config.clone();
         let server_gate = turn_gate.clone();
         let server_bridge_manager = bridge_manager.clone();
+        let server_shutdown = shutdown.clone();
         println!(
             "  Server: http://{}:{}",
             server_config.server.bind, server_config.server.port
         );
-        handles.spawn(async move {
+        draining.spawn(async move {
             match Server::new_daemon(&server_config, server_gate, server_bridge_manager) {
                 Err(e) => {
                     tracing::error!("Failed to create HTTP server: {}", e);
                 }
                 Ok(server) => {
-                    if let Err(e) = server.run().await {
+                    if let Err(e) = server.with_shutdown(server_shutdown).run().await {
                         tracing::error!("HTTP server error: {}", e);
                     }
                 }
@@ -357,17 +422,57 @@ async fn run_daemon_services(
         handles.spawn(async move {
             if let Err(e) = bridge_manager.serve(&bridge_socket).await {
                 tracing::error!("Bridge server error: {}", e);
+                readiness::report(readiness::BRIDGE, ComponentState::Failed(e.to_string()));
             }
         });
     } else {
         println!("  Server: disabled");
     }
 
-    tokio::signal::ctrl_c().await?;
-
-    println!("  Server: shutting down");
+    let signal = shutdown_signal().await;
+    println!(
+        "\nReceived {}, shutting down (waiting up to {} for in-flight work)",
+        signal, config.server.shutdown_timeout
+    );
+    shutdown.trigger();
+
+    // Listeners are closed and cron starts nothing new. Holding every turn
+    // slot waits out the running turns and keeps heartbeat and Telegram from
+    // starting another.
+    let _gate = match tokio::time::timeout(shutdown.remaining(), turn_gate.acquire_all()).await {
+        Ok(permit) => Some(permit),
+        Err(_) => {
+            println!("  Turns: still running at the deadline, abandoning");
+            None
+        }
+    };
     handles.shutdown().await;
 
+    // The server saves its sessions; cron waits for running jobs
+    let drained = tokio::time::timeout(shutdown.remaining(), async {
+        while draining.join_next().await.is_some() {}
+    })
+    .await;
+    if drained.is_err() {
+        println!(
+            "  Shutdown: {} service(s) still busy at the deadline, abandoning",
+            draining.len()
+        );
+    }
+    draining.shutdown().await;
+
+    // Index memory files written during the last turns, which the watcher
+    // may not have picked up yet
+    if let Err(e) = memory.reindex(false) {
+        tracing::warn!("Final memory index update failed: {}", e);
+    }
+
+    #[cfg(unix)]
+    if config.server.enabled {
+        let paths = localgpt_core::paths::Paths::resolve()?;
+        fs::remove_file(paths.bridge_socket_name()).ok();
+    }
+
     Ok(())
 }
 
@@ -396,19 +501,60 @@ pub enum DaemonCommands {
         foreground: bool,
     },
 
-    /// Show daemon status
-    Status,
+    /// Show daemon status: PID, uptime, listeners, and active sessions
+    Status {
+        /// API key to authenticate with (defaults to server.auth_token)
+        #[arg(long, env = "LOCALGPT_API_KEY", hide_env_values = true)]
+        api_key: Option<String>,
+    },
+
+    /// Print the end of the daemon log
+    Logs {
+        /// Keep printing lines as they are written
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Number of lines to print
+        #[arg(short = 'n', long, default_value_t = 50)]
+        lines: usize,
+    },
 
     /// Run heartbeat once (for testing)
     Heartbeat,
 }
 
+/// Asks on the terminal of a foreground daemon before an unknown bridge
+/// executable gets credentials.
+struct TerminalBridgeApprover;
+
+impl BridgeApprover for TerminalBridgeApprover {
+    fn ask(&self, request: &PendingBridge) -> bool {
+        let peer = &request.peer;
+        println!(
+            "\n[Bridge '{}' asks for its credentials from {} (fingerprint {})]",
+            peer.bridge_id,
+            peer.exe_display(),
+            peer.fingerprint
+        );
+        print!("Allow? [y/N]: ");
+        if std::io::stdout().flush().is_err() {
+            return false;
+        }
+        let mut input = String::new();
+        if std::io::stdin().read_line(&mut input).is_err() {
+            return false;
+        }
+        matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+}
+
 pub async fn run(args: DaemonArgs, agent_id: &str) -> Result<()> {
     match args.command {
         DaemonCommands::Start { foreground } => start_daemon(foreground, agent_id).await,
         DaemonCommands::Stop => stop_daemon().await,
         DaemonCommands::Restart { foreground } => restart_daemon(foreground, agent_id).await,
-        DaemonCommands::Status => show_status().await,
+        DaemonCommands::Status { api_key } => show_status(api_key).await,
+        DaemonCommands::Logs { follow, lines } => show_logs(follow, lines).await,
         DaemonCommands::Heartbeat => run_heartbeat_once(agent_id).await,
     }
 }
@@ -474,12 +620,24 @@ async fn start_daemon(foreground: bool, agent_id: &str) -> Result<()> {
         localgpt_core::config::spawn_sighup_handler(watcher.clone());
     }
 
+    // Reload skills into running agents when SKILL.md files change
+    let _skills_watcher = match SkillsWatcher::start(&config.workspace_path()) {
+        Ok(w) => {
+            println!("  Skills hot-reload: enabled");
+            Some(w)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to start skills watcher: {}", e);
+            None
+        }
+    };
+
     println!("Daemon started successfully");
 
-    run_daemon_services(&config, agent_id, config_watcher).await?;
+    run_daemon_services(&config, agent_id, &memory, config_watcher).await?;
 
-    println!("\nShutting down...");
     fs::remove_file(&pid_file).ok();
+    println!("Daemon stopped");
 
     Ok(())
 }
@@ -513,12 +671,38 @@ async fn stop_daemon() -> Result<()> {
         Command::new("taskkill").args(["/PID", &pid]).status()?;
     }
 
-    println!("Sent stop signal to daemon (PID: {})", pid);
-    fs::remove_file(&pid_file)?;
+    println!(
+        "Sent stop signal to daemon (PID: {}), waiting for in-flight work to finish...",
+        pid
+    );
+    if wait_for_exit(&pid).await {
+        // The daemon removes it on a clean exit
+        fs::remove_file(&pid_file).ok();
+        println!("Daemon stopped");
+    } else {
+        println!("Daemon is still shutting down (PID: {})", pid);
+    }
 
     Ok(())
 }
 
+/// Wait for a signalled daemon to exit. It may spend up to
+/// `server.shutdown_timeout` draining in-flight turns first.
+async fn wait_for_exit(pid: &str) -> bool {
+    let drain = Config::load()
+        .ok()
+        .and_then(|config| parse_duration(&config.server.shutdown_timeout).ok())
+        .unwrap_or(Duration::from_secs(30));
+    let deadline = std::time::Instant::now() + drain + Duration::from_secs(5);
+    while is_process_running(pid) {
+        if std::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    true
+}
+
 async fn restart_daemon(foreground: bool, agent_id: &str) -> Result<()> {
     // Stop the daemon if running
     let pid_file = get_pid_file()?;
@@ -539,15 +723,7 @@ async fn restart_daemon(foreground: bool, agent_id: &str) -> Result<()> {
                 Command::new("taskkill").args(["/PID", &pid]).status()?;
             }
 
-            // Wait for process to stop (up to 5 seconds)
-            for _ in 0..50 {
-                if !is_process_running(&pid) {
-                    break;
-                }
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            }
-
-            if is_process_running(&pid) {
+            if !wait_for_exit(&pid).await {
                 anyhow::bail!("Failed to stop daemon (PID: {})", pid);
             }
 
@@ -569,7 +745,7 @@ async fn restart_daemon(foreground: bool, agent_id: &str) -> Result<()> {
     start_daemon(foreground, agent_id).await
 }
 
-async fn show_status() -> Result<()> {
+async fn show_status(api_key: Option<String>) -> Result<()> {
     let config = Config::load()?;
     let pid_file = get_pid_file()?;
 
@@ -587,6 +763,78 @@ async fn show_status() -> Result<()> {
     if running {
         let pid = fs::read_to_string(&pid_file)?;
         println!("PID: {}", pid.trim());
+
+        // The PID file is written when the daemon starts
+        if let Ok(started) = fs::metadata(&pid_file).and_then(|m| m.modified())
+            && let Ok(uptime) = started.elapsed()
+        {
+            println!("Uptime: {}", super::status::format_uptime(uptime.as_secs()));
+        }
+
+        if config.server.enabled {
+            let host = match config.server.bind.as_str() {
+                "0.0.0.0" | "::" => "127.0.0.1",
+                bind => bind,
+            };
+            let base_url = format!("http://{}:{}", host, config.server.port);
+            let mut request = reqwest::Client::new()
+                .get(format!("{}/api/status", base_url))
+                .timeout(Duration::from_secs(3));
+            if let Some(token) = api_key.or(config.server.auth_token.clone()) {
+                request = request.bearer_auth(token);
+            }
+            match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    println!(
+                        "HTTP: http://{}:{} (listening)",
+                        config.server.bind, config.server.port
+                    );
+                    let status: serde_json::Value = response.json().await.unwrap_or_default();
+                    println!("Active sessions: {}", status["active_sessions"]);
+                }
+                Ok(response) => println!(
+                    "HTTP: http://{}:{} (listening; /api/status returned {})",
+                    config.server.bind,
+                    config.server.port,
+                    response.status()
+                ),
+                Err(_) => println!(
+                    "HTTP: http://{}:{} (not responding)",
+                    config.server.bind, config.server.port
+                ),
+            }
+        }
+
+        if let Ok(paths) = localgpt_core::paths::Paths::resolve() {
+            let socket = paths.bridge_socket_name();
+            #[cfg(unix)]
+            let listening = std::path::Path::new(&socket).exists();
+            #[cfg(not(unix))]
+            let listening = true;
+            println!(
+                "Bridge socket: {}{}",
+                socket,
+                if listening { "" } else { " (missing)" }
+            );
+        }
+    }
+
+    if let Ok(lock) = WorkspaceLock::new() {
+        let holders = lock.holders();
+        if holders.is_empty() {
+            println!("Workspace lock: free");
+        }
+        for holder in &holders {
+            println!(
+                "Workspace lock: {}{}",
+                describe_lock_holder(holder),
+                if holder.is_alive() {
+                    ""
+                } else {
+                    " (exited; stale)"
+                }
+            );
+        }
     }
 
     if running {
@@ -601,9 +849,13 @@ async fn show_status() -> Result<()> {
             println!("  Heartbeat timeout: {}", timeout);
         }
     }
-    println!("  Cron enabled: {}", !config.cron.jobs.is_empty());
-    if !config.cron.jobs.is_empty() {
-        println!("  Cron jobs: {}", config.cron.jobs.len());
+    let cron_jobs = localgpt_core::cron::CronJobStore::from_config(&config)
+        .and_then(|store| store.jobs())
+        .map(|jobs| jobs.len())
+        .unwrap_or(config.cron.jobs.len());
+    println!("  Cron enabled: {}", cron_jobs > 0);
+    if cron_jobs > 0 {
+        println!("  Cron jobs: {}", cron_jobs);
     }
     let telegram_enabled = config.telegram.as_ref().map_or(false, |t| t.enabled);
     println!("  Telegram enabled: {}", telegram_enabled);
@@ -628,6 +880,100 @@ async fn show_status() -> Result<()> {
     Ok(())
 }
 
+async fn show_logs(follow: bool, lines: usize) -> Result<()> {
+    let logs_dir = localgpt_core::paths::Paths::resolve()?.logs_dir();
+    let Some(mut path) = latest_log_file(&logs_dir) else {
+        anyhow::bail!(
+            "No daemon logs in {} (logs are written when the daemon runs in the background)",
+            logs_dir.display()
+        );
+    };
+
+    let mut file = fs::File::open(&path)?;
+    let mut pos = print_tail(&mut file, lines)?;
+    if !follow {
+        return Ok(());
+    }
+
+    loop {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // Log files are per day; move on when the next one appears
+        if let Some(latest) = latest_log_file(&logs_dir)
+            && latest != path
+        {
+            path = latest;
+            file = fs::File::open(&path)?;
+            pos = 0;
+        }
+
+        let len = file.metadata()?.len();
+        if len < pos {
+            // Truncated: start over
+            pos = 0;
+        }
+        if len > pos {
+            pos = copy_from(&mut file, pos)?;
+        }
+    }
+}
+
+/// The newest `localgpt-YYYY-MM-DD.log` in `logs_dir`
+fn latest_log_file(logs_dir: &std::path::Path) -> Option<PathBuf> {
+    fs::read_dir(logs_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("localgpt-") && n.ends_with(".log"))
+        })
+        .max()
+}
+
+/// Print the last `lines` lines of `file`; returns the position read up to
+fn print_tail(file: &mut fs::File, lines: usize) -> Result<u64> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let len = file.metadata()?.len();
+    // Read back from the end until there are enough lines
+    let mut start = len;
+    let mut chunk = 64 * 1024;
+    let mut buf = Vec::new();
+    while start > 0 {
+        start = len.saturating_sub(chunk);
+        buf.clear();
+        file.seek(SeekFrom::Start(start))?;
+        file.by_ref().take(len - start).read_to_end(&mut buf)?;
+        if buf.iter().filter(|&&b| b == b'\n').count() > lines {
+            break;
+        }
+        chunk *= 2;
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    let all: Vec<&str> = text.lines().collect();
+    // The first line may be cut in half unless we read from the start
+    let skip_partial = usize::from(start > 0);
+    let from = all.len().saturating_sub(lines).max(skip_partial);
+    for line in &all[from.min(all.len())..] {
+        println!("{}", line);
+    }
+    Ok(len)
+}
+
+/// Print `file` from `pos` to its end; returns the new position
+fn copy_from(file: &mut fs::File, pos: u64) -> Result<u64> {
+    use std::io::{Seek, SeekFrom, Write};
+
+    file.seek(SeekFrom::Start(pos))?;
+    let mut stdout = std::io::stdout().lock();
+    let copied = std::io::copy(file, &mut stdout)?;
+    stdout.flush()?;
+    Ok(pos + copied)
+}
+
 async fn run_heartbeat_once(agent_id: &str) -> Result<()> {
     let config = Config::load()?;
 
@@ -694,7 +1040,19 @@ fn prune_old_logs(logs_dir: &std::path::Path, keep_days: i64) {
     }
 }
 
-fn is_process_running(pid: &str) -> bool {
+/// e.g. "PID 4242 (write, localgpt chat, for 2m 5s)"
+pub(crate) fn describe_lock_holder(holder: &LockHolder) -> String {
+    let held = (chrono::Utc::now() - holder.since).num_seconds().max(0) as u64;
+    format!(
+        "PID {} ({}, {}, for {})",
+        holder.pid,
+        holder.mode,
+        holder.command,
+        super::status::format_uptime(held)
+    )
+}
+
+pub(crate) fn is_process_running(pid: &str) -> bool {
     let pid = pid.trim();
 
     #[cfg(unix)]
@@ -0,0 +1,356 @@
+//! Diagnostics bundle: `localgpt debug bundle`
+//!
+//! Collects doctor results, the config with secrets redacted, the tail of
+//! recent daemon logs, version and build info, memory stats, and bridge status
+//! into one `.tar.gz` to attach to a bug report. Every file is scrubbed before
+//! it is written: configured API keys and tokens are replaced wherever they
+//! appear, as are strings that look like common token formats.
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use regex::Regex;
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use localgpt_core::config::Config;
+use localgpt_core::memory::MemoryManager;
+use localgpt_core::paths::Paths;
+
+const REDACTED: &str = "[REDACTED]";
+
+#[derive(Args)]
+pub struct DebugArgs {
+    #[command(subcommand)]
+    pub command: DebugCommands,
+}
+
+#[derive(Subcommand)]
+pub enum DebugCommands {
+    /// Write a .tar.gz of diagnostics (secrets scrubbed) for a bug report
+    Bundle {
+        /// Archive to write (default: localgpt-debug-<timestamp>.tar.gz)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Lines to keep from the end of each recent daemon log
+        #[arg(long, default_value = "2000")]
+        log_lines: usize,
+    },
+}
+
+pub async fn run(args: DebugArgs, agent_id: &str) -> Result<()> {
+    match args.command {
+        DebugCommands::Bundle { output, log_lines } => bundle(output, log_lines, agent_id).await,
+    }
+}
+
+async fn bundle(output: Option<PathBuf>, log_lines: usize, agent_id: &str) -> Result<()> {
+    let paths = Paths::resolve()?;
+    let config = Config::load().ok();
+
+    let name = format!(
+        "localgpt-debug-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("{}.tar.gz", name)));
+    let staging = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    let dir = staging.join(&name);
+    fs::create_dir_all(&dir)?;
+
+    let scrubber = Scrubber::new(config.as_ref());
+    let result = collect(
+        &dir,
+        &paths,
+        config.as_ref(),
+        agent_id,
+        log_lines,
+        &scrubber,
+    )
+    .await
+    .and_then(|files| {
+        archive(&staging, &name, &output)?;
+        Ok(files)
+    });
+    let _ = fs::remove_dir_all(&staging);
+    let files = result?;
+
+    println!("Wrote {}", output.display());
+    for file in &files {
+        println!("  {}", file);
+    }
+    println!();
+    println!("Secrets were scrubbed, but please look through the bundle before sharing it.");
+    Ok(())
+}
+
+/// Write every part of the bundle into `dir`; returns the files written.
+/// A part that can't be collected is recorded as an error in its file.
+async fn collect(
+    dir: &Path,
+    paths: &Paths,
+    config: Option<&Config>,
+    agent_id: &str,
+    log_lines: usize,
+    scrubber: &Scrubber,
+) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    let mut write = |name: &str, content: &str| -> Result<()> {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, scrubber.scrub(content))?;
+        files.push(name.to_string());
+        Ok(())
+    };
+
+    write(
+        "version.json",
+        &serde_json::to_string_pretty(&build_info(paths))?,
+    )?;
+
+    let doctor = super::doctor::collect(&[]).await;
+    write("doctor.json", &serde_json::to_string_pretty(&doctor)?)?;
+
+    let config_text = match fs::read_to_string(paths.config_file()) {
+        Ok(content) => redact_config(&content),
+        Err(e) => format!(
+            "# Could not read {}: {}\n",
+            paths.config_file().display(),
+            e
+        ),
+    };
+    write("config.toml", &config_text)?;
+
+    let memory = match config {
+        Some(config) => memory_stats(config, agent_id),
+        None => json!({ "error": "config did not load" }),
+    };
+    write("memory-stats.json", &serde_json::to_string_pretty(&memory)?)?;
+
+    let bridges = match super::bridge::fetch_status().await {
+        Ok(report) => serde_json::to_value(report)?,
+        Err(e) => json!({ "error": e.to_string() }),
+    };
+    write(
+        "bridge-status.json",
+        &serde_json::to_string_pretty(&bridges)?,
+    )?;
+
+    for log in recent_logs(&paths.logs_dir(), 2) {
+        let Some(file_name) = log.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let content = fs::read(&log)?;
+        let text = String::from_utf8_lossy(&content);
+        write(&format!("logs/{}", file_name), &tail(&text, log_lines))?;
+    }
+
+    Ok(files)
+}
+
+fn build_info(paths: &Paths) -> serde_json::Value {
+    let features: Vec<&str> = [
+        ("desktop", cfg!(feature = "desktop")),
+        ("gen", cfg!(feature = "gen")),
+        ("wasm-plugins", cfg!(feature = "wasm-plugins")),
+        ("egui-web", cfg!(feature = "egui-web")),
+    ]
+    .into_iter()
+    .filter_map(|(name, on)| on.then_some(name))
+    .collect();
+
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "debug_build": cfg!(debug_assertions),
+        "features": features,
+        "profile": paths.profile,
+        "created": chrono::Local::now().to_rfc3339(),
+        "paths": {
+            "config": paths.config_dir,
+            "data": paths.data_dir,
+            "workspace": paths.workspace,
+            "state": paths.state_dir,
+            "cache": paths.cache_dir,
+            "runtime": paths.runtime_dir,
+        },
+    })
+}
+
+/// Index totals only; file names stay out of the bundle
+fn memory_stats(config: &Config, agent_id: &str) -> serde_json::Value {
+    let stats = MemoryManager::new_with_full_config(&config.memory, Some(config), agent_id)
+        .and_then(|memory| Ok((memory.stats()?, memory.embedded_chunk_count().unwrap_or(0))));
+    match stats {
+        Ok((stats, embedded)) => json!({
+            "total_files": stats.total_files,
+            "total_chunks": stats.total_chunks,
+            "embedded_chunks": embedded,
+            "index_size_kb": stats.index_size_kb,
+            "embedding_provider": config.memory.embedding_provider,
+            "embedding_model": config.memory.embedding_model,
+        }),
+        Err(e) => json!({ "error": e.to_string() }),
+    }
+}
+
+/// The newest `count` daemon log files, newest first
+fn recent_logs(logs_dir: &Path, count: usize) -> Vec<PathBuf> {
+    let mut logs: Vec<PathBuf> = fs::read_dir(logs_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("localgpt-") && n.ends_with(".log"))
+        })
+        .collect();
+    logs.sort();
+    logs.into_iter().rev().take(count).collect()
+}
+
+fn tail(text: &str, lines: usize) -> String {
+    let all: Vec<&str> = text.lines().collect();
+    let mut out = all[all.len().saturating_sub(lines)..].join("\n");
+    out.push('\n');
+    out
+}
+
+fn archive(staging: &Path, name: &str, output: &Path) -> Result<()> {
+    let output = std::path::absolute(output)?;
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(&output)
+        .arg("-C")
+        .arg(staging)
+        .arg(name)
+        .status()
+        .context("Failed to run tar")?;
+    if !status.success() {
+        anyhow::bail!("tar failed creating {}", output.display());
+    }
+    Ok(())
+}
+
+// ── Secret scrubbing ──
+
+/// Setting names whose values are secrets
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    ["key", "token", "secret", "password", "authorization"]
+        .iter()
+        .any(|word| key.contains(word))
+}
+
+/// Tables whose every value may be a secret (HTTP headers, env for MCP servers)
+fn is_secret_table(key: &str) -> bool {
+    matches!(key, "env" | "headers" | "extra_headers")
+}
+
+/// The config file with secret values replaced. `${VAR}` references are
+/// kept, since they name a variable rather than hold its value.
+fn redact_config(content: &str) -> String {
+    let mut value: toml::Value = match toml::from_str(content) {
+        Ok(value) => value,
+        Err(e) => return format!("# config.toml does not parse: {}\n", e),
+    };
+    redact_value(&mut value, false);
+    toml::to_string_pretty(&value).unwrap_or_default()
+}
+
+fn redact_value(value: &mut toml::Value, secret: bool) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                let secret = secret || is_secret_table(key) || is_secret_key(key);
+                redact_value(value, secret);
+            }
+        }
+        toml::Value::Array(items) => {
+            for item in items {
+                redact_value(item, secret);
+            }
+        }
+        toml::Value::String(s) if secret && !is_env_reference(s) && !s.is_empty() => {
+            *s = REDACTED.to_string();
+        }
+        _ => {}
+    }
+}
+
+fn is_env_reference(s: &str) -> bool {
+    s.starts_with("${") && s.ends_with('}')
+}
+
+/// Replaces known secret values and token-shaped strings in text
+struct Scrubber {
+    known: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl Scrubber {
+    fn new(config: Option<&Config>) -> Self {
+        let mut known = Vec::new();
+        if let Some(config) = config
+            && let Ok(value) = toml::Value::try_from(config)
+        {
+            collect_secrets(&value, false, &mut known);
+        }
+        // Longest first, so a secret containing another is replaced whole
+        known.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        known.dedup();
+
+        let patterns = [
+            r"sk-[A-Za-z0-9_\-]{16,}",
+            r"gh[pousr]_[A-Za-z0-9]{20,}",
+            r"xox[abprs]-[A-Za-z0-9\-]{10,}",
+            r"AIza[0-9A-Za-z_\-]{30,}",
+            r"\b[0-9]{6,12}:[A-Za-z0-9_\-]{30,}\b",
+            r"(?i)bearer\s+[A-Za-z0-9._~+/=\-]{16,}",
+        ]
+        .iter()
+        .filter_map(|p| Regex::new(p).ok())
+        .collect();
+
+        Self { known, patterns }
+    }
+
+    fn scrub(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for secret in &self.known {
+            out = out.replace(secret.as_str(), REDACTED);
+        }
+        for pattern in &self.patterns {
+            out = pattern.replace_all(&out, REDACTED).into_owned();
+        }
+        out
+    }
+}
+
+/// Secret values in the loaded config (env references already expanded)
+fn collect_secrets(value: &toml::Value, secret: bool, out: &mut Vec<String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let secret = secret || is_secret_table(key) || is_secret_key(key);
+                collect_secrets(value, secret, out);
+            }
+        }
+        toml::Value::Array(items) => {
+            for item in items {
+                collect_secrets(item, secret, out);
+            }
+        }
+        // Short values would match ordinary words in the logs
+        toml::Value::String(s) if secret && s.len() >= 8 && !is_env_reference(s) => {
+            out.push(s.clone());
+        }
+        _ => {}
+    }
+}
@@ -3,29 +3,57 @@
 use anyhow::Result;
 use clap::Args;
 
+use localgpt_core::config::Config;
+
 #[derive(Args)]
 pub struct DesktopArgs {
-    // No additional args needed for now
-    // Could add --theme, --size, etc. in future
+    /// Chat through a running daemon instead of an embedded agent.
+    /// Without a URL, uses server.bind and server.port from the config.
+    #[arg(long, value_name = "URL", num_args = 0..=1, default_missing_value = "")]
+    pub daemon: Option<String>,
+
+    /// API key for the daemon (defaults to server.auth_token)
+    #[arg(long, env = "LOCALGPT_API_KEY", hide_env_values = true)]
+    pub api_key: Option<String>,
 }
 
-pub fn run(_args: DesktopArgs, agent_id: &str) -> Result<()> {
-    use crate::desktop::DesktopApp;
+pub fn run(args: DesktopArgs, agent_id: &str) -> Result<()> {
+    use crate::desktop::{Backend, DaemonClient, DesktopApp};
+
+    let (backend, title) = match args.daemon {
+        Some(url) => {
+            let config = Config::load()?;
+            let url = if url.is_empty() {
+                let host = match config.server.bind.as_str() {
+                    "0.0.0.0" | "::" => "127.0.0.1",
+                    bind => bind,
+                };
+                format!("http://{}:{}", host, config.server.port)
+            } else {
+                url
+            };
+            let token = args.api_key.or(config.server.auth_token);
+            let title = format!("LocalGPT — {}", url);
+            (Backend::Daemon(DaemonClient::new(&url, token)), title)
+        }
+        None => (
+            Backend::Embedded(Some(agent_id.to_string())),
+            "LocalGPT".to_string(),
+        ),
+    };
 
     let native_options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
             .with_inner_size([800.0, 600.0])
             .with_min_inner_size([400.0, 300.0])
-            .with_title("LocalGPT"),
+            .with_title(title),
         ..Default::default()
     };
 
-    let agent_id = agent_id.to_string();
-
     eframe::run_native(
         "LocalGPT",
         native_options,
-        Box::new(move |cc| Ok(Box::new(DesktopApp::new(cc, Some(agent_id.clone()))))),
+        Box::new(move |cc| Ok(Box::new(DesktopApp::new(cc, backend)))),
     )
     .map_err(|e| anyhow::anyhow!("Failed to run desktop app: {}", e))
 }
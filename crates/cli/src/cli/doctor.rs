@@ -5,9 +5,14 @@
 use anyhow::Result;
 use clap::Args;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use localgpt_core::concurrency::WorkspaceLock;
+use localgpt_core::config::Config;
+use localgpt_core::paths::Paths;
+
 /// Check result status
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum CheckStatus {
@@ -58,73 +63,224 @@ impl CheckResult {
     }
 }
 
+/// Names accepted by `--check`, in the order the checks run
+const CHECK_NAMES: &[&str] = &[
+    "config",
+    "workspace",
+    "memory",
+    "embeddings",
+    "provider",
+    "api-keys",
+    "telegram",
+    "mcp",
+    "pid-file",
+    "disk",
+    "cron",
+    "server-port",
+    "sandbox",
+    "bridge",
+    "ollama",
+    "gpu",
+    "skills",
+    "clock",
+    "workspace-lock",
+    "device-key",
+];
+
+/// How the riskier `--fix` remediations get permission to run
+#[derive(Clone, Copy)]
+struct Fix {
+    enabled: bool,
+    yes: bool,
+}
+
+impl Fix {
+    /// Ask before a remediation; `--yes` answers for the user. Without a
+    /// terminal to ask on, the remediation is skipped.
+    fn confirm(self, prompt: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if self.yes {
+            return true;
+        }
+        if !std::io::stdin().is_terminal() {
+            return false;
+        }
+        eprint!("{} [y/N] ", prompt);
+        let _ = std::io::stderr().flush();
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).is_ok()
+            && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+}
+
 #[derive(Args)]
 pub struct DoctorArgs {
     /// Auto-fix issues where possible
     #[arg(short, long)]
     pub fix: bool,
 
+    /// Apply fixes that normally ask for confirmation without asking
+    #[arg(short, long, requires = "fix")]
+    pub yes: bool,
+
     /// Output results as JSON
     #[arg(long)]
     pub json: bool,
+
+    /// Run only this check (repeatable)
+    #[arg(long = "check", value_name = "NAME",
+          value_parser = clap::builder::PossibleValuesParser::new(CHECK_NAMES))]
+    pub checks: Vec<String>,
+
+    /// Print only warnings and failures, without the summary (for CI)
+    #[arg(short, long, conflicts_with = "json")]
+    pub quiet: bool,
 }
 
 pub async fn run(args: DoctorArgs) -> Result<()> {
+    let fix = Fix {
+        enabled: args.fix,
+        yes: args.yes,
+    };
+    let results = run_checks(&args.checks, fix).await;
+
+    // Output results
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else if args.quiet {
+        print_problems(&results);
+    } else {
+        print_results(&results);
+    }
+
+    // Exit with error code if any check failed
+    let has_failures = results.iter().any(|r| r.status == CheckStatus::Fail);
+    if has_failures {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Run every check (or only those named in `checks`) without fixing anything,
+/// for `localgpt debug bundle`
+pub(crate) async fn collect(checks: &[String]) -> Vec<CheckResult> {
+    let fix = Fix {
+        enabled: false,
+        yes: false,
+    };
+    run_checks(checks, fix).await
+}
+
+async fn run_checks(checks: &[String], fix: Fix) -> Vec<CheckResult> {
+    let enabled = |name: &str| checks.is_empty() || checks.iter().any(|c| c == name);
     let mut results = Vec::new();
 
     // Load config (check #1)
-    let config_result = check_config_file(args.fix);
-    results.push(config_result.clone());
+    if enabled("config") {
+        results.push(check_config_file(fix.enabled));
+    }
 
     let config = localgpt_core::config::Config::load().ok();
 
     // Check #2: Workspace directory
-    results.push(check_workspace_dir(config.as_ref(), args.fix));
+    if enabled("workspace") {
+        results.push(check_workspace_dir(config.as_ref(), fix.enabled));
+    }
 
     // Check #3: Memory database
-    results.push(check_memory_database(config.as_ref(), args.fix).await);
+    if enabled("memory") {
+        results.push(check_memory_database(config.as_ref(), fix).await);
+    }
 
     // Check #4: Embedding model
-    results.push(check_embedding_model(config.as_ref()).await);
+    if enabled("embeddings") {
+        results.push(check_embedding_model(config.as_ref()).await);
+    }
 
     // Check #5: Default provider reachable
-    results.push(check_default_provider(config.as_ref()).await);
+    if enabled("provider") {
+        results.push(check_default_provider(config.as_ref()).await);
+    }
 
     // Check #6: API keys configured
-    results.push(check_api_keys(config.as_ref()));
+    if enabled("api-keys") {
+        results.push(check_api_keys(config.as_ref()));
+    }
 
     // Check #7: Telegram token valid (if enabled)
-    results.push(check_telegram_token(config.as_ref()).await);
+    if enabled("telegram") {
+        results.push(check_telegram_token(config.as_ref(), fix).await);
+    }
 
     // Check #8: MCP servers connectable (if configured)
-    results.push(check_mcp_servers(config.as_ref()).await);
+    if enabled("mcp") {
+        results.push(check_mcp_servers(config.as_ref()).await);
+    }
 
     // Check #9: No stale PID file
-    results.push(check_stale_pid_file(args.fix));
+    if enabled("pid-file") {
+        results.push(check_stale_pid_file(fix.enabled));
+    }
 
     // Check #10: Disk space adequate
-    results.push(check_disk_space());
+    if enabled("disk") {
+        results.push(check_disk_space());
+    }
 
     // Check #11: Cron expressions valid (if configured)
-    results.push(check_cron_expressions(config.as_ref()));
+    if enabled("cron") {
+        results.push(check_cron_expressions(config.as_ref()));
+    }
 
     // Check #12: Server port available (if enabled)
-    results.push(check_server_port(config.as_ref()).await);
+    if enabled("server-port") {
+        results.push(check_server_port(config.as_ref()).await);
+    }
 
-    // Output results
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&results)?);
-    } else {
-        print_results(&results);
+    // Check #13: Sandbox kernel support
+    if enabled("sandbox") {
+        results.push(check_sandbox(config.as_ref()));
     }
 
-    // Exit with error code if any check failed
-    let has_failures = results.iter().any(|r| r.status == CheckStatus::Fail);
-    if has_failures {
-        std::process::exit(1);
+    // Check #14: Bridge socket reachable (if the daemon is running)
+    if enabled("bridge") {
+        results.push(check_bridge_socket().await);
     }
 
-    Ok(())
+    // Check #15: Ollama models pulled (if Ollama is used)
+    if enabled("ollama") {
+        results.push(check_ollama_models(config.as_ref()).await);
+    }
+
+    // Check #16: GPU for local embeddings
+    if enabled("gpu") {
+        results.push(check_gpu(config.as_ref()));
+    }
+
+    // Check #17: Skills eligible
+    if enabled("skills") {
+        results.push(check_skills(config.as_ref()));
+    }
+
+    // Check #18: Clock skew
+    if enabled("clock") {
+        results.push(check_clock_skew(config.as_ref()).await);
+    }
+
+    // Check #19: No stale workspace lock
+    if enabled("workspace-lock") {
+        results.push(check_workspace_lock(fix));
+    }
+
+    // Check #20: Device key usable
+    if enabled("device-key") {
+        results.push(check_device_key(config.as_ref(), fix));
+    }
+
+    results
 }
 
 fn print_results(results: &[CheckResult]) {
@@ -167,6 +323,21 @@ fn print_results(results: &[CheckResult]) {
     );
 }
 
+/// Print warnings and failures only, one per line
+fn print_problems(results: &[CheckResult]) {
+    for result in results {
+        let label = match result.status {
+            CheckStatus::Pass => continue,
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "FAIL",
+        };
+        match &result.fix_hint {
+            Some(hint) => println!("{}: {}: {} ({})", label, result.name, result.message, hint),
+            None => println!("{}: {}: {}", label, result.name, result.message),
+        }
+    }
+}
+
 /// Check #1: Config file exists and parses
 fn check_config_file(fix: bool) -> CheckResult {
     let paths = match localgpt_core::paths::Paths::resolve() {
@@ -294,7 +465,7 @@ fn check_workspace_dir(config: Option<&localgpt_core::config::Config>, fix: bool
 /// Check #3: Memory database opens without error
 async fn check_memory_database(
     config: Option<&localgpt_core::config::Config>,
-    _fix: bool,
+    fix: Fix,
 ) -> CheckResult {
     let config = match config {
         Some(c) => c,
@@ -308,29 +479,72 @@ async fn check_memory_database(
     };
 
     // Try to create memory manager
-    match localgpt_core::memory::MemoryManager::new_with_full_config(
+    let problem = match localgpt_core::memory::MemoryManager::new_with_full_config(
         &config.memory,
         Some(config),
         "main",
     ) {
-        Ok(mm) => {
-            // Get stats
-            match mm.stats() {
-                Ok(stats) => CheckResult::pass(
-                    "Memory database",
-                    format!("Memory database OK ({} chunks indexed)", stats.total_chunks),
-                ),
-                Err(_) => CheckResult::pass("Memory database", "Memory database OK"),
+        Ok(mm) => match mm.integrity_problems() {
+            Ok(problems) if problems.is_empty() => {
+                // Get stats
+                return match mm.stats() {
+                    Ok(stats) => CheckResult::pass(
+                        "Memory database",
+                        format!("Memory database OK ({} chunks indexed)", stats.total_chunks),
+                    ),
+                    Err(_) => CheckResult::pass("Memory database", "Memory database OK"),
+                };
             }
-        }
+            Ok(problems) => format!("Memory index corrupt: {}", problems.join("; ")),
+            Err(e) => format!("Memory index corrupt: {}", e),
+        },
+        Err(e) => format!("Memory database error: {}", e),
+    };
+
+    if !fix.confirm("Rebuild the memory index from the workspace files?") {
+        return CheckResult::fail(
+            "Memory database",
+            problem,
+            "Run with --fix to rebuild the index",
+        );
+    }
+    match rebuild_memory_index(config) {
+        Ok(chunks) => CheckResult::pass(
+            "Memory database",
+            format!("Rebuilt memory index ({} chunks indexed)", chunks),
+        ),
         Err(e) => CheckResult::fail(
             "Memory database",
-            format!("Memory database error: {}", e),
-            "Try 'localgpt memory reindex' to rebuild",
+            format!("Rebuilding the memory index failed: {}", e),
+            "Check permissions on the cache directory",
         ),
     }
 }
 
+/// Move the search index aside and index the workspace into a fresh one.
+/// Returns the number of chunks indexed.
+fn rebuild_memory_index(config: &Config) -> Result<usize> {
+    let db_path = config.paths.search_index("main");
+    if db_path.exists() {
+        let backup = db_path.with_extension("sqlite.corrupt");
+        std::fs::rename(&db_path, &backup)?;
+        eprintln!("Moved the old index to {}", backup.display());
+    }
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = db_path.clone().into_os_string();
+        sidecar.push(suffix);
+        let _ = std::fs::remove_file(sidecar);
+    }
+
+    let memory = localgpt_core::memory::MemoryManager::new_with_full_config(
+        &config.memory,
+        Some(config),
+        "main",
+    )?;
+    memory.reindex(true)?;
+    memory.chunk_count()
+}
+
 /// Check #4: Embedding model available
 async fn check_embedding_model(config: Option<&localgpt_core::config::Config>) -> CheckResult {
     let config = match config {
@@ -494,7 +708,10 @@ fn check_api_keys(config: Option<&localgpt_core::config::Config>) -> CheckResult
 }
 
 /// Check #7: Telegram token valid (if enabled)
-async fn check_telegram_token(config: Option<&localgpt_core::config::Config>) -> CheckResult {
+async fn check_telegram_token(
+    config: Option<&localgpt_core::config::Config>,
+    fix: Fix,
+) -> CheckResult {
     let config = match config {
         Some(c) => c,
         None => return CheckResult::pass("Telegram token", "Telegram not configured"),
@@ -524,9 +741,7 @@ async fn check_telegram_token(config: Option<&localgpt_core::config::Config>) ->
         .send()
         .await
     {
-        Ok(resp) if resp.status().is_success() => {
-            CheckResult::pass("Telegram token", "Telegram token valid")
-        }
+        Ok(resp) if resp.status().is_success() => check_bot_commands(token, config, fix).await,
         Ok(resp) => CheckResult::fail(
             "Telegram token",
             format!("Telegram API returned: {}", resp.status()),
@@ -540,6 +755,37 @@ async fn check_telegram_token(config: Option<&localgpt_core::config::Config>) ->
     }
 }
 
+/// Whether Telegram's command menu matches the core commands and skills
+async fn check_bot_commands(token: &str, config: &Config, fix: Fix) -> CheckResult {
+    let stale = match localgpt_server::telegram::bot_command_names(token, config).await {
+        Ok((registered, expected)) => registered != expected,
+        // The token itself was fine, so don't fail the check over the menu
+        Err(_) => false,
+    };
+    if !stale {
+        return CheckResult::pass("Telegram token", "Telegram token valid");
+    }
+
+    if !fix.confirm("Re-register the bot's commands with Telegram?") {
+        return CheckResult::warn(
+            "Telegram token",
+            "Telegram token valid, but the bot's command menu is out of date",
+            "Run with --fix to re-register the commands, or restart the daemon",
+        );
+    }
+    match localgpt_server::telegram::reregister_bot_commands(token, config).await {
+        Ok(count) => CheckResult::pass(
+            "Telegram token",
+            format!("Telegram token valid; re-registered {} bot commands", count),
+        ),
+        Err(e) => CheckResult::warn(
+            "Telegram token",
+            format!("Re-registering bot commands failed: {}", e),
+            "Check network connectivity",
+        ),
+    }
+}
+
 /// Check #8: MCP servers connectable (if configured)
 async fn check_mcp_servers(config: Option<&localgpt_core::config::Config>) -> CheckResult {
     let config = match config {
@@ -562,9 +808,35 @@ async fn check_mcp_servers(config: Option<&localgpt_core::config::Config>) -> Ch
                 failed_servers.push(server.name.clone());
             }
         }
+        // Servers with invalid filters are skipped at startup
+        if let Err(e) = localgpt_core::mcp::filter::ToolFilter::new(server) {
+            return CheckResult::warn(
+                "MCP servers",
+                e.to_string(),
+                "Fix the server's tools or arg_filters patterns",
+            );
+        }
     }
 
-    if failed_servers.is_empty() {
+    // Servers a running process is still trying to reconnect
+    let reconnecting: Vec<String> =
+        localgpt_core::mcp::supervisor::read_status(&config.paths.state_dir)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| {
+                s.state == localgpt_core::mcp::supervisor::ConnectionState::Reconnecting
+                    && super::daemon::is_process_running(&s.pid.to_string())
+            })
+            .map(|s| s.server)
+            .collect();
+
+    if !reconnecting.is_empty() {
+        CheckResult::warn(
+            "MCP servers",
+            format!("MCP server(s) down: {}", reconnecting.join(", ")),
+            "Run 'localgpt mcp status' for the last error",
+        )
+    } else if failed_servers.is_empty() {
         CheckResult::pass(
             "MCP servers",
             format!("{} MCP server(s) configured", config.mcp.servers.len()),
@@ -686,11 +958,12 @@ fn check_cron_expressions(config: Option<&localgpt_core::config::Config>) -> Che
     let mut invalid = Vec::new();
 
     for job in &config.cron.jobs {
-        if !job.enabled {
+        // Trigger-only jobs have no schedule
+        if !job.enabled || (job.schedule.trim().is_empty() && job.trigger.is_some()) {
             continue;
         }
-        // Try to parse the cron expression
-        if croner::Cron::new(&job.schedule).parse().is_err() {
+        // Try to parse the schedule (cron, interval, or natural language)
+        if localgpt_core::cron::interpret_schedule(&job.schedule).is_err() {
             invalid.push(format!("{}: {}", job.name, job.schedule));
         }
     }
@@ -705,7 +978,7 @@ fn check_cron_expressions(config: Option<&localgpt_core::config::Config>) -> Che
         CheckResult::fail(
             "Cron expressions",
             format!("Invalid cron expression(s): {}", invalid.join(", ")),
-            "Fix cron schedule syntax (use standard cron format)",
+            "Fix schedule syntax (cron expression, \"every 30m\", or \"every weekday at 9am\")",
         )
     }
 }
@@ -741,3 +1014,723 @@ async fn check_server_port(config: Option<&localgpt_core::config::Config>) -> Ch
         ),
     }
 }
+
+/// Check #13: Sandbox kernel support matches the configured level
+fn check_sandbox(config: Option<&localgpt_core::config::Config>) -> CheckResult {
+    use localgpt_sandbox::SandboxLevel;
+
+    let config = match config {
+        Some(c) => c,
+        None => return CheckResult::pass("Sandbox", "Cannot check without valid config"),
+    };
+    if !config.sandbox.enabled {
+        return CheckResult::warn(
+            "Sandbox",
+            "Shell sandbox disabled",
+            "Set sandbox.enabled = true to confine shell commands",
+        );
+    }
+
+    let caps = localgpt_sandbox::detect_capabilities();
+    let effective = caps.effective_level(&config.sandbox.level);
+    let name = |level: SandboxLevel| format!("{:?}", level).to_lowercase();
+
+    if effective == SandboxLevel::None {
+        CheckResult::warn(
+            "Sandbox",
+            "No kernel sandbox support; shell commands get rlimits and a timeout only",
+            "Run 'localgpt sandbox status' to see what's missing (Landlock, seccomp, Seatbelt)",
+        )
+    } else if !matches!(config.sandbox.level.as_str(), "auto" | "none")
+        && name(effective) != config.sandbox.level
+    {
+        CheckResult::warn(
+            "Sandbox",
+            format!(
+                "Sandbox level {} configured, but the kernel only supports {}",
+                config.sandbox.level,
+                name(effective)
+            ),
+            "Upgrade the kernel or lower sandbox.level",
+        )
+    } else {
+        CheckResult::pass("Sandbox", format!("Sandbox level {}", name(effective)))
+    }
+}
+
+/// Check #14: The daemon's bridge socket accepts connections
+async fn check_bridge_socket() -> CheckResult {
+    let paths = match localgpt_core::paths::Paths::resolve() {
+        Ok(p) => p,
+        Err(_) => return CheckResult::pass("Bridge socket", "Cannot check bridge socket"),
+    };
+    let daemon_running = std::fs::read_to_string(paths.pid_file())
+        .is_ok_and(|pid| super::daemon::is_process_running(&pid));
+    if !daemon_running {
+        return CheckResult::pass("Bridge socket", "Daemon not running; socket not checked");
+    }
+
+    let socket = paths.bridge_socket_name();
+
+    #[cfg(unix)]
+    {
+        let connect = tokio::net::UnixStream::connect(&socket);
+        match tokio::time::timeout(Duration::from_secs(2), connect).await {
+            Ok(Ok(_)) => CheckResult::pass(
+                "Bridge socket",
+                format!("Bridge socket accepting connections at {}", socket),
+            ),
+            Ok(Err(e)) => CheckResult::fail(
+                "Bridge socket",
+                format!("Cannot connect to bridge socket {}: {}", socket, e),
+                "Check the daemon log ('localgpt daemon logs') or restart the daemon",
+            ),
+            Err(_) => CheckResult::fail(
+                "Bridge socket",
+                format!("Bridge socket {} did not accept a connection", socket),
+                "Check the daemon log ('localgpt daemon logs') or restart the daemon",
+            ),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        CheckResult::pass(
+            "Bridge socket",
+            format!("Bridge pipe {} not checked on this platform", socket),
+        )
+    }
+}
+
+/// Check #15: Models used through Ollama are pulled
+async fn check_ollama_models(config: Option<&localgpt_core::config::Config>) -> CheckResult {
+    let config = match config {
+        Some(c) => c,
+        None => return CheckResult::pass("Ollama models", "Cannot check without valid config"),
+    };
+
+    let mut models: Vec<String> = std::iter::once(&config.agent.default_model)
+        .chain(&config.agent.fallback_models)
+        .filter_map(|m| m.strip_prefix("ollama/"))
+        .map(str::to_string)
+        .collect();
+    if let Some(ollama) = &config.providers.ollama {
+        models.push(ollama.model.clone());
+    }
+    models.sort();
+    models.dedup();
+    if models.is_empty() {
+        return CheckResult::pass("Ollama models", "Ollama not used");
+    }
+
+    let endpoint = config
+        .providers
+        .ollama
+        .as_ref()
+        .map_or("http://localhost:11434", |o| o.endpoint.as_str())
+        .trim_end_matches('/');
+    let tags: serde_json::Value = match reqwest::Client::new()
+        .get(format!("{}/api/tags", endpoint))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+    {
+        Ok(resp) if resp.status().is_success() => resp.json().await.unwrap_or_default(),
+        Ok(resp) => {
+            return CheckResult::fail(
+                "Ollama models",
+                format!("Ollama at {} returned {}", endpoint, resp.status()),
+                "Check providers.ollama.endpoint",
+            );
+        }
+        Err(e) => {
+            return CheckResult::fail(
+                "Ollama models",
+                format!("Cannot reach Ollama at {}: {}", endpoint, e),
+                "Start Ollama ('ollama serve') or fix providers.ollama.endpoint",
+            );
+        }
+    };
+
+    let pulled: Vec<&str> = tags["models"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m["name"].as_str())
+        .collect();
+    // "llama3" means "llama3:latest"
+    let is_pulled = |model: &str| {
+        pulled.iter().any(|name| {
+            *name == model || (!model.contains(':') && *name == format!("{}:latest", model))
+        })
+    };
+    let missing: Vec<&String> = models.iter().filter(|m| !is_pulled(m)).collect();
+
+    if missing.is_empty() {
+        CheckResult::pass("Ollama models", format!("Ollama has {}", models.join(", ")))
+    } else {
+        let names: Vec<&str> = missing.iter().map(|m| m.as_str()).collect();
+        CheckResult::fail(
+            "Ollama models",
+            format!("Ollama models not pulled: {}", names.join(", ")),
+            format!("Run 'ollama pull {}'", names[0]),
+        )
+    }
+}
+
+/// Check #16: A GPU for local embeddings
+fn check_gpu(config: Option<&localgpt_core::config::Config>) -> CheckResult {
+    let config = match config {
+        Some(c) => c,
+        None => return CheckResult::pass("GPU", "Cannot check without valid config"),
+    };
+
+    let provider = config.memory.embedding_provider.to_lowercase();
+    let gpu = detect_gpu();
+    match (provider.as_str(), gpu) {
+        ("local", gpu) => CheckResult::pass(
+            "GPU",
+            format!(
+                "Local embeddings (fastembed) run on the CPU; GPU: {}",
+                gpu.unwrap_or("none found")
+            ),
+        ),
+        ("gguf", Some(gpu)) => CheckResult::pass("GPU", format!("GGUF embeddings can use {}", gpu)),
+        ("gguf", None) => CheckResult::warn(
+            "GPU",
+            "No GPU found; GGUF embeddings will run on the CPU",
+            "Expect slower reindexing, or use memory.embedding_provider = \"local\"",
+        ),
+        _ => CheckResult::pass("GPU", "Local embeddings not used"),
+    }
+}
+
+/// Name of a GPU usable for inference, if one is found
+fn detect_gpu() -> Option<&'static str> {
+    if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        return Some("Apple Silicon (Metal)");
+    }
+    if cfg!(target_os = "linux") {
+        let exists = |path: &str| std::path::Path::new(path).exists();
+        if exists("/dev/nvidia0") {
+            return Some("NVIDIA (CUDA)");
+        }
+        if exists("/dev/kfd") {
+            return Some("AMD (ROCm)");
+        }
+        if exists("/dev/dri/renderD128") {
+            return Some("GPU render node (Vulkan)");
+        }
+    }
+    None
+}
+
+/// Check #17: Installed skills are eligible to run
+fn check_skills(config: Option<&localgpt_core::config::Config>) -> CheckResult {
+    let config = match config {
+        Some(c) => c,
+        None => return CheckResult::pass("Skills", "Cannot check without valid config"),
+    };
+
+    let skills = match localgpt_core::agent::load_skills(
+        &config.workspace_path(),
+        &config.skills.trust_policy(),
+    ) {
+        Ok(skills) => skills,
+        Err(e) => {
+            return CheckResult::warn(
+                "Skills",
+                format!("Cannot load skills: {}", e),
+                "Check the skills directories",
+            );
+        }
+    };
+    if skills.is_empty() {
+        return CheckResult::pass("Skills", "No skills installed");
+    }
+
+    let blocked: Vec<String> = skills
+        .iter()
+        .filter(|s| !s.eligibility.is_ready())
+        .map(|s| format!("{} ({})", s.name, s.eligibility.describe()))
+        .collect();
+    if blocked.is_empty() {
+        CheckResult::pass("Skills", format!("{} skill(s) ready", skills.len()))
+    } else {
+        CheckResult::warn(
+            "Skills",
+            format!(
+                "{} of {} skill(s) ready; not eligible: {}",
+                skills.len() - blocked.len(),
+                skills.len(),
+                blocked.join(", ")
+            ),
+            "Install the missing tools or set the variables; 'localgpt skills list' has details",
+        )
+    }
+}
+
+/// Check #18: The system clock agrees with a provider's servers
+async fn check_clock_skew(config: Option<&localgpt_core::config::Config>) -> CheckResult {
+    let config = match config {
+        Some(c) => c,
+        None => return CheckResult::pass("Clock", "Cannot check without valid config"),
+    };
+
+    // Compare against a remote API already in use; don't contact anything new
+    let providers = &config.providers;
+    let url = providers
+        .anthropic
+        .as_ref()
+        .map(|p| p.base_url.clone())
+        .or_else(|| providers.openai.as_ref().map(|p| p.base_url.clone()))
+        .or_else(|| providers.xai.as_ref().map(|p| p.base_url.clone()))
+        .or_else(|| {
+            providers
+                .openai_compatible
+                .as_ref()
+                .map(|p| p.base_url.clone())
+        });
+    let Some(url) = url else {
+        return CheckResult::pass(
+            "Clock",
+            "No remote API configured to compare the clock with",
+        );
+    };
+
+    let sent = chrono::Utc::now();
+    let response = match reqwest::Client::new()
+        .head(&url)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+    {
+        Ok(resp) => resp,
+        Err(e) => {
+            return CheckResult::warn(
+                "Clock",
+                format!("Cannot reach {} to compare clocks: {}", url, e),
+                "Check network connectivity",
+            );
+        }
+    };
+    let received = chrono::Utc::now();
+
+    let server_time = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|d| d.to_str().ok())
+        .and_then(|d| chrono::DateTime::parse_from_rfc2822(d).ok());
+    let Some(server_time) = server_time else {
+        return CheckResult::pass("Clock", format!("{} sent no Date header", url));
+    };
+
+    let local = sent + (received - sent) / 2;
+    let skew = (local - server_time.with_timezone(&chrono::Utc)).num_seconds();
+    let message = format!(
+        "Clock is {}s {} {}",
+        skew.abs(),
+        if skew >= 0 { "ahead of" } else { "behind" },
+        url
+    );
+    match skew.abs() {
+        0..=30 => CheckResult::pass(
+            "Clock",
+            format!("Clock in sync with {} ({}s off)", url, skew.abs()),
+        ),
+        31..=300 => CheckResult::warn("Clock", message, "Enable NTP time sync"),
+        _ => CheckResult::fail(
+            "Clock",
+            message,
+            "Enable NTP time sync; skewed clocks break OAuth tokens and TLS",
+        ),
+    }
+}
+
+/// Whether the daemon named in the PID file is alive
+fn daemon_running(paths: &Paths) -> bool {
+    std::fs::read_to_string(paths.pid_file())
+        .is_ok_and(|pid| super::daemon::is_process_running(pid.trim()))
+}
+
+/// Check #19: No stale workspace lock
+fn check_workspace_lock(fix: Fix) -> CheckResult {
+    let paths = match Paths::resolve() {
+        Ok(p) => p,
+        Err(_) => return CheckResult::pass("Workspace lock", "Cannot check workspace lock"),
+    };
+    let lock_path = paths.workspace_lock();
+    if !lock_path.exists() {
+        return CheckResult::pass("Workspace lock", "No workspace lock");
+    }
+
+    // The lock is an flock, so it normally dies with its holder. One still
+    // held while every recorded holder has exited, or a file nothing can
+    // open (say, left by a run as another user), blocks every agent turn;
+    // one nothing holds is just left over.
+    let Ok(lock) = WorkspaceLock::new() else {
+        return CheckResult::pass("Workspace lock", "Cannot check workspace lock");
+    };
+    let holders = lock.holders();
+    let problem = match lock.try_acquire_read() {
+        Ok(None) => {
+            let live: Vec<String> = holders
+                .iter()
+                .filter(|h| h.is_alive())
+                .map(super::daemon::describe_lock_holder)
+                .collect();
+            if !live.is_empty() {
+                return CheckResult::pass(
+                    "Workspace lock",
+                    format!("Workspace lock held by {}", live.join(", ")),
+                );
+            }
+            if holders.is_empty() {
+                return CheckResult::pass(
+                    "Workspace lock",
+                    "Workspace lock held by a running turn",
+                );
+            }
+            format!(
+                "Stale workspace lock: held, but its holders have exited ({})",
+                holders
+                    .iter()
+                    .map(super::daemon::describe_lock_holder)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+        Ok(Some(_guard)) if daemon_running(&paths) => {
+            return CheckResult::pass("Workspace lock", "Workspace lock free");
+        }
+        Ok(Some(_guard)) => "Stale workspace lock file (nothing holds it)".to_string(),
+        Err(e) => format!("Cannot open workspace lock: {}", e),
+    };
+
+    if !fix.confirm(&format!(
+        "Remove the workspace lock {}?",
+        lock_path.display()
+    )) {
+        return CheckResult::warn(
+            "Workspace lock",
+            problem,
+            format!("Run with --fix to remove {}", lock_path.display()),
+        );
+    }
+    match lock.break_lock() {
+        Ok(()) => CheckResult::pass("Workspace lock", "Removed stale workspace lock"),
+        Err(e) => CheckResult::fail(
+            "Workspace lock",
+            format!("Cannot remove workspace lock: {}", e),
+            format!("Remove {} manually", lock_path.display()),
+        ),
+    }
+}
+
+/// A secret encrypted with a key derived from the device key
+enum Credential {
+    McpTokens(String),
+    Bridge(String),
+    /// An `enc:` value in config.toml, by key
+    ConfigSecret(String),
+}
+
+impl Credential {
+    fn label(&self) -> String {
+        match self {
+            Credential::McpTokens(server) => {
+                format!("the OAuth tokens for MCP server '{}'", server)
+            }
+            Credential::Bridge(id) => format!("the credentials for bridge '{}'", id),
+            Credential::ConfigSecret(key) => format!("the encrypted config secret '{}'", key),
+        }
+    }
+
+    /// Command that recreates the credential from scratch
+    fn recreate_hint(&self) -> String {
+        match self {
+            Credential::McpTokens(server) => format!("localgpt mcp login {}", server),
+            Credential::Bridge(id) => format!("localgpt bridge register --id {} --secret ...", id),
+            Credential::ConfigSecret(key) => format!("localgpt secret set {}", key),
+        }
+    }
+}
+
+/// Credentials stored under `data_dir`, and `enc:` secrets in the config file
+fn encrypted_credentials(paths: &Paths) -> Vec<Credential> {
+    let data_dir = &paths.data_dir;
+    let mut mcp_servers: Vec<String> = std::fs::read_dir(data_dir.join("mcp-tokens"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.strip_suffix(".enc").map(str::to_string)
+        })
+        .collect();
+    mcp_servers.sort();
+
+    mcp_servers
+        .into_iter()
+        .map(Credential::McpTokens)
+        .chain(
+            localgpt_server::security::encrypted_bridge_ids(data_dir)
+                .into_iter()
+                .map(Credential::Bridge),
+        )
+        .chain(
+            encrypted_config_keys(&paths.config_file())
+                .into_iter()
+                .map(Credential::ConfigSecret),
+        )
+        .collect()
+}
+
+/// Dotted keys of the `enc:` values in the config file at `path`
+fn encrypted_config_keys(path: &Path) -> Vec<String> {
+    fn walk(value: &toml::Value, prefix: &str, out: &mut Vec<String>) {
+        match value {
+            toml::Value::Table(table) => {
+                for (key, value) in table {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(value, &path, out);
+                }
+            }
+            toml::Value::String(s) if localgpt_core::security::is_encrypted_secret(s) => {
+                out.push(prefix.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    let Some(raw) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+    else {
+        return Vec::new();
+    };
+    let mut keys = Vec::new();
+    walk(&raw, "", &mut keys);
+    keys
+}
+
+/// The raw value of the dotted `key` in the config file at `path`
+fn raw_config_string(path: &Path, key: &str) -> Option<String> {
+    let raw: toml::Value = toml::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    key.split('.')
+        .try_fold(&raw, |value, part| value.as_table()?.get(part))?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// A credential decrypted with the old device key, ready to re-encrypt
+enum Plaintext {
+    McpTokens(localgpt_core::mcp::oauth::McpTokens),
+    Bridge(Vec<u8>),
+    ConfigSecret(String),
+}
+
+fn decrypt_credential(paths: &Paths, credential: &Credential) -> Option<Plaintext> {
+    let data_dir = &paths.data_dir;
+    match credential {
+        Credential::McpTokens(server) => localgpt_core::mcp::oauth::load_tokens(data_dir, server)
+            .ok()
+            .flatten()
+            .map(Plaintext::McpTokens),
+        Credential::Bridge(id) => localgpt_server::security::read_bridge_secret(data_dir, id)
+            .ok()
+            .map(Plaintext::Bridge),
+        Credential::ConfigSecret(key) => raw_config_string(&paths.config_file(), key)
+            .and_then(|value| localgpt_core::security::decrypt_config_secret(data_dir, &value).ok())
+            .map(Plaintext::ConfigSecret),
+    }
+}
+
+fn encrypt_credential(paths: &Paths, credential: &Credential, plaintext: &Plaintext) -> Result<()> {
+    let data_dir = &paths.data_dir;
+    match (credential, plaintext) {
+        (Credential::McpTokens(server), Plaintext::McpTokens(tokens)) => {
+            localgpt_core::mcp::oauth::save_tokens(data_dir, server, tokens)
+        }
+        (Credential::Bridge(id), Plaintext::Bridge(secret)) => {
+            localgpt_server::security::write_bridge_secret(data_dir, id, secret)
+        }
+        (Credential::ConfigSecret(key), Plaintext::ConfigSecret(secret)) => {
+            let encrypted = localgpt_core::security::encrypt_config_secret(data_dir, secret)?;
+            localgpt_core::config::set_config_string(&paths.config_file(), key, &encrypted)?;
+            Ok(())
+        }
+        _ => anyhow::bail!("Credential and plaintext don't match"),
+    }
+}
+
+fn remove_credential(paths: &Paths, credential: &Credential) -> Result<()> {
+    let data_dir = &paths.data_dir;
+    match credential {
+        Credential::McpTokens(server) => {
+            localgpt_core::mcp::oauth::delete_tokens(data_dir, server)?;
+        }
+        Credential::Bridge(id) => {
+            std::fs::remove_file(data_dir.join("bridges").join(format!("{}.enc", id)))?;
+        }
+        Credential::ConfigSecret(key) => {
+            localgpt_core::config::set_config_string(&paths.config_file(), key, "")?;
+        }
+    }
+    Ok(())
+}
+
+/// Check #20: Device key usable, and private
+fn check_device_key(config: Option<&Config>, fix: Fix) -> CheckResult {
+    let paths = match Paths::resolve() {
+        Ok(p) => p,
+        Err(_) => return CheckResult::pass("Device key", "Cannot check device key"),
+    };
+    let key_path = paths.device_key();
+    let credentials = encrypted_credentials(&paths);
+
+    let problem = match localgpt_core::security::read_device_key(&paths.data_dir) {
+        Ok(_) if key_readable_by_others(&key_path) => {
+            "Device key is readable by other users".to_string()
+        }
+        Ok(_) => {
+            return CheckResult::pass(
+                "Device key",
+                format!(
+                    "Device key OK ({} encrypted credential(s))",
+                    credentials.len()
+                ),
+            );
+        }
+        Err(_) if !key_path.exists() && credentials.is_empty() => {
+            if !fix.confirm("Generate a device key?") {
+                return CheckResult::warn(
+                    "Device key",
+                    "No device key",
+                    "Run with --fix or 'localgpt init' to generate one",
+                );
+            }
+            return match localgpt_core::security::ensure_device_key(&paths.data_dir) {
+                Ok(()) => CheckResult::pass("Device key", "Generated device key"),
+                Err(e) => CheckResult::fail(
+                    "Device key",
+                    format!("Cannot generate device key: {}", e),
+                    "Check permissions on the data directory",
+                ),
+            };
+        }
+        Err(e) => format!("Device key unusable: {}", e),
+    };
+
+    if !fix.confirm("Regenerate the device key?") {
+        return CheckResult::fail(
+            "Device key",
+            problem,
+            "Run with --fix to regenerate it and re-encrypt stored credentials",
+        );
+    }
+    match regenerate_device_key(&paths, config, &credentials, fix) {
+        Ok(follow_ups) if follow_ups.is_empty() => {
+            CheckResult::pass("Device key", "Regenerated device key")
+        }
+        Ok(follow_ups) => CheckResult::warn(
+            "Device key",
+            "Regenerated device key; some credentials need recreating",
+            format!("Run {}", follow_ups.join(", ")),
+        ),
+        Err(e) => CheckResult::fail(
+            "Device key",
+            format!("Regenerating the device key failed: {}", e),
+            format!("Restore {} from the .bak copy", key_path.display()),
+        ),
+    }
+}
+
+#[cfg(unix)]
+fn key_readable_by_others(key_path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(key_path).is_ok_and(|m| m.permissions().mode() & 0o077 != 0)
+}
+
+#[cfg(not(unix))]
+fn key_readable_by_others(_key_path: &Path) -> bool {
+    false
+}
+
+/// Replace the device key, re-encrypting each credential the old key can
+/// still decrypt and re-signing LocalGPT.md, each after asking. Returns the
+/// commands the user still has to run.
+fn regenerate_device_key(
+    paths: &Paths,
+    config: Option<&Config>,
+    credentials: &[Credential],
+    fix: Fix,
+) -> Result<Vec<String>> {
+    let data_dir = &paths.data_dir;
+    let key_path = paths.device_key();
+
+    // Decrypt before the old key goes away
+    let plaintexts: Vec<Option<Plaintext>> = credentials
+        .iter()
+        .map(|c| decrypt_credential(paths, c))
+        .collect();
+
+    if key_path.exists() {
+        let backup = key_path.with_extension("key.bak");
+        std::fs::rename(&key_path, &backup)?;
+        eprintln!("Moved the old device key to {}", backup.display());
+    }
+    localgpt_core::security::ensure_device_key(data_dir)?;
+
+    let mut follow_ups = Vec::new();
+    for (credential, plaintext) in credentials.iter().zip(&plaintexts) {
+        let kept = match plaintext {
+            Some(plaintext) => {
+                fix.confirm(&format!(
+                    "Re-encrypt {} with the new key?",
+                    credential.label()
+                )) && encrypt_credential(paths, credential, plaintext).is_ok()
+            }
+            None => {
+                eprintln!("{} can't be decrypted with the old key", credential.label());
+                false
+            }
+        };
+        if !kept {
+            if fix.confirm(&format!("Remove {}?", credential.label())) {
+                remove_credential(paths, credential)?;
+            }
+            follow_ups.push(format!("'{}'", credential.recreate_hint()));
+        }
+    }
+
+    // The policy signature is an HMAC with the device key
+    if let Some(config) = config {
+        let workspace = config.workspace_path();
+        if workspace
+            .join(localgpt_core::security::POLICY_FILENAME)
+            .exists()
+        {
+            let signed = fix.confirm("Re-sign LocalGPT.md with the new key?")
+                && localgpt_core::security::sign_policy(data_dir, &workspace, "cli")
+                    .and_then(|manifest| {
+                        localgpt_core::security::append_audit_entry(
+                            &paths.state_dir,
+                            localgpt_core::security::AuditAction::Signed,
+                            &manifest.content_sha256,
+                            "cli",
+                        )
+                    })
+                    .is_ok();
+            if !signed {
+                follow_ups.push("'localgpt md sign'".to_string());
+            }
+        }
+    }
+
+    Ok(follow_ups)
+}
@@ -1,12 +1,34 @@
-use anyhow::Result;
+//! First-time setup: `localgpt init`
+//!
+//! Walks through picking a provider (installed CLIs and a running Ollama are
+//! detected), entering its API key, the workspace location, the embedding
+//! provider, and optional Telegram or bridge credentials, then writes a
+//! config.toml that has passed `config validate`. Without a terminal, or with
+//! `--defaults`, every question takes its default.
+
+use anyhow::{Result, bail};
 use clap::Args;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use localgpt_core::config::{Config, set_config_string, set_config_value, validate_config_file};
 use localgpt_core::paths::Paths;
 use localgpt_core::security::ensure_device_key;
+use localgpt_server::BridgeManager;
 
 #[derive(Args)]
-pub struct InitArgs {}
+pub struct InitArgs {
+    /// Run the setup again even though a config file exists (it is replaced)
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Don't ask; accept the detected defaults
+    #[arg(long)]
+    pub defaults: bool,
+}
 
-pub fn run(_args: InitArgs) -> Result<()> {
+pub async fn run(args: InitArgs) -> Result<()> {
     let paths = Paths::resolve()?;
 
     // Ensure directories exist
@@ -15,10 +37,498 @@ pub fn run(_args: InitArgs) -> Result<()> {
     // Generate device key in data directory
     ensure_device_key(&paths.data_dir)?;
 
-    println!("Initialized LocalGPT configuration.");
-    println!("  Data: {}", paths.data_dir.display());
-    println!("  State: {}", paths.state_dir.display());
+    let config_path = paths.config_file();
+    if config_path.exists() && !args.force {
+        println!("Initialized LocalGPT configuration.");
+        println!("  Config: {} (existing)", config_path.display());
+        println!("  Data: {}", paths.data_dir.display());
+        println!("  State: {}", paths.state_dir.display());
+        println!("  Device Key: {}", paths.device_key().display());
+        println!();
+        println!("Run 'localgpt init --force' to go through setup again.");
+        return Ok(());
+    }
+
+    let prompt = Prompt {
+        interactive: !args.defaults && std::io::stdin().is_terminal(),
+    };
+    if prompt.interactive {
+        println!("LocalGPT Setup");
+        println!("==============\n");
+    }
+
+    let mut settings = Vec::new();
+
+    // Provider and model
+    let detected = detect_providers().await;
+    let provider = choose_provider(&prompt, &detected)?;
+    configure_provider(&prompt, provider, &detected, &mut settings)?;
+
+    // Workspace
+    let default_ws = paths.workspace.display().to_string();
+    let workspace = prompt.ask("Workspace directory", &default_ws)?;
+    let workspace_path = PathBuf::from(shellexpand::tilde(&workspace).to_string());
+    if !workspace_path.is_absolute() {
+        bail!("Workspace must be an absolute path: {}", workspace);
+    }
+    if workspace_path != paths.workspace {
+        settings.push(Setting::string("memory.workspace", &workspace));
+    }
+
+    // Embeddings
+    let openai_configured = settings.iter().any(|s| s.key == "providers.openai.api_key");
+    configure_embeddings(&prompt, openai_configured, &mut settings)?;
+
+    // Telegram and bridges
+    let bridges = configure_messaging(&prompt, &mut settings)?;
+
+    write_config(&config_path, &settings)?;
+    std::fs::create_dir_all(&workspace_path)?;
+
+    let manager = BridgeManager::new();
+    for (id, secret) in &bridges {
+        manager.register_bridge(id, secret.as_bytes()).await?;
+        println!("Stored credentials for bridge '{}'.", id);
+    }
+
+    println!();
+    println!("Wrote {}", config_path.display());
+    println!("  Model: {}", provider_model(&settings));
+    println!("  Workspace: {}", workspace_path.display());
     println!("  Device Key: {}", paths.device_key().display());
+    println!();
+    println!("Next: 'localgpt doctor' to check the setup, then 'localgpt chat'.");
+    Ok(())
+}
+
+// ── Questions ──
+
+/// Asks on stdin when interactive; otherwise every answer is the default.
+struct Prompt {
+    interactive: bool,
+}
+
+impl Prompt {
+    fn read_line(&self, question: &str) -> Result<String> {
+        print!("{}", question);
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer)? == 0 {
+            bail!("Setup cancelled");
+        }
+        Ok(answer.trim().to_string())
+    }
+
+    /// Free-text answer; empty input takes `default`.
+    fn ask(&self, question: &str, default: &str) -> Result<String> {
+        if !self.interactive {
+            return Ok(default.to_string());
+        }
+        let answer = if default.is_empty() {
+            self.read_line(&format!("{}: ", question))?
+        } else {
+            self.read_line(&format!("{} [{}]: ", question, default))?
+        };
+        Ok(if answer.is_empty() {
+            default.to_string()
+        } else {
+            answer
+        })
+    }
+
+    fn confirm(&self, question: &str, default: bool) -> Result<bool> {
+        if !self.interactive {
+            return Ok(default);
+        }
+        let hint = if default { "[Y/n]" } else { "[y/N]" };
+        loop {
+            let answer = self.read_line(&format!("{} {} ", question, hint))?;
+            match answer.to_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => println!("Please answer y or n."),
+            }
+        }
+    }
+
+    /// Numbered menu; returns the index of the chosen option.
+    fn choose(&self, question: &str, options: &[String], default: usize) -> Result<usize> {
+        if !self.interactive {
+            return Ok(default);
+        }
+        println!("{}", question);
+        for (i, option) in options.iter().enumerate() {
+            println!("  {}) {}", i + 1, option);
+        }
+        loop {
+            let answer = self.read_line(&format!("Choice [{}]: ", default + 1))?;
+            if answer.is_empty() {
+                return Ok(default);
+            }
+            match answer.parse::<usize>() {
+                Ok(n) if (1..=options.len()).contains(&n) => return Ok(n - 1),
+                _ => println!("Enter a number from 1 to {}.", options.len()),
+            }
+        }
+    }
+}
+
+/// One config value to write, in order
+struct Setting {
+    key: &'static str,
+    value: String,
+    /// Written as a string even if it looks like another TOML type
+    is_string: bool,
+}
+
+impl Setting {
+    fn string(key: &'static str, value: &str) -> Self {
+        Self {
+            key,
+            value: value.to_string(),
+            is_string: true,
+        }
+    }
+
+    fn raw(key: &'static str, value: &str) -> Self {
+        Self {
+            key,
+            value: value.to_string(),
+            is_string: false,
+        }
+    }
+}
+
+// ── Providers ──
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Provider {
+    ClaudeCli,
+    GeminiCli,
+    CodexCli,
+    Ollama,
+    Anthropic,
+    OpenAI,
+}
+
+const PROVIDERS: [Provider; 6] = [
+    Provider::ClaudeCli,
+    Provider::GeminiCli,
+    Provider::CodexCli,
+    Provider::Ollama,
+    Provider::Anthropic,
+    Provider::OpenAI,
+];
 
+impl Provider {
+    fn label(self) -> &'static str {
+        match self {
+            Provider::ClaudeCli => "Claude CLI (uses your Claude Code login)",
+            Provider::GeminiCli => "Gemini CLI (uses your Gemini CLI login)",
+            Provider::CodexCli => "Codex CLI (uses your Codex login)",
+            Provider::Ollama => "Ollama (local models)",
+            Provider::Anthropic => "Anthropic API (API key)",
+            Provider::OpenAI => "OpenAI API (API key)",
+        }
+    }
+
+    fn default_model(self) -> &'static str {
+        match self {
+            Provider::ClaudeCli => "claude-cli/opus",
+            Provider::GeminiCli => "gemini-cli/gemini-3.1-pro-preview",
+            Provider::CodexCli => "codex-cli/o4-mini",
+            Provider::Ollama => "ollama/llama3",
+            Provider::Anthropic => "anthropic/claude-sonnet-4-6",
+            Provider::OpenAI => "openai/gpt-4o",
+        }
+    }
+
+    /// Environment variable the API key is usually in
+    fn key_env(self) -> Option<&'static str> {
+        match self {
+            Provider::Anthropic => Some("ANTHROPIC_API_KEY"),
+            Provider::OpenAI => Some("OPENAI_API_KEY"),
+            _ => None,
+        }
+    }
+}
+
+/// What was found on this machine
+struct Detected {
+    claude_cli: bool,
+    gemini_cli: bool,
+    codex_cli: bool,
+    /// Pulled models, if Ollama answered
+    ollama_models: Option<Vec<String>>,
+}
+
+impl Detected {
+    /// Why a provider looks usable, if it does
+    fn note(&self, provider: Provider) -> Option<String> {
+        match provider {
+            Provider::ClaudeCli => self.claude_cli.then(|| "claude found".to_string()),
+            Provider::GeminiCli => self.gemini_cli.then(|| "gemini found".to_string()),
+            Provider::CodexCli => self.codex_cli.then(|| "codex found".to_string()),
+            Provider::Ollama => self
+                .ollama_models
+                .as_ref()
+                .map(|models| format!("running, {} models", models.len())),
+            Provider::Anthropic | Provider::OpenAI => provider
+                .key_env()
+                .filter(|var| std::env::var_os(var).is_some())
+                .map(|var| format!("{} set", var)),
+        }
+    }
+}
+
+async fn detect_providers() -> Detected {
+    Detected {
+        claude_cli: which::which("claude").is_ok(),
+        gemini_cli: which::which("gemini").is_ok(),
+        codex_cli: which::which("codex").is_ok(),
+        ollama_models: ollama_models(OLLAMA_ENDPOINT).await,
+    }
+}
+
+const OLLAMA_ENDPOINT: &str = "http://localhost:11434";
+
+/// Models pulled into the Ollama at `endpoint`, or None if it doesn't answer
+async fn ollama_models(endpoint: &str) -> Option<Vec<String>> {
+    let tags: serde_json::Value = reqwest::Client::new()
+        .get(format!("{}/api/tags", endpoint))
+        .timeout(Duration::from_secs(2))
+        .send()
+        .await
+        .ok()
+        .filter(|resp| resp.status().is_success())?
+        .json()
+        .await
+        .ok()?;
+    Some(
+        tags["models"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m["name"].as_str())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+fn choose_provider(prompt: &Prompt, detected: &Detected) -> Result<Provider> {
+    let options: Vec<String> = PROVIDERS
+        .iter()
+        .map(|&p| match detected.note(p) {
+            Some(note) => format!("{} — {}", p.label(), note),
+            None => p.label().to_string(),
+        })
+        .collect();
+    // Default to the first provider that looks usable
+    let default = PROVIDERS
+        .iter()
+        .position(|&p| detected.note(p).is_some())
+        .unwrap_or(0);
+    let choice = prompt.choose(
+        "Which model provider should LocalGPT use?",
+        &options,
+        default,
+    )?;
+    Ok(PROVIDERS[choice])
+}
+
+fn configure_provider(
+    prompt: &Prompt,
+    provider: Provider,
+    detected: &Detected,
+    settings: &mut Vec<Setting>,
+) -> Result<()> {
+    let model = match provider {
+        Provider::ClaudeCli | Provider::GeminiCli | Provider::CodexCli => {
+            let (command, key) = match provider {
+                Provider::ClaudeCli => ("claude", "providers.claude_cli.command"),
+                Provider::GeminiCli => ("gemini", "providers.gemini_cli.command"),
+                _ => ("codex", "providers.codex_cli.command"),
+            };
+            if detected.note(provider).is_none() {
+                println!(
+                    "Note: '{}' is not on PATH; install it before chatting.",
+                    command
+                );
+            }
+            settings.push(Setting::string(key, command));
+            provider.default_model().to_string()
+        }
+        Provider::Ollama => {
+            settings.push(Setting::string(
+                "providers.ollama.endpoint",
+                OLLAMA_ENDPOINT,
+            ));
+            let model = match detected.ollama_models.as_deref() {
+                Some(models) if !models.is_empty() => {
+                    let choice = prompt.choose("Which Ollama model?", models, 0)?;
+                    models[choice].clone()
+                }
+                _ => {
+                    println!("Note: no pulled Ollama models found; run 'ollama pull <model>'.");
+                    prompt.ask("Ollama model", "llama3")?
+                }
+            };
+            settings.push(Setting::string("providers.ollama.model", &model));
+            format!("ollama/{}", model)
+        }
+        Provider::Anthropic | Provider::OpenAI => {
+            let (key, env) = match provider {
+                Provider::Anthropic => ("providers.anthropic.api_key", "ANTHROPIC_API_KEY"),
+                _ => ("providers.openai.api_key", "OPENAI_API_KEY"),
+            };
+            let api_key = ask_api_key(prompt, env)?;
+            settings.push(Setting::string(key, &api_key));
+            prompt.ask("Model", provider.default_model())?
+        }
+    };
+    settings.push(Setting::string("agent.default_model", &model));
+    Ok(())
+}
+
+/// An API key, or a `${VAR}` reference when the key is already in the
+/// environment (so it never lands in config.toml)
+fn ask_api_key(prompt: &Prompt, env: &str) -> Result<String> {
+    let reference = format!("${{{}}}", env);
+    if std::env::var_os(env).is_some()
+        && prompt.confirm(&format!("Use the key from ${}?", env), true)?
+    {
+        return Ok(reference);
+    }
+    if !prompt.interactive {
+        // Nothing to ask; the key can be exported later
+        return Ok(reference);
+    }
+    let key = prompt.ask(
+        &format!("API key (leave empty to read ${} at startup)", env),
+        "",
+    )?;
+    Ok(if key.is_empty() { reference } else { key })
+}
+
+fn configure_embeddings(
+    prompt: &Prompt,
+    openai_configured: bool,
+    settings: &mut Vec<Setting>,
+) -> Result<()> {
+    let mut options = vec![
+        (
+            "local",
+            "Local (fastembed; downloads a small model on first use)",
+        ),
+        ("none", "None (keyword search only)"),
+    ];
+    if openai_configured {
+        options.insert(1, ("openai", "OpenAI (uses your OpenAI key)"));
+    }
+    let labels: Vec<String> = options.iter().map(|(_, label)| label.to_string()).collect();
+    let choice = prompt.choose("How should memory be embedded for search?", &labels, 0)?;
+    let provider = options[choice].0;
+    settings.push(Setting::string("memory.embedding_provider", provider));
+    if provider == "openai" {
+        settings.push(Setting::string(
+            "memory.embedding_model",
+            "text-embedding-3-small",
+        ));
+    }
     Ok(())
 }
+
+/// Telegram and bridge setup. Returns bridge credentials to store once the
+/// config is written.
+fn configure_messaging(
+    prompt: &Prompt,
+    settings: &mut Vec<Setting>,
+) -> Result<Vec<(String, String)>> {
+    let mut bridges = Vec::new();
+
+    if prompt.confirm("Set up a Telegram bot?", false)? {
+        let token = prompt.ask("Bot token from @BotFather", "")?;
+        if token.is_empty() {
+            println!("No token given; skipping Telegram.");
+        } else if prompt.confirm(
+            "Run it as a standalone bridge (token kept in the encrypted credential store)?",
+            true,
+        )? {
+            bridges.push(("telegram".to_string(), token));
+        } else {
+            settings.push(Setting::string("telegram.api_token", &token));
+            settings.push(Setting::raw("telegram.enabled", "true"));
+        }
+    }
+
+    while prompt.confirm(
+        "Register credentials for another bridge (Discord, WhatsApp, ...)?",
+        false,
+    )? {
+        let id = prompt.ask("Bridge ID (e.g. discord)", "")?;
+        let secret = prompt.ask("Secret or token", "")?;
+        if id.is_empty() || secret.is_empty() {
+            println!("Bridge ID and secret are both required; skipped.");
+            continue;
+        }
+        bridges.push((id, secret));
+    }
+
+    Ok(bridges)
+}
+
+fn provider_model(settings: &[Setting]) -> &str {
+    settings
+        .iter()
+        .rev()
+        .find(|s| s.key == "agent.default_model")
+        .map_or("claude-cli/opus", |s| s.value.as_str())
+}
+
+// ── Writing ──
+
+/// Apply `settings` to the config template in a scratch file, validate it,
+/// and only then move it into place.
+fn write_config(path: &Path, settings: &[Setting]) -> Result<()> {
+    let draft = path.with_extension("toml.init");
+    std::fs::write(&draft, super::config::DEFAULT_CONFIG_TEMPLATE)?;
+
+    let result = (|| -> Result<()> {
+        for setting in settings {
+            if setting.is_string {
+                set_config_string(&draft, setting.key, &setting.value)?;
+            } else {
+                set_config_value(&draft, setting.key, &setting.value)?;
+            }
+        }
+        let errors: Vec<String> = validate_config_file(&draft)?
+            .into_iter()
+            .filter(|issue| issue.is_error)
+            .map(|issue| issue.message)
+            .collect();
+        if !errors.is_empty() {
+            bail!("Generated config is invalid: {}", errors.join("; "));
+        }
+        // The file may hold API keys
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&draft, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => {
+            std::fs::rename(&draft, path)?;
+            // Make sure it loads the way the rest of LocalGPT will load it
+            Config::load()?;
+            Ok(())
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&draft);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! CLI subcommand: `localgpt keyring`
+//!
+//! Stores secrets in the OS keyring (macOS Keychain, Secret Service,
+//! Windows Credential Manager) so config.toml can name them instead of
+//! holding them: `api_key = "keyring:openai"`. Secrets are read from stdin
+//! so they stay out of shell history.
+
+use anyhow::{Result, bail};
+use clap::{Args, Subcommand};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use localgpt_core::security::{
+    KEYRING_PREFIX, delete_keyring_secret, get_keyring_secret, keyring_service, set_keyring_secret,
+};
+
+#[derive(Args)]
+pub struct KeyringArgs {
+    #[command(subcommand)]
+    pub command: KeyringCommands,
+}
+
+#[derive(Subcommand)]
+pub enum KeyringCommands {
+    /// Store a secret, read from stdin
+    Set {
+        /// Entry name, used in config as `keyring:<name>` (e.g., openai)
+        name: String,
+    },
+    /// Check that an entry exists, without printing it
+    Check {
+        /// Entry name
+        name: String,
+    },
+    /// Remove an entry
+    Delete {
+        /// Entry name
+        name: String,
+    },
+}
+
+pub async fn run(args: KeyringArgs) -> Result<()> {
+    match args.command {
+        KeyringCommands::Set { name } => {
+            let secret = read_secret(&name)?;
+            set_keyring_secret(&name, &secret)?;
+            println!(
+                "Stored '{}' in the OS keyring (service '{}').",
+                name,
+                keyring_service()
+            );
+            println!("Use it in config.toml as \"{}{}\".", KEYRING_PREFIX, name);
+        }
+        KeyringCommands::Check { name } => {
+            let secret = get_keyring_secret(&name)?;
+            println!("'{}' is set ({} characters).", name, secret.chars().count());
+        }
+        KeyringCommands::Delete { name } => {
+            if delete_keyring_secret(&name)? {
+                println!("Deleted '{}' from the OS keyring.", name);
+            } else {
+                println!("No keyring entry '{}'.", name);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One line from stdin, prompting first when it's a terminal.
+pub(crate) fn read_secret(name: &str) -> Result<String> {
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        eprint!("Secret for '{}': ", name);
+        io::stderr().flush()?;
+    }
+    let mut secret = String::new();
+    stdin.lock().read_line(&mut secret)?;
+    let secret = secret.trim_end_matches(['\r', '\n']);
+    if secret.is_empty() {
+        bail!("No secret given on stdin");
+    }
+    Ok(secret.to_string())
+}
@@ -0,0 +1,283 @@
+//! CLI subcommand: `localgpt mcp`
+//!
+//! Lists configured MCP servers, shows their connection state, and manages
+//! OAuth logins for HTTP servers that require them. Tokens are stored
+//! encrypted in the data directory.
+//!
+//! `tools`, `call`, and `test` connect to a server directly, so its
+//! configuration can be checked without going through an agent.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use std::time::Instant;
+
+use localgpt_core::config::{Config, McpServerConfig};
+use localgpt_core::mcp::filter::ToolFilter;
+use localgpt_core::mcp::supervisor::{self, ConnectionState};
+use localgpt_core::mcp::{connect_server, oauth};
+
+#[derive(Args)]
+pub struct McpArgs {
+    #[command(subcommand)]
+    pub command: McpCommands,
+}
+
+#[derive(Subcommand)]
+pub enum McpCommands {
+    /// List configured MCP servers and their login status
+    List,
+    /// Show connection state of MCP servers
+    Status,
+    /// Connect to a server and list its tools
+    Tools {
+        /// Server name from [[mcp.servers]]
+        server: String,
+    },
+    /// Call a tool on a server
+    Call {
+        /// Server name from [[mcp.servers]]
+        server: String,
+        /// Tool name as reported by the server
+        tool: String,
+        /// Tool arguments as a JSON object
+        #[arg(long, default_value = "{}")]
+        args: String,
+    },
+    /// Connect to a server, list its tools, and ping it
+    Test {
+        /// Server name from [[mcp.servers]]
+        server: String,
+    },
+    /// Log in to an HTTP MCP server with OAuth
+    Login {
+        /// Server name from [[mcp.servers]]
+        server: String,
+    },
+    /// Delete the stored OAuth tokens for a server
+    Logout {
+        /// Server name from [[mcp.servers]]
+        server: String,
+    },
+}
+
+pub async fn run(args: McpArgs) -> Result<()> {
+    let config = Config::load()?;
+    match args.command {
+        McpCommands::List => list(&config),
+        McpCommands::Status => status(&config),
+        McpCommands::Tools { server } => tools(&config, find_server(&config, &server)?).await,
+        McpCommands::Call { server, tool, args } => {
+            call(&config, find_server(&config, &server)?, &tool, &args).await
+        }
+        McpCommands::Test { server } => test(&config, find_server(&config, &server)?).await,
+        McpCommands::Login { server } => login(&config, find_server(&config, &server)?).await,
+        McpCommands::Logout { server } => {
+            let server = find_server(&config, &server)?;
+            if oauth::delete_tokens(&config.paths.data_dir, &server.name)? {
+                println!("Logged out of MCP server '{}'", server.name);
+            } else {
+                println!("Not logged in to MCP server '{}'", server.name);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn find_server<'a>(config: &'a Config, name: &str) -> Result<&'a McpServerConfig> {
+    config
+        .mcp
+        .servers
+        .iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| anyhow::anyhow!("No MCP server named '{}' in config", name))
+}
+
+async fn login(config: &Config, server: &McpServerConfig) -> Result<()> {
+    if !matches!(server.transport.as_str(), "sse" | "http") {
+        anyhow::bail!(
+            "MCP server '{}' uses the {} transport; OAuth login is for HTTP servers",
+            server.name,
+            server.transport
+        );
+    }
+
+    let pending = oauth::begin_login(server).await?;
+    println!(
+        "\nTo log in to MCP server '{}', please visit the following URL:\n",
+        server.name
+    );
+    println!("{}", pending.authorize_url);
+    println!("\nWaiting for authorization...\n");
+
+    let port = pending.redirect_port;
+    let expected_state = pending.state.clone();
+    let code = tokio::task::spawn_blocking(move || {
+        crate::cli::auth::wait_for_callback(port, &expected_state)
+    })
+    .await??;
+
+    println!("Authorization code received. Exchanging for tokens...");
+    let tokens = pending.complete(&config.paths.data_dir, &code).await?;
+    println!("Logged in to MCP server '{}'.", server.name);
+    if tokens.refresh_token.is_none() && tokens.expires_at.is_some() {
+        println!("The server issued no refresh token; log in again when the token expires.");
+    }
+    Ok(())
+}
+
+fn list(config: &Config) -> Result<()> {
+    if config.mcp.servers.is_empty() {
+        println!("No MCP servers configured. Add them under [[mcp.servers]] in config.toml.");
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    for server in &config.mcp.servers {
+        let target = server
+            .url
+            .as_deref()
+            .or(server.command.as_deref())
+            .unwrap_or("");
+        println!("{} [{}] {}", server.name, server.transport, target);
+        if !server.tools.is_empty() {
+            println!("    tools: {}", server.tools.join(", "));
+        }
+        for filter in &server.arg_filters {
+            let allowed = filter
+                .path_prefixes
+                .iter()
+                .chain(&filter.values)
+                .cloned()
+                .collect::<Vec<_>>();
+            println!("    {}.{}: {}", filter.tool, filter.arg, allowed.join(", "));
+        }
+        if !matches!(server.transport.as_str(), "sse" | "http") {
+            continue;
+        }
+        let login = match oauth::load_tokens(&config.paths.data_dir, &server.name) {
+            Ok(Some(tokens)) if tokens.expiring(now) && tokens.refresh_token.is_none() => {
+                "expired (run `localgpt mcp login`)"
+            }
+            Ok(Some(_)) => "logged in",
+            Ok(None) if server.oauth.is_some() => "not logged in (run `localgpt mcp login`)",
+            Ok(None) => continue,
+            Err(_) => "unreadable tokens (run `localgpt mcp login`)",
+        };
+        println!("    oauth: {}", login);
+    }
+    Ok(())
+}
+
+async fn tools(config: &Config, server: &McpServerConfig) -> Result<()> {
+    let filter = ToolFilter::new(server)?;
+    let (client, tools) = connect_server(server, config).await?;
+    if tools.is_empty() {
+        println!("MCP server '{}' offers no tools.", server.name);
+    }
+    for tool in &tools {
+        let hidden = if filter.allows_tool(&tool.name) {
+            ""
+        } else {
+            " (not registered: excluded by `tools`)"
+        };
+        println!("{}{}", tool.name, hidden);
+        if let Some(description) = tool.description.as_deref().and_then(|d| d.lines().next()) {
+            println!("    {}", description);
+        }
+        let params: Vec<&str> = tool
+            .input_schema
+            .as_ref()
+            .and_then(|s| s.get("properties"))
+            .and_then(|p| p.as_object())
+            .map(|p| p.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        if !params.is_empty() {
+            println!("    args: {}", params.join(", "));
+        }
+    }
+    client.shutdown().await
+}
+
+async fn call(config: &Config, server: &McpServerConfig, tool: &str, args: &str) -> Result<()> {
+    let args: serde_json::Value =
+        serde_json::from_str(args).map_err(|e| anyhow::anyhow!("Invalid --args JSON: {}", e))?;
+    // Same restrictions the agent gets
+    let filter = ToolFilter::new(server)?;
+    if !filter.allows_tool(tool) {
+        anyhow::bail!(
+            "Tool '{}' is excluded by the `tools` setting of MCP server '{}'",
+            tool,
+            server.name
+        );
+    }
+    filter.check_arguments(tool, &args)?;
+
+    let (client, _) = connect_server(server, config).await?;
+    let result = client.call_tool(tool, args).await;
+    client.shutdown().await?;
+    let result = result?;
+
+    let text = result
+        .content
+        .iter()
+        .filter_map(|c| c.text.as_deref())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if result.is_error {
+        anyhow::bail!("MCP tool error: {}", text);
+    }
+    println!("{}", text);
+    Ok(())
+}
+
+async fn test(config: &Config, server: &McpServerConfig) -> Result<()> {
+    println!(
+        "Testing MCP server '{}' [{}]...",
+        server.name, server.transport
+    );
+    ToolFilter::new(server)?;
+    println!("  config: ok");
+
+    let started = Instant::now();
+    let (client, tools) = connect_server(server, config).await?;
+    println!(
+        "  connect: ok ({} ms, server reports '{}')",
+        started.elapsed().as_millis(),
+        client.server_name()
+    );
+    println!("  tools: {}", tools.len());
+
+    let started = Instant::now();
+    let ping = client.ping().await;
+    client.shutdown().await?;
+    ping?;
+    println!("  ping: ok ({} ms)", started.elapsed().as_millis());
+    Ok(())
+}
+
+fn status(config: &Config) -> Result<()> {
+    let statuses = supervisor::read_status(&config.paths.state_dir)?;
+    if statuses.is_empty() {
+        println!("No MCP connections recorded yet.");
+        return Ok(());
+    }
+
+    for status in &statuses {
+        let exited = status.state != ConnectionState::Closed
+            && !crate::cli::daemon::is_process_running(&status.pid.to_string());
+        println!(
+            "{}: {}{}",
+            status.server,
+            status.state.as_str(),
+            if exited { " (process exited)" } else { "" }
+        );
+        println!(
+            "    tools: {}  restarts: {}  updated: {}",
+            status.tools, status.restarts, status.updated_at
+        );
+        if let Some(ref error) = status.last_error {
+            println!("    last error: {}", error);
+        }
+    }
+    Ok(())
+}
@@ -1,6 +1,11 @@
-use anyhow::Result;
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
+use serde_json::json;
 
+use localgpt_core::concurrency::WorkspaceLock;
 use localgpt_core::config::Config;
 use localgpt_core::memory::MemoryManager;
 
@@ -8,6 +13,10 @@ use localgpt_core::memory::MemoryManager;
 pub struct MemoryArgs {
     #[command(subcommand)]
     pub command: MemoryCommands,
+
+    /// Output as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
 }
 
 #[derive(Subcommand)]
@@ -38,23 +47,62 @@ pub enum MemoryCommands {
         #[arg(short, long, default_value = "10")]
         count: usize,
     },
+
+    /// Copy a document into memory/ingested/ and index it ("-" reads stdin)
+    Ingest {
+        /// File to ingest
+        file: PathBuf,
+
+        /// Name for the saved file (default: the file's name)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+
+    /// Drop deleted files and stale entries from the index and compact it
+    Gc,
 }
 
 pub async fn run(args: MemoryArgs, agent_id: &str) -> Result<()> {
     let config = Config::load()?;
     let memory = MemoryManager::new_with_full_config(&config.memory, Some(&config), agent_id)?;
 
+    let json = args.json;
     match args.command {
-        MemoryCommands::Search { query, limit } => search_memory(&memory, &query, limit).await,
-        MemoryCommands::Reindex { force } => reindex_memory(&memory, force).await,
-        MemoryCommands::Stats => show_stats(&memory).await,
-        MemoryCommands::Recent { count } => show_recent(&memory, count).await,
+        MemoryCommands::Search { query, limit } => {
+            search_memory(&memory, &query, limit, json).await
+        }
+        MemoryCommands::Reindex { force } => {
+            // Don't index files halfway through an agent turn's writes
+            let _lock_guard = WorkspaceLock::new()?.acquire_read()?;
+            reindex_memory(&memory, force, json).await
+        }
+        MemoryCommands::Stats => show_stats(&memory, json).await,
+        MemoryCommands::Recent { count } => show_recent(&memory, count, json).await,
+        MemoryCommands::Ingest { file, name } => {
+            let _lock_guard = WorkspaceLock::new()?.acquire()?;
+            ingest_file(&memory, &file, name.as_deref(), json).await
+        }
+        MemoryCommands::Gc => collect_garbage(&memory, json).await,
     }
 }
 
-async fn search_memory(memory: &MemoryManager, query: &str, limit: usize) -> Result<()> {
+fn print_json(value: &serde_json::Value) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+async fn search_memory(
+    memory: &MemoryManager,
+    query: &str,
+    limit: usize,
+    json: bool,
+) -> Result<()> {
     let results = memory.search(query, limit)?;
 
+    if json {
+        return print_json(&json!({ "query": query, "results": results }));
+    }
+
     if results.is_empty() {
         println!("No results found for '{}'", query);
         return Ok(());
@@ -89,13 +137,44 @@ async fn search_memory(memory: &MemoryManager, query: &str, limit: usize) -> Res
     Ok(())
 }
 
-async fn reindex_memory(memory: &MemoryManager, force: bool) -> Result<()> {
-    println!(
-        "Reindexing memory files{}...",
-        if force { " (full)" } else { "" }
-    );
+async fn reindex_memory(memory: &MemoryManager, force: bool, json: bool) -> Result<()> {
+    if !json {
+        println!(
+            "Reindexing memory files{}...",
+            if force { " (full)" } else { "" }
+        );
+    }
 
-    let stats = memory.reindex(force)?;
+    // Progress goes to stderr, and only to a terminal
+    let show_progress = !json && std::io::stderr().is_terminal();
+    let stats = memory.reindex_with_progress(force, |done, total, path| {
+        if show_progress {
+            draw_progress(done, total, path);
+        }
+    })?;
+    if show_progress && stats.files_processed > 0 {
+        eprintln!();
+    }
+
+    let (processed, embedded) = if memory.has_embeddings() {
+        if !json {
+            println!("Generating embeddings...");
+        }
+        memory.generate_embeddings(50).await?
+    } else {
+        (0, 0)
+    };
+
+    if json {
+        return print_json(&json!({
+            "files_processed": stats.files_processed,
+            "files_updated": stats.files_updated,
+            "chunks_indexed": stats.chunks_indexed,
+            "duration_ms": stats.duration.as_millis() as u64,
+            "embeddings_processed": processed,
+            "embeddings_generated": embedded,
+        }));
+    }
 
     println!("Reindex complete:");
     println!("  Files processed: {}", stats.files_processed);
@@ -103,10 +182,8 @@ async fn reindex_memory(memory: &MemoryManager, force: bool) -> Result<()> {
     println!("  Chunks indexed: {}", stats.chunks_indexed);
     println!("  Duration: {:?}", stats.duration);
 
-    // Generate embeddings if provider is configured
+    // Embeddings are only generated if a provider is configured
     if memory.has_embeddings() {
-        println!("\nGenerating embeddings...");
-        let (processed, embedded) = memory.generate_embeddings(50).await?;
         if processed > 0 {
             println!("  Chunks processed: {}", processed);
             println!("  Embeddings generated: {}", embedded);
@@ -118,9 +195,47 @@ async fn reindex_memory(memory: &MemoryManager, force: bool) -> Result<()> {
     Ok(())
 }
 
-async fn show_stats(memory: &MemoryManager) -> Result<()> {
+/// Redraw a one-line progress bar on stderr
+fn draw_progress(done: usize, total: usize, path: &Path) {
+    const WIDTH: usize = 30;
+    let filled = (done * WIDTH).checked_div(total).unwrap_or(WIDTH);
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name: String = name.chars().take(40).collect();
+    let mut stderr = std::io::stderr();
+    let _ = write!(
+        stderr,
+        "\r\x1b[2K[{}{}] {}/{} {}",
+        "#".repeat(filled),
+        " ".repeat(WIDTH - filled),
+        done,
+        total,
+        name
+    );
+    let _ = stderr.flush();
+}
+
+async fn show_stats(memory: &MemoryManager, json: bool) -> Result<()> {
     let stats = memory.stats()?;
 
+    if json {
+        let files: Vec<_> = stats
+            .files
+            .iter()
+            .map(|f| json!({ "name": f.name, "chunks": f.chunks, "lines": f.lines }))
+            .collect();
+        return print_json(&json!({
+            "workspace": stats.workspace,
+            "total_files": stats.total_files,
+            "total_chunks": stats.total_chunks,
+            "index_size_kb": stats.index_size_kb,
+            "embedded_chunks": memory.embedded_chunk_count().unwrap_or(0),
+            "files": files,
+        }));
+    }
+
     println!("Memory Statistics");
     println!("-----------------");
     println!("Workspace: {}", stats.workspace);
@@ -138,9 +253,17 @@ async fn show_stats(memory: &MemoryManager) -> Result<()> {
     Ok(())
 }
 
-async fn show_recent(memory: &MemoryManager, count: usize) -> Result<()> {
+async fn show_recent(memory: &MemoryManager, count: usize, json: bool) -> Result<()> {
     let entries = memory.recent_entries(count)?;
 
+    if json {
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|e| json!({ "timestamp": e.timestamp, "file": e.file, "preview": e.preview }))
+            .collect();
+        return print_json(&json!(entries));
+    }
+
     if entries.is_empty() {
         println!("No recent memory entries found");
         return Ok(());
@@ -155,3 +278,69 @@ async fn show_recent(memory: &MemoryManager, count: usize) -> Result<()> {
 
     Ok(())
 }
+
+async fn ingest_file(
+    memory: &MemoryManager,
+    file: &Path,
+    name: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let content = if file == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read {}", file.display()))?
+    };
+    let stem = file
+        .file_stem()
+        .filter(|_| file != Path::new("-"))
+        .map(|s| s.to_string_lossy().into_owned());
+    let ingested = memory.ingest(name.or(stem.as_deref()), &content)?;
+
+    if memory.has_embeddings() {
+        memory.generate_embeddings(50).await?;
+    }
+
+    if json {
+        return print_json(&json!({ "file": ingested.file, "chunks": ingested.chunks }));
+    }
+    println!(
+        "Ingested {} as {} ({} chunks)",
+        file.display(),
+        ingested.file,
+        ingested.chunks
+    );
+    Ok(())
+}
+
+async fn collect_garbage(memory: &MemoryManager, json: bool) -> Result<()> {
+    let stats = memory.gc()?;
+
+    if json {
+        return print_json(&json!({
+            "files_removed": stats.files_removed,
+            "marks_removed": stats.marks_removed,
+            "cache_entries_removed": stats.cache_entries_removed,
+            "stale_rows_removed": stats.stale_rows_removed,
+            "bytes_before": stats.bytes_before,
+            "bytes_after": stats.bytes_after,
+        }));
+    }
+
+    println!("Memory gc complete:");
+    println!("  Deleted files dropped: {}", stats.files_removed);
+    println!("  Orphaned marks removed: {}", stats.marks_removed);
+    println!(
+        "  Unused cached embeddings removed: {}",
+        stats.cache_entries_removed
+    );
+    println!("  Stale search rows removed: {}", stats.stale_rows_removed);
+    println!(
+        "  Index size: {} KB -> {} KB",
+        stats.bytes_before / 1024,
+        stats.bytes_after / 1024
+    );
+    Ok(())
+}
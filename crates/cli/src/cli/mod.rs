@@ -1,20 +1,34 @@
+pub mod apikey;
 pub mod ask;
+pub mod audit;
 pub mod auth;
 pub mod bridge;
 pub mod chat;
+pub mod completions;
 pub mod config;
+pub mod cron;
 pub mod daemon;
+pub mod debug;
 #[cfg(feature = "desktop")]
 pub mod desktop;
 pub mod doctor;
 #[cfg(feature = "gen")]
 pub mod gen3d;
 pub mod init;
+pub mod keyring;
+pub mod mcp;
 pub mod md;
 pub mod memory;
 pub mod paths;
+pub mod run;
 pub mod sandbox;
 pub mod search;
+pub mod secret;
+pub mod sessions;
+pub mod skills;
+pub mod status;
+pub mod tui;
+pub mod watch;
 
 use clap::{Parser, Subcommand};
 
@@ -44,7 +58,8 @@ pub struct Cli {
     )]
     pub agent: String,
 
-    /// Profile name for complete isolation (suffixes all XDG dirs: ~/.config/localgpt-{profile}, etc.)
+    /// Profile name for complete isolation: separate config, memory, sessions, credentials, and cron jobs
+    /// (suffixes all XDG dirs: ~/.config/localgpt-{profile}, etc.)
     #[arg(short, long, global = true, env = "LOCALGPT_PROFILE")]
     pub profile: Option<String>,
 }
@@ -57,8 +72,15 @@ pub enum Commands {
     /// Ask a single question
     Ask(ask::AskArgs),
 
+    /// Run one prompt and print the reply (or a JSON envelope) for scripts
+    Run(run::RunArgs),
+
+    /// Run a prompt against files matching a glob whenever they change
+    Watch(watch::WatchArgs),
+
     /// Launch the desktop GUI
     #[cfg(feature = "desktop")]
+    #[command(alias = "ui")]
     Desktop(desktop::DesktopArgs),
 
     /// Launch 3D scene generation mode (Bevy renderer)
@@ -68,6 +90,15 @@ pub enum Commands {
     /// Manage the daemon
     Daemon(daemon::DaemonArgs),
 
+    /// Show the running daemon's uptime, providers, MCP servers, bridges, and scheduler
+    Status(status::StatusArgs),
+
+    /// Add, list, modify, and remove cron jobs
+    Cron(cron::CronArgs),
+
+    /// List, show, resume, delete, and export saved sessions
+    Sessions(sessions::SessionsArgs),
+
     /// Memory operations
     Memory(memory::MemoryArgs),
 
@@ -77,6 +108,12 @@ pub enum Commands {
     /// LocalGPT.md policy management
     Md(md::MdArgs),
 
+    /// Query and rotate the security, tool, and MCP sampling audit logs
+    Audit(audit::AuditArgs),
+
+    /// List MCP servers and log in to those that require OAuth
+    Mcp(mcp::McpArgs),
+
     /// Show resolved XDG directory paths
     Paths,
 
@@ -86,10 +123,23 @@ pub enum Commands {
     /// Test and manage web search
     Search(search::SearchArgs),
 
+    /// Install, update, and remove skills, or explain skill routing
+    Skills(skills::SkillsArgs),
+
     /// Authenticate with providers (Gemini, etc.)
     Auth(auth::AuthArgs),
 
-    /// Initialize configuration and keys
+    /// Create, list, and revoke HTTP API keys
+    #[command(name = "apikey")]
+    ApiKey(apikey::ApiKeyArgs),
+
+    /// Store, check, and delete secrets in the OS keyring
+    Keyring(keyring::KeyringArgs),
+
+    /// Encrypt config secrets with the device key, and show how secrets are stored
+    Secret(secret::SecretArgs),
+
+    /// Set up a provider, workspace, embeddings, and bridges interactively
     Init(init::InitArgs),
 
     /// Manage bridges and credentials
@@ -97,4 +147,10 @@ pub enum Commands {
 
     /// Run diagnostics to validate setup
     Doctor(doctor::DoctorArgs),
+
+    /// Collect diagnostics into a bundle for bug reports
+    Debug(debug::DebugArgs),
+
+    /// Print a shell completion script (bash, zsh, fish, powershell, elvish)
+    Completions(completions::CompletionsArgs),
 }
@@ -12,6 +12,11 @@ pub fn run() -> Result<()> {
     println!("LocalGPT Paths (XDG Base Directory)");
     println!("====================================");
     println!();
+    println!(
+        "Profile:    {}",
+        paths.profile.as_deref().unwrap_or("(default)")
+    );
+    println!();
     println!("Config:     {}", paths.config_dir.display());
     println!("  config.toml:    {}", paths.config_file().display());
     println!();
@@ -19,6 +24,9 @@ pub fn run() -> Result<()> {
     println!("  workspace:      {}", paths.workspace.display());
     println!("  device key:     {}", paths.device_key().display());
     println!("  skills:         {}", paths.managed_skills_dir().display());
+    println!("  skills lock:    {}", paths.skills_lock_file().display());
+    println!("  signing key:    {}", paths.skill_signing_key().display());
+    println!("  cron jobs:      {}", paths.cron_jobs_file().display());
     println!();
     println!("State:      {}", paths.state_dir.display());
     println!("  audit log:      {}", paths.audit_log().display());
@@ -32,6 +40,7 @@ pub fn run() -> Result<()> {
         "  embeddings:     {}",
         paths.embedding_cache_dir().display()
     );
+    println!("  bundled skills: {}", paths.bundled_skills_dir().display());
     println!();
     match paths.runtime_dir {
         Some(ref dir) => {
@@ -0,0 +1,212 @@
+//! CLI subcommand: `localgpt run`
+//!
+//! Runs one turn and exits, for use in scripts and pipelines. The reply goes
+//! to stdout and tool progress to stderr; `--json` prints a single envelope
+//! with the reply, the tool calls made, and token usage instead. With
+//! `--session` the turn continues a saved session and is saved back to it.
+
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use clap::Args;
+use clap_complete::engine::ArgValueCandidates;
+use futures::StreamExt;
+use serde::Serialize;
+use serde_json::Value;
+
+use localgpt_core::agent::tool_filters::InterfaceToolPolicy;
+use localgpt_core::agent::{
+    Agent, AgentConfig, StreamEvent, create_spawn_agent_tool, extract_tool_detail,
+};
+use localgpt_core::concurrency::WorkspaceLock;
+use localgpt_core::config::Config;
+use localgpt_core::memory::MemoryManager;
+
+use super::completions;
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// The prompt to run, or "-" to read it from stdin
+    pub prompt: String,
+
+    /// Print a JSON envelope with the response, tool calls, and usage
+    #[arg(long)]
+    pub json: bool,
+
+    /// Model to use (overrides config)
+    #[arg(short, long, add = ArgValueCandidates::new(completions::model_names))]
+    pub model: Option<String>,
+
+    /// Don't offer the model any tools
+    #[arg(long)]
+    pub no_tools: bool,
+
+    /// Continue this saved session, and save the turn to it
+    #[arg(short, long, add = ArgValueCandidates::new(completions::session_ids))]
+    pub session: Option<String>,
+
+    /// Save the new session so a later `--session` can continue it
+    #[arg(long, conflicts_with = "session")]
+    pub save: bool,
+}
+
+/// What `run --json` prints
+#[derive(Serialize)]
+struct RunOutput {
+    response: String,
+    model: String,
+    session_id: String,
+    /// Whether the session was saved, so `--session` can continue it
+    saved: bool,
+    tool_calls: Vec<ToolCallRecord>,
+    usage: UsageRecord,
+}
+
+#[derive(Serialize)]
+struct ToolCallRecord {
+    name: String,
+    /// The arguments as JSON, or as the model sent them if they don't parse
+    arguments: Value,
+    output: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct UsageRecord {
+    input_tokens: u64,
+    output_tokens: u64,
+    /// Context tokens of the session after the turn
+    context_tokens: usize,
+}
+
+pub async fn run(args: RunArgs, agent_id: &str) -> Result<()> {
+    let prompt = if args.prompt == "-" {
+        let mut prompt = String::new();
+        io::stdin().read_to_string(&mut prompt)?;
+        prompt
+    } else {
+        args.prompt
+    };
+    if prompt.trim().is_empty() {
+        bail!("The prompt is empty");
+    }
+
+    let config = Config::load()?;
+    let memory = Arc::new(MemoryManager::new_with_full_config(
+        &config.memory,
+        Some(&config),
+        agent_id,
+    )?);
+
+    let agent_config = AgentConfig {
+        model: args.model.unwrap_or(config.agent.default_model.clone()),
+        context_window: config.agent.context_window,
+        reserve_tokens: config.agent.reserve_tokens,
+    };
+
+    let mut agent = Agent::new(agent_config, &config, Arc::clone(&memory)).await?;
+    agent.extend_tools(crate::tools::create_cli_tools(&config)?);
+    agent.extend_tools(vec![create_spawn_agent_tool(config.clone(), memory)]);
+    // Nobody is there to approve tools, so those needing approval aren't offered
+    let deny = if args.no_tools {
+        vec!["*".to_string()]
+    } else {
+        agent.approval_required_tools().to_vec()
+    };
+    agent.restrict_tools(InterfaceToolPolicy {
+        allow: Vec::new(),
+        deny,
+    });
+
+    match &args.session {
+        Some(id) => agent.resume_session_for_agent(id, agent_id).await?,
+        None => agent.new_session().await?,
+    }
+
+    let workspace_lock = WorkspaceLock::new()?;
+    let _lock_guard = workspace_lock.acquire()?;
+
+    let mut response = String::new();
+    let mut tool_calls = Vec::new();
+    let mut stdout = io::stdout();
+
+    {
+        let event_stream = agent.chat_stream_with_tools(&prompt, Vec::new()).await?;
+        let mut pinned_stream = std::pin::pin!(event_stream);
+        while let Some(event) = pinned_stream.next().await {
+            match event? {
+                StreamEvent::Content(content) => {
+                    if !args.json {
+                        print!("{}", content);
+                        stdout.flush()?;
+                    }
+                    response.push_str(&content);
+                }
+                StreamEvent::ToolCallStart {
+                    name, arguments, ..
+                } => {
+                    if !args.json {
+                        match extract_tool_detail(&name, &arguments) {
+                            Some(detail) => eprintln!("> Running tool: {} ({})", name, detail),
+                            None => eprintln!("> Running tool: {}", name),
+                        }
+                    }
+                    tool_calls.push(ToolCallRecord {
+                        name,
+                        arguments: serde_json::from_str(&arguments)
+                            .unwrap_or(Value::String(arguments)),
+                        output: String::new(),
+                        warnings: Vec::new(),
+                    });
+                }
+                StreamEvent::ToolCallEnd {
+                    output, warnings, ..
+                } => {
+                    if !args.json {
+                        for warning in &warnings {
+                            eprintln!("  \u{26a0} Warning: {}", warning);
+                        }
+                    }
+                    if let Some(call) = tool_calls.last_mut() {
+                        call.output = output;
+                        call.warnings = warnings;
+                    }
+                }
+                StreamEvent::Done => {}
+            }
+        }
+    }
+
+    let saved = args.save || args.session.is_some();
+    if saved {
+        agent.save_session_for_agent(agent_id).await?;
+    }
+
+    if args.json {
+        let status = agent.session_status();
+        let output = RunOutput {
+            response,
+            model: agent.model().to_string(),
+            session_id: status.id,
+            saved,
+            tool_calls,
+            usage: UsageRecord {
+                input_tokens: status.api_input_tokens,
+                output_tokens: status.api_output_tokens,
+                context_tokens: status.token_count,
+            },
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        if !response.ends_with('\n') {
+            println!();
+        }
+        if args.save {
+            eprintln!("Saved session {}", agent.session_status().id);
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
 
-use localgpt_core::config::Config;
+use localgpt_core::config::{Config, SandboxNetwork};
 use localgpt_sandbox::{SandboxLevel, build_policy, detect_capabilities, run_sandboxed};
 
 #[derive(Args)]
@@ -51,7 +51,13 @@ async fn run_status() -> Result<()> {
         config.sandbox.max_file_size_bytes
     );
     println!("  Max procs:   {}", config.sandbox.max_processes);
-    println!("  Network:     {}", config.sandbox.network.policy);
+    println!(
+        "  Network:     {}",
+        format!("{:?}", config.sandbox.network.policy).to_lowercase()
+    );
+    if config.sandbox.network.policy == SandboxNetwork::Allowlist {
+        println!("  Allowed:     {:?}", config.sandbox.network.allow);
+    }
 
     if !config.sandbox.allow_paths.read.is_empty() {
         println!("  Extra read:  {:?}", config.sandbox.allow_paths.read);
@@ -162,37 +168,42 @@ async fn run_test() -> Result<()> {
         }
     }
 
-    // Test 4: Network denied
+    // Test 4: Network denied (an allowlist proxy refuses example.com)
     print!("  [4/6] Network (curl):               ");
-    match run_sandboxed(
-        "curl -s --connect-timeout 3 http://example.com 2>&1",
-        &policy,
-        15_000,
-    )
-    .await
-    {
-        Ok((output, code))
-            if code != 0
-                || output.contains("denied")
-                || output.contains("EPERM")
-                || output.contains("not found") =>
+    if config.sandbox.network.policy == SandboxNetwork::Allow {
+        println!("skipped (sandbox.network = allow)");
+        passed += 1;
+    } else {
+        match run_sandboxed(
+            "curl -s --connect-timeout 3 http://example.com 2>&1",
+            &policy,
+            15_000,
+        )
+        .await
         {
-            println!("denied (ok)");
-            passed += 1;
-        }
-        Ok((output, code)) => {
-            if output.contains("not found") || output.contains("No such file") {
-                println!("skipped (curl not installed)");
+            Ok((output, code))
+                if code != 0
+                    || output.contains("denied")
+                    || output.contains("EPERM")
+                    || output.contains("not found") =>
+            {
+                println!("denied (ok)");
+                passed += 1;
+            }
+            Ok((output, code)) => {
+                if output.contains("not found") || output.contains("No such file") {
+                    println!("skipped (curl not installed)");
+                    passed += 1;
+                } else {
+                    println!("FAIL (exit={}, network may not be blocked)", code);
+                    failed += 1;
+                }
+            }
+            Err(_e) => {
+                // Timeout could mean network was blocked and curl hung
+                println!("denied (ok, timed out)");
                 passed += 1;
-            } else {
-                println!("FAIL (exit={}, network may not be blocked)", code);
-                failed += 1;
             }
-        }
-        Err(_e) => {
-            // Timeout could mean network was blocked and curl hung
-            println!("denied (ok, timed out)");
-            passed += 1;
         }
     }
 
@@ -1,7 +1,9 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
 
-use localgpt_core::agent::tools::web_search::{SearchRouter, read_search_usage_stats};
+use localgpt_core::agent::tools::web_search::{
+    SearchRouter, read_search_quota_usage, read_search_usage_stats,
+};
 use localgpt_core::config::Config;
 
 #[derive(Args)]
@@ -39,14 +41,23 @@ async fn run_test(query: &str) -> Result<()> {
 
     let router = SearchRouter::from_config(ws_config)?;
 
-    eprintln!("Searching with provider: {} ...", router.provider_name());
+    eprintln!(
+        "Searching with provider: {} ...",
+        router.provider_names().join(" → ")
+    );
 
     let response = router.search(query).await?;
 
     println!(
-        "OK: {} results in {}ms (cost: ${:.3})",
-        response.meta.result_count, response.meta.latency_ms, response.meta.estimated_cost_usd
+        "OK: {} results from {} in {}ms (cost: ${:.3})",
+        response.meta.result_count,
+        response.meta.provider,
+        response.meta.latency_ms,
+        response.meta.estimated_cost_usd
     );
+    if !response.meta.fallbacks.is_empty() {
+        println!("Fell back from: {}", response.meta.fallbacks.join(", "));
+    }
     println!();
 
     for (i, result) in response.results.iter().enumerate() {
@@ -79,5 +90,19 @@ fn run_stats() -> Result<()> {
     println!("  Cached hits: {} ({:.0}%)", stats.cached_hits, cache_pct);
     println!("  Estimated cost: ${:.3}", stats.estimated_cost_usd);
 
+    let quota = read_search_quota_usage()?;
+    if !quota.providers.is_empty() {
+        println!();
+        println!("Today ({}):", quota.date);
+        let mut providers: Vec<_> = quota.providers.iter().collect();
+        providers.sort_by_key(|(name, _)| name.as_str());
+        for (name, usage) in providers {
+            println!(
+                "  {}: {} queries, ${:.3}",
+                name, usage.queries, usage.spend_usd
+            );
+        }
+    }
+
     Ok(())
 }
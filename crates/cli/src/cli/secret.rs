@@ -0,0 +1,130 @@
+//! CLI subcommand: `localgpt secret`
+//!
+//! Encrypts config secrets with the device key so config.toml holds
+//! `api_token = "enc:..."` instead of the token itself. `secret get`
+//! prints what a setting resolves to, whether it's encrypted, in the OS
+//! keyring, or an environment variable; `secret list` shows how each secret
+//! setting is stored.
+
+use anyhow::{Context, Result, bail};
+use clap::{Args, Subcommand};
+
+use localgpt_core::config::{Config, SecretResolver, SecretSource, set_config_string};
+use localgpt_core::security::{encrypt_config_secret, ensure_device_key};
+
+/// Settings holding secrets, by their last key segment
+const SECRET_FIELDS: &[&str] = &[
+    "api_key",
+    "api_token",
+    "access_token",
+    "refresh_token",
+    "client_secret",
+    "auth_token",
+    "secret",
+];
+
+#[derive(Args)]
+pub struct SecretArgs {
+    #[command(subcommand)]
+    pub command: SecretCommands,
+}
+
+#[derive(Subcommand)]
+pub enum SecretCommands {
+    /// Encrypt a secret, read from stdin, into a config setting
+    Set {
+        /// Config key (e.g., telegram.api_token, providers.openai.api_key)
+        key: String,
+    },
+    /// Print the secret a config setting resolves to
+    Get {
+        /// Config key
+        key: String,
+    },
+    /// Show how each secret setting is stored
+    List,
+}
+
+pub async fn run(args: SecretArgs) -> Result<()> {
+    let config = Config::load()?;
+    let path = config.paths.config_file();
+    let data_dir = &config.paths.data_dir;
+
+    match args.command {
+        SecretCommands::Set { key } => {
+            let secret = super::keyring::read_secret(&key)?;
+            ensure_device_key(data_dir)?;
+            let encrypted = encrypt_config_secret(data_dir, &secret)?;
+            set_config_string(&path, &key, &encrypted)?;
+            println!("Encrypted {} in {}", key, path.display());
+        }
+        SecretCommands::Get { key } => {
+            let raw = read_raw(&path)?;
+            let Some(value) = lookup(&raw, &key) else {
+                bail!("{} is not set in {}", key, path.display());
+            };
+            println!("{}", SecretResolver::new(data_dir).resolve(value)?);
+        }
+        SecretCommands::List => {
+            let raw = read_raw(&path)?;
+            let mut secrets = Vec::new();
+            collect_secrets(&raw, "", &mut secrets);
+            if secrets.is_empty() {
+                println!("No secrets in {}", path.display());
+                return Ok(());
+            }
+            for (key, source) in &secrets {
+                println!("  {:<40} {}", key, source.as_str());
+            }
+            if secrets
+                .iter()
+                .any(|(_, source)| *source == SecretSource::Plaintext)
+            {
+                println!();
+                println!("Encrypt plaintext secrets with `localgpt secret set <key>`.");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_raw(path: &std::path::Path) -> Result<toml::Value> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(toml::from_str(&content)?)
+}
+
+fn lookup<'a>(raw: &'a toml::Value, key: &str) -> Option<&'a str> {
+    key.split('.')
+        .try_fold(raw, |value, part| value.as_table()?.get(part))?
+        .as_str()
+}
+
+/// Non-empty secret settings under `value`, with where each comes from
+fn collect_secrets(value: &toml::Value, prefix: &str, out: &mut Vec<(String, SecretSource)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                collect_secrets(value, &path, out);
+            }
+        }
+        toml::Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                collect_secrets(value, &format!("{}[{}]", prefix, i), out);
+            }
+        }
+        toml::Value::String(s) if !s.is_empty() => {
+            let field = prefix.rsplit('.').next().unwrap_or(prefix);
+            let source = SecretSource::of(s);
+            if SECRET_FIELDS.contains(&field) || source != SecretSource::Plaintext {
+                out.push((prefix.to_string(), source));
+            }
+        }
+        _ => {}
+    }
+}
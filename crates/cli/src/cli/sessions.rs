@@ -0,0 +1,355 @@
+//! CLI subcommand: `localgpt sessions`
+//!
+//! Lists, shows, resumes, deletes, and exports saved sessions. With a daemon
+//! running, these go through its HTTP API and act on the daemon's sessions;
+//! otherwise (or with `--local`) they act on the session files of the
+//! `--agent` given to the CLI. `--json` prints machine-readable output.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use clap::{Args, Subcommand};
+use clap_complete::engine::ArgValueCandidates;
+use reqwest::{Client, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use localgpt_core::agent::{Session, delete_session_for_agent, list_sessions_for_agent};
+use localgpt_core::config::Config;
+use localgpt_core::security::redact_secrets;
+
+use super::completions;
+
+#[derive(Args)]
+pub struct SessionsArgs {
+    #[command(subcommand)]
+    pub command: SessionsCommands,
+
+    /// Output as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Use local session files even if the daemon is running
+    #[arg(long, global = true)]
+    pub local: bool,
+
+    /// API key to authenticate with (defaults to server.auth_token)
+    #[arg(long, global = true, env = "LOCALGPT_API_KEY", hide_env_values = true)]
+    pub api_key: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum SessionsCommands {
+    /// List saved sessions, newest first
+    List {
+        /// Maximum number of sessions to show
+        #[arg(short, long)]
+        limit: Option<usize>,
+    },
+    /// Show the messages of a session
+    Show {
+        /// Session ID
+        #[arg(add = ArgValueCandidates::new(completions::session_ids))]
+        id: String,
+    },
+    /// Resume a session: in the daemon if it's running, else in an interactive chat
+    Resume {
+        /// Session ID
+        #[arg(add = ArgValueCandidates::new(completions::session_ids))]
+        id: String,
+    },
+    /// Delete a session
+    #[command(alias = "rm")]
+    Delete {
+        /// Session ID
+        #[arg(add = ArgValueCandidates::new(completions::session_ids))]
+        id: String,
+    },
+    /// Export a session as Markdown (or JSON with --json)
+    Export {
+        /// Session ID
+        #[arg(add = ArgValueCandidates::new(completions::session_ids))]
+        id: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// One entry of `sessions list`
+#[derive(Serialize)]
+struct SessionSummary {
+    id: String,
+    title: Option<String>,
+    message_count: usize,
+    created_at: DateTime<Utc>,
+}
+
+/// A session's messages, as printed by `sessions show` and `export --json`
+#[derive(Serialize, Deserialize)]
+struct SessionDetail {
+    session_id: String,
+    messages: Vec<DetailMessage>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DetailMessage {
+    role: String,
+    content: Option<String>,
+}
+
+/// Where the sessions live
+enum Store {
+    Daemon(DaemonApi),
+    Local(String),
+}
+
+struct DaemonApi {
+    client: Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl DaemonApi {
+    fn request(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    fn get(&self, path: &str) -> RequestBuilder {
+        self.request(self.client.get(format!("{}{}", self.base_url, path)))
+    }
+
+    fn post(&self, path: &str) -> RequestBuilder {
+        self.request(self.client.post(format!("{}{}", self.base_url, path)))
+    }
+
+    fn delete(&self, path: &str) -> RequestBuilder {
+        self.request(self.client.delete(format!("{}{}", self.base_url, path)))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        bail!("Daemon returned {}: {}", status, body.trim())
+    }
+}
+
+pub async fn run(args: SessionsArgs, agent_id: &str) -> Result<()> {
+    let store = open_store(&args, agent_id).await?;
+    let json = args.json;
+
+    match args.command {
+        SessionsCommands::List { limit } => {
+            let mut sessions = list(&store).await?;
+            if let Some(limit) = limit {
+                sessions.truncate(limit);
+            }
+            if json {
+                println!("{}", serde_json::to_string_pretty(&sessions)?);
+            } else if sessions.is_empty() {
+                println!("No saved sessions.");
+            } else {
+                for s in &sessions {
+                    println!(
+                        "{}  {}  {:>4} msgs  {}",
+                        s.id,
+                        s.created_at.format("%Y-%m-%d %H:%M"),
+                        s.message_count,
+                        s.title.as_deref().unwrap_or("(untitled)")
+                    );
+                }
+            }
+        }
+        SessionsCommands::Show { id } => {
+            let detail = show(&store, &id).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&detail)?);
+            } else {
+                println!("Session {}\n", detail.session_id);
+                for msg in &detail.messages {
+                    let Some(content) = msg.content.as_deref().filter(|c| !c.is_empty()) else {
+                        continue;
+                    };
+                    println!("[{}]\n{}\n", msg.role, content);
+                }
+            }
+        }
+        SessionsCommands::Resume { id } => match &store {
+            Store::Daemon(api) => {
+                let path = format!("/api/sessions/{}/resume", id);
+                let reply: Value = api.send(api.post(&path)).await?.json().await?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&reply)?);
+                } else {
+                    println!(
+                        "Resumed session {} in the daemon (model: {})",
+                        reply["session_id"].as_str().unwrap_or(&id),
+                        reply["model"].as_str().unwrap_or("-")
+                    );
+                }
+            }
+            Store::Local(agent_id) => {
+                if json {
+                    bail!("--json is not supported when resuming into an interactive chat");
+                }
+                let args = crate::cli::chat::ChatArgs {
+                    model: None,
+                    session: Some(id),
+                    resume: false,
+                    tui: false,
+                };
+                crate::cli::chat::run(args, agent_id).await?;
+            }
+        },
+        SessionsCommands::Delete { id } => {
+            match &store {
+                Store::Daemon(api) => {
+                    let path = format!("/api/sessions/{}", id);
+                    api.send(api.delete(&path)).await?;
+                }
+                Store::Local(agent_id) => {
+                    if !delete_session_for_agent(agent_id, &id)? {
+                        bail!("Session not found: {}", id);
+                    }
+                }
+            }
+            if json {
+                println!("{}", serde_json::json!({"deleted": true, "session_id": id}));
+            } else {
+                println!("Deleted session {}", id);
+            }
+        }
+        SessionsCommands::Export { id, output } => {
+            let text = if json {
+                let json = serde_json::to_string_pretty(&show(&store, &id).await?)?;
+                redact_secrets(&json).into_owned()
+            } else {
+                match &store {
+                    Store::Daemon(api) => {
+                        let path = format!("/api/sessions/{}/export", id);
+                        api.send(api.get(&path)).await?.text().await?
+                    }
+                    Store::Local(agent_id) => {
+                        Session::load_for_agent(&id, agent_id)?.export_markdown(None)
+                    }
+                }
+            };
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, text)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    eprintln!("Exported session {} to {}", id, path.display());
+                }
+                None => println!("{}", text),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Use the daemon if it answers, else local session files
+async fn open_store(args: &SessionsArgs, agent_id: &str) -> Result<Store> {
+    if args.local {
+        return Ok(Store::Local(agent_id.to_string()));
+    }
+
+    let config = Config::load()?;
+    let host = match config.server.bind.as_str() {
+        "0.0.0.0" | "::" => "127.0.0.1",
+        bind => bind,
+    };
+    let api = DaemonApi {
+        client: Client::new(),
+        base_url: format!("http://{}:{}", host, config.server.port),
+        token: args.api_key.clone().or(config.server.auth_token.clone()),
+    };
+
+    match api.get("/health").send().await {
+        Ok(_) => Ok(Store::Daemon(api)),
+        Err(e) if e.is_connect() || e.is_timeout() => {
+            eprintln!(
+                "Daemon not reachable at {}; using local sessions for agent '{}'",
+                api.base_url, agent_id
+            );
+            Ok(Store::Local(agent_id.to_string()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn list(store: &Store) -> Result<Vec<SessionSummary>> {
+    match store {
+        Store::Daemon(api) => {
+            let reply: Value = api
+                .send(api.get("/api/saved-sessions"))
+                .await?
+                .json()
+                .await?;
+            Ok(reply["sessions"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|s| {
+                    Some(SessionSummary {
+                        id: s["id"].as_str()?.to_string(),
+                        title: s["title"].as_str().map(str::to_string),
+                        message_count: s["message_count"].as_u64().unwrap_or(0) as usize,
+                        created_at: s["created_at"]
+                            .as_str()
+                            .and_then(|t| {
+                                NaiveDateTime::parse_from_str(t, "%Y-%m-%dT%H:%M:%S").ok()
+                            })
+                            .map(|t| t.and_utc())
+                            .unwrap_or_default(),
+                    })
+                })
+                .collect())
+        }
+        Store::Local(agent_id) => Ok(list_sessions_for_agent(agent_id)?
+            .into_iter()
+            .map(|s| SessionSummary {
+                id: s.id,
+                title: s.title,
+                message_count: s.message_count,
+                created_at: s.created_at,
+            })
+            .collect()),
+    }
+}
+
+async fn show(store: &Store, id: &str) -> Result<SessionDetail> {
+    match store {
+        Store::Daemon(api) => {
+            let path = format!("/api/saved-sessions/{}", id);
+            Ok(api.send(api.get(&path)).await?.json().await?)
+        }
+        Store::Local(agent_id) => {
+            let session = Session::load_for_agent(id, agent_id)?;
+            let messages = session
+                .messages()
+                .into_iter()
+                .map(|msg| DetailMessage {
+                    role: serde_json::to_value(msg.role)
+                        .ok()
+                        .and_then(|v| v.as_str().map(str::to_string))
+                        .unwrap_or_default(),
+                    content: Some(msg.content.clone()),
+                })
+                .collect();
+            Ok(SessionDetail {
+                session_id: session.id().to_string(),
+                messages,
+            })
+        }
+    }
+}
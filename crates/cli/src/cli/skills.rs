@@ -0,0 +1,777 @@
+//! CLI subcommand: `localgpt skills`
+//!
+//! Installs skills into the managed skills directory from a git repository,
+//! a URL (a raw SKILL.md or a .tar.gz archive), or a local directory, keeps
+//! their sources and versions in the skills lockfile, and updates or removes
+//! them. `skills why` explains skill precedence and routing for a message,
+//! and `skills sign` signs a SKILL.md for the `[skills.trust]` policy.
+//! `list`, `info`, `enable`, and `disable` inspect and toggle the loaded
+//! skills, and `new` scaffolds a workspace skill.
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use localgpt_core::agent::{
+    ConditionCheck, LockedSkill, SIGNATURE_SIDECAR, Skill, SkillDiagnosis, SkillLock,
+    SkillRoutingContext, SkillSourceKind, diagnose_skills, find_skill_root, get_skills_summary,
+    install_skill_dir, load_or_create_signing_key, load_skill_definitions, load_skills, public_key,
+    remove_managed_skill, scaffold_skill, set_skill_disabled, sign_skill, skill_content_hash,
+    skill_usage, trusted_skill_keys, validate_skill_dir, verify_skill_signature,
+};
+use localgpt_core::config::{Config, SkillTrustConfig, SkillTrustLevel};
+use localgpt_core::paths::Paths;
+
+/// Maximum size of a downloaded SKILL.md or archive.
+const MAX_DOWNLOAD_BYTES: usize = 20 * 1024 * 1024;
+
+/// Maximum changelog lines shown per updated skill.
+const MAX_CHANGELOG_LINES: usize = 20;
+
+#[derive(Args)]
+pub struct SkillsArgs {
+    #[command(subcommand)]
+    pub command: SkillsCommands,
+}
+
+#[derive(Subcommand)]
+pub enum SkillsCommands {
+    /// List skills, ready and blocked
+    List,
+    /// Show a skill's frontmatter, routing rules, and eligibility
+    Info {
+        /// Skill name or command name
+        name: String,
+    },
+    /// Turn a disabled skill back on
+    Enable {
+        /// Skill name or command name
+        name: String,
+    },
+    /// Turn a skill off without removing it
+    Disable {
+        /// Skill name or command name
+        name: String,
+    },
+    /// Create a workspace skill from a SKILL.md template
+    New {
+        /// Skill name
+        name: String,
+    },
+    /// Install a skill from a git repository, URL, or local directory
+    Install {
+        /// Git URL, URL of a SKILL.md or .tar.gz archive, or local path
+        source: String,
+
+        /// Subdirectory containing the skill (for repositories with several skills)
+        #[arg(long)]
+        path: Option<String>,
+
+        /// Replace an already installed skill with the same name
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Update installed skills from their recorded sources
+    Update {
+        /// Only update this skill (default: all installed skills)
+        name: Option<String>,
+    },
+    /// Remove an installed (managed) skill
+    Remove {
+        /// Skill name
+        name: String,
+    },
+    /// Explain which skills would be offered for a message, and why
+    Why {
+        /// Message to route
+        message: String,
+
+        /// Channel the message arrives on (cli, telegram, discord, http)
+        #[arg(long, default_value = "cli")]
+        channel: String,
+
+        /// Tool available to the agent, for hasTool conditions (repeatable)
+        #[arg(long = "tool")]
+        tools: Vec<String>,
+    },
+    /// Sign a skill's SKILL.md with the local signing key
+    Sign {
+        /// Skill directory or SKILL.md path
+        path: PathBuf,
+
+        /// Store the signature in the SKILL.md frontmatter instead of a
+        /// SKILL.md.sig sidecar
+        #[arg(long)]
+        inline: bool,
+    },
+}
+
+pub async fn run(args: SkillsArgs) -> Result<()> {
+    let paths = Paths::resolve()?;
+    match args.command {
+        SkillsCommands::List => {
+            let config = Config::load()?;
+            let skills = load_skills(&config.workspace_path(), &config.skills.trust_policy())?;
+            println!("{}", get_skills_summary(&skills));
+            Ok(())
+        }
+        SkillsCommands::Info { name } => info(&name),
+        SkillsCommands::Enable { name } => set_disabled(&paths, &name, false),
+        SkillsCommands::Disable { name } => set_disabled(&paths, &name, true),
+        SkillsCommands::New { name } => {
+            let config = Config::load()?;
+            let path = scaffold_skill(&config.workspace_path().join("skills"), &name)?;
+            println!("Created {}", path.display());
+            println!("Fill in the description and steps; the skill loads on the next turn.");
+            Ok(())
+        }
+        SkillsCommands::Install {
+            source,
+            path,
+            force,
+        } => {
+            let config = Config::load()?;
+            install(
+                &paths,
+                &config.skills.trust_policy(),
+                &source,
+                path.as_deref(),
+                force,
+            )
+            .await
+        }
+        SkillsCommands::Update { name } => {
+            let config = Config::load()?;
+            update(&paths, &config.skills.trust_policy(), name.as_deref()).await
+        }
+        SkillsCommands::Remove { name } => {
+            let dir = remove_managed_skill(&paths.managed_skills_dir(), &name)?;
+            if let Some(key) = dir.file_name().and_then(|n| n.to_str()) {
+                let lock_path = paths.skills_lock_file();
+                let mut lock = SkillLock::load(&lock_path)?;
+                if lock.skills.remove(key).is_some() {
+                    lock.save(&lock_path)?;
+                }
+            }
+            println!("Removed skill '{}' ({})", name, dir.display());
+            Ok(())
+        }
+        SkillsCommands::Why {
+            message,
+            channel,
+            tools,
+        } => why(&message, &channel, tools),
+        SkillsCommands::Sign { path, inline } => sign(&paths, &path, inline),
+    }
+}
+
+/// Definitions of the skill called `name` (by name or command name), the
+/// effective one first, then those it shadows.
+fn find_definitions(config: &Config, name: &str) -> Result<Vec<Skill>> {
+    let mut definitions: Vec<Skill> =
+        load_skill_definitions(&config.workspace_path(), &config.skills.trust_policy())?
+            .into_iter()
+            .filter(|s| s.name == name || s.command_name == name.trim_start_matches('/'))
+            .collect();
+    if definitions.is_empty() {
+        anyhow::bail!("No skill named '{}' (see 'localgpt skills list')", name);
+    }
+    // Definitions load lowest precedence first
+    definitions.reverse();
+    Ok(definitions)
+}
+
+fn set_disabled(paths: &Paths, name: &str, disabled: bool) -> Result<()> {
+    let config = Config::load()?;
+    let skill = find_definitions(&config, name)?.remove(0);
+    let changed = set_skill_disabled(&paths.disabled_skills_file(), &skill.name, disabled)?;
+    let state = if disabled { "disabled" } else { "enabled" };
+    if changed {
+        println!("Skill '{}' {}.", skill.name, state);
+    } else {
+        println!("Skill '{}' is already {}.", skill.name, state);
+    }
+    Ok(())
+}
+
+fn info(name: &str) -> Result<()> {
+    let config = Config::load()?;
+    let definitions = find_definitions(&config, name)?;
+    let skill = &definitions[0];
+
+    let emoji = skill
+        .emoji
+        .as_ref()
+        .map(|e| format!("{} ", e))
+        .unwrap_or_default();
+    println!("{}{} [{}]", emoji, skill.name, skill.source.as_str());
+    println!("  Path:        {}", skill.path.display());
+    if let Some(version) = &skill.version {
+        println!("  Version:     {}", version);
+    }
+    println!("  Description: {}", skill.description);
+    if skill.user_invocable {
+        println!("  Command:     {}", skill_usage(skill));
+    }
+    if skill.disable_model_invocation {
+        println!("  Prompt:      not listed for the model (disable-model-invocation)");
+    }
+    println!("  Status:      {}", skill.eligibility.describe());
+    println!("  Signature:   {}", skill.signature.describe());
+
+    println!("\nFrontmatter:");
+    let content = fs::read_to_string(&skill.path)?;
+    let mut lines = content.lines();
+    if lines.next().map(str::trim) == Some("---") {
+        for line in lines.take_while(|l| l.trim() != "---") {
+            println!("  {}", line);
+        }
+    } else {
+        println!("  (none)");
+    }
+
+    println!("\nRouting:");
+    if skill.use_when.is_empty() && skill.dont_use_when.is_empty() {
+        println!("  No rules; offered for every message");
+    }
+    for condition in &skill.use_when {
+        println!("  useWhen:     {}", condition.describe());
+    }
+    for condition in &skill.dont_use_when {
+        println!("  dontUseWhen: {}", condition.describe());
+    }
+
+    let requires = &skill.requires;
+    if !requires.bins.is_empty() || !requires.any_bins.is_empty() || !requires.env.is_empty() {
+        println!("\nRequirements:");
+        let found = |bin: &String| which::which(bin).is_ok();
+        for bin in &requires.bins {
+            let state = if found(bin) { "found" } else { "missing" };
+            println!("  bin {}: {}", bin, state);
+        }
+        if !requires.any_bins.is_empty() {
+            let state = match requires.any_bins.iter().find(|&b| found(b)) {
+                Some(bin) => format!("found {}", bin),
+                None => "none found".to_string(),
+            };
+            println!("  any of {}: {}", requires.any_bins.join(", "), state);
+        }
+        for var in &requires.env {
+            let state = if std::env::var_os(var).is_some() {
+                "set"
+            } else {
+                "not set"
+            };
+            println!("  env {}: {}", var, state);
+        }
+    }
+
+    for shadowed in &definitions[1..] {
+        println!(
+            "\nShadows {} [{}] ({})",
+            shadowed.name,
+            shadowed.source.as_str(),
+            shadowed.path.display()
+        );
+    }
+    Ok(())
+}
+
+fn sign(paths: &Paths, path: &Path, inline: bool) -> Result<()> {
+    let skill_md = if path.is_dir() {
+        path.join("SKILL.md")
+    } else {
+        path.to_path_buf()
+    };
+    let key = load_or_create_signing_key(&paths.skill_signing_key())?;
+    sign_skill(&skill_md, &key, inline)?;
+
+    let location = if inline {
+        "frontmatter".to_string()
+    } else {
+        skill_md
+            .with_file_name(SIGNATURE_SIDECAR)
+            .display()
+            .to_string()
+    };
+    println!("Signed {} ({})", skill_md.display(), location);
+    println!("Public key: {}", public_key(&key));
+    println!("Add it to skills.trust.trusted_keys on other machines to trust this skill.");
+    Ok(())
+}
+
+/// Apply the managed skill trust policy to a fetched skill before it is
+/// installed: `require` refuses untrusted skills, `warn` prints a warning.
+fn check_trust(trust: &SkillTrustConfig, root: &Path) -> Result<()> {
+    if trust.managed == SkillTrustLevel::Off {
+        return Ok(());
+    }
+    let signature = verify_skill_signature(&root.join("SKILL.md"), &trusted_skill_keys(trust));
+    if signature.is_trusted() {
+        return Ok(());
+    }
+    if trust.managed == SkillTrustLevel::Require {
+        anyhow::bail!(
+            "Skill is {}; the managed skill policy requires a trusted signature",
+            signature.describe()
+        );
+    }
+    eprintln!("Warning: skill is {}", signature.describe());
+    Ok(())
+}
+
+fn why(message: &str, channel: &str, tools: Vec<String>) -> Result<()> {
+    let config = Config::load()?;
+    let definitions =
+        load_skill_definitions(&config.workspace_path(), &config.skills.trust_policy())?;
+    let ctx = SkillRoutingContext::new(message, channel).with_tools(tools);
+    let diagnoses = diagnose_skills(definitions, &ctx);
+
+    println!("Message: {:?}", message);
+    println!("Channel: {}", channel);
+    if diagnoses.is_empty() {
+        println!("\nNo skills found.");
+        return Ok(());
+    }
+
+    let included = diagnoses.iter().filter(|d| d.included).count();
+    println!(
+        "{} of {} skills would be included\n",
+        included,
+        diagnoses.len()
+    );
+    for diagnosis in &diagnoses {
+        print_diagnosis(diagnosis);
+    }
+    Ok(())
+}
+
+fn print_diagnosis(d: &SkillDiagnosis) {
+    let mark = if d.included { "✓" } else { "✗" };
+    println!(
+        "{} {} [{}] — {}",
+        mark,
+        d.skill.name,
+        d.skill.source.as_str(),
+        d.reason
+    );
+    println!("    path: {}", d.skill.path.display());
+    if !d.skill.eligibility.is_ready() {
+        println!("    eligibility: {}", d.skill.eligibility.describe());
+    }
+    println!("    signature: {}", d.skill.signature.describe());
+    print_conditions("useWhen", &d.use_when);
+    print_conditions("dontUseWhen", &d.dont_use_when);
+    for shadowed in &d.shadowed {
+        println!(
+            "    shadows: {} [{}] ({})",
+            shadowed.name,
+            shadowed.source.as_str(),
+            shadowed.path.display()
+        );
+    }
+    println!();
+}
+
+fn print_conditions(label: &str, checks: &[ConditionCheck]) {
+    for check in checks {
+        let result = if check.matched { "matched" } else { "no match" };
+        println!("    {}: {} — {}", label, check.condition, result);
+    }
+}
+
+/// A fetched skill source, staged for installation.
+struct Fetched {
+    /// Directory containing SKILL.md
+    root: PathBuf,
+    /// `root` relative to the fetched source, if not the top level
+    subpath: Option<String>,
+    kind: SkillSourceKind,
+    /// Git checkout and its HEAD commit, for git sources
+    checkout: Option<PathBuf>,
+    commit: Option<String>,
+}
+
+fn staging_dir(paths: &Paths) -> PathBuf {
+    paths.cache_dir.join("skill-install").join(format!(
+        "{}-{}",
+        std::process::id(),
+        chrono::Utc::now().timestamp_millis()
+    ))
+}
+
+async fn install(
+    paths: &Paths,
+    trust: &SkillTrustConfig,
+    source: &str,
+    subdir: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let staging = staging_dir(paths);
+    let result = install_staged(paths, trust, source, subdir, force, &staging).await;
+    if staging.exists() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+
+    print_installed(&result?);
+    Ok(())
+}
+
+async fn install_staged(
+    paths: &Paths,
+    trust: &SkillTrustConfig,
+    source: &str,
+    subdir: Option<&str>,
+    force: bool,
+    staging: &Path,
+) -> Result<Skill> {
+    let fetched = fetch_skill(source, subdir, staging, false).await?;
+    check_trust(trust, &fetched.root)?;
+    let skill = install_skill_dir(&fetched.root, &paths.managed_skills_dir(), force)?;
+    record_lock(paths, &skill, source, &fetched)?;
+    Ok(skill)
+}
+
+async fn update(paths: &Paths, trust: &SkillTrustConfig, only: Option<&str>) -> Result<()> {
+    let lock = SkillLock::load(&paths.skills_lock_file())?;
+    let entries: Vec<(&String, &LockedSkill)> = lock
+        .skills
+        .iter()
+        .filter(|(key, entry)| only.is_none_or(|n| n == key.as_str() || n == entry.name))
+        .collect();
+
+    if entries.is_empty() {
+        match only {
+            Some(name) => anyhow::bail!("Skill '{}' is not in the skills lockfile", name),
+            None => println!("No installed skills to update."),
+        }
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for (key, entry) in entries {
+        if let Err(e) = update_one(paths, trust, key, entry).await {
+            eprintln!("{}: update failed: {:#}", entry.name, e);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{} skill(s) failed to update", failed);
+    }
+    Ok(())
+}
+
+async fn update_one(
+    paths: &Paths,
+    trust: &SkillTrustConfig,
+    key: &str,
+    entry: &LockedSkill,
+) -> Result<()> {
+    let staging = staging_dir(paths);
+    let result = update_staged(paths, trust, key, entry, &staging).await;
+    if staging.exists() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+    result
+}
+
+async fn update_staged(
+    paths: &Paths,
+    trust: &SkillTrustConfig,
+    key: &str,
+    entry: &LockedSkill,
+    staging: &Path,
+) -> Result<()> {
+    let fetched = fetch_skill(&entry.source, entry.subpath.as_deref(), staging, true).await?;
+    let hash = skill_content_hash(&fetched.root)?;
+    if hash == entry.content_hash {
+        println!("{}: up to date ({})", entry.name, entry.version_label());
+        return Ok(());
+    }
+
+    let new_label = validate_skill_dir(&fetched.root)?
+        .version
+        .or_else(|| {
+            fetched
+                .commit
+                .as_ref()
+                .map(|c| c.chars().take(12).collect())
+        })
+        .unwrap_or_else(|| hash.chars().take(12).collect());
+    println!("{}: {} → {}", entry.name, entry.version_label(), new_label);
+    for line in changelog(entry, &fetched) {
+        println!("    {}", line);
+    }
+    check_trust(trust, &fetched.root)?;
+
+    let skill = install_skill_dir(&fetched.root, &paths.managed_skills_dir(), true)?;
+    if skill.command_name != key {
+        // Renamed upstream: drop the old directory and lock entry
+        let _ = remove_managed_skill(&paths.managed_skills_dir(), key);
+        let lock_path = paths.skills_lock_file();
+        let mut lock = SkillLock::load(&lock_path)?;
+        lock.skills.remove(key);
+        lock.save(&lock_path)?;
+    }
+    record_lock(paths, &skill, &entry.source, &fetched)?;
+    if !skill.eligibility.is_ready() {
+        println!("    not eligible ({})", skill.eligibility.describe());
+    }
+    Ok(())
+}
+
+/// Changes between the locked version and the fetched one: the git log for
+/// git sources, otherwise the newest section of the skill's CHANGELOG.md.
+fn changelog(entry: &LockedSkill, fetched: &Fetched) -> Vec<String> {
+    if let (Some(checkout), Some(old)) = (&fetched.checkout, &entry.commit) {
+        let mut cmd = Command::new("git");
+        cmd.arg("-C")
+            .arg(checkout)
+            .args(["log", "--oneline", "--no-decorate"])
+            .arg(format!("{}..HEAD", old));
+        if let Some(subpath) = &fetched.subpath {
+            cmd.args(["--", subpath]);
+        }
+        return match run_command(&mut cmd, "git log") {
+            Ok(log) if !log.trim().is_empty() => truncate_lines(&log),
+            Ok(_) => vec!["(no commits touching this skill)".to_string()],
+            Err(_) => vec!["(previous commit not found upstream)".to_string()],
+        };
+    }
+
+    let Ok(content) = fs::read_to_string(fetched.root.join("CHANGELOG.md")) else {
+        return Vec::new();
+    };
+    // First "## " section, which by convention is the latest release
+    let section: String = content
+        .lines()
+        .skip_while(|l| !l.starts_with("## "))
+        .enumerate()
+        .take_while(|(i, l)| *i == 0 || !l.starts_with("## "))
+        .map(|(_, l)| format!("{}\n", l))
+        .collect();
+    truncate_lines(&section)
+}
+
+fn truncate_lines(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let mut out: Vec<String> = lines
+        .iter()
+        .take(MAX_CHANGELOG_LINES)
+        .map(|l| l.to_string())
+        .collect();
+    if lines.len() > MAX_CHANGELOG_LINES {
+        out.push(format!(
+            "... and {} more",
+            lines.len() - MAX_CHANGELOG_LINES
+        ));
+    }
+    out
+}
+
+fn record_lock(paths: &Paths, skill: &Skill, source: &str, fetched: &Fetched) -> Result<()> {
+    let Some(dir) = skill.path.parent() else {
+        return Ok(());
+    };
+    let key = skill.command_name.clone();
+    let lock_path = paths.skills_lock_file();
+    let mut lock = SkillLock::load(&lock_path)?;
+    lock.skills.insert(
+        key,
+        LockedSkill {
+            name: skill.name.clone(),
+            source: source.to_string(),
+            kind: fetched.kind,
+            subpath: fetched.subpath.clone(),
+            version: skill.version.clone(),
+            commit: fetched.commit.clone(),
+            content_hash: skill_content_hash(dir)?,
+            installed_at: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+    lock.save(&lock_path)
+}
+
+/// Fetch `source` into `staging` and locate the skill inside it.
+///
+/// `full_history` clones git sources with full commit history (but no blobs
+/// beyond HEAD) so the changelog between versions can be shown.
+async fn fetch_skill(
+    source: &str,
+    subdir: Option<&str>,
+    staging: &Path,
+    full_history: bool,
+) -> Result<Fetched> {
+    let (base, kind) = fetch_source(source, staging, full_history).await?;
+    let root = match subdir {
+        Some(sub) => {
+            let dir = base.join(sub);
+            anyhow::ensure!(
+                dir.starts_with(&base) && !sub.split('/').any(|c| c == ".."),
+                "Invalid --path: {}",
+                sub
+            );
+            dir
+        }
+        None => find_skill_root(&base)?,
+    };
+    let subpath = root
+        .strip_prefix(&base)
+        .ok()
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .filter(|p| !p.is_empty());
+
+    let (checkout, commit) = if kind == SkillSourceKind::Git {
+        let head = run_command(
+            Command::new("git")
+                .arg("-C")
+                .arg(&base)
+                .args(["rev-parse", "HEAD"]),
+            "git rev-parse",
+        )?;
+        (Some(base), Some(head.trim().to_string()))
+    } else {
+        (None, None)
+    };
+
+    Ok(Fetched {
+        root,
+        subpath,
+        kind,
+        checkout,
+        commit,
+    })
+}
+
+/// Fetch `source` and return the directory holding its files.
+async fn fetch_source(
+    source: &str,
+    staging: &Path,
+    full_history: bool,
+) -> Result<(PathBuf, SkillSourceKind)> {
+    let local = PathBuf::from(shellexpand::tilde(source).to_string());
+    if local.is_dir() {
+        return Ok((local, SkillSourceKind::Local));
+    }
+
+    fs::create_dir_all(staging)?;
+    let is_http = source.starts_with("https://") || source.starts_with("http://");
+    let lower = source.to_lowercase();
+
+    if is_http && lower.ends_with(".md") {
+        let body = download(source).await?;
+        fs::write(staging.join("SKILL.md"), body)?;
+        Ok((staging.to_path_buf(), SkillSourceKind::Url))
+    } else if is_http && (lower.ends_with(".tar.gz") || lower.ends_with(".tgz")) {
+        let archive = staging.join("skill.tar.gz");
+        fs::write(&archive, download(source).await?)?;
+        let extracted = staging.join("archive");
+        fs::create_dir_all(&extracted)?;
+        run_command(
+            Command::new("tar")
+                .arg("-xzf")
+                .arg(&archive)
+                .arg("-C")
+                .arg(&extracted),
+            "tar",
+        )?;
+        Ok((single_top_level_dir(&extracted), SkillSourceKind::Archive))
+    } else {
+        let checkout = staging.join("repo");
+        eprintln!("Cloning {} ...", source);
+        let mut cmd = Command::new("git");
+        cmd.args(["clone", "--quiet"]);
+        if full_history {
+            cmd.arg("--filter=blob:none");
+        } else {
+            cmd.args(["--depth", "1"]);
+        }
+        run_command(cmd.arg("--").arg(source).arg(&checkout), "git clone")?;
+        Ok((checkout, SkillSourceKind::Git))
+    }
+}
+
+async fn download(url: &str) -> Result<Vec<u8>> {
+    eprintln!("Downloading {} ...", url);
+    let resp = reqwest::get(url)
+        .await
+        .with_context(|| format!("Failed to download {}", url))?;
+    if !resp.status().is_success() {
+        anyhow::bail!("Download failed: HTTP {}", resp.status());
+    }
+    let bytes = resp.bytes().await?;
+    if bytes.len() > MAX_DOWNLOAD_BYTES {
+        anyhow::bail!(
+            "Download is too large ({} bytes, limit {})",
+            bytes.len(),
+            MAX_DOWNLOAD_BYTES
+        );
+    }
+    Ok(bytes.to_vec())
+}
+
+/// Run a command, returning its stdout or an error with its stderr.
+fn run_command(cmd: &mut Command, what: &str) -> Result<String> {
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run {}", what))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} failed: {}",
+            what,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Archives usually wrap everything in one top-level directory; descend into it.
+fn single_top_level_dir(dir: &Path) -> PathBuf {
+    let entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|rd| rd.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    match entries.as_slice() {
+        [only] if only.is_dir() => only.clone(),
+        _ => dir.to_path_buf(),
+    }
+}
+
+fn print_installed(skill: &Skill) {
+    let emoji = skill
+        .emoji
+        .as_ref()
+        .map(|e| format!("{} ", e))
+        .unwrap_or_default();
+    println!("Installed skill {}{}", emoji, skill.name);
+    if let Some(version) = &skill.version {
+        println!("  Version:     {}", version);
+    }
+    println!("  Location:    {}", skill.path.display());
+    println!("  Description: {}", skill.description);
+    if skill.user_invocable {
+        println!("  Command:     /{}", skill.command_name);
+    }
+
+    let requires = &skill.requires;
+    if !requires.bins.is_empty() {
+        println!("  Requires:    {}", requires.bins.join(", "));
+    }
+    if !requires.any_bins.is_empty() {
+        println!("  Any of:      {}", requires.any_bins.join(", "));
+    }
+    if !requires.env.is_empty() {
+        println!("  Env:         {}", requires.env.join(", "));
+    }
+
+    if skill.eligibility.is_ready() {
+        println!("  Status:      ready");
+    } else {
+        println!(
+            "  Status:      not eligible ({})",
+            skill.eligibility.describe()
+        );
+        println!("  The skill is installed but hidden until its requirements are met.");
+    }
+}
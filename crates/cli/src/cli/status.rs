@@ -0,0 +1,175 @@
+//! CLI subcommand: `localgpt status`
+//!
+//! Queries the running daemon's `/api/admin/status` endpoint and prints
+//! uptime, providers, MCP servers, bridges, scheduler, and memory stats.
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use serde_json::Value;
+
+use localgpt_core::config::Config;
+
+#[derive(Args)]
+pub struct StatusArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// API key to authenticate with (defaults to server.auth_token)
+    #[arg(long, env = "LOCALGPT_API_KEY", hide_env_values = true)]
+    pub api_key: Option<String>,
+}
+
+pub async fn run(args: StatusArgs) -> Result<()> {
+    let config = Config::load()?;
+    let host = match config.server.bind.as_str() {
+        "0.0.0.0" | "::" => "127.0.0.1",
+        bind => bind,
+    };
+    let url = format!("http://{}:{}/api/admin/status", host, config.server.port);
+
+    let mut request = reqwest::Client::new().get(&url);
+    if let Some(token) = args.api_key.or(config.server.auth_token.clone()) {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await.with_context(|| {
+        format!(
+            "Could not reach the daemon at {} (is it running? try 'localgpt daemon start')",
+            url
+        )
+    })?;
+    if !response.status().is_success() {
+        bail!(
+            "Daemon returned {} (admin status needs an admin-scoped API key)",
+            response.status()
+        );
+    }
+    let status: Value = response.json().await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+    print_status(&status);
+    Ok(())
+}
+
+fn print_status(status: &Value) {
+    let str_of = |v: &Value| v.as_str().unwrap_or("-").to_string();
+
+    println!("LocalGPT Daemon Status");
+    println!("----------------------");
+    println!("Version: {}", str_of(&status["version"]));
+    println!("PID: {}", status["pid"]);
+    println!(
+        "Uptime: {}",
+        format_uptime(status["uptime_secs"].as_u64().unwrap_or(0))
+    );
+    println!("Model: {}", str_of(&status["model"]));
+    let providers: Vec<String> = status["providers"]
+        .as_array()
+        .map(|p| p.iter().map(str_of).collect())
+        .unwrap_or_default();
+    println!(
+        "Providers: {}",
+        if providers.is_empty() {
+            "none".to_string()
+        } else {
+            providers.join(", ")
+        }
+    );
+    println!("Active sessions: {}", status["active_sessions"]);
+
+    let servers = status["mcp_servers"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    println!("\nMCP servers: {}", servers.len());
+    for server in &servers {
+        print!(
+            "  {:<20} {:<13} {} tools",
+            str_of(&server["server"]),
+            str_of(&server["state"]),
+            server["tools"]
+        );
+        if let Some(error) = server["last_error"].as_str() {
+            print!("  ({})", error);
+        }
+        println!();
+    }
+
+    let bridges = status["bridges"].as_array().cloned().unwrap_or_default();
+    println!("\nBridges: {}", bridges.len());
+    for bridge in &bridges {
+        println!(
+            "  {:<20} {:<10} since {}",
+            bridge["bridge_id"].as_str().unwrap_or("(unidentified)"),
+            str_of(&bridge["health"]),
+            str_of(&bridge["connected_at"])
+        );
+    }
+
+    let scheduler = &status["scheduler"];
+    println!("\nScheduler:");
+    let heartbeat = &scheduler["heartbeat"];
+    if heartbeat["enabled"].as_bool().unwrap_or(false) {
+        print!("  Heartbeat: every {}", str_of(&heartbeat["interval"]));
+        if let Some(event) = heartbeat["last_event"].as_object() {
+            print!(
+                ", last {} {}s ago",
+                event["status"].as_str().unwrap_or("-"),
+                event["age_seconds"]
+            );
+        }
+        println!();
+    } else {
+        println!("  Heartbeat: disabled");
+    }
+    println!(
+        "  Cron jobs: {} ({} enabled, {} paused)",
+        scheduler["cron_jobs"], scheduler["cron_enabled"], scheduler["cron_paused"]
+    );
+    if let Some(next) = scheduler["next_run"].as_str() {
+        println!("  Next run: {}", next);
+    }
+
+    let turns = &status["turns"];
+    if turns.is_object() {
+        println!("\nTurns:");
+        println!(
+            "  {} of {} running, {} waiting",
+            turns["in_flight"], turns["max_concurrent"], turns["waiting"]
+        );
+        println!(
+            "  {} started, {} waited (avg {} ms, max {} ms), {} timed out",
+            turns["turns"],
+            turns["waited"],
+            turns["avg_wait_ms"],
+            turns["max_wait_ms"],
+            turns["timeouts"]
+        );
+    }
+
+    let memory = &status["memory"];
+    println!("\nMemory:");
+    if memory.is_null() {
+        println!("  unavailable");
+    } else {
+        println!("  Workspace: {}", str_of(&memory["workspace"]));
+        println!(
+            "  Files: {}, chunks: {}, index: {} KB",
+            memory["total_files"], memory["total_chunks"], memory["index_size_kb"]
+        );
+    }
+}
+
+pub(crate) fn format_uptime(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, mins)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m {}s", mins, secs % 60)
+    }
+}
@@ -0,0 +1,1030 @@
+//! Full-screen chat for `localgpt chat --tui`
+//!
+//! The agent runs on its own task and reports back over a channel, so the
+//! screen keeps redrawing while a reply streams in or a tool runs. Tool calls
+//! show up as panes that collapse to one line, and tools listed in
+//! `tools.require_approval` are confirmed in a prompt, as in the line REPL.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::StreamExt;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::sync::{mpsc as async_mpsc, oneshot};
+
+use localgpt_core::agent::{
+    Agent, Role, SessionInfo, extract_tool_detail, list_sessions_for_agent,
+};
+use localgpt_core::concurrency::{CancellationToken, WorkspaceLock, is_cancelled};
+use localgpt_core::mcp::sampling::{SamplingApprover, set_sampling_approver};
+use localgpt_core::security::{PendingWrite, WriteApprover, add_write_approver};
+
+use super::completions;
+
+/// Longest tool output shown in an expanded pane, in lines
+const MAX_OUTPUT_LINES: usize = 40;
+
+/// Lines moved by PageUp/PageDown
+const PAGE_LINES: u16 = 10;
+
+const HELP_TEXT: &str = "\
+Keys:
+  Enter        Send the message
+  Esc          Stop the running reply
+  Ctrl+K       Compact the session (/compact)
+  Ctrl+O       Switch model (/model [name])
+  Ctrl+S       Switch to a saved session (/resume [id])
+  Ctrl+N       Start a new session (/new)
+  Ctrl+T       Expand or collapse tool calls
+  Ctrl+U       Clear the input
+  PgUp/PgDn    Scroll; End jumps to the bottom
+  F1           This help
+  Ctrl+C       Quit (/quit)";
+
+/// Answers a yes/no prompt shown by the UI
+type Reply = Box<dyn FnOnce(bool) + Send>;
+
+/// What the UI asks the agent task to do
+enum Request {
+    /// A message, and the token that stops its turn
+    Send(String, CancellationToken),
+    Compact,
+    SetModel(String),
+    NewSession,
+    Resume(String),
+}
+
+/// What the agent task tells the UI
+enum Update {
+    /// Streamed text of the reply
+    Delta(String),
+    /// The reply that follows tool calls, all at once
+    Reply(String),
+    ToolCall {
+        id: String,
+        name: String,
+        detail: Option<String>,
+        arguments: String,
+    },
+    ToolState {
+        id: String,
+        state: ToolState,
+    },
+    ToolOutput {
+        id: String,
+        output: String,
+    },
+    Approve {
+        prompt: String,
+        reply: Reply,
+    },
+    Notice(String),
+    Error(String),
+    /// The session was switched; this is its history
+    History(Vec<Entry>),
+    Status(StatusLine),
+    /// The request is finished
+    Idle,
+}
+
+/// One item in the scrollback
+enum Entry {
+    User(String),
+    Assistant(String),
+    Tool(ToolPane),
+    Notice(String),
+    Error(String),
+}
+
+struct ToolPane {
+    id: String,
+    name: String,
+    detail: Option<String>,
+    arguments: String,
+    output: Option<String>,
+    state: ToolState,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ToolState {
+    /// Waiting for approval
+    Pending,
+    Running,
+    Done,
+    Failed,
+    Skipped,
+}
+
+/// What the status bar shows
+#[derive(Default)]
+struct StatusLine {
+    model: String,
+    session_id: String,
+    context_used: usize,
+    context_usable: usize,
+    input_tokens: u64,
+    output_tokens: u64,
+    search_cost_usd: f64,
+}
+
+impl StatusLine {
+    fn of(agent: &Agent) -> Self {
+        let status = agent.session_status();
+        let (used, usable, _) = agent.context_usage();
+        Self {
+            model: agent.model().to_string(),
+            session_id: status.id,
+            context_used: used,
+            context_usable: usable,
+            input_tokens: status.api_input_tokens,
+            output_tokens: status.api_output_tokens,
+            search_cost_usd: status.search_cost_usd,
+        }
+    }
+}
+
+enum Popup {
+    Models {
+        items: Vec<String>,
+        state: ListState,
+    },
+    Sessions {
+        items: Vec<SessionInfo>,
+        state: ListState,
+    },
+    Help,
+}
+
+/// Asks in the TUI before an MCP server with `sampling = "ask"` runs a
+/// completion.
+struct TuiSamplingApprover {
+    updates: Sender<Update>,
+}
+
+impl SamplingApprover for TuiSamplingApprover {
+    fn approve(&self, server: &str, preview: &str) -> bool {
+        let (tx, rx) = mpsc::channel();
+        let reply: Reply = Box::new(move |approved| {
+            let _ = tx.send(approved);
+        });
+        let prompt = format!(
+            "MCP server '{}' requests a completion:\n\n{}",
+            server, preview
+        );
+        self.updates.send(Update::Approve { prompt, reply }).is_ok() && rx.recv().unwrap_or(false)
+    }
+}
+
+/// Asks in the TUI before the agent writes to a protected file.
+struct TuiWriteApprover {
+    updates: Sender<Update>,
+}
+
+impl WriteApprover for TuiWriteApprover {
+    fn name(&self) -> &str {
+        "cli"
+    }
+
+    fn ask(&self, write: &PendingWrite) -> Option<bool> {
+        let (tx, rx) = mpsc::channel();
+        let reply: Reply = Box::new(move |approved| {
+            let _ = tx.send(approved);
+        });
+        let prompt = format!(
+            "{} wants to change protected file {}:\n\n{}",
+            write.tool,
+            write.path.display(),
+            write.preview
+        );
+        Some(
+            self.updates.send(Update::Approve { prompt, reply }).is_ok()
+                && rx.recv().unwrap_or(false),
+        )
+    }
+}
+
+/// Run the full-screen chat until the user quits
+pub async fn run(agent: Agent, agent_id: &str, workspace_lock: WorkspaceLock) -> Result<()> {
+    let (request_tx, request_rx) = async_mpsc::unbounded_channel();
+    let (update_tx, update_rx) = mpsc::channel();
+    set_sampling_approver(Arc::new(TuiSamplingApprover {
+        updates: update_tx.clone(),
+    }));
+    add_write_approver(Arc::new(TuiWriteApprover {
+        updates: update_tx.clone(),
+    }));
+
+    let mut app = App::new(&agent, agent_id, request_tx);
+    let worker = tokio::spawn(agent_loop(agent, workspace_lock, request_rx, update_tx));
+
+    let mut terminal = ratatui::try_init()?;
+    let result = tokio::task::block_in_place(|| app.run(&mut terminal, &update_rx));
+    ratatui::restore();
+
+    // Quitting mid-reply drops that turn; finished turns are already saved
+    worker.abort();
+    result
+}
+
+struct App {
+    agent_id: String,
+    entries: Vec<Entry>,
+    input: String,
+    status: StatusLine,
+    busy: bool,
+    tools_expanded: bool,
+    /// Lines scrolled up from the bottom
+    scroll: u16,
+    popup: Option<Popup>,
+    approvals: VecDeque<(String, Reply)>,
+    /// Cancels the reply being streamed
+    turn: Option<CancellationToken>,
+    quit: bool,
+    requests: async_mpsc::UnboundedSender<Request>,
+}
+
+impl App {
+    fn new(agent: &Agent, agent_id: &str, requests: async_mpsc::UnboundedSender<Request>) -> Self {
+        let mut entries = vec![Entry::Notice(format!(
+            "LocalGPT v{} | Agent: {} | Model: {} | Memory: {} chunks\nPress F1 for keys, Ctrl+C to quit",
+            env!("CARGO_PKG_VERSION"),
+            agent_id,
+            agent.model(),
+            agent.memory_chunk_count(),
+        ))];
+        if agent.is_brand_new() {
+            entries.push(Entry::Notice(
+                localgpt_core::agent::FIRST_RUN_WELCOME.to_string(),
+            ));
+        }
+        entries.extend(history(agent));
+
+        Self {
+            agent_id: agent_id.to_string(),
+            entries,
+            input: String::new(),
+            status: StatusLine::of(agent),
+            busy: false,
+            tools_expanded: false,
+            scroll: 0,
+            popup: None,
+            approvals: VecDeque::new(),
+            turn: None,
+            quit: false,
+            requests,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal, updates: &Receiver<Update>) -> Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(Duration::from_millis(50))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.on_key(key);
+            }
+
+            loop {
+                match updates.try_recv() {
+                    Ok(update) => self.on_update(update),
+                    Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn on_update(&mut self, update: Update) {
+        match update {
+            Update::Delta(text) => match self.entries.last_mut() {
+                Some(Entry::Assistant(reply)) => reply.push_str(&text),
+                _ => self.entries.push(Entry::Assistant(text)),
+            },
+            Update::Reply(text) => {
+                if !text.trim().is_empty() {
+                    self.entries.push(Entry::Assistant(text));
+                }
+            }
+            Update::ToolCall {
+                id,
+                name,
+                detail,
+                arguments,
+            } => self.entries.push(Entry::Tool(ToolPane {
+                id,
+                name,
+                detail,
+                arguments,
+                output: None,
+                state: ToolState::Pending,
+            })),
+            Update::ToolState { id, state } => {
+                if let Some(pane) = self.tool_pane(&id) {
+                    pane.state = state;
+                }
+            }
+            Update::ToolOutput { id, output } => {
+                if let Some(pane) = self.tool_pane(&id) {
+                    pane.output = Some(output);
+                    pane.state = ToolState::Done;
+                }
+            }
+            Update::Approve { prompt, reply } => self.approvals.push_back((prompt, reply)),
+            Update::Notice(text) => self.entries.push(Entry::Notice(text)),
+            Update::Error(text) => self.entries.push(Entry::Error(text)),
+            Update::History(entries) => {
+                self.entries = entries;
+                self.scroll = 0;
+            }
+            Update::Status(status) => self.status = status,
+            Update::Idle => {
+                self.busy = false;
+                self.turn = None;
+            }
+        }
+    }
+
+    fn tool_pane(&mut self, id: &str) -> Option<&mut ToolPane> {
+        self.entries.iter_mut().rev().find_map(|entry| match entry {
+            Entry::Tool(pane) if pane.id == id => Some(pane),
+            _ => None,
+        })
+    }
+
+    fn on_key(&mut self, key: KeyEvent) {
+        if !self.approvals.is_empty() {
+            let approved = match key.code {
+                KeyCode::Char('y' | 'Y') => true,
+                KeyCode::Char('n' | 'N') | KeyCode::Esc => false,
+                _ => return,
+            };
+            if let Some((_, reply)) = self.approvals.pop_front() {
+                reply(approved);
+            }
+            return;
+        }
+        if self.popup.is_some() {
+            self.on_popup_key(key);
+            return;
+        }
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c' | 'd') if ctrl => self.quit = true,
+            KeyCode::Char('k') if ctrl => self.send(Request::Compact),
+            KeyCode::Char('o') if ctrl => self.open_models(),
+            KeyCode::Char('s') if ctrl => self.open_sessions(),
+            KeyCode::Char('n') if ctrl => self.send(Request::NewSession),
+            KeyCode::Char('t') if ctrl => self.tools_expanded = !self.tools_expanded,
+            KeyCode::Char('u') if ctrl => self.input.clear(),
+            KeyCode::Char(c) if !ctrl => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => self.submit(),
+            KeyCode::Esc => {
+                if let Some(turn) = &self.turn {
+                    turn.cancel();
+                }
+            }
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_add(PAGE_LINES),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(PAGE_LINES),
+            KeyCode::End => self.scroll = 0,
+            KeyCode::F(1) => self.popup = Some(Popup::Help),
+            _ => {}
+        }
+    }
+
+    fn on_popup_key(&mut self, key: KeyEvent) {
+        let Some(popup) = &mut self.popup else {
+            return;
+        };
+        let (state, len) = match popup {
+            Popup::Models { items, state } => (state, items.len()),
+            Popup::Sessions { items, state } => (state, items.len()),
+            Popup::Help => {
+                self.popup = None;
+                return;
+            }
+        };
+
+        match key.code {
+            KeyCode::Up => state.select(Some(state.selected().unwrap_or(0).saturating_sub(1))),
+            KeyCode::Down => {
+                let next = state.selected().map_or(0, |i| i + 1);
+                state.select(Some(next.min(len.saturating_sub(1))));
+            }
+            KeyCode::Enter => {
+                let selected = state.selected();
+                let request = match (self.popup.take(), selected) {
+                    (Some(Popup::Models { items, .. }), Some(i)) => {
+                        items.get(i).cloned().map(Request::SetModel)
+                    }
+                    (Some(Popup::Sessions { items, .. }), Some(i)) => {
+                        items.get(i).map(|s| Request::Resume(s.id.clone()))
+                    }
+                    _ => None,
+                };
+                if let Some(request) = request {
+                    self.send(request);
+                }
+            }
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.popup = None
+            }
+            _ => {}
+        }
+    }
+
+    fn submit(&mut self) {
+        let input = self.input.trim().to_string();
+        if input.is_empty() {
+            return;
+        }
+        if self.busy {
+            self.entries.push(Entry::Error(
+                "Still working on the last request".to_string(),
+            ));
+            return;
+        }
+        self.input.clear();
+        self.scroll = 0;
+
+        if let Some(command) = input.strip_prefix('/') {
+            self.command(command);
+            return;
+        }
+        self.entries.push(Entry::User(input.clone()));
+        let turn = CancellationToken::new();
+        self.turn = Some(turn.clone());
+        self.send(Request::Send(input, turn));
+    }
+
+    fn command(&mut self, command: &str) {
+        let mut parts = command.split_whitespace();
+        match (parts.next().unwrap_or(""), parts.next()) {
+            ("quit" | "exit" | "q", _) => self.quit = true,
+            ("compact", _) => self.send(Request::Compact),
+            ("model", Some(model)) => self.send(Request::SetModel(model.to_string())),
+            ("model", None) => self.open_models(),
+            ("new", _) => self.send(Request::NewSession),
+            ("resume", Some(prefix)) => self.resume_prefix(prefix),
+            ("resume" | "sessions", None) => self.open_sessions(),
+            ("help", _) => self.popup = Some(Popup::Help),
+            (other, _) => self.entries.push(Entry::Error(format!(
+                "Unknown command: /{} (press F1 for help)",
+                other
+            ))),
+        }
+    }
+
+    fn send(&mut self, request: Request) {
+        if self.busy {
+            self.entries.push(Entry::Error(
+                "Still working on the last request".to_string(),
+            ));
+            return;
+        }
+        if self.requests.send(request).is_ok() {
+            self.busy = true;
+        } else {
+            self.entries
+                .push(Entry::Error("The agent has stopped".to_string()));
+        }
+    }
+
+    fn open_models(&mut self) {
+        let items = completions::known_models();
+        let current = items.iter().position(|m| *m == self.status.model);
+        self.popup = Some(Popup::Models {
+            items,
+            state: ListState::default().with_selected(Some(current.unwrap_or(0))),
+        });
+    }
+
+    fn open_sessions(&mut self) {
+        match list_sessions_for_agent(&self.agent_id) {
+            Ok(items) if items.is_empty() => {
+                self.entries
+                    .push(Entry::Notice("No saved sessions.".to_string()));
+            }
+            Ok(items) => {
+                self.popup = Some(Popup::Sessions {
+                    items,
+                    state: ListState::default().with_selected(Some(0)),
+                });
+            }
+            Err(e) => self
+                .entries
+                .push(Entry::Error(format!("Failed to list sessions: {}", e))),
+        }
+    }
+
+    fn resume_prefix(&mut self, prefix: &str) {
+        let sessions = match list_sessions_for_agent(&self.agent_id) {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                self.entries
+                    .push(Entry::Error(format!("Failed to list sessions: {}", e)));
+                return;
+            }
+        };
+        let matching: Vec<_> = sessions
+            .into_iter()
+            .filter(|s| s.id.starts_with(prefix))
+            .collect();
+        match matching.as_slice() {
+            [] => self.entries.push(Entry::Error(format!(
+                "No session found matching '{}'",
+                prefix
+            ))),
+            [session] => self.send(Request::Resume(session.id.clone())),
+            _ => self.entries.push(Entry::Error(format!(
+                "Multiple sessions match '{}'. Please be more specific.",
+                prefix
+            ))),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [chat_area, input_area, status_area] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        // Scrollback, pinned to the bottom unless scrolled up
+        let chat = Paragraph::new(self.chat_text()).wrap(Wrap { trim: false });
+        let total = u16::try_from(chat.line_count(chat_area.width)).unwrap_or(u16::MAX);
+        let max_scroll = total.saturating_sub(chat_area.height);
+        self.scroll = self.scroll.min(max_scroll);
+        frame.render_widget(chat.scroll((max_scroll - self.scroll, 0)), chat_area);
+
+        // Input, showing its tail when it's wider than the box
+        let title = if self.busy {
+            " Working... "
+        } else {
+            " Message (Enter to send, F1 for help) "
+        };
+        let width = input_area.width.saturating_sub(3) as usize;
+        let skip = self.input.chars().count().saturating_sub(width);
+        let visible: String = self.input.chars().skip(skip).collect();
+        let cursor_x = input_area.x + 1 + visible.chars().count() as u16;
+        frame.render_widget(
+            Paragraph::new(visible).block(Block::bordered().title(title)),
+            input_area,
+        );
+        if self.popup.is_none() && self.approvals.is_empty() {
+            frame.set_cursor_position((cursor_x, input_area.y + 1));
+        }
+
+        frame.render_widget(
+            Paragraph::new(self.status_line()).style(Style::new().bg(Color::DarkGray)),
+            status_area,
+        );
+
+        self.draw_popup(frame);
+    }
+
+    fn chat_text(&self) -> Text<'static> {
+        let mut lines = Vec::new();
+        for entry in &self.entries {
+            match entry {
+                Entry::User(text) => {
+                    lines.push(Line::styled("You", Style::new().bold().fg(Color::Cyan)));
+                    lines.extend(text.lines().map(|l| Line::raw(l.to_string())));
+                }
+                Entry::Assistant(text) => {
+                    lines.push(Line::styled(
+                        "LocalGPT",
+                        Style::new().bold().fg(Color::Green),
+                    ));
+                    lines.extend(text.lines().map(|l| Line::raw(l.to_string())));
+                }
+                Entry::Tool(pane) => pane.render(self.tools_expanded, &mut lines),
+                Entry::Notice(text) => lines.extend(
+                    text.lines()
+                        .map(|l| Line::styled(l.to_string(), Style::new().dim())),
+                ),
+                Entry::Error(text) => lines.extend(
+                    text.lines()
+                        .map(|l| Line::styled(l.to_string(), Style::new().fg(Color::Red))),
+                ),
+            }
+            lines.push(Line::default());
+        }
+        Text::from(lines)
+    }
+
+    fn status_line(&self) -> Line<'static> {
+        let status = &self.status;
+        let pct = if status.context_usable > 0 {
+            status.context_used * 100 / status.context_usable
+        } else {
+            0
+        };
+        let context_style = if pct > 80 {
+            Style::new().fg(Color::Yellow)
+        } else {
+            Style::new()
+        };
+
+        let mut spans = vec![
+            Span::styled(format!(" {} ", status.model), Style::new().reversed()),
+            Span::raw(format!(
+                " session {} | ",
+                &status.session_id[..status.session_id.floor_char_boundary(8)]
+            )),
+            Span::styled(
+                format!(
+                    "context {}/{} ({}%)",
+                    status.context_used, status.context_usable, pct
+                ),
+                context_style,
+            ),
+            Span::raw(format!(
+                " | tokens {} in / {} out",
+                status.input_tokens, status.output_tokens
+            )),
+        ];
+        if status.search_cost_usd > 0.0 {
+            spans.push(Span::raw(format!(
+                " | search ${:.3}",
+                status.search_cost_usd
+            )));
+        }
+        Line::from(spans)
+    }
+
+    fn draw_popup(&mut self, frame: &mut Frame) {
+        let area = frame.area();
+
+        if let Some((prompt, _)) = self.approvals.front() {
+            let rect = centered(area, 70, 12);
+            frame.render_widget(Clear, rect);
+            frame.render_widget(
+                Paragraph::new(prompt.as_str())
+                    .wrap(Wrap { trim: false })
+                    .block(
+                        Block::bordered()
+                            .title(" Approve? [y/n] ")
+                            .border_style(Style::new().fg(Color::Yellow)),
+                    ),
+                rect,
+            );
+            return;
+        }
+
+        match &mut self.popup {
+            Some(Popup::Models { items, state }) => {
+                let rect = centered(area, 50, items.len() as u16 + 2);
+                let list = List::new(items.iter().map(|m| ListItem::new(m.clone())))
+                    .block(Block::bordered().title(" Switch model (Enter, Esc) "))
+                    .highlight_style(Style::new().reversed());
+                frame.render_widget(Clear, rect);
+                frame.render_stateful_widget(list, rect, state);
+            }
+            Some(Popup::Sessions { items, state }) => {
+                let rect = centered(area, 80, items.len() as u16 + 2);
+                let list = List::new(items.iter().map(|s| {
+                    ListItem::new(format!(
+                        "{}  {}  {:>4} msgs  {}",
+                        &s.id[..s.id.floor_char_boundary(8)],
+                        s.created_at.format("%Y-%m-%d %H:%M"),
+                        s.message_count,
+                        s.title.as_deref().unwrap_or("(untitled)")
+                    ))
+                }))
+                .block(Block::bordered().title(" Switch session (Enter, Esc) "))
+                .highlight_style(Style::new().reversed());
+                frame.render_widget(Clear, rect);
+                frame.render_stateful_widget(list, rect, state);
+            }
+            Some(Popup::Help) => {
+                let rect = centered(area, 60, HELP_TEXT.lines().count() as u16 + 2);
+                frame.render_widget(Clear, rect);
+                frame.render_widget(
+                    Paragraph::new(HELP_TEXT).block(Block::bordered().title(" Help ")),
+                    rect,
+                );
+            }
+            None => {}
+        }
+    }
+}
+
+impl ToolPane {
+    fn render(&self, expanded: bool, lines: &mut Vec<Line<'static>>) {
+        let (label, color) = match self.state {
+            ToolState::Pending => ("waiting", Color::Yellow),
+            ToolState::Running => ("running", Color::Yellow),
+            ToolState::Done => ("done", Color::Green),
+            ToolState::Failed => ("failed", Color::Red),
+            ToolState::Skipped => ("skipped", Color::DarkGray),
+        };
+        let mut header = format!("{} {}", if expanded { "▾" } else { "▸" }, self.name);
+        if let Some(detail) = &self.detail {
+            header.push_str(&format!(" ({})", detail));
+        }
+        lines.push(Line::from(vec![
+            Span::styled(header, Style::new().fg(Color::Magenta)),
+            Span::raw(" "),
+            Span::styled(label, Style::new().fg(color)),
+        ]));
+        if !expanded {
+            return;
+        }
+
+        lines.push(Line::styled("  arguments:", Style::new().dim()));
+        lines.extend(
+            self.arguments
+                .lines()
+                .map(|l| Line::raw(format!("    {}", l))),
+        );
+        if let Some(output) = &self.output {
+            lines.push(Line::styled("  output:", Style::new().dim()));
+            lines.extend(
+                output
+                    .lines()
+                    .take(MAX_OUTPUT_LINES)
+                    .map(|l| Line::raw(format!("    {}", l))),
+            );
+            let more = output.lines().count().saturating_sub(MAX_OUTPUT_LINES);
+            if more > 0 {
+                lines.push(Line::styled(
+                    format!("    ... {} more lines", more),
+                    Style::new().dim(),
+                ));
+            }
+        }
+    }
+}
+
+/// A `width` x `height` rectangle in the middle of `area`
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// The current session's messages as scrollback entries
+fn history(agent: &Agent) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    for message in agent.raw_session_messages().iter().map(|m| &m.message) {
+        let content = message.content.trim();
+        match message.role {
+            Role::User if !content.is_empty() => entries.push(Entry::User(content.to_string())),
+            Role::Assistant => {
+                if !content.is_empty() {
+                    entries.push(Entry::Assistant(content.to_string()));
+                }
+                for call in message.tool_calls.iter().flatten() {
+                    entries.push(Entry::Tool(ToolPane {
+                        id: call.id.clone(),
+                        name: call.name.clone(),
+                        detail: extract_tool_detail(&call.name, &call.arguments),
+                        arguments: call.arguments.clone(),
+                        output: None,
+                        state: ToolState::Done,
+                    }));
+                }
+            }
+            Role::Tool => {
+                let pane = entries.iter_mut().rev().find_map(|entry| match entry {
+                    Entry::Tool(pane) if message.tool_call_id.as_ref() == Some(&pane.id) => {
+                        Some(pane)
+                    }
+                    _ => None,
+                });
+                if let Some(pane) = pane {
+                    pane.output = Some(message.content.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// Serve the UI's requests one at a time until it goes away
+async fn agent_loop(
+    mut agent: Agent,
+    workspace_lock: WorkspaceLock,
+    mut requests: async_mpsc::UnboundedReceiver<Request>,
+    updates: Sender<Update>,
+) {
+    while let Some(request) = requests.recv().await {
+        let result = match request {
+            Request::Send(message, cancel) => {
+                agent.set_cancel_token(cancel);
+                let before = agent.session_status();
+                let result = chat_turn(&mut agent, &workspace_lock, &message, &updates).await;
+                if agent.is_cancelled() {
+                    // Drop the stopped turn so the next one starts clean
+                    agent.rewind_session(&before);
+                    let _ = updates.send(Update::Notice("(stopped)".to_string()));
+                    agent.auto_save_session()
+                } else {
+                    result
+                }
+            }
+            Request::Compact => agent.compact_session().await.map(|(before, after)| {
+                let _ = updates.send(Update::Notice(format!(
+                    "Session compacted. Token count: {} → {}",
+                    before, after
+                )));
+            }),
+            Request::SetModel(model) => agent.set_model(&model).map(|()| {
+                let _ = updates.send(Update::Notice(format!("Switched to model: {}", model)));
+            }),
+            Request::NewSession => new_session(&mut agent, &updates).await,
+            Request::Resume(id) => agent.resume_session(&id).await.map(|()| {
+                let _ = updates.send(Update::History(history(&agent)));
+                let _ = updates.send(Update::Notice(format!(
+                    "Resumed session {} ({} messages)",
+                    &id[..id.floor_char_boundary(8)],
+                    agent.session_status().message_count
+                )));
+            }),
+        };
+        if let Err(e) = result {
+            let _ = updates.send(Update::Error(e.to_string()));
+        }
+        let _ = updates.send(Update::Status(StatusLine::of(&agent)));
+        let _ = updates.send(Update::Idle);
+    }
+}
+
+/// Send `message` and stream the reply, running any tool calls it makes
+async fn chat_turn(
+    agent: &mut Agent,
+    workspace_lock: &WorkspaceLock,
+    message: &str,
+    updates: &Sender<Update>,
+) -> Result<()> {
+    let _lock_guard = workspace_lock.acquire()?;
+    let mut stream = agent.chat_stream_with_images(message, Vec::new()).await?;
+    let mut full_response = String::new();
+    let mut pending_tool_calls = None;
+
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(chunk) => {
+                if !chunk.delta.is_empty() {
+                    let _ = updates.send(Update::Delta(chunk.delta.clone()));
+                }
+                full_response.push_str(&chunk.delta);
+
+                // Capture tool calls from the final chunk
+                if chunk.done && chunk.tool_calls.is_some() {
+                    pending_tool_calls = chunk.tool_calls;
+                }
+            }
+            Err(e) if is_cancelled(&e) => return Ok(()),
+            Err(e) => {
+                let _ = updates.send(Update::Error(format!("Stream error: {}", e)));
+                break;
+            }
+        }
+    }
+
+    let Some(tool_calls) = pending_tool_calls else {
+        agent.finish_chat_stream(&full_response);
+        return agent.auto_save_session();
+    };
+
+    let mut approved_calls = Vec::new();
+    for tc in tool_calls {
+        let detail = extract_tool_detail(&tc.name, &tc.arguments);
+        let _ = updates.send(Update::ToolCall {
+            id: tc.id.clone(),
+            name: tc.name.clone(),
+            detail: detail.clone(),
+            arguments: tc.arguments.clone(),
+        });
+
+        if agent.requires_approval(&tc.name) {
+            let prompt = match &detail {
+                Some(d) => format!("Execute {} ({})?", tc.name, d),
+                None => format!("Execute {}?\n\n{}", tc.name, tc.arguments),
+            };
+            if !ask(updates, prompt).await {
+                let _ = updates.send(Update::ToolState {
+                    id: tc.id.clone(),
+                    state: ToolState::Skipped,
+                });
+                continue;
+            }
+        }
+        approved_calls.push(tc);
+    }
+
+    if approved_calls.is_empty() {
+        agent.finish_chat_stream(&full_response);
+        let _ = updates.send(Update::Notice("(Tool execution skipped)".to_string()));
+        return agent.auto_save_session();
+    }
+
+    let ids: Vec<String> = approved_calls.iter().map(|tc| tc.id.clone()).collect();
+    for id in &ids {
+        let _ = updates.send(Update::ToolState {
+            id: id.clone(),
+            state: ToolState::Running,
+        });
+    }
+
+    match agent
+        .execute_streaming_tool_calls(&full_response, approved_calls, |_, _| {}, |_, _| {})
+        .await
+    {
+        Ok((follow_up, warnings)) => {
+            for message in agent.raw_session_messages().iter().map(|m| &m.message) {
+                if message.role == Role::Tool
+                    && let Some(id) = &message.tool_call_id
+                    && ids.contains(id)
+                {
+                    let _ = updates.send(Update::ToolOutput {
+                        id: id.clone(),
+                        output: message.content.clone(),
+                    });
+                }
+            }
+            for (tool_name, tool_warnings) in &warnings {
+                for w in tool_warnings {
+                    let _ = updates.send(Update::Error(format!(
+                        "\u{26a0} Suspicious content in {} output: {}",
+                        tool_name, w
+                    )));
+                }
+            }
+            let _ = updates.send(Update::Reply(follow_up));
+        }
+        Err(e) => {
+            for id in ids {
+                let _ = updates.send(Update::ToolState {
+                    id,
+                    state: ToolState::Failed,
+                });
+            }
+            if !is_cancelled(&e) {
+                let _ = updates.send(Update::Error(format!("Tool execution error: {}", e)));
+            }
+        }
+    }
+
+    agent.auto_save_session()
+}
+
+/// Save the session to memory and start a fresh one, like `/new` in the REPL
+async fn new_session(agent: &mut Agent, updates: &Sender<Update>) -> Result<()> {
+    match agent.save_session_to_memory().await {
+        Ok(Some(path)) => {
+            let _ = updates.send(Update::Notice(format!(
+                "Session saved to: {}",
+                path.display()
+            )));
+        }
+        Ok(None) => {}
+        Err(e) => {
+            let _ = updates.send(Update::Error(format!(
+                "Failed to save session to memory: {}",
+                e
+            )));
+        }
+    }
+    agent.new_session().await?;
+    let _ = updates.send(Update::History(Vec::new()));
+    let _ = updates.send(Update::Notice(
+        "New session started. Memory context reloaded.".to_string(),
+    ));
+    Ok(())
+}
+
+/// Ask the user a yes/no question; no answer counts as no
+async fn ask(updates: &Sender<Update>, prompt: String) -> bool {
+    let (tx, rx) = oneshot::channel();
+    let reply: Reply = Box::new(move |approved| {
+        let _ = tx.send(approved);
+    });
+    if updates.send(Update::Approve { prompt, reply }).is_err() {
+        return false;
+    }
+    rx.await.unwrap_or(false)
+}
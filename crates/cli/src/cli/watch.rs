@@ -0,0 +1,305 @@
+//! CLI subcommand: `localgpt watch`
+//!
+//! Watches files matching `--glob` patterns and, once changes have settled
+//! for the debounce period, runs the prompt as one agent turn with the
+//! changed files (or their `git diff`) attached. Replies are printed and sent
+//! to `[[webhooks]]` subscribed to `watch.finished`. Changes made while a turn
+//! runs, including the agent's own edits, don't trigger another turn.
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use clap_complete::engine::ArgValueCandidates;
+use futures::StreamExt;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use localgpt_core::agent::attachments::{Attachment, attachment_budget, build_message};
+use localgpt_core::agent::tool_filters::InterfaceToolPolicy;
+use localgpt_core::agent::{
+    Agent, AgentConfig, StreamEvent, create_spawn_agent_tool, extract_tool_detail,
+};
+use localgpt_core::concurrency::WorkspaceLock;
+use localgpt_core::config::{Config, parse_duration};
+use localgpt_core::memory::MemoryManager;
+use localgpt_core::webhooks::{self, WebhookEvent};
+
+use super::completions;
+
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Files to watch, relative to --dir (e.g. 'src/**/*.rs'); repeatable
+    #[arg(short, long = "glob", value_name = "PATTERN", required = true)]
+    pub globs: Vec<String>,
+
+    /// What to ask on each change; the changed files are attached
+    #[arg(short, long)]
+    pub prompt: String,
+
+    /// Attach `git diff` of each changed file instead of its full content
+    #[arg(long)]
+    pub diff: bool,
+
+    /// Directory to watch (default: current directory)
+    #[arg(long, default_value = ".")]
+    pub dir: PathBuf,
+
+    /// Quiet period after the last change before running, e.g. "2s"
+    #[arg(long, default_value = "2s")]
+    pub debounce: String,
+
+    /// Model to use (overrides config)
+    #[arg(short, long, add = ArgValueCandidates::new(completions::model_names))]
+    pub model: Option<String>,
+
+    /// Don't offer the model any tools
+    #[arg(long)]
+    pub no_tools: bool,
+}
+
+pub async fn run(args: WatchArgs, agent_id: &str) -> Result<()> {
+    let debounce = parse_duration(&args.debounce).map_err(|e| anyhow::anyhow!(e))?;
+    let dir = std::path::absolute(&args.dir)?;
+    if !dir.is_dir() {
+        bail!("{} is not a directory", dir.display());
+    }
+    let patterns = args
+        .globs
+        .iter()
+        .map(|g| glob::Pattern::new(g).with_context(|| format!("Invalid glob: {}", g)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let config = Config::load()?;
+    let memory = Arc::new(MemoryManager::new_with_full_config(
+        &config.memory,
+        Some(&config),
+        agent_id,
+    )?);
+    let agent_config = AgentConfig {
+        model: args.model.unwrap_or(config.agent.default_model.clone()),
+        context_window: config.agent.context_window,
+        reserve_tokens: config.agent.reserve_tokens,
+    };
+    let mut agent = Agent::new(agent_config, &config, Arc::clone(&memory)).await?;
+    agent.extend_tools(crate::tools::create_cli_tools(&config)?);
+    agent.extend_tools(vec![create_spawn_agent_tool(config.clone(), memory)]);
+    // Nobody is there to approve tools, so those needing approval aren't offered
+    let deny = if args.no_tools {
+        vec!["*".to_string()]
+    } else {
+        agent.approval_required_tools().to_vec()
+    };
+    agent.restrict_tools(InterfaceToolPolicy {
+        allow: Vec::new(),
+        deny,
+    });
+    let workspace_lock = WorkspaceLock::new()?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let mut watcher = RecommendedWatcher::new(
+        move |res: Result<Event, notify::Error>| match res {
+            Ok(event) => {
+                if matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    let _ = tx.send(event.paths);
+                }
+            }
+            Err(e) => tracing::warn!("Watch error: {}", e),
+        },
+        notify::Config::default(),
+    )?;
+    watcher.watch(&dir, RecursiveMode::Recursive)?;
+
+    eprintln!(
+        "Watching {} in {} (Ctrl-C to stop)",
+        args.globs.join(", "),
+        dir.display()
+    );
+
+    let mut pending: BTreeSet<PathBuf> = BTreeSet::new();
+    loop {
+        let paths = if pending.is_empty() {
+            rx.recv().await
+        } else {
+            match tokio::time::timeout(debounce, rx.recv()).await {
+                Ok(paths) => paths,
+                Err(_) => {
+                    let changed: Vec<PathBuf> = std::mem::take(&mut pending).into_iter().collect();
+                    {
+                        let _lock_guard = workspace_lock.acquire()?;
+                        run_turn(&mut agent, &config, &args.prompt, &dir, &changed, args.diff)
+                            .await;
+                    }
+                    // Drop what changed during the turn (often the agent's own edits)
+                    while rx.try_recv().is_ok() {}
+                    continue;
+                }
+            }
+        };
+        let Some(paths) = paths else {
+            break;
+        };
+        for path in paths {
+            if let Ok(relative) = path.strip_prefix(&dir)
+                && patterns.iter().any(|p| p.matches_path(relative))
+            {
+                pending.insert(relative.to_path_buf());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// One turn for a batch of changes: print the reply and send it to webhooks.
+/// Failures are reported and watching continues.
+async fn run_turn(
+    agent: &mut Agent,
+    config: &Config,
+    prompt: &str,
+    dir: &Path,
+    changed: &[PathBuf],
+    diff: bool,
+) {
+    let files: Vec<String> = changed.iter().map(|p| p.display().to_string()).collect();
+    eprintln!(
+        "\n── {} changed: {} ──",
+        chrono::Local::now().format("%H:%M:%S"),
+        files.join(", ")
+    );
+
+    let started = Instant::now();
+    let result = turn(agent, prompt, dir, changed, diff).await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let (status, response, error) = match result {
+        Ok(response) => ("ok", response, None),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ("error", String::new(), Some(e.to_string()))
+        }
+    };
+    webhooks::emit(
+        config,
+        WebhookEvent::WatchFinished {
+            files,
+            status: status.to_string(),
+            duration_ms,
+            response,
+            error,
+        },
+    );
+}
+
+async fn turn(
+    agent: &mut Agent,
+    prompt: &str,
+    dir: &Path,
+    changed: &[PathBuf],
+    diff: bool,
+) -> Result<String> {
+    // Each batch starts fresh so earlier reviews don't fill the context
+    agent.new_session().await?;
+
+    let mut attachments = Vec::new();
+    let mut removed = Vec::new();
+    for relative in changed {
+        let path = dir.join(relative);
+        if !path.exists() {
+            removed.push(relative.display().to_string());
+            continue;
+        }
+        let attachment = if diff {
+            match git_diff(dir, relative) {
+                Some(patch) => Ok(Attachment::Text {
+                    name: format!("{} (git diff)", relative.display()),
+                    content: patch,
+                }),
+                // Untracked or not in a repository: send the whole file
+                None => Attachment::load(&path),
+            }
+        } else {
+            Attachment::load(&path)
+        };
+        match attachment {
+            Ok(Attachment::Text { content, .. }) if !diff => attachments.push(Attachment::Text {
+                name: relative.display().to_string(),
+                content,
+            }),
+            Ok(attachment) => attachments.push(attachment),
+            Err(e) => eprintln!("Skipping {}: {}", relative.display(), e),
+        }
+    }
+
+    let mut input = format!(
+        "{}\n\nChanged files: {}",
+        prompt,
+        changed
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if !removed.is_empty() {
+        input.push_str(&format!("\nDeleted: {}", removed.join(", ")));
+    }
+
+    let (used, usable, _) = agent.context_usage();
+    let message = build_message(&input, attachments, attachment_budget(used, usable));
+    for notice in &message.truncated {
+        eprintln!("Truncated attachment {}", notice);
+    }
+
+    let mut response = String::new();
+    let mut stdout = io::stdout();
+    let event_stream = agent
+        .chat_stream_with_images(&message.text, message.images)
+        .await?;
+    let mut pinned_stream = std::pin::pin!(event_stream);
+    while let Some(event) = pinned_stream.next().await {
+        match event? {
+            StreamEvent::Content(content) => {
+                print!("{}", content);
+                stdout.flush()?;
+                response.push_str(&content);
+            }
+            StreamEvent::ToolCallStart {
+                name, arguments, ..
+            } => match extract_tool_detail(&name, &arguments) {
+                Some(detail) => eprintln!("> Running tool: {} ({})", name, detail),
+                None => eprintln!("> Running tool: {}", name),
+            },
+            StreamEvent::ToolCallEnd { warnings, .. } => {
+                for warning in &warnings {
+                    eprintln!("  \u{26a0} Warning: {}", warning);
+                }
+            }
+            StreamEvent::Done => {}
+        }
+    }
+    if !response.ends_with('\n') {
+        println!();
+    }
+    Ok(response)
+}
+
+/// `git diff HEAD` for one file, or None if git has nothing to say about it
+fn git_diff(dir: &Path, relative: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["diff", "HEAD", "--"])
+        .arg(relative)
+        .output()
+        .ok()?;
+    let patch = String::from_utf8(output.stdout).ok()?;
+    (output.status.success() && !patch.trim().is_empty()).then_some(patch)
+}
@@ -2,10 +2,19 @@
 
 use eframe::egui;
 
-use super::state::{Panel, UiState};
+use super::remote::DaemonClient;
+use super::state::{Panel, UiState, WorkerMessage};
 use super::views::{ChatView, SessionsView, StatusView, chat::show_toolbar};
 use super::worker::WorkerHandle;
 
+/// Where the app's agent runs
+pub enum Backend {
+    /// In-process, for the given agent id
+    Embedded(Option<String>),
+    /// In a running daemon
+    Daemon(DaemonClient),
+}
+
 /// The main desktop application
 pub struct DesktopApp {
     state: UiState,
@@ -14,12 +23,16 @@ pub struct DesktopApp {
 
 impl DesktopApp {
     /// Create a new desktop app
-    pub fn new(cc: &eframe::CreationContext<'_>, agent_id: Option<String>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, backend: Backend) -> Self {
         // Configure fonts and visuals
         Self::configure_style(&cc.egui_ctx);
 
         // Start the background worker
-        let worker = WorkerHandle::start(agent_id).expect("Failed to start worker");
+        let worker = match backend {
+            Backend::Embedded(agent_id) => WorkerHandle::start(agent_id),
+            Backend::Daemon(client) => WorkerHandle::start_remote(client),
+        }
+        .expect("Failed to start worker");
 
         Self {
             state: UiState::new(),
@@ -55,8 +68,17 @@ impl DesktopApp {
     }
 
     /// Process all pending worker messages
-    fn process_worker_messages(&mut self) {
+    fn process_worker_messages(&mut self, ctx: &egui::Context) {
         while let Some(msg) = self.worker.try_recv() {
+            // Flag the window when a reply finishes while it's in the background
+            if matches!(msg, WorkerMessage::Done | WorkerMessage::Error(_))
+                && self.state.is_loading
+                && ctx.input(|i| i.viewport().focused) == Some(false)
+            {
+                ctx.send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(
+                    egui::UserAttentionType::Informational,
+                ));
+            }
             self.state.handle_worker_message(msg);
         }
     }
@@ -65,7 +87,7 @@ impl DesktopApp {
 impl eframe::App for DesktopApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Process worker messages
-        self.process_worker_messages();
+        self.process_worker_messages(ctx);
 
         // Request repaint while loading or streaming
         if self.state.is_loading || !self.state.streaming_content.is_empty() {
@@ -2,11 +2,14 @@
 //!
 //! This module provides a native desktop application that embeds the LocalGPT agent
 //! directly - no HTTP, no daemon needed. The agent runs in a background thread
-//! and communicates with the UI via channels.
+//! and communicates with the UI via channels. With `--daemon` the background
+//! thread talks to a running daemon over HTTP instead.
 
 mod app;
+mod remote;
 mod state;
 mod views;
 mod worker;
 
-pub use app::DesktopApp;
+pub use app::{Backend, DesktopApp};
+pub use remote::DaemonClient;
@@ -0,0 +1,343 @@
+//! Worker that drives a running daemon over its HTTP API
+//!
+//! Used by `localgpt desktop --daemon`: instead of embedding an agent, the
+//! window chats through the daemon's sessions, so it shares the daemon's
+//! memory, scheduler, and configured tools. Replies stream over the
+//! `/api/chat/stream` SSE endpoint.
+
+use std::sync::mpsc::{Receiver, Sender};
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{NaiveDateTime, Utc};
+use futures::StreamExt;
+use reqwest::{Client, RequestBuilder, Response};
+use serde_json::{Value, json};
+
+use localgpt_core::agent::{SessionInfo, SessionStatus};
+
+use super::state::{UiMessage, WorkerMessage};
+use super::worker::HELP_TEXT;
+
+/// HTTP client for one daemon
+pub struct DaemonClient {
+    client: Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl DaemonClient {
+    pub fn new(base_url: &str, token: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn get(&self, path: &str) -> RequestBuilder {
+        self.authorize(self.client.get(format!("{}{}", self.base_url, path)))
+    }
+
+    fn post(&self, path: &str) -> RequestBuilder {
+        self.authorize(self.client.post(format!("{}{}", self.base_url, path)))
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn json(request: RequestBuilder) -> Result<Value> {
+        let response = check(request.send().await?).await?;
+        Ok(response.json().await?)
+    }
+}
+
+/// Turn a non-success response into an error carrying the daemon's message
+async fn check(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    bail!("Daemon returned {}: {}", status, body.trim())
+}
+
+pub(super) async fn remote_worker_loop(
+    client: DaemonClient,
+    rx: Receiver<UiMessage>,
+    tx: Sender<WorkerMessage>,
+) -> Result<()> {
+    let status = DaemonClient::json(client.get("/api/status"))
+        .await
+        .map_err(|e| anyhow!("Cannot reach the daemon at {}: {}", client.base_url(), e))?;
+    let _ = tx.send(WorkerMessage::Ready {
+        model: status["model"].as_str().unwrap_or_default().to_string(),
+        memory_chunks: status["memory_chunks"].as_u64().unwrap_or(0) as usize,
+        has_embeddings: false,
+    });
+
+    let mut session_id = new_session(&client, &tx).await?;
+    refresh_sessions(&client, &tx).await;
+    if status["is_brand_new"].as_bool() == Some(true) {
+        let _ = tx.send(WorkerMessage::SystemMessage(
+            localgpt_core::agent::FIRST_RUN_WELCOME.to_string(),
+        ));
+    }
+
+    while let Ok(msg) = rx.recv() {
+        let result = match msg {
+            UiMessage::Chat(message) => chat(&client, &mut session_id, &message, &tx).await,
+            UiMessage::NewSession => new_session(&client, &tx).await.map(|id| {
+                session_id = id;
+            }),
+            UiMessage::ResumeSession(id) => resume_session(&client, &id, &tx).await.map(|id| {
+                session_id = id;
+            }),
+            // The daemon's SSE stream runs tools without asking; approvals
+            // only happen over its WebSocket
+            UiMessage::ApproveTools(_) | UiMessage::DenyTools => {
+                let _ = tx.send(WorkerMessage::Done);
+                Ok(())
+            }
+            UiMessage::RefreshSessions => {
+                refresh_sessions(&client, &tx).await;
+                Ok(())
+            }
+            UiMessage::RefreshStatus => session_status(&client, &session_id)
+                .await
+                .map(|status| {
+                    let _ = tx.send(WorkerMessage::Status(status));
+                }),
+            UiMessage::SetModel(model) => {
+                let path = format!("/api/sessions/{}/model", session_id);
+                DaemonClient::json(client.post(&path).json(&json!({ "model": model })))
+                    .await
+                    .map(|_| {
+                        let _ = tx.send(WorkerMessage::SystemMessage(format!(
+                            "Model set to: {}",
+                            model
+                        )));
+                    })
+            }
+            UiMessage::Compact => {
+                let path = format!("/api/sessions/{}/compact", session_id);
+                DaemonClient::json(client.post(&path)).await.map(|reply| {
+                    let _ = tx.send(WorkerMessage::SystemMessage(format!(
+                        "Session compacted: {} -> {} tokens",
+                        reply["token_count_before"], reply["token_count_after"]
+                    )));
+                })
+            }
+            UiMessage::SearchMemory(query) => {
+                let request = client
+                    .get("/api/memory/search")
+                    .query(&[("q", query.as_str())]);
+                DaemonClient::json(request).await.map(|reply| {
+                    let _ = tx.send(WorkerMessage::SystemMessage(format_search(&query, &reply)));
+                })
+            }
+            UiMessage::Save => {
+                let _ = tx.send(WorkerMessage::SystemMessage(
+                    "The daemon saves sessions as they go.".to_string(),
+                ));
+                Ok(())
+            }
+            UiMessage::ShowHelp => {
+                let _ = tx.send(WorkerMessage::SystemMessage(HELP_TEXT.to_string()));
+                Ok(())
+            }
+            UiMessage::ShowStatus => {
+                session_status(&client, &session_id)
+                    .await
+                    .map(|status| {
+                        let text = format!(
+                            "Daemon: {}\nSession: {}\nMessages: {}\nTokens: {} context / {} API in / {} API out",
+                            client.base_url(),
+                            &status.id[..8.min(status.id.len())],
+                            status.message_count,
+                            status.token_count,
+                            status.api_input_tokens,
+                            status.api_output_tokens,
+                        );
+                        let _ = tx.send(WorkerMessage::SystemMessage(text));
+                        let _ = tx.send(WorkerMessage::Status(status));
+                    })
+            }
+        };
+
+        if let Err(e) = result {
+            let _ = tx.send(WorkerMessage::Error(e.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+async fn new_session(client: &DaemonClient, tx: &Sender<WorkerMessage>) -> Result<String> {
+    let reply = DaemonClient::json(client.post("/api/sessions").json(&json!({}))).await?;
+    let id = reply["session_id"]
+        .as_str()
+        .ok_or_else(|| anyhow!("Daemon did not return a session id"))?
+        .to_string();
+    let _ = tx.send(WorkerMessage::SessionChanged {
+        id: id.clone(),
+        message_count: 0,
+    });
+    Ok(id)
+}
+
+async fn resume_session(
+    client: &DaemonClient,
+    saved_id: &str,
+    tx: &Sender<WorkerMessage>,
+) -> Result<String> {
+    let path = format!("/api/sessions/{}/resume", saved_id);
+    let reply = DaemonClient::json(client.post(&path)).await?;
+    let id = reply["session_id"].as_str().unwrap_or(saved_id).to_string();
+    let status = session_status(client, &id).await?;
+    let _ = tx.send(WorkerMessage::SessionChanged {
+        id: id.clone(),
+        message_count: status.message_count,
+    });
+    let _ = tx.send(WorkerMessage::Status(status));
+    Ok(id)
+}
+
+async fn session_status(client: &DaemonClient, session_id: &str) -> Result<SessionStatus> {
+    let reply = DaemonClient::json(client.get(&format!("/api/sessions/{}", session_id))).await?;
+    let count = |key: &str| reply[key].as_u64().unwrap_or(0);
+    Ok(SessionStatus {
+        id: session_id.to_string(),
+        message_count: count("message_count") as usize,
+        token_count: count("token_count") as usize,
+        compaction_count: 0,
+        api_input_tokens: count("api_input_tokens"),
+        api_output_tokens: count("api_output_tokens"),
+        search_queries: count("search_queries"),
+        search_cached_hits: count("search_cached_hits"),
+        search_cost_usd: reply["search_cost_usd"].as_f64().unwrap_or(0.0),
+    })
+}
+
+async fn refresh_sessions(client: &DaemonClient, tx: &Sender<WorkerMessage>) {
+    let reply = match DaemonClient::json(client.get("/api/saved-sessions")).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            let _ = tx.send(WorkerMessage::Error(e.to_string()));
+            return;
+        }
+    };
+    let sessions = reply["sessions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| {
+            Some(SessionInfo {
+                id: s["id"].as_str()?.to_string(),
+                created_at: s["created_at"]
+                    .as_str()
+                    .and_then(|t| NaiveDateTime::parse_from_str(t, "%Y-%m-%dT%H:%M:%S").ok())
+                    .map(|t| t.and_utc())
+                    .unwrap_or_else(Utc::now),
+                message_count: s["message_count"].as_u64().unwrap_or(0) as usize,
+                file_size: 0,
+                title: s["title"].as_str().map(str::to_string),
+            })
+        })
+        .collect();
+    let _ = tx.send(WorkerMessage::Sessions(sessions));
+}
+
+/// Run a turn through the daemon's SSE stream, forwarding its events
+async fn chat(
+    client: &DaemonClient,
+    session_id: &mut String,
+    message: &str,
+    tx: &Sender<WorkerMessage>,
+) -> Result<()> {
+    let request = client
+        .post("/api/chat/stream")
+        .json(&json!({ "message": message, "session_id": session_id }));
+    let response = check(request.send().await?).await?;
+
+    let mut body = response.bytes_stream();
+    // Bytes, not text, so a character split across chunks stays intact
+    let mut buffer: Vec<u8> = Vec::new();
+    while let Some(bytes) = body.next().await {
+        buffer.extend_from_slice(&bytes?);
+        while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim_end().strip_prefix("data: ") else {
+                continue;
+            };
+            let Ok(event) = serde_json::from_str::<Value>(data) else {
+                continue;
+            };
+            let text = |key: &str| event[key].as_str().unwrap_or_default().to_string();
+
+            let msg = match event["type"].as_str() {
+                Some("session") => {
+                    *session_id = text("session_id");
+                    continue;
+                }
+                Some("content") => WorkerMessage::ContentChunk(text("delta")),
+                Some("tool_start") => WorkerMessage::ToolCallStart {
+                    name: text("name"),
+                    id: text("id"),
+                    detail: event["detail"].as_str().map(str::to_string),
+                },
+                Some("tool_end") => WorkerMessage::ToolCallEnd {
+                    name: text("name"),
+                    id: text("id"),
+                    output: text("output"),
+                    warnings: serde_json::from_value(event["warnings"].clone()).unwrap_or_default(),
+                },
+                Some("done") => WorkerMessage::Done,
+                _ => match event["error"].as_str() {
+                    Some(error) => bail!("{}", error),
+                    None => continue,
+                },
+            };
+            let _ = tx.send(msg);
+        }
+    }
+    Ok(())
+}
+
+fn format_search(query: &str, reply: &Value) -> String {
+    let results = reply["results"].as_array().cloned().unwrap_or_default();
+    if results.is_empty() {
+        return "No memory results found.".to_string();
+    }
+    let text = results
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let preview: String = chunk["content"]
+                .as_str()
+                .unwrap_or_default()
+                .chars()
+                .take(150)
+                .collect();
+            format!(
+                "{}. {} (lines {}-{}, score: {:.3})\n   {}",
+                i + 1,
+                chunk["file"].as_str().unwrap_or_default(),
+                chunk["line_start"],
+                chunk["line_end"],
+                chunk["score"].as_f64().unwrap_or(0.0),
+                preview.replace('\n', " "),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!("Memory search results for \"{}\":\n{}", query, text)
+}
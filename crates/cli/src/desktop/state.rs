@@ -231,6 +231,7 @@ impl UiState {
                     message_count,
                     created_at: chrono::Utc::now(),
                     file_size: 0,
+                    title: None,
                 });
                 // Clear chat on session change
                 self.messages.clear();
@@ -18,8 +18,21 @@ use localgpt_core::agent::{
 use localgpt_core::config::Config;
 use localgpt_core::memory::MemoryManager;
 
+use super::remote::{DaemonClient, remote_worker_loop};
 use super::state::{UiMessage, WorkerMessage};
 
+pub(super) const HELP_TEXT: &str = "\
+Available commands:
+  /new              Start a new session
+  /model [name]     Show or set the current model
+  /compact          Compact session history
+  /memory <query>   Search memory files
+  /save             Save current session to disk
+  /status           Show session status
+  /sessions         Show saved sessions
+  /resume <id>      Resume a session by ID
+  /help             Show this help text";
+
 /// Handle to the background worker
 pub struct WorkerHandle {
     /// Send commands to the worker
@@ -31,13 +44,27 @@ pub struct WorkerHandle {
 }
 
 impl WorkerHandle {
-    /// Start the background worker
+    /// Start the background worker with an embedded agent
     pub fn start(agent_id: Option<String>) -> Result<Self> {
+        let agent_id = agent_id.unwrap_or_else(|| DEFAULT_AGENT_ID.to_string());
+        Ok(Self::spawn(move |rx, tx| worker_loop(agent_id, rx, tx)))
+    }
+
+    /// Start the background worker against a running daemon
+    pub fn start_remote(client: DaemonClient) -> Result<Self> {
+        Ok(Self::spawn(move |rx, tx| {
+            remote_worker_loop(client, rx, tx)
+        }))
+    }
+
+    fn spawn<F, Fut>(run: F) -> Self
+    where
+        F: FnOnce(Receiver<UiMessage>, Sender<WorkerMessage>) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<()>>,
+    {
         let (ui_tx, ui_rx) = mpsc::channel::<UiMessage>();
         let (worker_tx, worker_rx) = mpsc::channel::<WorkerMessage>();
 
-        let agent_id = agent_id.unwrap_or_else(|| DEFAULT_AGENT_ID.to_string());
-
         let thread = thread::spawn(move || {
             // Create tokio runtime for this thread
             let rt = tokio::runtime::Builder::new_current_thread()
@@ -45,18 +72,20 @@ impl WorkerHandle {
                 .build()
                 .expect("Failed to create tokio runtime");
 
+            let error_tx = worker_tx.clone();
             rt.block_on(async {
-                if let Err(e) = worker_loop(agent_id, ui_rx, worker_tx).await {
+                if let Err(e) = run(ui_rx, worker_tx).await {
                     eprintln!("Worker error: {}", e);
+                    let _ = error_tx.send(WorkerMessage::Error(e.to_string()));
                 }
             });
         });
 
-        Ok(Self {
+        Self {
             tx: ui_tx,
             rx: worker_rx,
             _thread: thread,
-        })
+        }
     }
 
     /// Send a message to the worker
@@ -91,6 +120,7 @@ async fn worker_loop(
     };
 
     let mut agent = Agent::new(agent_config, &config, Arc::clone(&memory)).await?;
+    agent.set_channel("desktop");
     agent.extend_tools(crate::tools::create_cli_tools(&config)?);
     agent.extend_tools(vec![create_spawn_agent_tool(config.clone(), memory)]);
     agent.new_session().await?;
@@ -319,18 +349,7 @@ async fn worker_loop(
                 }
             },
             UiMessage::ShowHelp => {
-                let help_text = "\
-Available commands:
-  /new              Start a new session
-  /model [name]     Show or set the current model
-  /compact          Compact session history
-  /memory <query>   Search memory files
-  /save             Save current session to disk
-  /status           Show session status
-  /sessions         Show saved sessions
-  /resume <id>      Resume a session by ID
-  /help             Show this help text";
-                let _ = tx.send(WorkerMessage::SystemMessage(help_text.to_string()));
+                let _ = tx.send(WorkerMessage::SystemMessage(HELP_TEXT.to_string()));
             }
             UiMessage::ShowStatus => {
                 let status = agent.session_status();
@@ -1,12 +1,15 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 
 mod cli;
 #[cfg(feature = "desktop")]
 mod desktop;
+#[cfg(feature = "wasm-plugins")]
+mod plugins;
 mod tools;
 
 use cli::{Cli, Commands};
+use localgpt_core::security::RedactingWriter;
 
 fn main() -> Result<()> {
     // argv[0] dispatch: if re-exec'd as "localgpt-sandbox", enter sandbox child path
@@ -18,11 +21,17 @@ fn main() -> Result<()> {
         localgpt_sandbox::sandbox_child_main();
     }
 
+    // With COMPLETE set, print completions for the shell and exit
+    clap_complete::CompleteEnv::with_factory(Cli::command)
+        .var(cli::completions::COMPLETE_VAR)
+        .complete();
+
     let cli = Cli::parse();
 
     // Set LOCALGPT_PROFILE env var if --profile was provided
     // This must be done early before any paths are resolved
     if let Some(ref profile) = cli.profile {
+        let profile = localgpt_core::paths::normalize_profile(profile)?.unwrap_or_default();
         // SAFETY: Setting env var early before any multi-threaded code runs
         #[allow(unsafe_code)]
         unsafe {
@@ -41,7 +50,7 @@ fn main() -> Result<()> {
                 tracing_subscriber::EnvFilter::try_from_default_env()
                     .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(log_level)),
             )
-            .with_writer(std::io::stderr)
+            .with_writer(|| RedactingWriter::new(std::io::stderr()))
             .init();
         return crate::cli::gen3d::run(args, &cli.agent);
     }
@@ -77,6 +86,9 @@ async fn async_main(cli: Cli) -> Result<()> {
         "debug"
     } else if matches!(&cli.command, Commands::Daemon(_)) {
         "info"
+    } else if matches!(&cli.command, Commands::Chat(args) if args.tui) {
+        // Log lines would be drawn over the full-screen UI
+        "off"
     } else {
         "warn"
     };
@@ -85,26 +97,39 @@ async fn async_main(cli: Cli) -> Result<()> {
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(log_level)),
         )
-        .with_writer(std::io::stderr)
+        .with_writer(|| RedactingWriter::new(std::io::stderr()))
         .init();
 
     match cli.command {
         Commands::Chat(args) => crate::cli::chat::run(args, &cli.agent).await,
         Commands::Ask(args) => crate::cli::ask::run(args, &cli.agent).await,
+        Commands::Run(args) => crate::cli::run::run(args, &cli.agent).await,
+        Commands::Watch(args) => crate::cli::watch::run(args, &cli.agent).await,
         #[cfg(feature = "desktop")]
         Commands::Desktop(args) => crate::cli::desktop::run(args, &cli.agent),
         #[cfg(feature = "gen")]
         Commands::Gen(_) => unreachable!("Gen is handled before tokio runtime starts"),
         Commands::Daemon(args) => crate::cli::daemon::run(args, &cli.agent).await,
+        Commands::Status(args) => crate::cli::status::run(args).await,
+        Commands::Cron(args) => crate::cli::cron::run(args).await,
+        Commands::Sessions(args) => crate::cli::sessions::run(args, &cli.agent).await,
         Commands::Memory(args) => crate::cli::memory::run(args, &cli.agent).await,
         Commands::Config(args) => crate::cli::config::run(args).await,
         Commands::Paths => crate::cli::paths::run(),
         Commands::Md(args) => crate::cli::md::run(args).await,
+        Commands::Audit(args) => crate::cli::audit::run(args).await,
+        Commands::Mcp(args) => crate::cli::mcp::run(args).await,
         Commands::Sandbox(args) => crate::cli::sandbox::run(args).await,
         Commands::Search(args) => crate::cli::search::run(args).await,
+        Commands::Skills(args) => crate::cli::skills::run(args).await,
         Commands::Auth(args) => crate::cli::auth::run(args).await,
-        Commands::Init(args) => crate::cli::init::run(args),
+        Commands::ApiKey(args) => crate::cli::apikey::run(args).await,
+        Commands::Keyring(args) => crate::cli::keyring::run(args).await,
+        Commands::Secret(args) => crate::cli::secret::run(args).await,
+        Commands::Init(args) => crate::cli::init::run(args).await,
         Commands::Bridge(args) => crate::cli::bridge::run(args).await,
         Commands::Doctor(args) => crate::cli::doctor::run(args).await,
+        Commands::Debug(args) => crate::cli::debug::run(args, &cli.agent).await,
+        Commands::Completions(args) => crate::cli::completions::run(args),
     }
 }
@@ -0,0 +1,337 @@
+//! WASM plugin tools.
+//!
+//! Loads WASM components implementing the `tool-plugin` world
+//! (`wit/tool-plugin.wit`) and exposes each one as a [`Tool`]. Plugins run
+//! in wasmtime with no ambient authority: network and filesystem access go
+//! through host functions that are gated by the capabilities granted in
+//! `[[tools.plugins]]`. Every call gets a fresh instance and a fuel budget.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{debug, warn};
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Engine, Store};
+
+use localgpt_core::agent::path_utils::{check_path_allowed, resolve_real_path};
+use localgpt_core::agent::providers::ToolSchema;
+use localgpt_core::agent::tools::{Tool, validate_web_fetch_url};
+use localgpt_core::config::{Config, PluginCapability, WasmPluginConfig};
+use localgpt_core::security;
+
+wasmtime::component::bindgen!({
+    path: "wit/tool-plugin.wit",
+    world: "tool-plugin",
+});
+
+use self::localgpt::plugin::host;
+
+/// Fuel budget for a single plugin call (bounds runaway plugins).
+const PLUGIN_FUEL: u64 = 10_000_000_000;
+
+/// Maximum response body returned by the `http-get` host function.
+const MAX_HTTP_BYTES: usize = 1024 * 1024;
+
+/// Append `chunk` to `buf` without growing it past `max` bytes. Returns
+/// whether `buf` is full.
+fn append_capped(buf: &mut Vec<u8>, chunk: &[u8], max: usize) -> bool {
+    let room = max.saturating_sub(buf.len());
+    buf.extend_from_slice(&chunk[..chunk.len().min(room)]);
+    buf.len() >= max
+}
+
+/// Decode a capped body, dropping a trailing character split by the cap.
+fn capped_text(mut bytes: Vec<u8>) -> String {
+    if let Err(e) = std::str::from_utf8(&bytes) {
+        if e.error_len().is_none() {
+            bytes.truncate(e.valid_up_to());
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Load all plugins declared in `[[tools.plugins]]`.
+///
+/// Plugins that fail to load are skipped with a warning.
+pub fn create_plugin_tools(config: &Config) -> Vec<Box<dyn Tool>> {
+    if config.tools.plugins.is_empty() {
+        return Vec::new();
+    }
+
+    let engine = match create_engine() {
+        Ok(engine) => engine,
+        Err(e) => {
+            warn!("WASM plugin engine init failed: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+    for plugin in &config.tools.plugins {
+        match WasmPluginTool::load(&engine, plugin, config) {
+            Ok(tool) => {
+                debug!(
+                    "Loaded WASM plugin tool '{}' from {}",
+                    tool.name(),
+                    plugin.path
+                );
+                tools.push(Box::new(tool));
+            }
+            Err(e) => warn!("Failed to load WASM plugin {}: {:#}", plugin.path, e),
+        }
+    }
+    tools
+}
+
+fn create_engine() -> Result<Engine> {
+    let mut engine_config = wasmtime::Config::new();
+    engine_config.wasm_component_model(true);
+    engine_config.consume_fuel(true);
+    Engine::new(&engine_config)
+}
+
+/// Per-instance host state: what the plugin is allowed to touch.
+struct HostState {
+    plugin: String,
+    capabilities: Vec<PluginCapability>,
+    workspace: PathBuf,
+    state_dir: PathBuf,
+    runtime: tokio::runtime::Handle,
+}
+
+impl HostState {
+    fn require(&self, capability: PluginCapability) -> Result<(), String> {
+        if self.capabilities.contains(&capability) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Plugin '{}' lacks the {:?} capability",
+                self.plugin, capability
+            ))
+        }
+    }
+
+    /// Resolve a workspace-relative path, rejecting anything that escapes the workspace.
+    fn resolve_workspace_path(&self, path: &str) -> Result<PathBuf, String> {
+        let workspace = std::fs::canonicalize(&self.workspace).map_err(|e| e.to_string())?;
+        let joined = workspace.join(path);
+        let real = resolve_real_path(&joined.to_string_lossy()).map_err(|e| e.to_string())?;
+        check_path_allowed(&real, &[workspace]).map_err(|e| e.to_string())?;
+        Ok(real)
+    }
+}
+
+impl host::Host for HostState {
+    fn http_get(&mut self, url: String) -> Result<String, String> {
+        self.require(PluginCapability::Http)?;
+        self.runtime.block_on(async {
+            let parsed = validate_web_fetch_url(&url)
+                .await
+                .map_err(|e| e.to_string())?;
+            let client = reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .map_err(|e| e.to_string())?;
+            let mut response = client
+                .get(parsed)
+                .header("User-Agent", "LocalGPT-Plugin/0.1")
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+
+            // Stop reading at the cap rather than buffering the whole body
+            let mut body = Vec::new();
+            while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+                if append_capped(&mut body, &chunk, MAX_HTTP_BYTES) {
+                    break;
+                }
+            }
+            Ok(capped_text(body))
+        })
+    }
+
+    fn read_file(&mut self, path: String) -> Result<String, String> {
+        self.require(PluginCapability::Fs)?;
+        let real = self.resolve_workspace_path(&path)?;
+        std::fs::read_to_string(&real).map_err(|e| e.to_string())
+    }
+
+    fn write_file(&mut self, path: String, contents: String) -> Result<(), String> {
+        self.require(PluginCapability::Fs)?;
+        let real = self.resolve_workspace_path(&path)?;
+        if security::is_path_protected(&real.to_string_lossy(), &self.workspace, &self.state_dir) {
+            let detail = format!("Plugin attempted write to {}", real.display());
+            let _ = security::append_audit_entry_with_detail(
+                &self.state_dir,
+                security::AuditAction::WriteBlocked,
+                "",
+                &format!("plugin:{}", self.plugin),
+                Some(&detail),
+            );
+            return Err(format!("Cannot write protected file: {}", path));
+        }
+        if let Some(parent) = real.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&real, contents).map_err(|e| e.to_string())
+    }
+
+    fn log(&mut self, message: String) {
+        debug!("[plugin:{}] {}", self.plugin, message);
+    }
+}
+
+/// Compiled plugin shared by all calls to the tool.
+struct PluginModule {
+    engine: Engine,
+    component: Component,
+    linker: Linker<HostState>,
+    path: String,
+    capabilities: Vec<PluginCapability>,
+    workspace: PathBuf,
+    state_dir: PathBuf,
+}
+
+impl PluginModule {
+    fn instantiate(
+        &self,
+        runtime: tokio::runtime::Handle,
+    ) -> Result<(Store<HostState>, ToolPlugin)> {
+        let state = HostState {
+            plugin: self.path.clone(),
+            capabilities: self.capabilities.clone(),
+            workspace: self.workspace.clone(),
+            state_dir: self.state_dir.clone(),
+            runtime,
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.set_fuel(PLUGIN_FUEL)?;
+        let plugin = ToolPlugin::instantiate(&mut store, &self.component, &self.linker)?;
+        Ok((store, plugin))
+    }
+}
+
+// WASM Plugin Tool
+pub struct WasmPluginTool {
+    module: Arc<PluginModule>,
+    schema: ToolSchema,
+}
+
+impl WasmPluginTool {
+    fn load(engine: &Engine, plugin: &WasmPluginConfig, config: &Config) -> Result<Self> {
+        let path = shellexpand::tilde(&plugin.path).to_string();
+        let component = Component::from_file(engine, &path)
+            .with_context(|| format!("Failed to compile {}", path))?;
+
+        let mut linker = Linker::new(engine);
+        ToolPlugin::add_to_linker(&mut linker, |state: &mut HostState| state)?;
+
+        let module = PluginModule {
+            engine: engine.clone(),
+            component,
+            linker,
+            path,
+            capabilities: plugin.capabilities.clone(),
+            workspace: config.workspace_path(),
+            state_dir: config.paths.state_dir.clone(),
+        };
+
+        // Read the tool metadata once from a throwaway instance
+        let runtime = tokio::runtime::Handle::try_current()
+            .context("WASM plugins must be loaded inside a tokio runtime")?;
+        let (mut store, instance) = module.instantiate(runtime)?;
+        let name = instance.call_name(&mut store)?;
+        let description = instance.call_description(&mut store)?;
+        let parameters = serde_json::from_str(&instance.call_parameters(&mut store)?)
+            .context("Plugin returned invalid JSON schema")?;
+
+        Ok(Self {
+            module: Arc::new(module),
+            schema: ToolSchema {
+                name,
+                description,
+                parameters,
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for WasmPluginTool {
+    fn name(&self) -> &str {
+        &self.schema.name
+    }
+
+    fn schema(&self) -> ToolSchema {
+        self.schema.clone()
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let module = Arc::clone(&self.module);
+        let arguments = arguments.to_string();
+        let runtime = tokio::runtime::Handle::current();
+
+        // Host functions block on the runtime, so run the instance off the async workers
+        tokio::task::spawn_blocking(move || {
+            let (mut store, instance) = module.instantiate(runtime)?;
+            instance
+                .call_execute(&mut store, &arguments)?
+                .map_err(|e| anyhow::anyhow!(e))
+        })
+        .await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_config_parses() {
+        let config: Config = toml::from_str(
+            r#"
+[[tools.plugins]]
+path = "~/plugins/weather.wasm"
+capabilities = ["http"]
+
+[[tools.plugins]]
+path = "/opt/plugins/notes.wasm"
+"#,
+        )
+        .unwrap();
+        let plugins = &config.tools.plugins;
+        assert_eq!(plugins.len(), 2);
+        assert_eq!(plugins[0].path, "~/plugins/weather.wasm");
+        assert_eq!(plugins[0].capabilities, vec![PluginCapability::Http]);
+        assert!(plugins[1].capabilities.is_empty());
+
+        let unknown = toml::from_str::<Config>(
+            r#"
+[[tools.plugins]]
+path = "x.wasm"
+capabilities = ["exec"]
+"#,
+        );
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn http_body_is_capped() {
+        let mut body = Vec::new();
+        assert!(!append_capped(&mut body, b"hello ", 10));
+        assert!(append_capped(&mut body, b"world, again", 10));
+        assert_eq!(body, b"hello worl");
+        assert!(append_capped(&mut body, b"more", 10));
+        assert_eq!(body.len(), 10);
+    }
+
+    #[test]
+    fn capped_text_drops_split_character() {
+        let mut body = Vec::new();
+        append_capped(&mut body, "ab\u{e9}".as_bytes(), 3);
+        assert_eq!(capped_text(body), "ab");
+        assert_eq!(capped_text("caf\u{e9}".as_bytes().to_vec()), "caf\u{e9}");
+        assert_eq!(capped_text(vec![b'a', 0xff, b'b']), "a\u{fffd}b");
+    }
+}
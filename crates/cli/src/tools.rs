@@ -1,4 +1,6 @@
-//! CLI-only tools: bash, read_file, write_file, edit_file.
+//! CLI-only tools: bash, read_file, write_file, edit_file, and user-defined
+//! command tools from `[[tools.commands]]` (plus WASM plugins from
+//! `[[tools.plugins]]` when built with the `wasm-plugins` feature).
 //!
 //! These tools are not included in `localgpt-core` because they have
 //! platform-specific dependencies (sandbox) and security implications
@@ -8,7 +10,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::{Value, json};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::debug;
 
 use localgpt_core::agent::hardcoded_filters;
@@ -16,8 +18,9 @@ use localgpt_core::agent::path_utils::{check_path_allowed, resolve_real_path};
 use localgpt_core::agent::providers::ToolSchema;
 use localgpt_core::agent::tool_filters::CompiledToolFilter;
 use localgpt_core::agent::tools::Tool;
-use localgpt_core::config::Config;
-use localgpt_core::security;
+use localgpt_core::agent::tools::command::render_command_template;
+use localgpt_core::config::{CommandToolConfig, CommandToolSandbox, Config};
+use localgpt_core::security::{self, WriteDecision};
 use localgpt_sandbox::{self, SandboxPolicy};
 
 /// Compile a tool filter from config (if present), then merge hardcoded defaults.
@@ -36,6 +39,35 @@ fn compile_filter_for(
     base.merge_hardcoded(hardcoded_subs, hardcoded_pats)
 }
 
+/// Error for a protected write the user denied or hasn't answered yet.
+fn approval_error(decision: &WriteDecision, path: &Path) -> anyhow::Error {
+    match decision {
+        WriteDecision::Pending(id) => anyhow::anyhow!(
+            "Write to protected file {} is waiting for the user's approval (request {}). \
+                 Retry the same write once it is approved.",
+            path.display(),
+            id
+        ),
+        _ => anyhow::anyhow!(
+            "Write to protected file {} was denied by the user.",
+            path.display()
+        ),
+    }
+}
+
+/// Appended to the result of an approved write to a protected file.
+fn approved_write_note(path: &Path) -> &'static str {
+    if path
+        .file_name()
+        .is_some_and(|name| name == security::POLICY_FILENAME)
+    {
+        " The user approved this write. The policy must be re-signed with \
+         `localgpt md sign` before it takes effect."
+    } else {
+        " The user approved this write."
+    }
+}
+
 /// Canonicalize configured allowed_directories into absolute paths.
 fn resolve_allowed_directories(config: &Config) -> Vec<PathBuf> {
     config
@@ -98,9 +130,10 @@ pub fn create_cli_tools(config: &Config) -> Result<Vec<Box<dyn Tool>>> {
     let allowed_dirs = resolve_allowed_directories(config);
     let strict_policy = config.security.strict_policy;
 
-    Ok(vec![
+    let mut tools: Vec<Box<dyn Tool>> = vec![
         Box::new(BashTool::new(
             config.tools.bash_timeout_ms,
+            workspace.clone(),
             state_dir.clone(),
             sandbox_policy.clone(),
             bash_filter,
@@ -122,16 +155,110 @@ pub fn create_cli_tools(config: &Config) -> Result<Vec<Box<dyn Tool>>> {
         Box::new(EditFileTool::new(
             workspace,
             state_dir,
-            sandbox_policy,
+            sandbox_policy.clone(),
             file_filter,
             allowed_dirs,
         )),
-    ])
+    ];
+
+    // User-defined command tools from [[tools.commands]]
+    for command in &config.tools.commands {
+        if tools.iter().any(|t| t.name() == command.name) {
+            tracing::warn!(
+                "Skipping command tool '{}': name clashes with an existing tool",
+                command.name
+            );
+            continue;
+        }
+        tools.push(Box::new(CommandTool::new(
+            command.clone(),
+            config.tools.bash_timeout_ms,
+            sandbox_policy.clone(),
+        )));
+    }
+
+    // WASM plugin tools from [[tools.plugins]]
+    #[cfg(feature = "wasm-plugins")]
+    for plugin in crate::plugins::create_plugin_tools(config) {
+        if tools.iter().any(|t| t.name() == plugin.name()) {
+            tracing::warn!(
+                "Skipping plugin tool '{}': name clashes with an existing tool",
+                plugin.name()
+            );
+            continue;
+        }
+        tools.push(plugin);
+    }
+    #[cfg(not(feature = "wasm-plugins"))]
+    if !config.tools.plugins.is_empty() {
+        tracing::warn!(
+            "[[tools.plugins]] configured but this build lacks the wasm-plugins feature"
+        );
+    }
+
+    Ok(tools)
+}
+
+/// Run a shell command, sandboxed if a policy is given, and collect its output.
+async fn run_shell_command(
+    command: &str,
+    sandbox_policy: Option<&SandboxPolicy>,
+    timeout_ms: u64,
+) -> Result<String> {
+    if let Some(policy) = sandbox_policy {
+        let (output, exit_code) =
+            localgpt_sandbox::run_sandboxed(command, policy, timeout_ms).await?;
+
+        if output.is_empty() {
+            return Ok(format!("Command completed with exit code: {}", exit_code));
+        }
+
+        return Ok(output);
+    }
+
+    // Fallback: run command directly without sandbox
+    let timeout_duration = std::time::Duration::from_millis(timeout_ms);
+    let output = tokio::time::timeout(
+        timeout_duration,
+        tokio::process::Command::new("bash")
+            .arg("-c")
+            .arg(command)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("Command timed out after {}ms", timeout_ms))??;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let mut result = String::new();
+
+    if !stdout.is_empty() {
+        result.push_str(&stdout);
+    }
+
+    if !stderr.is_empty() {
+        if !result.is_empty() {
+            result.push_str("\n\nSTDERR:\n");
+        }
+        result.push_str(&stderr);
+    }
+
+    if result.is_empty() {
+        result = format!(
+            "Command completed with exit code: {}",
+            output.status.code().unwrap_or(-1)
+        );
+    }
+
+    Ok(result)
 }
 
 // Bash Tool
 pub struct BashTool {
     default_timeout_ms: u64,
+    workspace: PathBuf,
     state_dir: PathBuf,
     sandbox_policy: Option<SandboxPolicy>,
     filter: CompiledToolFilter,
@@ -141,6 +268,7 @@ pub struct BashTool {
 impl BashTool {
     pub fn new(
         default_timeout_ms: u64,
+        workspace: PathBuf,
         state_dir: PathBuf,
         sandbox_policy: Option<SandboxPolicy>,
         filter: CompiledToolFilter,
@@ -148,6 +276,7 @@ impl BashTool {
     ) -> Self {
         Self {
             default_timeout_ms,
+            workspace,
             state_dir,
             sandbox_policy,
             filter,
@@ -198,7 +327,31 @@ impl Tool for BashTool {
 
         // Best-effort protected file check for bash commands
         let suspicious = security::check_bash_command(command);
-        if !suspicious.is_empty() {
+        // In strict mode, a command that touches a single workspace protected
+        // file can still run with the user's approval. An approval names one
+        // path, so commands touching several are refused outright.
+        let approved = if self.strict_policy
+            && suspicious.len() == 1
+            && security::is_workspace_file_protected(suspicious[0])
+        {
+            let path = self.workspace.join(suspicious[0]);
+            match security::request_protected_write(
+                &self.state_dir,
+                "bash",
+                &path,
+                command,
+                command,
+            )
+            .await
+            {
+                WriteDecision::Approved => true,
+                WriteDecision::Blocked => false,
+                decision => return Err(approval_error(&decision, &path)),
+            }
+        } else {
+            false
+        };
+        if !suspicious.is_empty() && !approved {
             let detail = format!(
                 "Bash command references protected files: {:?} (cmd: {})",
                 suspicious,
@@ -225,54 +378,60 @@ impl Tool for BashTool {
             timeout_ms, command
         );
 
-        // Use sandbox if policy is configured
-        if let Some(ref policy) = self.sandbox_policy {
-            let (output, exit_code) =
-                localgpt_sandbox::run_sandboxed(command, policy, timeout_ms).await?;
+        run_shell_command(command, self.sandbox_policy.as_ref(), timeout_ms).await
+    }
+}
 
-            if output.is_empty() {
-                return Ok(format!("Command completed with exit code: {}", exit_code));
-            }
+// Command Tool - user-defined tool backed by a shell command template
+pub struct CommandTool {
+    config: CommandToolConfig,
+    timeout_ms: u64,
+    sandbox_policy: Option<SandboxPolicy>,
+}
 
-            return Ok(output);
+impl CommandTool {
+    pub fn new(
+        config: CommandToolConfig,
+        default_timeout_ms: u64,
+        sandbox_policy: Option<SandboxPolicy>,
+    ) -> Self {
+        let timeout_ms = config.timeout_ms.unwrap_or(default_timeout_ms);
+        let sandbox_policy = match config.sandbox {
+            CommandToolSandbox::Inherit => sandbox_policy,
+            CommandToolSandbox::None => None,
+        };
+        Self {
+            config,
+            timeout_ms,
+            sandbox_policy,
         }
+    }
+}
 
-        // Fallback: run command directly without sandbox
-        let timeout_duration = std::time::Duration::from_millis(timeout_ms);
-        let output = tokio::time::timeout(
-            timeout_duration,
-            tokio::process::Command::new("bash")
-                .arg("-c")
-                .arg(command)
-                .output(),
-        )
-        .await
-        .map_err(|_| anyhow::anyhow!("Command timed out after {}ms", timeout_ms))??;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-
-        let mut result = String::new();
-
-        if !stdout.is_empty() {
-            result.push_str(&stdout);
-        }
+#[async_trait]
+impl Tool for CommandTool {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
 
-        if !stderr.is_empty() {
-            if !result.is_empty() {
-                result.push_str("\n\nSTDERR:\n");
-            }
-            result.push_str(&stderr);
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: self.config.name.clone(),
+            description: self.config.description.clone(),
+            parameters: self.config.parameters.clone(),
         }
+    }
 
-        if result.is_empty() {
-            result = format!(
-                "Command completed with exit code: {}",
-                output.status.code().unwrap_or(-1)
-            );
-        }
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let command = render_command_template(&self.config.command, &args)?;
 
-        Ok(result)
+        debug!(
+            "Executing command tool '{}' (timeout: {}ms): {}",
+            self.config.name, self.timeout_ms, command
+        );
+
+        run_shell_command(&command, self.sandbox_policy.as_ref(), self.timeout_ms).await
     }
 }
 
@@ -480,25 +639,41 @@ impl Tool for WriteFileTool {
             );
         }
 
-        // Check protected files
-        if security::is_path_protected(
+        // Check protected files; where an interface can ask, the user may
+        // approve this exact write
+        let protected = security::is_path_protected(
             &real_path.to_string_lossy(),
             &self.workspace,
             &self.state_dir,
-        ) {
-            let detail = format!("Agent attempted write to {}", real_path.display());
-            let _ = security::append_audit_entry_with_detail(
+        );
+        if protected {
+            match security::request_protected_write(
                 &self.state_dir,
-                security::AuditAction::WriteBlocked,
-                "",
-                "tool:write_file",
-                Some(&detail),
-            );
-            anyhow::bail!(
-                "Cannot write to protected file: {}. This file is managed by the security system. \
-                     Use `localgpt md sign` to update the security policy.",
-                real_path.display()
-            );
+                "write_file",
+                &real_path,
+                content,
+                content,
+            )
+            .await
+            {
+                WriteDecision::Approved => {}
+                WriteDecision::Blocked => {
+                    let detail = format!("Agent attempted write to {}", real_path.display());
+                    let _ = security::append_audit_entry_with_detail(
+                        &self.state_dir,
+                        security::AuditAction::WriteBlocked,
+                        "",
+                        "tool:write_file",
+                        Some(&detail),
+                    );
+                    anyhow::bail!(
+                        "Cannot write to protected file: {}. This file is managed by the security system. \
+                             Use `localgpt md sign` to update the security policy.",
+                        real_path.display()
+                    );
+                }
+                decision => return Err(approval_error(&decision, &real_path)),
+            }
         }
 
         debug!("Writing file: {}", real_path.display());
@@ -510,11 +685,15 @@ impl Tool for WriteFileTool {
 
         fs::write(&real_path, content)?;
 
-        Ok(format!(
+        let mut result = format!(
             "Successfully wrote {} bytes to {}",
             content.len(),
             real_path.display()
-        ))
+        );
+        if protected {
+            result.push_str(approved_write_note(&real_path));
+        }
+        Ok(result)
     }
 }
 
@@ -621,11 +800,12 @@ impl Tool for EditFileTool {
         }
 
         // Check protected files
-        if security::is_path_protected(
+        let protected = security::is_path_protected(
             &real_path.to_string_lossy(),
             &self.workspace,
             &self.state_dir,
-        ) {
+        );
+        let block = || {
             let detail = format!("Agent attempted edit to {}", real_path.display());
             let _ = security::append_audit_entry_with_detail(
                 &self.state_dir,
@@ -634,10 +814,14 @@ impl Tool for EditFileTool {
                 "tool:edit_file",
                 Some(&detail),
             );
-            anyhow::bail!(
+            anyhow::anyhow!(
                 "Cannot edit protected file: {}. This file is managed by the security system.",
                 real_path.display()
-            );
+            )
+        };
+        // The device key and audit log can't be approved; don't even read them
+        if protected && !security::is_workspace_file_protected(&real_path_str) {
+            return Err(block());
         }
 
         debug!("Editing file: {}", real_path.display());
@@ -653,12 +837,34 @@ impl Tool for EditFileTool {
             return Err(anyhow::anyhow!("old_string not found in file"));
         };
 
+        // Where an interface can ask, the user may approve this exact edit
+        if protected {
+            let preview = format!("- {}\n+ {}", old_string, new_string);
+            match security::request_protected_write(
+                &self.state_dir,
+                "edit_file",
+                &real_path,
+                &new_content,
+                &preview,
+            )
+            .await
+            {
+                WriteDecision::Approved => {}
+                WriteDecision::Blocked => return Err(block()),
+                decision => return Err(approval_error(&decision, &real_path)),
+            }
+        }
+
         fs::write(&real_path, &new_content)?;
 
-        Ok(format!(
+        let mut result = format!(
             "Replaced {} occurrence(s) in {}",
             count,
             real_path.display()
-        ))
+        );
+        if protected {
+            result.push_str(approved_write_note(&real_path));
+        }
+        Ok(result)
     }
 }
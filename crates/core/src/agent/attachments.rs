@@ -0,0 +1,260 @@
+//! Files attached to a chat turn.
+//!
+//! `localgpt chat --attach <path>` and the REPL's `/attach <path>` load
+//! files here. Images (png, jpg, gif, webp) are sent as image inputs for
+//! vision models; anything else must be UTF-8 text and is inlined into the
+//! user message. Text is fitted to a token budget taken from the free part
+//! of the context window: small files go in whole, and the rest share what
+//! is left, each truncated at a line boundary with a notice.
+
+use anyhow::{Context, Result, bail};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use std::path::Path;
+
+use super::providers::ImageAttachment;
+
+/// Largest image that will be attached; provider APIs reject bigger ones.
+pub const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Rough token cost of one image input, charged against the budget.
+pub const IMAGE_TOKEN_ESTIMATE: usize = 1_600;
+
+/// Fewest tokens a text attachment gets, however full the context is.
+const MIN_TEXT_BUDGET: usize = 1_000;
+
+/// A file waiting to be sent with the next message.
+#[derive(Debug, Clone)]
+pub enum Attachment {
+    Text { name: String, content: String },
+    Image { name: String, data: ImageAttachment },
+}
+
+impl Attachment {
+    /// Read `path` as an image (by extension) or as UTF-8 text.
+    pub fn load(path: &Path) -> Result<Self> {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_else(|| path.to_str().unwrap_or("attachment"))
+            .to_string();
+        let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", name))?;
+
+        if let Some(media_type) = image_media_type(path) {
+            if bytes.len() > MAX_IMAGE_BYTES {
+                bail!(
+                    "{} is {} bytes; images must be at most {} bytes",
+                    name,
+                    bytes.len(),
+                    MAX_IMAGE_BYTES
+                );
+            }
+            return Ok(Attachment::Image {
+                name,
+                data: ImageAttachment {
+                    data: STANDARD.encode(&bytes),
+                    media_type: media_type.to_string(),
+                },
+            });
+        }
+
+        let content = String::from_utf8(bytes)
+            .map_err(|_| anyhow::anyhow!("{} is not a text file or a supported image", name))?;
+        Ok(Attachment::Text { name, content })
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Attachment::Text { name, .. } | Attachment::Image { name, .. } => name,
+        }
+    }
+
+    /// Estimated tokens this attachment adds before any truncation.
+    pub fn estimated_tokens(&self) -> usize {
+        match self {
+            Attachment::Text { content, .. } => estimate_tokens(content),
+            Attachment::Image { .. } => IMAGE_TOKEN_ESTIMATE,
+        }
+    }
+}
+
+/// The MIME type for a supported image extension.
+fn image_media_type(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+fn estimate_tokens(text: &str) -> usize {
+    text.len() / 4
+}
+
+/// Tokens available for attachments: half of what's left of the usable
+/// context, so the reply and any tool calls still fit.
+pub fn attachment_budget(used: usize, usable: usize) -> usize {
+    (usable.saturating_sub(used) / 2).max(MIN_TEXT_BUDGET)
+}
+
+/// A message ready to send, with the attachments folded in.
+#[derive(Debug, Default)]
+pub struct AttachedMessage {
+    pub text: String,
+    pub images: Vec<ImageAttachment>,
+    /// One line per truncated file, e.g. "notes.md: sent 4000 of 18000 bytes".
+    pub truncated: Vec<String>,
+}
+
+/// Append text attachments to `input` under an "Attached files" heading
+/// and collect the images, keeping the text within `budget_tokens`.
+///
+/// Images are charged first. The remaining budget is split across text
+/// files smallest first, so a file that fits its share goes in whole and
+/// hands its leftover to the larger ones.
+pub fn build_message(
+    input: &str,
+    attachments: Vec<Attachment>,
+    budget_tokens: usize,
+) -> AttachedMessage {
+    let mut message = AttachedMessage {
+        text: input.to_string(),
+        ..Default::default()
+    };
+    let mut texts = Vec::new();
+    for attachment in attachments {
+        match attachment {
+            Attachment::Text { name, content } => texts.push((name, content)),
+            Attachment::Image { data, .. } => message.images.push(data),
+        }
+    }
+    if texts.is_empty() {
+        return message;
+    }
+
+    let image_tokens = message.images.len() * IMAGE_TOKEN_ESTIMATE;
+    let mut remaining = budget_tokens
+        .saturating_sub(image_tokens)
+        .max(MIN_TEXT_BUDGET)
+        * 4;
+
+    let mut order: Vec<usize> = (0..texts.len()).collect();
+    order.sort_by_key(|&i| texts[i].1.len());
+    let mut limits = vec![0; texts.len()];
+    for (n, &i) in order.iter().enumerate() {
+        let share = remaining / (order.len() - n);
+        limits[i] = texts[i].1.len().min(share);
+        remaining -= limits[i];
+    }
+
+    message.text.push_str("\n\n---\n\n**Attached files:**\n");
+    for ((name, content), limit) in texts.iter().zip(limits) {
+        let shown = truncate_at_line(content, limit);
+        message
+            .text
+            .push_str(&format!("\n### {}\n```\n{}\n```\n", name, shown));
+        if shown.len() < content.len() {
+            message.text.push_str(&format!(
+                "[...truncated: sent {} of {} bytes]\n",
+                shown.len(),
+                content.len()
+            ));
+            message.truncated.push(format!(
+                "{}: sent {} of {} bytes",
+                name,
+                shown.len(),
+                content.len()
+            ));
+        }
+    }
+    message
+}
+
+/// The longest prefix of `content` within `max_bytes`, cut after the last
+/// full line when there is one in the back half.
+fn truncate_at_line(content: &str, max_bytes: usize) -> &str {
+    if content.len() <= max_bytes {
+        return content;
+    }
+    let end = content.floor_char_boundary(max_bytes);
+    let prefix = &content[..end];
+    match prefix.rfind('\n') {
+        Some(newline) if newline >= end / 2 => &prefix[..newline],
+        _ => prefix,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(name: &str, content: String) -> Attachment {
+        Attachment::Text {
+            name: name.to_string(),
+            content,
+        }
+    }
+
+    #[test]
+    fn test_small_files_go_in_whole() {
+        let message = build_message(
+            "summarize",
+            vec![
+                text("a.md", "alpha\n".into()),
+                text("b.md", "beta\n".into()),
+            ],
+            10_000,
+        );
+        assert!(message.text.starts_with("summarize\n\n---"));
+        assert!(message.text.contains("### a.md\n```\nalpha\n"));
+        assert!(message.text.contains("### b.md\n```\nbeta\n"));
+        assert!(message.truncated.is_empty());
+    }
+
+    #[test]
+    fn test_large_file_gets_the_leftover_budget() {
+        let small = "x".repeat(400);
+        let large = "line\n".repeat(10_000);
+        let message = build_message(
+            "",
+            vec![text("big.log", large), text("small.md", small.clone())],
+            MIN_TEXT_BUDGET,
+        );
+        assert!(message.text.contains(&small));
+        // The small file's unused share goes to the large one (3600 bytes),
+        // cut back to the last full line.
+        assert_eq!(message.truncated, vec!["big.log: sent 3599 of 50000 bytes"]);
+        assert!(
+            message
+                .text
+                .contains("line\n```\n[...truncated: sent 3599 of 50000 bytes]")
+        );
+    }
+
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        let content = "é".repeat(10);
+        assert_eq!(truncate_at_line(&content, 5), "éé");
+    }
+
+    #[test]
+    fn test_load_rejects_binary_and_encodes_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("blob.bin");
+        std::fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+        assert!(Attachment::load(&binary).is_err());
+
+        let image = dir.path().join("pixel.PNG");
+        std::fs::write(&image, b"png").unwrap();
+        match Attachment::load(&image).unwrap() {
+            Attachment::Image { name, data } => {
+                assert_eq!(name, "pixel.PNG");
+                assert_eq!(data.media_type, "image/png");
+                assert_eq!(data.data, "cG5n");
+            }
+            other => panic!("expected image, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! JSON output mode for the OpenAI-compatible API.
+//!
+//! Providers are asked for JSON through an extra system instruction, and the
+//! reply is pulled out of any surrounding prose or code fence and checked
+//! against the requested shape. Schemas are checked for the commonly used
+//! subset of JSON Schema: `type`, `enum`, `const`, `properties`, `required`,
+//! `additionalProperties`, `items`, and `anyOf`/`oneOf`.
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::Value;
+
+/// The shape a JSON-mode reply must have.
+#[derive(Debug, Clone)]
+pub enum JsonFormat {
+    /// Any JSON object (`response_format: {"type": "json_object"}`)
+    Object,
+    /// A value matching a JSON Schema (`response_format: {"type": "json_schema"}`)
+    Schema(Value),
+}
+
+impl JsonFormat {
+    /// System instruction asking the model for this format.
+    pub fn instruction(&self) -> String {
+        match self {
+            JsonFormat::Object => "Respond with a single valid JSON object and nothing else: \
+                 no prose, no markdown code fences."
+                .to_string(),
+            JsonFormat::Schema(schema) => format!(
+                "Respond with a single valid JSON value matching this JSON Schema and nothing \
+                 else: no prose, no markdown code fences.\n\n{}",
+                serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string())
+            ),
+        }
+    }
+
+    /// Parse a model reply, failing with a description of what's wrong.
+    pub fn parse_reply(&self, text: &str) -> Result<Value> {
+        let value = extract_json(text)?;
+        match self {
+            JsonFormat::Object if !value.is_object() => {
+                bail!("Expected a JSON object, got {}", type_name(&value))
+            }
+            JsonFormat::Object => {}
+            JsonFormat::Schema(schema) => validate(&value, schema, "$")?,
+        }
+        Ok(value)
+    }
+}
+
+/// Parse the JSON value in a model reply, tolerating surrounding prose and
+/// markdown code fences.
+pub fn extract_json(text: &str) -> Result<Value> {
+    let text = text.trim();
+    let first_error = match serde_json::from_str(text) {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+
+    if let Some(start) = text.find("```") {
+        let fenced = &text[start + 3..];
+        // Skip the info string (e.g. "json") on the opening fence line
+        let body = fenced.split_once('\n').map_or("", |(_, body)| body);
+        if let Some(end) = body.find("```")
+            && let Ok(value) = serde_json::from_str(body[..end].trim())
+        {
+            return Ok(value);
+        }
+    }
+
+    let start = text.find(['{', '[']);
+    let end = text.rfind(['}', ']']);
+    if let (Some(start), Some(end)) = (start, end)
+        && start < end
+        && let Ok(value) = serde_json::from_str(&text[start..=end])
+    {
+        return Ok(value);
+    }
+
+    Err(anyhow!("Reply is not valid JSON: {}", first_error))
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    let actual = type_name(value);
+    actual == expected
+        || (expected == "number" && actual == "integer")
+        || (expected == "integer" && value.as_f64().is_some_and(|f| f.fract() == 0.0))
+}
+
+/// Check `value` against `schema`, naming the offending path on failure.
+fn validate(value: &Value, schema: &Value, path: &str) -> Result<()> {
+    let Some(schema) = schema.as_object() else {
+        // `true` or `{}`-like schemas accept anything; `false` accepts nothing
+        if schema == &Value::Bool(false) {
+            bail!("{}: no value is allowed here", path);
+        }
+        return Ok(());
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            bail!(
+                "{}: expected {}, got {}",
+                path,
+                types.join(" or "),
+                type_name(value)
+            );
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        bail!(
+            "{}: {} is not one of {}",
+            path,
+            value,
+            Value::from(allowed.clone())
+        );
+    }
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        bail!("{}: expected {}", path, expected);
+    }
+
+    for key in ["anyOf", "oneOf"] {
+        if let Some(options) = schema.get(key).and_then(Value::as_array)
+            && !options.iter().any(|o| validate(value, o, path).is_ok())
+        {
+            bail!("{}: does not match any allowed schema", path);
+        }
+    }
+
+    if let Value::Object(object) = value {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    bail!("{}: missing required property '{}'", path, name);
+                }
+            }
+        }
+        for (name, field) in object {
+            let field_path = format!("{}.{}", path, name);
+            match properties.and_then(|p| p.get(name)) {
+                Some(field_schema) => validate(field, field_schema, &field_path)?,
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        bail!("{}: unexpected property '{}'", path, name)
+                    }
+                    Some(extra @ Value::Object(_)) => validate(field, extra, &field_path)?,
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    if let Value::Array(items) = value
+        && let Some(item_schema) = schema.get("items")
+    {
+        for (i, item) in items.iter().enumerate() {
+            validate(item, item_schema, &format!("{}[{}]", path, i))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extract_json() {
+        assert_eq!(extract_json(r#"{"a": 1}"#).unwrap(), json!({"a": 1}));
+        assert_eq!(
+            extract_json("Here you go:\n```json\n{\"a\": 1}\n```\nAnything else?").unwrap(),
+            json!({"a": 1})
+        );
+        assert_eq!(
+            extract_json("Sure! [1, 2, 3] is the list.").unwrap(),
+            json!([1, 2, 3])
+        );
+        assert!(extract_json("I can't do that.").is_err());
+        assert!(extract_json(r#"{"a": 1"#).is_err());
+    }
+
+    #[test]
+    fn test_object_format() {
+        assert!(JsonFormat::Object.parse_reply(r#"{"ok": true}"#).is_ok());
+        let err = JsonFormat::Object.parse_reply("[1]").unwrap_err();
+        assert!(err.to_string().contains("got array"));
+    }
+
+    #[test]
+    fn test_schema_format() {
+        let format = JsonFormat::Schema(json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"},
+                "tags": {"type": "array", "items": {"enum": ["a", "b"]}}
+            },
+            "required": ["name", "age"],
+            "additionalProperties": false
+        }));
+
+        let value = format
+            .parse_reply(r#"{"name": "Ada", "age": 36, "tags": ["a"]}"#)
+            .unwrap();
+        assert_eq!(value["name"], "Ada");
+
+        let missing = format.parse_reply(r#"{"name": "Ada"}"#).unwrap_err();
+        assert!(
+            missing
+                .to_string()
+                .contains("missing required property 'age'")
+        );
+
+        let wrong_type = format
+            .parse_reply(r#"{"name": "Ada", "age": "36"}"#)
+            .unwrap_err();
+        assert!(wrong_type.to_string().contains("$.age: expected integer"));
+
+        let bad_item = format
+            .parse_reply(r#"{"name": "Ada", "age": 36, "tags": ["c"]}"#)
+            .unwrap_err();
+        assert!(bad_item.to_string().starts_with("$.tags[0]:"));
+
+        let extra = format
+            .parse_reply(r#"{"name": "Ada", "age": 36, "email": "x"}"#)
+            .unwrap_err();
+        assert!(extra.to_string().contains("unexpected property 'email'"));
+    }
+}
@@ -1,16 +1,24 @@
+pub mod attachments;
 pub mod failover;
 pub mod hardcoded_filters;
+pub mod json_output;
+pub mod model_catalog;
 pub mod path_utils;
 pub mod providers;
 pub mod sanitize;
 pub mod session;
 pub mod session_pruning;
 pub mod session_store;
+pub mod skill_lock;
+pub mod skill_router;
+pub mod skill_signing;
 pub mod skills;
+pub mod skills_watcher;
 pub mod system_prompt;
 pub mod tool_filters;
 pub mod tools;
 
+pub use attachments::{AttachedMessage, Attachment};
 pub use providers::{
     ImageAttachment, LLMProvider, LLMResponse, LLMResponseContent, Message, Role, StreamChunk,
     StreamEvent, StreamResult, ToolCall, ToolSchema, Usage,
@@ -23,12 +31,27 @@ pub use sanitize::{
 };
 pub use session::{
     DEFAULT_AGENT_ID, Session, SessionInfo, SessionMessage, SessionSearchResult, SessionStatus,
-    get_last_session_id, get_last_session_id_for_agent, get_sessions_dir_for_agent, get_state_dir,
-    list_sessions, list_sessions_for_agent, search_sessions, search_sessions_for_agent,
+    delete_session_for_agent, get_last_session_id, get_last_session_id_for_agent,
+    get_sessions_dir_for_agent, get_state_dir, list_sessions, list_sessions_for_agent,
+    rename_session_for_agent, search_sessions, search_sessions_for_agent,
 };
 pub use session_pruning::{PruneResult, preview_prune, prune_all_agents, prune_sessions};
 pub use session_store::{SessionEntry, SessionStore};
-pub use skills::{Skill, SkillInvocation, get_skills_summary, load_skills, parse_skill_command};
+pub use skill_lock::{LockedSkill, SkillLock, SkillSourceKind, skill_content_hash};
+pub use skill_router::SemanticSkillRouter;
+pub use skill_signing::{
+    SIGNATURE_SIDECAR, SkillSignature, load_or_create_signing_key, public_key, sign_skill,
+    verify_skill_signature,
+};
+pub use skills::{
+    ConditionCheck, Skill, SkillArgument, SkillDiagnosis, SkillInvocation, SkillRoutingContext,
+    bind_skill_args, diagnose_skills, find_skill_root, get_skills_summary, install_skill_dir,
+    instantiate_skill, load_disabled_skills, load_skill_definitions, load_skills,
+    parse_skill_command, remove_managed_skill, scaffold_skill, set_skill_disabled,
+    skill_invocation_prompt, skill_usage, slash_command_skills, trust_level_for,
+    trusted_skill_keys, validate_skill_dir,
+};
+pub use skills_watcher::{SkillsWatcher, notify_skills_changed, skills_generation};
 pub use system_prompt::{
     HEARTBEAT_OK_TOKEN, SILENT_REPLY_TOKEN, build_heartbeat_prompt, filter_silent_reply,
     is_heartbeat_ok, is_silent_reply,
@@ -43,13 +66,19 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::{debug, info};
 
+use crate::concurrency::{CancellationToken, cancellable, cancellable_stream};
 use crate::config::{Config, SearchProviderType};
 use crate::memory::{MemoryChunk, MemoryManager};
+use tool_filters::InterfaceToolPolicy;
+use tools::output_pager::{OutputPager, READ_MORE_TOOL, ReadMoreTool};
 
 /// Soft threshold buffer before compaction (tokens)
 /// Memory flush runs when within this buffer of the hard limit
 const MEMORY_FLUSH_SOFT_THRESHOLD: usize = 4000;
 
+/// Channel recorded for agents that were not assigned one explicitly
+pub const DEFAULT_CHANNEL: &str = "cli";
+
 /// Token budget reserved for the per-turn security block (~80 suffix + ~1000 policy + margin).
 /// Subtracted from available context to prevent the security block from being dropped
 /// during context window management.
@@ -97,6 +126,24 @@ pub struct Agent {
     verified_security_policy: Option<String>,
     /// Loop detection for repeated tool calls
     loop_detector: LoopDetector,
+    /// Interface driving this agent (`cli`, `http`, `telegram`, ...), recorded in the tool audit log
+    channel: String,
+    /// Oversized tool outputs, paged out through the `read_more` tool
+    output_pager: OutputPager,
+    /// Skills loaded for the current session
+    skills: Vec<skills::Skill>,
+    /// Skills section embedded in the current system context
+    skills_prompt: String,
+    /// Skills generation the system context was built at
+    skills_generation: u64,
+    /// Per-message skill selection when `skills.routing = "semantic"`
+    skill_router: Option<SemanticSkillRouter>,
+    /// Extra tool allow/deny list on top of the interface policy (e.g. per cron job)
+    tool_restriction: Option<InterfaceToolPolicy>,
+    /// If set, only skills with these names are loaded
+    skill_filter: Option<Vec<String>>,
+    /// Aborts the current turn's provider requests and tools when cancelled
+    cancel: CancellationToken,
 }
 
 /// Detects when the agent is stuck in a tool-call loop
@@ -190,10 +237,12 @@ impl Agent {
 
         // Memory is already wrapped in Arc, create safe tools sharing it
         let mut tools = tools::create_safe_tools(app_config, Some(Arc::clone(&memory)))?;
+        let output_pager = OutputPager::new();
+        tools.push(Box::new(ReadMoreTool::new(output_pager.clone())));
 
         // Connect to MCP servers and discover tools
         if !app_config.mcp.servers.is_empty() {
-            match crate::mcp::McpManager::connect_all(&app_config.mcp.servers).await {
+            match crate::mcp::McpManager::connect_all(&app_config.mcp.servers, app_config).await {
                 Ok((_manager, mcp_tools)) => {
                     info!(
                         "MCP: {} tools discovered from {} server(s)",
@@ -303,7 +352,9 @@ impl Agent {
             }
         };
 
-        Ok(Self {
+        let skill_router = SemanticSkillRouter::from_config(&app_config.skills, &memory);
+
+        let mut agent = Self {
             config,
             app_config: app_config.clone(),
             provider,
@@ -316,7 +367,18 @@ impl Agent {
             search_cost_usd: 0.0,
             verified_security_policy,
             loop_detector: LoopDetector::new(app_config.agent.max_tool_repeats),
-        })
+            channel: DEFAULT_CHANNEL.to_string(),
+            output_pager,
+            skills: Vec::new(),
+            skills_prompt: String::new(),
+            skills_generation: skills_watcher::skills_generation(),
+            skill_router,
+            tool_restriction: None,
+            skill_filter: None,
+            cancel: CancellationToken::new(),
+        };
+        agent.apply_interface_policy();
+        Ok(agent)
     }
 
     /// Create an agent with custom pre-built tools (e.g., for Gen mode).
@@ -324,7 +386,7 @@ impl Agent {
         app_config: Config,
         _agent_id: &str,
         memory: Arc<MemoryManager>,
-        tools: Vec<Box<dyn Tool>>,
+        mut tools: Vec<Box<dyn Tool>>,
     ) -> Result<Self> {
         let agent_config = AgentConfig {
             model: app_config.agent.default_model.clone(),
@@ -376,7 +438,12 @@ impl Agent {
 
         let max_tool_repeats = app_config.agent.max_tool_repeats;
 
-        Ok(Self {
+        let output_pager = OutputPager::new();
+        tools.push(Box::new(ReadMoreTool::new(output_pager.clone())));
+
+        let skill_router = SemanticSkillRouter::from_config(&app_config.skills, &memory);
+
+        let mut agent = Self {
             config: agent_config,
             app_config,
             provider,
@@ -389,18 +456,114 @@ impl Agent {
             search_cost_usd: 0.0,
             verified_security_policy,
             loop_detector: LoopDetector::new(max_tool_repeats),
-        })
+            channel: DEFAULT_CHANNEL.to_string(),
+            output_pager,
+            skills: Vec::new(),
+            skills_prompt: String::new(),
+            skills_generation: skills_watcher::skills_generation(),
+            skill_router,
+            tool_restriction: None,
+            skill_filter: None,
+            cancel: CancellationToken::new(),
+        };
+        agent.apply_interface_policy();
+        Ok(agent)
     }
 
     /// Add extra tools to an already-constructed agent (e.g., dangerous CLI tools).
+    ///
+    /// Tools not permitted on this agent's channel are dropped.
     pub fn extend_tools(&mut self, extra: Vec<Box<dyn Tool>>) {
         self.tools.extend(extra);
+        self.apply_interface_policy();
+    }
+
+    /// Drop tools that the `[tools.interfaces]` policy forbids on this channel,
+    /// or that the agent's tool restriction excludes.
+    fn apply_interface_policy(&mut self) {
+        if let Some(policy) = self.app_config.tools.interfaces.get(&self.channel) {
+            let channel = &self.channel;
+            self.tools.retain(|tool| {
+                let permitted = policy.permits(tool.name());
+                if !permitted {
+                    debug!(
+                        "Tool '{}' not permitted on '{}' interface",
+                        tool.name(),
+                        channel
+                    );
+                }
+                permitted
+            });
+        }
+        if let Some(restriction) = &self.tool_restriction {
+            self.tools.retain(|tool| {
+                let permitted = restriction.permits(tool.name());
+                if !permitted {
+                    debug!("Tool '{}' excluded by tool restriction", tool.name());
+                }
+                permitted
+            });
+        }
+    }
+
+    /// Further limit the available tools, on top of the interface policy.
+    /// Applies to tools added later with [`Agent::extend_tools`] too.
+    pub fn restrict_tools(&mut self, policy: InterfaceToolPolicy) {
+        self.tool_restriction = Some(policy);
+        self.apply_interface_policy();
+    }
+
+    /// Only offer the named skills. Takes effect when the session context
+    /// is next built.
+    pub fn set_skill_filter(&mut self, names: Vec<String>) {
+        self.skill_filter = Some(names);
+    }
+
+    /// Load workspace skills, keeping only those allowed by the skill filter.
+    fn load_agent_skills(&self) -> Vec<skills::Skill> {
+        let mut loaded =
+            skills::load_skills(self.memory.workspace(), &self.app_config.skills.trust_policy())
+                .unwrap_or_default();
+        if let Some(filter) = &self.skill_filter {
+            loaded.retain(|s| {
+                filter
+                    .iter()
+                    .any(|name| *name == s.name || *name == s.command_name)
+            });
+        }
+        loaded
     }
 
     pub fn model(&self) -> &str {
         &self.config.model
     }
 
+    /// Set the interface driving this agent (e.g. `"http"`, `"telegram"`).
+    ///
+    /// Tools not permitted by `[tools.interfaces.<channel>]` are removed.
+    pub fn set_channel(&mut self, channel: &str) {
+        self.channel = channel.to_string();
+        self.apply_interface_policy();
+    }
+
+    /// Interface driving this agent (default: `"cli"`)
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// Token for the next turn. Cancelling it ends the turn with
+    /// [`TurnCancelled`](crate::concurrency::TurnCancelled) at the running
+    /// provider request or tool; set a fresh one before each turn, since a
+    /// cancelled token stays cancelled.
+    pub fn set_cancel_token(&mut self, token: CancellationToken) {
+        self.cancel = token;
+    }
+
+    /// Whether the current turn has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
     /// Check if a tool requires user approval before execution
     pub fn requires_approval(&self, tool_name: &str) -> bool {
         self.app_config
@@ -458,23 +621,7 @@ impl Agent {
 
     /// Export session messages as markdown
     pub fn export_markdown(&self) -> String {
-        let mut output = String::new();
-        output.push_str("# LocalGPT Session Export\n\n");
-        output.push_str(&format!("Model: {}\n", self.config.model));
-        output.push_str(&format!("Session ID: {}\n\n", self.session.id()));
-        output.push_str("---\n\n");
-
-        for msg in self.session.messages() {
-            let role = match msg.role {
-                Role::User => "**User**",
-                Role::Assistant => "**Assistant**",
-                Role::System => "**System**",
-                Role::Tool => "**Tool**",
-            };
-            output.push_str(&format!("{}\n\n{}\n\n---\n\n", role, msg.content));
-        }
-
-        output
+        self.session.export_markdown(Some(&self.config.model))
     }
 
     /// Get cumulative token usage for this session
@@ -543,6 +690,9 @@ impl Agent {
             SearchProviderType::Brave => 0.005,
             SearchProviderType::Tavily => 0.005,
             SearchProviderType::Perplexity => 0.003,
+            SearchProviderType::Serper => 0.001,
+            SearchProviderType::DuckDuckGo => 0.0,
+            SearchProviderType::Kagi => 0.025,
             SearchProviderType::None => 0.0,
         }
     }
@@ -617,11 +767,28 @@ impl Agent {
         // Reset provider session state (e.g., clear Claude CLI session ID)
         self.provider.reset_session();
 
+        let full_context = self.build_session_context().await?;
+        self.session.set_system_context(full_context);
+
+        info!("Created new session: {}", self.session.id());
+        Ok(())
+    }
+
+    /// Build the session system context: system prompt (with skills) plus
+    /// workspace memory context.
+    async fn build_session_context(&mut self) -> Result<String> {
         // Load skills from workspace
-        let workspace_skills = skills::load_skills(self.memory.workspace()).unwrap_or_default();
-        let skills_prompt = skills::build_skills_prompt(&workspace_skills, None);
-        debug!("Loaded {} skills from workspace", workspace_skills.len());
+        self.skills_generation = skills_watcher::skills_generation();
+        self.skills = self.load_agent_skills();
+        debug!("Loaded {} skills from workspace", self.skills.len());
+        self.skills_prompt = skills::build_skills_prompt(&self.skills, None);
 
+        self.compose_session_context(self.skills_prompt.clone())
+            .await
+    }
+
+    /// System prompt with the given skills section, plus memory context.
+    async fn compose_session_context(&mut self, skills_prompt: String) -> Result<String> {
         // Build system prompt with identity, safety, workspace info
         let tool_names = self.tool_names_for_provider();
         debug!(
@@ -649,9 +816,74 @@ impl Agent {
             )
         };
 
-        self.session.set_system_context(full_context);
+        Ok(full_context)
+    }
 
-        info!("Created new session: {}", self.session.id());
+    /// Bring the skills section up to date before a turn: reload skills if
+    /// they changed on disk (see [`SkillsWatcher`]) and, with semantic
+    /// routing, select the skills relevant to `message`.
+    async fn refresh_skills(&mut self, message: &str) -> Result<()> {
+        let generation = skills_watcher::skills_generation();
+        let reloaded = generation != self.skills_generation;
+        if reloaded {
+            self.skills_generation = generation;
+            self.skills = self.load_agent_skills();
+        }
+        if !reloaded && self.skill_router.is_none() {
+            return Ok(());
+        }
+
+        let skills_prompt = match self.skill_router.as_mut() {
+            Some(router) => {
+                let tools = self
+                    .tools
+                    .iter()
+                    .map(|t| t.name().to_string())
+                    .collect::<Vec<_>>();
+                let ctx = skills::SkillRoutingContext::new(message, self.channel.as_str())
+                    .with_tools(tools);
+                match router.route(&self.skills, &ctx).await {
+                    Ok(selected) => skills::build_routed_skills_prompt(&self.skills, &selected),
+                    Err(e) => {
+                        tracing::warn!(
+                            "Semantic skill routing failed, including all skills: {}",
+                            e
+                        );
+                        skills::build_skills_prompt(&self.skills, None)
+                    }
+                }
+            }
+            None => skills::build_skills_prompt(&self.skills, None),
+        };
+        if skills_prompt == self.skills_prompt {
+            return Ok(());
+        }
+        if reloaded {
+            info!(
+                "Skills changed on disk, reloading {} skills",
+                self.skills.len()
+            );
+        }
+
+        let Some(context) = self.session.system_context() else {
+            return Ok(());
+        };
+        if !self.skills_prompt.is_empty() && context.contains(&self.skills_prompt) {
+            let updated = context.replacen(&self.skills_prompt, &skills_prompt, 1);
+            self.session.set_system_context(updated);
+        } else {
+            // No skills section to swap (e.g. first skill added): rebuild
+            let full_context = self.compose_session_context(skills_prompt.clone()).await?;
+            self.session.set_system_context(full_context);
+        }
+        self.skills_prompt = skills_prompt;
+        Ok(())
+    }
+
+    /// Start a new session saved under `session_id` rather than a fresh UUID
+    pub async fn new_session_with_id(&mut self, session_id: &str) -> Result<()> {
+        self.new_session().await?;
+        self.session.set_id(session_id);
         Ok(())
     }
 
@@ -661,6 +893,17 @@ impl Agent {
         Ok(())
     }
 
+    /// Resume a session saved by a specific agent (e.g. `save_session_for_agent`)
+    pub async fn resume_session_for_agent(
+        &mut self,
+        session_id: &str,
+        agent_id: &str,
+    ) -> Result<()> {
+        self.session = Session::load_in(&self.app_config.paths.sessions_dir(agent_id), session_id)?;
+        info!("Resumed session: {}", session_id);
+        Ok(())
+    }
+
     pub async fn chat(&mut self, message: &str) -> Result<String> {
         self.chat_with_images(message, Vec::new()).await
     }
@@ -672,6 +915,7 @@ impl Agent {
     ) -> Result<String> {
         // Reset loop detector for new turn
         self.loop_detector.reset();
+        self.refresh_skills(message).await?;
 
         // Add user message with images
         self.session.add_message(Message {
@@ -701,8 +945,7 @@ impl Agent {
 
         // Invoke LLM
         let response = self
-            .provider
-            .chat(&messages, Some(tool_schemas.as_slice()))
+            .provider_chat(&messages, Some(tool_schemas.as_slice()))
             .await?;
 
         // Handle token update if refreshed during chat
@@ -731,7 +974,9 @@ impl Agent {
     ///
     /// This method takes a list of messages directly and does NOT modify the session.
     /// Used by the OpenAI-compatible HTTP API for stateless requests.
-    /// Tool calls are executed if the model returns them.
+    /// Without `tools`, the agent's own tools are offered and executed until
+    /// the model answers. With `tools` (defined by the client), tool calls
+    /// are returned to the caller unexecuted.
     pub async fn chat_with_messages(
         &mut self,
         messages: &[Message],
@@ -740,7 +985,140 @@ impl Agent {
         // Reset loop detector for this call
         self.loop_detector.reset();
 
-        // Build messages with system prompt prepended if needed
+        let api_messages = self.stateless_messages(messages);
+
+        // Client-defined tools are executed by the client
+        if let Some(tools) = tools {
+            let response = self.provider_chat(&api_messages, Some(tools)).await?;
+            let _ = self.handle_token_update();
+            self.add_usage(response.usage.clone());
+            return Ok(response);
+        }
+
+        let tool_schemas = self.tool_schemas_for_provider();
+
+        // Invoke LLM
+        let response = self
+            .provider_chat(&api_messages, Some(tool_schemas.as_slice()))
+            .await?;
+
+        // Handle token update if refreshed during chat
+        let _ = self.handle_token_update();
+
+        // Handle tool calls recursively
+        self.handle_response_stateless(response, &api_messages, &tool_schemas)
+            .await
+    }
+
+    /// Streaming variant of [`Self::chat_with_messages`]. With client
+    /// `tools`, tool calls are yielded as `ToolCallStart` events and the
+    /// stream ends without executing them; otherwise the agent's tools are
+    /// executed (`ToolCallStart`/`ToolCallEnd`) until the model answers.
+    pub fn chat_stream_with_messages(
+        &mut self,
+        messages: &[Message],
+        tools: Option<Vec<ToolSchema>>,
+    ) -> impl futures::Stream<Item = Result<StreamEvent>> + '_ {
+        self.loop_detector.reset();
+        let mut api_messages = self.stateless_messages(messages);
+
+        async_stream::stream! {
+            let passthrough = tools.is_some();
+            let tool_schemas = tools.unwrap_or_else(|| self.tool_schemas_for_provider());
+            let max_tool_iterations = 10;
+
+            for _ in 0..max_tool_iterations {
+                let response = match self
+                    .provider_chat(&api_messages, Some(tool_schemas.as_slice()))
+                    .await
+                {
+                    Ok(response) => response,
+                    Err(e) => {
+                        yield Err(e);
+                        return;
+                    }
+                };
+                let _ = self.handle_token_update();
+                self.add_usage(response.usage);
+
+                let (calls, text) = match response.content {
+                    LLMResponseContent::Text(text) => {
+                        yield Ok(StreamEvent::Content(filter_silent_reply(text)));
+                        yield Ok(StreamEvent::Done);
+                        return;
+                    }
+                    LLMResponseContent::ToolCalls { calls, text } => (calls, text),
+                };
+
+                if let Some(ref reasoning) = text
+                    && !reasoning.is_empty()
+                {
+                    yield Ok(StreamEvent::Content(reasoning.clone()));
+                }
+
+                if passthrough {
+                    for call in calls {
+                        yield Ok(StreamEvent::ToolCallStart {
+                            name: call.name,
+                            id: call.id,
+                            arguments: call.arguments,
+                        });
+                    }
+                    yield Ok(StreamEvent::Done);
+                    return;
+                }
+
+                api_messages.push(Message {
+                    role: Role::Assistant,
+                    content: text.unwrap_or_default(),
+                    tool_calls: Some(calls.clone()),
+                    tool_call_id: None,
+                    images: Vec::new(),
+                });
+
+                for call in &calls {
+                    self.loop_detector.record(&call.name, &call.arguments);
+                    if self.loop_detector.is_stuck() {
+                        let tool_name = self.loop_detector.last_tool_name().unwrap_or("unknown");
+                        yield Err(anyhow::anyhow!(
+                            "Tool '{}' called in a loop. Please try a different approach.",
+                            tool_name
+                        ));
+                        return;
+                    }
+
+                    yield Ok(StreamEvent::ToolCallStart {
+                        name: call.name.clone(),
+                        id: call.id.clone(),
+                        arguments: call.arguments.clone(),
+                    });
+                    let (output, warnings) = match self.execute_tool(call).await {
+                        Ok((content, warnings)) => (content, warnings),
+                        Err(e) => (format!("Error: {}", e), Vec::new()),
+                    };
+                    yield Ok(StreamEvent::ToolCallEnd {
+                        name: call.name.clone(),
+                        id: call.id.clone(),
+                        output: output.clone(),
+                        warnings,
+                    });
+
+                    api_messages.push(Message {
+                        role: Role::Tool,
+                        content: output,
+                        tool_calls: None,
+                        tool_call_id: Some(call.id.clone()),
+                        images: Vec::new(),
+                    });
+                }
+            }
+
+            yield Err(anyhow::anyhow!("Max tool iterations exceeded"));
+        }
+    }
+
+    /// `messages` with a system prompt prepended unless the client sent one.
+    fn stateless_messages(&self, messages: &[Message]) -> Vec<Message> {
         let mut api_messages = Vec::new();
 
         // Check if messages already start with a system prompt
@@ -769,25 +1147,7 @@ impl Agent {
 
         // Add provided messages
         api_messages.extend(messages.iter().cloned());
-
-        // If no tools provided, use the agent's default tools
-        let tool_schemas: Vec<ToolSchema> = match tools {
-            Some(t) => t.to_vec(),
-            None => self.tool_schemas_for_provider(),
-        };
-
-        // Invoke LLM
-        let response = self
-            .provider
-            .chat(&api_messages, Some(tool_schemas.as_slice()))
-            .await?;
-
-        // Handle token update if refreshed during chat
-        let _ = self.handle_token_update();
-
-        // Handle tool calls recursively
-        self.handle_response_stateless(response, &api_messages, &tool_schemas)
-            .await
+        api_messages
     }
 
     /// Handle LLM response for stateless chat (OpenAI API)
@@ -855,8 +1215,7 @@ impl Agent {
 
                 // Continue conversation with tool results
                 let next_response = self
-                    .provider
-                    .chat(&updated_messages, Some(tool_schemas))
+                    .provider_chat(&updated_messages, Some(tool_schemas))
                     .await?;
 
                 // Recursively handle (in case of more tool calls)
@@ -944,8 +1303,7 @@ impl Agent {
                 let messages = self.messages_for_api_call();
                 let tool_schemas = self.tool_schemas_for_provider();
                 let next_response = self
-                    .provider
-                    .chat(&messages, Some(tool_schemas.as_slice()))
+                    .provider_chat(&messages, Some(tool_schemas.as_slice()))
                     .await?;
 
                 // Recursively handle (in case of more tool calls)
@@ -1008,8 +1366,7 @@ impl Agent {
                     let messages = self.messages_for_api_call();
                     let tool_schemas = self.tool_schemas_for_provider();
                     response = self
-                        .provider
-                        .chat(&messages, Some(tool_schemas.as_slice()))
+                        .provider_chat(&messages, Some(tool_schemas.as_slice()))
                         .await?;
                 }
             }
@@ -1019,6 +1376,8 @@ impl Agent {
     /// Like `chat`, but saves the session log to `agent_id`'s sessions directory after each
     /// tool call round. Used by the heartbeat runner so in-progress sessions are visible.
     pub async fn chat_saving_session(&mut self, message: &str, agent_id: &str) -> Result<String> {
+        self.refresh_skills(message).await?;
+
         // Add user message and start out saved session file
         self.session.add_message(Message {
             role: Role::User,
@@ -1058,8 +1417,7 @@ impl Agent {
 
         // Invoke LLM
         let response = self
-            .provider
-            .chat(&messages, Some(tool_schemas.as_slice()))
+            .provider_chat(&messages, Some(tool_schemas.as_slice()))
             .await?;
 
         // Handle tool calls, saving session after each round
@@ -1157,8 +1515,7 @@ impl Agent {
                 let messages = self.messages_for_api_call();
                 let tool_schemas = self.tool_schemas_for_provider();
                 let next_response = self
-                    .provider
-                    .chat(&messages, Some(tool_schemas.as_slice()))
+                    .provider_chat(&messages, Some(tool_schemas.as_slice()))
                     .await?;
 
                 // Handle token update
@@ -1175,20 +1532,61 @@ impl Agent {
         }
     }
 
+    /// `provider.chat`, abandoned if the turn is cancelled
+    async fn provider_chat(
+        &self,
+        messages: &[Message],
+        tools: Option<&[ToolSchema]>,
+    ) -> Result<LLMResponse> {
+        cancellable(&self.cancel, self.provider.chat(messages, tools)).await
+    }
+
     async fn execute_tool(&mut self, call: &ToolCall) -> Result<(String, Vec<String>)> {
-        let raw_output = {
-            let tool = self
-                .tools
-                .iter()
-                .find(|tool| tool.name() == call.name)
-                .ok_or_else(|| anyhow::anyhow!("Unknown tool: {}", call.name))?;
-            tool.execute(&call.arguments).await?
+        let started = std::time::Instant::now();
+        // Protected-write approvals are shown with the turn's channel and session
+        let origin = crate::security::WriteOrigin {
+            channel: self.channel.clone(),
+            session_id: Some(self.session.id().to_string()),
+        };
+        let result = match self.tools.iter().find(|tool| tool.name() == call.name) {
+            Some(tool) => {
+                let execute = tool.execute(&call.arguments);
+                cancellable(
+                    &self.cancel,
+                    crate::security::with_write_origin(origin, execute),
+                )
+                .await
+            }
+            None => Err(anyhow::anyhow!("Unknown tool: {}", call.name)),
+        };
+        self.record_tool_audit(call, started.elapsed(), result.as_ref().err());
+        // Keep keys printed by e.g. `env` out of the session and the provider
+        let raw_output = match result {
+            Ok(output) => crate::security::redact_tool_output(&call.name, &output).into_owned(),
+            Err(e) => anyhow::bail!(
+                "{}",
+                crate::security::redact_tool_output(&call.name, &e.to_string())
+            ),
         };
 
         if call.name == "web_search" {
             self.track_web_search_usage(&raw_output);
         }
 
+        // Page oversized outputs; read_more pages are already sized
+        let raw_output = if call.name == READ_MORE_TOOL {
+            raw_output
+        } else {
+            let tools_config = &self.app_config.tools;
+            let max_tokens = tools_config
+                .output_limits
+                .get(&call.name)
+                .copied()
+                .unwrap_or(tools_config.tool_output_max_tokens);
+            self.output_pager
+                .paginate(&call.name, raw_output, max_tokens)
+        };
+
         // Apply sanitization if configured
         if self.app_config.tools.use_content_delimiters {
             let max_chars = if self.app_config.tools.tool_output_max_chars > 0 {
@@ -1214,6 +1612,32 @@ impl Agent {
         Ok((raw_output, Vec::new()))
     }
 
+    /// Append a tool invocation to the tool usage audit log (best effort).
+    fn record_tool_audit(
+        &self,
+        call: &ToolCall,
+        elapsed: std::time::Duration,
+        error: Option<&anyhow::Error>,
+    ) {
+        if !self.app_config.tools.audit_log {
+            return;
+        }
+        let error = error.map(|e| e.to_string());
+        let entry = crate::security::ToolAuditEntry::new(
+            &call.name,
+            &call.arguments,
+            elapsed.as_millis() as u64,
+            error.as_deref(),
+            self.session.id(),
+            &self.channel,
+        );
+        if let Err(e) =
+            crate::security::append_tool_audit_entry(&self.app_config.paths.state_dir, &entry)
+        {
+            debug!("Failed to record tool audit entry: {}", e);
+        }
+    }
+
     async fn build_memory_context(&self) -> Result<String> {
         let mut context = String::new();
         let use_delimiters = self.app_config.tools.use_content_delimiters;
@@ -1423,7 +1847,7 @@ impl Agent {
         let tool_schemas = self.tool_schemas_for_provider();
         let messages = self.messages_for_api_call();
 
-        let response = self.provider.chat(&messages, Some(&tool_schemas)).await?;
+        let response = self.provider_chat(&messages, Some(&tool_schemas)).await?;
 
         // Handle token update
         let _ = self.handle_token_update();
@@ -1560,7 +1984,7 @@ impl Agent {
 
     /// Save session for a specific agent ID (used by HTTP server)
     pub async fn save_session_for_agent(&self, agent_id: &str) -> Result<PathBuf> {
-        self.session.save_for_agent(agent_id)
+        self.session.save_in(&self.app_config.paths.sessions_dir(agent_id))
     }
 
     pub fn session_status(&self) -> SessionStatus {
@@ -1573,6 +1997,23 @@ impl Agent {
         )
     }
 
+    /// Set or clear the session's user-given title; it's saved with the session
+    pub fn set_session_title(&mut self, title: Option<String>) {
+        self.session.set_title(title);
+    }
+
+    /// Drop the messages added since `before` was taken, e.g. to undo a
+    /// cancelled turn. Returns false, leaving the session alone, if it was
+    /// compacted in the meantime.
+    pub fn rewind_session(&mut self, before: &SessionStatus) -> bool {
+        let status = self.session.status();
+        if status.id != before.id || status.compaction_count != before.compaction_count {
+            return false;
+        }
+        self.session.truncate_messages(before.message_count);
+        true
+    }
+
     /// Stream chat response - returns a stream of chunks
     /// After consuming the stream, call `finish_chat_stream` with the full response
     /// Note: Tool calls during streaming are not yet supported - the model will know
@@ -1586,6 +2027,8 @@ impl Agent {
         message: &str,
         images: Vec<ImageAttachment>,
     ) -> Result<StreamResult> {
+        self.refresh_skills(message).await?;
+
         // Add user message with images
         self.session.add_message(Message {
             role: Role::User,
@@ -1613,9 +2056,12 @@ impl Agent {
         let tool_schemas = self.tool_schemas_for_provider();
 
         // Get stream from provider with tools
-        self.provider
-            .chat_stream(&messages, Some(&tool_schemas))
-            .await
+        let stream = cancellable(
+            &self.cancel,
+            self.provider.chat_stream(&messages, Some(&tool_schemas)),
+        )
+        .await?;
+        Ok(cancellable_stream(self.cancel.clone(), stream))
     }
 
     /// Complete a streaming chat by adding the assistant response to the session
@@ -1700,8 +2146,7 @@ impl Agent {
         let messages = self.messages_for_api_call();
         let tool_schemas = self.tool_schemas_for_provider();
         let response = self
-            .provider
-            .chat(&messages, Some(tool_schemas.as_slice()))
+            .provider_chat(&messages, Some(tool_schemas.as_slice()))
             .await?;
 
         // Handle token update
@@ -1769,6 +2214,8 @@ impl Agent {
         message: &str,
         images: Vec<ImageAttachment>,
     ) -> Result<impl futures::Stream<Item = Result<StreamEvent>> + '_> {
+        self.refresh_skills(message).await?;
+
         // Add user message
         self.session.add_message(Message {
             role: Role::User,
@@ -1813,8 +2260,7 @@ impl Agent {
                 // Try streaming first (without tools since most providers don't support tool streaming)
                 // Then check for tool calls in the response
                 let response = self
-                    .provider
-                    .chat(&messages, Some(tool_schemas.as_slice()))
+                    .provider_chat(&messages, Some(tool_schemas.as_slice()))
                     .await;
 
                 match response {
@@ -0,0 +1,326 @@
+//! Discovery of the models LocalGPT can route to.
+//!
+//! Used by `GET /v1/models` so OpenAI-compatible clients get a real model
+//! picker. Configured API providers are asked for their model lists,
+//! Ollama for its installed models (`/api/tags`), and CLI providers are
+//! listed when their command is on `PATH`. A provider that can't be reached
+//! contributes only its configured model.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::config::Config;
+
+/// How long each provider may take to list its models.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Anthropic API version sent with the models request.
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// A model id as accepted by `create_provider`, with its provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelEntry {
+    pub id: String,
+    pub owned_by: String,
+}
+
+/// All models LocalGPT can route to, configured models first. Ids are
+/// unique; providers are probed concurrently.
+pub async fn discover_models(config: &Config) -> Vec<ModelEntry> {
+    let mut models = vec![entry("localgpt", config.agent.default_model.clone())];
+    for model in &config.agent.fallback_models {
+        models.push(entry("localgpt", model.clone()));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    let providers = &config.providers;
+
+    let ollama = async {
+        let Some(ollama) = &providers.ollama else {
+            return Vec::new();
+        };
+        match ollama_models(&client, &ollama.endpoint).await {
+            Ok(names) => names,
+            Err(e) => {
+                debug!("Ollama model listing failed: {}", e);
+                vec![ollama.model.clone()]
+            }
+        }
+    };
+    let openai = async {
+        match &providers.openai {
+            Some(openai) => list_or_empty(
+                "openai",
+                openai_models(&client, &openai.base_url, &openai.api_key).await,
+            ),
+            None => Vec::new(),
+        }
+    };
+    let xai = async {
+        match &providers.xai {
+            Some(xai) => list_or_empty(
+                "xai",
+                openai_models(&client, &xai.base_url, &xai.api_key).await,
+            ),
+            None => Vec::new(),
+        }
+    };
+    let anthropic = async {
+        match &providers.anthropic {
+            Some(anthropic) => list_or_empty(
+                "anthropic",
+                anthropic_models(&client, &anthropic.base_url, &anthropic.api_key).await,
+            ),
+            None => Vec::new(),
+        }
+    };
+    let compatible = async {
+        match &providers.openai_compatible {
+            Some(compat) => list_or_empty(
+                "openai-compat",
+                openai_models(&client, &compat.base_url, &compat.api_key).await,
+            ),
+            None => Vec::new(),
+        }
+    };
+    let (ollama, openai, xai, anthropic, compatible) =
+        futures::join!(ollama, openai, xai, anthropic, compatible);
+
+    for (provider, names) in [
+        ("ollama", ollama),
+        ("openai", openai),
+        ("xai", xai),
+        ("anthropic", anthropic),
+        ("openai-compat", compatible),
+    ] {
+        for name in names {
+            models.push(entry(provider, format!("{}/{}", provider, name)));
+        }
+    }
+    models.extend(cli_models(config));
+
+    let mut seen = std::collections::HashSet::new();
+    models.retain(|m| seen.insert(m.id.clone()));
+    models
+}
+
+/// Check that the provider serving `model` answers by listing its models,
+/// or for CLI providers that its command is installed. Providers without a
+/// model listing only need to be constructible. Returns a short summary.
+pub async fn probe_model(config: &Config, model: &str) -> Result<String> {
+    crate::agent::providers::create_provider(model, config)?;
+
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    let providers = &config.providers;
+    let provider = model.split_once('/').map_or("", |(provider, _)| provider);
+
+    let listed = if let ("ollama", Some(ollama)) = (provider, &providers.ollama) {
+        ollama_models(&client, &ollama.endpoint).await
+    } else if let ("openai", Some(openai)) = (provider, &providers.openai) {
+        openai_models(&client, &openai.base_url, &openai.api_key).await
+    } else if let ("xai", Some(xai)) = (provider, &providers.xai) {
+        openai_models(&client, &xai.base_url, &xai.api_key).await
+    } else if let ("anthropic", Some(anthropic)) = (provider, &providers.anthropic) {
+        anthropic_models(&client, &anthropic.base_url, &anthropic.api_key).await
+    } else if let ("openai-compat" | "openai_compat", Some(compat)) =
+        (provider, &providers.openai_compatible)
+    {
+        openai_models(&client, &compat.base_url, &compat.api_key).await
+    } else if let Some(command) = cli_command(config, provider) {
+        anyhow::ensure!(command_on_path(command), "'{}' not found", command);
+        return Ok(format!("'{}' installed", command));
+    } else {
+        return Ok(format!("{} configured (not probed)", model));
+    };
+
+    let names = listed.with_context(|| format!("{} is unreachable", provider))?;
+    Ok(format!("{} reachable ({} models)", provider, names.len()))
+}
+
+/// The command a CLI provider runs, if `provider` is one.
+fn cli_command<'a>(config: &'a Config, provider: &str) -> Option<&'a str> {
+    let providers = &config.providers;
+    match provider {
+        "claude-cli" => Some(
+            providers
+                .claude_cli
+                .as_ref()
+                .map_or("claude", |c| c.command.as_str()),
+        ),
+        "gemini-cli" => Some(
+            providers
+                .gemini_cli
+                .as_ref()
+                .map_or("gemini", |c| c.command.as_str()),
+        ),
+        "codex-cli" => Some(
+            providers
+                .codex_cli
+                .as_ref()
+                .map_or("codex", |c| c.command.as_str()),
+        ),
+        _ => None,
+    }
+}
+
+fn entry(owned_by: &str, id: String) -> ModelEntry {
+    ModelEntry {
+        id,
+        owned_by: owned_by.to_string(),
+    }
+}
+
+fn list_or_empty(provider: &str, result: Result<Vec<String>>) -> Vec<String> {
+    result.unwrap_or_else(|e| {
+        debug!("{} model listing failed: {}", provider, e);
+        Vec::new()
+    })
+}
+
+async fn ollama_models(client: &reqwest::Client, endpoint: &str) -> Result<Vec<String>> {
+    let url = format!("{}/api/tags", endpoint.trim_end_matches('/'));
+    let body: Value = client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    names_at(&body, "models", "name")
+}
+
+/// `GET {base_url}/models` on an OpenAI-style API.
+async fn openai_models(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: &str,
+) -> Result<Vec<String>> {
+    let url = format!("{}/models", base_url.trim_end_matches('/'));
+    let body: Value = client
+        .get(&url)
+        .bearer_auth(api_key)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    names_at(&body, "data", "id")
+}
+
+async fn anthropic_models(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: &str,
+) -> Result<Vec<String>> {
+    let url = format!("{}/v1/models", base_url.trim_end_matches('/'));
+    let body: Value = client
+        .get(&url)
+        .header("x-api-key", api_key)
+        .header("anthropic-version", ANTHROPIC_VERSION)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    names_at(&body, "data", "id")
+}
+
+/// The `field` of each object in the `list` array of `body`.
+fn names_at(body: &Value, list: &str, field: &str) -> Result<Vec<String>> {
+    let items = body
+        .get(list)
+        .and_then(|v| v.as_array())
+        .with_context(|| format!("Response has no '{}' list", list))?;
+    Ok(items
+        .iter()
+        .filter_map(|item| item.get(field).and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .collect())
+}
+
+/// CLI providers whose command is installed. Their model lists aren't
+/// queryable, so the configured model and well-known aliases are listed.
+fn cli_models(config: &Config) -> Vec<ModelEntry> {
+    let mut models = Vec::new();
+    let providers = &config.providers;
+
+    if cfg!(feature = "claude-cli") {
+        let command = providers
+            .claude_cli
+            .as_ref()
+            .map_or("claude", |c| c.command.as_str());
+        if command_on_path(command) {
+            let configured = providers.claude_cli.as_ref().map(|c| c.model.as_str());
+            for model in configured.into_iter().chain(["opus", "sonnet", "haiku"]) {
+                models.push(entry("claude-cli", format!("claude-cli/{}", model)));
+            }
+        }
+    }
+    if cfg!(feature = "gemini-cli") {
+        let command = providers
+            .gemini_cli
+            .as_ref()
+            .map_or("gemini", |c| c.command.as_str());
+        if command_on_path(command) {
+            let model = providers
+                .gemini_cli
+                .as_ref()
+                .map_or("gemini-3.1-pro-preview", |c| c.model.as_str());
+            models.push(entry("gemini-cli", format!("gemini-cli/{}", model)));
+        }
+    }
+    if cfg!(feature = "codex-cli") {
+        let command = providers
+            .codex_cli
+            .as_ref()
+            .map_or("codex", |c| c.command.as_str());
+        if command_on_path(command) {
+            let model = providers
+                .codex_cli
+                .as_ref()
+                .map_or("o4-mini", |c| c.model.as_str());
+            models.push(entry("codex-cli", format!("codex-cli/{}", model)));
+        }
+    }
+    models
+}
+
+/// Whether `command` is a path to a file or names one on `PATH`.
+fn command_on_path(command: &str) -> bool {
+    let command = command.split_whitespace().next().unwrap_or(command);
+    if command.contains(std::path::MAIN_SEPARATOR) {
+        return Path::new(command).is_file();
+    }
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(command).is_file()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_model_names_from_listings() {
+        let ollama = json!({ "models": [{ "name": "llama3:8b" }, { "name": "qwen2.5" }] });
+        assert_eq!(
+            names_at(&ollama, "models", "name").unwrap(),
+            vec!["llama3:8b", "qwen2.5"]
+        );
+
+        let openai =
+            json!({ "object": "list", "data": [{ "id": "gpt-4o" }, { "object": "model" }] });
+        assert_eq!(names_at(&openai, "data", "id").unwrap(), vec!["gpt-4o"]);
+
+        assert!(names_at(&json!({ "error": "unauthorized" }), "data", "id").is_err());
+    }
+}
@@ -26,6 +26,30 @@ pub struct ImageAttachment {
     pub media_type: String,
 }
 
+impl ImageAttachment {
+    /// Parse a base64 `data:image/...;base64,...` URL.
+    pub fn from_data_url(url: &str) -> Result<Self> {
+        let rest = url
+            .strip_prefix("data:")
+            .ok_or_else(|| anyhow::anyhow!("Not a data URL"))?;
+        let (header, data) = rest
+            .split_once(',')
+            .ok_or_else(|| anyhow::anyhow!("Malformed data URL"))?;
+        let media_type = header
+            .strip_suffix(";base64")
+            .ok_or_else(|| anyhow::anyhow!("Only base64 data URLs are supported"))?
+            .to_lowercase();
+        if !media_type.starts_with("image/") {
+            anyhow::bail!("Data URL is not an image: {}", media_type);
+        }
+        let data: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+        if data.is_empty() {
+            anyhow::bail!("Data URL has no image data");
+        }
+        Ok(Self { data, media_type })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub role: Role,
@@ -2078,6 +2102,22 @@ impl LLMProvider for OllamaProvider {
     }
 }
 
+/// Run a CLI provider's command to completion. Dropping the future kills the
+/// process, so a turn abandoned by its client stops generating.
+#[cfg(any(feature = "claude-cli", feature = "gemini-cli", feature = "codex-cli"))]
+async fn run_cli(
+    command: &str,
+    args: &[String],
+    workspace: &std::path::Path,
+) -> std::io::Result<std::process::Output> {
+    tokio::process::Command::new(command)
+        .args(args)
+        .current_dir(workspace)
+        .kill_on_drop(true)
+        .output()
+        .await
+}
+
 #[cfg(feature = "claude-cli")]
 /// Claude CLI Provider - invokes the `claude` CLI command
 /// No tool support (text in → text out only)
@@ -2127,8 +2167,6 @@ impl ClaudeCliProvider {
         system_prompt: Option<&str>,
         existing_session: Option<&str>,
     ) -> Result<(std::process::Output, bool)> {
-        use std::process::Command;
-
         // First attempt: try with existing session if available
         if let Some(cli_sid) = existing_session {
             let args = self.build_cli_args(prompt, system_prompt, Some(cli_sid), false);
@@ -2138,18 +2176,7 @@ impl ClaudeCliProvider {
                 self.command, args, self.workspace
             );
 
-            let output = tokio::task::spawn_blocking({
-                let command = self.command.clone();
-                let args = args.clone();
-                let workspace = self.workspace.clone();
-                move || {
-                    Command::new(&command)
-                        .args(&args)
-                        .current_dir(&workspace)
-                        .output()
-                }
-            })
-            .await??;
+            let output = run_cli(&self.command, &args, &self.workspace).await?;
 
             if output.status.success() {
                 return Ok((output, false));
@@ -2183,18 +2210,7 @@ impl ClaudeCliProvider {
             self.command, args, self.workspace
         );
 
-        let output = tokio::task::spawn_blocking({
-            let command = self.command.clone();
-            let args = args.clone();
-            let workspace = self.workspace.clone();
-            move || {
-                Command::new(&command)
-                    .args(&args)
-                    .current_dir(&workspace)
-                    .output()
-            }
-        })
-        .await??;
+        let output = run_cli(&self.command, &args, &self.workspace).await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -2502,6 +2518,7 @@ impl LLMProvider for ClaudeCliProvider {
             .current_dir(&self.workspace)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
+            .kill_on_drop(true)
             .spawn()
             .map_err(|e| anyhow::anyhow!("Failed to spawn Claude CLI: {}", e))?;
 
@@ -2790,8 +2807,6 @@ impl GeminiCliProvider {
         system_prompt: Option<&str>,
         existing_session: Option<&str>,
     ) -> Result<(std::process::Output, bool)> {
-        use std::process::Command;
-
         // First attempt: try with existing session if available
         if let Some(cli_sid) = existing_session {
             let args = self.build_cli_args(prompt, system_prompt, Some(cli_sid), false);
@@ -2801,18 +2816,7 @@ impl GeminiCliProvider {
                 self.command, args, self.workspace
             );
 
-            let output = tokio::task::spawn_blocking({
-                let command = self.command.clone();
-                let args = args.clone();
-                let workspace = self.workspace.clone();
-                move || {
-                    Command::new(&command)
-                        .args(&args)
-                        .current_dir(&workspace)
-                        .output()
-                }
-            })
-            .await??;
+            let output = run_cli(&self.command, &args, &self.workspace).await?;
 
             if output.status.success() {
                 return Ok((output, false));
@@ -2846,18 +2850,7 @@ impl GeminiCliProvider {
             self.command, args, self.workspace
         );
 
-        let output = tokio::task::spawn_blocking({
-            let command = self.command.clone();
-            let args = args.clone();
-            let workspace = self.workspace.clone();
-            move || {
-                Command::new(&command)
-                    .args(&args)
-                    .current_dir(&workspace)
-                    .output()
-            }
-        })
-        .await??;
+        let output = run_cli(&self.command, &args, &self.workspace).await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -3097,8 +3090,6 @@ impl CodexCliProvider {
         system_prompt: Option<&str>,
         existing_session: Option<&str>,
     ) -> Result<(std::process::Output, bool)> {
-        use std::process::Command;
-
         let mut args = vec!["-q".to_string(), "--json".to_string()];
 
         if !self.model.is_empty() {
@@ -3123,18 +3114,7 @@ impl CodexCliProvider {
             self.command, args, self.workspace
         );
 
-        let output = tokio::task::spawn_blocking({
-            let command = self.command.clone();
-            let args = args.clone();
-            let workspace = self.workspace.clone();
-            move || {
-                Command::new(&command)
-                    .args(&args)
-                    .current_dir(&workspace)
-                    .output()
-            }
-        })
-        .await??;
+        let output = run_cli(&self.command, &args, &self.workspace).await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -3268,6 +3248,18 @@ mod tests {
         assert_eq!(usage.total(), 150);
     }
 
+    #[test]
+    fn test_image_from_data_url() {
+        let image = ImageAttachment::from_data_url("data:image/PNG;base64,iVBO\nRw0K").unwrap();
+        assert_eq!(image.media_type, "image/png");
+        assert_eq!(image.data, "iVBORw0K");
+
+        assert!(ImageAttachment::from_data_url("https://example.com/a.png").is_err());
+        assert!(ImageAttachment::from_data_url("data:text/plain;base64,aGk=").is_err());
+        assert!(ImageAttachment::from_data_url("data:image/png,raw").is_err());
+        assert!(ImageAttachment::from_data_url("data:image/png;base64,").is_err());
+    }
+
     #[test]
     fn test_usage_default() {
         let usage = Usage::default();
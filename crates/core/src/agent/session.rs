@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 use super::providers::{LLMProvider, Message, Role, ToolCall, Usage};
@@ -29,6 +29,8 @@ pub struct Session {
     token_count: usize,
     compaction_count: u32,
     memory_flush_compaction_count: u32,
+    /// Name given by the user, shown instead of the derived title
+    title: Option<String>,
 }
 
 /// Message with metadata for persistence
@@ -144,6 +146,7 @@ impl Session {
             token_count: 0,
             compaction_count: 0,
             memory_flush_compaction_count: 0,
+            title: None,
         }
     }
 
@@ -151,6 +154,34 @@ impl Session {
         &self.id
     }
 
+    pub(crate) fn set_id(&mut self, id: &str) {
+        self.id = id.to_string();
+    }
+
+    /// Render the conversation as Markdown, noting `model` if known
+    pub fn export_markdown(&self, model: Option<&str>) -> String {
+        let mut output = String::new();
+        output.push_str("# LocalGPT Session Export\n\n");
+        if let Some(model) = model {
+            output.push_str(&format!("Model: {}\n", model));
+        }
+        output.push_str(&format!("Session ID: {}\n\n", self.id));
+        output.push_str("---\n\n");
+
+        for msg in self.messages() {
+            let role = match msg.role {
+                Role::User => "**User**",
+                Role::Assistant => "**Assistant**",
+                Role::System => "**System**",
+                Role::Tool => "**Tool**",
+            };
+            output.push_str(&format!("{}\n\n{}\n\n---\n\n", role, msg.content));
+        }
+
+        // Messages from before redaction (or from the user) may hold secrets
+        crate::security::redact_secrets(&output).into_owned()
+    }
+
     pub fn token_count(&self) -> usize {
         self.token_count
     }
@@ -167,6 +198,18 @@ impl Session {
         self.memory_flush_compaction_count = self.compaction_count + 1;
     }
 
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.title = title;
+    }
+
+    pub fn system_context(&self) -> Option<&str> {
+        self.system_context.as_deref()
+    }
+
     pub fn set_system_context(&mut self, context: String) {
         self.system_context = Some(context);
         self.recalculate_tokens();
@@ -199,6 +242,14 @@ impl Session {
         ));
     }
 
+    /// Drop every message after the first `len`
+    pub fn truncate_messages(&mut self, len: usize) {
+        if len < self.messages.len() {
+            self.messages.truncate(len);
+            self.recalculate_tokens();
+        }
+    }
+
     pub fn messages_for_llm(&self) -> Vec<Message> {
         let mut messages = Vec::new();
 
@@ -289,8 +340,12 @@ impl Session {
     }
 
     pub fn save_for_agent(&self, agent_id: &str) -> Result<PathBuf> {
-        let dir = get_sessions_dir_for_agent(agent_id)?;
-        fs::create_dir_all(&dir)?;
+        self.save_in(&get_sessions_dir_for_agent(agent_id)?)
+    }
+
+    /// Save session as `<id>.jsonl` in `dir`
+    pub fn save_in(&self, dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(dir)?;
 
         let path = dir.join(format!("{}.jsonl", self.id));
         self.save_to_path(&path)?;
@@ -309,7 +364,7 @@ impl Session {
         }
 
         // Write Pi-compatible header
-        let header = json!({
+        let mut header = json!({
             "type": "session",
             "version": CURRENT_SESSION_VERSION,
             "id": self.id,
@@ -319,6 +374,9 @@ impl Session {
             "compactionCount": self.compaction_count,
             "memoryFlushCompactionCount": self.memory_flush_compaction_count
         });
+        if let Some(ref title) = self.title {
+            header["title"] = json!(title);
+        }
         writeln!(file, "{}", serde_json::to_string(&header)?)?;
 
         // Write system context as a system message
@@ -424,7 +482,16 @@ impl Session {
 
     /// Load session (supports both old and Pi formats)
     pub fn load(session_id: &str) -> Result<Self> {
-        let dir = get_sessions_dir()?;
+        Self::load_for_agent(session_id, DEFAULT_AGENT_ID)
+    }
+
+    /// Load a session saved by a specific agent
+    pub fn load_for_agent(session_id: &str, agent_id: &str) -> Result<Self> {
+        Self::load_in(&get_sessions_dir_for_agent(agent_id)?, session_id)
+    }
+
+    /// Load the session saved as `<session_id>.jsonl` in `dir`
+    pub fn load_in(dir: &Path, session_id: &str) -> Result<Self> {
         let path = dir.join(format!("{}.jsonl", session_id));
 
         if !path.exists() {
@@ -447,6 +514,7 @@ impl Session {
             token_count: 0,
             compaction_count: 0,
             memory_flush_compaction_count: 0,
+            title: None,
         };
 
         for line in reader.lines() {
@@ -477,6 +545,9 @@ impl Session {
                     if let Some(count) = entry["memoryFlushCompactionCount"].as_u64() {
                         session.memory_flush_compaction_count = count as u32;
                     }
+                    if let Some(title) = entry["title"].as_str() {
+                        session.title = Some(title.to_string());
+                    }
                 }
                 // Pi format message
                 Some("message") => {
@@ -639,8 +710,14 @@ pub struct SessionInfo {
     pub created_at: DateTime<Utc>,
     pub message_count: usize,
     pub file_size: u64,
+    /// The name given by the user, or else the start of the first user
+    /// message, if there is one
+    pub title: Option<String>,
 }
 
+/// Longest session title, in characters.
+const MAX_TITLE_CHARS: usize = 60;
+
 pub fn list_sessions() -> Result<Vec<SessionInfo>> {
     list_sessions_for_agent(DEFAULT_AGENT_ID)
 }
@@ -684,15 +761,18 @@ pub fn list_sessions_for_agent(agent_id: &str) -> Result<Vec<SessionInfo>> {
                         .map(|dt| dt.with_timezone(&Utc))
                         .unwrap_or_else(Utc::now);
 
-                    let message_count = fs::read_to_string(&path)
-                        .map(|s| s.lines().count().saturating_sub(1))
-                        .unwrap_or(0);
+                    let transcript = fs::read_to_string(&path).unwrap_or_default();
+                    let message_count = transcript.lines().count().saturating_sub(1);
 
                     sessions.push(SessionInfo {
                         id: filename.to_string(),
                         created_at,
                         message_count,
                         file_size,
+                        title: header["title"]
+                            .as_str()
+                            .map(str::to_string)
+                            .or_else(|| session_title(&transcript)),
                     });
                 }
             }
@@ -703,6 +783,102 @@ pub fn list_sessions_for_agent(agent_id: &str) -> Result<Vec<SessionInfo>> {
     Ok(sessions)
 }
 
+/// Title for a saved session: its first user message, whitespace collapsed
+/// and truncated.
+fn session_title(transcript: &str) -> Option<String> {
+    transcript
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|entry| entry["type"].as_str() == Some("message"))
+        .map(|entry| entry["message"].clone())
+        .filter(|message| message["role"].as_str() == Some("user"))
+        .find_map(|message| {
+            let text = match &message["content"] {
+                serde_json::Value::String(text) => text.clone(),
+                serde_json::Value::Array(parts) => parts
+                    .iter()
+                    .filter(|part| part["type"].as_str() == Some("text"))
+                    .filter_map(|part| part["text"].as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                _ => String::new(),
+            };
+            let words: Vec<&str> = text.split_whitespace().collect();
+            if words.is_empty() {
+                return None;
+            }
+            let title = words.join(" ");
+            if title.chars().count() <= MAX_TITLE_CHARS {
+                Some(title)
+            } else {
+                let cut: String = title.chars().take(MAX_TITLE_CHARS - 1).collect();
+                Some(format!("{}…", cut.trim_end()))
+            }
+        })
+}
+
+/// Delete a saved session and its entries in the agent's session store.
+/// Returns false if there was no such session.
+pub fn delete_session_for_agent(agent_id: &str, session_id: &str) -> Result<bool> {
+    let path = get_sessions_dir_for_agent(agent_id)?.join(format!("{}.jsonl", session_id));
+    if !path.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(&path)?;
+
+    let mut store = super::session_store::SessionStore::load_for_agent(agent_id)?;
+    if store.remove_session(session_id) {
+        store.save()?;
+    }
+    Ok(true)
+}
+
+/// Set or clear (`None`) the title of a saved session by rewriting its
+/// header. Returns false if there was no such session.
+pub fn rename_session_for_agent(
+    agent_id: &str,
+    session_id: &str,
+    title: Option<&str>,
+) -> Result<bool> {
+    let path = get_sessions_dir_for_agent(agent_id)?.join(format!("{}.jsonl", session_id));
+    if !path.exists() {
+        return Ok(false);
+    }
+    rewrite_title(&path, title)?;
+    Ok(true)
+}
+
+fn rewrite_title(path: &PathBuf, title: Option<&str>) -> Result<()> {
+    let transcript = fs::read_to_string(path)?;
+    let (first_line, rest) = transcript.split_once('\n').unwrap_or((&transcript, ""));
+    let mut header: serde_json::Value = serde_json::from_str(first_line)?;
+    if header["type"].as_str() != Some("session") {
+        anyhow::bail!("Session file {} has no header", path.display());
+    }
+    match title {
+        Some(title) => header["title"] = json!(title),
+        None => {
+            if let Some(fields) = header.as_object_mut() {
+                fields.remove("title");
+            }
+        }
+    }
+
+    // Write alongside and rename so a crash can't truncate the transcript
+    let tmp_path = path.with_extension("jsonl.tmp");
+    let mut file = File::create(&tmp_path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    writeln!(file, "{}", serde_json::to_string(&header)?)?;
+    file.write_all(rest.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
 pub fn get_last_session_id() -> Result<Option<String>> {
     get_last_session_id_for_agent(DEFAULT_AGENT_ID)
 }
@@ -803,6 +979,44 @@ mod tests {
         assert_eq!(session.compaction_count(), 0);
     }
 
+    #[test]
+    fn test_truncate_messages() {
+        let mut session = Session::new();
+        for content in ["hello", "hi there", "bye"] {
+            session.add_message(Message {
+                role: Role::User,
+                content: content.to_string(),
+                tool_calls: None,
+                tool_call_id: None,
+                images: Vec::new(),
+            });
+        }
+        let tokens = session.token_count();
+
+        session.truncate_messages(1);
+        assert_eq!(session.messages().len(), 1);
+        assert!(session.token_count() < tokens);
+
+        session.truncate_messages(5);
+        assert_eq!(session.messages().len(), 1);
+    }
+
+    #[test]
+    fn test_export_markdown_redacts_secrets() {
+        let mut session = Session::new();
+        session.add_message(Message {
+            role: Role::Tool,
+            content: "HOME=/home/me\nOPENAI_API_KEY=sk-abcdefghijklmnopqrstuvwx".to_string(),
+            tool_calls: None,
+            tool_call_id: Some("call_1".to_string()),
+            images: Vec::new(),
+        });
+
+        let markdown = session.export_markdown(None);
+        assert!(markdown.contains("HOME=/home/me\nOPENAI_API_KEY=[REDACTED]"));
+        assert!(!markdown.contains("sk-abcdefghijklmnopqrstuvwx"));
+    }
+
     #[test]
     fn test_message_usage_from() {
         let usage = Usage {
@@ -814,4 +1028,63 @@ mod tests {
         assert_eq!(msg_usage.output, 50);
         assert_eq!(msg_usage.total_tokens, 150);
     }
+
+    #[test]
+    fn test_session_title() {
+        let transcript = [
+            json!({ "type": "session", "id": "s" }),
+            json!({ "type": "message", "message": { "role": "system", "content": "rules" } }),
+            json!({ "type": "message", "message": { "role": "user", "content": [] } }),
+            json!({ "type": "message", "message": {
+                "role": "user",
+                "content": [{ "type": "text", "text": "  Plan my\n trip to Lisbon " }]
+            } }),
+        ]
+        .map(|v| v.to_string())
+        .join("\n");
+        assert_eq!(
+            session_title(&transcript).as_deref(),
+            Some("Plan my trip to Lisbon")
+        );
+
+        let long = json!({ "type": "message", "message": {
+            "role": "user", "content": "word ".repeat(40)
+        } });
+        let title = session_title(&long.to_string()).unwrap();
+        assert_eq!(title.chars().count(), MAX_TITLE_CHARS);
+        assert!(title.ends_with('…'));
+
+        assert_eq!(session_title(""), None);
+    }
+
+    #[test]
+    fn test_session_title_persists() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("session.jsonl");
+
+        let mut session = Session::new();
+        session.add_message(Message {
+            role: Role::User,
+            content: "hello".to_string(),
+            tool_calls: None,
+            tool_call_id: None,
+            images: Vec::new(),
+        });
+        session.set_title(Some("Greetings".to_string()));
+        session.save_to_path(&path).unwrap();
+
+        let loaded = Session::load_from_path(&path, session.id()).unwrap();
+        assert_eq!(loaded.title(), Some("Greetings"));
+        assert_eq!(loaded.messages().len(), 1);
+
+        rewrite_title(&path, Some("Renamed")).unwrap();
+        let renamed = Session::load_from_path(&path, session.id()).unwrap();
+        assert_eq!(renamed.title(), Some("Renamed"));
+        assert_eq!(renamed.messages().len(), 1);
+
+        rewrite_title(&path, None).unwrap();
+        let cleared = Session::load_from_path(&path, session.id()).unwrap();
+        assert_eq!(cleared.title(), None);
+        assert_eq!(cleared.messages().len(), 1);
+    }
 }
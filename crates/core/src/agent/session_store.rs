@@ -207,6 +207,14 @@ impl SessionStore {
             .or_insert_with(|| SessionEntry::new(session_id))
     }
 
+    /// Drop every entry that points at `session_id`. Returns whether any did.
+    pub fn remove_session(&mut self, session_id: &str) -> bool {
+        let before = self.entries.len();
+        self.entries
+            .retain(|_, entry| entry.session_id != session_id);
+        self.entries.len() != before
+    }
+
     /// Update a session entry
     pub fn update<F>(&mut self, session_key: &str, session_id: &str, f: F) -> Result<()>
     where
@@ -0,0 +1,178 @@
+//! Lockfile for installed skills.
+//!
+//! Records where each managed skill was installed from and which version
+//! (git commit, declared version, content hash) is on disk, so
+//! `localgpt skills update` can tell whether the source has moved on.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// How a skill was fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillSourceKind {
+    Git,
+    Url,
+    Archive,
+    Local,
+}
+
+/// Lock entry for one managed skill.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedSkill {
+    /// Skill name from frontmatter
+    pub name: String,
+    /// Source as given to `skills install` (git URL, URL, or path)
+    pub source: String,
+    pub kind: SkillSourceKind,
+    /// Subdirectory of the source holding the skill
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<String>,
+    /// Declared version from SKILL.md frontmatter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Git commit the skill was installed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// SHA-256 over the installed skill files
+    pub content_hash: String,
+    pub installed_at: String,
+}
+
+impl LockedSkill {
+    /// Short version label for display: declared version, else commit, else hash.
+    pub fn version_label(&self) -> String {
+        if let Some(version) = &self.version {
+            return version.clone();
+        }
+        let id = self.commit.as_deref().unwrap_or(&self.content_hash);
+        id.chars().take(12).collect()
+    }
+}
+
+/// Installed skills keyed by their directory name in the managed skills dir.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillLock {
+    #[serde(default)]
+    pub skills: BTreeMap<String, LockedSkill>,
+}
+
+impl SkillLock {
+    /// Read the lockfile; a missing file is an empty lock.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Hash every non-hidden file under `dir` (paths and contents, in sorted order).
+pub fn skill_content_hash(dir: &Path) -> Result<String> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for rel in &files {
+        hasher.update(rel.as_bytes());
+        hasher.update([0]);
+        hasher.update(fs::read(dir.join(rel))?);
+        hasher.update([0]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if file_type.is_file()
+            && let Ok(rel) = path.strip_prefix(root)
+        {
+            files.push(rel.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(commit: Option<&str>, version: Option<&str>) -> LockedSkill {
+        LockedSkill {
+            name: "deploy".to_string(),
+            source: "https://example.com/deploy.git".to_string(),
+            kind: SkillSourceKind::Git,
+            subpath: None,
+            version: version.map(str::to_string),
+            commit: commit.map(str::to_string),
+            content_hash: "ab".repeat(32),
+            installed_at: "2026-03-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn lock_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("skills.lock.json");
+        assert!(SkillLock::load(&path).unwrap().skills.is_empty());
+
+        let mut lock = SkillLock::default();
+        lock.skills
+            .insert("deploy".to_string(), entry(Some("0123456789abcdef"), None));
+        lock.save(&path).unwrap();
+
+        let loaded = SkillLock::load(&path).unwrap();
+        assert_eq!(loaded.skills["deploy"], lock.skills["deploy"]);
+    }
+
+    #[test]
+    fn version_label_prefers_declared_version() {
+        assert_eq!(
+            entry(Some("0123456789abcdef"), Some("1.2.0")).version_label(),
+            "1.2.0"
+        );
+        assert_eq!(
+            entry(Some("0123456789abcdef"), None).version_label(),
+            "0123456789ab"
+        );
+        assert_eq!(entry(None, None).version_label(), "abababababab");
+    }
+
+    #[test]
+    fn content_hash_tracks_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("SKILL.md"), "v1").unwrap();
+        fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        fs::write(tmp.path().join(".git/HEAD"), "ignored").unwrap();
+        let first = skill_content_hash(tmp.path()).unwrap();
+
+        fs::write(tmp.path().join(".git/HEAD"), "changed").unwrap();
+        assert_eq!(skill_content_hash(tmp.path()).unwrap(), first);
+
+        fs::create_dir_all(tmp.path().join("scripts")).unwrap();
+        fs::write(tmp.path().join("scripts/run.sh"), "echo").unwrap();
+        assert_ne!(skill_content_hash(tmp.path()).unwrap(), first);
+    }
+}
@@ -0,0 +1,230 @@
+//! Semantic skill routing
+//!
+//! With `skills.routing = "semantic"`, each skill's name and description is
+//! embedded once and every user message is matched against them by cosine
+//! similarity. Skills at or above `skills.semantic_threshold` are included in
+//! the prompt, best first, up to `skills.semantic_top_k`. Hand-written rules
+//! still apply: dontUseWhen excludes a skill, and a matching useWhen includes
+//! it regardless of similarity.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::skills::{Skill, SkillRoutingContext};
+use crate::config::{SkillRoutingMode, SkillsConfig};
+use crate::memory::{
+    EmbeddingProvider, MemoryManager, cosine_similarity, hash_text, normalize_embedding,
+};
+
+pub struct SemanticSkillRouter {
+    provider: Arc<dyn EmbeddingProvider>,
+    threshold: f32,
+    top_k: usize,
+    /// Skill embeddings keyed by hash of the embedded text
+    embeddings: HashMap<String, Vec<f32>>,
+}
+
+impl SemanticSkillRouter {
+    pub fn new(provider: Arc<dyn EmbeddingProvider>, threshold: f32, top_k: usize) -> Self {
+        Self {
+            provider,
+            threshold,
+            top_k,
+            embeddings: HashMap::new(),
+        }
+    }
+
+    /// Router for the configured routing mode, or `None` when semantic
+    /// routing is off or no embedding provider is available.
+    pub fn from_config(config: &SkillsConfig, memory: &MemoryManager) -> Option<Self> {
+        if config.routing != SkillRoutingMode::Semantic {
+            return None;
+        }
+        match memory.embedding_provider() {
+            Some(provider) => Some(Self::new(
+                provider,
+                config.semantic_threshold,
+                config.semantic_top_k,
+            )),
+            None => {
+                warn!(
+                    "skills.routing = \"semantic\" needs an embedding provider \
+                     (memory.embedding_provider); including all skills"
+                );
+                None
+            }
+        }
+    }
+
+    /// Skills to include in the prompt for `ctx.message`, sorted by name.
+    pub async fn route<'a>(
+        &mut self,
+        skills: &'a [Skill],
+        ctx: &SkillRoutingContext,
+    ) -> Result<Vec<&'a Skill>> {
+        let (mut selected, unruled): (Vec<&Skill>, Vec<&Skill>) = skills
+            .iter()
+            .filter(|s| s.include_in_prompt() && s.should_use(ctx))
+            .partition(|s| !s.use_when.is_empty());
+
+        if !unruled.is_empty() {
+            self.embed_missing(&unruled).await?;
+            let query = normalize_embedding(self.provider.embed(&ctx.message).await?);
+            let scored: Vec<(&Skill, f32)> = unruled
+                .into_iter()
+                .filter_map(|skill| {
+                    let embedding = self.embeddings.get(&hash_text(&embedding_text(skill)))?;
+                    Some((skill, cosine_similarity(&query, embedding)))
+                })
+                .collect();
+            selected.extend(top_matches(scored, self.threshold, self.top_k));
+        }
+
+        selected.sort_by(|a, b| a.name.cmp(&b.name));
+        debug!(
+            "Semantic skill routing selected: {:?}",
+            selected.iter().map(|s| s.name.as_str()).collect::<Vec<_>>()
+        );
+        Ok(selected)
+    }
+
+    async fn embed_missing(&mut self, skills: &[&Skill]) -> Result<()> {
+        let mut texts: Vec<String> = skills.iter().map(|s| embedding_text(s)).collect();
+        texts.retain(|t| !self.embeddings.contains_key(&hash_text(t)));
+        texts.dedup();
+        if texts.is_empty() {
+            return Ok(());
+        }
+
+        let embeddings = self.provider.embed_batch(&texts).await?;
+        for (text, embedding) in texts.iter().zip(embeddings) {
+            self.embeddings
+                .insert(hash_text(text), normalize_embedding(embedding));
+        }
+        Ok(())
+    }
+}
+
+/// Text embedded for a skill.
+fn embedding_text(skill: &Skill) -> String {
+    format!("{}: {}", skill.name, skill.description)
+}
+
+/// Skills scoring at least `threshold`, best first, at most `top_k`.
+fn top_matches(mut scored: Vec<(&Skill, f32)>, threshold: f32, top_k: usize) -> Vec<&Skill> {
+    scored.retain(|(_, score)| *score >= threshold);
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().take(top_k).map(|(s, _)| s).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::skills::validate_skill_dir;
+    use async_trait::async_trait;
+
+    /// Embeds text as counts of a few keywords.
+    struct KeywordEmbedder;
+
+    const KEYWORDS: [&str; 3] = ["weather", "deploy", "email"];
+
+    #[async_trait]
+    impl EmbeddingProvider for KeywordEmbedder {
+        fn id(&self) -> &str {
+            "keywords"
+        }
+
+        fn model(&self) -> &str {
+            "keywords"
+        }
+
+        fn dimensions(&self) -> usize {
+            KEYWORDS.len()
+        }
+
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            let text = text.to_lowercase();
+            Ok(KEYWORDS
+                .iter()
+                .map(|k| text.matches(k).count() as f32)
+                .collect())
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut out = Vec::new();
+            for text in texts {
+                out.push(self.embed(text).await?);
+            }
+            Ok(out)
+        }
+    }
+
+    fn skill(dir: &std::path::Path, frontmatter: &str) -> Skill {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\n{}\n---\n# Skill\n", frontmatter),
+        )
+        .unwrap();
+        validate_skill_dir(dir).unwrap()
+    }
+
+    #[tokio::test]
+    async fn routes_by_similarity_and_rules() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills = vec![
+            skill(
+                &tmp.path().join("weather"),
+                "name: weather\ndescription: Weather forecasts",
+            ),
+            skill(
+                &tmp.path().join("deploy"),
+                "name: deploy\ndescription: Deploy services",
+            ),
+            skill(
+                &tmp.path().join("mail"),
+                "name: mail\ndescription: Send email\nuseWhen:\n  - contains: \"inbox\"",
+            ),
+        ];
+        let mut router = SemanticSkillRouter::new(Arc::new(KeywordEmbedder), 0.5, 5);
+
+        let ctx = SkillRoutingContext::new("what's the weather tomorrow?", "cli");
+        let names: Vec<_> = router
+            .route(&skills, &ctx)
+            .await
+            .unwrap()
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["weather"]);
+
+        // A matching useWhen rule includes the skill regardless of similarity
+        let ctx = SkillRoutingContext::new("clean up my inbox", "cli");
+        let names: Vec<_> = router
+            .route(&skills, &ctx)
+            .await
+            .unwrap()
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["mail"]);
+    }
+
+    #[test]
+    fn top_matches_applies_threshold_and_limit() {
+        let tmp = tempfile::tempdir().unwrap();
+        let a = skill(&tmp.path().join("a"), "name: a\ndescription: A");
+        let b = skill(&tmp.path().join("b"), "name: b\ndescription: B");
+        let c = skill(&tmp.path().join("c"), "name: c\ndescription: C");
+
+        let scored = vec![(&a, 0.4), (&b, 0.9), (&c, 0.2)];
+        let names: Vec<_> = top_matches(scored.clone(), 0.3, 5)
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["b", "a"]);
+        assert_eq!(top_matches(scored, 0.3, 1).len(), 1);
+    }
+}
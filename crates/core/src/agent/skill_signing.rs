@@ -0,0 +1,281 @@
+//! Ed25519 signatures for SKILL.md files.
+//!
+//! Skills inject instructions directly into the system prompt, so managed
+//! and workspace skills can be required (or expected) to carry a signature
+//! from a trusted key; see `[skills.trust]` and `skills.require_signature`
+//! in the config.
+//!
+//! A signature is the string `ed25519:<public key>:<signature>` (both
+//! base64) stored either in a `SKILL.md.sig` sidecar next to SKILL.md or as a
+//! `signature:` frontmatter field. It covers the SKILL.md content with any
+//! frontmatter `signature:` line removed, so inline signing does not
+//! invalidate itself.
+//!
+//! `localgpt skills sign` signs with a local key kept in the data directory
+//! (`skill-signing.key`); its public key is always trusted.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use rand::RngExt;
+use std::fs;
+use std::path::Path;
+
+/// Sidecar file holding a detached signature for SKILL.md.
+pub const SIGNATURE_SIDECAR: &str = "SKILL.md.sig";
+
+const SIGNATURE_PREFIX: &str = "ed25519:";
+
+/// Signature status of a SKILL.md.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkillSignature {
+    /// No signature in frontmatter or sidecar
+    Unsigned,
+    /// Valid signature by a trusted key
+    Trusted { key: String },
+    /// Valid signature by a key that is not trusted
+    UnknownKey { key: String },
+    /// Signature present but malformed or not matching the content
+    Invalid(String),
+}
+
+impl SkillSignature {
+    pub fn is_trusted(&self) -> bool {
+        matches!(self, SkillSignature::Trusted { .. })
+    }
+
+    /// Human-readable status (e.g. "signed by untrusted key AbC…")
+    pub fn describe(&self) -> String {
+        match self {
+            SkillSignature::Unsigned => "unsigned".to_string(),
+            SkillSignature::Trusted { key } => format!("signed by trusted key {}", short_key(key)),
+            SkillSignature::UnknownKey { key } => {
+                format!("signed by untrusted key {}", short_key(key))
+            }
+            SkillSignature::Invalid(reason) => format!("invalid signature ({})", reason),
+        }
+    }
+}
+
+fn short_key(key: &str) -> String {
+    let short: String = key.chars().take(12).collect();
+    format!("{}…", short)
+}
+
+/// Content covered by a signature: SKILL.md without a frontmatter
+/// `signature:` line.
+fn signed_payload(content: &str) -> String {
+    let mut in_frontmatter = false;
+    let mut out = String::with_capacity(content.len());
+    for (i, line) in content.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+        if i == 0 && trimmed == "---" {
+            in_frontmatter = true;
+        } else if in_frontmatter && trimmed == "---" {
+            in_frontmatter = false;
+        } else if in_frontmatter && line.starts_with("signature:") {
+            continue;
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// The `signature:` value from SKILL.md frontmatter, if any.
+fn frontmatter_signature(content: &str) -> Option<String> {
+    let mut lines = content.lines();
+    if lines.next()?.trim() != "---" {
+        return None;
+    }
+    lines
+        .take_while(|l| l.trim() != "---")
+        .find_map(|l| l.strip_prefix("signature:"))
+        .map(|v| v.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+}
+
+/// Check the signature of `skill_md` against `trusted_keys` (base64 public keys).
+pub fn verify_skill_signature(skill_md: &Path, trusted_keys: &[String]) -> SkillSignature {
+    let Ok(content) = fs::read_to_string(skill_md) else {
+        return SkillSignature::Invalid("SKILL.md unreadable".to_string());
+    };
+    let sidecar = skill_md.with_file_name(SIGNATURE_SIDECAR);
+    let signature = match fs::read_to_string(&sidecar) {
+        Ok(s) => s.trim().to_string(),
+        Err(_) => match frontmatter_signature(&content) {
+            Some(s) => s,
+            None => return SkillSignature::Unsigned,
+        },
+    };
+
+    match verify_payload(&signed_payload(&content), &signature) {
+        Ok(key) if trusted_keys.iter().any(|k| k.trim() == key) => SkillSignature::Trusted { key },
+        Ok(key) => SkillSignature::UnknownKey { key },
+        Err(e) => SkillSignature::Invalid(e.to_string()),
+    }
+}
+
+/// Verify `signature` over `payload`, returning the signer's public key.
+fn verify_payload(payload: &str, signature: &str) -> Result<String> {
+    let rest = signature
+        .strip_prefix(SIGNATURE_PREFIX)
+        .context("expected ed25519:<key>:<signature>")?;
+    let (key_b64, sig_b64) = rest
+        .split_once(':')
+        .context("expected ed25519:<key>:<signature>")?;
+
+    let key_bytes: [u8; 32] = BASE64
+        .decode(key_b64)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .context("malformed public key")?;
+    let sig_bytes: [u8; 64] = BASE64
+        .decode(sig_b64)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .context("malformed signature")?;
+
+    let key = VerifyingKey::from_bytes(&key_bytes).context("malformed public key")?;
+    key.verify_strict(payload.as_bytes(), &Signature::from_bytes(&sig_bytes))
+        .map_err(|_| anyhow::anyhow!("does not match content"))?;
+    Ok(key_b64.to_string())
+}
+
+/// Sign `skill_md`, writing the signature to the sidecar or, with `inline`,
+/// to a `signature:` frontmatter field. Returns the signature string.
+pub fn sign_skill(skill_md: &Path, key: &SigningKey, inline: bool) -> Result<String> {
+    let content = fs::read_to_string(skill_md)
+        .with_context(|| format!("Failed to read {}", skill_md.display()))?;
+    let payload = signed_payload(&content);
+    let signature = format!(
+        "{}{}:{}",
+        SIGNATURE_PREFIX,
+        public_key(key),
+        BASE64.encode(key.sign(payload.as_bytes()).to_bytes())
+    );
+
+    let sidecar = skill_md.with_file_name(SIGNATURE_SIDECAR);
+    if inline {
+        anyhow::ensure!(
+            content.lines().next().map(str::trim) == Some("---"),
+            "SKILL.md has no frontmatter to hold an inline signature"
+        );
+        // Insert right after the opening `---`; the payload excludes this line
+        let (first, rest) = payload.split_once('\n').unwrap_or((&payload, ""));
+        fs::write(
+            skill_md,
+            format!("{}\nsignature: \"{}\"\n{}", first, signature, rest),
+        )?;
+        if sidecar.exists() {
+            fs::remove_file(&sidecar)?;
+        }
+    } else {
+        fs::write(&sidecar, format!("{}\n", signature))?;
+    }
+    Ok(signature)
+}
+
+/// Base64 public key for a signing key.
+pub fn public_key(key: &SigningKey) -> String {
+    BASE64.encode(key.verifying_key().to_bytes())
+}
+
+/// Load the local skill signing key, generating it on first use.
+pub fn load_or_create_signing_key(path: &Path) -> Result<SigningKey> {
+    if let Some(key) = read_signing_key(path)? {
+        return Ok(key);
+    }
+
+    let mut seed = [0u8; 32];
+    rand::rng().fill(&mut seed);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, seed).context("Failed to write skill signing key")?;
+
+    #[cfg(all(unix, not(target_os = "ios"), not(target_os = "android")))]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+    }
+
+    tracing::info!("Generated skill signing key at {}", path.display());
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Read the local skill signing key, if one has been created.
+pub fn read_signing_key(path: &Path) -> Result<Option<SigningKey>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let seed: [u8; 32] = fs::read(path)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Skill signing key has unexpected length"))?;
+    Ok(Some(SigningKey::from_bytes(&seed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKILL: &str = "---\nname: deploy\ndescription: Deploy\n---\n# Deploy\n";
+
+    fn setup() -> (tempfile::TempDir, std::path::PathBuf, SigningKey) {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill_md = tmp.path().join("SKILL.md");
+        fs::write(&skill_md, SKILL).unwrap();
+        let key = load_or_create_signing_key(&tmp.path().join("keys/skill.key")).unwrap();
+        (tmp, skill_md, key)
+    }
+
+    #[test]
+    fn sidecar_signature_roundtrip() {
+        let (_tmp, skill_md, key) = setup();
+        let trusted = vec![public_key(&key)];
+        assert_eq!(
+            verify_skill_signature(&skill_md, &trusted),
+            SkillSignature::Unsigned
+        );
+
+        sign_skill(&skill_md, &key, false).unwrap();
+        assert!(verify_skill_signature(&skill_md, &trusted).is_trusted());
+        assert!(matches!(
+            verify_skill_signature(&skill_md, &[]),
+            SkillSignature::UnknownKey { .. }
+        ));
+
+        fs::write(&skill_md, SKILL.replace("Deploy\n", "Deploy anywhere\n")).unwrap();
+        assert!(matches!(
+            verify_skill_signature(&skill_md, &trusted),
+            SkillSignature::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn inline_signature_roundtrip() {
+        let (tmp, skill_md, key) = setup();
+        let trusted = vec![public_key(&key)];
+
+        sign_skill(&skill_md, &key, true).unwrap();
+        let content = fs::read_to_string(&skill_md).unwrap();
+        assert!(content.starts_with("---\nsignature: \"ed25519:"));
+        assert!(!tmp.path().join(SIGNATURE_SIDECAR).exists());
+        assert!(verify_skill_signature(&skill_md, &trusted).is_trusted());
+
+        // Re-signing replaces the existing inline signature
+        sign_skill(&skill_md, &key, true).unwrap();
+        let content = fs::read_to_string(&skill_md).unwrap();
+        assert_eq!(content.matches("signature:").count(), 1);
+        assert!(verify_skill_signature(&skill_md, &trusted).is_trusted());
+    }
+
+    #[test]
+    fn signing_key_is_persisted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("skill.key");
+        assert!(read_signing_key(&path).unwrap().is_none());
+        let key = load_or_create_signing_key(&path).unwrap();
+        let again = load_or_create_signing_key(&path).unwrap();
+        assert_eq!(public_key(&key), public_key(&again));
+    }
+}
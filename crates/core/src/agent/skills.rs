@@ -4,6 +4,7 @@
 //! Supports multiple sources, requirements gating, slash command invocation, and routing rules.
 
 use anyhow::Result;
+use include_dir::{Dir, include_dir};
 use regex::Regex;
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
@@ -13,6 +14,12 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use tracing::{debug, warn};
 
+use super::skill_signing::{SkillSignature, public_key, read_signing_key, verify_skill_signature};
+use crate::config::{SkillTrustConfig, SkillTrustLevel};
+
+/// Core skills shipped with the binary (lowest precedence)
+static BUNDLED_SKILLS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/skills");
+
 /// Skill requirements for eligibility gating
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(default)]
@@ -121,6 +128,33 @@ impl RoutingCondition {
             }
         }
     }
+
+    /// Human-readable form, e.g. `contains "debug"` or `channel=telegram, hasTool=bash`
+    pub fn describe(&self) -> String {
+        match self {
+            RoutingCondition::Contains(text) => format!("contains \"{}\"", text),
+            RoutingCondition::Complex(complex) => {
+                let mut parts = Vec::new();
+                if let Some(text) = &complex.contains {
+                    parts.push(format!("contains \"{}\"", text));
+                }
+                if let Some(pattern) = &complex.matches {
+                    parts.push(format!("matches /{}/", pattern));
+                }
+                if let Some(channel) = &complex.channel {
+                    parts.push(format!("channel={}", channel));
+                }
+                if let Some(tool) = &complex.has_tool {
+                    parts.push(format!("hasTool={}", tool));
+                }
+                if parts.is_empty() {
+                    "(any message)".to_string()
+                } else {
+                    parts.join(", ")
+                }
+            }
+        }
+    }
 }
 
 /// Context for skill routing decisions
@@ -157,6 +191,62 @@ impl SkillRoutingContext {
     }
 }
 
+/// Type of a declared skill argument
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillArgType {
+    #[default]
+    String,
+    Number,
+    Boolean,
+}
+
+impl SkillArgType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SkillArgType::String => "string",
+            SkillArgType::Number => "number",
+            SkillArgType::Boolean => "boolean",
+        }
+    }
+}
+
+/// Named argument declared in SKILL.md frontmatter, substituted into
+/// `{{name}}` placeholders in the skill body on invocation
+#[derive(Debug, Clone, Deserialize)]
+pub struct SkillArgument {
+    pub name: String,
+
+    #[serde(default, rename = "type")]
+    pub arg_type: SkillArgType,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Value used when the argument is not given
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub default: Option<String>,
+
+    /// Invocation fails if a required argument without a default is missing
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Accept any YAML scalar (`default: 3`, `default: true`) as a string
+fn deserialize_scalar<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    match Option::<serde_yaml::Value>::deserialize(deserializer)? {
+        None | Some(serde_yaml::Value::Null) => Ok(None),
+        Some(serde_yaml::Value::String(s)) => Ok(Some(s)),
+        Some(serde_yaml::Value::Number(n)) => Ok(Some(n.to_string())),
+        Some(serde_yaml::Value::Bool(b)) => Ok(Some(b.to_string())),
+        Some(_) => Err(D::Error::custom("argument default must be a scalar")),
+    }
+}
+
 /// Frontmatter parsed from SKILL.md
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(default)]
@@ -167,6 +257,9 @@ pub struct SkillFrontmatter {
     /// Brief description
     pub description: Option<String>,
 
+    /// Skill version (semver recommended)
+    pub version: Option<String>,
+
     /// Whether this skill can be invoked via slash command (default: true)
     #[serde(default = "default_true", rename = "user-invocable")]
     pub user_invocable: bool,
@@ -194,6 +287,10 @@ pub struct SkillFrontmatter {
     /// Conditions for when NOT to use this skill (any match = skip)
     #[serde(default, rename = "dontUseWhen")]
     pub dont_use_when: Vec<RoutingCondition>,
+
+    /// Named arguments for slash command invocation
+    #[serde(default)]
+    pub arguments: Vec<SkillArgument>,
 }
 
 /// Wrapper for nested metadata (handles both flat and nested openclaw key)
@@ -224,6 +321,16 @@ pub enum SkillSource {
     Bundled,
 }
 
+impl SkillSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkillSource::Workspace => "workspace",
+            SkillSource::Managed => "managed",
+            SkillSource::Bundled => "bundled",
+        }
+    }
+}
+
 /// Eligibility status for a skill
 #[derive(Debug, Clone)]
 pub enum SkillEligibility {
@@ -235,12 +342,30 @@ pub enum SkillEligibility {
     MissingEnv(Vec<String>),
     /// Missing at least one of anyBins
     MissingAnyBins(Vec<String>),
+    /// Not signed by a trusted key, and the trust policy requires it
+    Untrusted(String),
+    /// Turned off with `localgpt skills disable`
+    Disabled,
 }
 
 impl SkillEligibility {
     pub fn is_ready(&self) -> bool {
         matches!(self, SkillEligibility::Ready)
     }
+
+    /// Human-readable status (e.g. "missing bins: jq")
+    pub fn describe(&self) -> String {
+        match self {
+            SkillEligibility::Ready => "ready".to_string(),
+            SkillEligibility::MissingBins(bins) => format!("missing bins: {}", bins.join(", ")),
+            SkillEligibility::MissingEnv(vars) => format!("missing env: {}", vars.join(", ")),
+            SkillEligibility::MissingAnyBins(bins) => {
+                format!("need one of: {}", bins.join(", "))
+            }
+            SkillEligibility::Untrusted(reason) => format!("untrusted: {}", reason),
+            SkillEligibility::Disabled => "disabled".to_string(),
+        }
+    }
 }
 
 /// A skill loaded from SKILL.md
@@ -258,6 +383,9 @@ pub struct Skill {
     /// Brief description
     pub description: String,
 
+    /// Version from frontmatter, if declared
+    pub version: Option<String>,
+
     /// Emoji for display
     pub emoji: Option<String>,
 
@@ -284,6 +412,12 @@ pub struct Skill {
 
     /// Conditions for when NOT to use this skill (any match = skip)
     pub dont_use_when: Vec<RoutingCondition>,
+
+    /// Declared arguments (empty = free-form arguments)
+    pub arguments: Vec<SkillArgument>,
+
+    /// Signature status, checked against the trust policy on load
+    pub signature: SkillSignature,
 }
 
 /// Command dispatch configuration for direct tool execution
@@ -338,35 +472,238 @@ impl Skill {
 }
 
 /// Load all skills from multiple sources
-/// Returns skills sorted by name with workspace skills taking priority over managed
-pub fn load_skills(workspace: &Path) -> Result<Vec<Skill>> {
+/// Returns skills sorted by name with workspace skills taking priority over
+/// managed, and managed over bundled
+pub fn load_skills(workspace: &Path, trust: &SkillTrustConfig) -> Result<Vec<Skill>> {
     let mut skills_map: HashMap<String, Skill> = HashMap::new();
 
-    // Load from managed directory first (lower priority)
+    for skill in load_skill_definitions(workspace, trust)? {
+        if let Some(shadowed) = skills_map.get(&skill.name)
+            && skill.source == SkillSource::Workspace
+            && shadowed.source == SkillSource::Managed
+            && shadowed.version != skill.version
+        {
+            warn!(
+                "Workspace skill '{}' ({}) shadows managed skill at a different version ({})",
+                skill.name,
+                skill.version.as_deref().unwrap_or("unversioned"),
+                shadowed.version.as_deref().unwrap_or("unversioned")
+            );
+        }
+        skills_map.insert(skill.name.clone(), skill);
+    }
+
+    // Convert to vec and sort
+    let mut skills: Vec<Skill> = skills_map.into_values().collect();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+
+    debug!("Loaded {} skills", skills.len());
+    Ok(skills)
+}
+
+/// Load every skill definition from all sources, lowest precedence first:
+/// bundled, then managed, then workspace. A later definition of a name
+/// shadows the earlier ones. Signatures are checked against `trust`.
+pub fn load_skill_definitions(workspace: &Path, trust: &SkillTrustConfig) -> Result<Vec<Skill>> {
+    let mut skills = Vec::new();
+
+    // Bundled skills first (lowest priority)
+    if let Some(bundled_dir) = get_bundled_skills_dir() {
+        match extract_bundled_skills(&bundled_dir) {
+            Ok(()) => skills.extend(load_skills_from_dir(&bundled_dir, SkillSource::Bundled)?),
+            Err(e) => warn!("Failed to extract bundled skills: {}", e),
+        }
+    }
+
+    // Managed directory (overrides bundled)
     if let Some(managed_dir) = get_managed_skills_dir()
         && managed_dir.exists()
     {
-        for skill in load_skills_from_dir(&managed_dir, SkillSource::Managed)? {
-            skills_map.insert(skill.name.clone(), skill);
-        }
+        skills.extend(load_skills_from_dir(&managed_dir, SkillSource::Managed)?);
     }
 
-    // Load from workspace (higher priority, overwrites managed)
+    // Workspace (highest priority)
     let workspace_skills_dir = workspace.join("skills");
     if workspace_skills_dir.exists() {
-        for skill in load_skills_from_dir(&workspace_skills_dir, SkillSource::Workspace)? {
-            skills_map.insert(skill.name.clone(), skill);
-        }
+        skills.extend(load_skills_from_dir(
+            &workspace_skills_dir,
+            SkillSource::Workspace,
+        )?);
     }
 
-    // Convert to vec and sort
-    let mut skills: Vec<Skill> = skills_map.into_values().collect();
-    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    let trusted_keys = trusted_skill_keys(trust);
+    let disabled = crate::paths::Paths::resolve()
+        .map(|paths| {
+            load_disabled_skills(&paths.disabled_skills_file()).unwrap_or_else(|e| {
+                warn!("Failed to read disabled skills: {}", e);
+                Vec::new()
+            })
+        })
+        .unwrap_or_default();
+    for skill in &mut skills {
+        apply_trust_policy(skill, trust, &trusted_keys);
+        if disabled.contains(&skill.name) {
+            skill.eligibility = SkillEligibility::Disabled;
+        }
+    }
 
-    debug!("Loaded {} skills", skills.len());
     Ok(skills)
 }
 
+/// Names of the skills turned off with `localgpt skills disable`.
+pub fn load_disabled_skills(path: &Path) -> Result<Vec<String>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Turn a skill off or back on. Returns whether anything changed.
+pub fn set_skill_disabled(path: &Path, name: &str, disabled: bool) -> Result<bool> {
+    let mut names = load_disabled_skills(path)?;
+    let present = names.iter().any(|n| n == name);
+    if present == disabled {
+        return Ok(false);
+    }
+    if disabled {
+        names.push(name.to_string());
+        names.sort();
+    } else {
+        names.retain(|n| n != name);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(&names)?)?;
+    Ok(true)
+}
+
+/// Public keys whose skill signatures are trusted: the configured keys plus
+/// the local signing key used by `localgpt skills sign`.
+pub fn trusted_skill_keys(trust: &SkillTrustConfig) -> Vec<String> {
+    let mut keys = trust.trusted_keys.clone();
+    if let Ok(paths) = crate::paths::Paths::resolve()
+        && let Ok(Some(key)) = read_signing_key(&paths.skill_signing_key())
+    {
+        keys.push(public_key(&key));
+    }
+    keys
+}
+
+/// Trust policy level for skills from `source`. Bundled skills ship in the
+/// binary and are always trusted.
+pub fn trust_level_for(source: &SkillSource, trust: &SkillTrustConfig) -> SkillTrustLevel {
+    match source {
+        SkillSource::Managed => trust.managed,
+        SkillSource::Workspace => trust.workspace,
+        SkillSource::Bundled => SkillTrustLevel::Off,
+    }
+}
+
+/// Verify a skill's signature and apply the trust policy for its source:
+/// `require` makes an untrusted skill ineligible, `warn` logs it.
+fn apply_trust_policy(skill: &mut Skill, trust: &SkillTrustConfig, trusted_keys: &[String]) {
+    skill.signature = verify_skill_signature(&skill.path, trusted_keys);
+    if skill.signature.is_trusted() {
+        return;
+    }
+    match trust_level_for(&skill.source, trust) {
+        SkillTrustLevel::Off => {}
+        SkillTrustLevel::Warn => warn!(
+            "{} skill '{}' is {}",
+            skill.source.as_str(),
+            skill.name,
+            skill.signature.describe()
+        ),
+        SkillTrustLevel::Require => {
+            skill.eligibility = SkillEligibility::Untrusted(skill.signature.describe());
+        }
+    }
+}
+
+/// Result of evaluating one routing condition against a message.
+#[derive(Debug, Clone)]
+pub struct ConditionCheck {
+    pub condition: String,
+    pub matched: bool,
+}
+
+/// Why a skill would or would not be offered for a message.
+#[derive(Debug, Clone)]
+pub struct SkillDiagnosis {
+    /// Effective definition after source precedence
+    pub skill: Skill,
+    /// Lower-precedence definitions of the same name, nearest first
+    pub shadowed: Vec<Skill>,
+    pub use_when: Vec<ConditionCheck>,
+    pub dont_use_when: Vec<ConditionCheck>,
+    /// Whether the skill would be listed in the system prompt
+    pub included: bool,
+    /// Short explanation of `included`
+    pub reason: String,
+}
+
+/// Explain skill precedence and routing for a message. `definitions` is the
+/// output of [`load_skill_definitions`]; results are sorted by skill name.
+pub fn diagnose_skills(definitions: Vec<Skill>, ctx: &SkillRoutingContext) -> Vec<SkillDiagnosis> {
+    let mut by_name: HashMap<String, Vec<Skill>> = HashMap::new();
+    for skill in definitions {
+        by_name.entry(skill.name.clone()).or_default().push(skill);
+    }
+
+    let mut diagnoses: Vec<SkillDiagnosis> = by_name
+        .into_values()
+        .filter_map(|mut defs| {
+            let skill = defs.pop()?;
+            defs.reverse();
+            Some(diagnose_skill(skill, defs, ctx))
+        })
+        .collect();
+    diagnoses.sort_by(|a, b| a.skill.name.cmp(&b.skill.name));
+    diagnoses
+}
+
+fn diagnose_skill(skill: Skill, shadowed: Vec<Skill>, ctx: &SkillRoutingContext) -> SkillDiagnosis {
+    let check = |conditions: &[RoutingCondition]| -> Vec<ConditionCheck> {
+        conditions
+            .iter()
+            .map(|c| ConditionCheck {
+                condition: c.describe(),
+                matched: c.matches(ctx),
+            })
+            .collect()
+    };
+    let use_when = check(&skill.use_when);
+    let dont_use_when = check(&skill.dont_use_when);
+
+    // Same order of checks as build_skills_prompt / should_use
+    let (included, reason) = if skill.disable_model_invocation {
+        (false, "disableModelInvocation is set".to_string())
+    } else if !skill.eligibility.is_ready() {
+        (
+            false,
+            format!("not eligible ({})", skill.eligibility.describe()),
+        )
+    } else if dont_use_when.iter().any(|c| c.matched) {
+        (false, "a dontUseWhen condition matched".to_string())
+    } else if use_when.is_empty() {
+        (true, "no routing rules".to_string())
+    } else if use_when.iter().any(|c| c.matched) {
+        (true, "a useWhen condition matched".to_string())
+    } else {
+        (false, "no useWhen condition matched".to_string())
+    };
+
+    SkillDiagnosis {
+        skill,
+        shadowed,
+        use_when,
+        dont_use_when,
+        included,
+        reason,
+    }
+}
+
 /// Get the managed skills directory (data_dir/skills)
 fn get_managed_skills_dir() -> Option<PathBuf> {
     crate::paths::Paths::resolve()
@@ -374,6 +711,36 @@ fn get_managed_skills_dir() -> Option<PathBuf> {
         .map(|paths| paths.managed_skills_dir())
 }
 
+/// Get the directory bundled skills are extracted to (cache_dir/bundled-skills)
+fn get_bundled_skills_dir() -> Option<PathBuf> {
+    crate::paths::Paths::resolve()
+        .ok()
+        .map(|paths| paths.bundled_skills_dir())
+}
+
+/// Write the embedded skills to `dir` so they can be read like any other
+/// skill file. Files are only rewritten when their content changed.
+pub fn extract_bundled_skills(dir: &Path) -> Result<()> {
+    extract_dir(&BUNDLED_SKILLS, dir)
+}
+
+fn extract_dir(embedded: &Dir<'_>, dir: &Path) -> Result<()> {
+    for file in embedded.files() {
+        let target = dir.join(file.path());
+        if fs::read(&target).is_ok_and(|existing| existing == file.contents()) {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, file.contents())?;
+    }
+    for sub in embedded.dirs() {
+        extract_dir(sub, dir)?;
+    }
+    Ok(())
+}
+
 /// Load skills from a single directory
 fn load_skills_from_dir(dir: &Path, source: SkillSource) -> Result<Vec<Skill>> {
     let mut skills = Vec::new();
@@ -465,6 +832,7 @@ fn load_skill(path: &Path, dir_name: &str, source: SkillSource) -> Result<Skill>
         command_name,
         path: path.to_path_buf(),
         description,
+        version: frontmatter.version,
         emoji,
         source,
         user_invocable: frontmatter.user_invocable,
@@ -474,6 +842,8 @@ fn load_skill(path: &Path, dir_name: &str, source: SkillSource) -> Result<Skill>
         eligibility,
         use_when: frontmatter.use_when,
         dont_use_when: frontmatter.dont_use_when,
+        arguments: frontmatter.arguments,
+        signature: SkillSignature::Unsigned,
     })
 }
 
@@ -511,6 +881,40 @@ fn parse_frontmatter(content: &str) -> (SkillFrontmatter, String) {
     }
 }
 
+/// Parse frontmatter, failing instead of falling back to defaults.
+/// Used to validate skills before installation.
+fn parse_frontmatter_strict(content: &str) -> Result<SkillFrontmatter> {
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.first().map(|l| l.trim()) != Some("---") {
+        anyhow::bail!("SKILL.md has no YAML frontmatter (expected a leading '---')");
+    }
+    let end_idx = lines
+        .iter()
+        .skip(1)
+        .position(|l| l.trim() == "---")
+        .map(|i| i + 1)
+        .ok_or_else(|| anyhow::anyhow!("SKILL.md frontmatter is not closed with '---'"))?;
+
+    let frontmatter: SkillFrontmatter = serde_yaml::from_str(&lines[1..end_idx].join("\n"))
+        .map_err(|e| anyhow::anyhow!("Invalid SKILL.md frontmatter: {}", e))?;
+
+    if frontmatter
+        .name
+        .as_deref()
+        .is_none_or(|n| n.trim().is_empty())
+    {
+        anyhow::bail!("SKILL.md frontmatter is missing 'name'");
+    }
+    if frontmatter
+        .description
+        .as_deref()
+        .is_none_or(|d| d.trim().is_empty())
+    {
+        anyhow::bail!("SKILL.md frontmatter is missing 'description'");
+    }
+    Ok(frontmatter)
+}
+
 /// Extract description from markdown body
 fn extract_description_from_body(body: &str) -> String {
     for line in body.lines() {
@@ -587,6 +991,177 @@ fn has_binary(name: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Locate the skill directory inside a downloaded or cloned source.
+///
+/// Returns `dir` itself if it contains SKILL.md, otherwise the single
+/// subdirectory (directly or under `skills/`) that does.
+pub fn find_skill_root(dir: &Path) -> Result<PathBuf> {
+    if dir.join("SKILL.md").is_file() {
+        return Ok(dir.to_path_buf());
+    }
+
+    let mut candidates = Vec::new();
+    for parent in [dir.to_path_buf(), dir.join("skills")] {
+        let Ok(entries) = fs::read_dir(&parent) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() && path.join("SKILL.md").is_file() {
+                candidates.push(path);
+            }
+        }
+    }
+    candidates.sort();
+
+    match candidates.len() {
+        0 => anyhow::bail!("No SKILL.md found in {}", dir.display()),
+        1 => Ok(candidates.remove(0)),
+        _ => {
+            let names: Vec<String> = candidates
+                .iter()
+                .filter_map(|p| p.strip_prefix(dir).ok())
+                .map(|p| p.display().to_string())
+                .collect();
+            anyhow::bail!(
+                "Multiple skills found ({}); choose one with --path",
+                names.join(", ")
+            )
+        }
+    }
+}
+
+/// Validate a skill directory without installing it.
+///
+/// Unlike loading, this rejects SKILL.md files whose frontmatter is missing,
+/// malformed, or lacks a name and description.
+pub fn validate_skill_dir(dir: &Path) -> Result<Skill> {
+    let skill_file = dir.join("SKILL.md");
+    if !skill_file.is_file() {
+        anyhow::bail!("No SKILL.md found in {}", dir.display());
+    }
+
+    let frontmatter = parse_frontmatter_strict(&fs::read_to_string(&skill_file)?)?;
+    let name = frontmatter.name.unwrap_or_default();
+    if sanitize_command_name(&name).is_empty() {
+        anyhow::bail!("Skill name '{}' has no usable characters", name);
+    }
+
+    load_skill(&skill_file, &name, SkillSource::Managed)
+}
+
+/// Copy a skill directory into `managed_dir` under its command name.
+///
+/// Hidden entries (such as `.git`) and symlinks are not copied. An existing
+/// skill with the same name is only replaced when `force` is set.
+pub fn install_skill_dir(src: &Path, managed_dir: &Path, force: bool) -> Result<Skill> {
+    let skill = validate_skill_dir(src)?;
+    let target = managed_dir.join(&skill.command_name);
+
+    if target.exists() {
+        if !force {
+            anyhow::bail!(
+                "Skill '{}' is already installed at {} (use --force to replace it)",
+                skill.name,
+                target.display()
+            );
+        }
+        fs::remove_dir_all(&target)?;
+    }
+
+    fs::create_dir_all(managed_dir)?;
+    copy_skill_files(src, &target)?;
+    load_skill(&target.join("SKILL.md"), &skill.name, SkillSource::Managed)
+}
+
+fn copy_skill_files(src: &Path, dst: &Path) -> Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        let target = dst.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_skill_files(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove a managed skill by name, command name, or directory name.
+/// Returns the removed directory.
+pub fn remove_managed_skill(managed_dir: &Path, name: &str) -> Result<PathBuf> {
+    if managed_dir.is_dir() {
+        let command_name = sanitize_command_name(name);
+        for skill in load_skills_from_dir(managed_dir, SkillSource::Managed)? {
+            let Some(dir) = skill.path.parent() else {
+                continue;
+            };
+            if skill.name == name
+                || skill.command_name == command_name
+                || dir.file_name().is_some_and(|d| d == name)
+            {
+                fs::remove_dir_all(dir)?;
+                return Ok(dir.to_path_buf());
+            }
+        }
+    }
+    anyhow::bail!("No managed skill named '{}'", name)
+}
+
+/// Create `skills_dir/<name>/SKILL.md` from a template with commented-out
+/// routing rules and arguments. Returns the new SKILL.md path.
+pub fn scaffold_skill(skills_dir: &Path, name: &str) -> Result<PathBuf> {
+    let command_name = sanitize_command_name(name);
+    if command_name.is_empty() {
+        anyhow::bail!("Skill name '{}' has no usable characters", name);
+    }
+    let dir = skills_dir.join(&command_name);
+    if dir.exists() {
+        anyhow::bail!("{} already exists", dir.display());
+    }
+
+    let template = format!(
+        r#"---
+name: {command_name}
+description: One line on what this skill does and when to use it
+version: 0.1.0
+# Only offer the skill for matching messages (any match):
+# useWhen:
+#   - contains: "deploy"
+#   - channel: telegram
+# Never offer it for these (any match):
+# dontUseWhen:
+#   - matches: "^/"
+# Named arguments for /{command_name}, substituted into {{{{name}}}} below:
+# arguments:
+#   - name: target
+#     description: What to act on
+#     required: true
+# Binaries and environment variables the skill needs:
+# metadata:
+#   openclaw:
+#     requires:
+#       bins: [git]
+#       env: [GITHUB_TOKEN]
+---
+
+# {command_name}
+
+Describe the steps the agent should follow when this skill applies.
+"#
+    );
+
+    fs::create_dir_all(&dir)?;
+    let skill_file = dir.join("SKILL.md");
+    fs::write(&skill_file, template)?;
+    Ok(skill_file)
+}
+
 /// Result of parsing a slash command
 #[derive(Debug, Clone)]
 pub struct SkillInvocation {
@@ -640,6 +1215,204 @@ pub fn parse_skill_command(input: &str, skills: &[Skill]) -> Option<SkillInvocat
     None
 }
 
+/// Split invocation arguments into `name=value` pairs and positional values.
+/// Values may be single- or double-quoted to include whitespace.
+fn tokenize_skill_args(input: &str) -> Result<(Vec<(String, String)>, Vec<String>)> {
+    let mut named = Vec::new();
+    let mut positional = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut token = String::new();
+        let mut key = None;
+        let mut quote = None;
+        while let Some(c) = chars.next() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => token.push(c),
+                None if c == '"' || c == '\'' => quote = Some(c),
+                None if c.is_whitespace() => break,
+                None if c == '=' && key.is_none() && is_arg_name(&token) => {
+                    key = Some(std::mem::take(&mut token));
+                }
+                None => token.push(c),
+            }
+        }
+        if quote.is_some() {
+            anyhow::bail!("Unterminated quote in skill arguments");
+        }
+
+        match key {
+            Some(key) => named.push((key, token)),
+            None => positional.push(token),
+        }
+    }
+
+    Ok((named, positional))
+}
+
+fn is_arg_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Bind invocation arguments to a skill's declared arguments.
+///
+/// Named values (`key=value`) are matched by name; any remaining free text
+/// goes to the first argument not given by name. Defaults are applied and
+/// types are checked.
+pub fn bind_skill_args(skill: &Skill, input: &str) -> Result<HashMap<String, String>> {
+    let (named, positional) = tokenize_skill_args(input)?;
+    let mut values: HashMap<String, String> = HashMap::new();
+
+    for (key, value) in named {
+        if !skill.arguments.iter().any(|a| a.name == key) {
+            anyhow::bail!("Unknown argument '{}' for /{}", key, skill.command_name);
+        }
+        values.insert(key, value);
+    }
+
+    if !positional.is_empty() {
+        let Some(first) = skill
+            .arguments
+            .iter()
+            .find(|a| !values.contains_key(&a.name))
+        else {
+            anyhow::bail!("Too many arguments for /{}", skill.command_name);
+        };
+        values.insert(first.name.clone(), positional.join(" "));
+    }
+
+    for arg in &skill.arguments {
+        let value = match values.get(&arg.name) {
+            Some(value) => value.clone(),
+            None => match (&arg.default, arg.required) {
+                (Some(default), _) => default.clone(),
+                (None, true) => anyhow::bail!("Missing required argument '{}'", arg.name),
+                (None, false) => String::new(),
+            },
+        };
+        let valid = match arg.arg_type {
+            SkillArgType::String => true,
+            SkillArgType::Number => value.is_empty() || value.parse::<f64>().is_ok(),
+            SkillArgType::Boolean => {
+                value.is_empty() || matches!(value.as_str(), "true" | "false" | "yes" | "no")
+            }
+        };
+        if !valid {
+            anyhow::bail!(
+                "Argument '{}' must be a {} (got '{}')",
+                arg.name,
+                arg.arg_type.as_str(),
+                value
+            );
+        }
+        values.insert(arg.name.clone(), value);
+    }
+
+    Ok(values)
+}
+
+/// Usage line for a skill with declared arguments, e.g.
+/// `/github-pr title=<string> [base=main]`
+pub fn skill_usage(skill: &Skill) -> String {
+    let mut usage = format!("/{}", skill.command_name);
+    for arg in &skill.arguments {
+        let value = match &arg.default {
+            Some(default) => default.clone(),
+            None => format!("<{}>", arg.arg_type.as_str()),
+        };
+        if arg.required && arg.default.is_none() {
+            usage.push_str(&format!(" {}={}", arg.name, value));
+        } else {
+            usage.push_str(&format!(" [{}={}]", arg.name, value));
+        }
+    }
+    usage
+}
+
+/// Instantiate a skill's body with invocation arguments.
+///
+/// Returns `None` for skills without declared arguments, which keep the
+/// free-form behavior. Otherwise the SKILL.md body (without frontmatter) is
+/// returned with every `{{name}}` placeholder replaced by its bound value.
+pub fn instantiate_skill(skill: &Skill, input: &str) -> Result<Option<String>> {
+    if skill.arguments.is_empty() {
+        return Ok(None);
+    }
+
+    let values = bind_skill_args(skill, input)?;
+    let content = fs::read_to_string(&skill.path)?;
+    let (_, body) = parse_frontmatter(&content);
+    Ok(Some(substitute_placeholders(body.trim(), &values)))
+}
+
+/// Build the message sent to the agent for a `/skill args` invocation.
+///
+/// Skills with declared arguments are instantiated inline; others point the
+/// agent at the SKILL.md to read. Argument errors include the usage line.
+pub fn skill_invocation_prompt(skill: &Skill, args: &str) -> Result<String> {
+    let instantiated = instantiate_skill(skill, args)
+        .map_err(|e| anyhow::anyhow!("{}\nUsage: {}", e, skill_usage(skill)))?;
+
+    Ok(if let Some(body) = instantiated {
+        format!(
+            "Follow the skill \"{}\" ({}), instantiated with the given arguments:\n\n{}",
+            skill.name,
+            skill.path.display(),
+            body
+        )
+    } else if args.is_empty() {
+        format!(
+            "Use the skill at {}. Read it first, then follow its instructions.",
+            skill.path.display()
+        )
+    } else {
+        format!(
+            "Use the skill at {} with this request: {}\n\nRead the skill file first, then follow its instructions.",
+            skill.path.display(),
+            args
+        )
+    })
+}
+
+/// Skills to offer as slash commands on chat surfaces: invocable now and not
+/// shadowed by a built-in command of the same name.
+pub fn slash_command_skills(skills: &[Skill]) -> Vec<&Skill> {
+    skills
+        .iter()
+        .filter(|s| s.can_invoke() && !crate::commands::is_builtin_command(&s.command_name))
+        .collect()
+}
+
+/// Replace `{{name}}` placeholders; unknown names are left untouched.
+pub(crate) fn substitute_placeholders(template: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        match values.get(after[..end].trim()) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
 /// Build skills prompt section for the system prompt
 /// If routing context is provided, skills are filtered through routing rules
 pub fn build_skills_prompt(skills: &[Skill], routing_ctx: Option<&SkillRoutingContext>) -> String {
@@ -659,6 +1432,12 @@ pub fn build_skills_prompt(skills: &[Skill], routing_ctx: Option<&SkillRoutingCo
         })
         .collect();
 
+    build_routed_skills_prompt(skills, &prompt_skills)
+}
+
+/// Build the skills prompt section for an already chosen set of skills (e.g.
+/// by semantic routing). Slash commands still list every invocable skill.
+pub fn build_routed_skills_prompt(skills: &[Skill], prompt_skills: &[&Skill]) -> String {
     if prompt_skills.is_empty() {
         return String::new();
     }
@@ -673,7 +1452,7 @@ pub fn build_skills_prompt(skills: &[Skill], routing_ctx: Option<&SkillRoutingCo
         "<available_skills>".to_string(),
     ];
 
-    for skill in &prompt_skills {
+    for skill in prompt_skills {
         let emoji_prefix = skill
             .emoji
             .as_ref()
@@ -749,14 +1528,12 @@ pub fn get_skills_summary(skills: &[Skill]) -> String {
                 .as_ref()
                 .map(|e| format!(" {}", e))
                 .unwrap_or_default();
-            let source = match skill.source {
-                SkillSource::Workspace => "[workspace]",
-                SkillSource::Managed => "[managed]",
-                SkillSource::Bundled => "[bundled]",
-            };
             lines.push(format!(
-                "  /{}{} - {} {}",
-                skill.command_name, emoji, skill.description, source
+                "  /{}{} - {} [{}]",
+                skill.command_name,
+                emoji,
+                skill.description,
+                skill.source.as_str()
             ));
         }
     }
@@ -765,15 +1542,22 @@ pub fn get_skills_summary(skills: &[Skill]) -> String {
         lines.push(String::new());
         lines.push("Blocked:".to_string());
         for skill in &blocked {
-            let reason = match &skill.eligibility {
-                SkillEligibility::Ready => "ready".to_string(),
-                SkillEligibility::MissingBins(bins) => format!("missing bins: {}", bins.join(", ")),
-                SkillEligibility::MissingEnv(vars) => format!("missing env: {}", vars.join(", ")),
-                SkillEligibility::MissingAnyBins(bins) => {
-                    format!("need one of: {}", bins.join(", "))
-                }
-            };
-            lines.push(format!("  {} - {}", skill.name, reason));
+            lines.push(format!(
+                "  {} - {}",
+                skill.name,
+                skill.eligibility.describe()
+            ));
+        }
+        if blocked
+            .iter()
+            .any(|s| matches!(s.eligibility, SkillEligibility::Untrusted(_)))
+        {
+            lines.push(String::new());
+            lines.push(
+                "Untrusted skills need a signature from a trusted key \
+                 (`localgpt skills sign`, or skills.trust.trusted_keys)."
+                    .to_string(),
+            );
         }
     }
 
@@ -872,6 +1656,7 @@ A skill for doing things.
             command_name: "github-pr".to_string(),
             path: PathBuf::from("/test/SKILL.md"),
             description: "Create PRs".to_string(),
+            version: None,
             emoji: Some("🐙".to_string()),
             source: SkillSource::Workspace,
             user_invocable: true,
@@ -881,6 +1666,8 @@ A skill for doing things.
             eligibility: SkillEligibility::Ready,
             use_when: vec![],
             dont_use_when: vec![],
+            arguments: vec![],
+            signature: SkillSignature::Unsigned,
         }];
 
         // Match by command name
@@ -1027,6 +1814,7 @@ dontUseWhen:
             command_name: "test".to_string(),
             path: PathBuf::from("/test/SKILL.md"),
             description: "Test".to_string(),
+            version: None,
             emoji: None,
             source: SkillSource::Workspace,
             user_invocable: true,
@@ -1036,6 +1824,8 @@ dontUseWhen:
             eligibility: SkillEligibility::Ready,
             use_when: vec![],
             dont_use_when: vec![],
+            arguments: vec![],
+            signature: SkillSignature::Unsigned,
         };
 
         let ctx = SkillRoutingContext::new("any message", "any_channel");
@@ -1050,6 +1840,7 @@ dontUseWhen:
             command_name: "debug-skill".to_string(),
             path: PathBuf::from("/test/SKILL.md"),
             description: "Debug helper".to_string(),
+            version: None,
             emoji: None,
             source: SkillSource::Workspace,
             user_invocable: true,
@@ -1062,6 +1853,8 @@ dontUseWhen:
                 RoutingCondition::Contains("error".to_string()),
             ],
             dont_use_when: vec![],
+            arguments: vec![],
+            signature: SkillSignature::Unsigned,
         };
 
         // Should match "debug"
@@ -1085,6 +1878,7 @@ dontUseWhen:
             command_name: "serious-skill".to_string(),
             path: PathBuf::from("/test/SKILL.md"),
             description: "Serious stuff".to_string(),
+            version: None,
             emoji: None,
             source: SkillSource::Workspace,
             user_invocable: true,
@@ -1094,6 +1888,8 @@ dontUseWhen:
             eligibility: SkillEligibility::Ready,
             use_when: vec![],
             dont_use_when: vec![RoutingCondition::Contains("joke".to_string())],
+            arguments: vec![],
+            signature: SkillSignature::Unsigned,
         };
 
         // Should be blocked by dontUseWhen
@@ -1113,6 +1909,7 @@ dontUseWhen:
             command_name: "code-review".to_string(),
             path: PathBuf::from("/test/SKILL.md"),
             description: "Code review".to_string(),
+            version: None,
             emoji: None,
             source: SkillSource::Workspace,
             user_invocable: true,
@@ -1125,6 +1922,8 @@ dontUseWhen:
                 RoutingCondition::Contains("review".to_string()),
             ],
             dont_use_when: vec![RoutingCondition::Contains("joke".to_string())],
+            arguments: vec![],
+            signature: SkillSignature::Unsigned,
         };
 
         // Matches useWhen
@@ -1140,6 +1939,332 @@ dontUseWhen:
         assert!(!skill.should_use(&ctx_weather));
     }
 
+    fn write_skill(dir: &Path, frontmatter: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("SKILL.md"),
+            format!("---\n{}\n---\n\n# Skill\n", frontmatter),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_validate_skill_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+
+        write_skill(tmp.path(), "name: Git Helper\ndescription: Git tips");
+        let skill = validate_skill_dir(tmp.path()).unwrap();
+        assert_eq!(skill.command_name, "git-helper");
+        assert_eq!(skill.source, SkillSource::Managed);
+
+        write_skill(tmp.path(), "name: git-helper");
+        let err = validate_skill_dir(tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("description"));
+
+        write_skill(tmp.path(), "name: [unclosed");
+        assert!(validate_skill_dir(tmp.path()).is_err());
+
+        fs::write(tmp.path().join("SKILL.md"), "# No frontmatter\n").unwrap();
+        assert!(validate_skill_dir(tmp.path()).is_err());
+    }
+
+    #[test]
+    fn test_find_skill_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(find_skill_root(tmp.path()).is_err());
+
+        write_skill(&tmp.path().join("skills/one"), "name: one\ndescription: d");
+        assert_eq!(
+            find_skill_root(tmp.path()).unwrap(),
+            tmp.path().join("skills/one")
+        );
+
+        write_skill(&tmp.path().join("two"), "name: two\ndescription: d");
+        let err = find_skill_root(tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("--path"));
+    }
+
+    #[test]
+    fn test_install_and_remove_skill() {
+        let src = tempfile::tempdir().unwrap();
+        let managed = tempfile::tempdir().unwrap();
+        write_skill(src.path(), "name: Deploy\ndescription: Ship it");
+        fs::create_dir_all(src.path().join(".git")).unwrap();
+        fs::create_dir_all(src.path().join("scripts")).unwrap();
+        fs::write(src.path().join("scripts/run.sh"), "echo hi").unwrap();
+
+        let skill = install_skill_dir(src.path(), managed.path(), false).unwrap();
+        let target = managed.path().join("deploy");
+        assert_eq!(skill.path, target.join("SKILL.md"));
+        assert!(target.join("scripts/run.sh").is_file());
+        assert!(!target.join(".git").exists());
+
+        assert!(install_skill_dir(src.path(), managed.path(), false).is_err());
+        assert!(install_skill_dir(src.path(), managed.path(), true).is_ok());
+
+        assert!(remove_managed_skill(managed.path(), "missing").is_err());
+        assert_eq!(
+            remove_managed_skill(managed.path(), "Deploy").unwrap(),
+            target
+        );
+        assert!(!target.exists());
+    }
+
+    fn skill_with_args(dir: &Path) -> Skill {
+        write_skill(
+            dir,
+            r#"name: github-pr
+description: Open a pull request
+arguments:
+  - name: title
+    required: true
+  - name: base
+    default: main
+  - name: draft
+    type: boolean
+    default: false
+  - name: reviewers
+    type: number"#,
+        );
+        fs::write(
+            dir.join("SKILL.md"),
+            fs::read_to_string(dir.join("SKILL.md")).unwrap()
+                + "Open a PR titled \"{{title}}\" against {{ base }} (draft: {{draft}}). Keep {{unknown}}.\n",
+        )
+        .unwrap();
+        validate_skill_dir(dir).unwrap()
+    }
+
+    #[test]
+    fn test_bind_skill_args() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill = skill_with_args(tmp.path());
+        assert_eq!(skill.arguments.len(), 4);
+        assert_eq!(skill.arguments[2].default.as_deref(), Some("false"));
+
+        let values = bind_skill_args(&skill, r#"title="Fix the bug" base=dev"#).unwrap();
+        assert_eq!(values["title"], "Fix the bug");
+        assert_eq!(values["base"], "dev");
+        assert_eq!(values["draft"], "false");
+        assert_eq!(values["reviewers"], "");
+
+        // Free text goes to the first argument not given by name
+        let values = bind_skill_args(&skill, "base=dev Fix the bug").unwrap();
+        assert_eq!(values["title"], "Fix the bug");
+
+        assert!(bind_skill_args(&skill, "base=dev").is_err());
+        assert!(bind_skill_args(&skill, "title=x color=red").is_err());
+        assert!(bind_skill_args(&skill, "title=x reviewers=two").is_err());
+        assert!(bind_skill_args(&skill, "title=\"unterminated").is_err());
+
+        assert_eq!(
+            skill_usage(&skill),
+            "/github-pr title=<string> [base=main] [draft=false] [reviewers=<number>]"
+        );
+    }
+
+    #[test]
+    fn test_instantiate_skill() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill = skill_with_args(tmp.path());
+        let body = instantiate_skill(&skill, "title='Add docs'")
+            .unwrap()
+            .unwrap();
+        assert!(body.starts_with("# Skill"));
+        assert!(body.contains(r#"Open a PR titled "Add docs" against main (draft: false)."#));
+        assert!(body.contains("Keep {{unknown}}."));
+
+        write_skill(tmp.path(), "name: plain\ndescription: No args");
+        let plain = validate_skill_dir(tmp.path()).unwrap();
+        assert!(instantiate_skill(&plain, "anything").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_skill_invocation_prompt() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill = skill_with_args(tmp.path());
+        let prompt = skill_invocation_prompt(&skill, "title=Docs").unwrap();
+        assert!(prompt.starts_with("Follow the skill"));
+        assert!(prompt.contains(r#"Open a PR titled "Docs""#));
+
+        let err = skill_invocation_prompt(&skill, "").unwrap_err().to_string();
+        assert!(err.contains("Usage: /"));
+
+        write_skill(tmp.path(), "name: plain\ndescription: No args");
+        let plain = validate_skill_dir(tmp.path()).unwrap();
+        assert!(
+            skill_invocation_prompt(&plain, "")
+                .unwrap()
+                .starts_with("Use the skill at")
+        );
+        assert!(
+            skill_invocation_prompt(&plain, "fix it")
+                .unwrap()
+                .contains("with this request: fix it")
+        );
+    }
+
+    #[test]
+    fn test_slash_command_skills() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(tmp.path(), "name: deploy\ndescription: Deploy");
+        let deploy = validate_skill_dir(tmp.path()).unwrap();
+        let mut help = deploy.clone();
+        help.command_name = "help".to_string();
+        let mut hidden = deploy.clone();
+        hidden.command_name = "hidden".to_string();
+        hidden.user_invocable = false;
+
+        let skills = vec![deploy, help, hidden];
+        let names: Vec<_> = slash_command_skills(&skills)
+            .iter()
+            .map(|s| s.command_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["deploy"]);
+    }
+
+    #[test]
+    fn test_apply_trust_policy() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(tmp.path(), "name: deploy\ndescription: Deploy");
+        let key =
+            crate::agent::skill_signing::load_or_create_signing_key(&tmp.path().join("skill.key"))
+                .unwrap();
+        let trusted = vec![public_key(&key)];
+        let trust = SkillTrustConfig {
+            managed: SkillTrustLevel::Require,
+            workspace: SkillTrustLevel::Warn,
+            trusted_keys: vec![],
+        };
+
+        // Unsigned managed skill is ineligible; unsigned workspace skill only warns
+        let mut managed = validate_skill_dir(tmp.path()).unwrap();
+        apply_trust_policy(&mut managed, &trust, &trusted);
+        assert!(matches!(
+            managed.eligibility,
+            SkillEligibility::Untrusted(_)
+        ));
+        let mut workspace = validate_skill_dir(tmp.path()).unwrap();
+        workspace.source = SkillSource::Workspace;
+        apply_trust_policy(&mut workspace, &trust, &trusted);
+        assert!(workspace.eligibility.is_ready());
+
+        crate::agent::skill_signing::sign_skill(&tmp.path().join("SKILL.md"), &key, false).unwrap();
+        let mut managed = validate_skill_dir(tmp.path()).unwrap();
+        apply_trust_policy(&mut managed, &trust, &trusted);
+        assert!(managed.signature.is_trusted());
+        assert!(managed.eligibility.is_ready());
+    }
+
+    #[test]
+    fn test_require_signature() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(tmp.path(), "name: deploy\ndescription: Deploy");
+        let mut config = crate::config::SkillsConfig::default();
+        config.trust.workspace = SkillTrustLevel::Warn;
+
+        config.require_signature = crate::config::SkillSignatureRequirement::Managed;
+        let trust = config.trust_policy();
+        assert_eq!(trust.managed, SkillTrustLevel::Require);
+        assert_eq!(trust.workspace, SkillTrustLevel::Warn);
+
+        // With "all", an unsigned workspace skill is blocked and reported
+        config.require_signature = crate::config::SkillSignatureRequirement::All;
+        let trust = config.trust_policy();
+        let mut skill = validate_skill_dir(tmp.path()).unwrap();
+        skill.source = SkillSource::Workspace;
+        apply_trust_policy(&mut skill, &trust, &[]);
+        assert!(!skill.include_in_prompt());
+        let skills = [skill];
+        assert!(build_skills_prompt(&skills, None).is_empty());
+        let summary = get_skills_summary(&skills);
+        assert!(summary.contains("0 ready, 1 blocked"));
+        assert!(summary.contains("deploy - untrusted: unsigned"));
+
+        // Bundled skills are never held to a signature
+        assert_eq!(
+            trust_level_for(&SkillSource::Bundled, &trust),
+            SkillTrustLevel::Off
+        );
+    }
+
+    #[test]
+    fn test_diagnose_skills() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(
+            tmp.path(),
+            r#"name: deploy
+description: Deploy
+useWhen:
+  - contains: "deploy"
+dontUseWhen:
+  - channel: telegram"#,
+        );
+        let deploy = validate_skill_dir(tmp.path()).unwrap();
+        let mut managed = deploy.clone();
+        managed.source = SkillSource::Managed;
+        let mut workspace = deploy.clone();
+        workspace.source = SkillSource::Workspace;
+        let mut blocked = deploy.clone();
+        blocked.name = "blocked".to_string();
+        blocked.eligibility = SkillEligibility::MissingBins(vec!["kubectl".to_string()]);
+
+        let definitions = vec![managed, blocked, workspace];
+        let ctx = SkillRoutingContext::new("please deploy", "cli");
+        let diagnoses = diagnose_skills(definitions.clone(), &ctx);
+        assert_eq!(diagnoses.len(), 2);
+
+        assert_eq!(diagnoses[0].skill.name, "blocked");
+        assert!(!diagnoses[0].included);
+        assert!(diagnoses[0].reason.contains("kubectl"));
+
+        let d = &diagnoses[1];
+        assert_eq!(d.skill.source, SkillSource::Workspace);
+        assert_eq!(d.shadowed.len(), 1);
+        assert_eq!(d.shadowed[0].source, SkillSource::Managed);
+        assert!(d.included);
+        assert_eq!(d.use_when[0].condition, r#"contains "deploy""#);
+        assert!(d.use_when[0].matched);
+        assert!(!d.dont_use_when[0].matched);
+        assert_eq!(
+            d.included,
+            d.skill.include_in_prompt() && d.skill.should_use(&ctx)
+        );
+
+        let telegram = SkillRoutingContext::new("please deploy", "telegram");
+        let d = &diagnose_skills(definitions, &telegram)[1];
+        assert!(!d.included);
+        assert_eq!(d.dont_use_when[0].condition, "channel=telegram");
+        assert!(d.dont_use_when[0].matched);
+    }
+
+    #[test]
+    fn test_bundled_skills_are_valid() {
+        let tmp = tempfile::tempdir().unwrap();
+        extract_bundled_skills(tmp.path()).unwrap();
+
+        let mut names = Vec::new();
+        for entry in fs::read_dir(tmp.path()).unwrap() {
+            let skill = validate_skill_dir(&entry.unwrap().path()).unwrap();
+            names.push(skill.name);
+        }
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["daily-briefing", "memory-hygiene", "summarize-file"]
+        );
+
+        // Re-extracting leaves identical files alone and restores edits
+        let skill_md = tmp.path().join("summarize-file/SKILL.md");
+        fs::write(&skill_md, "edited").unwrap();
+        extract_bundled_skills(tmp.path()).unwrap();
+        assert!(
+            fs::read_to_string(&skill_md)
+                .unwrap()
+                .contains("name: summarize-file")
+        );
+    }
+
     #[test]
     fn test_build_skills_prompt_with_routing() {
         let skills = vec![
@@ -1148,6 +2273,7 @@ dontUseWhen:
                 command_name: "debug-skill".to_string(),
                 path: PathBuf::from("/test/debug/SKILL.md"),
                 description: "Debug helper".to_string(),
+                version: None,
                 emoji: None,
                 source: SkillSource::Workspace,
                 user_invocable: true,
@@ -1157,12 +2283,15 @@ dontUseWhen:
                 eligibility: SkillEligibility::Ready,
                 use_when: vec![RoutingCondition::Contains("debug".to_string())],
                 dont_use_when: vec![],
+                arguments: vec![],
+                signature: SkillSignature::Unsigned,
             },
             Skill {
                 name: "weather-skill".to_string(),
                 command_name: "weather-skill".to_string(),
                 path: PathBuf::from("/test/weather/SKILL.md"),
                 description: "Weather helper".to_string(),
+                version: None,
                 emoji: None,
                 source: SkillSource::Workspace,
                 user_invocable: true,
@@ -1172,6 +2301,8 @@ dontUseWhen:
                 eligibility: SkillEligibility::Ready,
                 use_when: vec![RoutingCondition::Contains("weather".to_string())],
                 dont_use_when: vec![],
+                arguments: vec![],
+                signature: SkillSignature::Unsigned,
             },
         ];
 
@@ -1200,4 +2331,38 @@ dontUseWhen:
         assert!(prompt.contains("- debug-skill: Debug helper"));
         assert!(prompt.contains("- weather-skill: Weather helper"));
     }
+
+    #[test]
+    fn test_scaffold_skill_is_valid() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = scaffold_skill(tmp.path(), "Release Notes").unwrap();
+        assert_eq!(path, tmp.path().join("release-notes").join("SKILL.md"));
+
+        let skill = validate_skill_dir(path.parent().unwrap()).unwrap();
+        assert_eq!(skill.name, "release-notes");
+        assert!(skill.use_when.is_empty());
+        assert!(skill.eligibility.is_ready());
+
+        assert!(scaffold_skill(tmp.path(), "release-notes").is_err());
+        assert!(scaffold_skill(tmp.path(), "!!!").is_err());
+    }
+
+    #[test]
+    fn test_set_skill_disabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("skills").join("disabled.json");
+        assert!(load_disabled_skills(&path).unwrap().is_empty());
+
+        assert!(set_skill_disabled(&path, "weather", true).unwrap());
+        assert!(set_skill_disabled(&path, "deploy", true).unwrap());
+        assert!(!set_skill_disabled(&path, "weather", true).unwrap());
+        assert_eq!(
+            load_disabled_skills(&path).unwrap(),
+            vec!["deploy", "weather"]
+        );
+
+        assert!(set_skill_disabled(&path, "weather", false).unwrap());
+        assert!(!set_skill_disabled(&path, "weather", false).unwrap());
+        assert_eq!(load_disabled_skills(&path).unwrap(), vec!["deploy"]);
+    }
 }
@@ -0,0 +1,135 @@
+//! Skills hot-reload support
+//!
+//! Watches the workspace and managed skills directories and bumps a global
+//! skills generation whenever a SKILL.md (or a skill directory) changes.
+//! Running agents compare the generation at the start of each turn and
+//! rebuild the skills section of their system prompt when it has moved.
+
+use anyhow::Result;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, info, warn};
+
+static SKILLS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Current skills generation. Changes whenever a watched skill changes.
+pub fn skills_generation() -> u64 {
+    SKILLS_GENERATION.load(Ordering::Relaxed)
+}
+
+/// Mark skills as changed so running agents reload them on their next turn.
+pub fn notify_skills_changed() {
+    SKILLS_GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Handle keeping the skills directory watcher alive.
+pub struct SkillsWatcher {
+    _watcher: RecommendedWatcher,
+    roots: Vec<PathBuf>,
+}
+
+impl SkillsWatcher {
+    /// Start watching `workspace/skills` and the managed skills directory.
+    /// Missing directories are created so skills added later are seen.
+    pub fn start(workspace: &Path) -> Result<Self> {
+        let mut roots = vec![workspace.join("skills")];
+        if let Ok(paths) = crate::paths::Paths::resolve() {
+            roots.push(paths.managed_skills_dir());
+        }
+        for root in &roots {
+            fs::create_dir_all(root)?;
+        }
+
+        let watched = roots.clone();
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<Event, notify::Error>| match res {
+                Ok(event) => {
+                    if is_skill_change(&event, &watched) {
+                        debug!("Skill change detected: {:?}", event.paths);
+                        notify_skills_changed();
+                    }
+                }
+                Err(e) => warn!("Skills watch error: {}", e),
+            },
+            notify::Config::default(),
+        )?;
+
+        for root in &roots {
+            watcher.watch(root, RecursiveMode::Recursive)?;
+        }
+        info!(
+            "Watching skills directories: {}",
+            roots
+                .iter()
+                .map(|r| r.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        Ok(Self {
+            _watcher: watcher,
+            roots,
+        })
+    }
+
+    /// Directories being watched.
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+}
+
+/// A SKILL.md was written or removed, or a whole skill directory came or went.
+fn is_skill_change(event: &Event, roots: &[PathBuf]) -> bool {
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return false;
+    }
+    event.paths.iter().any(|path| {
+        path.file_name().is_some_and(|n| n == "SKILL.md")
+            || path.parent().is_some_and(|p| roots.iter().any(|r| r == p))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+
+    #[test]
+    fn detects_skill_changes() {
+        let roots = vec![PathBuf::from("/ws/skills")];
+        let event = |kind, path: &str| Event::new(kind).add_path(PathBuf::from(path));
+
+        let modify = EventKind::Modify(ModifyKind::Any);
+        assert!(is_skill_change(
+            &event(modify, "/ws/skills/deploy/SKILL.md"),
+            &roots
+        ));
+        assert!(is_skill_change(
+            &event(EventKind::Create(CreateKind::Folder), "/ws/skills/new"),
+            &roots
+        ));
+        assert!(!is_skill_change(
+            &event(modify, "/ws/skills/deploy/scripts/run.sh"),
+            &roots
+        ));
+        assert!(!is_skill_change(
+            &event(
+                EventKind::Access(AccessKind::Any),
+                "/ws/skills/deploy/SKILL.md"
+            ),
+            &roots
+        ));
+    }
+
+    #[test]
+    fn generation_advances() {
+        let before = skills_generation();
+        notify_skills_changed();
+        assert!(skills_generation() > before);
+    }
+}
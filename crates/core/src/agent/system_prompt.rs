@@ -255,6 +255,10 @@ fn get_tool_summary(tool_name: &str) -> &'static str {
         "memory_get" => "Fetch specific lines from memory files (use after memory_search)",
         "web_fetch" => "Fetch and extract content from a URL",
         "web_search" => "Search web with a Query string",
+        "grep" => "Regex search of file contents in the workspace",
+        "glob" => "List workspace files matching a glob pattern",
+        "read_more" => "Fetch the next page of a truncated tool output",
+        "schedule_task" => "Schedule a one-time task to run later in a fresh session",
         _ => "Tool",
     }
 }
@@ -173,6 +173,36 @@ impl CompiledToolFilter {
     }
 }
 
+/// Which tools an interface (cli, http, telegram, bridge, cron, ...) may use.
+/// Configured per-interface in config.toml under [tools.interfaces.<name>].
+///
+/// Entries are exact tool names, or a prefix ending in `*` (e.g. `"mcp_*"`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InterfaceToolPolicy {
+    /// If non-empty, only these tools are available on the interface
+    #[serde(default)]
+    pub allow: Vec<String>,
+
+    /// Tools that are never available on the interface (checked after allow)
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl InterfaceToolPolicy {
+    /// Check whether a tool may be used on this interface.
+    pub fn permits(&self, tool_name: &str) -> bool {
+        let matches = |entry: &String| match entry.strip_suffix('*') {
+            Some(prefix) => tool_name.starts_with(prefix),
+            None => entry == tool_name,
+        };
+
+        if !self.allow.is_empty() && !self.allow.iter().any(matches) {
+            return false;
+        }
+        !self.deny.iter().any(matches)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -275,4 +305,24 @@ mod tests {
         assert_eq!(merged.deny_substrings.len(), 2); // original + mkfs
         assert_eq!(merged.deny_patterns.len(), 2); // original + curl|sh
     }
+
+    #[test]
+    fn interface_policy_allow_and_deny() {
+        let policy = InterfaceToolPolicy {
+            allow: vec!["memory_*".to_string(), "web_fetch".to_string()],
+            deny: vec!["memory_get".to_string()],
+        };
+        assert!(policy.permits("memory_search"));
+        assert!(policy.permits("web_fetch"));
+        assert!(!policy.permits("memory_get"));
+        assert!(!policy.permits("write_file"));
+
+        let deny_only = InterfaceToolPolicy {
+            allow: Vec::new(),
+            deny: vec!["write_file".to_string(), "bash".to_string()],
+        };
+        assert!(deny_only.permits("read_file"));
+        assert!(!deny_only.permits("bash"));
+        assert!(InterfaceToolPolicy::default().permits("anything"));
+    }
 }
@@ -0,0 +1,85 @@
+//! Shell command templates for user-defined command tools.
+//!
+//! Command tools are declared in config.toml under `[[tools.commands]]`.
+//! Their `command` is a shell template where `{{name}}` placeholders are
+//! replaced with the matching tool argument, single-quoted so the model
+//! cannot inject extra shell syntax.
+
+use anyhow::Result;
+use serde_json::Value;
+
+/// Render a command template, substituting `{{name}}` placeholders with
+/// shell-quoted values from the JSON `args` object.
+///
+/// Missing or null arguments render as an empty quoted string (`''`).
+/// Arrays render as space-separated quoted elements. An unterminated
+/// placeholder is an error.
+pub fn render_command_template(template: &str, args: &Value) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| anyhow::anyhow!("Unterminated placeholder in command template"))?;
+        let name = after[..end].trim();
+        out.push_str(&render_value(&args[name]));
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+
+    Ok(out)
+}
+
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::Null => shell_quote(""),
+        Value::String(s) => shell_quote(s),
+        Value::Array(items) => items.iter().map(render_value).collect::<Vec<_>>().join(" "),
+        other => shell_quote(&other.to_string()),
+    }
+}
+
+/// Quote a string for POSIX shells using single quotes.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn substitutes_and_quotes_arguments() {
+        let rendered = render_command_template(
+            "gh issue view {{number}} --repo {{ repo }}",
+            &json!({"number": 42, "repo": "owner/name"}),
+        )
+        .unwrap();
+        assert_eq!(rendered, "gh issue view '42' --repo 'owner/name'");
+    }
+
+    #[test]
+    fn quoting_blocks_shell_injection() {
+        let rendered =
+            render_command_template("echo {{msg}}", &json!({"msg": "hi'; rm -rf ~; echo '"}))
+                .unwrap();
+        assert_eq!(rendered, r"echo 'hi'\''; rm -rf ~; echo '\'''");
+    }
+
+    #[test]
+    fn missing_and_array_arguments() {
+        let rendered =
+            render_command_template("ls {{flags}} {{paths}}", &json!({"paths": ["a", "b c"]}))
+                .unwrap();
+        assert_eq!(rendered, "ls '' 'a' 'b c'");
+    }
+
+    #[test]
+    fn unterminated_placeholder_fails() {
+        assert!(render_command_template("echo {{oops", &json!({})).is_err());
+    }
+}
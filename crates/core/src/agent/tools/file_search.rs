@@ -0,0 +1,416 @@
+//! Read-only file exploration tools: `grep` and `glob`.
+//!
+//! Both tools are confined to the workspace plus any
+//! `security.allowed_directories`, so they are safe to offer on every
+//! interface (unlike bash). Hidden entries and symlinks are skipped while
+//! walking, and results are capped to keep output small.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use regex::RegexBuilder;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use super::Tool;
+use crate::agent::path_utils::{check_path_allowed, resolve_real_path};
+use crate::agent::providers::ToolSchema;
+use crate::config::Config;
+
+/// Stop walking after this many files to bound the cost of a single call.
+const MAX_FILES_SCANNED: usize = 20_000;
+
+/// Files larger than this are not searched by `grep`.
+const MAX_GREP_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Maximum context lines around each `grep` match.
+const MAX_CONTEXT_LINES: usize = 10;
+
+/// Directories the search tools may read: the workspace first (the default
+/// base for relative paths), then any configured allowed directories.
+pub fn search_roots(config: &Config) -> Vec<PathBuf> {
+    let workspace = config.workspace_path();
+    let mut roots = vec![fs::canonicalize(&workspace).unwrap_or(workspace)];
+    for dir in &config.security.allowed_directories {
+        let expanded = shellexpand::tilde(dir).to_string();
+        if let Ok(path) = fs::canonicalize(&expanded)
+            && !roots.contains(&path)
+        {
+            roots.push(path);
+        }
+    }
+    roots
+}
+
+/// Resolve an optional user-supplied directory against the search roots.
+fn resolve_base(roots: &[PathBuf], path: Option<&str>) -> Result<PathBuf> {
+    let workspace = &roots[0];
+    let Some(path) = path.filter(|p| !p.is_empty()) else {
+        return Ok(workspace.clone());
+    };
+
+    let expanded = shellexpand::tilde(path).to_string();
+    let candidate = if Path::new(&expanded).is_absolute() {
+        PathBuf::from(expanded)
+    } else {
+        workspace.join(expanded)
+    };
+    let real = resolve_real_path(&candidate.to_string_lossy())?;
+    check_path_allowed(&real, roots)?;
+    Ok(real)
+}
+
+/// Show paths relative to the root that contains them.
+fn display_path(path: &Path, roots: &[PathBuf]) -> String {
+    roots
+        .iter()
+        .find_map(|root| path.strip_prefix(root).ok())
+        .filter(|rel| !rel.as_os_str().is_empty())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .map(|n| n.to_string_lossy().starts_with('.'))
+        .unwrap_or(false)
+}
+
+/// Recursively collect regular files under `dir`, skipping hidden entries and symlinks.
+fn walk_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        if files.len() >= MAX_FILES_SCANNED {
+            return;
+        }
+        let path = entry.path();
+        if is_hidden(&path) {
+            continue;
+        }
+        match entry.file_type() {
+            Ok(ft) if ft.is_dir() => walk_files(&path, files),
+            Ok(ft) if ft.is_file() => files.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// Match a file filter against the file name, or the relative path if it contains `/`.
+fn matches_filter(filter: &glob::Pattern, path: &Path, base: &Path) -> bool {
+    if filter.as_str().contains('/') {
+        path.strip_prefix(base)
+            .map(|rel| filter.matches_path(rel))
+            .unwrap_or(false)
+    } else {
+        path.file_name()
+            .map(|n| filter.matches(&n.to_string_lossy()))
+            .unwrap_or(false)
+    }
+}
+
+// Grep Tool
+pub struct GrepTool {
+    roots: Vec<PathBuf>,
+}
+
+impl GrepTool {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self { roots }
+    }
+}
+
+#[async_trait]
+impl Tool for GrepTool {
+    fn name(&self) -> &str {
+        "grep"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "grep".to_string(),
+            description: "Search file contents in the workspace with a regular expression. Returns path:line: text for each match.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "pattern": {
+                        "type": "string",
+                        "description": "Regular expression to search for"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "File or directory to search (default: workspace root)"
+                    },
+                    "glob": {
+                        "type": "string",
+                        "description": "Only search files matching this glob (e.g., '*.md' or 'src/**/*.rs')"
+                    },
+                    "context": {
+                        "type": "integer",
+                        "description": "Lines of context before and after each match (default: 0, max: 10)"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "Ignore case (default: false)"
+                    },
+                    "max_results": {
+                        "type": "integer",
+                        "description": "Maximum number of matches (default: 100)"
+                    }
+                },
+                "required": ["pattern"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let pattern = args["pattern"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing pattern"))?;
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(args["case_insensitive"].as_bool().unwrap_or(false))
+            .build()?;
+        let filter = args["glob"].as_str().map(glob::Pattern::new).transpose()?;
+        let context = (args["context"].as_u64().unwrap_or(0) as usize).min(MAX_CONTEXT_LINES);
+        let max_results = args["max_results"].as_u64().unwrap_or(100).max(1) as usize;
+
+        let base = resolve_base(&self.roots, args["path"].as_str())?;
+        debug!("Grep: {} in {}", pattern, base.display());
+
+        let roots = self.roots.clone();
+        // Walking and reading files blocks, so keep it off the async workers
+        tokio::task::spawn_blocking(move || -> Result<String> {
+            let mut files = Vec::new();
+            if base.is_file() {
+                files.push(base.clone());
+            } else {
+                walk_files(&base, &mut files);
+            }
+
+            let mut output = Vec::new();
+            let mut matches = 0;
+            'files: for file in &files {
+                if let Some(ref filter) = filter
+                    && !matches_filter(filter, file, &base)
+                {
+                    continue;
+                }
+                if fs::metadata(file).map(|m| m.len()).unwrap_or(0) > MAX_GREP_FILE_BYTES {
+                    continue;
+                }
+                // Non-UTF-8 (binary) files fail to read and are skipped
+                let Ok(content) = fs::read_to_string(file) else {
+                    continue;
+                };
+
+                let lines: Vec<&str> = content.lines().collect();
+                let name = display_path(file, &roots);
+                let mut last_printed: Option<usize> = None;
+
+                for (i, line) in lines.iter().enumerate() {
+                    if !regex.is_match(line) {
+                        continue;
+                    }
+                    if matches >= max_results {
+                        output.push(format!("[stopped after {} matches]", max_results));
+                        break 'files;
+                    }
+                    matches += 1;
+
+                    let start = i.saturating_sub(context);
+                    let end = (i + context).min(lines.len() - 1);
+                    let start = last_printed.map_or(start, |last| start.max(last + 1));
+                    if context > 0 && last_printed.is_some_and(|last| start > last + 1) {
+                        output.push("--".to_string());
+                    }
+                    for (j, text) in lines.iter().enumerate().take(end + 1).skip(start) {
+                        let sep = if j == i || regex.is_match(text) {
+                            ':'
+                        } else {
+                            '-'
+                        };
+                        output.push(format!("{}{}{}{} {}", name, sep, j + 1, sep, text));
+                    }
+                    last_printed = Some(end.max(last_printed.unwrap_or(0)));
+                }
+            }
+
+            if output.is_empty() {
+                Ok("No matches found".to_string())
+            } else {
+                Ok(output.join("\n"))
+            }
+        })
+        .await?
+    }
+}
+
+// Glob Tool
+pub struct GlobTool {
+    roots: Vec<PathBuf>,
+}
+
+impl GlobTool {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self { roots }
+    }
+}
+
+#[async_trait]
+impl Tool for GlobTool {
+    fn name(&self) -> &str {
+        "glob"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "glob".to_string(),
+            description: "List workspace files matching a glob pattern (e.g., '**/*.md')."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "pattern": {
+                        "type": "string",
+                        "description": "Glob pattern relative to path (e.g., 'memory/*.md', '**/*.rs')"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Directory to search from (default: workspace root)"
+                    },
+                    "max_results": {
+                        "type": "integer",
+                        "description": "Maximum number of paths (default: 200)"
+                    }
+                },
+                "required": ["pattern"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let pattern = args["pattern"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing pattern"))?;
+        let max_results = args["max_results"].as_u64().unwrap_or(200).max(1) as usize;
+
+        let base = resolve_base(&self.roots, args["path"].as_str())?;
+        debug!("Glob: {} in {}", pattern, base.display());
+
+        let full_pattern = base.join(pattern);
+        let roots = self.roots.clone();
+        // Matching walks the filesystem, so keep it off the async workers
+        tokio::task::spawn_blocking(move || -> Result<String> {
+            let options = glob::MatchOptions {
+                require_literal_leading_dot: true,
+                ..Default::default()
+            };
+
+            let mut paths = Vec::new();
+            let mut truncated = false;
+            for entry in glob::glob_with(&full_pattern.to_string_lossy(), options)? {
+                let Ok(path) = entry else {
+                    continue;
+                };
+                // Patterns with `..` or symlinks must not escape the allowed roots
+                let Ok(real) = fs::canonicalize(&path) else {
+                    continue;
+                };
+                if check_path_allowed(&real, &roots).is_err() {
+                    continue;
+                }
+                if paths.len() >= max_results {
+                    truncated = true;
+                    break;
+                }
+                let mut shown = display_path(&real, &roots);
+                if real.is_dir() {
+                    shown.push('/');
+                }
+                paths.push(shown);
+            }
+
+            if paths.is_empty() {
+                return Ok("No files found".to_string());
+            }
+            let mut output = paths.join("\n");
+            if truncated {
+                output.push_str(&format!("\n[stopped after {} paths]", max_results));
+            }
+            Ok(output)
+        })
+        .await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Vec<PathBuf>) {
+        let tmp = TempDir::new().unwrap();
+        let root = fs::canonicalize(tmp.path()).unwrap();
+        fs::create_dir_all(root.join("memory")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("MEMORY.md"), "alpha\nbeta\ngamma\ndelta\n").unwrap();
+        fs::write(root.join("memory/2026-01-01.md"), "notes about beta\n").unwrap();
+        fs::write(root.join(".git/config"), "beta\n").unwrap();
+        (tmp, vec![root])
+    }
+
+    #[tokio::test]
+    async fn grep_finds_matches_and_skips_hidden() {
+        let (_tmp, roots) = setup();
+        let tool = GrepTool::new(roots);
+        let out = tool.execute(r#"{"pattern": "beta"}"#).await.unwrap();
+        assert!(out.contains("MEMORY.md:2: beta"));
+        assert!(out.contains("memory/2026-01-01.md:1: notes about beta"));
+        assert!(!out.contains(".git"));
+    }
+
+    #[tokio::test]
+    async fn grep_context_and_glob_filter() {
+        let (_tmp, roots) = setup();
+        let tool = GrepTool::new(roots);
+        let out = tool
+            .execute(r#"{"pattern": "^gamma", "context": 1, "glob": "MEMORY.md"}"#)
+            .await
+            .unwrap();
+        assert_eq!(
+            out,
+            "MEMORY.md-2- beta\nMEMORY.md:3: gamma\nMEMORY.md-4- delta"
+        );
+    }
+
+    #[tokio::test]
+    async fn glob_lists_files() {
+        let (_tmp, roots) = setup();
+        let tool = GlobTool::new(roots);
+        let out = tool.execute(r#"{"pattern": "**/*.md"}"#).await.unwrap();
+        assert!(out.contains("MEMORY.md"));
+        assert!(out.contains("memory/2026-01-01.md"));
+    }
+
+    #[tokio::test]
+    async fn paths_outside_roots_are_denied() {
+        let (_tmp, roots) = setup();
+        let grep = GrepTool::new(roots.clone());
+        assert!(
+            grep.execute(r#"{"pattern": "root", "path": "/etc"}"#)
+                .await
+                .is_err()
+        );
+        let glob = GlobTool::new(roots);
+        let out = glob.execute(r#"{"pattern": "../*"}"#).await.unwrap();
+        assert_eq!(out, "No files found");
+    }
+}
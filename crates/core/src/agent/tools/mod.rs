@@ -1,3 +1,7 @@
+pub mod command;
+pub mod file_search;
+pub mod output_pager;
+pub mod schedule;
 pub mod spawn_agent;
 pub mod web_search;
 
@@ -14,12 +18,14 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::debug;
 
-use super::providers::ToolSchema;
+use super::providers::{ImageAttachment, ToolSchema};
 use crate::config::{Config, SearchProviderType};
 use crate::memory::MemoryManager;
 
+use file_search::{GlobTool, GrepTool};
+use schedule::ScheduleTaskTool;
 use spawn_agent::{SpawnAgentTool, SpawnContext};
-use web_search::{SearchRouter, WebSearchTool};
+use web_search::{ContentFetcher, SearchRouter, WebSearchTool};
 
 #[derive(Debug, Clone)]
 pub struct ToolResult {
@@ -34,7 +40,8 @@ pub trait Tool: Send + Sync {
     async fn execute(&self, arguments: &str) -> Result<String>;
 }
 
-/// Create the safe (mobile-compatible) tools: memory search, memory get, web fetch, web search.
+/// Create the safe (mobile-compatible) tools: memory search, memory get, web fetch, web search,
+/// and schedule_task.
 ///
 /// Dangerous tools (bash, read_file, write_file, edit_file) are provided by the CLI crate.
 /// Use `Agent::new_with_tools()` to supply the full tool set.
@@ -68,12 +75,17 @@ pub fn create_safe_tools(
             hardcoded_filters::WEB_FETCH_DENY_PATTERNS,
         )?;
 
+    // Read-only exploration scoped to the workspace and allowed directories
+    let search_roots = file_search::search_roots(config);
+
     let mut tools: Vec<Box<dyn Tool>> = vec![
         memory_search_tool,
         Box::new(MemoryGetTool::new(workspace)),
+        Box::new(GrepTool::new(search_roots.clone())),
+        Box::new(GlobTool::new(search_roots)),
         Box::new(WebFetchTool::new(
             config.tools.web_fetch_max_bytes,
-            web_fetch_filter,
+            web_fetch_filter.clone(),
         )?),
     ];
 
@@ -82,11 +94,23 @@ pub fn create_safe_tools(
         && !matches!(ws_config.provider, SearchProviderType::None)
     {
         match SearchRouter::from_config(ws_config) {
-            Ok(router) => tools.push(Box::new(WebSearchTool::new(Arc::new(router)))),
+            Ok(router) => {
+                let router = router.with_webhooks(config.webhooks.clone());
+                let fetcher =
+                    ContentFetcher::new(ws_config.fetch_content.clone(), web_fetch_filter)?;
+                tools.push(Box::new(
+                    WebSearchTool::new(Arc::new(router)).with_content_fetcher(fetcher),
+                ))
+            }
             Err(e) => tracing::warn!("Web search init failed: {e}"),
         }
     }
 
+    match crate::cron::CronJobStore::from_config(config) {
+        Ok(store) => tools.push(Box::new(ScheduleTaskTool::new(store))),
+        Err(e) => tracing::warn!("schedule_task init failed: {e}"),
+    }
+
     Ok(tools)
 }
 
@@ -453,7 +477,9 @@ fn is_blocked_hostname(host: &str) -> bool {
     blocked.contains(&host.as_str()) || blocked_tlds.iter().any(|tld| host.ends_with(tld))
 }
 
-async fn validate_web_fetch_url(url: &str) -> Result<reqwest::Url> {
+/// Validate a URL for outbound fetches: http(s) only, no blocked hostnames,
+/// and no hosts that resolve to private/loopback addresses (SSRF protection).
+pub async fn validate_web_fetch_url(url: &str) -> Result<reqwest::Url> {
     let parsed = reqwest::Url::parse(url)?;
 
     if !matches!(parsed.scheme(), "http" | "https") {
@@ -551,6 +577,127 @@ fn extract_readable_text(html: &str, url: &reqwest::Url) -> String {
     }
 }
 
+/// GET a URL, following redirects manually so every hop passes SSRF validation.
+async fn fetch_with_validated_redirects(
+    client: &reqwest::Client,
+    mut current_url: reqwest::Url,
+) -> Result<(reqwest::Response, reqwest::Url)> {
+    for redirect_count in 0..=MAX_WEB_FETCH_REDIRECTS {
+        let response = client
+            .get(current_url.clone())
+            .header("User-Agent", "LocalGPT/0.1")
+            .send()
+            .await?;
+
+        if !should_follow_redirect(response.status()) {
+            return Ok((response, current_url));
+        }
+
+        if redirect_count == MAX_WEB_FETCH_REDIRECTS {
+            anyhow::bail!(
+                "Too many redirects (>{}) while fetching {}",
+                MAX_WEB_FETCH_REDIRECTS,
+                current_url
+            );
+        }
+
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Redirect response {} missing Location header",
+                    response.status()
+                )
+            })?
+            .to_str()
+            .map_err(|_| anyhow::anyhow!("Redirect Location header is not valid UTF-8"))?;
+
+        let next_url = resolve_and_validate_redirect_target(&current_url, location).await?;
+        debug!(
+            "Following redirect {}: {} -> {}",
+            redirect_count + 1,
+            current_url,
+            next_url
+        );
+        current_url = next_url;
+    }
+
+    unreachable!("redirect loop should return or bail")
+}
+
+/// Fetch a URL and return its readable text, with the same SSRF protections
+/// as `web_fetch`. HTML pages are reduced to their main content.
+pub(crate) async fn fetch_readable_text(client: &reqwest::Client, url: &str) -> Result<String> {
+    let parsed_url = validate_web_fetch_url(url).await?;
+    let (response, final_url) = fetch_with_validated_redirects(client, parsed_url).await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("HTTP {}", status);
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let body = response.text().await?;
+    if content_type.contains("text/html") || content_type.contains("application/xhtml") {
+        Ok(extract_readable_text(&body, &final_url))
+    } else {
+        Ok(body)
+    }
+}
+
+/// Download an image for a multimodal message, with the same SSRF protections
+/// as `web_fetch`. Fails unless the response is an image of at most
+/// `max_bytes`.
+pub async fn fetch_image(url: &str, max_bytes: usize) -> Result<ImageAttachment> {
+    use base64::{Engine as _, engine::general_purpose};
+
+    let parsed_url = validate_web_fetch_url(url).await?;
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let (mut response, _) = fetch_with_validated_redirects(&client, parsed_url).await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("HTTP {}", status);
+    }
+    let media_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .unwrap_or("")
+        .trim()
+        .to_lowercase();
+    if !media_type.starts_with("image/") {
+        anyhow::bail!("Not an image (content type '{}')", media_type);
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len > max_bytes as u64)
+    {
+        anyhow::bail!("Image is larger than {} bytes", max_bytes);
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() > max_bytes {
+            anyhow::bail!("Image is larger than {} bytes", max_bytes);
+        }
+    }
+    Ok(ImageAttachment {
+        data: general_purpose::STANDARD.encode(&bytes),
+        media_type,
+    })
+}
+
 // Web Fetch Tool
 pub struct WebFetchTool {
     client: reqwest::Client,
@@ -570,55 +717,6 @@ impl WebFetchTool {
             filter,
         })
     }
-
-    async fn fetch_with_validated_redirects(
-        &self,
-        mut current_url: reqwest::Url,
-    ) -> Result<(reqwest::Response, reqwest::Url)> {
-        for redirect_count in 0..=MAX_WEB_FETCH_REDIRECTS {
-            let response = self
-                .client
-                .get(current_url.clone())
-                .header("User-Agent", "LocalGPT/0.1")
-                .send()
-                .await?;
-
-            if !should_follow_redirect(response.status()) {
-                return Ok((response, current_url));
-            }
-
-            if redirect_count == MAX_WEB_FETCH_REDIRECTS {
-                anyhow::bail!(
-                    "Too many redirects (>{}) while fetching {}",
-                    MAX_WEB_FETCH_REDIRECTS,
-                    current_url
-                );
-            }
-
-            let location = response
-                .headers()
-                .get(reqwest::header::LOCATION)
-                .ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "Redirect response {} missing Location header",
-                        response.status()
-                    )
-                })?
-                .to_str()
-                .map_err(|_| anyhow::anyhow!("Redirect Location header is not valid UTF-8"))?;
-
-            let next_url = resolve_and_validate_redirect_target(&current_url, location).await?;
-            debug!(
-                "Following redirect {}: {} -> {}",
-                redirect_count + 1,
-                current_url,
-                next_url
-            );
-            current_url = next_url;
-        }
-
-        unreachable!("redirect loop should return or bail")
-    }
 }
 
 #[async_trait]
@@ -656,7 +754,8 @@ impl Tool for WebFetchTool {
         let parsed_url = validate_web_fetch_url(url).await?;
         debug!("Fetching URL: {}", parsed_url);
 
-        let (response, final_url) = self.fetch_with_validated_redirects(parsed_url).await?;
+        let (response, final_url) =
+            fetch_with_validated_redirects(&self.client, parsed_url).await?;
 
         let status = response.status();
         let content_type = response
@@ -722,6 +821,10 @@ pub fn extract_tool_detail(tool_name: &str, arguments: &str) -> Option<String> {
             .get("query")
             .and_then(|v| v.as_str())
             .map(|s| format!("\"{}\"", s)),
+        "grep" | "glob" => args
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
         _ => None,
     }
 }
@@ -0,0 +1,239 @@
+//! Output paging for large tool results.
+//!
+//! Tool outputs larger than the configured token cap are cut to a first page
+//! and kept in an [`OutputPager`]. The page ends with a cursor that the model
+//! can pass to the `read_more` tool to fetch the next page, so long results
+//! are consumed deliberately instead of flooding the context.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use super::Tool;
+use crate::agent::providers::ToolSchema;
+
+/// Name of the continuation tool.
+pub const READ_MORE_TOOL: &str = "read_more";
+
+/// Approximate bytes per token (matches session token estimation).
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Number of paged outputs kept for `read_more` before the oldest is evicted.
+const MAX_STORED_OUTPUTS: usize = 16;
+
+struct StoredOutput {
+    id: u64,
+    tool: String,
+    content: String,
+    page_bytes: usize,
+}
+
+#[derive(Default)]
+struct PagerState {
+    next_id: u64,
+    outputs: VecDeque<StoredOutput>,
+}
+
+/// Shared store of truncated tool outputs, addressed by `read_more` cursors.
+#[derive(Clone, Default)]
+pub struct OutputPager {
+    state: Arc<Mutex<PagerState>>,
+}
+
+impl OutputPager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return `output` unchanged if it fits in `max_tokens` (0 = unlimited).
+    /// Otherwise store it and return the first page with a `read_more` cursor.
+    pub fn paginate(&self, tool: &str, output: String, max_tokens: usize) -> String {
+        let page_bytes = max_tokens.saturating_mul(BYTES_PER_TOKEN);
+        if max_tokens == 0 || output.len() <= page_bytes {
+            return output;
+        }
+
+        let Ok(mut state) = self.state.lock() else {
+            return output;
+        };
+        let stored = StoredOutput {
+            id: state.next_id,
+            tool: tool.to_string(),
+            content: output,
+            page_bytes,
+        };
+        state.next_id += 1;
+
+        let page = render_page(&stored, 0);
+        state.outputs.push_back(stored);
+        if state.outputs.len() > MAX_STORED_OUTPUTS {
+            state.outputs.pop_front();
+        }
+        page
+    }
+
+    /// Fetch the page of a stored output starting at `cursor`.
+    pub fn read(&self, cursor: &str) -> Result<String> {
+        let (id, offset) = parse_cursor(cursor)?;
+        let state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Output pager lock poisoned"))?;
+        let stored = state
+            .outputs
+            .iter()
+            .find(|o| o.id == id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown or expired cursor: {}", cursor))?;
+        if offset >= stored.content.len() {
+            anyhow::bail!("Cursor {} is past the end of the output", cursor);
+        }
+        Ok(render_page(stored, offset))
+    }
+}
+
+fn format_cursor(id: u64, offset: usize) -> String {
+    format!("{}:{}", id, offset)
+}
+
+fn parse_cursor(cursor: &str) -> Result<(u64, usize)> {
+    let invalid = || anyhow::anyhow!("Invalid cursor: {}", cursor);
+    let (id, offset) = cursor.trim().split_once(':').ok_or_else(invalid)?;
+    Ok((
+        id.parse().map_err(|_| invalid())?,
+        offset.parse().map_err(|_| invalid())?,
+    ))
+}
+
+/// Render one page starting at `offset`, preferring to end on a line break.
+fn render_page(stored: &StoredOutput, offset: usize) -> String {
+    let content = &stored.content;
+    let offset = content.floor_char_boundary(offset);
+    let mut end = content.floor_char_boundary(offset.saturating_add(stored.page_bytes));
+    if end < content.len()
+        && let Some(newline) = content[offset..end].rfind('\n')
+        && newline > 0
+    {
+        end = offset + newline + 1;
+    }
+
+    let mut page = content[offset..end].to_string();
+    if end < content.len() {
+        page.push_str(&format!(
+            "\n\n[{} output truncated: showing bytes {}-{} of {}. \
+             Call {} with cursor \"{}\" for the next page.]",
+            stored.tool,
+            offset,
+            end,
+            content.len(),
+            READ_MORE_TOOL,
+            format_cursor(stored.id, end)
+        ));
+    } else if offset > 0 {
+        page.push_str(&format!(
+            "\n\n[End of {} output ({} bytes).]",
+            stored.tool,
+            content.len()
+        ));
+    }
+    page
+}
+
+// Read More Tool
+pub struct ReadMoreTool {
+    pager: OutputPager,
+}
+
+impl ReadMoreTool {
+    pub fn new(pager: OutputPager) -> Self {
+        Self { pager }
+    }
+}
+
+#[async_trait]
+impl Tool for ReadMoreTool {
+    fn name(&self) -> &str {
+        READ_MORE_TOOL
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: READ_MORE_TOOL.to_string(),
+            description: "Fetch the next page of a truncated tool output. \
+                          Pass the cursor from the truncation notice."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "cursor": {
+                        "type": "string",
+                        "description": "Cursor from the truncation notice"
+                    }
+                },
+                "required": ["cursor"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let cursor = args["cursor"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing cursor"))?;
+        self.pager.read(cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_output_passes_through() {
+        let pager = OutputPager::new();
+        assert_eq!(pager.paginate("bash", "hello".to_string(), 10), "hello");
+        assert_eq!(pager.paginate("bash", "x".repeat(100), 0), "x".repeat(100));
+    }
+
+    #[test]
+    fn pages_through_large_output() {
+        let pager = OutputPager::new();
+        let output: String = (0..100).map(|i| format!("line {:03}\n", i)).collect();
+
+        let first = pager.paginate("bash", output.clone(), 25);
+        assert!(first.starts_with("line 000\n"));
+        assert!(first.contains("Call read_more with cursor \"0:"));
+
+        let mut collected = first.split("\n\n[").next().unwrap().to_string();
+        let mut page = first;
+        while let Some(start) = page.find("cursor \"") {
+            let rest = &page[start + 8..];
+            let cursor = &rest[..rest.find('"').unwrap()];
+            page = pager.read(cursor).unwrap();
+            collected.push_str(page.split("\n\n[").next().unwrap());
+        }
+        assert!(page.contains("[End of bash output"));
+        assert_eq!(collected, output);
+    }
+
+    #[test]
+    fn rejects_bad_cursors() {
+        let pager = OutputPager::new();
+        pager.paginate("bash", "y".repeat(1000), 10);
+        assert!(pager.read("garbage").is_err());
+        assert!(pager.read("7:0").is_err());
+        assert!(pager.read("0:5000").is_err());
+        assert!(pager.read("0:40").is_ok());
+    }
+
+    #[test]
+    fn evicts_oldest_outputs() {
+        let pager = OutputPager::new();
+        for _ in 0..=MAX_STORED_OUTPUTS {
+            pager.paginate("bash", "z".repeat(100), 1);
+        }
+        assert!(pager.read("0:4").is_err());
+        assert!(pager.read("1:4").is_ok());
+    }
+}
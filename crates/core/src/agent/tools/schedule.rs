@@ -0,0 +1,102 @@
+//! `schedule_task` tool: lets the agent schedule a one-time task.
+//!
+//! Tasks are added as one-shot runtime cron jobs, so the daemon's scheduler
+//! runs the prompt in a fresh agent session at the requested time and then
+//! archives the job. Recurring jobs are left to `localgpt cron add`.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Local;
+use serde_json::{Value, json};
+use tracing::debug;
+
+use super::Tool;
+use crate::agent::providers::ToolSchema;
+use crate::config::{CronJob, default_cron_timeout};
+use crate::cron::{CronJobStore, interpret_schedule, resolve_relative};
+
+pub struct ScheduleTaskTool {
+    store: CronJobStore,
+}
+
+impl ScheduleTaskTool {
+    pub fn new(store: CronJobStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for ScheduleTaskTool {
+    fn name(&self) -> &str {
+        "schedule_task"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "schedule_task".to_string(),
+            description: "Schedule a one-time task: the prompt runs in a fresh agent session at the given time (requires the daemon to be running).".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "when": {
+                        "type": "string",
+                        "description": "When to run: 'in 45 minutes', 'in 2 hours', or 'at 2025-07-01 09:00' (local time)"
+                    },
+                    "prompt": {
+                        "type": "string",
+                        "description": "Self-contained instructions for the task"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Optional unique task name"
+                    }
+                },
+                "required": ["when", "prompt"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let when = args["when"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing when"))?;
+        let prompt = args["prompt"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing prompt"))?;
+
+        let now = Local::now();
+        let schedule = resolve_relative(when, now)?.unwrap_or_else(|| when.trim().to_string());
+        if !schedule.starts_with("at ") {
+            anyhow::bail!(
+                "schedule_task only schedules one-time tasks ('in ...' or 'at ...'); \
+                 recurring jobs are added with `localgpt cron add`"
+            );
+        }
+        interpret_schedule(&schedule)?;
+
+        let name = args["name"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("task-{}", now.format("%Y%m%d-%H%M%S")));
+        debug!("Scheduling task '{}' {}", name, schedule);
+
+        self.store.add(CronJob {
+            name: name.clone(),
+            schedule: schedule.clone(),
+            prompt: prompt.to_string(),
+            channel: None,
+            enabled: true,
+            timeout: default_cron_timeout(),
+            catch_up: Default::default(),
+            jitter: None,
+            model: None,
+            tools: Vec::new(),
+            skills: Vec::new(),
+            memory_namespace: None,
+            retry: Default::default(),
+            trigger: None,
+        })?;
+        Ok(format!("Scheduled task '{}' to run {}", name, schedule))
+    }
+}
@@ -1,21 +1,29 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::fmt::Write as FmtWrite;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, warn};
 
-use super::Tool;
+use super::{Tool, fetch_readable_text};
 use crate::agent::providers::ToolSchema;
+use crate::agent::tool_filters::CompiledToolFilter;
 use crate::config::{
-    BraveConfig, PerplexityConfig, SearchProviderType, SearxngConfig, TavilyConfig, WebSearchConfig,
+    BraveConfig, DuckDuckGoConfig, FetchContentConfig, KagiConfig, PerplexityConfig,
+    SearchProviderType, SearchQuotaConfig, SearxngConfig, SerperConfig, TavilyConfig,
+    WebSearchConfig, WebhookConfig,
 };
+use crate::webhooks::WebhookEvent;
 
 /// Percent-encode a string for use in URL query parameters.
 fn url_encode(s: &str) -> String {
@@ -43,6 +51,15 @@ pub struct SearchResult {
     pub snippet: String,
     pub score: Option<f64>,
     pub published_date: Option<String>,
+    /// Thumbnail image URL (image, video, and news results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+    /// Publisher or source site (news, image, and video results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Running time (video results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +71,9 @@ pub struct SearchMeta {
     pub estimated_cost_usd: f64,
     pub answer: Option<String>,
     pub cached: bool,
+    /// Providers skipped (over quota) or failed before this one served the query
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +82,47 @@ pub struct SearchResponse {
     pub meta: SearchMeta,
 }
 
+/// Search vertical requested by the caller.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchCategory {
+    #[default]
+    Web,
+    News,
+    Images,
+    Videos,
+}
+
+impl SearchCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Web => "web",
+            Self::News => "news",
+            Self::Images => "images",
+            Self::Videos => "videos",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "web" | "general" => Ok(Self::Web),
+            "news" => Ok(Self::News),
+            "images" | "image" => Ok(Self::Images),
+            "videos" | "video" => Ok(Self::Videos),
+            other => anyhow::bail!(
+                "Unknown search category '{}' (expected web, news, images, or videos)",
+                other
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for SearchCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchUsageStats {
     pub since: String,
@@ -134,6 +195,26 @@ pub trait SearchProvider: Send + Sync {
     fn name(&self) -> &str;
     async fn search(&self, query: &str, max_results: u8) -> Result<SearchResponse>;
     fn cost_per_query(&self) -> f64;
+
+    /// Request filtering of explicit results. Providers without a SafeSearch
+    /// parameter ignore this.
+    fn set_safe_search(&mut self, _enabled: bool) {}
+
+    /// Whether this provider can serve the given vertical.
+    fn supports_category(&self, category: SearchCategory) -> bool {
+        category == SearchCategory::Web
+    }
+
+    /// Search a specific vertical. Only called for categories the provider
+    /// reports as supported.
+    async fn search_category(
+        &self,
+        query: &str,
+        max_results: u8,
+        _category: SearchCategory,
+    ) -> Result<SearchResponse> {
+        self.search(query, max_results).await
+    }
 }
 
 // ── SearXNG Provider ─────────────────────────────────────────────────────────
@@ -141,6 +222,7 @@ pub trait SearchProvider: Send + Sync {
 pub struct SearxngProvider {
     client: reqwest::Client,
     config: SearxngConfig,
+    safe_search: bool,
 }
 
 impl SearxngProvider {
@@ -148,6 +230,7 @@ impl SearxngProvider {
         Self {
             client: reqwest::Client::new(),
             config,
+            safe_search: false,
         }
     }
 
@@ -165,6 +248,12 @@ impl SearxngProvider {
                     snippet: r["content"].as_str().unwrap_or("").to_string(),
                     score: r["score"].as_f64(),
                     published_date: r["publishedDate"].as_str().map(|s| s.to_string()),
+                    thumbnail: ["thumbnail_src", "thumbnail", "img_src"]
+                        .iter()
+                        .find_map(|k| r[*k].as_str().filter(|s| !s.is_empty()))
+                        .map(|s| s.to_string()),
+                    source: r["source"].as_str().map(|s| s.to_string()),
+                    duration: r["length"].as_str().map(|s| s.to_string()),
                 })
             })
             .collect()
@@ -178,6 +267,20 @@ impl SearchProvider for SearxngProvider {
     }
 
     async fn search(&self, query: &str, max_results: u8) -> Result<SearchResponse> {
+        self.search_category(query, max_results, SearchCategory::Web)
+            .await
+    }
+
+    fn supports_category(&self, _category: SearchCategory) -> bool {
+        true
+    }
+
+    async fn search_category(
+        &self,
+        query: &str,
+        max_results: u8,
+        category: SearchCategory,
+    ) -> Result<SearchResponse> {
         let start = Instant::now();
 
         let base = self.config.base_url.trim_end_matches('/');
@@ -187,11 +290,14 @@ impl SearchProvider for SearxngProvider {
             url_encode(query)
         );
 
-        if !self.config.categories.is_empty() {
-            url.push_str(&format!(
-                "&categories={}",
-                url_encode(&self.config.categories)
-            ));
+        // Verticals map directly onto SearXNG categories and replace the
+        // configured default
+        let categories = match category {
+            SearchCategory::Web => self.config.categories.as_str(),
+            other => other.as_str(),
+        };
+        if !categories.is_empty() {
+            url.push_str(&format!("&categories={}", url_encode(categories)));
         }
         if !self.config.language.is_empty() {
             url.push_str(&format!("&language={}", url_encode(&self.config.language)));
@@ -202,6 +308,9 @@ impl SearchProvider for SearxngProvider {
                 url_encode(&self.config.time_range)
             ));
         }
+        if self.safe_search {
+            url.push_str("&safesearch=2");
+        }
 
         let resp = self
             .client
@@ -228,6 +337,7 @@ impl SearchProvider for SearxngProvider {
                 estimated_cost_usd: 0.0,
                 answer: None,
                 cached: false,
+                fallbacks: Vec::new(),
             },
             results,
         })
@@ -236,6 +346,10 @@ impl SearchProvider for SearxngProvider {
     fn cost_per_query(&self) -> f64 {
         0.0
     }
+
+    fn set_safe_search(&mut self, enabled: bool) {
+        self.safe_search = enabled;
+    }
 }
 
 // ── Brave Provider ───────────────────────────────────────────────────────────
@@ -243,6 +357,7 @@ impl SearchProvider for SearxngProvider {
 pub struct BraveProvider {
     client: reqwest::Client,
     config: BraveConfig,
+    safe_search: bool,
 }
 
 impl BraveProvider {
@@ -250,6 +365,7 @@ impl BraveProvider {
         Self {
             client: reqwest::Client::new(),
             config,
+            safe_search: false,
         }
     }
 
@@ -266,6 +382,35 @@ impl BraveProvider {
                     snippet: r["description"].as_str().unwrap_or("").to_string(),
                     score: None,
                     published_date: r["age"].as_str().map(|s| s.to_string()),
+                    thumbnail: None,
+                    source: None,
+                    duration: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Parse a news, images, or videos endpoint response (top-level `results`).
+    pub fn parse_vertical_response(body: &Value, max_results: u8) -> Vec<SearchResult> {
+        let empty = vec![];
+        body["results"]
+            .as_array()
+            .unwrap_or(&empty)
+            .iter()
+            .take(max_results as usize)
+            .filter_map(|r| {
+                let str_field = |v: &Value| v.as_str().map(|s| s.to_string());
+                Some(SearchResult {
+                    title: r["title"].as_str()?.to_string(),
+                    url: r["url"].as_str()?.to_string(),
+                    snippet: r["description"].as_str().unwrap_or("").to_string(),
+                    score: None,
+                    published_date: str_field(&r["page_age"]).or_else(|| str_field(&r["age"])),
+                    thumbnail: str_field(&r["thumbnail"]["src"])
+                        .or_else(|| str_field(&r["properties"]["url"])),
+                    source: str_field(&r["meta_url"]["hostname"])
+                        .or_else(|| str_field(&r["source"])),
+                    duration: str_field(&r["video"]["duration"]),
                 })
             })
             .collect()
@@ -279,10 +424,25 @@ impl SearchProvider for BraveProvider {
     }
 
     async fn search(&self, query: &str, max_results: u8) -> Result<SearchResponse> {
+        self.search_category(query, max_results, SearchCategory::Web)
+            .await
+    }
+
+    fn supports_category(&self, _category: SearchCategory) -> bool {
+        true
+    }
+
+    async fn search_category(
+        &self,
+        query: &str,
+        max_results: u8,
+        category: SearchCategory,
+    ) -> Result<SearchResponse> {
         let start = Instant::now();
 
         let mut url = format!(
-            "https://api.search.brave.com/res/v1/web/search?q={}&count={}",
+            "https://api.search.brave.com/res/v1/{}/search?q={}&count={}",
+            category.as_str(),
             url_encode(query),
             max_results
         );
@@ -290,12 +450,16 @@ impl SearchProvider for BraveProvider {
         if !self.config.country.is_empty() {
             url.push_str(&format!("&country={}", url_encode(&self.config.country)));
         }
-        if !self.config.freshness.is_empty() {
+        // The images endpoint has no freshness filter
+        if !self.config.freshness.is_empty() && category != SearchCategory::Images {
             url.push_str(&format!(
                 "&freshness={}",
                 url_encode(&self.config.freshness)
             ));
         }
+        if self.safe_search {
+            url.push_str("&safesearch=strict");
+        }
 
         let resp = self
             .client
@@ -311,7 +475,10 @@ impl SearchProvider for BraveProvider {
 
         let body: Value = resp.json().await?;
         let latency = start.elapsed().as_millis() as u64;
-        let results = Self::parse_response(&body, max_results);
+        let results = match category {
+            SearchCategory::Web => Self::parse_response(&body, max_results),
+            _ => Self::parse_vertical_response(&body, max_results),
+        };
 
         Ok(SearchResponse {
             meta: SearchMeta {
@@ -322,6 +489,7 @@ impl SearchProvider for BraveProvider {
                 estimated_cost_usd: 0.005,
                 answer: None,
                 cached: false,
+                fallbacks: Vec::new(),
             },
             results,
         })
@@ -330,6 +498,10 @@ impl SearchProvider for BraveProvider {
     fn cost_per_query(&self) -> f64 {
         0.005
     }
+
+    fn set_safe_search(&mut self, enabled: bool) {
+        self.safe_search = enabled;
+    }
 }
 
 // ── Tavily Provider ──────────────────────────────────────────────────────────
@@ -364,6 +536,9 @@ impl TavilyProvider {
                         .as_str()
                         .or_else(|| r["publishedDate"].as_str())
                         .map(|s| s.to_string()),
+                    thumbnail: None,
+                    source: None,
+                    duration: None,
                 })
             })
             .collect();
@@ -417,6 +592,7 @@ impl SearchProvider for TavilyProvider {
                 estimated_cost_usd: 0.005,
                 answer,
                 cached: false,
+                fallbacks: Vec::new(),
             },
             results,
         })
@@ -471,6 +647,9 @@ impl PerplexityProvider {
                         snippet: item["snippet"].as_str().unwrap_or("").to_string(),
                         score: item["score"].as_f64(),
                         published_date: item["date"].as_str().map(|s| s.to_string()),
+                        thumbnail: None,
+                        source: None,
+                        duration: None,
                     });
                 }
             }
@@ -495,6 +674,9 @@ impl PerplexityProvider {
                         snippet: String::new(),
                         score: None,
                         published_date: None,
+                        thumbnail: None,
+                        source: None,
+                        duration: None,
                     });
                 }
             }
@@ -546,6 +728,7 @@ impl SearchProvider for PerplexityProvider {
                 estimated_cost_usd: 0.003,
                 answer,
                 cached: false,
+                fallbacks: Vec::new(),
             },
             results,
         })
@@ -556,218 +739,1126 @@ impl SearchProvider for PerplexityProvider {
     }
 }
 
-// ── Cache ────────────────────────────────────────────────────────────────────
-
-struct CacheEntry {
-    response: SearchResponse,
-    inserted_at: Instant,
-}
+// ── Serper Provider ──────────────────────────────────────────────────────────
 
-pub struct SearchCache {
-    entries: RwLock<HashMap<String, CacheEntry>>,
-    ttl: Duration,
+pub struct SerperProvider {
+    client: reqwest::Client,
+    config: SerperConfig,
+    safe_search: bool,
 }
 
-impl SearchCache {
-    pub fn new(ttl_seconds: u64) -> Self {
+impl SerperProvider {
+    pub fn new(config: SerperConfig) -> Self {
         Self {
-            entries: RwLock::new(HashMap::new()),
-            ttl: Duration::from_secs(ttl_seconds),
+            client: reqwest::Client::new(),
+            config,
+            safe_search: false,
         }
     }
 
-    fn cache_key(provider: &str, query: &str, max_results: u8) -> String {
-        format!(
-            "{}:{}:{}",
-            provider,
-            query.to_lowercase().trim(),
-            max_results
-        )
-    }
+    pub fn parse_response(body: &Value, max_results: u8) -> (Vec<SearchResult>, Option<String>) {
+        let empty = vec![];
+        let results = body["organic"]
+            .as_array()
+            .unwrap_or(&empty)
+            .iter()
+            .take(max_results as usize)
+            .filter_map(|r| {
+                Some(SearchResult {
+                    title: r["title"].as_str()?.to_string(),
+                    url: r["link"].as_str()?.to_string(),
+                    snippet: r["snippet"].as_str().unwrap_or("").to_string(),
+                    score: None,
+                    published_date: r["date"].as_str().map(|s| s.to_string()),
+                    thumbnail: None,
+                    source: None,
+                    duration: None,
+                })
+            })
+            .collect();
 
-    pub async fn get(
-        &self,
-        provider: &str,
-        query: &str,
-        max_results: u8,
-    ) -> Option<SearchResponse> {
-        let key = Self::cache_key(provider, query, max_results);
-        let entries = self.entries.read().await;
-        if let Some(entry) = entries.get(&key)
-            && entry.inserted_at.elapsed() < self.ttl
-        {
-            let mut response = entry.response.clone();
-            response.meta.cached = true;
-            response.meta.estimated_cost_usd = 0.0;
-            return Some(response);
-        }
-        None
-    }
+        let answer = body["answerBox"]["answer"]
+            .as_str()
+            .or_else(|| body["answerBox"]["snippet"].as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
 
-    pub async fn put(
-        &self,
-        provider: &str,
-        query: &str,
-        max_results: u8,
-        response: SearchResponse,
-    ) {
-        let key = Self::cache_key(provider, query, max_results);
-        let mut entries = self.entries.write().await;
-        entries.insert(
-            key,
-            CacheEntry {
-                response,
-                inserted_at: Instant::now(),
-            },
-        );
-        // Lazy eviction of expired entries
-        let ttl = self.ttl;
-        entries.retain(|_, e| e.inserted_at.elapsed() < ttl);
+        (results, answer)
     }
 }
 
-// ── Router ───────────────────────────────────────────────────────────────────
-
-pub struct SearchRouter {
-    provider: Box<dyn SearchProvider>,
-    cache: SearchCache,
-    max_results: u8,
-}
-
-impl std::fmt::Debug for SearchRouter {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("SearchRouter")
-            .field("provider", &self.provider.name())
-            .field("max_results", &self.max_results)
-            .finish()
+#[async_trait]
+impl SearchProvider for SerperProvider {
+    fn name(&self) -> &str {
+        "serper"
     }
-}
 
-impl SearchRouter {
-    pub fn from_config(config: &WebSearchConfig) -> Result<Self> {
-        let provider: Box<dyn SearchProvider> = match config.provider {
-            SearchProviderType::Searxng => {
-                let c = config.searxng.as_ref().ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "tools.web_search.searxng config required when provider = 'searxng'"
-                    )
-                })?;
-                Box::new(SearxngProvider::new(c.clone()))
-            }
-            SearchProviderType::Brave => {
-                let c = config.brave.as_ref().ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "tools.web_search.brave config required when provider = 'brave'"
-                    )
-                })?;
-                Box::new(BraveProvider::new(c.clone()))
-            }
-            SearchProviderType::Tavily => {
-                let c = config.tavily.as_ref().ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "tools.web_search.tavily config required when provider = 'tavily'"
-                    )
-                })?;
-                Box::new(TavilyProvider::new(c.clone()))
-            }
-            SearchProviderType::Perplexity => {
-                let c = config.perplexity.as_ref().ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "tools.web_search.perplexity config required when provider = 'perplexity'"
-                    )
-                })?;
-                Box::new(PerplexityProvider::new(c.clone()))
-            }
-            SearchProviderType::None => {
-                anyhow::bail!("Web search is disabled (provider = 'none')")
-            }
-        };
+    async fn search(&self, query: &str, max_results: u8) -> Result<SearchResponse> {
+        let start = Instant::now();
 
-        let cache = SearchCache::new(if config.cache_enabled {
-            config.cache_ttl
-        } else {
-            0
+        let mut body = json!({
+            "q": query,
+            "num": max_results
         });
+        if !self.config.country.is_empty() {
+            body["gl"] = json!(self.config.country);
+        }
+        if !self.config.language.is_empty() {
+            body["hl"] = json!(self.config.language);
+        }
+        if self.safe_search {
+            body["safe"] = json!("active");
+        }
 
-        Ok(Self {
-            provider,
-            cache,
-            max_results: config.max_results.clamp(1, 10),
+        let resp = self
+            .client
+            .post("https://google.serper.dev/search")
+            .header("X-API-KEY", &self.config.api_key)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            anyhow::bail!("Serper API returned HTTP {}", resp.status());
+        }
+
+        let body: Value = resp.json().await?;
+        let latency = start.elapsed().as_millis() as u64;
+        let (results, answer) = Self::parse_response(&body, max_results);
+
+        Ok(SearchResponse {
+            meta: SearchMeta {
+                provider: "serper".to_string(),
+                query: query.to_string(),
+                result_count: results.len(),
+                latency_ms: latency,
+                estimated_cost_usd: 0.001,
+                answer,
+                cached: false,
+                fallbacks: Vec::new(),
+            },
+            results,
         })
     }
 
-    pub fn provider_name(&self) -> &str {
-        self.provider.name()
+    fn cost_per_query(&self) -> f64 {
+        0.001
     }
 
-    pub async fn search(&self, query: &str) -> Result<SearchResponse> {
-        self.search_with_count(query, None).await
+    fn set_safe_search(&mut self, enabled: bool) {
+        self.safe_search = enabled;
     }
+}
 
-    pub async fn search_with_count(
-        &self,
-        query: &str,
-        count: Option<u8>,
-    ) -> Result<SearchResponse> {
-        let requested = count.unwrap_or(self.max_results).clamp(1, 10);
-
-        // Check cache first
-        if let Some(cached) = self.cache.get(self.provider.name(), query, requested).await {
-            if let Err(e) =
-                record_search_usage(self.provider.name(), true, cached.meta.estimated_cost_usd)
-            {
-                warn!("Failed to record search usage stats: {}", e);
+// ── DuckDuckGo Provider ──────────────────────────────────────────────────────
+
+static DDG_RESULT_LINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?s)<a[^>]*class="result__a"[^>]*href="([^"]*)"[^>]*>(.*?)</a>"#).unwrap()
+});
+static DDG_RESULT_SNIPPET: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?s)class="result__snippet"[^>]*>(.*?)</a>"#).unwrap());
+static HTML_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+/// Decode `%XX` escapes and `+` in a URL query component.
+fn url_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
             }
-            return Ok(cached);
+            b => out.push(b),
         }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
 
-        let response = self.provider.search(query, requested).await?;
+/// Strip tags and decode the common HTML entities in a text fragment.
+fn html_to_text(html: &str) -> String {
+    HTML_TAG
+        .replace_all(html, "")
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .trim()
+        .to_string()
+}
 
-        self.cache
-            .put(self.provider.name(), query, requested, response.clone())
-            .await;
+pub struct DuckDuckGoProvider {
+    client: reqwest::Client,
+    config: DuckDuckGoConfig,
+    safe_search: bool,
+}
 
-        if let Err(e) = record_search_usage(
-            self.provider.name(),
-            response.meta.cached,
-            response.meta.estimated_cost_usd,
-        ) {
-            warn!("Failed to record search usage stats: {}", e);
+impl DuckDuckGoProvider {
+    pub fn new(config: DuckDuckGoConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+            safe_search: false,
         }
+    }
 
-        Ok(response)
+    /// Resolve a result link, unwrapping DuckDuckGo's `/l/?uddg=` redirect.
+    fn resolve_link(href: &str) -> Option<String> {
+        let href = href.replace("&amp;", "&");
+        if let Some(pos) = href.find("uddg=") {
+            let encoded = href[pos + 5..].split('&').next().unwrap_or("");
+            return Some(url_decode(encoded));
+        }
+        // Sponsored results go through an ad click endpoint; skip them
+        if href.contains("duckduckgo.com/y.js") {
+            return None;
+        }
+        if let Some(rest) = href.strip_prefix("//") {
+            return Some(format!("https://{}", rest));
+        }
+        Some(href)
     }
-}
 
-// ── WebSearchTool ────────────────────────────────────────────────────────────
+    /// Parse the DuckDuckGo HTML results page.
+    pub fn parse_response(html: &str, max_results: u8) -> Vec<SearchResult> {
+        let links: Vec<_> = DDG_RESULT_LINK.captures_iter(html).collect();
+        let mut results = Vec::new();
 
-pub struct WebSearchTool {
-    router: Arc<SearchRouter>,
-}
+        for (i, caps) in links.iter().enumerate() {
+            if results.len() >= max_results as usize {
+                break;
+            }
+            let Some(url) = Self::resolve_link(&caps[1]) else {
+                continue;
+            };
+
+            // The snippet sits between this link and the next one
+            let segment_start = caps.get(0).map_or(0, |m| m.end());
+            let segment_end = links
+                .get(i + 1)
+                .and_then(|next| next.get(0))
+                .map_or(html.len(), |m| m.start());
+            let snippet = DDG_RESULT_SNIPPET
+                .captures(&html[segment_start..segment_end])
+                .map(|c| html_to_text(&c[1]))
+                .unwrap_or_default();
 
-impl WebSearchTool {
-    pub fn new(router: Arc<SearchRouter>) -> Self {
-        Self { router }
+            results.push(SearchResult {
+                title: html_to_text(&caps[2]),
+                url,
+                snippet,
+                score: None,
+                published_date: None,
+                thumbnail: None,
+                source: None,
+                duration: None,
+            });
+        }
+
+        results
     }
 }
 
 #[async_trait]
-impl Tool for WebSearchTool {
+impl SearchProvider for DuckDuckGoProvider {
     fn name(&self) -> &str {
-        "web_search"
+        "duckduckgo"
     }
 
-    fn schema(&self) -> ToolSchema {
-        ToolSchema {
-            name: "web_search".to_string(),
-            description: "Search the web for current information. Use this when you need \
-                up-to-date facts, recent events, or information not in your training data. \
-                Returns titles, URLs, and snippets from top results."
-                .to_string(),
-            parameters: json!({
-                "type": "object",
-                "properties": {
-                    "query": {
+    async fn search(&self, query: &str, max_results: u8) -> Result<SearchResponse> {
+        let start = Instant::now();
+
+        let mut url = format!("https://html.duckduckgo.com/html/?q={}", url_encode(query));
+        if !self.config.region.is_empty() {
+            url.push_str(&format!("&kl={}", url_encode(&self.config.region)));
+        }
+        if self.safe_search {
+            url.push_str("&kp=1");
+        }
+
+        let resp = self
+            .client
+            .get(&url)
+            .header("User-Agent", "Mozilla/5.0 (compatible; LocalGPT/0.1)")
+            .header("Accept", "text/html")
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            anyhow::bail!("DuckDuckGo returned HTTP {}", resp.status());
+        }
+
+        let html = resp.text().await?;
+        let latency = start.elapsed().as_millis() as u64;
+        let results = Self::parse_response(&html, max_results);
+
+        Ok(SearchResponse {
+            meta: SearchMeta {
+                provider: "duckduckgo".to_string(),
+                query: query.to_string(),
+                result_count: results.len(),
+                latency_ms: latency,
+                estimated_cost_usd: 0.0,
+                answer: None,
+                cached: false,
+                fallbacks: Vec::new(),
+            },
+            results,
+        })
+    }
+
+    fn cost_per_query(&self) -> f64 {
+        0.0
+    }
+
+    fn set_safe_search(&mut self, enabled: bool) {
+        self.safe_search = enabled;
+    }
+}
+
+// ── Kagi Provider ────────────────────────────────────────────────────────────
+
+pub struct KagiProvider {
+    client: reqwest::Client,
+    config: KagiConfig,
+}
+
+impl KagiProvider {
+    pub fn new(config: KagiConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+        }
+    }
+
+    pub fn parse_response(body: &Value, max_results: u8) -> Vec<SearchResult> {
+        let empty = vec![];
+        body["data"]
+            .as_array()
+            .unwrap_or(&empty)
+            .iter()
+            // t = 0 is a search result; t = 1 is a related-searches block
+            .filter(|r| r["t"].as_u64() == Some(0))
+            .take(max_results as usize)
+            .filter_map(|r| {
+                Some(SearchResult {
+                    title: r["title"].as_str()?.to_string(),
+                    url: r["url"].as_str()?.to_string(),
+                    snippet: r["snippet"].as_str().unwrap_or("").to_string(),
+                    score: None,
+                    published_date: r["published"].as_str().map(|s| s.to_string()),
+                    thumbnail: None,
+                    source: None,
+                    duration: None,
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for KagiProvider {
+    fn name(&self) -> &str {
+        "kagi"
+    }
+
+    async fn search(&self, query: &str, max_results: u8) -> Result<SearchResponse> {
+        let start = Instant::now();
+
+        let url = format!(
+            "https://kagi.com/api/v0/search?q={}&limit={}",
+            url_encode(query),
+            max_results
+        );
+
+        let resp = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bot {}", self.config.api_key))
+            .header("Accept", "application/json")
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            anyhow::bail!("Kagi Search API returned HTTP {}", resp.status());
+        }
+
+        let body: Value = resp.json().await?;
+        let latency = start.elapsed().as_millis() as u64;
+        let results = Self::parse_response(&body, max_results);
+
+        Ok(SearchResponse {
+            meta: SearchMeta {
+                provider: "kagi".to_string(),
+                query: query.to_string(),
+                result_count: results.len(),
+                latency_ms: latency,
+                estimated_cost_usd: 0.025,
+                answer: None,
+                cached: false,
+                fallbacks: Vec::new(),
+            },
+            results,
+        })
+    }
+
+    fn cost_per_query(&self) -> f64 {
+        0.025
+    }
+}
+
+// ── Cache ────────────────────────────────────────────────────────────────────
+
+struct CacheEntry {
+    response: SearchResponse,
+    inserted_at: Instant,
+}
+
+/// SQLite-backed cache tier that survives restarts.
+struct DiskCache {
+    conn: std::sync::Mutex<rusqlite::Connection>,
+    max_entries: usize,
+}
+
+impl DiskCache {
+    fn open(db_path: &Path, max_entries: usize) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = rusqlite::Connection::open(db_path)?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS search_cache (
+                key TEXT PRIMARY KEY,
+                response TEXT NOT NULL,
+                inserted_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_search_cache_inserted_at ON search_cache(inserted_at);
+            "#,
+        )?;
+        Ok(Self {
+            conn: std::sync::Mutex::new(conn),
+            max_entries,
+        })
+    }
+
+    fn get(&self, key: &str, ttl: Duration) -> Result<Option<SearchResponse>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Search cache lock poisoned"))?;
+        let row: Option<(String, i64)> = conn
+            .query_row(
+                "SELECT response, inserted_at FROM search_cache WHERE key = ?1",
+                rusqlite::params![key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        let Some((json, inserted_at)) = row else {
+            return Ok(None);
+        };
+        let age = chrono::Utc::now().timestamp() - inserted_at;
+        if age < 0 || age as u64 >= ttl.as_secs() {
+            return Ok(None);
+        }
+        Ok(serde_json::from_str(&json).ok())
+    }
+
+    fn put(&self, key: &str, response: &SearchResponse, ttl: Duration) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Search cache lock poisoned"))?;
+        let now = chrono::Utc::now().timestamp();
+        conn.execute(
+            "INSERT OR REPLACE INTO search_cache (key, response, inserted_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![key, serde_json::to_string(response)?, now],
+        )?;
+        // Evict expired entries, then the oldest beyond max_entries
+        conn.execute(
+            "DELETE FROM search_cache WHERE inserted_at <= ?1",
+            rusqlite::params![now - ttl.as_secs() as i64],
+        )?;
+        conn.execute(
+            "DELETE FROM search_cache WHERE key NOT IN \
+             (SELECT key FROM search_cache ORDER BY inserted_at DESC LIMIT ?1)",
+            rusqlite::params![self.max_entries as i64],
+        )?;
+        Ok(())
+    }
+}
+
+pub struct SearchCache {
+    entries: RwLock<HashMap<String, CacheEntry>>,
+    ttl: Duration,
+    disk: Option<DiskCache>,
+}
+
+impl SearchCache {
+    pub fn new(ttl_seconds: u64) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            ttl: Duration::from_secs(ttl_seconds),
+            disk: None,
+        }
+    }
+
+    /// Create a cache that also persists entries to a SQLite database,
+    /// keeping at most `max_entries` rows.
+    pub fn with_disk(ttl_seconds: u64, db_path: &Path, max_entries: usize) -> Result<Self> {
+        Ok(Self {
+            disk: Some(DiskCache::open(db_path, max_entries.max(1))?),
+            ..Self::new(ttl_seconds)
+        })
+    }
+
+    fn cache_key(provider: &str, query: &str, max_results: u8) -> String {
+        format!(
+            "{}:{}:{}",
+            provider,
+            query.to_lowercase().trim(),
+            max_results
+        )
+    }
+
+    pub async fn get(
+        &self,
+        provider: &str,
+        query: &str,
+        max_results: u8,
+    ) -> Option<SearchResponse> {
+        let key = Self::cache_key(provider, query, max_results);
+        let cached = {
+            let entries = self.entries.read().await;
+            entries
+                .get(&key)
+                .filter(|entry| entry.inserted_at.elapsed() < self.ttl)
+                .map(|entry| entry.response.clone())
+        };
+
+        let cached = match (cached, &self.disk) {
+            (Some(response), _) => Some(response),
+            (None, Some(disk)) => disk.get(&key, self.ttl).unwrap_or_else(|e| {
+                warn!("Search cache read failed: {}", e);
+                None
+            }),
+            (None, None) => None,
+        };
+
+        cached.map(|mut response| {
+            response.meta.cached = true;
+            response.meta.estimated_cost_usd = 0.0;
+            response
+        })
+    }
+
+    pub async fn put(
+        &self,
+        provider: &str,
+        query: &str,
+        max_results: u8,
+        response: SearchResponse,
+    ) {
+        let key = Self::cache_key(provider, query, max_results);
+        if let Some(ref disk) = self.disk
+            && let Err(e) = disk.put(&key, &response, self.ttl)
+        {
+            warn!("Search cache write failed: {}", e);
+        }
+
+        let mut entries = self.entries.write().await;
+        entries.insert(
+            key,
+            CacheEntry {
+                response,
+                inserted_at: Instant::now(),
+            },
+        );
+        // Lazy eviction of expired entries
+        let ttl = self.ttl;
+        entries.retain(|_, e| e.inserted_at.elapsed() < ttl);
+    }
+}
+
+// ── Daily Quotas ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProviderDailyUsage {
+    pub queries: u64,
+    pub spend_usd: f64,
+}
+
+/// Per-provider usage for the current UTC day, used to enforce quotas.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchQuotaUsage {
+    pub date: String,
+    pub providers: HashMap<String, ProviderDailyUsage>,
+}
+
+impl SearchQuotaUsage {
+    fn today() -> String {
+        chrono::Utc::now().format("%Y-%m-%d").to_string()
+    }
+
+    /// Reset the counters when the day has rolled over.
+    fn roll_over(&mut self) {
+        let today = Self::today();
+        if self.date != today {
+            self.date = today;
+            self.providers.clear();
+        }
+    }
+
+    fn usage(&self, provider: &str) -> ProviderDailyUsage {
+        self.providers.get(provider).cloned().unwrap_or_default()
+    }
+
+    fn record(&mut self, provider: &str, cost_usd: f64) {
+        self.roll_over();
+        let usage = self.providers.entry(provider.to_string()).or_default();
+        usage.queries += 1;
+        usage.spend_usd += cost_usd.max(0.0);
+    }
+}
+
+fn search_cache_path() -> Result<PathBuf> {
+    let paths = crate::paths::Paths::resolve()?;
+    Ok(paths.cache_dir.join("search_cache.db"))
+}
+
+fn search_quota_path() -> Result<PathBuf> {
+    let paths = crate::paths::Paths::resolve()?;
+    Ok(paths.state_dir.join("search_quota.json"))
+}
+
+/// Read today's per-provider search usage.
+pub fn read_search_quota_usage() -> Result<SearchQuotaUsage> {
+    let path = search_quota_path()?;
+    let mut usage: SearchQuotaUsage = if path.exists() {
+        serde_json::from_str(&fs::read_to_string(path)?).unwrap_or_default()
+    } else {
+        SearchQuotaUsage::default()
+    };
+    usage.roll_over();
+    Ok(usage)
+}
+
+fn write_search_quota_usage(usage: &SearchQuotaUsage) -> Result<()> {
+    let path = search_quota_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(usage)?)?;
+    Ok(())
+}
+
+/// Whether another query to this provider stays within its daily quota.
+fn within_quota(quota: Option<&SearchQuotaConfig>, usage: &ProviderDailyUsage, cost: f64) -> bool {
+    let Some(quota) = quota else {
+        return true;
+    };
+    if let Some(max) = quota.daily_queries
+        && usage.queries >= max
+    {
+        return false;
+    }
+    if let Some(max) = quota.daily_spend_usd
+        && usage.spend_usd + cost > max
+    {
+        return false;
+    }
+    true
+}
+
+// ── Domain Filter ────────────────────────────────────────────────────────────
+
+/// Allow/deny lists applied to result URLs before they reach the model.
+///
+/// Entries match the domain and all of its subdomains. Deny wins over allow;
+/// an empty allow list permits every domain not denied.
+#[derive(Debug, Clone, Default)]
+pub struct DomainFilter {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl DomainFilter {
+    pub fn new(allow: &[String], deny: &[String]) -> Self {
+        let normalize = |domains: &[String]| -> Vec<String> {
+            domains
+                .iter()
+                .map(|d| {
+                    d.trim()
+                        .trim_start_matches("*.")
+                        .trim_matches('.')
+                        .to_lowercase()
+                })
+                .filter(|d| !d.is_empty())
+                .collect()
+        };
+        Self {
+            allow: normalize(allow),
+            deny: normalize(deny),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    fn matches(host: &str, domain: &str) -> bool {
+        host == domain
+            || host
+                .strip_suffix(domain)
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    }
+
+    pub fn permits(&self, url: &str) -> bool {
+        let Some(host) = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        else {
+            return self.allow.is_empty();
+        };
+        if self.deny.iter().any(|d| Self::matches(&host, d)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|d| Self::matches(&host, d))
+    }
+
+    /// Drop results whose URL is not permitted.
+    pub fn apply(&self, response: &mut SearchResponse) {
+        if self.is_empty() {
+            return;
+        }
+        response.results.retain(|r| self.permits(&r.url));
+        response.meta.result_count = response.results.len();
+    }
+}
+
+// ── Router ───────────────────────────────────────────────────────────────────
+
+fn create_search_provider(
+    kind: &SearchProviderType,
+    config: &WebSearchConfig,
+) -> Result<Box<dyn SearchProvider>> {
+    let mut provider: Box<dyn SearchProvider> = match kind {
+        SearchProviderType::Searxng => {
+            let c = config.searxng.as_ref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "tools.web_search.searxng config required when provider = 'searxng'"
+                )
+            })?;
+            Box::new(SearxngProvider::new(c.clone()))
+        }
+        SearchProviderType::Brave => {
+            let c = config.brave.as_ref().ok_or_else(|| {
+                anyhow::anyhow!("tools.web_search.brave config required when provider = 'brave'")
+            })?;
+            Box::new(BraveProvider::new(c.clone()))
+        }
+        SearchProviderType::Tavily => {
+            let c = config.tavily.as_ref().ok_or_else(|| {
+                anyhow::anyhow!("tools.web_search.tavily config required when provider = 'tavily'")
+            })?;
+            Box::new(TavilyProvider::new(c.clone()))
+        }
+        SearchProviderType::Perplexity => {
+            let c = config.perplexity.as_ref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "tools.web_search.perplexity config required when provider = 'perplexity'"
+                )
+            })?;
+            Box::new(PerplexityProvider::new(c.clone()))
+        }
+        SearchProviderType::Serper => {
+            let c = config.serper.as_ref().ok_or_else(|| {
+                anyhow::anyhow!("tools.web_search.serper config required when provider = 'serper'")
+            })?;
+            Box::new(SerperProvider::new(c.clone()))
+        }
+        SearchProviderType::DuckDuckGo => Box::new(DuckDuckGoProvider::new(
+            config.duckduckgo.clone().unwrap_or_default(),
+        )),
+        SearchProviderType::Kagi => {
+            let c = config.kagi.as_ref().ok_or_else(|| {
+                anyhow::anyhow!("tools.web_search.kagi config required when provider = 'kagi'")
+            })?;
+            Box::new(KagiProvider::new(c.clone()))
+        }
+        SearchProviderType::None => {
+            anyhow::bail!("Web search is disabled (provider = 'none')")
+        }
+    };
+    provider.set_safe_search(config.safe_search);
+    Ok(provider)
+}
+
+pub struct SearchRouter {
+    /// Primary provider first, then fallbacks in order
+    providers: Vec<Box<dyn SearchProvider>>,
+    quotas: HashMap<String, SearchQuotaConfig>,
+    prefer_free: bool,
+    /// Today's usage; `None` until first loaded from disk
+    quota_usage: RwLock<Option<SearchQuotaUsage>>,
+    domain_filter: DomainFilter,
+    cache: SearchCache,
+    max_results: u8,
+    /// Notified when a provider uses up its daily quota
+    webhooks: Vec<WebhookConfig>,
+}
+
+impl std::fmt::Debug for SearchRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchRouter")
+            .field("providers", &self.provider_names())
+            .field("max_results", &self.max_results)
+            .finish()
+    }
+}
+
+impl SearchRouter {
+    pub fn from_config(config: &WebSearchConfig) -> Result<Self> {
+        let mut providers = vec![create_search_provider(&config.provider, config)?];
+
+        for kind in &config.fallback {
+            match create_search_provider(kind, config) {
+                Ok(p) if providers.iter().any(|e| e.name() == p.name()) => {}
+                Ok(p) => providers.push(p),
+                Err(e) => warn!("Skipping web search fallback {:?}: {}", kind, e),
+            }
+        }
+
+        let ttl = if config.cache_enabled {
+            config.cache_ttl
+        } else {
+            0
+        };
+        let cache = if config.cache_enabled && config.persistent_cache {
+            match search_cache_path()
+                .and_then(|path| SearchCache::with_disk(ttl, &path, config.cache_max_entries))
+            {
+                Ok(cache) => cache,
+                Err(e) => {
+                    warn!(
+                        "Persistent search cache unavailable, using memory only: {}",
+                        e
+                    );
+                    SearchCache::new(ttl)
+                }
+            }
+        } else {
+            SearchCache::new(ttl)
+        };
+
+        Ok(Self {
+            providers,
+            quotas: config.quotas.clone(),
+            prefer_free: config.prefer_free,
+            quota_usage: RwLock::new(None),
+            domain_filter: DomainFilter::new(&config.allowed_domains, &config.blocked_domains),
+            cache,
+            max_results: config.max_results.clamp(1, 10),
+            webhooks: Vec::new(),
+        })
+    }
+
+    /// Send `budget.exceeded` to these webhooks when a quota runs out.
+    pub fn with_webhooks(mut self, webhooks: Vec<WebhookConfig>) -> Self {
+        self.webhooks = webhooks;
+        self
+    }
+
+    /// Name of the primary provider.
+    pub fn provider_name(&self) -> &str {
+        self.providers[0].name()
+    }
+
+    /// Names of all providers, in fallback order.
+    pub fn provider_names(&self) -> Vec<&str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
+    pub async fn search(&self, query: &str) -> Result<SearchResponse> {
+        self.search_with_count(query, None).await
+    }
+
+    pub async fn search_with_count(
+        &self,
+        query: &str,
+        count: Option<u8>,
+    ) -> Result<SearchResponse> {
+        self.search_with_options(query, count, false, SearchCategory::Web)
+            .await
+    }
+
+    /// Search, trying providers in order until one succeeds.
+    ///
+    /// Providers over their daily quota or without support for `category`
+    /// are skipped. When `low_stakes` is set and `prefer_free` is enabled,
+    /// free providers are tried first.
+    pub async fn search_with_options(
+        &self,
+        query: &str,
+        count: Option<u8>,
+        low_stakes: bool,
+        category: SearchCategory,
+    ) -> Result<SearchResponse> {
+        let requested = count.unwrap_or(self.max_results).clamp(1, 10);
+        let order: Vec<usize> = self
+            .provider_order(low_stakes)
+            .into_iter()
+            .filter(|&i| self.providers[i].supports_category(category))
+            .collect();
+        if order.is_empty() {
+            anyhow::bail!("No configured search provider supports {} search", category);
+        }
+
+        // Check cache first (any provider in the chain may have served this query)
+        for &i in &order {
+            let provider = &self.providers[i];
+            let key = cache_namespace(provider.name(), category);
+            if let Some(mut cached) = self.cache.get(&key, query, requested).await {
+                self.domain_filter.apply(&mut cached);
+                if let Err(e) =
+                    record_search_usage(provider.name(), true, cached.meta.estimated_cost_usd)
+                {
+                    warn!("Failed to record search usage stats: {}", e);
+                }
+                return Ok(cached);
+            }
+        }
+
+        let mut fallbacks = Vec::new();
+        let mut last_error = None;
+
+        for &i in &order {
+            let provider = &self.providers[i];
+            let name = provider.name();
+
+            if !self.has_quota(name, provider.cost_per_query()).await {
+                debug!("Web search provider {} is over its daily quota", name);
+                fallbacks.push(name.to_string());
+                continue;
+            }
+
+            let mut response = match provider.search_category(query, requested, category).await {
+                Ok(response) => response,
+                Err(e) => {
+                    warn!("Web search provider {} failed: {}", name, e);
+                    fallbacks.push(name.to_string());
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            response.meta.fallbacks = fallbacks;
+            self.domain_filter.apply(&mut response);
+
+            let key = cache_namespace(name, category);
+            self.cache
+                .put(&key, query, requested, response.clone())
+                .await;
+            self.record_quota(name, response.meta.estimated_cost_usd)
+                .await;
+
+            if let Err(e) =
+                record_search_usage(name, response.meta.cached, response.meta.estimated_cost_usd)
+            {
+                warn!("Failed to record search usage stats: {}", e);
+            }
+
+            return Ok(response);
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            anyhow::anyhow!(
+                "All web search providers are over their daily quota ({})",
+                fallbacks.join(", ")
+            )
+        }))
+    }
+
+    /// Provider indices in the order they should be tried.
+    fn provider_order(&self, low_stakes: bool) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.providers.len()).collect();
+        if low_stakes && self.prefer_free {
+            // Stable sort keeps configured order within free and paid groups
+            order.sort_by_key(|&i| self.providers[i].cost_per_query() > 0.0);
+        }
+        order
+    }
+
+    async fn has_quota(&self, provider: &str, cost: f64) -> bool {
+        let Some(quota) = self.quotas.get(provider) else {
+            return true;
+        };
+        let mut guard = self.quota_usage.write().await;
+        let usage = guard.get_or_insert_with(|| read_search_quota_usage().unwrap_or_default());
+        usage.roll_over();
+        within_quota(Some(quota), &usage.usage(provider), cost)
+    }
+
+    async fn record_quota(&self, provider: &str, cost: f64) {
+        let mut guard = self.quota_usage.write().await;
+        let usage = guard.get_or_insert_with(|| read_search_quota_usage().unwrap_or_default());
+        usage.record(provider, cost);
+        if let Err(e) = write_search_quota_usage(usage) {
+            warn!("Failed to record search quota usage: {}", e);
+        }
+
+        // Only successful searches are recorded, so this fires once: for the
+        // search that used up the quota
+        let next_cost = self
+            .providers
+            .iter()
+            .find(|p| p.name() == provider)
+            .map_or(0.0, |p| p.cost_per_query());
+        if let Some(quota) = self.quotas.get(provider)
+            && !within_quota(Some(quota), &usage.usage(provider), next_cost)
+        {
+            let daily = usage.usage(provider);
+            crate::webhooks::emit_to(
+                &self.webhooks,
+                WebhookEvent::BudgetExceeded {
+                    budget: format!("web_search.quotas.{}", provider),
+                    limit: [
+                        quota.daily_queries.map(|q| format!("{} queries/day", q)),
+                        quota.daily_spend_usd.map(|s| format!("${:.2}/day", s)),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                    detail: format!(
+                        "{} queries, ${:.4} spent today",
+                        daily.queries, daily.spend_usd
+                    ),
+                },
+            );
+        }
+    }
+}
+
+/// Cache namespace for a provider and vertical. Web searches keep the bare
+/// provider name so existing cache entries stay valid.
+fn cache_namespace(provider: &str, category: SearchCategory) -> String {
+    match category {
+        SearchCategory::Web => provider.to_string(),
+        other => format!("{}:{}", provider, other),
+    }
+}
+
+// ── Content Enrichment ───────────────────────────────────────────────────────
+
+/// Approximate bytes per token for page excerpts.
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Fetches the pages behind top search results so the model gets real
+/// content instead of short snippets.
+pub struct ContentFetcher {
+    client: reqwest::Client,
+    config: FetchContentConfig,
+    filter: CompiledToolFilter,
+}
+
+impl ContentFetcher {
+    pub fn new(config: FetchContentConfig, filter: CompiledToolFilter) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        Ok(Self {
+            client,
+            config,
+            filter,
+        })
+    }
+
+    /// Fetch one page and trim it to the per-page token budget.
+    async fn fetch_excerpt(&self, url: &str) -> Result<String> {
+        self.filter.check(url, "web_fetch", "url")?;
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let text = tokio::time::timeout(timeout, fetch_readable_text(&self.client, url))
+            .await
+            .map_err(|_| anyhow::anyhow!("timed out after {}s", self.config.timeout_secs))??;
+        Ok(excerpt(&text, self.config.page_tokens * BYTES_PER_TOKEN))
+    }
+
+    /// Fetch the first `max_pages` results with bounded concurrency.
+    /// Returns one entry per fetched result, in result order.
+    pub async fn fetch(&self, results: &[SearchResult]) -> Vec<Result<String>> {
+        futures::stream::iter(results.iter().take(self.config.max_pages))
+            .map(|r| self.fetch_excerpt(&r.url))
+            .buffered(self.config.concurrency.max(1))
+            .collect()
+            .await
+    }
+}
+
+/// Collapse whitespace and cut to `max_bytes` on a char boundary.
+fn excerpt(text: &str, max_bytes: usize) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.len() <= max_bytes {
+        return collapsed;
+    }
+    format!(
+        "{}…",
+        &collapsed[..collapsed.floor_char_boundary(max_bytes)]
+    )
+}
+
+// ── WebSearchTool ────────────────────────────────────────────────────────────
+
+pub struct WebSearchTool {
+    router: Arc<SearchRouter>,
+    fetcher: Option<ContentFetcher>,
+}
+
+impl WebSearchTool {
+    pub fn new(router: Arc<SearchRouter>) -> Self {
+        Self {
+            router,
+            fetcher: None,
+        }
+    }
+
+    /// Enable the `fetch_content` argument.
+    pub fn with_content_fetcher(mut self, fetcher: ContentFetcher) -> Self {
+        self.fetcher = Some(fetcher);
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "web_search".to_string(),
+            description: "Search the web for current information. Use this when you need \
+                up-to-date facts, recent events, or information not in your training data. \
+                Returns titles, URLs, and snippets from top results."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": {
                         "type": "string",
                         "description": "Search query. Be specific and use keywords for best results."
                     },
@@ -776,6 +1867,20 @@ impl Tool for WebSearchTool {
                         "description": "Number of results to return (1-10, default: 5)",
                         "minimum": 1,
                         "maximum": 10
+                    },
+                    "fetch_content": {
+                        "type": "boolean",
+                        "description": "Also fetch the top result pages and include an excerpt of each (slower; use when snippets are not enough)"
+                    },
+                    "stakes": {
+                        "type": "string",
+                        "enum": ["low", "normal"],
+                        "description": "Use \"low\" for quick lookups where a free provider is good enough (default: normal)"
+                    },
+                    "category": {
+                        "type": "string",
+                        "enum": ["web", "news", "images", "videos"],
+                        "description": "Search vertical (default: web). News results include publish dates; image and video results include thumbnails."
                     }
                 },
                 "required": ["query"]
@@ -789,10 +1894,22 @@ impl Tool for WebSearchTool {
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("Missing query parameter"))?;
         let count = args["count"].as_u64().map(|n| n.clamp(1, 10) as u8);
+        let low_stakes = args["stakes"].as_str() == Some("low");
+        let fetch_content = args["fetch_content"].as_bool().unwrap_or(false);
+        let category = match args["category"].as_str() {
+            Some(s) => SearchCategory::parse(s)?,
+            None => SearchCategory::Web,
+        };
 
-        debug!("Web search: {} (count={:?})", query, count);
+        debug!(
+            "Web search: {} (count={:?}, low_stakes={}, category={})",
+            query, count, low_stakes, category
+        );
 
-        let response = self.router.search_with_count(query, count).await?;
+        let response = self
+            .router
+            .search_with_options(query, count, low_stakes, category)
+            .await?;
 
         // Include synthesized answer when provider supplies one
         let mut output = String::new();
@@ -800,8 +1917,18 @@ impl Tool for WebSearchTool {
             output.push_str(&format!("**AI Summary:**\n{}\n\n", answer));
         }
 
+        let fallback_note = if response.meta.fallbacks.is_empty() {
+            String::new()
+        } else {
+            format!(" | fell back from {}", response.meta.fallbacks.join(", "))
+        };
+        let heading = match category {
+            SearchCategory::Web => "Search results".to_string(),
+            other => format!("{} results", capitalize(other.as_str())),
+        };
         output.push_str(&format!(
-            "**Search results for:** {}\n*Provider: {} | {} results | {}ms{}*\n\n",
+            "**{} for:** {}\n*Provider: {} | {} results | {}ms{}{}*\n\n",
+            heading,
             response.meta.query,
             response.meta.provider,
             response.meta.result_count,
@@ -811,16 +1938,40 @@ impl Tool for WebSearchTool {
             } else {
                 ""
             },
+            fallback_note,
         ));
 
+        let contents = match self.fetcher {
+            Some(ref fetcher) if fetch_content => fetcher.fetch(&response.results).await,
+            _ => Vec::new(),
+        };
+
         for (i, result) in response.results.iter().enumerate() {
             output.push_str(&format!(
-                "{}. **{}**\n   {}\n   {}\n\n",
+                "{}. **{}**\n   {}\n   {}\n",
                 i + 1,
                 result.title,
                 result.url,
                 result.snippet,
             ));
+            if category != SearchCategory::Web {
+                let details: Vec<&str> = [&result.source, &result.published_date, &result.duration]
+                    .into_iter()
+                    .filter_map(|v| v.as_deref())
+                    .collect();
+                if !details.is_empty() {
+                    output.push_str(&format!("   {}\n", details.join(" · ")));
+                }
+                if let Some(thumbnail) = result.thumbnail.as_ref() {
+                    output.push_str(&format!("   Thumbnail: {}\n", thumbnail));
+                }
+            }
+            match contents.get(i) {
+                Some(Ok(content)) => output.push_str(&format!("   Content: {}\n", content)),
+                Some(Err(e)) => output.push_str(&format!("   Content unavailable: {}\n", e)),
+                None => {}
+            }
+            output.push('\n');
         }
 
         if response.results.is_empty() {
@@ -831,6 +1982,14 @@ impl Tool for WebSearchTool {
     }
 }
 
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 // ── Tests ────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -847,6 +2006,9 @@ mod tests {
                 snippet: "A test result".to_string(),
                 score: None,
                 published_date: None,
+                thumbnail: None,
+                source: None,
+                duration: None,
             }],
             meta: SearchMeta {
                 provider: "test".to_string(),
@@ -856,6 +2018,7 @@ mod tests {
                 estimated_cost_usd: 0.005,
                 answer: None,
                 cached: false,
+                fallbacks: Vec::new(),
             },
         };
 
@@ -889,6 +2052,7 @@ mod tests {
                 estimated_cost_usd: 0.0,
                 answer: None,
                 cached: false,
+                fallbacks: Vec::new(),
             },
         };
 
@@ -913,6 +2077,7 @@ mod tests {
                 estimated_cost_usd: 0.0,
                 answer: None,
                 cached: false,
+                fallbacks: Vec::new(),
             },
         };
 
@@ -940,6 +2105,7 @@ mod tests {
                 estimated_cost_usd: 0.0,
                 answer: None,
                 cached: false,
+                fallbacks: Vec::new(),
             },
         };
 
@@ -949,6 +2115,57 @@ mod tests {
         assert!(cache.get("test", "hello", 3).await.is_none());
     }
 
+    fn cache_response(query: &str) -> SearchResponse {
+        SearchResponse {
+            results: vec![],
+            meta: SearchMeta {
+                provider: "test".to_string(),
+                query: query.to_string(),
+                result_count: 0,
+                latency_ms: 0,
+                estimated_cost_usd: 0.005,
+                answer: None,
+                cached: false,
+                fallbacks: Vec::new(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_survives_restart() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_path = tmp.path().join("search_cache.db");
+
+        let cache = SearchCache::with_disk(60, &db_path, 10).unwrap();
+        cache.put("test", "hello", 5, cache_response("hello")).await;
+        drop(cache);
+
+        let reopened = SearchCache::with_disk(60, &db_path, 10).unwrap();
+        let cached = reopened.get("test", "hello", 5).await.unwrap();
+        assert!(cached.meta.cached);
+        assert_eq!(cached.meta.estimated_cost_usd, 0.0);
+        assert!(reopened.get("test", "other", 5).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_max_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_path = tmp.path().join("search_cache.db");
+
+        let cache = SearchCache::with_disk(60, &db_path, 2).unwrap();
+        for query in ["a", "b", "c"] {
+            cache.put("test", query, 5, cache_response(query)).await;
+        }
+        drop(cache);
+
+        let reopened = SearchCache::with_disk(60, &db_path, 2).unwrap();
+        let conn = reopened.disk.as_ref().unwrap().conn.lock().unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM search_cache", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
     #[test]
     fn test_router_missing_searxng_config() {
         let config = WebSearchConfig {
@@ -961,6 +2178,18 @@ mod tests {
             brave: None,
             tavily: None,
             perplexity: None,
+            serper: None,
+            duckduckgo: None,
+            kagi: None,
+            fallback: Vec::new(),
+            quotas: HashMap::new(),
+            prefer_free: true,
+            persistent_cache: false,
+            cache_max_entries: 1000,
+            fetch_content: FetchContentConfig::default(),
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+            safe_search: false,
         };
         let result = SearchRouter::from_config(&config);
         assert!(result.is_err());
@@ -984,6 +2213,18 @@ mod tests {
             brave: None,
             tavily: None,
             perplexity: None,
+            serper: None,
+            duckduckgo: None,
+            kagi: None,
+            fallback: Vec::new(),
+            quotas: HashMap::new(),
+            prefer_free: true,
+            persistent_cache: false,
+            cache_max_entries: 1000,
+            fetch_content: FetchContentConfig::default(),
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+            safe_search: false,
         };
         let result = SearchRouter::from_config(&config);
         assert!(result.is_err());
@@ -1007,6 +2248,18 @@ mod tests {
             brave: None,
             tavily: None,
             perplexity: None,
+            serper: None,
+            duckduckgo: None,
+            kagi: None,
+            fallback: Vec::new(),
+            quotas: HashMap::new(),
+            prefer_free: true,
+            persistent_cache: false,
+            cache_max_entries: 1000,
+            fetch_content: FetchContentConfig::default(),
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+            safe_search: false,
         };
         let result = SearchRouter::from_config(&config);
         assert!(result.is_err());
@@ -1025,6 +2278,18 @@ mod tests {
             brave: None,
             tavily: None,
             perplexity: None,
+            serper: None,
+            duckduckgo: None,
+            kagi: None,
+            fallback: Vec::new(),
+            quotas: HashMap::new(),
+            prefer_free: true,
+            persistent_cache: false,
+            cache_max_entries: 1000,
+            fetch_content: FetchContentConfig::default(),
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+            safe_search: false,
         };
         let result = SearchRouter::from_config(&config);
         assert!(result.is_err());
@@ -1048,6 +2313,18 @@ mod tests {
             brave: None,
             tavily: None,
             perplexity: None,
+            serper: None,
+            duckduckgo: None,
+            kagi: None,
+            fallback: Vec::new(),
+            quotas: HashMap::new(),
+            prefer_free: true,
+            persistent_cache: false,
+            cache_max_entries: 1000,
+            fetch_content: FetchContentConfig::default(),
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+            safe_search: false,
         };
         let result = SearchRouter::from_config(&config);
         assert!(result.is_err());
@@ -1139,6 +2416,136 @@ mod tests {
         assert!(results[0].score.is_none());
     }
 
+    #[test]
+    fn test_brave_parse_news_response() {
+        let body: Value = serde_json::from_str(
+            r#"{
+                "type": "news",
+                "results": [
+                    {
+                        "title": "Rust 2.0 announced",
+                        "url": "https://news.example.com/rust",
+                        "description": "The Rust team announced...",
+                        "age": "3 hours ago",
+                        "page_age": "2026-03-01T10:00:00",
+                        "meta_url": { "hostname": "news.example.com" },
+                        "thumbnail": { "src": "https://imgs.search.brave.com/abc.jpg" }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let results = BraveProvider::parse_vertical_response(&body, 5);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].published_date.as_deref(),
+            Some("2026-03-01T10:00:00")
+        );
+        assert_eq!(results[0].source.as_deref(), Some("news.example.com"));
+        assert_eq!(
+            results[0].thumbnail.as_deref(),
+            Some("https://imgs.search.brave.com/abc.jpg")
+        );
+        assert!(results[0].duration.is_none());
+    }
+
+    #[test]
+    fn test_brave_parse_video_and_image_responses() {
+        let videos: Value = serde_json::from_str(
+            r#"{
+                "results": [
+                    {
+                        "title": "Async Rust in 10 minutes",
+                        "url": "https://video.example.com/watch?v=1",
+                        "age": "1 week ago",
+                        "video": { "duration": "10:02" },
+                        "thumbnail": { "src": "https://imgs.search.brave.com/v.jpg" }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let results = BraveProvider::parse_vertical_response(&videos, 5);
+        assert_eq!(results[0].duration.as_deref(), Some("10:02"));
+        assert_eq!(results[0].published_date.as_deref(), Some("1 week ago"));
+
+        let images: Value = serde_json::from_str(
+            r#"{
+                "results": [
+                    {
+                        "title": "Ferris the crab",
+                        "url": "https://rustacean.net/",
+                        "source": "rustacean.net",
+                        "properties": { "url": "https://rustacean.net/ferris.png" }
+                    },
+                    { "title": "Missing URL" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let results = BraveProvider::parse_vertical_response(&images, 5);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].source.as_deref(), Some("rustacean.net"));
+        assert_eq!(
+            results[0].thumbnail.as_deref(),
+            Some("https://rustacean.net/ferris.png")
+        );
+    }
+
+    #[test]
+    fn test_searxng_parse_media_fields() {
+        let body: Value = serde_json::from_str(
+            r#"{
+                "results": [
+                    {
+                        "title": "Ferris",
+                        "url": "https://rustacean.net/",
+                        "img_src": "https://rustacean.net/ferris.png",
+                        "thumbnail_src": "https://rustacean.net/ferris-small.png",
+                        "source": "rustacean.net"
+                    },
+                    {
+                        "title": "Rust talk",
+                        "url": "https://video.example.com/1",
+                        "thumbnail": "https://video.example.com/1.jpg",
+                        "length": "45:10",
+                        "publishedDate": "2026-02-01T00:00:00"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let results = SearxngProvider::parse_response(&body, 5);
+        assert_eq!(
+            results[0].thumbnail.as_deref(),
+            Some("https://rustacean.net/ferris-small.png")
+        );
+        assert_eq!(results[0].source.as_deref(), Some("rustacean.net"));
+        assert_eq!(
+            results[1].thumbnail.as_deref(),
+            Some("https://video.example.com/1.jpg")
+        );
+        assert_eq!(results[1].duration.as_deref(), Some("45:10"));
+    }
+
+    #[test]
+    fn test_search_category_parse() {
+        assert_eq!(SearchCategory::parse("news").unwrap(), SearchCategory::News);
+        assert_eq!(
+            SearchCategory::parse("Images").unwrap(),
+            SearchCategory::Images
+        );
+        assert_eq!(
+            SearchCategory::parse("video").unwrap(),
+            SearchCategory::Videos
+        );
+        assert!(SearchCategory::parse("maps").is_err());
+        assert_eq!(cache_namespace("brave", SearchCategory::Web), "brave");
+        assert_eq!(cache_namespace("brave", SearchCategory::News), "brave:news");
+    }
+
     #[test]
     fn test_tavily_parse_response_with_answer() {
         let body: Value = serde_json::from_str(
@@ -1190,6 +2597,314 @@ mod tests {
         assert_eq!(answer, Some("Tokio is Rust's async runtime.".to_string()));
     }
 
+    #[test]
+    fn test_serper_parse_response_with_answer_box() {
+        let body: Value = serde_json::from_str(
+            r#"{
+                "answerBox": {"answer": "1.85.0"},
+                "organic": [
+                    {
+                        "title": "Rust Blog",
+                        "link": "https://blog.rust-lang.org",
+                        "snippet": "Announcing Rust 1.85.0",
+                        "date": "Feb 20, 2025",
+                        "position": 1
+                    },
+                    {"title": "Releases", "link": "https://github.com/rust-lang/rust/releases"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let (results, answer) = SerperProvider::parse_response(&body, 5);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].url, "https://blog.rust-lang.org");
+        assert_eq!(results[0].published_date, Some("Feb 20, 2025".to_string()));
+        assert_eq!(results[1].snippet, "");
+        assert_eq!(answer, Some("1.85.0".to_string()));
+    }
+
+    #[test]
+    fn test_duckduckgo_parse_response() {
+        let html = r#"
+            <div class="result results_links">
+              <h2 class="result__title">
+                <a rel="nofollow" class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.rust%2Dlang.org%2F&amp;rut=abc">Rust <b>Programming</b> Language</a>
+              </h2>
+              <a class="result__snippet" href="//duckduckgo.com/l/?uddg=x">A language empowering everyone &amp; more.</a>
+            </div>
+            <div class="result results_links">
+              <a rel="nofollow" class="result__a" href="https://duckduckgo.com/y.js?ad_domain=example.com">Sponsored</a>
+            </div>
+            <div class="result results_links">
+              <a rel="nofollow" class="result__a" href="https://doc.rust-lang.org/book/">The Book</a>
+            </div>
+        "#;
+
+        let results = DuckDuckGoProvider::parse_response(html, 5);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "Rust Programming Language");
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].snippet, "A language empowering everyone & more.");
+        assert_eq!(results[1].url, "https://doc.rust-lang.org/book/");
+        assert_eq!(results[1].snippet, "");
+
+        assert_eq!(DuckDuckGoProvider::parse_response(html, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_kagi_parse_response_skips_related() {
+        let body: Value = serde_json::from_str(
+            r#"{
+                "meta": {"id": "abc", "ms": 120},
+                "data": [
+                    {"t": 0, "url": "https://tokio.rs", "title": "Tokio", "snippet": "Async runtime", "published": "2024-11-01T00:00:00Z"},
+                    {"t": 1, "list": ["tokio tutorial", "tokio vs async-std"]},
+                    {"t": 0, "url": "https://docs.rs/tokio", "title": "tokio - Rust"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let results = KagiProvider::parse_response(&body, 5);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "Tokio");
+        assert_eq!(
+            results[0].published_date,
+            Some("2024-11-01T00:00:00Z".to_string())
+        );
+        assert_eq!(results[1].url, "https://docs.rs/tokio");
+    }
+
+    #[test]
+    fn test_router_provider_configs() {
+        let mut config = WebSearchConfig {
+            provider: SearchProviderType::Serper,
+            cache_enabled: true,
+            cache_ttl: 900,
+            max_results: 5,
+            prefer_native: true,
+            searxng: None,
+            brave: None,
+            tavily: None,
+            perplexity: None,
+            serper: None,
+            duckduckgo: None,
+            kagi: None,
+            fallback: Vec::new(),
+            quotas: HashMap::new(),
+            prefer_free: true,
+            persistent_cache: false,
+            cache_max_entries: 1000,
+            fetch_content: FetchContentConfig::default(),
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+            safe_search: false,
+        };
+        let err = SearchRouter::from_config(&config).unwrap_err();
+        assert!(err.to_string().contains("serper config required"));
+
+        config.provider = SearchProviderType::Kagi;
+        let err = SearchRouter::from_config(&config).unwrap_err();
+        assert!(err.to_string().contains("kagi config required"));
+
+        // DuckDuckGo needs no API key, so no config block is required
+        config.provider = SearchProviderType::DuckDuckGo;
+        let router = SearchRouter::from_config(&config).unwrap();
+        assert_eq!(router.provider_name(), "duckduckgo");
+    }
+
+    fn fallback_config() -> WebSearchConfig {
+        WebSearchConfig {
+            provider: SearchProviderType::Brave,
+            cache_enabled: true,
+            cache_ttl: 900,
+            max_results: 5,
+            prefer_native: true,
+            searxng: Some(SearxngConfig {
+                base_url: "http://localhost:8080".to_string(),
+                categories: String::new(),
+                language: String::new(),
+                time_range: String::new(),
+            }),
+            brave: Some(BraveConfig {
+                api_key: "key".to_string(),
+                country: String::new(),
+                freshness: String::new(),
+            }),
+            tavily: None,
+            perplexity: None,
+            serper: None,
+            duckduckgo: None,
+            kagi: None,
+            fallback: vec![
+                SearchProviderType::Tavily,
+                SearchProviderType::Brave,
+                SearchProviderType::Searxng,
+                SearchProviderType::DuckDuckGo,
+            ],
+            quotas: HashMap::new(),
+            prefer_free: true,
+            persistent_cache: false,
+            cache_max_entries: 1000,
+            fetch_content: FetchContentConfig::default(),
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+            safe_search: false,
+        }
+    }
+
+    #[test]
+    fn test_router_fallback_chain() {
+        let router = SearchRouter::from_config(&fallback_config()).unwrap();
+        // Tavily is skipped (no config), Brave is deduplicated
+        assert_eq!(
+            router.provider_names(),
+            vec!["brave", "searxng", "duckduckgo"]
+        );
+        assert_eq!(router.provider_name(), "brave");
+    }
+
+    #[test]
+    fn test_router_prefers_free_for_low_stakes() {
+        let mut config = fallback_config();
+        let router = SearchRouter::from_config(&config).unwrap();
+        assert_eq!(router.provider_order(false), vec![0, 1, 2]);
+        assert_eq!(router.provider_order(true), vec![1, 2, 0]);
+
+        config.prefer_free = false;
+        let router = SearchRouter::from_config(&config).unwrap();
+        assert_eq!(router.provider_order(true), vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_router_category_requires_support() {
+        let mut config = fallback_config();
+        config.provider = SearchProviderType::DuckDuckGo;
+        config.fallback = Vec::new();
+        let router = SearchRouter::from_config(&config).unwrap();
+        let err = router
+            .search_with_options("rust", None, false, SearchCategory::News)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("supports news search"));
+
+        let router = SearchRouter::from_config(&fallback_config()).unwrap();
+        let supported: Vec<&str> = router
+            .providers
+            .iter()
+            .filter(|p| p.supports_category(SearchCategory::Videos))
+            .map(|p| p.name())
+            .collect();
+        assert_eq!(supported, vec!["brave", "searxng"]);
+    }
+
+    #[test]
+    fn test_within_quota() {
+        let quota = SearchQuotaConfig {
+            daily_queries: Some(2),
+            daily_spend_usd: Some(0.01),
+        };
+        let mut usage = ProviderDailyUsage::default();
+        assert!(within_quota(None, &usage, 1.0));
+        assert!(within_quota(Some(&quota), &usage, 0.005));
+
+        usage.queries = 1;
+        usage.spend_usd = 0.008;
+        assert!(!within_quota(Some(&quota), &usage, 0.005));
+        assert!(within_quota(Some(&quota), &usage, 0.0));
+
+        usage.queries = 2;
+        assert!(!within_quota(Some(&quota), &usage, 0.0));
+    }
+
+    #[test]
+    fn test_quota_usage_rolls_over() {
+        let mut usage = SearchQuotaUsage {
+            date: "2000-01-01".to_string(),
+            providers: HashMap::new(),
+        };
+        usage.providers.insert(
+            "brave".to_string(),
+            ProviderDailyUsage {
+                queries: 50,
+                spend_usd: 0.25,
+            },
+        );
+
+        usage.record("brave", 0.005);
+        assert_eq!(usage.date, SearchQuotaUsage::today());
+        assert_eq!(usage.usage("brave").queries, 1);
+        assert_eq!(usage.usage("brave").spend_usd, 0.005);
+    }
+
+    #[test]
+    fn test_excerpt_budget() {
+        assert_eq!(excerpt("  hello \n\n  world  ", 100), "hello world");
+        assert_eq!(excerpt("abcdef ghij", 6), "abcdef…");
+        // Never splits a multi-byte character
+        assert_eq!(excerpt("ééé", 3), "é…");
+    }
+
+    #[test]
+    fn test_schema_includes_fetch_content() {
+        let router = SearchRouter::from_config(&fallback_config()).unwrap();
+        let tool = WebSearchTool::new(Arc::new(router));
+        let params = tool.schema().parameters;
+        assert_eq!(params["properties"]["fetch_content"]["type"], "boolean");
+    }
+
+    #[test]
+    fn test_domain_filter() {
+        let filter = DomainFilter::new(&[], &["pinterest.com".to_string()]);
+        assert!(filter.permits("https://example.com/a"));
+        assert!(!filter.permits("https://pinterest.com/pin/1"));
+        assert!(!filter.permits("https://www.Pinterest.com/pin/1"));
+        assert!(filter.permits("https://notpinterest.com/"));
+
+        let filter = DomainFilter::new(
+            &["*.rust-lang.org".to_string(), "docs.rs".to_string()],
+            &["blog.rust-lang.org".to_string()],
+        );
+        assert!(filter.permits("https://rust-lang.org"));
+        assert!(filter.permits("https://doc.rust-lang.org/std/"));
+        assert!(filter.permits("https://docs.rs/tokio"));
+        assert!(!filter.permits("https://blog.rust-lang.org/"));
+        assert!(!filter.permits("https://example.com"));
+        assert!(!filter.permits("not a url"));
+    }
+
+    #[test]
+    fn test_domain_filter_apply_updates_count() {
+        let filter = DomainFilter::new(&[], &["spam.example".to_string()]);
+        let mut response = cache_response("q");
+        response.results = ["https://good.example/", "https://spam.example/x"]
+            .iter()
+            .map(|url| SearchResult {
+                title: "t".to_string(),
+                url: url.to_string(),
+                snippet: String::new(),
+                score: None,
+                published_date: None,
+                thumbnail: None,
+                source: None,
+                duration: None,
+            })
+            .collect();
+        response.meta.result_count = 2;
+
+        filter.apply(&mut response);
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.meta.result_count, 1);
+    }
+
+    #[test]
+    fn test_url_decode() {
+        assert_eq!(url_decode("a%20b+c%2Fd"), "a b c/d");
+        assert_eq!(url_decode("100%"), "100%");
+        assert_eq!(url_decode("%zz"), "%zz");
+    }
+
     #[test]
     fn test_web_search_tool_schema() {
         let config = WebSearchConfig {
@@ -1207,6 +2922,18 @@ mod tests {
             brave: None,
             tavily: None,
             perplexity: None,
+            serper: None,
+            duckduckgo: None,
+            kagi: None,
+            fallback: Vec::new(),
+            quotas: HashMap::new(),
+            prefer_free: true,
+            persistent_cache: false,
+            cache_max_entries: 1000,
+            fetch_content: FetchContentConfig::default(),
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+            safe_search: false,
         };
         let router = SearchRouter::from_config(&config).unwrap();
         let tool = WebSearchTool::new(Arc::new(router));
@@ -1231,6 +2958,9 @@ mod tests {
                     snippet: "First snippet".to_string(),
                     score: None,
                     published_date: None,
+                    thumbnail: None,
+                    source: None,
+                    duration: None,
                 },
                 SearchResult {
                     title: "Second Result".to_string(),
@@ -1238,6 +2968,9 @@ mod tests {
                     snippet: "Second snippet".to_string(),
                     score: None,
                     published_date: None,
+                    thumbnail: None,
+                    source: None,
+                    duration: None,
                 },
             ],
             meta: SearchMeta {
@@ -1248,6 +2981,7 @@ mod tests {
                 estimated_cost_usd: 0.0,
                 answer: None,
                 cached: false,
+                fallbacks: Vec::new(),
             },
         };
 
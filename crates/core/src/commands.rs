@@ -169,6 +169,13 @@ pub const COMMANDS: &[SlashCommand] = &[
         usage: "",
         interfaces: &[Interface::Cli, Interface::Telegram, Interface::Discord],
     },
+    SlashCommand {
+        name: "stop",
+        description: "Stop the running response",
+        aliases: &[],
+        usage: "",
+        interfaces: &[Interface::Telegram],
+    },
     SlashCommand {
         name: "unpair",
         description: "Unpair this bot account",
@@ -178,6 +185,13 @@ pub const COMMANDS: &[SlashCommand] = &[
     },
 ];
 
+/// Whether `name` is a built-in command or alias on any interface.
+pub fn is_builtin_command(name: &str) -> bool {
+    COMMANDS
+        .iter()
+        .any(|c| c.name == name || c.aliases.contains(&name))
+}
+
 /// Format help text for a given interface.
 pub fn format_help_text(iface: Interface) -> String {
     let mut lines = vec!["Commands:".to_string()];
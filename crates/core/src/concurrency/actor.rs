@@ -27,25 +27,39 @@
 //!
 //! # Supervision
 //!
-//! Actors can be supervised to restart on panic:
+//! Actors can be supervised to restart on panic. The supervisor runs the
+//! message loop in its own task and, when it panics or the agent fails to
+//! start, waits `restart_delay` and starts it again with the same mailbox, up
+//! to `max_restarts` times:
 //!
 //! ```ignore
-//! let (actor_ref, handle) = AgentActor::spawn_supervised(config, agent_id);
+//! let handle = AgentActor::spawn_supervised(config, agent_id)?;
+//! let mut events = handle.subscribe();
+//! handle.reference.chat("hello").await?;
 //! // If the actor panics, it will be restarted automatically
+//! while let Ok(event) = events.recv().await {
+//!     println!("{:?}", event); // Started, Failed, Stopped
+//! }
 //! ```
 
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{Mutex, broadcast, mpsc, oneshot};
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info};
 
-use crate::agent::{Agent, AgentConfig};
+use futures::StreamExt;
+
+use super::cancel::CancellationToken;
+use crate::agent::{Agent, AgentConfig, StreamEvent};
 use crate::config::Config;
 use crate::memory::MemoryManager;
 
+use super::mailbox::{MailboxReceiver, MailboxSender, Prioritized, SendError, mailbox};
+use super::pool::PoolInner;
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Messages
 // ─────────────────────────────────────────────────────────────────────────────
@@ -53,15 +67,20 @@ use crate::memory::MemoryManager;
 /// Messages that can be sent to an agent actor
 #[derive(Debug)]
 pub enum AgentMessage {
-    /// Send a chat message and wait for response
+    /// Send a chat message and wait for response. Cancelling `cancel` ends
+    /// the turn with `TurnCancelled`.
     Chat {
         input: String,
+        cancel: CancellationToken,
         reply: oneshot::Sender<Result<String>>,
     },
 
-    /// Send a chat message with tools and stream response
+    /// Send a chat message with tools and stream response. Chunks are sent
+    /// as the turn produces them; dropping the receiver or cancelling
+    /// `cancel` ends the turn and removes it from the session.
     ChatStream {
         input: String,
+        cancel: CancellationToken,
         reply: oneshot::Sender<Result<mpsc::Receiver<StreamChunk>>>,
     },
 
@@ -108,12 +127,17 @@ pub enum StreamChunk {
     /// Text content
     Content(String),
     /// Tool call started
-    ToolStart { name: String, id: String },
+    ToolStart {
+        name: String,
+        id: String,
+        arguments: String,
+    },
     /// Tool call completed
     ToolEnd {
         name: String,
         id: String,
         output: String,
+        warnings: Vec<String>,
     },
     /// Stream complete
     Done,
@@ -121,6 +145,35 @@ pub enum StreamChunk {
     Error(String),
 }
 
+impl From<StreamEvent> for StreamChunk {
+    fn from(event: StreamEvent) -> Self {
+        match event {
+            StreamEvent::Content(text) => StreamChunk::Content(text),
+            StreamEvent::ToolCallStart {
+                name,
+                id,
+                arguments,
+            } => StreamChunk::ToolStart {
+                name,
+                id,
+                arguments,
+            },
+            StreamEvent::ToolCallEnd {
+                name,
+                id,
+                output,
+                warnings,
+            } => StreamChunk::ToolEnd {
+                name,
+                id,
+                output,
+                warnings,
+            },
+            StreamEvent::Done => StreamChunk::Done,
+        }
+    }
+}
+
 /// Status information about an agent
 #[derive(Debug, Clone)]
 pub struct AgentStatus {
@@ -132,8 +185,18 @@ pub struct AgentStatus {
     pub message_count: usize,
     /// Token count
     pub token_count: usize,
-    /// Whether the agent is busy
+    /// Times the session was compacted
+    pub compaction_count: u32,
+    /// Tokens sent to the provider this session
+    pub api_input_tokens: u64,
+    /// Tokens received from the provider this session
+    pub api_output_tokens: u64,
+    /// Chunks in the memory index
+    pub memory_chunks: usize,
+    /// Whether messages are waiting behind this status request
     pub is_busy: bool,
+    /// Messages waiting in the mailbox (control messages not counted)
+    pub queue_depth: usize,
 }
 
 /// Result from memory search
@@ -153,26 +216,89 @@ pub struct MemorySearchResult {
 /// A reference to an agent actor for sending messages
 #[derive(Clone)]
 pub struct AgentRef {
-    sender: mpsc::Sender<AgentMessage>,
+    mailbox: Mailbox,
+}
+
+/// Where an `AgentRef` delivers its messages
+#[derive(Clone)]
+enum Mailbox {
+    /// A single actor's mailbox
+    Actor(MailboxSender<AgentMessage>),
+    /// A session in an [`ActorPool`](super::ActorPool), routed on each send
+    Pool {
+        pool: Arc<PoolInner>,
+        session_id: Arc<str>,
+    },
 }
 
 impl AgentRef {
     /// Create a new agent reference
-    fn new(sender: mpsc::Sender<AgentMessage>) -> Self {
-        Self { sender }
+    fn new(sender: MailboxSender<AgentMessage>) -> Self {
+        Self {
+            mailbox: Mailbox::Actor(sender),
+        }
+    }
+
+    /// A reference to one session of a pool
+    pub(super) fn pooled(pool: Arc<PoolInner>, session_id: &str) -> Self {
+        Self {
+            mailbox: Mailbox::Pool {
+                pool,
+                session_id: session_id.into(),
+            },
+        }
+    }
+
+    async fn send(&self, msg: AgentMessage) -> Result<()> {
+        match &self.mailbox {
+            Mailbox::Actor(sender) => sender.send(msg).await.map_err(SendError::into_error),
+            Mailbox::Pool { pool, session_id } => pool.send(session_id, msg).await,
+        }
     }
 
     /// Send a chat message and wait for response
     pub async fn chat(&self, input: &str) -> Result<String> {
+        self.chat_with_cancel(input, CancellationToken::new()).await
+    }
+
+    /// Like [`Self::chat`], ending the turn early if `cancel` is cancelled
+    pub async fn chat_with_cancel(&self, input: &str, cancel: CancellationToken) -> Result<String> {
         let (reply_tx, reply_rx) = oneshot::channel();
 
-        self.sender
-            .send(AgentMessage::Chat {
-                input: input.to_string(),
-                reply: reply_tx,
-            })
+        self.send(AgentMessage::Chat {
+            input: input.to_string(),
+            cancel,
+            reply: reply_tx,
+        })
+        .await?;
+
+        reply_rx
             .await
-            .map_err(|_| anyhow::anyhow!("Actor channel closed"))?;
+            .map_err(|_| anyhow::anyhow!("Actor did not respond"))?
+    }
+
+    /// Send a chat message and receive the reply as it streams, with tool
+    /// calls. Dropping the receiver cancels the turn.
+    pub async fn chat_stream(&self, input: &str) -> Result<mpsc::Receiver<StreamChunk>> {
+        self.chat_stream_with_cancel(input, CancellationToken::new())
+            .await
+    }
+
+    /// Like [`Self::chat_stream`], ending the turn early if `cancel` is
+    /// cancelled
+    pub async fn chat_stream_with_cancel(
+        &self,
+        input: &str,
+        cancel: CancellationToken,
+    ) -> Result<mpsc::Receiver<StreamChunk>> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+
+        self.send(AgentMessage::ChatStream {
+            input: input.to_string(),
+            cancel,
+            reply: reply_tx,
+        })
+        .await?;
 
         reply_rx
             .await
@@ -183,10 +309,8 @@ impl AgentRef {
     pub async fn new_session(&self) -> Result<()> {
         let (reply_tx, reply_rx) = oneshot::channel();
 
-        self.sender
-            .send(AgentMessage::NewSession { reply: reply_tx })
-            .await
-            .map_err(|_| anyhow::anyhow!("Actor channel closed"))?;
+        self.send(AgentMessage::NewSession { reply: reply_tx })
+            .await?;
 
         reply_rx
             .await
@@ -197,13 +321,11 @@ impl AgentRef {
     pub async fn resume_session(&self, session_id: &str) -> Result<()> {
         let (reply_tx, reply_rx) = oneshot::channel();
 
-        self.sender
-            .send(AgentMessage::ResumeSession {
-                session_id: session_id.to_string(),
-                reply: reply_tx,
-            })
-            .await
-            .map_err(|_| anyhow::anyhow!("Actor channel closed"))?;
+        self.send(AgentMessage::ResumeSession {
+            session_id: session_id.to_string(),
+            reply: reply_tx,
+        })
+        .await?;
 
         reply_rx
             .await
@@ -214,10 +336,7 @@ impl AgentRef {
     pub async fn compact(&self) -> Result<(usize, usize)> {
         let (reply_tx, reply_rx) = oneshot::channel();
 
-        self.sender
-            .send(AgentMessage::Compact { reply: reply_tx })
-            .await
-            .map_err(|_| anyhow::anyhow!("Actor channel closed"))?;
+        self.send(AgentMessage::Compact { reply: reply_tx }).await?;
 
         reply_rx
             .await
@@ -229,7 +348,6 @@ impl AgentRef {
         let (reply_tx, reply_rx) = oneshot::channel();
 
         let _ = self
-            .sender
             .send(AgentMessage::ClearSession { reply: reply_tx })
             .await;
 
@@ -240,10 +358,7 @@ impl AgentRef {
     pub async fn status(&self) -> Result<AgentStatus> {
         let (reply_tx, reply_rx) = oneshot::channel();
 
-        self.sender
-            .send(AgentMessage::Status { reply: reply_tx })
-            .await
-            .map_err(|_| anyhow::anyhow!("Actor channel closed"))?;
+        self.send(AgentMessage::Status { reply: reply_tx }).await?;
 
         reply_rx
             .await
@@ -254,13 +369,11 @@ impl AgentRef {
     pub async fn set_model(&self, model: &str) -> Result<()> {
         let (reply_tx, reply_rx) = oneshot::channel();
 
-        self.sender
-            .send(AgentMessage::SetModel {
-                model: model.to_string(),
-                reply: reply_tx,
-            })
-            .await
-            .map_err(|_| anyhow::anyhow!("Actor channel closed"))?;
+        self.send(AgentMessage::SetModel {
+            model: model.to_string(),
+            reply: reply_tx,
+        })
+        .await?;
 
         reply_rx
             .await
@@ -275,31 +388,38 @@ impl AgentRef {
     ) -> Result<Vec<MemorySearchResult>> {
         let (reply_tx, reply_rx) = oneshot::channel();
 
-        self.sender
-            .send(AgentMessage::SearchMemory {
-                query: query.to_string(),
-                max_results,
-                reply: reply_tx,
-            })
-            .await
-            .map_err(|_| anyhow::anyhow!("Actor channel closed"))?;
+        self.send(AgentMessage::SearchMemory {
+            query: query.to_string(),
+            max_results,
+            reply: reply_tx,
+        })
+        .await?;
 
         reply_rx
             .await
             .map_err(|_| anyhow::anyhow!("Actor did not respond"))?
     }
 
-    /// Stop the actor
+    /// Stop the actor; for a pooled reference, end and save the session
     pub async fn stop(&self) -> Result<()> {
-        self.sender
-            .send(AgentMessage::Stop)
-            .await
-            .map_err(|_| anyhow::anyhow!("Actor channel closed"))
+        self.send(AgentMessage::Stop).await
+    }
+
+    /// Messages waiting for the actor, not counting control messages.
+    /// A pooled reference reports its actor's queue, shared by its sessions.
+    pub fn queue_depth(&self) -> usize {
+        match &self.mailbox {
+            Mailbox::Actor(sender) => sender.queue_depth(),
+            Mailbox::Pool { pool, session_id } => pool.queue_depth(session_id),
+        }
     }
 
     /// Check if the actor is still running
     pub fn is_connected(&self) -> bool {
-        !self.sender.is_closed()
+        match &self.mailbox {
+            Mailbox::Actor(sender) => !sender.is_closed(),
+            Mailbox::Pool { pool, .. } => !pool.is_shut_down(),
+        }
     }
 }
 
@@ -310,7 +430,8 @@ impl AgentRef {
 /// Configuration for spawning an agent actor
 #[derive(Debug, Clone)]
 pub struct ActorConfig {
-    /// Size of the mailbox buffer
+    /// Size of the mailbox buffer; sends to a full mailbox fail with
+    /// [`ActorBusy`](super::ActorBusy) instead of waiting
     pub mailbox_size: usize,
     /// Whether to restart on panic
     pub restart_on_panic: bool,
@@ -354,7 +475,7 @@ impl AgentActor {
         agent_id: &str,
         actor_config: ActorConfig,
     ) -> Result<ActorHandle> {
-        let (sender, mut receiver) = mpsc::channel::<AgentMessage>(actor_config.mailbox_size);
+        let (sender, mut receiver) = mailbox(actor_config.mailbox_size);
         let reference = AgentRef::new(sender);
 
         let agent_id = agent_id.to_string();
@@ -366,17 +487,8 @@ impl AgentActor {
             &agent_id,
         )?);
 
-        let agent_config = AgentConfig {
-            model: config.agent.default_model.clone(),
-            context_window: config.agent.context_window,
-            reserve_tokens: config.agent.reserve_tokens,
-        };
-
         let task = tokio::spawn(async move {
-            // Initialize agent
-            let agent_result = Agent::new(agent_config, &config, Arc::clone(&memory)).await;
-
-            let mut agent = match agent_result {
+            let mut agent = match start_agent(&config, Arc::clone(&memory)).await {
                 Ok(a) => a,
                 Err(e) => {
                     error!("Failed to initialize agent actor: {}", e);
@@ -384,103 +496,8 @@ impl AgentActor {
                 }
             };
 
-            // Start a new session
-            if let Err(e) = agent.new_session().await {
-                error!("Failed to create initial session: {}", e);
-                return;
-            }
-
             info!("Agent actor '{}' started", agent_id);
-
-            // Message loop
-            while let Some(msg) = receiver.recv().await {
-                match msg {
-                    AgentMessage::Chat { input, reply } => {
-                        let result = agent.chat(&input).await;
-                        let _ = reply.send(result);
-                    }
-
-                    AgentMessage::ChatStream { input, reply } => {
-                        // For streaming, we create a channel and spawn a task
-                        // Note: For now, we use non-streaming chat and send as single chunk
-                        // Full streaming would require restructuring to avoid borrow issues
-                        let (tx, rx) = mpsc::channel(32);
-
-                        match agent.chat(&input).await {
-                            Ok(response) => {
-                                let _ = reply.send(Ok(rx));
-                                let _ = tx.send(StreamChunk::Content(response)).await;
-                                let _ = tx.send(StreamChunk::Done).await;
-                            }
-                            Err(e) => {
-                                let _ = reply.send(Err(e));
-                            }
-                        }
-                    }
-
-                    AgentMessage::NewSession { reply } => {
-                        let result = agent.new_session().await;
-                        let _ = reply.send(result);
-                    }
-
-                    AgentMessage::ResumeSession { session_id, reply } => {
-                        let result = agent.resume_session(&session_id).await;
-                        let _ = reply.send(result);
-                    }
-
-                    AgentMessage::Compact { reply } => {
-                        let result = agent.compact_session().await;
-                        let _ = reply.send(result);
-                    }
-
-                    AgentMessage::ClearSession { reply } => {
-                        agent.clear_session();
-                        let _ = reply.send(());
-                    }
-
-                    AgentMessage::Status { reply } => {
-                        let status = agent.session_status();
-                        let _ = reply.send(AgentStatus {
-                            model: agent.model().to_string(),
-                            session_id: status.id,
-                            message_count: status.message_count,
-                            token_count: status.token_count,
-                            is_busy: false, // Would need more tracking
-                        });
-                    }
-
-                    AgentMessage::SetModel { model, reply } => {
-                        let result = agent.set_model(&model);
-                        let _ = reply.send(result);
-                    }
-
-                    AgentMessage::SearchMemory {
-                        query,
-                        max_results,
-                        reply,
-                    } => {
-                        let result = memory.search(&query, max_results).map(|chunks| {
-                            chunks
-                                .into_iter()
-                                .map(|c| MemorySearchResult {
-                                    file: c.file,
-                                    content: c.content,
-                                    score: c.score,
-                                    line_start: c.line_start as usize,
-                                    line_end: c.line_end as usize,
-                                })
-                                .collect()
-                        });
-                        let _ = reply.send(result);
-                    }
-
-                    AgentMessage::Stop => {
-                        info!("Agent actor '{}' stopping", agent_id);
-                        break;
-                    }
-                }
-            }
-
+            run_loop(&mut agent, &memory, &agent_id, &mut receiver).await;
             debug!("Agent actor '{}' stopped", agent_id);
         });
 
@@ -497,60 +514,248 @@ impl AgentActor {
     }
 
     /// Spawn a supervised actor with custom configuration
+    ///
+    /// Each run of the actor builds a fresh agent and session. Messages queued
+    /// in the mailbox survive a restart; the one being handled when the actor
+    /// panicked is dropped, so its caller gets "Actor did not respond".
     pub fn spawn_supervised_with_config(
         config: Config,
         agent_id: &str,
         actor_config: ActorConfig,
     ) -> Result<SupervisedHandle> {
-        let (supervisor_tx, _supervisor_rx) = mpsc::channel::<SupervisorMessage>(10);
-        let (sender, _receiver) = mpsc::channel::<AgentMessage>(actor_config.mailbox_size);
-        let reference = AgentRef::new(sender);
-
         let agent_id = agent_id.to_string();
+        let (sender, receiver) = mailbox(actor_config.mailbox_size);
+        let start = {
+            let agent_id = agent_id.clone();
+            move |mailbox: Arc<Mutex<MailboxReceiver<AgentMessage>>>| {
+                let config = config.clone();
+                let agent_id = agent_id.clone();
+                async move {
+                    let memory = Arc::new(MemoryManager::new_with_full_config(
+                        &config.memory,
+                        Some(&config),
+                        &agent_id,
+                    )?);
+                    let mut agent = start_agent(&config, Arc::clone(&memory)).await?;
+                    let mut mailbox = mailbox.lock().await;
+                    run_loop(&mut agent, &memory, &agent_id, &mut mailbox).await;
+                    Ok(())
+                }
+            }
+        };
+        let supervisor = supervise(agent_id, actor_config, receiver, start);
+        Ok(SupervisedHandle {
+            reference: AgentRef::new(sender),
+            supervisor_task: supervisor.task,
+            control: supervisor.control,
+            events: supervisor.events,
+        })
+    }
+}
 
-        // Supervisor task (simplified - full implementation would monitor the actor)
-        let supervisor_task = tokio::spawn(async move {
-            // Initialize agent
-            let memory =
-                match MemoryManager::new_with_full_config(&config.memory, Some(&config), &agent_id)
-                {
-                    Ok(m) => Arc::new(m),
-                    Err(e) => {
-                        error!("Failed to create memory for supervised actor: {}", e);
-                        return;
-                    }
-                };
+/// Build an agent with a new session, ready to handle messages
+pub(super) async fn start_agent(config: &Config, memory: Arc<MemoryManager>) -> Result<Agent> {
+    let agent_config = AgentConfig {
+        model: config.agent.default_model.clone(),
+        context_window: config.agent.context_window,
+        reserve_tokens: config.agent.reserve_tokens,
+    };
+    let mut agent = Agent::new(agent_config, config, memory).await?;
+    agent.new_session().await?;
+    Ok(agent)
+}
 
-            let agent_config = AgentConfig {
-                model: config.agent.default_model.clone(),
-                context_window: config.agent.context_window,
-                reserve_tokens: config.agent.reserve_tokens,
-            };
+/// Handle messages until `Stop` or until every `AgentRef` is dropped
+async fn run_loop(
+    agent: &mut Agent,
+    memory: &MemoryManager,
+    agent_id: &str,
+    receiver: &mut MailboxReceiver<AgentMessage>,
+) {
+    while let Some(msg) = receiver.recv().await {
+        if let AgentMessage::Stop = msg {
+            info!("Agent actor '{}' stopping", agent_id);
+            break;
+        }
+        handle_message(agent, memory, msg, receiver.queue_depth()).await;
+    }
+}
 
-            let _agent = match Agent::new(agent_config, &config, memory).await {
-                Ok(a) => a,
-                Err(e) => {
-                    error!("Failed to create supervised agent: {}", e);
-                    return;
+/// Handle one message and reply to its sender. `Stop` is left to the caller.
+/// `queue_depth` is the number of messages waiting, for `Status`.
+pub(super) async fn handle_message(
+    agent: &mut Agent,
+    memory: &MemoryManager,
+    msg: AgentMessage,
+    queue_depth: usize,
+) {
+    match msg {
+        AgentMessage::Chat {
+            input,
+            cancel,
+            reply,
+        } => {
+            agent.set_cancel_token(cancel);
+            let result = agent.chat(&input).await;
+            let _ = reply.send(result);
+        }
+
+        AgentMessage::ChatStream {
+            input,
+            cancel,
+            reply,
+        } => {
+            agent.set_cancel_token(cancel);
+            stream_turn(agent, &input, reply).await;
+        }
+
+        AgentMessage::NewSession { reply } => {
+            let result = agent.new_session().await;
+            let _ = reply.send(result);
+        }
+
+        AgentMessage::ResumeSession { session_id, reply } => {
+            let result = agent.resume_session(&session_id).await;
+            let _ = reply.send(result);
+        }
+
+        AgentMessage::Compact { reply } => {
+            let result = agent.compact_session().await;
+            let _ = reply.send(result);
+        }
+
+        AgentMessage::ClearSession { reply } => {
+            agent.clear_session();
+            let _ = reply.send(());
+        }
+
+        AgentMessage::Status { reply } => {
+            let status = agent.session_status();
+            let _ = reply.send(AgentStatus {
+                model: agent.model().to_string(),
+                session_id: status.id,
+                message_count: status.message_count,
+                token_count: status.token_count,
+                compaction_count: status.compaction_count,
+                api_input_tokens: status.api_input_tokens,
+                api_output_tokens: status.api_output_tokens,
+                memory_chunks: agent.memory_chunk_count(),
+                is_busy: queue_depth > 0,
+                queue_depth,
+            });
+        }
+
+        AgentMessage::SetModel { model, reply } => {
+            let result = agent.set_model(&model);
+            let _ = reply.send(result);
+        }
+
+        AgentMessage::SearchMemory {
+            query,
+            max_results,
+            reply,
+        } => {
+            let result = memory.search(&query, max_results).map(|chunks| {
+                chunks
+                    .into_iter()
+                    .map(|c| MemorySearchResult {
+                        file: c.file,
+                        content: c.content,
+                        score: c.score,
+                        line_start: c.line_start as usize,
+                        line_end: c.line_end as usize,
+                    })
+                    .collect()
+            });
+            let _ = reply.send(result);
+        }
+
+        AgentMessage::Stop => {}
+    }
+}
+
+/// Run a turn, forwarding its events to the caller as they arrive. The
+/// stream borrows the agent, so the actor drives it here rather than handing
+/// it off; other messages wait until the turn ends.
+async fn stream_turn(
+    agent: &mut Agent,
+    input: &str,
+    reply: oneshot::Sender<Result<mpsc::Receiver<StreamChunk>>>,
+) {
+    let (tx, rx) = mpsc::channel(32);
+    let before = agent.session_status();
+
+    let cancelled = {
+        let stream = match agent.chat_stream_with_tools(input, Vec::new()).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = reply.send(Err(e));
+                return;
+            }
+        };
+        if reply.send(Ok(rx)).is_err() {
+            true
+        } else {
+            let mut stream = std::pin::pin!(stream);
+            let mut cancelled = false;
+            while let Some(event) = stream.next().await {
+                let chunk = match event {
+                    Ok(event) => StreamChunk::from(event),
+                    Err(e) => StreamChunk::Error(e.to_string()),
+                };
+                if tx.send(chunk).await.is_err() {
+                    cancelled = true;
+                    break;
                 }
-            };
+            }
+            cancelled
+        }
+    };
 
-            info!("Supervised agent '{}' started", agent_id);
+    if cancelled || agent.is_cancelled() {
+        debug!("Turn cancelled or stream receiver dropped; discarding the turn");
+        agent.rewind_session(&before);
+    }
+}
 
-            // For now, supervised mode just tracks initialization
-            // Full implementation would run the actor loop with panic catching
-            // and use _supervisor_rx for control messages
-            // TODO: Implement full supervision with restart on panic
-        });
+impl Prioritized for AgentMessage {
+    fn is_priority(&self) -> bool {
+        matches!(self, AgentMessage::Stop | AgentMessage::Status { .. })
+    }
+}
 
-        Ok(SupervisedHandle {
-            reference,
-            supervisor_task,
-            control: supervisor_tx,
-        })
+impl AgentMessage {
+    /// Reply with `error` instead of handling the message
+    pub(super) fn fail(self, error: anyhow::Error) {
+        match self {
+            AgentMessage::Chat { reply, .. } => {
+                let _ = reply.send(Err(error));
+            }
+            AgentMessage::ChatStream { reply, .. } => {
+                let _ = reply.send(Err(error));
+            }
+            AgentMessage::NewSession { reply }
+            | AgentMessage::ResumeSession { reply, .. }
+            | AgentMessage::SetModel { reply, .. } => {
+                let _ = reply.send(Err(error));
+            }
+            AgentMessage::Compact { reply } => {
+                let _ = reply.send(Err(error));
+            }
+            AgentMessage::SearchMemory { reply, .. } => {
+                let _ = reply.send(Err(error));
+            }
+            // No error to carry; dropping the reply tells the caller
+            AgentMessage::ClearSession { .. } | AgentMessage::Status { .. } => {}
+            AgentMessage::Stop => {}
+        }
     }
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// Supervision
+// ─────────────────────────────────────────────────────────────────────────────
+
 /// Handle to a supervised actor
 pub struct SupervisedHandle {
     /// Reference to send messages
@@ -559,10 +764,11 @@ pub struct SupervisedHandle {
     pub supervisor_task: JoinHandle<()>,
     /// Control channel for supervisor
     control: mpsc::Sender<SupervisorMessage>,
+    /// Lifecycle notifications from the supervisor
+    events: broadcast::Sender<SupervisorEvent>,
 }
 
 /// Messages for supervisor control
-#[allow(dead_code)]
 enum SupervisorMessage {
     /// Stop the supervised actor
     Stop,
@@ -570,12 +776,146 @@ enum SupervisorMessage {
     Restart,
 }
 
+/// Lifecycle notifications sent by the supervisor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SupervisorEvent {
+    /// The actor started; `restarts` counts restarts after failures so far
+    Started { restarts: u32 },
+    /// The actor panicked or failed to start
+    Failed { error: String, will_restart: bool },
+    /// The actor was stopped and will not run again
+    Stopped,
+}
+
 impl SupervisedHandle {
-    /// Stop the supervised actor
+    /// Stop the supervised actor now, abandoning queued messages.
+    /// Use `reference.stop()` to stop after the queued messages instead.
     pub async fn stop(&self) -> Result<()> {
-        self.reference.stop().await?;
-        let _ = self.control.send(SupervisorMessage::Stop).await;
-        Ok(())
+        self.control
+            .send(SupervisorMessage::Stop)
+            .await
+            .map_err(|_| anyhow::anyhow!("Supervisor has exited"))
+    }
+
+    /// Abandon the current message and start the actor afresh.
+    /// Doesn't count against `max_restarts`.
+    pub async fn restart(&self) -> Result<()> {
+        self.control
+            .send(SupervisorMessage::Restart)
+            .await
+            .map_err(|_| anyhow::anyhow!("Supervisor has exited"))
+    }
+
+    /// Receive lifecycle notifications from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<SupervisorEvent> {
+        self.events.subscribe()
+    }
+}
+
+/// How one run of a supervised actor ended
+enum RunEnd {
+    /// The loop returned: `Stop` was handled or every `AgentRef` was dropped
+    Finished,
+    Failed(String),
+    Control(SupervisorMessage),
+}
+
+/// The channels and task of a running supervisor
+struct Supervisor {
+    task: JoinHandle<()>,
+    control: mpsc::Sender<SupervisorMessage>,
+    events: broadcast::Sender<SupervisorEvent>,
+}
+
+/// Spawn a supervisor that runs `start` until it returns, restarting it
+/// when it fails. `start` gets the mailbox, which outlives every run, and
+/// should hold its lock while handling messages.
+fn supervise<R, F, Fut>(
+    agent_id: String,
+    actor_config: ActorConfig,
+    receiver: R,
+    mut start: F,
+) -> Supervisor
+where
+    R: Send + 'static,
+    F: FnMut(Arc<Mutex<R>>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let (control_tx, mut control_rx) = mpsc::channel::<SupervisorMessage>(10);
+    let (events, _) = broadcast::channel(16);
+    let mailbox = Arc::new(Mutex::new(receiver));
+
+    let notify = events.clone();
+    let task = tokio::spawn(async move {
+        let mut restarts = 0u32;
+        loop {
+            info!("Supervised agent '{}' started", agent_id);
+            let _ = notify.send(SupervisorEvent::Started { restarts });
+
+            let mut run = tokio::spawn(start(Arc::clone(&mailbox)));
+            let end = tokio::select! {
+                result = &mut run => match result {
+                    Ok(Ok(())) => RunEnd::Finished,
+                    Ok(Err(e)) => RunEnd::Failed(format!("{:#}", e)),
+                    Err(e) => RunEnd::Failed(panic_message(e)),
+                },
+                Some(msg) = control_rx.recv() => {
+                    run.abort();
+                    let _ = run.await;
+                    RunEnd::Control(msg)
+                }
+            };
+
+            match end {
+                RunEnd::Finished | RunEnd::Control(SupervisorMessage::Stop) => break,
+                RunEnd::Control(SupervisorMessage::Restart) => {
+                    info!("Restarting supervised agent '{}' on request", agent_id);
+                    continue;
+                }
+                RunEnd::Failed(error) => {
+                    let will_restart = actor_config.restart_on_panic
+                        && (actor_config.max_restarts == 0 || restarts < actor_config.max_restarts);
+                    error!("Supervised agent '{}' failed: {}", agent_id, error);
+                    let _ = notify.send(SupervisorEvent::Failed {
+                        error,
+                        will_restart,
+                    });
+                    if !will_restart {
+                        break;
+                    }
+                    restarts += 1;
+                    tokio::select! {
+                        _ = tokio::time::sleep(actor_config.restart_delay) => {}
+                        Some(SupervisorMessage::Stop) = control_rx.recv() => break,
+                    }
+                }
+            }
+        }
+
+        // Drop the mailbox so senders see the actor is gone
+        drop(mailbox);
+        debug!("Supervised agent '{}' stopped", agent_id);
+        let _ = notify.send(SupervisorEvent::Stopped);
+    });
+
+    Supervisor {
+        task,
+        control: control_tx,
+        events,
+    }
+}
+
+fn panic_message(err: tokio::task::JoinError) -> String {
+    if !err.is_panic() {
+        return err.to_string();
+    }
+    let payload = err.into_panic();
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        format!("panicked: {}", s)
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        format!("panicked: {}", s)
+    } else {
+        "panicked".to_string()
     }
 }
 
@@ -594,7 +934,12 @@ mod tests {
             session_id: "session-123".to_string(),
             message_count: 5,
             token_count: 1000,
+            compaction_count: 0,
+            api_input_tokens: 0,
+            api_output_tokens: 0,
+            memory_chunks: 0,
             is_busy: false,
+            queue_depth: 0,
         };
 
         assert_eq!(status.model, "test-model");
@@ -607,11 +952,13 @@ mod tests {
         let tool_start = StreamChunk::ToolStart {
             name: "test".to_string(),
             id: "123".to_string(),
+            arguments: "{}".to_string(),
         };
         let tool_end = StreamChunk::ToolEnd {
             name: "test".to_string(),
             id: "123".to_string(),
             output: "result".to_string(),
+            warnings: Vec::new(),
         };
         let done = StreamChunk::Done;
         let error = StreamChunk::Error("failed".to_string());
@@ -624,6 +971,27 @@ mod tests {
         let _ = error.clone();
     }
 
+    #[test]
+    fn test_stream_chunk_from_event() {
+        let chunk = StreamChunk::from(StreamEvent::ToolCallEnd {
+            name: "bash".to_string(),
+            id: "1".to_string(),
+            output: "ok".to_string(),
+            warnings: vec!["slow".to_string()],
+        });
+        match chunk {
+            StreamChunk::ToolEnd { name, warnings, .. } => {
+                assert_eq!(name, "bash");
+                assert_eq!(warnings, vec!["slow".to_string()]);
+            }
+            other => panic!("unexpected chunk {:?}", other),
+        }
+        assert!(matches!(
+            StreamChunk::from(StreamEvent::Done),
+            StreamChunk::Done
+        ));
+    }
+
     #[test]
     fn test_memory_search_result() {
         let result = MemorySearchResult {
@@ -650,14 +1018,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_agent_ref_channel_behavior() {
-        let (sender, mut receiver) = mpsc::channel::<AgentMessage>(10);
-        let reference = AgentRef::new(sender.clone());
+        let (sender, mut receiver) = mailbox::<AgentMessage>(10);
+        let reference = AgentRef::new(sender);
 
         assert!(reference.is_connected());
 
         // Send a message
         let (reply_tx, reply_rx) = oneshot::channel();
-        sender
+        reference
             .send(AgentMessage::Status { reply: reply_tx })
             .await
             .unwrap();
@@ -672,7 +1040,12 @@ mod tests {
                         session_id: "123".to_string(),
                         message_count: 0,
                         token_count: 0,
+                        compaction_count: 0,
+                        api_input_tokens: 0,
+                        api_output_tokens: 0,
+                        memory_chunks: 0,
                         is_busy: false,
+                        queue_depth: 0,
                     })
                     .unwrap();
             }
@@ -683,4 +1056,138 @@ mod tests {
         let status = reply_rx.await.unwrap();
         assert_eq!(status.model, "test");
     }
+
+    enum TestMessage {
+        Ping(oneshot::Sender<()>),
+        Panic,
+    }
+
+    /// A message loop that answers pings and panics on request
+    fn test_loop(
+        mailbox: Arc<Mutex<mpsc::Receiver<TestMessage>>>,
+    ) -> impl Future<Output = Result<()>> {
+        async move {
+            let mut mailbox = mailbox.lock().await;
+            while let Some(msg) = mailbox.recv().await {
+                match msg {
+                    TestMessage::Ping(reply) => {
+                        let _ = reply.send(());
+                    }
+                    TestMessage::Panic => panic!("boom"),
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn test_config(max_restarts: u32) -> ActorConfig {
+        ActorConfig {
+            restart_on_panic: true,
+            max_restarts,
+            restart_delay: Duration::from_millis(1),
+            ..ActorConfig::default()
+        }
+    }
+
+    async fn ping(sender: &mpsc::Sender<TestMessage>) -> bool {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        sender.send(TestMessage::Ping(reply_tx)).await.is_ok() && reply_rx.await.is_ok()
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_restarts_after_panic() {
+        let (sender, receiver) = mpsc::channel(10);
+        let supervisor = supervise("test".to_string(), test_config(3), receiver, test_loop);
+        let mut events = supervisor.events.subscribe();
+
+        assert!(ping(&sender).await);
+        assert_eq!(
+            events.recv().await.unwrap(),
+            SupervisorEvent::Started { restarts: 0 }
+        );
+        sender.send(TestMessage::Panic).await.unwrap();
+        // Queued behind the panic, so it is handled by the restarted loop
+        assert!(ping(&sender).await);
+
+        match events.recv().await.unwrap() {
+            SupervisorEvent::Failed {
+                error,
+                will_restart,
+            } => {
+                assert!(error.contains("boom"));
+                assert!(will_restart);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert_eq!(
+            events.recv().await.unwrap(),
+            SupervisorEvent::Started { restarts: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_gives_up_after_max_restarts() {
+        let (sender, receiver) = mpsc::channel(10);
+        let supervisor = supervise("test".to_string(), test_config(1), receiver, test_loop);
+
+        sender.send(TestMessage::Panic).await.unwrap();
+        sender.send(TestMessage::Panic).await.unwrap();
+        supervisor.task.await.unwrap();
+
+        assert!(sender.is_closed());
+        assert!(!ping(&sender).await);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_failed_start_is_retried() {
+        let mut attempts = 0;
+        let (sender, receiver) = mpsc::channel(10);
+        let supervisor = supervise(
+            "test".to_string(),
+            test_config(3),
+            receiver,
+            move |mailbox| {
+                attempts += 1;
+                let fail = attempts == 1;
+                async move {
+                    if fail {
+                        anyhow::bail!("not ready");
+                    }
+                    test_loop(mailbox).await
+                }
+            },
+        );
+
+        assert!(ping(&sender).await);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_stop_and_restart() {
+        let (sender, receiver) = mpsc::channel(10);
+        let supervisor = supervise("test".to_string(), test_config(0), receiver, test_loop);
+        let mut events = supervisor.events.subscribe();
+
+        supervisor
+            .control
+            .send(SupervisorMessage::Restart)
+            .await
+            .unwrap();
+        // The first run, then the restarted one
+        for _ in 0..2 {
+            assert_eq!(
+                events.recv().await.unwrap(),
+                SupervisorEvent::Started { restarts: 0 }
+            );
+        }
+        assert!(ping(&sender).await);
+
+        supervisor
+            .control
+            .send(SupervisorMessage::Stop)
+            .await
+            .unwrap();
+        assert_eq!(events.recv().await.unwrap(), SupervisorEvent::Stopped);
+        supervisor.task.await.unwrap();
+        assert!(!ping(&sender).await);
+    }
 }
@@ -0,0 +1,134 @@
+//! Cancelling agent turns.
+//!
+//! Each entry point (HTTP, WebSocket, Telegram `/stop`, the bridge's
+//! `cancel_chat`, Ctrl-C or Esc in `localgpt chat`) gives the agent a fresh
+//! [`CancellationToken`] for every turn with `Agent::set_cancel_token` and
+//! keeps a clone. Cancelling it aborts the provider request or tool that's
+//! running and ends the turn with [`TurnCancelled`]; tools that haven't
+//! started yet are not run.
+
+use std::fmt;
+use std::pin::Pin;
+
+use anyhow::Result;
+use futures::{Stream, StreamExt};
+
+pub use tokio_util::sync::{CancellationToken, DropGuard};
+
+/// Error a turn ends with when its token is cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TurnCancelled;
+
+impl fmt::Display for TurnCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Turn cancelled")
+    }
+}
+
+impl std::error::Error for TurnCancelled {}
+
+/// Whether `error` means the turn was cancelled rather than failed
+pub fn is_cancelled(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<TurnCancelled>().is_some()
+}
+
+/// Run `future` unless `token` is cancelled first. A token that is already
+/// cancelled fails without polling `future` at all.
+pub async fn cancellable<T>(
+    token: &CancellationToken,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(TurnCancelled.into()),
+        result = future => result,
+    }
+}
+
+/// Forward `stream` until `token` is cancelled, then end it with
+/// [`TurnCancelled`].
+pub fn cancellable_stream<T: Send + 'static>(
+    token: CancellationToken,
+    stream: Pin<Box<dyn Stream<Item = Result<T>> + Send>>,
+) -> Pin<Box<dyn Stream<Item = Result<T>> + Send>> {
+    Box::pin(async_stream::stream! {
+        let mut stream = stream;
+        loop {
+            tokio::select! {
+                biased;
+                _ = token.cancelled() => {
+                    yield Err(TurnCancelled.into());
+                    break;
+                }
+                item = stream.next() => match item {
+                    Some(item) => yield item,
+                    None => break,
+                },
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancellable_aborts_pending_future() {
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            canceller.cancel();
+        });
+
+        let err = cancellable(&token, std::future::pending::<Result<()>>())
+            .await
+            .unwrap_err();
+        assert!(is_cancelled(&err));
+        assert_eq!(err.to_string(), "Turn cancelled");
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_token_skips_future() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let mut ran = false;
+        let result = cancellable(&token, async {
+            ran = true;
+            Ok(())
+        })
+        .await;
+        assert!(result.is_err());
+        assert!(!ran);
+    }
+
+    #[tokio::test]
+    async fn test_cancellable_passes_results_through() {
+        let token = CancellationToken::new();
+        assert_eq!(cancellable(&token, async { Ok(7) }).await.unwrap(), 7);
+
+        let err = cancellable::<()>(&token, async { Err(anyhow::anyhow!("boom")) })
+            .await
+            .unwrap_err();
+        assert!(!is_cancelled(&err));
+    }
+
+    #[tokio::test]
+    async fn test_cancellable_stream_ends_with_error() {
+        let token = CancellationToken::new();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Result<u32>>();
+        let stream = tokio_stream::wrappers::UnboundedReceiverStream::new(rx);
+        let mut stream = cancellable_stream(token.clone(), Box::pin(stream));
+
+        tx.send(Ok(1)).unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+
+        token.cancel();
+        tx.send(Ok(2)).unwrap();
+        assert!(is_cancelled(&stream.next().await.unwrap().unwrap_err()));
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -0,0 +1,203 @@
+//! Two-lane actor mailboxes.
+//!
+//! Control messages (`Stop`, `Status`) go in a priority lane that the actor
+//! drains before its normal lane, so they don't wait behind a queue of chat
+//! turns. They still wait for the message being handled to finish. The
+//! normal lane is bounded and never waits for room: when it's full the send
+//! fails with [`ActorBusy`], which callers can report as "busy".
+
+use std::fmt;
+
+use tokio::sync::mpsc;
+
+/// Room in the priority lane; control messages are small and rare
+const PRIORITY_CAPACITY: usize = 16;
+
+/// Error returned when an actor's mailbox is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActorBusy {
+    /// Messages waiting when the send was refused
+    pub queued: usize,
+}
+
+impl fmt::Display for ActorBusy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Agent is busy ({} messages queued)", self.queued)
+    }
+}
+
+impl std::error::Error for ActorBusy {}
+
+/// Messages that may skip the queue
+pub(super) trait Prioritized {
+    fn is_priority(&self) -> bool;
+}
+
+/// Why a send failed; carries the message back
+pub(super) enum SendError<T> {
+    Busy(ActorBusy),
+    Closed(T),
+}
+
+impl<T> SendError<T> {
+    pub(super) fn into_error(self) -> anyhow::Error {
+        match self {
+            SendError::Busy(busy) => busy.into(),
+            SendError::Closed(_) => anyhow::anyhow!("Actor channel closed"),
+        }
+    }
+}
+
+pub(super) struct MailboxSender<T> {
+    priority: mpsc::Sender<T>,
+    normal: mpsc::Sender<T>,
+}
+
+// Derived Clone would require `T: Clone`
+impl<T> Clone for MailboxSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            priority: self.priority.clone(),
+            normal: self.normal.clone(),
+        }
+    }
+}
+
+pub(super) struct MailboxReceiver<T> {
+    priority: mpsc::Receiver<T>,
+    normal: mpsc::Receiver<T>,
+}
+
+/// A mailbox whose normal lane holds `capacity` messages
+pub(super) fn mailbox<T>(capacity: usize) -> (MailboxSender<T>, MailboxReceiver<T>) {
+    let (priority_tx, priority_rx) = mpsc::channel(PRIORITY_CAPACITY);
+    let (normal_tx, normal_rx) = mpsc::channel(capacity.max(1));
+    (
+        MailboxSender {
+            priority: priority_tx,
+            normal: normal_tx,
+        },
+        MailboxReceiver {
+            priority: priority_rx,
+            normal: normal_rx,
+        },
+    )
+}
+
+impl<T: Prioritized> MailboxSender<T> {
+    pub(super) async fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        if msg.is_priority() {
+            return self
+                .priority
+                .send(msg)
+                .await
+                .map_err(|mpsc::error::SendError(msg)| SendError::Closed(msg));
+        }
+        self.normal.try_send(msg).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => SendError::Busy(ActorBusy {
+                queued: self.queue_depth(),
+            }),
+            mpsc::error::TrySendError::Closed(msg) => SendError::Closed(msg),
+        })
+    }
+}
+
+impl<T> MailboxSender<T> {
+    /// Messages waiting in the normal lane
+    pub(super) fn queue_depth(&self) -> usize {
+        self.normal.max_capacity() - self.normal.capacity()
+    }
+
+    pub(super) fn is_closed(&self) -> bool {
+        self.normal.is_closed()
+    }
+}
+
+impl<T> MailboxReceiver<T> {
+    /// The next message, priority lane first; None once every sender is gone
+    pub(super) async fn recv(&mut self) -> Option<T> {
+        tokio::select! {
+            biased;
+            Some(msg) = self.priority.recv() => Some(msg),
+            msg = self.normal.recv() => msg,
+        }
+    }
+
+    /// Messages waiting in the normal lane
+    pub(super) fn queue_depth(&self) -> usize {
+        self.normal.len()
+    }
+
+    /// Refuse new messages; those already queued can still be received
+    pub(super) fn close(&mut self) {
+        self.priority.close();
+        self.normal.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Msg {
+        id: u32,
+        priority: bool,
+    }
+
+    impl Prioritized for Msg {
+        fn is_priority(&self) -> bool {
+            self.priority
+        }
+    }
+
+    fn msg(id: u32, priority: bool) -> Msg {
+        Msg { id, priority }
+    }
+
+    #[tokio::test]
+    async fn test_priority_messages_skip_the_queue() {
+        let (sender, mut receiver) = mailbox(10);
+        for id in 0..3 {
+            assert!(sender.send(msg(id, false)).await.is_ok());
+        }
+        assert!(sender.send(msg(99, true)).await.is_ok());
+
+        assert_eq!(receiver.recv().await.unwrap().id, 99);
+        for id in 0..3 {
+            assert_eq!(receiver.recv().await.unwrap().id, id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_mailbox_is_busy() {
+        let (sender, _receiver) = mailbox(2);
+        assert!(sender.send(msg(0, false)).await.is_ok());
+        assert!(sender.send(msg(1, false)).await.is_ok());
+        assert_eq!(sender.queue_depth(), 2);
+
+        match sender.send(msg(2, false)).await {
+            Err(SendError::Busy(busy)) => assert_eq!(busy.queued, 2),
+            _ => panic!("expected busy"),
+        }
+        // Control messages still get through
+        assert!(sender.send(msg(3, true)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_closed_mailbox_returns_message() {
+        let (sender, receiver) = mailbox(2);
+        drop(receiver);
+        assert!(sender.is_closed());
+        match sender.send(msg(7, false)).await {
+            Err(SendError::Closed(m)) => assert_eq!(m.id, 7),
+            _ => panic!("expected closed"),
+        }
+    }
+
+    #[test]
+    fn test_busy_error_message() {
+        let err = SendError::<Msg>::Busy(ActorBusy { queued: 5 }).into_error();
+        assert_eq!(err.to_string(), "Agent is busy (5 messages queued)");
+        assert!(err.downcast_ref::<ActorBusy>().is_some());
+    }
+}
@@ -1,10 +1,20 @@
 mod actor;
+mod cancel;
+mod mailbox;
+mod pool;
+mod shutdown;
 mod turn_gate;
 mod workspace_lock;
 
 pub use actor::{
     ActorConfig, ActorHandle, AgentActor, AgentMessage, AgentRef, AgentStatus, MemorySearchResult,
-    StreamChunk, SupervisedHandle,
+    StreamChunk, SupervisedHandle, SupervisorEvent,
 };
-pub use turn_gate::TurnGate;
-pub use workspace_lock::{WorkspaceLock, WorkspaceLockGuard};
+pub use cancel::{
+    CancellationToken, DropGuard, TurnCancelled, cancellable, cancellable_stream, is_cancelled,
+};
+pub use mailbox::ActorBusy;
+pub use pool::{ActorPool, ActorPoolConfig};
+pub use shutdown::{Shutdown, shutdown_signal};
+pub use turn_gate::{TurnGate, TurnGateBusy, TurnGateStats, TurnPermit};
+pub use workspace_lock::{LockHolder, LockMode, WorkspaceLock, WorkspaceLockGuard};
@@ -0,0 +1,522 @@
+//! A fixed set of agent actors shared by many sessions.
+//!
+//! Each session ID maps to one of N actors on a consistent-hash ring, so a
+//! session's messages are always handled in order by the same actor while
+//! different actors run in parallel. An actor keeps an agent per session it
+//! serves; nothing is shared between actors, so no lock is held across a
+//! turn.
+//!
+//! Actors are spawned on the first message routed to them. Sessions are
+//! saved after each message that changes their history. A session idle for
+//! `idle_timeout` is dropped, and an actor left with no sessions exits until
+//! a message needs it again. A session that isn't in memory is resumed from
+//! disk when saved under its ID, or started fresh under that ID otherwise;
+//! `new_session()` starts it over under the same ID.
+//!
+//! ```ignore
+//! let pool = ActorPool::new(config, "http", ActorPoolConfig::default())?;
+//! let reply = pool.session("3f2a...").chat("hello").await?;
+//! ```
+
+use std::collections::HashMap;
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::agent::Agent;
+use crate::config::Config;
+use crate::memory::MemoryManager;
+
+use super::actor::{AgentMessage, AgentRef, handle_message, start_agent};
+use super::mailbox::{MailboxReceiver, MailboxSender, Prioritized, SendError, mailbox};
+
+/// Points per actor on the hash ring; more points spread sessions more evenly
+const VIRTUAL_NODES: usize = 64;
+
+/// Configuration for an actor pool
+#[derive(Debug, Clone)]
+pub struct ActorPoolConfig {
+    /// Number of actors sessions are spread over
+    pub actors: usize,
+    /// Save and drop a session after this long without messages
+    pub idle_timeout: Duration,
+    /// Size of each actor's mailbox
+    pub mailbox_size: usize,
+    /// Channel the agents run on (see `Agent::set_channel`), which picks
+    /// their interface tool policy
+    pub channel: Option<String>,
+}
+
+impl Default for ActorPoolConfig {
+    fn default() -> Self {
+        Self {
+            actors: 4,
+            idle_timeout: Duration::from_secs(30 * 60),
+            mailbox_size: 100,
+            channel: None,
+        }
+    }
+}
+
+/// Agent actors that serve sessions routed by session ID
+pub struct ActorPool {
+    inner: Arc<PoolInner>,
+}
+
+pub(super) struct PoolInner {
+    config: Config,
+    agent_id: String,
+    memory: Arc<MemoryManager>,
+    pool_config: ActorPoolConfig,
+    ring: Vec<(u64, usize)>,
+    slots: Vec<Mutex<Option<Worker>>>,
+    shut_down: AtomicBool,
+}
+
+struct Worker {
+    sender: MailboxSender<PoolMessage>,
+    task: JoinHandle<()>,
+}
+
+struct PoolMessage {
+    session_id: Arc<str>,
+    message: AgentMessage,
+}
+
+impl Prioritized for PoolMessage {
+    // `Stop` ends a session here, so it stays behind the session's queued turns
+    fn is_priority(&self) -> bool {
+        matches!(self.message, AgentMessage::Status { .. })
+    }
+}
+
+impl ActorPool {
+    /// Create a pool; no actor runs until a message is sent
+    pub fn new(config: Config, agent_id: &str, pool_config: ActorPoolConfig) -> Result<Self> {
+        let memory = Arc::new(MemoryManager::new_with_full_config(
+            &config.memory,
+            Some(&config),
+            agent_id,
+        )?);
+        Self::with_memory(config, agent_id, memory, pool_config)
+    }
+
+    /// Create a pool whose agents share `memory`
+    pub fn with_memory(
+        config: Config,
+        agent_id: &str,
+        memory: Arc<MemoryManager>,
+        pool_config: ActorPoolConfig,
+    ) -> Result<Self> {
+        if pool_config.actors == 0 {
+            bail!("An actor pool needs at least one actor");
+        }
+        let ring = build_ring(pool_config.actors);
+        let slots = (0..pool_config.actors).map(|_| Mutex::new(None)).collect();
+
+        Ok(Self {
+            inner: Arc::new(PoolInner {
+                config,
+                agent_id: agent_id.to_string(),
+                memory,
+                pool_config,
+                ring,
+                slots,
+                shut_down: AtomicBool::new(false),
+            }),
+        })
+    }
+
+    /// A reference to one session; `stop()` on it ends and saves the session
+    pub fn session(&self, session_id: &str) -> AgentRef {
+        AgentRef::pooled(Arc::clone(&self.inner), session_id)
+    }
+
+    /// Index of the actor that serves `session_id`
+    pub fn actor_for(&self, session_id: &str) -> usize {
+        route(&self.inner.ring, session_id)
+    }
+
+    /// Number of actors currently running
+    pub fn running_actors(&self) -> usize {
+        self.inner
+            .slots
+            .iter()
+            .filter(|slot| {
+                lock(*slot)
+                    .as_ref()
+                    .is_some_and(|worker| !worker.sender.is_closed())
+            })
+            .count()
+    }
+
+    /// Stop every actor after the messages already queued, saving their
+    /// sessions. Later sends fail.
+    pub async fn shutdown(&self) {
+        self.inner.shut_down.store(true, Ordering::SeqCst);
+        let workers: Vec<Worker> = self
+            .inner
+            .slots
+            .iter()
+            .filter_map(|slot| lock(slot).take())
+            .collect();
+        for worker in workers {
+            // Dropping the only sender lets the actor drain its mailbox and exit
+            drop(worker.sender);
+            let _ = worker.task.await;
+        }
+    }
+}
+
+impl PoolInner {
+    pub(super) fn is_shut_down(&self) -> bool {
+        self.shut_down.load(Ordering::SeqCst)
+    }
+
+    pub(super) async fn send(
+        self: &Arc<Self>,
+        session_id: &Arc<str>,
+        msg: AgentMessage,
+    ) -> Result<()> {
+        let index = route(&self.ring, session_id);
+        let msg = PoolMessage {
+            session_id: Arc::clone(session_id),
+            message: msg,
+        };
+        let msg = match self.sender(index)?.send(msg).await {
+            Ok(()) => return Ok(()),
+            // The actor went idle and exited after we looked; start it again
+            Err(SendError::Closed(msg)) => msg,
+            Err(e) => return Err(e.into_error()),
+        };
+        self.sender(index)?
+            .send(msg)
+            .await
+            .map_err(SendError::into_error)
+    }
+
+    /// Messages waiting for the actor that serves `session_id`
+    pub(super) fn queue_depth(&self, session_id: &str) -> usize {
+        let index = route(&self.ring, session_id);
+        lock(&self.slots[index])
+            .as_ref()
+            .map_or(0, |worker| worker.sender.queue_depth())
+    }
+
+    /// The mailbox of actor `index`, spawning the actor if it isn't running
+    fn sender(self: &Arc<Self>, index: usize) -> Result<MailboxSender<PoolMessage>> {
+        let mut slot = lock(&self.slots[index]);
+        if self.is_shut_down() {
+            bail!("Actor pool is shut down");
+        }
+        if let Some(worker) = slot.as_ref()
+            && !worker.sender.is_closed()
+        {
+            return Ok(worker.sender.clone());
+        }
+
+        let (sender, receiver) = mailbox(self.pool_config.mailbox_size);
+        let pool = Arc::clone(self);
+        let task = tokio::spawn(async move { pool.run_worker(index, receiver).await });
+        *slot = Some(Worker {
+            sender: sender.clone(),
+            task,
+        });
+        Ok(sender)
+    }
+
+    async fn run_worker(&self, index: usize, mut receiver: MailboxReceiver<PoolMessage>) {
+        debug!("Pool actor {} started", index);
+        let idle_timeout = self.pool_config.idle_timeout;
+        let mut sessions: HashMap<Arc<str>, PooledSession> = HashMap::new();
+        let mut last_message = Instant::now();
+        let mut reap = tokio::time::interval(
+            (idle_timeout / 2).clamp(Duration::from_millis(10), Duration::from_secs(60)),
+        );
+        reap.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                msg = receiver.recv() => {
+                    let Some(msg) = msg else { break };
+                    last_message = Instant::now();
+                    self.handle(&mut sessions, msg, receiver.queue_depth()).await;
+                }
+                _ = reap.tick() => {
+                    let idle: Vec<Arc<str>> = sessions
+                        .iter()
+                        .filter(|(_, s)| s.last_used.elapsed() >= idle_timeout)
+                        .map(|(id, _)| Arc::clone(id))
+                        .collect();
+                    for id in idle {
+                        if let Some(session) = sessions.remove(&id) {
+                            debug!("Pool actor {} dropping idle session {}", index, id);
+                            self.close_session(session).await;
+                        }
+                    }
+                    if sessions.is_empty() && last_message.elapsed() >= idle_timeout {
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Refuse new messages, but handle those already queued
+        receiver.close();
+        while let Some(msg) = receiver.recv().await {
+            self.handle(&mut sessions, msg, receiver.queue_depth())
+                .await;
+        }
+        for (_, session) in sessions.drain() {
+            self.close_session(session).await;
+        }
+        debug!("Pool actor {} stopped", index);
+    }
+
+    async fn handle(
+        &self,
+        sessions: &mut HashMap<Arc<str>, PooledSession>,
+        msg: PoolMessage,
+        queue_depth: usize,
+    ) {
+        let PoolMessage {
+            session_id,
+            message,
+        } = msg;
+
+        if let AgentMessage::Stop = message {
+            if let Some(session) = sessions.remove(&session_id) {
+                info!("Ending pooled session {}", session_id);
+                self.close_session(session).await;
+            }
+            return;
+        }
+
+        // Starting over keeps the ID, so the saved history isn't resumed
+        let message = match message {
+            AgentMessage::NewSession { reply } => {
+                let result = self.restart_session(sessions, session_id).await;
+                let _ = reply.send(result);
+                return;
+            }
+            message => message,
+        };
+
+        let save = changes_history(&message);
+        let session = match sessions.entry(session_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let opened = self.open_session(entry.key(), true).await;
+                match opened {
+                    Ok(agent) => entry.insert(PooledSession {
+                        agent,
+                        last_used: Instant::now(),
+                    }),
+                    Err(e) => {
+                        message.fail(e);
+                        return;
+                    }
+                }
+            }
+        };
+        session.last_used = Instant::now();
+        handle_message(&mut session.agent, &self.memory, message, queue_depth).await;
+        if save {
+            self.save_session(&session.agent).await;
+        }
+    }
+
+    /// Replace `session_id` with a new, empty session under the same ID
+    async fn restart_session(
+        &self,
+        sessions: &mut HashMap<Arc<str>, PooledSession>,
+        session_id: Arc<str>,
+    ) -> Result<()> {
+        let agent = self.open_session(&session_id, false).await?;
+        self.save_session(&agent).await;
+        sessions.insert(
+            session_id,
+            PooledSession {
+                agent,
+                last_used: Instant::now(),
+            },
+        );
+        Ok(())
+    }
+
+    /// An agent for `session_id`: the saved session if there is one and
+    /// `resume` is set, otherwise a new session under that ID
+    async fn open_session(&self, session_id: &str, resume: bool) -> Result<Agent> {
+        if session_id.is_empty()
+            || session_id.starts_with('.')
+            || !session_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            bail!("Invalid session ID: {}", session_id);
+        }
+
+        let mut agent = start_agent(&self.config, Arc::clone(&self.memory)).await?;
+        if let Some(channel) = &self.pool_config.channel {
+            agent.set_channel(channel);
+        }
+        let resumed = resume
+            && agent
+                .resume_session_for_agent(session_id, &self.agent_id)
+                .await
+                .is_ok();
+        if !resumed {
+            agent.new_session_with_id(session_id).await?;
+        }
+        Ok(agent)
+    }
+
+    async fn close_session(&self, session: PooledSession) {
+        if session.agent.session_status().message_count == 0 {
+            return;
+        }
+        self.save_session(&session.agent).await;
+    }
+
+    async fn save_session(&self, agent: &Agent) {
+        if let Err(e) = agent.save_session_for_agent(&self.agent_id).await {
+            warn!("Failed to save pooled session: {}", e);
+        }
+    }
+}
+
+/// Whether `message` changes the session's history, which is then saved
+fn changes_history(message: &AgentMessage) -> bool {
+    matches!(
+        message,
+        AgentMessage::Chat { .. }
+            | AgentMessage::ChatStream { .. }
+            | AgentMessage::Compact { .. }
+            | AgentMessage::ClearSession { .. }
+    )
+}
+
+struct PooledSession {
+    agent: Agent,
+    last_used: Instant,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn hash_key(key: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Ring points for `actors` actors, sorted by hash
+fn build_ring(actors: usize) -> Vec<(u64, usize)> {
+    let mut ring: Vec<(u64, usize)> = (0..actors)
+        .flat_map(|actor| {
+            (0..VIRTUAL_NODES).map(move |node| (hash_key(&format!("actor-{actor}-{node}")), actor))
+        })
+        .collect();
+    ring.sort_unstable();
+    ring
+}
+
+/// The actor owning the first ring point at or after the key's hash
+fn route(ring: &[(u64, usize)], key: &str) -> usize {
+    let hash = hash_key(key);
+    let i = ring.partition_point(|&(point, _)| point < hash);
+    ring[i % ring.len()].1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OllamaConfig;
+    use crate::paths::Paths;
+
+    fn test_config(root: &std::path::Path) -> Config {
+        let mut config = Config {
+            paths: Paths::from_root(root),
+            ..Default::default()
+        };
+        config.memory.embedding_provider = "none".to_string();
+        config.agent.default_model = "ollama/llama3".to_string();
+        config.providers.ollama = Some(OllamaConfig {
+            endpoint: "http://127.0.0.1:9".to_string(),
+            model: "llama3".to_string(),
+        });
+        config
+    }
+
+    #[tokio::test]
+    async fn test_sessions_keep_their_id() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pool = ActorPool::new(
+            test_config(tmp.path()),
+            "pool-test",
+            ActorPoolConfig::default(),
+        )
+        .unwrap();
+
+        let session = pool.session("abc");
+        assert_eq!(session.status().await.unwrap().session_id, "abc");
+        session.new_session().await.unwrap();
+        let status = session.status().await.unwrap();
+        assert_eq!(status.session_id, "abc");
+        assert_eq!(status.message_count, 0);
+        assert!(pool.session("../escape").status().await.is_err());
+
+        pool.shutdown().await;
+        assert_eq!(pool.running_actors(), 0);
+    }
+
+    #[test]
+    fn test_route_is_stable() {
+        let ring = build_ring(4);
+        for key in ["a", "session-1", "3f2a9c"] {
+            assert_eq!(route(&ring, key), route(&ring, key));
+            assert!(route(&ring, key) < 4);
+        }
+    }
+
+    #[test]
+    fn test_route_spreads_sessions() {
+        let ring = build_ring(4);
+        let mut counts = [0usize; 4];
+        for i in 0..4000 {
+            counts[route(&ring, &format!("session-{i}"))] += 1;
+        }
+        for count in counts {
+            assert!(count > 500, "uneven spread: {:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_adding_an_actor_moves_few_sessions() {
+        let before = build_ring(4);
+        let after = build_ring(5);
+        let keys: Vec<String> = (0..2000).map(|i| format!("session-{i}")).collect();
+        let moved = keys
+            .iter()
+            .filter(|key| route(&before, key) != route(&after, key))
+            .count();
+        // Ideally a fifth move, all to the new actor
+        assert!(
+            moved < keys.len() * 2 / 5,
+            "{} of {} moved",
+            moved,
+            keys.len()
+        );
+        for key in &keys {
+            let (old, new) = (route(&before, key), route(&after, key));
+            assert!(old == new || new == 4);
+        }
+    }
+}
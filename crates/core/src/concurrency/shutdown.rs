@@ -0,0 +1,143 @@
+//! Daemon-wide shutdown coordination.
+//!
+//! A [`Shutdown`] is cloned into every service. When it's triggered, services
+//! stop taking new work and finish what they're doing; [`Shutdown::remaining`]
+//! tells them how much of the drain deadline is left, after which the daemon
+//! stops waiting and exits anyway.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+
+#[derive(Clone)]
+pub struct Shutdown {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    triggered: watch::Sender<bool>,
+    /// How long in-flight work may take once shutdown starts
+    timeout: Duration,
+    /// Set when triggered
+    deadline: Mutex<Option<Instant>>,
+}
+
+impl Shutdown {
+    pub fn new(timeout: Duration) -> Self {
+        let (triggered, _) = watch::channel(false);
+        Self {
+            inner: Arc::new(Inner {
+                triggered,
+                timeout,
+                deadline: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Start shutting down. Later calls keep the first deadline.
+    pub fn trigger(&self) {
+        let mut deadline = self
+            .inner
+            .deadline
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if deadline.is_none() {
+            *deadline = Some(Instant::now() + self.inner.timeout);
+        }
+        self.inner.triggered.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.inner.triggered.borrow()
+    }
+
+    /// Resolves once shutdown is triggered. The future owns what it needs, so
+    /// it can be handed to `axum::serve(..).with_graceful_shutdown`.
+    pub fn wait(&self) -> impl Future<Output = ()> + Send + 'static {
+        let inner = Arc::clone(&self.inner);
+        async move {
+            // Holding `inner` keeps the sender alive, so this can't fail
+            let _ = inner.triggered.subscribe().wait_for(|t| *t).await;
+        }
+    }
+
+    /// Time left before the drain deadline; the full timeout until triggered
+    pub fn remaining(&self) -> Duration {
+        let deadline = self
+            .inner
+            .deadline
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match *deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => self.inner.timeout,
+        }
+    }
+}
+
+/// Wait for ctrl-c or, on Unix, SIGTERM; returns the signal's name
+pub async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = sigterm.recv() => "SIGTERM",
+            },
+            Err(e) => {
+                tracing::warn!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "ctrl-c"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_resolves_on_trigger() {
+        let shutdown = Shutdown::new(Duration::from_secs(30));
+        let waiting = tokio::spawn(shutdown.wait());
+        assert!(!shutdown.is_triggered());
+
+        shutdown.trigger();
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("wait should resolve")
+            .unwrap();
+        assert!(shutdown.is_triggered());
+
+        // Already triggered: resolves immediately
+        tokio::time::timeout(Duration::from_secs(1), shutdown.wait())
+            .await
+            .expect("wait should resolve");
+    }
+
+    #[test]
+    fn test_remaining_counts_down_from_trigger() {
+        let shutdown = Shutdown::new(Duration::from_secs(30));
+        assert_eq!(shutdown.remaining(), Duration::from_secs(30));
+
+        shutdown.trigger();
+        let remaining = shutdown.remaining();
+        assert!(remaining <= Duration::from_secs(30));
+        assert!(remaining > Duration::from_secs(29));
+
+        // A second trigger doesn't push the deadline back
+        std::thread::sleep(Duration::from_millis(20));
+        shutdown.trigger();
+        assert!(shutdown.remaining() < remaining);
+    }
+}
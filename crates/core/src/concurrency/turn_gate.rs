@@ -1,44 +1,234 @@
-//! In-process turn gate using a tokio Semaphore.
+//! In-process turn gate using tokio Semaphores.
 //!
-//! Prevents heartbeat and HTTP sessions from running agent turns
-//! simultaneously within the same daemon process.
+//! Limits how many agent turns heartbeat, HTTP, and Telegram run at once
+//! within the same daemon process: `server.turns.max_concurrent` overall
+//! (one by default) and optionally fewer per channel. Waiting turns are
+//! served first-come, first-served, and give up with [`TurnGateBusy`] after
+//! `server.turns.queue_timeout`.
 
+use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use serde::Serialize;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
-/// A single-permit gate that serializes agent turns within a process.
+use crate::config::{TurnsConfig, parse_duration};
+
+/// A gate that limits concurrent agent turns within a process.
 ///
-/// HTTP handlers call `acquire()` (async, waits for the permit).
+/// HTTP and Telegram call `acquire()` (async, waits in line for a slot).
 /// Heartbeat calls `try_acquire()` and skips if busy.
 #[derive(Clone)]
 pub struct TurnGate {
-    semaphore: Arc<Semaphore>,
+    inner: Arc<GateInner>,
+}
+
+struct GateInner {
+    global: Arc<Semaphore>,
+    max_concurrent: usize,
+    /// Per-channel limits, within `global`
+    channels: HashMap<String, Arc<Semaphore>>,
+    /// How long `acquire` waits; `None` waits as long as it takes
+    queue_timeout: Option<Duration>,
+    waiting: AtomicUsize,
+    stats: std::sync::Mutex<WaitStats>,
+}
+
+#[derive(Default)]
+struct WaitStats {
+    turns: u64,
+    waited: u64,
+    total_wait: Duration,
+    max_wait: Duration,
+    timeouts: u64,
+}
+
+/// A turn slot; the turn ends when this is dropped.
+pub struct TurnPermit {
+    _global: OwnedSemaphorePermit,
+    _channel: Option<OwnedSemaphorePermit>,
+}
+
+/// Error returned when no turn slot frees up within the queue timeout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurnGateBusy {
+    pub channel: String,
+    pub waited: Duration,
+}
+
+impl fmt::Display for TurnGateBusy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Busy: no free turn slot for {} after {}s, try again later",
+            self.channel,
+            self.waited.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for TurnGateBusy {}
+
+/// Turn counts and queue wait times since the gate was created
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TurnGateStats {
+    pub max_concurrent: usize,
+    pub in_flight: usize,
+    pub waiting: usize,
+    /// Turns started
+    pub turns: u64,
+    /// Turns that found no free slot and had to wait
+    pub waited: u64,
+    pub avg_wait_ms: u64,
+    pub max_wait_ms: u64,
+    /// Turns refused after waiting the whole queue timeout
+    pub timeouts: u64,
 }
 
 impl TurnGate {
+    /// One turn at a time, waiting as long as it takes.
     pub fn new() -> Self {
+        Self::with_limits(1, HashMap::new(), None)
+    }
+
+    /// A gate with `[server.turns]` limits.
+    pub fn from_config(config: &TurnsConfig) -> Result<Self> {
+        let queue_timeout = parse_duration(&config.queue_timeout)
+            .map_err(|e| anyhow!("Invalid server.turns.queue_timeout: {}", e))?;
+        Ok(Self::with_limits(
+            config.max_concurrent,
+            config.channels.clone(),
+            Some(queue_timeout),
+        ))
+    }
+
+    fn with_limits(
+        max_concurrent: usize,
+        channels: HashMap<String, usize>,
+        queue_timeout: Option<Duration>,
+    ) -> Self {
+        let max_concurrent = max_concurrent.max(1);
         Self {
-            semaphore: Arc::new(Semaphore::new(1)),
+            inner: Arc::new(GateInner {
+                global: Arc::new(Semaphore::new(max_concurrent)),
+                max_concurrent,
+                channels: channels
+                    .into_iter()
+                    .filter(|(_, limit)| *limit > 0)
+                    .map(|(channel, limit)| (channel, Arc::new(Semaphore::new(limit))))
+                    .collect(),
+                queue_timeout,
+                waiting: AtomicUsize::new(0),
+                stats: std::sync::Mutex::new(WaitStats::default()),
+            }),
+        }
+    }
+
+    /// Wait in line for a slot for a turn on `channel` ("http",
+    /// "telegram", "heartbeat", ...). Fails with [`TurnGateBusy`] once the
+    /// queue timeout passes.
+    pub async fn acquire(&self, channel: &str) -> Result<TurnPermit, TurnGateBusy> {
+        if let Some(permit) = self.try_acquire(channel) {
+            return Ok(permit);
+        }
+
+        let started = Instant::now();
+        let waiting = Waiting::new(&self.inner.waiting);
+        let wait = async {
+            // Channel first, so a busy channel's backlog doesn't hold places
+            // in the shared line
+            let channel_permit = match self.inner.channels.get(channel) {
+                Some(semaphore) => Some(acquire_owned(semaphore).await),
+                None => None,
+            };
+            let global = acquire_owned(&self.inner.global).await;
+            TurnPermit {
+                _global: global,
+                _channel: channel_permit,
+            }
+        };
+        let result = match self.inner.queue_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, wait).await.ok(),
+            None => Some(wait.await),
+        };
+        drop(waiting);
+
+        let waited = started.elapsed();
+        let mut stats = self.stats_lock();
+        match result {
+            Some(permit) => {
+                stats.turns += 1;
+                stats.waited += 1;
+                stats.total_wait += waited;
+                stats.max_wait = stats.max_wait.max(waited);
+                Ok(permit)
+            }
+            None => {
+                stats.timeouts += 1;
+                Err(TurnGateBusy {
+                    channel: channel.to_string(),
+                    waited,
+                })
+            }
         }
     }
 
-    /// Async acquire — waits until the permit is available.
-    pub async fn acquire(&self) -> OwnedSemaphorePermit {
-        self.semaphore
+    /// Non-blocking try-acquire — returns `None` if no slot is free for
+    /// `channel` or turns are already waiting for one.
+    pub fn try_acquire(&self, channel: &str) -> Option<TurnPermit> {
+        // Slots are only free when nobody is queued, so this can't jump the line
+        let channel_permit = match self.inner.channels.get(channel) {
+            Some(semaphore) => Some(semaphore.clone().try_acquire_owned().ok()?),
+            None => None,
+        };
+        let global = self.inner.global.clone().try_acquire_owned().ok()?;
+        self.stats_lock().turns += 1;
+        Some(TurnPermit {
+            _global: global,
+            _channel: channel_permit,
+        })
+    }
+
+    /// Wait for every in-flight turn to finish. Until the returned permit
+    /// is dropped no new turn can start.
+    pub async fn acquire_all(&self) -> OwnedSemaphorePermit {
+        self.inner
+            .global
             .clone()
-            .acquire_owned()
+            .acquire_many_owned(self.inner.max_concurrent as u32)
             .await
             .expect("TurnGate semaphore should never be closed")
     }
 
-    /// Non-blocking try-acquire — returns `None` if an agent turn is in flight.
-    pub fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
-        self.semaphore.clone().try_acquire_owned().ok()
+    /// Returns `true` if no turn slot is free.
+    pub fn is_busy(&self) -> bool {
+        self.inner.global.available_permits() == 0
+    }
+
+    pub fn stats(&self) -> TurnGateStats {
+        let stats = self.stats_lock();
+        TurnGateStats {
+            max_concurrent: self.inner.max_concurrent,
+            in_flight: self.inner.max_concurrent - self.inner.global.available_permits(),
+            waiting: self.inner.waiting.load(Ordering::Relaxed),
+            turns: stats.turns,
+            waited: stats.waited,
+            avg_wait_ms: stats
+                .total_wait
+                .as_millis()
+                .checked_div(stats.waited as u128)
+                .unwrap_or(0) as u64,
+            max_wait_ms: stats.max_wait.as_millis() as u64,
+            timeouts: stats.timeouts,
+        }
     }
 
-    /// Returns `true` if an agent turn is currently in progress.
-    pub fn is_busy(&self) -> bool {
-        self.semaphore.available_permits() == 0
+    fn stats_lock(&self) -> std::sync::MutexGuard<'_, WaitStats> {
+        self.inner.stats.lock().unwrap_or_else(|e| e.into_inner())
     }
 }
 
@@ -48,6 +238,30 @@ impl Default for TurnGate {
     }
 }
 
+/// Counts a waiting turn until dropped, including when the caller gives up
+struct Waiting<'a>(&'a AtomicUsize);
+
+impl<'a> Waiting<'a> {
+    fn new(count: &'a AtomicUsize) -> Self {
+        count.fetch_add(1, Ordering::Relaxed);
+        Self(count)
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+async fn acquire_owned(semaphore: &Arc<Semaphore>) -> OwnedSemaphorePermit {
+    semaphore
+        .clone()
+        .acquire_owned()
+        .await
+        .expect("TurnGate semaphore should never be closed")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,7 +271,7 @@ mod tests {
         let gate = TurnGate::new();
         assert!(!gate.is_busy());
 
-        let permit = gate.acquire().await;
+        let permit = gate.acquire("http").await.unwrap();
         assert!(gate.is_busy());
 
         drop(permit);
@@ -68,14 +282,14 @@ mod tests {
     async fn try_acquire_returns_none_when_busy() {
         let gate = TurnGate::new();
 
-        let _permit = gate.acquire().await;
-        assert!(gate.try_acquire().is_none());
+        let _permit = gate.acquire("http").await.unwrap();
+        assert!(gate.try_acquire("heartbeat").is_none());
     }
 
     #[tokio::test]
     async fn try_acquire_succeeds_when_free() {
         let gate = TurnGate::new();
-        let permit = gate.try_acquire();
+        let permit = gate.try_acquire("heartbeat");
         assert!(permit.is_some());
     }
 
@@ -84,8 +298,87 @@ mod tests {
         let gate1 = TurnGate::new();
         let gate2 = gate1.clone();
 
-        let _permit = gate1.acquire().await;
+        let _permit = gate1.acquire("http").await.unwrap();
         assert!(gate2.is_busy());
-        assert!(gate2.try_acquire().is_none());
+        assert!(gate2.try_acquire("http").is_none());
+    }
+
+    #[tokio::test]
+    async fn channel_limit_applies_within_global_limit() {
+        let gate = TurnGate::with_limits(3, HashMap::from([("telegram".to_string(), 1)]), None);
+
+        let _telegram = gate.try_acquire("telegram").unwrap();
+        assert!(gate.try_acquire("telegram").is_none());
+
+        // Other channels still have room
+        let _http1 = gate.try_acquire("http").unwrap();
+        let _http2 = gate.try_acquire("http").unwrap();
+        assert!(gate.is_busy());
+        assert_eq!(gate.stats().in_flight, 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn acquire_times_out_as_busy() {
+        let gate = TurnGate::with_limits(1, HashMap::new(), Some(Duration::from_secs(5)));
+        let _held = gate.acquire("http").await.unwrap();
+
+        let err = gate
+            .acquire("telegram")
+            .await
+            .err()
+            .expect("should time out");
+        assert_eq!(err.channel, "telegram");
+        assert!(err.waited >= Duration::from_secs(5));
+        assert!(err.to_string().contains("try again later"));
+
+        let stats = gate.stats();
+        assert_eq!(stats.timeouts, 1);
+        assert_eq!(stats.waiting, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waiting_turns_are_served_in_order() {
+        let gate = TurnGate::new();
+        let held = gate.acquire("http").await.unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for id in 0..3 {
+            let gate = gate.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let _permit = gate.acquire("http").await.unwrap();
+                tx.send(id).unwrap();
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            });
+            // Let each waiter join the line before the next
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(gate.stats().waiting, 3);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        drop(held);
+        for id in 0..3 {
+            assert_eq!(rx.recv().await, Some(id));
+        }
+
+        let stats = gate.stats();
+        assert_eq!(stats.turns, 4);
+        assert_eq!(stats.waited, 3);
+        assert!(stats.max_wait_ms >= 100);
+    }
+
+    #[tokio::test]
+    async fn acquire_all_waits_for_every_turn() {
+        let gate = TurnGate::with_limits(2, HashMap::new(), None);
+        let first = gate.try_acquire("http").unwrap();
+        let second = gate.try_acquire("http").unwrap();
+
+        let all = tokio::time::timeout(Duration::from_millis(20), gate.acquire_all()).await;
+        assert!(all.is_err(), "turns are still running");
+
+        drop(first);
+        drop(second);
+        let _all = gate.acquire_all().await;
+        assert!(gate.try_acquire("http").is_none());
     }
 }
@@ -2,12 +2,32 @@
 //!
 //! Serializes all agent turns across processes (daemon, CLI, desktop)
 //! so that shared workspace files (MEMORY.md, sessions.json, etc.)
-//! are never written concurrently.
+//! are never written concurrently. Writers (agent turns) take the lock
+//! exclusively; readers that only need a consistent view (reindexing) share it.
+//!
+//! Each guard records who holds it in a file under `workspace.lock.holders/`,
+//! for `localgpt doctor` and `daemon status`. An flock dies with its process,
+//! but one held through a dead process's descendant (or a network filesystem
+//! that loses track) would block every turn; when the lock stays held while
+//! every recorded holder is dead, it's broken by replacing the lock file.
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use fs2::FileExt;
+use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// How often a waiting acquire retries the lock
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long the lock must stay held with only dead holders before it's broken
+const STALE_AFTER: Duration = Duration::from_secs(5);
+
+/// Distinguishes this process's holder records
+static NEXT_RECORD: AtomicU64 = AtomicU64::new(0);
 
 /// Advisory file lock for the agent workspace.
 ///
@@ -18,13 +38,75 @@ pub struct WorkspaceLock {
     path: PathBuf,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockMode {
+    /// Shared with other readers
+    Read,
+    /// Exclusive
+    Write,
+}
+
+impl std::fmt::Display for LockMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockMode::Read => write!(f, "read"),
+            LockMode::Write => write!(f, "write"),
+        }
+    }
+}
+
+/// A process holding (or, if it died, having held) the workspace lock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub mode: LockMode,
+    pub since: DateTime<Utc>,
+    /// The holder's command line, e.g. "localgpt daemon start"
+    pub command: String,
+}
+
+impl LockHolder {
+    fn current(mode: LockMode) -> Self {
+        let mut args = std::env::args();
+        let program = args
+            .next()
+            .and_then(|p| {
+                Path::new(&p)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+            })
+            .unwrap_or_default();
+        let command = std::iter::once(program)
+            .chain(args.take(2))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            pid: std::process::id(),
+            mode,
+            since: Utc::now(),
+            command,
+        }
+    }
+
+    /// Whether the holder's process is still running
+    pub fn is_alive(&self) -> bool {
+        process_alive(self.pid)
+    }
+}
+
 /// RAII guard that releases the lock on drop.
 pub struct WorkspaceLockGuard {
     file: File,
+    /// This guard's holder record
+    record: Option<PathBuf>,
 }
 
 impl Drop for WorkspaceLockGuard {
     fn drop(&mut self) {
+        if let Some(record) = &self.record {
+            let _ = fs::remove_file(record);
+        }
         let _ = self.file.unlock();
     }
 }
@@ -35,7 +117,11 @@ impl WorkspaceLock {
     /// The lock file is placed in the runtime directory (or state directory fallback).
     pub fn new() -> Result<Self> {
         let paths = crate::paths::Paths::resolve()?;
-        let path = paths.workspace_lock();
+        Self::at(paths.workspace_lock())
+    }
+
+    /// Create a WorkspaceLock on the lock file at `path`.
+    pub fn at(path: PathBuf) -> Result<Self> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
@@ -43,29 +129,178 @@ impl WorkspaceLock {
         Ok(Self { path })
     }
 
-    /// Blocking acquire — waits until the lock is available.
+    /// Blocking exclusive acquire — waits until no one else holds the lock,
+    /// breaking it if it's stale.
     ///
     /// Returns an RAII guard that releases the lock on drop.
     pub fn acquire(&self) -> Result<WorkspaceLockGuard> {
-        let file = File::create(&self.path)?;
-        file.lock_exclusive()?;
-        Ok(WorkspaceLockGuard { file })
+        self.acquire_mode(LockMode::Write)
+    }
+
+    /// Blocking shared acquire — waits only for a writer.
+    pub fn acquire_read(&self) -> Result<WorkspaceLockGuard> {
+        self.acquire_mode(LockMode::Read)
     }
 
     /// Non-blocking try-acquire — returns `None` if another process holds it.
     pub fn try_acquire(&self) -> Result<Option<WorkspaceLockGuard>> {
+        self.try_acquire_mode(LockMode::Write)
+    }
+
+    /// Non-blocking shared try-acquire — returns `None` if a writer holds it.
+    pub fn try_acquire_read(&self) -> Result<Option<WorkspaceLockGuard>> {
+        self.try_acquire_mode(LockMode::Read)
+    }
+
+    /// Who holds the lock, according to their records. Records of dead
+    /// processes are included; see [`LockHolder::is_alive`].
+    pub fn holders(&self) -> Vec<LockHolder> {
+        let mut holders: Vec<LockHolder> = fs::read_dir(self.holders_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .filter_map(|content| serde_json::from_str(&content).ok())
+            .collect();
+        holders.sort_by_key(|h| h.since);
+        holders
+    }
+
+    /// Whether the lock is held with records naming only dead processes.
+    /// Such a lock is broken by the next acquire that waits [`STALE_AFTER`].
+    pub fn is_stale(&self) -> Result<bool> {
+        let held = self.try_acquire_read()?.is_none();
+        Ok(held && self.only_dead_holders())
+    }
+
+    /// Replace the lock file so new acquires don't wait on whoever holds the
+    /// old one, and drop the dead holders' records.
+    pub fn break_lock(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        self.remove_dead_records();
+        Ok(())
+    }
+
+    fn acquire_mode(&self, mode: LockMode) -> Result<WorkspaceLockGuard> {
+        // Polled rather than blocking, so a lock whose holder died can be
+        // noticed and broken
+        let mut stale_since: Option<Instant> = None;
+        loop {
+            if let Some(guard) = self.try_acquire_mode(mode)? {
+                return Ok(guard);
+            }
+            if self.only_dead_holders() {
+                let since = *stale_since.get_or_insert_with(Instant::now);
+                if since.elapsed() >= STALE_AFTER {
+                    tracing::warn!(
+                        "Breaking stale workspace lock {} (held, but its recorded holders have exited)",
+                        self.path.display()
+                    );
+                    self.break_lock()?;
+                    stale_since = None;
+                    continue;
+                }
+            } else {
+                stale_since = None;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn try_acquire_mode(&self, mode: LockMode) -> Result<Option<WorkspaceLockGuard>> {
         let file = File::create(&self.path)?;
-        match file.try_lock_exclusive() {
-            Ok(()) => Ok(Some(WorkspaceLockGuard { file })),
-            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(None),
-            #[cfg(unix)]
-            Err(ref e) if e.raw_os_error() == Some(35) || e.raw_os_error() == Some(11) => {
-                // EAGAIN(11) / EWOULDBLOCK(35 on macOS) — lock contention
-                Ok(None)
+        let locked = match mode {
+            LockMode::Read => file.try_lock_shared(),
+            LockMode::Write => file.try_lock_exclusive(),
+        };
+        match locked {
+            Ok(()) => {
+                // Whoever left these exited without releasing their records
+                self.remove_dead_records();
+                Ok(Some(WorkspaceLockGuard {
+                    record: self.write_record(mode),
+                    file,
+                }))
             }
+            Err(ref e) if is_contended(e) => Ok(None),
             Err(e) => Err(e.into()),
         }
     }
+
+    fn holders_dir(&self) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(".holders");
+        PathBuf::from(name)
+    }
+
+    /// Record this process as a holder. Best effort: the lock works without it.
+    fn write_record(&self, mode: LockMode) -> Option<PathBuf> {
+        let dir = self.holders_dir();
+        fs::create_dir_all(&dir).ok()?;
+        let record = dir.join(format!(
+            "{}-{}.json",
+            std::process::id(),
+            NEXT_RECORD.fetch_add(1, Ordering::Relaxed)
+        ));
+        let content = serde_json::to_string(&LockHolder::current(mode)).ok()?;
+        fs::write(&record, content).ok()?;
+        Some(record)
+    }
+
+    /// True if there are holder records and none of them is alive. Without
+    /// records the holder is unknown (perhaps an older version), so not stale.
+    fn only_dead_holders(&self) -> bool {
+        let holders = self.holders();
+        !holders.is_empty() && holders.iter().all(|h| !h.is_alive())
+    }
+
+    fn remove_dead_records(&self) {
+        for entry in fs::read_dir(self.holders_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let dead = fs::read_to_string(entry.path())
+                .ok()
+                .and_then(|content| serde_json::from_str::<LockHolder>(&content).ok())
+                // Unparseable may be a record still being written
+                .is_some_and(|holder| !holder.is_alive());
+            if dead {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+fn is_contended(e: &std::io::Error) -> bool {
+    if e.kind() == std::io::ErrorKind::WouldBlock {
+        return true;
+    }
+    // EAGAIN(11) / EWOULDBLOCK(35 on macOS) — lock contention
+    #[cfg(unix)]
+    if e.raw_os_error() == Some(35) || e.raw_os_error() == Some(11) {
+        return true;
+    }
+    false
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 checks existence; EPERM means it exists under another user
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    // No cheap check; assume alive so a held lock is never broken
+    true
 }
 
 #[cfg(test)]
@@ -158,4 +393,80 @@ mod tests {
 
         assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 3);
     }
+
+    #[test]
+    fn readers_share_and_writers_exclude() {
+        let tmp = tempfile::tempdir().unwrap();
+        let lock = test_lock(tmp.path());
+
+        let read1 = lock.acquire_read().unwrap();
+        let read2 = lock.try_acquire_read().unwrap();
+        assert!(read2.is_some(), "readers should share the lock");
+        assert!(lock.try_acquire().unwrap().is_none());
+
+        drop(read1);
+        drop(read2);
+        let _write = lock.try_acquire().unwrap().expect("lock should be free");
+        assert!(lock.try_acquire_read().unwrap().is_none());
+    }
+
+    #[test]
+    fn guards_record_their_holder() {
+        let tmp = tempfile::tempdir().unwrap();
+        let lock = test_lock(tmp.path());
+
+        let guard = lock.acquire().unwrap();
+        let holders = lock.holders();
+        assert_eq!(holders.len(), 1);
+        assert_eq!(holders[0].pid, std::process::id());
+        assert_eq!(holders[0].mode, LockMode::Write);
+        assert!(holders[0].is_alive());
+        assert!(!lock.is_stale().unwrap());
+
+        drop(guard);
+        assert!(lock.holders().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stale_lock_is_detected_and_broken() {
+        let tmp = tempfile::tempdir().unwrap();
+        let lock = test_lock(tmp.path());
+
+        // Held by something whose only recorded holder has exited
+        let file = File::create(&lock.path).unwrap();
+        file.lock_exclusive().unwrap();
+        let dead = LockHolder {
+            pid: u32::MAX,
+            mode: LockMode::Write,
+            since: Utc::now(),
+            command: "localgpt chat".to_string(),
+        };
+        fs::create_dir_all(lock.holders_dir()).unwrap();
+        fs::write(
+            lock.holders_dir().join("dead.json"),
+            serde_json::to_string(&dead).unwrap(),
+        )
+        .unwrap();
+
+        assert!(lock.try_acquire().unwrap().is_none());
+        assert!(lock.is_stale().unwrap());
+
+        lock.break_lock().unwrap();
+        assert!(lock.holders().is_empty());
+        let _guard = lock
+            .try_acquire()
+            .unwrap()
+            .expect("broken lock should be free");
+    }
+
+    #[test]
+    fn held_lock_without_records_is_not_stale() {
+        let tmp = tempfile::tempdir().unwrap();
+        let lock = test_lock(tmp.path());
+
+        let file = File::create(&lock.path).unwrap();
+        file.lock_exclusive().unwrap();
+        assert!(!lock.is_stale().unwrap());
+    }
 }
@@ -0,0 +1,466 @@
+//! Reading and editing settings by dotted key
+//!
+//! `localgpt config get/set/validate` go through here. Reads use the loaded
+//! [`Config`], so defaults show up. Writes edit config.toml with toml_edit,
+//! keeping comments and layout, and take the value's type from the setting
+//! being replaced. A write is refused unless the result still loads and the
+//! key is one the config knows about.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use toml_edit::{DocumentMut, Item, Table};
+
+use super::{Config, SecretResolver, SecretSource};
+use crate::paths::Paths;
+
+/// A problem found by [`validate_config_file`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    /// Dotted key the problem is with, if it is about one setting
+    pub key: Option<String>,
+    pub message: String,
+    /// Errors stop the config from loading; warnings don't
+    pub is_error: bool,
+}
+
+impl Config {
+    /// Effective value of the dotted `key`, defaults included. Strings are
+    /// returned bare, everything else as TOML.
+    pub fn get_value(&self, key: &str) -> Result<String> {
+        let root = toml::Value::try_from(self)?;
+        let value =
+            lookup(&root, key).ok_or_else(|| anyhow!("Unknown or unset config key: {}", key))?;
+        Ok(match value {
+            toml::Value::String(s) => s.clone(),
+            toml::Value::Table(table) => toml::to_string_pretty(table)?.trim_end().to_string(),
+            other => other.to_string(),
+        })
+    }
+}
+
+/// Set the dotted `key` to `raw` in the config file at `path`, keeping the
+/// file's comments and formatting. Returns the value as written.
+pub fn set_config_value(path: &Path, key: &str, raw: &str) -> Result<String> {
+    edit_config_value(path, key, raw, |kind| kind.parse(raw))
+}
+
+/// Set the dotted `key` to the string `value`, whatever type the setting
+/// currently has. For secrets and model names that must never be read as
+/// TOML (a key like `123` would otherwise become an integer).
+pub fn set_config_string(path: &Path, key: &str, value: &str) -> Result<String> {
+    edit_config_value(path, key, value, |_| Ok(value.into()))
+}
+
+fn edit_config_value(
+    path: &Path,
+    key: &str,
+    raw: &str,
+    make_value: impl FnOnce(Kind) -> Result<toml_edit::Value>,
+) -> Result<String> {
+    let content = if path.exists() {
+        fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+    let mut doc: DocumentMut = content
+        .parse()
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let parts = split_key(key)?;
+    let (last, parents) = parts
+        .split_last()
+        .expect("split_key returns a non-empty key");
+
+    // The type comes from the current value, else from the default config
+    let defaults = toml::Value::try_from(Config::default())?;
+    let existing = lookup_item(doc.as_item(), &parts).and_then(Item::as_value);
+    let kind = match existing {
+        Some(value) => Kind::of_edit(value),
+        None => lookup(&defaults, key).map_or(Kind::Other, Kind::of_toml),
+    };
+    let mut value = make_value(kind).with_context(|| format!("Invalid value for {}", key))?;
+
+    let mut table = doc.as_item_mut();
+    for part in parents {
+        table = table
+            .as_table_like_mut()
+            .ok_or_else(|| anyhow!("{} is not a table", key))?
+            .entry(part)
+            .or_insert_with(|| {
+                let mut table = Table::new();
+                table.set_implicit(true);
+                Item::Table(table)
+            });
+        if !table.is_table_like() {
+            bail!("Cannot set {}: {} is not a table", key, part);
+        }
+    }
+    let table = table
+        .as_table_like_mut()
+        .ok_or_else(|| anyhow!("{} is not a table", key))?;
+    let written = value.to_string().trim().to_string();
+    if let Some(old) = table.get(last).and_then(Item::as_value) {
+        *value.decor_mut() = old.decor().clone();
+    }
+    table.insert(last, Item::Value(value));
+
+    let updated = doc.to_string();
+    let config: Config = toml::from_str(&updated)
+        .with_context(|| format!("Setting {} = {} would make the config invalid", key, raw))?;
+    if lookup(&toml::Value::try_from(&config)?, key).is_none() {
+        bail!("Unknown config key: {}", key);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Write then rename, so a crash can't leave a half-written config
+    let tmp = path.with_extension("toml.tmp");
+    write_like(&tmp, path, &updated)?;
+    fs::rename(&tmp, path)?;
+    Ok(written)
+}
+
+/// Write `content` to `tmp` with the permissions of `original` (0600 if it
+/// doesn't exist yet), since the config can hold secrets. The permissions
+/// are set before anything is written.
+fn write_like(tmp: &Path, original: &Path, content: &str) -> Result<()> {
+    let mut file = fs::File::create(tmp)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(original)
+            .map(|meta| meta.permissions().mode() & 0o777)
+            .unwrap_or(0o600);
+        file.set_permissions(fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = original;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Check the config file at `path` without loading it: TOML syntax, the
+/// type of every setting, keys the config doesn't know (which are ignored
+/// on load), `${VAR}` references to unset environment variables, and
+/// `enc:` and `keyring:` secrets that can't be read.
+pub fn validate_config_file(path: &Path) -> Result<Vec<ConfigIssue>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let resolver = Paths::resolve()
+        .ok()
+        .map(|paths| SecretResolver::new(&paths.data_dir));
+    validate_config_str(&content, resolver.as_ref())
+}
+
+fn validate_config_str(
+    content: &str,
+    resolver: Option<&SecretResolver>,
+) -> Result<Vec<ConfigIssue>> {
+    let error = |message: String| ConfigIssue {
+        key: None,
+        message,
+        is_error: true,
+    };
+
+    let raw: toml::Value = match toml::from_str(content) {
+        Ok(raw) => raw,
+        Err(e) => return Ok(vec![error(e.to_string().trim_end().to_string())]),
+    };
+    let config: Config = match toml::from_str(content) {
+        Ok(config) => config,
+        Err(e) => return Ok(vec![error(e.to_string().trim_end().to_string())]),
+    };
+
+    let mut issues = Vec::new();
+    let known = toml::Value::try_from(&config)?;
+    unknown_keys(&raw, &known, "", &mut issues);
+    unresolved_refs(&raw, "", resolver, &mut issues);
+    Ok(issues)
+}
+
+/// The primitive type a setting holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    String,
+    Integer,
+    Float,
+    Boolean,
+    /// Arrays, tables, dates, or unknown: parsed as a TOML value
+    Other,
+}
+
+impl Kind {
+    fn of_toml(value: &toml::Value) -> Self {
+        match value {
+            toml::Value::String(_) => Kind::String,
+            toml::Value::Integer(_) => Kind::Integer,
+            toml::Value::Float(_) => Kind::Float,
+            toml::Value::Boolean(_) => Kind::Boolean,
+            _ => Kind::Other,
+        }
+    }
+
+    fn of_edit(value: &toml_edit::Value) -> Self {
+        match value {
+            toml_edit::Value::String(_) => Kind::String,
+            toml_edit::Value::Integer(_) => Kind::Integer,
+            toml_edit::Value::Float(_) => Kind::Float,
+            toml_edit::Value::Boolean(_) => Kind::Boolean,
+            _ => Kind::Other,
+        }
+    }
+
+    fn parse(self, raw: &str) -> Result<toml_edit::Value> {
+        Ok(match self {
+            Kind::String => raw.into(),
+            Kind::Integer => raw
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| anyhow!("expected an integer, got {:?}", raw))?
+                .into(),
+            Kind::Float => raw
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| anyhow!("expected a number, got {:?}", raw))?
+                .into(),
+            Kind::Boolean => match raw.trim() {
+                "true" => true.into(),
+                "false" => false.into(),
+                _ => bail!("expected true or false, got {:?}", raw),
+            },
+            // Anything that isn't valid TOML is taken as a string
+            Kind::Other => raw.trim().parse().unwrap_or_else(|_| raw.into()),
+        })
+    }
+}
+
+fn split_key(key: &str) -> Result<Vec<&str>> {
+    let parts: Vec<&str> = key.split('.').collect();
+    if parts.iter().any(|p| p.is_empty()) {
+        bail!("Invalid config key: {:?}", key);
+    }
+    Ok(parts)
+}
+
+fn lookup<'a>(root: &'a toml::Value, key: &str) -> Option<&'a toml::Value> {
+    key.split('.')
+        .try_fold(root, |value, part| value.as_table()?.get(part))
+}
+
+fn lookup_item<'a>(root: &'a Item, parts: &[&str]) -> Option<&'a Item> {
+    parts
+        .iter()
+        .try_fold(root, |item, part| item.as_table_like()?.get(part))
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// Report keys in `raw` that didn't survive a load and save, i.e. that the
+/// config ignores
+fn unknown_keys(raw: &toml::Value, known: &toml::Value, prefix: &str, out: &mut Vec<ConfigIssue>) {
+    match (raw, known) {
+        (toml::Value::Table(raw), toml::Value::Table(known)) => {
+            for (key, value) in raw {
+                let path = join_key(prefix, key);
+                match known.get(key) {
+                    Some(known) => unknown_keys(value, known, &path, out),
+                    None => out.push(ConfigIssue {
+                        message: "Unknown key; it is ignored".to_string(),
+                        key: Some(path),
+                        is_error: false,
+                    }),
+                }
+            }
+        }
+        (toml::Value::Array(raw), toml::Value::Array(known)) => {
+            for (i, (raw, known)) in raw.iter().zip(known).enumerate() {
+                unknown_keys(raw, known, &format!("{}[{}]", prefix, i), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Report `${VAR}` values whose variable isn't set, and `enc:` and
+/// `keyring:` values that `resolver` can't read
+fn unresolved_refs(
+    raw: &toml::Value,
+    prefix: &str,
+    resolver: Option<&SecretResolver>,
+    out: &mut Vec<ConfigIssue>,
+) {
+    match raw {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                unresolved_refs(value, &join_key(prefix, key), resolver, out);
+            }
+        }
+        toml::Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                unresolved_refs(value, &format!("{}[{}]", prefix, i), resolver, out);
+            }
+        }
+        toml::Value::String(s) => {
+            if let Some(var) = s.strip_prefix("${").and_then(|s| s.strip_suffix('}'))
+                && std::env::var_os(var).is_none()
+            {
+                out.push(ConfigIssue {
+                    key: Some(prefix.to_string()),
+                    message: format!("Environment variable {} is not set", var),
+                    is_error: false,
+                });
+            } else if let Some(resolver) = resolver
+                && matches!(
+                    SecretSource::of(s),
+                    SecretSource::Encrypted | SecretSource::Keyring
+                )
+                && let Err(e) = resolver.resolve(s)
+            {
+                out.push(ConfigIssue {
+                    key: Some(prefix.to_string()),
+                    message: format!("{:#}", e),
+                    is_error: false,
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_set_config_value_keeps_comments_and_types() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "# My config\n[server]\nport = 31327 # default port\nbind = \"127.0.0.1\"\n",
+        )?;
+
+        assert_eq!(set_config_value(&path, "server.port", "8080")?, "8080");
+        assert_eq!(
+            set_config_value(&path, "server.bind", "0.0.0.0")?,
+            "\"0.0.0.0\""
+        );
+        // A section that isn't in the file yet, typed from the defaults
+        assert_eq!(
+            set_config_value(&path, "heartbeat.enabled", "false")?,
+            "false"
+        );
+
+        let content = fs::read_to_string(&path)?;
+        assert!(content.starts_with("# My config\n"));
+        assert!(content.contains("port = 8080 # default port"));
+        assert!(content.contains("bind = \"0.0.0.0\""));
+        assert!(content.contains("[heartbeat]\nenabled = false"));
+
+        let config: Config = toml::from_str(&content)?;
+        assert_eq!(config.server.port, 8080);
+        assert!(!config.heartbeat.enabled);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_config_value_rejects_bad_input() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("config.toml");
+        let original = "[server]\nport = 31327\n";
+        fs::write(&path, original)?;
+
+        assert!(set_config_value(&path, "server.port", "eighty").is_err());
+        assert!(set_config_value(&path, "server.port", "99999").is_err());
+        assert!(set_config_value(&path, "server.no_such_key", "1").is_err());
+        assert!(set_config_value(&path, "server..port", "1").is_err());
+        assert_eq!(fs::read_to_string(&path)?, original);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_set_config_value_keeps_permissions() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "[server]\nport = 31327\n")?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+
+        set_config_value(&path, "server.port", "8080")?;
+        assert_eq!(fs::metadata(&path)?.permissions().mode() & 0o777, 0o600);
+
+        // A new config is only readable by its owner
+        let new_path = temp_dir.path().join("new.toml");
+        set_config_value(&new_path, "server.port", "8080")?;
+        assert_eq!(fs::metadata(&new_path)?.permissions().mode() & 0o777, 0o600);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_config_string_never_parses_toml() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "[agent]\ndefault_model = \"claude-cli/opus\"\n")?;
+
+        set_config_string(&path, "providers.openai.api_key", "12345")?;
+        set_config_string(&path, "agent.default_model", "ollama/llama3")?;
+
+        let config: Config = toml::from_str(&fs::read_to_string(&path)?)?;
+        assert_eq!(config.providers.openai.unwrap().api_key, "12345");
+        assert_eq!(config.agent.default_model, "ollama/llama3");
+
+        // Still refuses keys the config doesn't know
+        assert!(set_config_string(&path, "agent.no_such_key", "x").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_config() -> Result<()> {
+        assert!(validate_config_str("[server]\nport = 31327\n", None)?.is_empty());
+
+        let issues = validate_config_str("[server\nport = 1\n", None)?;
+        assert!(issues.len() == 1 && issues[0].is_error);
+
+        let issues = validate_config_str("[server]\nport = \"high\"\n", None)?;
+        assert!(issues.len() == 1 && issues[0].is_error);
+
+        let issues = validate_config_str(
+            "[server]\nprot = 1\n\n[providers.openai]\napi_key = \"${LOCALGPT_TEST_UNSET_VAR}\"\n",
+            None,
+        )?;
+        let keys: Vec<_> = issues.iter().filter_map(|i| i.key.as_deref()).collect();
+        assert_eq!(keys, vec!["server.prot", "providers.openai.api_key"]);
+        assert!(issues.iter().all(|i| !i.is_error));
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_config_checks_encrypted_secrets() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        crate::security::ensure_device_key(temp_dir.path())?;
+        let resolver = SecretResolver::new(temp_dir.path());
+        let good = crate::security::encrypt_config_secret(temp_dir.path(), "token")?;
+
+        let content = format!(
+            "[providers.openai]\napi_key = \"{}\"\n\n[server]\nauth_token = \"enc:AAAAAAAAAAAAAAAAAAAAAAAA\"\n",
+            good
+        );
+        let issues = validate_config_str(&content, Some(&resolver))?;
+        let keys: Vec<_> = issues.iter().filter_map(|i| i.key.as_deref()).collect();
+        assert_eq!(keys, vec!["server.auth_token"]);
+        assert!(issues[0].message.contains("different device key"));
+        Ok(())
+    }
+}
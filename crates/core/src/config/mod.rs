@@ -1,15 +1,20 @@
+mod edit;
 mod migrate;
 mod schema;
+mod secrets;
 pub mod watcher;
 
+pub use edit::{ConfigIssue, set_config_string, set_config_value, validate_config_file};
 pub use migrate::check_openclaw_detected;
 pub use schema::*;
+pub use secrets::{SecretResolver, SecretSource};
 pub use watcher::{ConfigWatcher, spawn_sighup_handler};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 use tracing::debug;
 
 use crate::env::LOCALGPT_WORKSPACE;
@@ -57,6 +62,45 @@ pub struct Config {
 
     #[serde(default)]
     pub mcp: McpConfig,
+
+    #[serde(default)]
+    pub skills: SkillsConfig,
+
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+/// An outbound webhook (`[[webhooks]]`): daemon events are POSTed to `url`
+/// as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+
+    /// Events to deliver, by name ("cron.finished") or prefix ("bridge.*").
+    /// See `webhooks::WebhookEvent` for the names. Empty delivers all.
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// Key for the `X-LocalGPT-Signature` HMAC-SHA256 header.
+    /// Supports ${ENV_VAR}, `keyring:<name>`, and `enc:` values.
+    #[serde(default)]
+    pub secret: Option<String>,
+
+    /// Delivery attempts before giving up, with exponential backoff
+    #[serde(default = "default_webhook_attempts")]
+    pub max_attempts: u32,
+
+    /// Timeout per attempt, e.g. "10s"
+    #[serde(default = "default_webhook_timeout")]
+    pub timeout: String,
+}
+
+fn default_webhook_attempts() -> u32 {
+    3
+}
+
+fn default_webhook_timeout() -> String {
+    "10s".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +182,15 @@ pub struct ToolsConfig {
     #[serde(default = "default_tool_output_max_chars")]
     pub tool_output_max_chars: usize,
 
+    /// Maximum tool output size in tokens (~4 chars each) before it is paged.
+    /// Larger outputs return the first page plus a `read_more` cursor (0 = unlimited)
+    #[serde(default = "default_tool_output_max_tokens")]
+    pub tool_output_max_tokens: usize,
+
+    /// Per-tool overrides of `tool_output_max_tokens`, keyed by tool name
+    #[serde(default)]
+    pub output_limits: std::collections::HashMap<String, usize>,
+
     /// Log warnings for suspicious injection patterns detected in tool outputs
     #[serde(default = "default_true")]
     pub log_injection_warnings: bool,
@@ -146,6 +199,11 @@ pub struct ToolsConfig {
     #[serde(default = "default_true")]
     pub use_content_delimiters: bool,
 
+    /// Record every tool invocation to the tool usage audit log
+    /// (`localgpt.tools.jsonl` in the state directory)
+    #[serde(default = "default_true")]
+    pub audit_log: bool,
+
     /// Web search configuration (disabled by default)
     #[serde(default)]
     pub web_search: Option<WebSearchConfig>,
@@ -154,6 +212,79 @@ pub struct ToolsConfig {
     /// Keys are tool names (e.g. "bash", "web_fetch").
     #[serde(default)]
     pub filters: std::collections::HashMap<String, crate::agent::tool_filters::ToolFilter>,
+
+    /// Per-interface tool allow/deny lists, enforced when agents are constructed.
+    /// Keys are interface names (e.g. "cli", "http", "telegram", "bridge", "cron").
+    #[serde(default)]
+    pub interfaces:
+        std::collections::HashMap<String, crate::agent::tool_filters::InterfaceToolPolicy>,
+
+    /// User-defined tools backed by shell command templates.
+    /// Declared in config.toml as [[tools.commands]] entries.
+    #[serde(default)]
+    pub commands: Vec<CommandToolConfig>,
+
+    /// WASM plugin tools. Declared in config.toml as [[tools.plugins]] entries.
+    #[serde(default)]
+    pub plugins: Vec<WasmPluginConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmPluginConfig {
+    /// Path to the WASM component (.wasm) implementing the tool-plugin world
+    pub path: String,
+
+    /// Host capabilities granted to the plugin (default: none)
+    #[serde(default)]
+    pub capabilities: Vec<PluginCapability>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginCapability {
+    /// Outbound HTTP GET (same SSRF protections as web_fetch)
+    Http,
+    /// Read/write files inside the workspace
+    Fs,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandToolConfig {
+    /// Tool name exposed to the model (must not clash with built-in tools)
+    pub name: String,
+
+    /// Description shown to the model
+    pub description: String,
+
+    /// JSON schema for the tool arguments
+    #[serde(default = "default_command_tool_parameters")]
+    pub parameters: serde_json::Value,
+
+    /// Shell command template; `{{arg}}` placeholders are replaced with
+    /// shell-quoted argument values
+    pub command: String,
+
+    /// Sandbox policy: "inherit" (same sandbox as bash, default) or "none"
+    #[serde(default)]
+    pub sandbox: CommandToolSandbox,
+
+    /// Timeout in milliseconds (default: tools.bash_timeout_ms)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandToolSandbox {
+    /// Run under the same sandbox policy as the bash tool
+    #[default]
+    Inherit,
+    /// Run without sandbox enforcement (timeout still applies)
+    None,
+}
+
+fn default_command_tool_parameters() -> serde_json::Value {
+    serde_json::json!({"type": "object", "properties": {}})
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -163,6 +294,10 @@ pub enum SearchProviderType {
     Brave,
     Tavily,
     Perplexity,
+    Serper,
+    #[serde(rename = "duckduckgo")]
+    DuckDuckGo,
+    Kagi,
     #[default]
     None,
 }
@@ -179,6 +314,14 @@ pub struct WebSearchConfig {
     #[serde(default = "default_cache_ttl")]
     pub cache_ttl: u64,
 
+    /// Persist cached results in SQLite so they survive restarts (default: false)
+    #[serde(default)]
+    pub persistent_cache: bool,
+
+    /// Maximum entries kept in the persistent cache (oldest evicted first)
+    #[serde(default = "default_cache_max_entries")]
+    pub cache_max_entries: usize,
+
     /// Maximum results per query (1-10, default: 5)
     #[serde(default = "default_max_results")]
     pub max_results: u8,
@@ -187,6 +330,35 @@ pub struct WebSearchConfig {
     #[serde(default = "default_true")]
     pub prefer_native: bool,
 
+    /// Providers tried in order when the primary fails, is rate limited,
+    /// or is over its daily quota
+    #[serde(default)]
+    pub fallback: Vec<SearchProviderType>,
+
+    /// Per-provider daily limits, keyed by provider name (e.g. "brave")
+    #[serde(default)]
+    pub quotas: std::collections::HashMap<String, SearchQuotaConfig>,
+
+    /// Try free providers first for low-stakes queries
+    #[serde(default = "default_true")]
+    pub prefer_free: bool,
+
+    /// Limits for `fetch_content` (fetching the top result pages)
+    #[serde(default)]
+    pub fetch_content: FetchContentConfig,
+
+    /// Only return results from these domains (and their subdomains); empty = any
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+
+    /// Never return results from these domains (and their subdomains)
+    #[serde(default)]
+    pub blocked_domains: Vec<String>,
+
+    /// Ask providers to filter explicit results (SearXNG, Brave, Serper, DuckDuckGo)
+    #[serde(default)]
+    pub safe_search: bool,
+
     #[serde(default)]
     pub searxng: Option<SearxngConfig>,
 
@@ -198,6 +370,57 @@ pub struct WebSearchConfig {
 
     #[serde(default)]
     pub perplexity: Option<PerplexityConfig>,
+
+    #[serde(default)]
+    pub serper: Option<SerperConfig>,
+
+    /// Optional: DuckDuckGo needs no API key
+    #[serde(default)]
+    pub duckduckgo: Option<DuckDuckGoConfig>,
+
+    #[serde(default)]
+    pub kagi: Option<KagiConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchContentConfig {
+    /// Maximum result pages fetched per search (default: 3)
+    #[serde(default = "default_fetch_content_max_pages")]
+    pub max_pages: usize,
+
+    /// Pages fetched in parallel (default: 3)
+    #[serde(default = "default_fetch_content_concurrency")]
+    pub concurrency: usize,
+
+    /// Token budget per fetched page (~4 chars each, default: 1000)
+    #[serde(default = "default_fetch_content_page_tokens")]
+    pub page_tokens: usize,
+
+    /// Per-page fetch timeout in seconds (default: 10)
+    #[serde(default = "default_fetch_content_timeout")]
+    pub timeout_secs: u64,
+}
+
+impl Default for FetchContentConfig {
+    fn default() -> Self {
+        Self {
+            max_pages: default_fetch_content_max_pages(),
+            concurrency: default_fetch_content_concurrency(),
+            page_tokens: default_fetch_content_page_tokens(),
+            timeout_secs: default_fetch_content_timeout(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchQuotaConfig {
+    /// Maximum queries per UTC day
+    #[serde(default)]
+    pub daily_queries: Option<u64>,
+
+    /// Maximum estimated spend per UTC day (USD)
+    #[serde(default)]
+    pub daily_spend_usd: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -244,7 +467,32 @@ pub struct PerplexityConfig {
     pub model: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerperConfig {
+    pub api_key: String,
+
+    /// Country code (Google `gl`, e.g. "us")
+    #[serde(default)]
+    pub country: String,
+
+    /// Interface language (Google `hl`, e.g. "en")
+    #[serde(default)]
+    pub language: String,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DuckDuckGoConfig {
+    /// Region code (DuckDuckGo `kl`, e.g. "us-en")
+    #[serde(default)]
+    pub region: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KagiConfig {
+    pub api_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityConfig {
     /// Abort agent startup on tamper or suspicious content (default: false).
     ///
@@ -280,6 +528,61 @@ pub struct SecurityConfig {
     /// Paths are canonicalized at startup. Symlinks are resolved before checking.
     #[serde(default)]
     pub allowed_directories: Vec<String>,
+
+    /// Replace configured secrets and common token formats with
+    /// `[REDACTED]` in logs, tool results, and exported transcripts
+    /// (default: true)
+    #[serde(default = "default_true")]
+    pub redact_secrets: bool,
+
+    /// Security audit log rotation (`[security.audit]`)
+    #[serde(default)]
+    pub audit: AuditLogConfig,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            strict_policy: false,
+            disable_policy: false,
+            disable_suffix: false,
+            allowed_directories: Vec::new(),
+            redact_secrets: true,
+            audit: AuditLogConfig::default(),
+        }
+    }
+}
+
+/// When the security audit log is rotated, and what happens to old segments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditLogConfig {
+    /// Rotate once the log reaches this size in megabytes (0 = no size limit)
+    #[serde(default = "default_audit_max_size_mb")]
+    pub max_size_mb: u64,
+
+    /// Rotate once the oldest entry is older than this, e.g. "30d"
+    /// (empty = no age limit)
+    #[serde(default = "default_audit_max_age")]
+    pub max_age: String,
+
+    /// Gzip rotated segments (default: true)
+    #[serde(default = "default_true")]
+    pub compress: bool,
+
+    /// Rotated segments to keep; older ones are deleted (0 = keep all)
+    #[serde(default = "default_audit_keep")]
+    pub keep: usize,
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            max_size_mb: default_audit_max_size_mb(),
+            max_age: default_audit_max_age(),
+            compress: true,
+            keep: default_audit_keep(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -312,7 +615,8 @@ pub struct SandboxConfig {
     #[serde(default)]
     pub allow_paths: AllowPathsConfig,
 
-    /// Network policy
+    /// Network access for sandboxed commands; `network = "allow"` or a
+    /// `[sandbox.network]` table
     #[serde(default)]
     pub network: SandboxNetworkConfig,
 }
@@ -328,11 +632,53 @@ pub struct AllowPathsConfig {
     pub write: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "SandboxNetworkRepr")]
 pub struct SandboxNetworkConfig {
-    /// Network policy: "deny" | "proxy"
-    #[serde(default = "default_sandbox_network_policy")]
-    pub policy: String,
+    /// Network policy: "deny" | "allow" | "allowlist"
+    pub policy: SandboxNetwork,
+
+    /// Hosts reachable under "allowlist": `example.com`, `*.example.com`,
+    /// optionally with `:port`
+    pub allow: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxNetwork {
+    /// No network at all
+    #[default]
+    #[serde(alias = "proxy")]
+    Deny,
+    /// Unrestricted network
+    Allow,
+    /// HTTP(S) to the hosts in `allow` only, through a filtering proxy
+    Allowlist,
+}
+
+/// The shorthand string or the full table
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SandboxNetworkRepr {
+    Policy(SandboxNetwork),
+    Table {
+        #[serde(default)]
+        policy: SandboxNetwork,
+        #[serde(default)]
+        allow: Vec<String>,
+    },
+}
+
+impl From<SandboxNetworkRepr> for SandboxNetworkConfig {
+    fn from(repr: SandboxNetworkRepr) -> Self {
+        match repr {
+            SandboxNetworkRepr::Policy(policy) => Self {
+                policy,
+                allow: Vec::new(),
+            },
+            SandboxNetworkRepr::Table { policy, allow } => Self { policy, allow },
+        }
+    }
 }
 
 impl Default for SandboxConfig {
@@ -350,14 +696,6 @@ impl Default for SandboxConfig {
     }
 }
 
-impl Default for SandboxNetworkConfig {
-    fn default() -> Self {
-        Self {
-            policy: default_sandbox_network_policy(),
-        }
-    }
-}
-
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProvidersConfig {
     #[serde(default)]
@@ -402,13 +740,37 @@ pub struct ProvidersConfig {
     pub openai_compatible: Option<OpenAICompatibleConfig>,
 }
 
+impl ProvidersConfig {
+    /// Names of the providers that have a config section, in declaration order.
+    pub fn configured(&self) -> Vec<&'static str> {
+        [
+            ("openai", self.openai.is_some()),
+            ("xai", self.xai.is_some()),
+            ("anthropic", self.anthropic.is_some()),
+            ("ollama", self.ollama.is_some()),
+            ("claude_cli", self.claude_cli.is_some()),
+            ("gemini_cli", self.gemini_cli.is_some()),
+            ("codex_cli", self.codex_cli.is_some()),
+            ("glm", self.glm.is_some()),
+            ("anthropic_oauth", self.anthropic_oauth.is_some()),
+            ("gemini_oauth", self.gemini_oauth.is_some()),
+            ("openai_oauth", self.openai_oauth.is_some()),
+            ("github_copilot", self.github_copilot.is_some()),
+            ("openai_compatible", self.openai_compatible.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, configured)| configured.then_some(name))
+        .collect()
+    }
+}
+
 /// Configuration for OpenAI-compatible providers (OpenRouter, DeepSeek, Groq, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenAICompatibleConfig {
     /// Base URL for the API endpoint (e.g., "https://openrouter.ai/api/v1")
     pub base_url: String,
 
-    /// API key for authentication (supports ${ENV_VAR} expansion)
+    /// API key for authentication (supports ${ENV_VAR}, `keyring:<name>`, and `enc:` values)
     pub api_key: String,
 
     /// Extra headers to include in every request (e.g., OpenRouter attribution)
@@ -681,9 +1043,26 @@ pub struct ServerConfig {
     #[serde(default = "default_bind")]
     pub bind: String,
 
+    /// Listen on `bind`:`port`. Set to false to serve only on `unix_socket`.
+    #[serde(default = "default_true")]
+    pub tcp: bool,
+
+    /// Also serve the API on this unix domain socket (Unix only), e.g.
+    /// "~/.local/state/localgpt/localgpt.sock". The socket is owner-only;
+    /// requests over it authenticate like TCP ones unless
+    /// `unix_socket_trust` is set.
+    #[serde(default)]
+    pub unix_socket: Option<String>,
+
+    /// Treat requests over `unix_socket` without an Authorization header
+    /// as admin. Every process running as the daemon's user can connect,
+    /// including tools, plugins, and MCP servers the agent starts.
+    #[serde(default)]
+    pub unix_socket_trust: bool,
+
     /// Bearer token for API authentication.
     /// If set, all /api/* routes require Authorization: Bearer <token>.
-    /// Supports ${ENV_VAR} expansion.
+    /// Supports ${ENV_VAR}, `keyring:<name>`, and `enc:` values.
     /// If unset, auth is disabled (backward compatible for local-only use).
     #[serde(default)]
     pub auth_token: Option<String>,
@@ -691,17 +1070,160 @@ pub struct ServerConfig {
     #[serde(default)]
     pub rate_limit: RateLimitConfig,
 
+    #[serde(default)]
+    pub cors: CorsConfig,
+
+    #[serde(default)]
+    pub model_concurrency: ModelConcurrencyConfig,
+
+    #[serde(default)]
+    pub turns: TurnsConfig,
+
+    /// Domains the OpenAI-compatible API may download `image_url` parts
+    /// from (subdomains included). Empty accepts only `data:` URLs.
+    #[serde(default)]
+    pub image_url_domains: Vec<String>,
+
     /// Maximum request body size in bytes.
     /// Requests larger than this return 413 Payload Too Large.
     /// Default: 10MB
     #[serde(default = "default_max_request_body")]
     pub max_request_body: usize,
+
+    /// How long the daemon waits on SIGTERM or ctrl-c for in-flight turns
+    /// and cron jobs to finish before exiting anyway, e.g. "30s"
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: String,
 }
 
 fn default_max_request_body() -> usize {
     10 * 1024 * 1024 // 10MB
 }
 
+fn default_shutdown_timeout() -> String {
+    "30s".to_string()
+}
+
+impl ServerConfig {
+    /// `unix_socket` with `~` expanded.
+    pub fn unix_socket_path(&self) -> Option<PathBuf> {
+        self.unix_socket
+            .as_deref()
+            .map(|path| PathBuf::from(shellexpand::tilde(path).to_string()))
+    }
+}
+
+/// Cross-origin access for browser-based clients. The defaults allow any
+/// origin, method, and header, without credentials.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API, e.g. "http://localhost:3000".
+    /// "*" allows any origin.
+    #[serde(default = "default_cors_any")]
+    pub allowed_origins: Vec<String>,
+
+    /// Allowed methods, e.g. "GET", "POST". "*" allows any.
+    #[serde(default = "default_cors_any")]
+    pub allowed_methods: Vec<String>,
+
+    /// Allowed request headers, e.g. "authorization". "*" allows any.
+    #[serde(default = "default_cors_any")]
+    pub allowed_headers: Vec<String>,
+
+    /// Allow cookies and `Authorization` from browsers. Requires explicit
+    /// origins, methods, and headers (no "*").
+    #[serde(default)]
+    pub allow_credentials: bool,
+
+    /// How long browsers may cache preflight responses, e.g. "10m"
+    #[serde(default)]
+    pub max_age: Option<String>,
+}
+
+fn default_cors_any() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: default_cors_any(),
+            allowed_methods: default_cors_any(),
+            allowed_headers: default_cors_any(),
+            allow_credentials: false,
+            max_age: None,
+        }
+    }
+}
+
+/// Caps on generations running at once per model or provider. Requests
+/// over the cap wait in a first-come, first-served queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelConcurrencyConfig {
+    /// Generations in flight at once for models not in `limits`. 0 = unlimited.
+    #[serde(default)]
+    pub default_limit: usize,
+
+    /// Limits keyed by model ("ollama/llama3") or provider ("ollama"). A
+    /// provider limit is shared by all of that provider's models.
+    #[serde(default)]
+    pub limits: std::collections::HashMap<String, usize>,
+
+    /// How long a queued request waits for a slot before failing, e.g. "2m"
+    #[serde(default = "default_queue_timeout")]
+    pub queue_timeout: String,
+}
+
+fn default_queue_timeout() -> String {
+    "2m".to_string()
+}
+
+/// `[server.turns]`: agent turns the daemon runs at once, across HTTP,
+/// WebSocket, Telegram, and heartbeat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnsConfig {
+    /// Turns in flight at once. Turns also take the cross-process workspace
+    /// lock, so above 1 they overlap only while waiting on it.
+    #[serde(default = "default_max_concurrent_turns")]
+    pub max_concurrent: usize,
+
+    /// Lower limits for some channels ("http", "telegram", "heartbeat")
+    #[serde(default)]
+    pub channels: std::collections::HashMap<String, usize>,
+
+    /// How long a turn waits for a slot before failing as busy, e.g. "5m"
+    #[serde(default = "default_turn_queue_timeout")]
+    pub queue_timeout: String,
+}
+
+fn default_max_concurrent_turns() -> usize {
+    1
+}
+
+fn default_turn_queue_timeout() -> String {
+    "5m".to_string()
+}
+
+impl Default for TurnsConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: default_max_concurrent_turns(),
+            channels: std::collections::HashMap::new(),
+            queue_timeout: default_turn_queue_timeout(),
+        }
+    }
+}
+
+impl Default for ModelConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            default_limit: 0,
+            limits: std::collections::HashMap::new(),
+            queue_timeout: default_queue_timeout(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimitConfig {
     #[serde(default = "default_true")]
@@ -714,6 +1236,16 @@ pub struct RateLimitConfig {
     /// Burst allowance (extra requests above steady rate)
     #[serde(default = "default_burst")]
     pub burst: u32,
+
+    /// Maximum requests per minute per API key, for requests made with a
+    /// key or `auth_token`. Unset: same as `requests_per_minute`.
+    #[serde(default)]
+    pub key_requests_per_minute: Option<u32>,
+
+    /// Maximum chat requests (including streaming responses) in flight at
+    /// once per API key or IP. 0 = unlimited.
+    #[serde(default = "default_max_concurrent_streams")]
+    pub max_concurrent_streams: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -737,20 +1269,33 @@ pub struct TelegramConfig {
     pub api_token: String,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CronConfig {
     #[serde(default)]
     pub jobs: Vec<CronJob>,
+
+    /// Maximum cron jobs running at once; further due jobs wait (default: 2)
+    #[serde(default = "default_cron_max_concurrent")]
+    pub max_concurrent: usize,
+
+    /// Custom `{{name}}` variables for job prompts, alongside the built-in
+    /// `{{date}}`, `{{time}}`, `{{weekday}}`, `{{job}}`, and `{{last_run_output}}`
+    #[serde(default)]
+    pub vars: std::collections::HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CronJob {
     pub name: String,
 
-    /// Cron expression ("0 */6 * * *") or interval ("every 30m", "every 2h", "every 1d")
+    /// Cron expression ("0 */6 * * *"), interval ("every 30m", "every 2h", "every 1d"),
+    /// or natural language ("every weekday at 9am", "first monday of the month").
+    /// May be empty for jobs that only run on their `trigger`
+    #[serde(default)]
     pub schedule: String,
 
-    /// Prompt to send to a fresh agent session
+    /// Prompt to send to a fresh agent session. `{{name}}` placeholders
+    /// are filled in at run time (see `CronConfig::vars`)
     pub prompt: String,
 
     /// Optional Telegram channel/chat to route output to
@@ -763,12 +1308,308 @@ pub struct CronJob {
     /// Timeout for the job (e.g., "5m", "1h"). Default: 10m
     #[serde(default = "default_cron_timeout")]
     pub timeout: String,
+
+    /// What to do about runs missed while the daemon was down
+    #[serde(default)]
+    pub catch_up: CatchUpPolicy,
+
+    /// Random delay of up to this long (e.g., "2m") before each run, to
+    /// spread out jobs scheduled for the same time
+    #[serde(default)]
+    pub jitter: Option<String>,
+
+    /// Model for this job (default: agent.default_model)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// If non-empty, only these tools are available to the job, on top of
+    /// `[tools.interfaces.cron]`. Exact names or prefixes ending in `*`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+
+    /// If non-empty, only these skills are offered to the job
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<String>,
+
+    /// Memory namespace (agent ID for the search index and sessions).
+    /// Jobs sharing a namespace share memory. Default: "cron-<name>"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_namespace: Option<String>,
+
+    /// Retries after a failed or timed-out run
+    #[serde(default)]
+    pub retry: CronRetryPolicy,
+
+    /// Event that runs the job, in addition to its schedule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<JobTrigger>,
+}
+
+/// Daemon event that runs a cron job. The event is available to the prompt
+/// as `{{event}}`.
+///
+/// ```toml
+/// trigger = { type = "file", path = "~/Downloads", pattern = "*.pdf" }
+/// trigger = { type = "webhook", secret = "s3cret" }
+/// trigger = { type = "memory" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum JobTrigger {
+    /// A new file appears in the directory `path`, optionally matching the
+    /// file name glob `pattern`. The event is the file's path
+    File {
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+    },
+    /// `POST /api/cron/hooks/{name}`, which needs no API auth; instead the
+    /// request's `X-Webhook-Secret` header must match `secret`, so a job
+    /// without one can't be triggered. The event is the request body
+    Webhook {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret: Option<String>,
+    },
+    /// MEMORY.md or memory/*.md in the workspace changed
+    Memory,
+}
+
+impl JobTrigger {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JobTrigger::File { .. } => "file",
+            JobTrigger::Webhook { .. } => "webhook",
+            JobTrigger::Memory => "memory",
+        }
+    }
+
+    /// Whether a webhook request with the `X-Webhook-Secret` header value
+    /// `secret` may trigger the job.
+    pub fn accepts_webhook(&self, secret: Option<&str>) -> bool {
+        match self {
+            JobTrigger::Webhook {
+                secret: Some(expected),
+            } => secret.is_some_and(|s| {
+                crate::security::constant_time_eq(s.as_bytes(), expected.as_bytes())
+            }),
+            _ => false,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+impl std::str::FromStr for JobTrigger {
+    type Err = String;
+
+    /// Parse "file:<dir>", "file:<dir>/<glob>", "webhook:<secret>", or
+    /// "memory".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, arg) = match s.split_once(':') {
+            Some((kind, arg)) => (kind, Some(arg).filter(|a| !a.is_empty())),
+            None => (s, None),
+        };
+        match (kind, arg) {
+            ("file", Some(path)) => {
+                let path = std::path::Path::new(path);
+                let is_glob = |name: &str| name.contains(['*', '?', '[']);
+                match path.file_name().and_then(|n| n.to_str()) {
+                    Some(name) if is_glob(name) => Ok(JobTrigger::File {
+                        path: path
+                            .parent()
+                            .map(|p| p.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                        pattern: Some(name.to_string()),
+                    }),
+                    _ => Ok(JobTrigger::File {
+                        path: path.to_string_lossy().to_string(),
+                        pattern: None,
+                    }),
+                }
+            }
+            ("file", None) => Err("file trigger needs a directory: file:<dir>".to_string()),
+            ("webhook", Some(secret)) => Ok(JobTrigger::Webhook {
+                secret: Some(secret.to_string()),
+            }),
+            ("webhook", None) => {
+                Err("webhook trigger needs a secret: webhook:<secret>".to_string())
+            }
+            ("memory", None) => Ok(JobTrigger::Memory),
+            _ => Err(format!(
+                "unknown trigger '{}' (use file:<dir>, webhook:<secret>, or memory)",
+                s
+            )),
+        }
+    }
+}
+
+/// Handling of cron runs that were due while the daemon was not running,
+/// based on the job's last recorded run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CatchUpPolicy {
+    /// Missed runs are dropped
+    #[default]
+    Skip,
+    /// Run once on start if any run was missed
+    RunOnce,
+    /// Run every missed occurrence (capped), one after another
+    RunAll,
+}
+
+impl CatchUpPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CatchUpPolicy::Skip => "skip",
+            CatchUpPolicy::RunOnce => "run-once",
+            CatchUpPolicy::RunAll => "run-all",
+        }
+    }
+}
+
+/// Retry policy for failed cron runs. Each retry waits `backoff`, doubling
+/// after every attempt. When the final attempt fails, `on_cron_failure`
+/// hooks are fired.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CronRetryPolicy {
+    /// Total attempts per run, including the first (default: 1, no retries)
+    #[serde(default = "default_cron_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry (e.g., "1m")
+    #[serde(default = "default_cron_retry_backoff")]
+    pub backoff: String,
+}
+
+impl CronRetryPolicy {
+    /// Delay before retrying after `attempt` (1-based) failed, capped at 1h.
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let base = parse_duration(&self.backoff).unwrap_or(Duration::from_secs(60));
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        base.saturating_mul(factor).min(Duration::from_secs(3600))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpConfig {
     #[serde(default)]
     pub servers: Vec<McpServerConfig>,
+
+    /// Register servers' tools from the schema cache at startup and connect
+    /// in the background instead of waiting for each server (default: true)
+    #[serde(default = "default_true")]
+    pub lazy_connect: bool,
+
+    /// How long cached tool schemas are trusted before the server is
+    /// connected at startup again (e.g., "24h")
+    #[serde(default = "default_mcp_schema_cache_ttl")]
+    pub schema_cache_ttl: String,
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self {
+            servers: Vec::new(),
+            lazy_connect: true,
+            schema_cache_ttl: default_mcp_schema_cache_ttl(),
+        }
+    }
+}
+
+fn default_mcp_schema_cache_ttl() -> String {
+    "24h".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillsConfig {
+    /// How skills are chosen for the system prompt
+    #[serde(default)]
+    pub routing: SkillRoutingMode,
+
+    /// Minimum cosine similarity between a message and a skill's
+    /// description for semantic routing (default: 0.35)
+    #[serde(default = "default_skill_semantic_threshold")]
+    pub semantic_threshold: f32,
+
+    /// Maximum skills included per message by semantic routing (default: 5)
+    #[serde(default = "default_skill_semantic_top_k")]
+    pub semantic_top_k: usize,
+
+    /// Which skills must carry a trusted signature to be loaded; raises the
+    /// matching `trust` levels to `require`
+    #[serde(default)]
+    pub require_signature: SkillSignatureRequirement,
+
+    /// Signature requirements for managed and workspace skills
+    #[serde(default)]
+    pub trust: SkillTrustConfig,
+}
+
+impl SkillsConfig {
+    /// The trust policy skills are loaded and installed under: `trust` with
+    /// `require_signature` applied.
+    pub fn trust_policy(&self) -> SkillTrustConfig {
+        let mut trust = self.trust.clone();
+        match self.require_signature {
+            SkillSignatureRequirement::None => {}
+            SkillSignatureRequirement::Managed => trust.managed = SkillTrustLevel::Require,
+            SkillSignatureRequirement::All => {
+                trust.managed = SkillTrustLevel::Require;
+                trust.workspace = SkillTrustLevel::Require;
+            }
+        }
+        trust
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillSignatureRequirement {
+    /// Only the `[skills.trust]` levels apply
+    #[default]
+    None,
+    /// Managed skills must be signed by a trusted key
+    Managed,
+    /// Managed and workspace skills must be signed by a trusted key
+    /// (bundled skills ship in the binary and are always trusted)
+    All,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillRoutingMode {
+    /// Every eligible skill is listed
+    #[default]
+    All,
+    /// Skills are matched to each message by embedding similarity, after
+    /// useWhen/dontUseWhen rules (requires a memory embedding provider)
+    Semantic,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillTrustConfig {
+    /// Policy for skills installed under the managed skills directory
+    #[serde(default)]
+    pub managed: SkillTrustLevel,
+
+    /// Policy for skills in the workspace `skills/` directory
+    #[serde(default)]
+    pub workspace: SkillTrustLevel,
+
+    /// Base64 ed25519 public keys whose signatures are trusted, in addition
+    /// to the local `localgpt skills sign` key
+    #[serde(default)]
+    pub trusted_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillTrustLevel {
+    /// Signatures are not checked
+    #[default]
+    Off,
+    /// Unsigned or untrusted skills load with a warning
+    Warn,
+    /// Unsigned or untrusted skills are not loaded
+    Require,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -793,6 +1634,118 @@ pub struct McpServerConfig {
 
     /// URL for SSE transport
     pub url: Option<String>,
+
+    /// Whether the server may request LLM completions through
+    /// `sampling/createMessage` (stdio transport only)
+    #[serde(default)]
+    pub sampling: SamplingPolicy,
+
+    /// Model used for the server's sampling requests (default:
+    /// agent.default_model)
+    #[serde(default)]
+    pub sampling_model: Option<String>,
+
+    /// OAuth client settings for HTTP servers that require login
+    /// (`localgpt mcp login <name>`)
+    #[serde(default)]
+    pub oauth: Option<McpOAuthConfig>,
+
+    /// Tools to register, as glob patterns on the server's tool names
+    /// (default: all discovered tools)
+    #[serde(default)]
+    pub tools: Vec<String>,
+
+    /// Constraints on tool arguments, checked before each call
+    #[serde(default)]
+    pub arg_filters: Vec<McpArgFilter>,
+}
+
+/// Restricts one argument of an MCP server's tools.
+///
+/// ```toml
+/// [[mcp.servers.arg_filters]]
+/// tool = "*"
+/// arg = "path"
+/// path_prefixes = ["~/notes"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpArgFilter {
+    /// Tool name glob pattern the filter applies to (default: all tools)
+    #[serde(default = "default_mcp_arg_filter_tool")]
+    pub tool: String,
+
+    /// Argument name
+    pub arg: String,
+
+    /// The argument must be a path inside one of these directories
+    #[serde(default)]
+    pub path_prefixes: Vec<String>,
+
+    /// The argument must be one of these values
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+fn default_mcp_arg_filter_tool() -> String {
+    "*".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpOAuthConfig {
+    /// Pre-registered client ID; without one, LocalGPT registers itself
+    /// with the authorization server (dynamic client registration)
+    #[serde(default)]
+    pub client_id: Option<String>,
+
+    #[serde(default)]
+    pub client_secret: Option<String>,
+
+    /// Scopes to request
+    #[serde(default)]
+    pub scopes: Vec<String>,
+
+    /// Local port for the login redirect (default: 8976)
+    #[serde(default = "default_mcp_oauth_redirect_port")]
+    pub redirect_port: u16,
+}
+
+impl Default for McpOAuthConfig {
+    fn default() -> Self {
+        Self {
+            client_id: None,
+            client_secret: None,
+            scopes: Vec::new(),
+            redirect_port: default_mcp_oauth_redirect_port(),
+        }
+    }
+}
+
+fn default_mcp_oauth_redirect_port() -> u16 {
+    8976
+}
+
+/// Policy for an MCP server's sampling requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SamplingPolicy {
+    /// Sampling is not offered to the server
+    #[default]
+    Deny,
+    /// Each request is confirmed interactively; denied where nobody can
+    /// answer (daemon, HTTP)
+    Ask,
+    /// Requests are served without confirmation
+    Allow,
+}
+
+impl SamplingPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SamplingPolicy::Deny => "deny",
+            SamplingPolicy::Ask => "ask",
+            SamplingPolicy::Allow => "allow",
+        }
+    }
 }
 
 fn default_mcp_transport() -> String {
@@ -822,6 +1775,9 @@ fn default_web_fetch_max_bytes() -> usize {
 fn default_tool_output_max_chars() -> usize {
     50000 // 50k characters max for tool output by default
 }
+fn default_tool_output_max_tokens() -> usize {
+    8000 // ~32k characters per page
+}
 fn default_openai_base_url() -> String {
     "https://api.openai.com/v1".to_string()
 }
@@ -861,6 +1817,18 @@ fn default_glm_base_url() -> String {
 fn default_gemini_base_url() -> String {
     "https://generativelanguage.googleapis.com".to_string()
 }
+fn default_audit_max_size_mb() -> u64 {
+    10
+}
+
+fn default_audit_max_age() -> String {
+    "90d".to_string()
+}
+
+fn default_audit_keep() -> usize {
+    12
+}
+
 fn default_true() -> bool {
     true
 }
@@ -904,7 +1872,16 @@ fn default_session_max_messages() -> usize {
 fn default_port() -> u16 {
     31327
 }
-fn default_cron_timeout() -> String {
+fn default_cron_max_concurrent() -> usize {
+    2
+}
+fn default_cron_max_attempts() -> u32 {
+    1
+}
+fn default_cron_retry_backoff() -> String {
+    "1m".to_string()
+}
+pub(crate) fn default_cron_timeout() -> String {
     "10m".to_string()
 }
 fn default_requests_per_minute() -> u32 {
@@ -913,6 +1890,9 @@ fn default_requests_per_minute() -> u32 {
 fn default_burst() -> u32 {
     10
 }
+fn default_max_concurrent_streams() -> u32 {
+    4
+}
 fn default_bind() -> String {
     "127.0.0.1".to_string()
 }
@@ -937,12 +1917,24 @@ fn default_sandbox_max_file_size() -> u64 {
 fn default_sandbox_max_processes() -> u32 {
     64
 }
-fn default_sandbox_network_policy() -> String {
-    "deny".to_string()
-}
 fn default_cache_ttl() -> u64 {
     900 // 15 minutes
 }
+fn default_fetch_content_max_pages() -> usize {
+    3
+}
+fn default_fetch_content_concurrency() -> usize {
+    3
+}
+fn default_fetch_content_page_tokens() -> usize {
+    1000
+}
+fn default_fetch_content_timeout() -> u64 {
+    10
+}
+fn default_cache_max_entries() -> usize {
+    1000
+}
 fn default_max_results() -> u8 {
     5
 }
@@ -952,6 +1944,12 @@ fn default_basic() -> String {
 fn default_sonar() -> String {
     "sonar".to_string()
 }
+fn default_skill_semantic_threshold() -> f32 {
+    0.35
+}
+fn default_skill_semantic_top_k() -> usize {
+    5
+}
 
 impl Default for AgentConfig {
     fn default() -> Self {
@@ -977,10 +1975,16 @@ impl Default for ToolsConfig {
             web_fetch_max_bytes: default_web_fetch_max_bytes(),
             require_approval: Vec::new(),
             tool_output_max_chars: default_tool_output_max_chars(),
+            tool_output_max_tokens: default_tool_output_max_tokens(),
+            output_limits: std::collections::HashMap::new(),
             log_injection_warnings: default_true(),
             use_content_delimiters: default_true(),
+            audit_log: default_true(),
             web_search: None,
             filters: std::collections::HashMap::new(),
+            interfaces: std::collections::HashMap::new(),
+            commands: Vec::new(),
+            plugins: Vec::new(),
         }
     }
 }
@@ -1021,9 +2025,17 @@ impl Default for ServerConfig {
             enabled: default_true(),
             port: default_port(),
             bind: default_bind(),
+            tcp: default_true(),
+            unix_socket: None,
+            unix_socket_trust: false,
             auth_token: None,
             rate_limit: RateLimitConfig::default(),
+            cors: CorsConfig::default(),
+            model_concurrency: ModelConcurrencyConfig::default(),
+            turns: TurnsConfig::default(),
+            image_url_domains: Vec::new(),
             max_request_body: default_max_request_body(),
+            shutdown_timeout: default_shutdown_timeout(),
         }
     }
 }
@@ -1034,6 +2046,39 @@ impl Default for RateLimitConfig {
             enabled: default_true(),
             requests_per_minute: default_requests_per_minute(),
             burst: default_burst(),
+            key_requests_per_minute: None,
+            max_concurrent_streams: default_max_concurrent_streams(),
+        }
+    }
+}
+
+impl Default for CronConfig {
+    fn default() -> Self {
+        Self {
+            jobs: Vec::new(),
+            max_concurrent: default_cron_max_concurrent(),
+            vars: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl Default for CronRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_cron_max_attempts(),
+            backoff: default_cron_retry_backoff(),
+        }
+    }
+}
+
+impl Default for SkillsConfig {
+    fn default() -> Self {
+        Self {
+            routing: SkillRoutingMode::default(),
+            semantic_threshold: default_skill_semantic_threshold(),
+            semantic_top_k: default_skill_semantic_top_k(),
+            require_signature: SkillSignatureRequirement::default(),
+            trust: SkillTrustConfig::default(),
         }
     }
 }
@@ -1068,8 +2113,9 @@ impl Config {
         let mut config: Config = toml::from_str(&content)?;
         config.paths = paths;
 
-        // Expand environment variables in API keys
-        config.expand_env_vars();
+        // Resolve encrypted, keyring, and environment variable secrets
+        config.resolve_secrets();
+        crate::security::set_redaction_enabled(config.security.redact_secrets);
 
         // Apply deprecated memory.workspace override if set and LOCALGPT_WORKSPACE not set
         if config.memory.workspace != default_workspace()
@@ -1105,7 +2151,8 @@ impl Config {
         let content = fs::read_to_string(&path)?;
         let mut config: Config = toml::from_str(&content)?;
         config.paths = paths;
-        config.expand_env_vars();
+        config.resolve_secrets();
+        crate::security::set_redaction_enabled(config.security.redact_secrets);
         Ok(config)
     }
 
@@ -1143,128 +2190,125 @@ impl Config {
         Ok(paths.config_file())
     }
 
-    fn expand_env_vars(&mut self) {
+    /// Resolve `enc:`, `keyring:`, and `${VAR}` references in secrets
+    fn resolve_secrets(&mut self) {
+        let resolver = SecretResolver::new(&self.paths.data_dir);
+        // Resolved secrets are redacted from logs and tool output
+        let resolve_secret = |s: &str| {
+            let secret = resolver.resolve_or_keep(s);
+            crate::security::register_secret(&secret);
+            secret
+        };
         if let Some(ref mut openai) = self.providers.openai {
-            openai.api_key = expand_env(&openai.api_key);
+            openai.api_key = resolve_secret(&openai.api_key);
         }
         if let Some(ref mut xai) = self.providers.xai {
-            xai.api_key = expand_env(&xai.api_key);
+            xai.api_key = resolve_secret(&xai.api_key);
         }
         if let Some(ref mut anthropic) = self.providers.anthropic {
-            anthropic.api_key = expand_env(&anthropic.api_key);
+            anthropic.api_key = resolve_secret(&anthropic.api_key);
         }
         if let Some(ref mut telegram) = self.telegram {
-            telegram.api_token = expand_env(&telegram.api_token);
+            telegram.api_token = resolve_secret(&telegram.api_token);
         }
         if let Some(ref mut ws) = self.tools.web_search
             && let Some(ref mut brave) = ws.brave
         {
-            brave.api_key = expand_env(&brave.api_key);
+            brave.api_key = resolve_secret(&brave.api_key);
         }
         if let Some(ref mut ws) = self.tools.web_search
             && let Some(ref mut tavily) = ws.tavily
         {
-            tavily.api_key = expand_env(&tavily.api_key);
+            tavily.api_key = resolve_secret(&tavily.api_key);
         }
         if let Some(ref mut ws) = self.tools.web_search
             && let Some(ref mut perplexity) = ws.perplexity
         {
-            perplexity.api_key = expand_env(&perplexity.api_key);
+            perplexity.api_key = resolve_secret(&perplexity.api_key);
         }
         if let Some(ref mut anthropic_oauth) = self.providers.anthropic_oauth {
-            anthropic_oauth.access_token = expand_env(&anthropic_oauth.access_token);
+            anthropic_oauth.access_token = resolve_secret(&anthropic_oauth.access_token);
             if let Some(ref mut refresh) = anthropic_oauth.refresh_token {
-                *refresh = expand_env(refresh);
+                *refresh = resolve_secret(refresh);
             }
             if let Some(ref mut client_id) = anthropic_oauth.client_id {
-                *client_id = expand_env(client_id);
+                *client_id = resolve_secret(client_id);
             }
             if let Some(ref mut client_secret) = anthropic_oauth.client_secret {
-                *client_secret = expand_env(client_secret);
+                *client_secret = resolve_secret(client_secret);
             }
         }
         if let Some(ref mut gemini_oauth) = self.providers.gemini_oauth {
-            gemini_oauth.access_token = expand_env(&gemini_oauth.access_token);
+            gemini_oauth.access_token = resolve_secret(&gemini_oauth.access_token);
             if let Some(ref mut refresh) = gemini_oauth.refresh_token {
-                *refresh = expand_env(refresh);
+                *refresh = resolve_secret(refresh);
             }
             if let Some(ref mut client_id) = gemini_oauth.client_id {
-                *client_id = expand_env(client_id);
+                *client_id = resolve_secret(client_id);
             }
             if let Some(ref mut client_secret) = gemini_oauth.client_secret {
-                *client_secret = expand_env(client_secret);
+                *client_secret = resolve_secret(client_secret);
+            }
+        }
+        if let Some(ref mut openai_oauth) = self.providers.openai_oauth {
+            openai_oauth.access_token = resolve_secret(&openai_oauth.access_token);
+            if let Some(ref mut refresh) = openai_oauth.refresh_token {
+                *refresh = resolve_secret(refresh);
+            }
+            if let Some(ref mut client_id) = openai_oauth.client_id {
+                *client_id = resolve_secret(client_id);
+            }
+            if let Some(ref mut client_secret) = openai_oauth.client_secret {
+                *client_secret = resolve_secret(client_secret);
             }
         }
         if let Some(ref mut github) = self.providers.github_copilot {
-            github.access_token = expand_env(&github.access_token);
+            github.access_token = resolve_secret(&github.access_token);
             if let Some(ref mut refresh) = github.refresh_token {
-                *refresh = expand_env(refresh);
+                *refresh = resolve_secret(refresh);
             }
             if let Some(ref mut client_id) = github.client_id {
-                *client_id = expand_env(client_id);
+                *client_id = resolve_secret(client_id);
             }
             if let Some(ref mut client_secret) = github.client_secret {
-                *client_secret = expand_env(client_secret);
+                *client_secret = resolve_secret(client_secret);
             }
         }
         if let Some(ref mut openai_compat) = self.providers.openai_compatible {
-            openai_compat.api_key = expand_env(&openai_compat.api_key);
+            openai_compat.api_key = resolve_secret(&openai_compat.api_key);
             openai_compat.base_url = expand_env(&openai_compat.base_url);
         }
         if let Some(ref mut auth_token) = self.server.auth_token {
-            *auth_token = expand_env(auth_token);
+            *auth_token = resolve_secret(auth_token);
         }
-    }
-
-    pub fn get_value(&self, key: &str) -> Result<String> {
-        let parts: Vec<&str> = key.split('.').collect();
-
-        match parts.as_slice() {
-            ["agent", "default_model"] => Ok(self.agent.default_model.clone()),
-            ["agent", "context_window"] => Ok(self.agent.context_window.to_string()),
-            ["agent", "reserve_tokens"] => Ok(self.agent.reserve_tokens.to_string()),
-            ["heartbeat", "enabled"] => Ok(self.heartbeat.enabled.to_string()),
-            ["heartbeat", "interval"] => Ok(self.heartbeat.interval.clone()),
-            ["server", "enabled"] => Ok(self.server.enabled.to_string()),
-            ["server", "port"] => Ok(self.server.port.to_string()),
-            ["server", "bind"] => Ok(self.server.bind.clone()),
-            ["memory", "workspace"] => Ok(self.memory.workspace.clone()),
-            ["logging", "level"] => Ok(self.logging.level.clone()),
-            _ => anyhow::bail!("Unknown config key: {}", key),
-        }
-    }
-
-    pub fn set_value(&mut self, key: &str, value: &str) -> Result<()> {
-        let parts: Vec<&str> = key.split('.').collect();
-
-        match parts.as_slice() {
-            ["agent", "default_model"] => self.agent.default_model = value.to_string(),
-            ["agent", "context_window"] => self.agent.context_window = value.parse()?,
-            ["agent", "reserve_tokens"] => self.agent.reserve_tokens = value.parse()?,
-            ["heartbeat", "enabled"] => self.heartbeat.enabled = value.parse()?,
-            ["heartbeat", "interval"] => self.heartbeat.interval = value.to_string(),
-            ["server", "enabled"] => self.server.enabled = value.parse()?,
-            ["server", "port"] => self.server.port = value.parse()?,
-            ["server", "bind"] => self.server.bind = value.to_string(),
-            ["memory", "workspace"] => self.memory.workspace = value.to_string(),
-            ["logging", "level"] => self.logging.level = value.to_string(),
-            _ => anyhow::bail!("Unknown config key: {}", key),
+        for webhook in &mut self.webhooks {
+            webhook.url = expand_env(&webhook.url);
+            if let Some(ref mut secret) = webhook.secret {
+                *secret = resolve_secret(secret);
+            }
         }
-
-        Ok(())
     }
 
     /// Get workspace path from resolved Paths.
     ///
     /// Resolution is handled by `Paths::resolve()`:
     /// 1. LOCALGPT_WORKSPACE env var (absolute path override)
-    /// 2. LOCALGPT_PROFILE env var (creates workspace-{profile} under data_dir)
+    /// 2. LOCALGPT_PROFILE env var (workspace under the profile's data_dir, e.g. localgpt-work/workspace)
     /// 3. memory.workspace from config file (deprecated compat)
     /// 4. Default: data_dir/workspace
     pub fn workspace_path(&self) -> PathBuf {
         self.paths.workspace.clone()
     }
 
+    /// The same settings for an HTTP API user's agents, with the user's own
+    /// workspace (`Paths::user_workspace`) so their memory and workspace
+    /// files stay apart from other users'.
+    pub fn for_user(&self, user: &str) -> Config {
+        let mut config = self.clone();
+        config.paths.workspace = self.paths.user_workspace(user);
+        config
+    }
+
     /// Update OAuth tokens for a provider and save config
     pub fn update_oauth_token(
         &mut self,
@@ -1273,7 +2317,7 @@ impl Config {
         refresh_token: Option<String>,
         expires_at: Option<u64>,
     ) -> Result<()> {
-        let mut changed = false;
+        let mut section = None;
 
         match provider {
             "gemini" => {
@@ -1282,12 +2326,12 @@ impl Config {
                         || oauth.refresh_token != refresh_token
                         || oauth.expires_at != expires_at)
                 {
-                    oauth.access_token = access_token;
+                    oauth.access_token = access_token.clone();
                     if refresh_token.is_some() {
-                        oauth.refresh_token = refresh_token;
+                        oauth.refresh_token = refresh_token.clone();
                     }
                     oauth.expires_at = expires_at;
-                    changed = true;
+                    section = Some("gemini_oauth");
                 }
             }
             "anthropic" => {
@@ -1296,12 +2340,12 @@ impl Config {
                         || oauth.refresh_token != refresh_token
                         || oauth.expires_at != expires_at)
                 {
-                    oauth.access_token = access_token;
+                    oauth.access_token = access_token.clone();
                     if refresh_token.is_some() {
-                        oauth.refresh_token = refresh_token;
+                        oauth.refresh_token = refresh_token.clone();
                     }
                     oauth.expires_at = expires_at;
-                    changed = true;
+                    section = Some("anthropic_oauth");
                 }
             }
             "openai" => {
@@ -1310,12 +2354,12 @@ impl Config {
                         || oauth.refresh_token != refresh_token
                         || oauth.expires_at != expires_at)
                 {
-                    oauth.access_token = access_token;
+                    oauth.access_token = access_token.clone();
                     if refresh_token.is_some() {
-                        oauth.refresh_token = refresh_token;
+                        oauth.refresh_token = refresh_token.clone();
                     }
                     oauth.expires_at = expires_at;
-                    changed = true;
+                    section = Some("openai_oauth");
                 }
             }
             "github" => {
@@ -1324,24 +2368,86 @@ impl Config {
                         || oauth.refresh_token != refresh_token
                         || oauth.expires_at != expires_at)
                 {
-                    oauth.access_token = access_token;
+                    oauth.access_token = access_token.clone();
                     if refresh_token.is_some() {
-                        oauth.refresh_token = refresh_token;
+                        oauth.refresh_token = refresh_token.clone();
                     }
                     oauth.expires_at = expires_at;
-                    changed = true;
+                    section = Some("github_copilot");
                 }
             }
             _ => {}
         }
 
-        if changed {
-            self.save()?;
+        if let Some(section) = section {
+            self.persist_oauth_token(section, &access_token, refresh_token.as_deref(), expires_at)?;
             debug!("Persisted refreshed OAuth tokens for {}", provider);
         }
 
         Ok(())
     }
+
+    /// Write the OAuth token fields of `[providers.<section>]` to the config
+    /// file without rewriting the rest of it, so secrets resolved from the
+    /// environment or the keyring stay out of the file. A token the file
+    /// names as `keyring:<name>` is updated in the keyring instead, and an
+    /// `enc:` token is replaced with the new one, encrypted. A `${VAR}`
+    /// reference is kept: the refreshed token is only used by this process.
+    fn persist_oauth_token(
+        &self,
+        section: &str,
+        access_token: &str,
+        refresh_token: Option<&str>,
+        expires_at: Option<u64>,
+    ) -> Result<()> {
+        let path = self.paths.config_file();
+        if !path.exists() {
+            return self.save();
+        }
+
+        let raw: toml::Value = toml::from_str(&fs::read_to_string(&path)?)?;
+        let write_secret = |field: &str, value: &str| -> Result<()> {
+            let current = raw
+                .get("providers")
+                .and_then(|p| p.get(section))
+                .and_then(|s| s.get(field))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let key = format!("providers.{}.{}", section, field);
+            match SecretSource::of(current) {
+                SecretSource::Keyring => crate::security::set_keyring_secret(
+                    crate::security::keyring_reference(current).unwrap_or_default(),
+                    value,
+                ),
+                SecretSource::Encrypted => {
+                    let encrypted =
+                        crate::security::encrypt_config_secret(&self.paths.data_dir, value)?;
+                    set_config_string(&path, &key, &encrypted)?;
+                    Ok(())
+                }
+                SecretSource::Env => {
+                    debug!("Keeping {} as an environment variable reference", key);
+                    Ok(())
+                }
+                SecretSource::Plaintext => {
+                    set_config_string(&path, &key, value)?;
+                    Ok(())
+                }
+            }
+        };
+        write_secret("access_token", access_token)?;
+        if let Some(refresh_token) = refresh_token {
+            write_secret("refresh_token", refresh_token)?;
+        }
+        if let Some(expires_at) = expires_at {
+            set_config_value(
+                &path,
+                &format!("providers.{}.expires_at", section),
+                &expires_at.to_string(),
+            )?;
+        }
+        Ok(())
+    }
 }
 
 fn expand_env(s: &str) -> String {
@@ -1423,7 +2529,7 @@ interval = "30m"
 # Default: XDG data dir (~/.local/share/localgpt/workspace)
 # Override with environment variables:
 #   LOCALGPT_WORKSPACE=/path/to/workspace  - absolute path override
-#   LOCALGPT_PROFILE=work                  - uses data_dir/workspace-work
+#   LOCALGPT_PROFILE=work                  - uses ~/.local/share/localgpt-work/workspace
 # workspace = "~/.local/share/localgpt/workspace"
 
 # Session memory settings (for /new command)
@@ -1436,6 +2542,8 @@ port = 31327
 bind = "127.0.0.1"
 # Optional bearer token for API authentication
 # auth_token = "${LOCALGPT_AUTH_TOKEN}"
+# How long the daemon waits for in-flight turns and cron jobs when stopping
+# shutdown_timeout = "30s"
 
 [logging]
 level = "info"
@@ -1452,15 +2560,42 @@ level = "info"
 # write = ["/tmp/builds"]               # additional writable paths
 #
 # [sandbox.network]
-# policy = "deny"                       # deny | proxy
+# policy = "deny"                       # deny | allow | allowlist
+# allow = ["api.github.com", "*.pypi.org"]  # hosts reachable under allowlist
+
+# Tool output paging (optional)
+# Outputs over the cap return a first page plus a read_more cursor.
+# [tools]
+# tool_output_max_tokens = 8000   # 0 = unlimited
+#
+# [tools.output_limits]
+# bash = 4000
+# read_file = 12000
 
 # Web search (optional)
 # [tools.web_search]
-# provider = "searxng"            # searxng | brave | tavily | perplexity | none
+# provider = "searxng"            # searxng | brave | tavily | perplexity | serper | duckduckgo | kagi | none
 # cache_enabled = true
 # cache_ttl = 900                 # seconds (default: 15 min)
+# persistent_cache = false        # keep cached results across restarts (SQLite)
+# cache_max_entries = 1000        # persistent cache size limit
 # max_results = 5                 # 1-10
 # prefer_native = true            # prefer native provider search when available
+# fallback = ["searxng", "duckduckgo"]  # tried in order on errors, rate limits, or exhausted quota
+# prefer_free = true              # try free providers first for low-stakes queries
+# allowed_domains = []            # only these domains (and subdomains); empty = any
+# blocked_domains = ["pinterest.com"]
+# safe_search = false             # filter explicit results where the provider supports it
+#
+# [tools.web_search.fetch_content]  # limits for web_search fetch_content = true
+# max_pages = 3
+# concurrency = 3
+# page_tokens = 1000
+# timeout_secs = 10
+#
+# [tools.web_search.quotas.brave]
+# daily_queries = 60
+# daily_spend_usd = 0.50
 #
 # [tools.web_search.searxng]
 # base_url = "http://localhost:8080"
@@ -1478,6 +2613,56 @@ level = "info"
 # [tools.web_search.perplexity]
 # api_key = "${PERPLEXITY_API_KEY}"
 # model = "sonar"
+#
+# [tools.web_search.serper]
+# api_key = "${SERPER_API_KEY}"
+# country = "us"
+# language = "en"
+#
+# [tools.web_search.duckduckgo]    # no API key needed
+# region = "us-en"
+#
+# [tools.web_search.kagi]
+# api_key = "${KAGI_API_KEY}"
+
+# Per-interface tool restrictions (optional)
+# Interfaces: cli, desktop, http, telegram, bridge, cron, heartbeat, ...
+# Entries are tool names or prefixes ending in "*".
+# [tools.interfaces.telegram]
+# deny = ["bash", "write_file", "edit_file"]
+#
+# [tools.interfaces.http]
+# allow = ["memory_*", "web_fetch", "web_search"]
+
+# User-defined command tools (optional)
+# {{arg}} placeholders are replaced with shell-quoted argument values.
+# [[tools.commands]]
+# name = "gh_issue"
+# description = "Show a GitHub issue"
+# command = "gh issue view {{number}} --repo {{repo}}"
+# sandbox = "inherit"             # inherit | none
+# timeout_ms = 30000
+# parameters = { type = "object", properties = { number = { type = "integer" }, repo = { type = "string" } }, required = ["number", "repo"] }
+
+# WASM plugin tools (optional, requires the `wasm-plugins` build feature)
+# [[tools.plugins]]
+# path = "~/.local/share/localgpt/plugins/weather.wasm"
+# capabilities = ["http"]        # http | fs (fs is scoped to the workspace)
+
+# Skill selection for the system prompt
+# [skills]
+# routing = "all"              # "all" or "semantic" (embedding match per message;
+#                              # needs memory.embedding_provider)
+# semantic_threshold = 0.35    # Minimum cosine similarity to include a skill
+# semantic_top_k = 5           # Maximum skills included per message
+# require_signature = "none"   # "managed" or "all": skills without a trusted
+#                              # signature are blocked (see [skills.trust])
+
+# Skill signature policy: "off", "warn", or "require" (sign with `localgpt skills sign`)
+# [skills.trust]
+# managed = "require"          # Skills installed with `localgpt skills install`
+# workspace = "warn"           # Skills in <workspace>/skills
+# trusted_keys = []            # Extra base64 ed25519 public keys to trust
 
 # Telegram bot (optional)
 # [telegram]
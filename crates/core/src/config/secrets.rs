@@ -0,0 +1,133 @@
+//! Resolving secret references in config values.
+//!
+//! A secret setting (API key, bot token, auth token, webhook secret) can
+//! hold the secret itself or a reference to it:
+//!
+//! - `enc:<base64>`: encrypted with the device key (`localgpt secret set`)
+//! - `keyring:<name>`: an OS keyring entry (`localgpt keyring set`)
+//! - `${VAR}` or `$VAR`: an environment variable
+//!
+//! [`SecretResolver`] turns a reference into the value when the config is
+//! loaded.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use super::expand_env;
+use crate::security::{
+    decrypt_config_secret, get_keyring_secret, is_encrypted_secret, keyring_reference,
+};
+
+/// Where a secret setting's value comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretSource {
+    /// `enc:` value encrypted with the device key
+    Encrypted,
+    /// `keyring:` OS keyring entry
+    Keyring,
+    /// `${VAR}` environment variable
+    Env,
+    /// The secret itself, in plaintext
+    Plaintext,
+}
+
+impl SecretSource {
+    pub fn of(value: &str) -> Self {
+        if is_encrypted_secret(value) {
+            SecretSource::Encrypted
+        } else if keyring_reference(value).is_some() {
+            SecretSource::Keyring
+        } else if value.starts_with('$') {
+            SecretSource::Env
+        } else {
+            SecretSource::Plaintext
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SecretSource::Encrypted => "encrypted",
+            SecretSource::Keyring => "keyring",
+            SecretSource::Env => "env",
+            SecretSource::Plaintext => "plaintext",
+        }
+    }
+}
+
+/// Resolves `enc:`, `keyring:`, and `${VAR}` config values
+pub struct SecretResolver {
+    /// Holds the device key that decrypts `enc:` values
+    data_dir: PathBuf,
+}
+
+impl SecretResolver {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+        }
+    }
+
+    /// The secret `value` stands for. Plain values come back unchanged, and
+    /// so do `${VAR}` references to unset variables.
+    pub fn resolve(&self, value: &str) -> Result<String> {
+        match SecretSource::of(value) {
+            SecretSource::Encrypted => decrypt_config_secret(&self.data_dir, value),
+            SecretSource::Keyring => get_keyring_secret(keyring_reference(value).unwrap_or("")),
+            SecretSource::Env | SecretSource::Plaintext => Ok(expand_env(value)),
+        }
+    }
+
+    /// Like [`resolve`](Self::resolve), but a reference that can't be
+    /// resolved is logged and left as is, like an unset environment variable.
+    pub fn resolve_or_keep(&self, value: &str) -> String {
+        match self.resolve(value) {
+            Ok(secret) => secret,
+            Err(e) => {
+                tracing::warn!("Could not resolve config secret: {:#}", e);
+                value.to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{encrypt_config_secret, ensure_device_key};
+
+    #[test]
+    fn secret_source_from_prefix() {
+        assert_eq!(SecretSource::of("enc:AAAA"), SecretSource::Encrypted);
+        assert_eq!(SecretSource::of("keyring:openai"), SecretSource::Keyring);
+        assert_eq!(SecretSource::of("${OPENAI_API_KEY}"), SecretSource::Env);
+        assert_eq!(SecretSource::of("sk-abc"), SecretSource::Plaintext);
+    }
+
+    #[test]
+    fn resolves_encrypted_and_plain_values() {
+        let tmp = tempfile::tempdir().unwrap();
+        ensure_device_key(tmp.path()).unwrap();
+        let resolver = SecretResolver::new(tmp.path());
+
+        let value = encrypt_config_secret(tmp.path(), "bot-token").unwrap();
+        assert_eq!(resolver.resolve(&value).unwrap(), "bot-token");
+        assert_eq!(resolver.resolve("sk-abc").unwrap(), "sk-abc");
+        assert_eq!(
+            resolver.resolve("${LOCALGPT_TEST_UNSET_SECRET}").unwrap(),
+            "${LOCALGPT_TEST_UNSET_SECRET}"
+        );
+    }
+
+    #[test]
+    fn unresolvable_values_are_kept() {
+        let tmp = tempfile::tempdir().unwrap();
+        let resolver = SecretResolver::new(tmp.path());
+
+        // No device key to decrypt with
+        assert!(resolver.resolve("enc:AAAAAAAAAAAAAAAAAAAAAAAA").is_err());
+        assert_eq!(
+            resolver.resolve_or_keep("enc:AAAAAAAAAAAAAAAAAAAAAAAA"),
+            "enc:AAAAAAAAAAAAAAAAAAAAAAAA"
+        );
+    }
+}
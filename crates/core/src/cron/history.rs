@@ -0,0 +1,205 @@
+//! Cron run history.
+//!
+//! Every cron job run is appended to `localgpt.cron.jsonl` in the state
+//! directory, one JSON object per line, so `localgpt cron history` and
+//! `GET /api/cron/history` can show what scheduled jobs did.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const CRON_HISTORY_FILENAME: &str = "localgpt.cron.jsonl";
+
+/// Maximum characters of job output kept in a history entry.
+pub const MAX_HISTORY_OUTPUT_CHARS: usize = 2000;
+
+/// Maximum characters of a trigger event kept in a history entry.
+pub const MAX_HISTORY_EVENT_CHARS: usize = 200;
+
+/// How a cron run ended.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CronRunStatus {
+    Ok,
+    Error,
+    TimedOut,
+}
+
+impl CronRunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CronRunStatus::Ok => "ok",
+            CronRunStatus::Error => "error",
+            CronRunStatus::TimedOut => "timed_out",
+        }
+    }
+}
+
+/// One cron job run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronRunEntry {
+    pub job: String,
+    /// ISO 8601 start and end timestamps
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u64,
+    pub status: CronRunStatus,
+    /// Agent response, truncated to [`MAX_HISTORY_OUTPUT_CHARS`]
+    #[serde(default)]
+    pub output: String,
+    /// Error message for failed runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Provider-reported token usage for the run
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    /// Attempts made, including retries; `status` is from the last one
+    #[serde(default = "default_attempts")]
+    pub attempts: u32,
+    /// Trigger event that started the run, truncated to
+    /// [`MAX_HISTORY_EVENT_CHARS`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+}
+
+fn default_attempts() -> u32 {
+    1
+}
+
+impl CronRunEntry {
+    /// Truncate `output` to [`MAX_HISTORY_OUTPUT_CHARS`].
+    pub fn truncate_output(output: &str) -> String {
+        truncate(output, MAX_HISTORY_OUTPUT_CHARS)
+    }
+
+    /// Truncate a trigger event to [`MAX_HISTORY_EVENT_CHARS`].
+    pub fn truncate_event(event: &str) -> String {
+        truncate(event, MAX_HISTORY_EVENT_CHARS)
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let prefix: String = text.chars().take(max_chars).collect();
+    format!("{}...", prefix)
+}
+
+/// Append a run to the cron history.
+pub fn append_cron_run(state_dir: &Path, entry: &CronRunEntry) -> Result<()> {
+    let path = cron_history_file_path(state_dir);
+    let json = serde_json::to_string(entry).context("Failed to serialize cron run")?;
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context("Failed to open cron history")?;
+    writeln!(file, "{}", json).context("Failed to write cron run")?;
+    Ok(())
+}
+
+/// Read runs, oldest first, optionally only for `job`, keeping the most
+/// recent `limit`. Corrupted lines are skipped.
+pub fn read_cron_history(
+    state_dir: &Path,
+    job: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<CronRunEntry>> {
+    let path = cron_history_file_path(state_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path).context("Failed to read cron history")?;
+    let mut entries: Vec<CronRunEntry> = content
+        .lines()
+        .filter(|l| !l.is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .filter(|e: &CronRunEntry| job.is_none_or(|j| j == e.job))
+        .collect();
+
+    if let Some(limit) = limit
+        && entries.len() > limit
+    {
+        entries.drain(..entries.len() - limit);
+    }
+
+    Ok(entries)
+}
+
+/// Start time of the most recent recorded run of each job.
+pub fn last_run_times(state_dir: &Path) -> Result<HashMap<String, DateTime<Utc>>> {
+    let mut last = HashMap::new();
+    for entry in read_cron_history(state_dir, None, None)? {
+        if let Ok(started) = DateTime::parse_from_rfc3339(&entry.started_at) {
+            let started = started.with_timezone(&Utc);
+            let slot = last.entry(entry.job).or_insert(started);
+            if started > *slot {
+                *slot = started;
+            }
+        }
+    }
+    Ok(last)
+}
+
+/// Get the full path to the cron history file.
+pub fn cron_history_file_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(CRON_HISTORY_FILENAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(job: &str, status: CronRunStatus) -> CronRunEntry {
+        CronRunEntry {
+            job: job.to_string(),
+            started_at: "2026-01-01T09:00:00Z".to_string(),
+            finished_at: "2026-01-01T09:00:05Z".to_string(),
+            duration_ms: 5000,
+            status,
+            output: "done".to_string(),
+            error: None,
+            input_tokens: 100,
+            output_tokens: 20,
+            attempts: 1,
+            event: None,
+        }
+    }
+
+    #[test]
+    fn append_and_filter_history() {
+        let tmp = tempfile::tempdir().unwrap();
+        append_cron_run(tmp.path(), &entry("inbox", CronRunStatus::Ok)).unwrap();
+        append_cron_run(tmp.path(), &entry("backup", CronRunStatus::Error)).unwrap();
+        append_cron_run(tmp.path(), &entry("inbox", CronRunStatus::TimedOut)).unwrap();
+
+        assert_eq!(read_cron_history(tmp.path(), None, None).unwrap().len(), 3);
+
+        let inbox = read_cron_history(tmp.path(), Some("inbox"), None).unwrap();
+        assert_eq!(inbox.len(), 2);
+
+        let latest = read_cron_history(tmp.path(), Some("inbox"), Some(1)).unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].status, CronRunStatus::TimedOut);
+
+        let last = last_run_times(tmp.path()).unwrap();
+        assert_eq!(last.len(), 2);
+        assert!(last.contains_key("backup"));
+    }
+
+    #[test]
+    fn truncates_long_output() {
+        let long = "x".repeat(MAX_HISTORY_OUTPUT_CHARS + 10);
+        let truncated = CronRunEntry::truncate_output(&long);
+        assert_eq!(truncated.chars().count(), MAX_HISTORY_OUTPUT_CHARS + 3);
+    }
+}
@@ -2,30 +2,90 @@
 //!
 //! Supports standard cron expressions and "every X" interval syntax.
 //! Each job runs in a fresh agent session with overlap prevention.
+//! Jobs come from `[[cron.jobs]]` in config.toml and from the runtime jobs
+//! file managed by [`CronJobStore`]. Runs missed while the daemon was down
+//! are caught up according to each job's `catch_up` policy. Runs start after
+//! an optional random `jitter`, and at most `cron.max_concurrent` run at once.
+//! Paused jobs skip their scheduled runs, and run requests from
+//! [`CronJobStore::request_run`] start a job on the next tick. Failed runs
+//! are retried per the job's `retry` policy, then `on_cron_failure` hooks fire.
+//! One-shot jobs ("at 2025-07-01 09:00", or "in 45 minutes" when added at
+//! runtime) run once, or on start if overdue, and are then archived. Jobs
+//! with a `trigger` also run on daemon events: a new file in a watched
+//! directory, a webhook hit, or a memory change.
 
+pub mod history;
 mod parser;
 pub mod runner;
+mod store;
+mod triggers;
 
 use chrono::Local;
+use rand::RngExt;
+use std::collections::VecDeque;
+use std::path::Path;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::Mutex;
-use tracing::{error, info};
+use std::time::{Duration, SystemTime};
+use tokio::sync::{Mutex, Semaphore};
+use tracing::{debug, error, info, warn};
 
-use crate::config::{Config, CronJob};
+use crate::config::{CatchUpPolicy, Config, CronJob};
+use crate::hooks::{HookEngine, HookEvent};
+use crate::webhooks::WebhookEvent;
+use history::{CronRunEntry, CronRunStatus, append_cron_run, last_run_times};
 use parser::Schedule;
+pub use parser::{natural_to_cron, resolve_relative};
+pub use store::{
+    ArchivedCronJob, CronJobEvent, CronJobInfo, CronJobSource, CronJobStore, CronJobUpdate,
+    UpcomingRun,
+};
+use triggers::TriggerWatch;
 
 /// Runtime state for a single scheduled job.
 struct JobState {
     config: CronJob,
-    schedule: Schedule,
+    /// `None` for jobs that only run on their trigger
+    schedule: Option<Schedule>,
     next_run: chrono::DateTime<Local>,
     running: bool,
+    /// Missed runs still to catch up, run back to back ahead of `next_run`
+    pending_catch_up: usize,
+    /// Scheduled runs (and catch-ups) are skipped while paused
+    paused: bool,
+    /// Run on the next tick regardless of schedule or pause state
+    run_now: bool,
+    /// File or memory trigger poll state
+    trigger: Option<TriggerWatch>,
+    /// Trigger events waiting to run, one run each, oldest first
+    pending_events: VecDeque<String>,
 }
 
+impl JobState {
+    fn queue_event(&mut self, detail: String) {
+        if self.pending_events.len() >= MAX_PENDING_EVENTS {
+            error!(
+                "Cron job '{}' has too many pending events; dropping oldest",
+                self.config.name
+            );
+            self.pending_events.pop_front();
+        }
+        self.pending_events.push_back(detail);
+    }
+}
+
+/// Upper bound on missed runs replayed by `catch_up = "run-all"`.
+const MAX_CATCH_UP_RUNS: usize = 24;
+
+/// Upper bound on trigger events queued for one job.
+const MAX_PENDING_EVENTS: usize = 100;
+
 /// Scheduler that checks and runs cron jobs.
 pub struct CronScheduler {
     jobs: Arc<Mutex<Vec<JobState>>>,
+    /// Runtime jobs file to follow, with the modification time last loaded
+    store: Option<(CronJobStore, Mutex<Option<SystemTime>>)>,
+    /// Limits how many jobs run at once
+    limit: Arc<Semaphore>,
 }
 
 /// Tool factory for providing additional tools to cron jobs (e.g., CLI tools).
@@ -34,91 +94,337 @@ pub type ToolFactory = Box<dyn Fn(&Config) -> Vec<Box<dyn crate::agent::Tool>> +
 impl CronScheduler {
     /// Create a new scheduler from config. Invalid schedules are logged and skipped.
     pub fn new(jobs: &[CronJob]) -> Self {
+        CronScheduler {
+            jobs: Arc::new(Mutex::new(schedule_jobs(jobs, &[], &[]))),
+            store: None,
+            limit: Arc::new(Semaphore::new(Semaphore::MAX_PERMITS)),
+        }
+    }
+
+    /// Create a scheduler for the jobs in `store` that reloads them
+    /// whenever the runtime jobs file changes.
+    pub fn with_store(store: CronJobStore) -> anyhow::Result<Self> {
+        let modified = store.modified();
+        let jobs = store.jobs()?;
+        let paused = store.paused()?;
+        Ok(CronScheduler {
+            jobs: Arc::new(Mutex::new(schedule_jobs(&jobs, &paused, &[]))),
+            store: Some((store, Mutex::new(modified))),
+            limit: Arc::new(Semaphore::new(Semaphore::MAX_PERMITS)),
+        })
+    }
+
+    /// Run at most `max` jobs at once; due jobs beyond that wait for a slot.
+    pub fn with_max_concurrent(mut self, max: usize) -> Self {
+        self.limit = Arc::new(Semaphore::new(max.clamp(1, Semaphore::MAX_PERMITS)));
+        self
+    }
+
+    /// Replace the scheduled jobs, pausing those named in `paused`. Jobs
+    /// whose schedule is unchanged keep their next run time, and running
+    /// jobs stay marked as running.
+    pub async fn reload(&self, jobs: &[CronJob], paused: &[String]) {
+        let mut states = self.jobs.lock().await;
+        let reloaded = schedule_jobs(jobs, paused, &states);
+        *states = reloaded;
+    }
+
+    /// Queue runs missed since each job's last recorded run (see
+    /// [`CatchUpPolicy`]). Call once when the scheduler starts. Returns the
+    /// number of runs queued.
+    pub async fn schedule_catch_up(&self, state_dir: &Path) -> usize {
+        let last_runs = match last_run_times(state_dir) {
+            Ok(last_runs) => last_runs,
+            Err(e) => {
+                error!("Failed to read cron history for catch-up: {}", e);
+                return 0;
+            }
+        };
+
         let now = Local::now();
-        let states: Vec<JobState> = jobs
-            .iter()
-            .filter(|j| j.enabled)
-            .filter_map(|j| match Schedule::parse(&j.schedule) {
-                Ok(schedule) => {
-                    let next_run = schedule.next_after(now).unwrap_or(now);
-                    info!(
-                        "Cron job '{}' scheduled: {} (next: {})",
-                        j.name, j.schedule, next_run
-                    );
-                    Some(JobState {
-                        config: j.clone(),
-                        schedule,
-                        next_run,
-                        running: false,
-                    })
+        let mut queued = 0;
+        let mut jobs = self.jobs.lock().await;
+        for job in jobs.iter_mut() {
+            let max = match job.config.catch_up {
+                CatchUpPolicy::Skip => continue,
+                CatchUpPolicy::RunOnce => 1,
+                CatchUpPolicy::RunAll => MAX_CATCH_UP_RUNS,
+            };
+            let (Some(schedule), Some(last)) = (&job.schedule, last_runs.get(&job.config.name))
+            else {
+                continue;
+            };
+            let missed = schedule.occurrences_between(last.with_timezone(&Local), now, max);
+            if missed > 0 {
+                info!(
+                    "Cron job '{}' missed {} run(s) since {}; catching up ({})",
+                    job.config.name,
+                    missed,
+                    last,
+                    job.config.catch_up.as_str()
+                );
+                job.pending_catch_up = missed;
+                queued += missed;
+            }
+        }
+        queued
+    }
+
+    /// Reload from the runtime jobs file if it changed since the last load,
+    /// and pick up run requests and webhook events.
+    async fn sync_store(&self) {
+        let Some((store, loaded)) = &self.store else {
+            return;
+        };
+        let modified = store.modified();
+        let mut loaded = loaded.lock().await;
+        if *loaded == modified {
+            return;
+        }
+        let state = store
+            .jobs()
+            .and_then(|jobs| store.paused().map(|paused| (jobs, paused)));
+        match state {
+            Ok((jobs, paused)) => {
+                info!("Cron jobs file changed, reloading");
+                self.reload(&jobs, &paused).await;
+                *loaded = modified;
+            }
+            Err(e) => {
+                error!("Failed to reload cron jobs: {}", e);
+                return;
+            }
+        }
+
+        let pending = store
+            .take_run_requests()
+            .and_then(|requests| store.take_events().map(|events| (requests, events)));
+        let (requests, events) = match pending {
+            Ok(pending) => pending,
+            Err(e) => {
+                error!("Failed to read cron run requests: {}", e);
+                return;
+            }
+        };
+        if requests.is_empty() && events.is_empty() {
+            return;
+        }
+
+        let mut jobs = self.jobs.lock().await;
+        for name in requests {
+            match jobs.iter_mut().find(|j| j.config.name == name) {
+                Some(job) => {
+                    info!("Cron job '{}' requested to run now", name);
+                    job.run_now = true;
                 }
-                Err(e) => {
-                    error!("Skipping cron job '{}': {}", j.name, e);
-                    None
+                None => error!("Cannot run cron job '{}': not scheduled", name),
+            }
+        }
+        for event in events {
+            match jobs.iter_mut().find(|j| j.config.name == event.job) {
+                Some(job) => {
+                    info!("Cron job '{}' triggered by webhook", event.job);
+                    job.queue_event(event.detail);
                 }
-            })
-            .collect();
-
-        CronScheduler {
-            jobs: Arc::new(Mutex::new(states)),
+                None => error!(
+                    "Dropping webhook event for cron job '{}': not scheduled",
+                    event.job
+                ),
+            }
         }
+        // Taking them rewrote the file; don't reload again
+        *loaded = store.modified();
     }
 
     /// Check for due jobs and spawn them. Non-blocking.
     pub async fn tick(&self, config: &Config, tool_factory: Option<&ToolFactory>) {
+        self.sync_store().await;
         let now = Local::now();
+        let workspace = config.workspace_path();
         let mut jobs = self.jobs.lock().await;
 
         for job in jobs.iter_mut() {
-            if job.running || now < job.next_run {
+            if let Some(watch) = &mut job.trigger {
+                let events = watch.poll(&workspace);
+                if job.paused {
+                    if !events.is_empty() {
+                        debug!("Cron job '{}' is paused, ignoring events", job.config.name);
+                    }
+                } else {
+                    for event in events {
+                        info!("Cron job '{}' triggered: {}", job.config.name, event);
+                        job.queue_event(event);
+                    }
+                }
+            }
+
+            let mut due = job.schedule.is_some() && now >= job.next_run;
+            if due && job.paused {
+                // Skip this run but keep the schedule moving
+                debug!("Cron job '{}' is paused, skipping run", job.config.name);
+                if let Some(next) = job.schedule.as_ref().and_then(|s| s.next_after(now)) {
+                    job.next_run = next;
+                }
+                due = false;
+            }
+            let catch_up = job.pending_catch_up > 0 && !job.paused;
+            let triggered = !job.pending_events.is_empty();
+            if job.running || !(due || catch_up || job.run_now || triggered) {
                 continue;
             }
 
             job.running = true;
             let job_name = job.config.name.clone();
-            let prompt = job.config.prompt.clone();
+            let job_config = job.config.clone();
             let timeout_str = job.config.timeout.clone();
+            let jitter = job.config.jitter.as_deref().map(jitter_delay);
+            let limit = self.limit.clone();
             let config = config.clone();
-            let extra_tools = tool_factory.map(|f| f(&config));
+            let max_attempts = job.config.retry.max_attempts.max(1);
+            // One set of extra tools per attempt; run_job consumes them
+            let mut tool_sets: Vec<_> = (0..max_attempts)
+                .map(|_| tool_factory.map(|f| f(&config)))
+                .collect();
             let jobs_ref = self.jobs.clone();
+            let one_shot = job.schedule.as_ref().and_then(Schedule::once_at).is_some();
+            let store = self.store.as_ref().map(|(store, _)| store.clone());
 
-            // Advance next_run now to prevent re-triggering
-            if let Some(next) = job.schedule.next_after(now) {
-                job.next_run = next;
-            }
+            // Advance next_run now to prevent re-triggering; catch-up,
+            // run-now, and triggered runs leave the regular schedule alone
+            let event = if due {
+                if let Some(next) = job.schedule.as_ref().and_then(|s| s.next_after(now)) {
+                    job.next_run = next;
+                }
+                None
+            } else if job.run_now {
+                None
+            } else if catch_up {
+                job.pending_catch_up -= 1;
+                None
+            } else {
+                job.pending_events.pop_front()
+            };
+            job.run_now = false;
 
             tokio::spawn(async move {
+                if let Some(delay) = jitter.filter(|d| !d.is_zero()) {
+                    debug!("Cron job '{}' starting after {:?} jitter", job_name, delay);
+                    tokio::time::sleep(delay).await;
+                }
                 let timeout =
                     crate::config::parse_duration(&timeout_str).unwrap_or(Duration::from_secs(600));
 
-                let result = tokio::time::timeout(
-                    timeout,
-                    runner::run_job(&config, &job_name, &prompt, extra_tools),
-                )
-                .await;
+                let started_at = chrono::Utc::now();
+                let started = std::time::Instant::now();
+                let mut attempt = 0;
+                let result = loop {
+                    attempt += 1;
+                    let result = {
+                        // Held for this attempt only; the semaphore is never closed
+                        let _permit = limit.clone().acquire_owned().await.ok();
+                        let extra_tools = tool_sets.pop().flatten();
+                        tokio::time::timeout(
+                            timeout,
+                            runner::run_job(&config, &job_config, event.as_deref(), extra_tools),
+                        )
+                        .await
+                    };
+                    if matches!(result, Ok(Ok(_))) || attempt >= max_attempts {
+                        break result;
+                    }
+                    let reason = match &result {
+                        Ok(Err(e)) => e.to_string(),
+                        _ => "timed out".to_string(),
+                    };
+                    let delay = job_config.retry.delay_after(attempt);
+                    warn!(
+                        "Cron job '{}' attempt {}/{} failed ({}); retrying in {:?}",
+                        job_name, attempt, max_attempts, reason, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                };
 
+                let mut entry = CronRunEntry {
+                    job: job_name.clone(),
+                    started_at: started_at.to_rfc3339(),
+                    finished_at: chrono::Utc::now().to_rfc3339(),
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    status: CronRunStatus::Ok,
+                    output: String::new(),
+                    error: None,
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    attempts: attempt,
+                    event: event.as_deref().map(CronRunEntry::truncate_event),
+                };
                 match result {
-                    Ok(Ok(response)) => {
+                    Ok(Ok(output)) => {
+                        let response = &output.response;
                         if !response.is_empty() {
                             info!(
                                 "Cron '{}' output: {}",
                                 job_name,
-                                &response[..response.len().min(200)]
+                                &response[..response.floor_char_boundary(200)]
                             );
                         }
+                        entry.output = CronRunEntry::truncate_output(response);
+                        entry.input_tokens = output.input_tokens;
+                        entry.output_tokens = output.output_tokens;
                     }
                     Ok(Err(e)) => {
-                        error!("Cron job '{}' failed: {}", job_name, e);
+                        error!(
+                            "Cron job '{}' failed after {} attempt(s): {}",
+                            job_name, attempt, e
+                        );
+                        entry.status = CronRunStatus::Error;
+                        entry.error = Some(e.to_string());
                     }
                     Err(_) => {
-                        error!("Cron job '{}' timed out", job_name);
+                        error!(
+                            "Cron job '{}' timed out after {} attempt(s)",
+                            job_name, attempt
+                        );
+                        entry.status = CronRunStatus::TimedOut;
+                        entry.error = Some(format!("timed out after {:?}", timeout));
                     }
                 }
+                if let Err(e) = append_cron_run(&config.paths.state_dir, &entry) {
+                    error!("Failed to record cron run for '{}': {}", job_name, e);
+                }
+                crate::webhooks::emit(
+                    &config,
+                    WebhookEvent::CronFinished {
+                        job: job_name.clone(),
+                        status: entry.status.as_str().to_string(),
+                        duration_ms: entry.duration_ms,
+                        attempts: attempt,
+                        error: entry.error.clone(),
+                    },
+                );
+                if entry.status != CronRunStatus::Ok {
+                    let event = HookEvent::OnCronFailure {
+                        job: job_name.clone(),
+                        attempts: attempt,
+                        error: entry.error.clone().unwrap_or_default(),
+                    };
+                    HookEngine::new(&config.workspace_path()).fire(&event).await;
+                }
 
-                // Mark job as no longer running
                 let mut jobs = jobs_ref.lock().await;
-                if let Some(j) = jobs.iter_mut().find(|j| j.config.name == job_name) {
+                if one_shot {
+                    // One-shot jobs run once, then move to the archive
+                    jobs.retain(|j| j.config.name != job_name);
+                    if let Some(store) = store
+                        && let Err(e) = store.archive(&job_name, entry.status)
+                    {
+                        error!("Failed to archive cron job '{}': {}", job_name, e);
+                    }
+                } else if let Some(j) = jobs.iter_mut().find(|j| j.config.name == job_name) {
+                    // Mark job as no longer running
                     j.running = false;
+                    if let Some(watch) = &mut j.trigger {
+                        watch.rebaseline(&config.workspace_path());
+                    }
                 }
             });
         }
@@ -130,4 +436,134 @@ impl CronScheduler {
         // Use try_lock to avoid async in a sync context
         self.jobs.try_lock().map(|j| !j.is_empty()).unwrap_or(false)
     }
+
+    /// Number of enabled jobs with a valid schedule.
+    pub async fn job_count(&self) -> usize {
+        self.jobs.lock().await.len()
+    }
+
+    /// Number of jobs whose run is still in progress.
+    pub async fn running_jobs(&self) -> usize {
+        self.jobs.lock().await.iter().filter(|j| j.running).count()
+    }
+}
+
+/// Validate a schedule and return how it is interpreted: the cron expression
+/// for a natural-language schedule, otherwise the schedule itself.
+pub fn interpret_schedule(schedule: &str) -> anyhow::Result<String> {
+    Schedule::parse(schedule)?;
+    let trimmed = schedule.trim();
+    Ok(natural_to_cron(trimmed).unwrap_or_else(|| trimmed.to_string()))
+}
+
+/// Random delay between zero and `jitter` (e.g. "2m").
+fn jitter_delay(jitter: &str) -> Duration {
+    match crate::config::parse_duration(jitter) {
+        Ok(max) if !max.is_zero() => {
+            let max_ms = max.as_millis().min(u64::MAX as u128) as u64;
+            Duration::from_millis(rand::rng().random_range(0..=max_ms))
+        }
+        Ok(_) => Duration::ZERO,
+        Err(e) => {
+            error!("Ignoring invalid cron jitter '{}': {}", jitter, e);
+            Duration::ZERO
+        }
+    }
+}
+
+/// Build scheduler state for the enabled jobs in `jobs`, carrying over
+/// `next_run` and `running` from `previous` where the schedule is unchanged,
+/// and trigger state where the trigger is unchanged.
+fn schedule_jobs(jobs: &[CronJob], paused: &[String], previous: &[JobState]) -> Vec<JobState> {
+    let now = Local::now();
+    jobs.iter()
+        .filter(|j| j.enabled)
+        .filter_map(|j| {
+            let schedule = if j.schedule.trim().is_empty() {
+                None
+            } else {
+                match Schedule::parse(&j.schedule) {
+                    Ok(schedule) => Some(schedule),
+                    Err(e) => {
+                        error!("Skipping cron job '{}': {}", j.name, e);
+                        return None;
+                    }
+                }
+            };
+            if schedule.is_none() && j.trigger.is_none() {
+                error!("Skipping cron job '{}': no schedule or trigger", j.name);
+                return None;
+            }
+
+            let prev = previous.iter().find(|p| p.config.name == j.name);
+            let next_run = prev
+                .filter(|p| p.config.schedule == j.schedule)
+                .map(|p| p.next_run)
+                .unwrap_or_else(|| {
+                    schedule
+                        .as_ref()
+                        .and_then(|s| s.next_after(now))
+                        .unwrap_or(now)
+                });
+            let same_trigger = prev.filter(|p| p.config.trigger == j.trigger);
+            if prev.is_none_or(|p| p.config.schedule != j.schedule) && schedule.is_some() {
+                info!(
+                    "Cron job '{}' scheduled: {} (next: {})",
+                    j.name, j.schedule, next_run
+                );
+            }
+            if let Some(trigger) = &j.trigger
+                && same_trigger.is_none()
+            {
+                info!(
+                    "Cron job '{}' waiting for {} trigger",
+                    j.name,
+                    trigger.kind()
+                );
+            }
+
+            Some(JobState {
+                config: j.clone(),
+                schedule,
+                next_run,
+                running: prev.is_some_and(|p| p.running),
+                pending_catch_up: prev.map_or(0, |p| p.pending_catch_up),
+                paused: paused.contains(&j.name),
+                run_now: prev.is_some_and(|p| p.run_now),
+                trigger: match same_trigger {
+                    Some(p) => p.trigger.clone(),
+                    None => j.trigger.clone().map(TriggerWatch::new),
+                },
+                pending_events: same_trigger
+                    .map(|p| p.pending_events.clone())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_delay_is_bounded() {
+        for _ in 0..20 {
+            assert!(jitter_delay("2s") <= Duration::from_secs(2));
+        }
+        assert_eq!(jitter_delay("0s"), Duration::ZERO);
+        assert_eq!(jitter_delay("soon"), Duration::ZERO);
+    }
+
+    #[test]
+    fn retry_backoff_doubles_up_to_an_hour() {
+        let retry = crate::config::CronRetryPolicy {
+            max_attempts: 5,
+            backoff: "30s".to_string(),
+        };
+        assert_eq!(retry.delay_after(1), Duration::from_secs(30));
+        assert_eq!(retry.delay_after(2), Duration::from_secs(60));
+        assert_eq!(retry.delay_after(3), Duration::from_secs(120));
+        assert_eq!(retry.delay_after(20), Duration::from_secs(3600));
+    }
 }
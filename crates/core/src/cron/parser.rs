@@ -1,7 +1,9 @@
-//! Schedule parsing: cron expressions and "every X" interval syntax.
+//! Schedule parsing: cron expressions, "every X" interval syntax,
+//! natural-language schedules ("every weekday at 9am", "first monday of the
+//! month") translated to cron expressions, and one-shot "at" times.
 
 use anyhow::{Result, bail};
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
 use croner::Cron;
 use std::time::Duration;
 
@@ -11,23 +13,47 @@ pub enum Schedule {
     Cron(Box<Cron>),
     /// Simple interval (e.g., "every 30m", "every 2h")
     Interval(Duration),
+    /// One-time run (e.g., "at 2025-07-01 09:00")
+    Once(DateTime<Local>),
 }
 
 impl Schedule {
     /// Parse a schedule string. Accepts:
     /// - "every 30m", "every 2h", "every 1d"
+    /// - Natural language: "every weekday at 9am", "daily at 18:30",
+    ///   "every monday and friday at noon", "first monday of the month at 9am",
+    ///   "the 15th of every month"
     /// - Standard cron expressions: "0 */6 * * *"
+    /// - One-shot local times: "at 2025-07-01 09:00"
+    ///
+    /// Relative one-shot schedules ("in 45 minutes") must first be turned
+    /// into "at" times with [`resolve_relative`].
     pub fn parse(s: &str) -> Result<Self> {
         let trimmed = s.trim();
 
-        if let Some(interval_str) = trimmed.strip_prefix("every ") {
-            let duration = parse_interval(interval_str.trim())?;
+        if let Some(at) = trimmed.strip_prefix("at ") {
+            return parse_at(at)
+                .map(Schedule::Once)
+                .ok_or_else(|| anyhow::anyhow!("Invalid time '{}'; use YYYY-MM-DD HH:MM", at));
+        }
+        if trimmed.starts_with("in ") {
+            bail!(
+                "Relative schedule '{}' can only be used when adding a job at runtime; \
+                 use \"at YYYY-MM-DD HH:MM\"",
+                trimmed
+            );
+        }
+
+        if let Some(interval_str) = trimmed.strip_prefix("every ")
+            && let Ok(duration) = parse_interval(interval_str.trim())
+        {
             return Ok(Schedule::Interval(duration));
         }
 
-        let cron = Cron::new(trimmed)
+        let expr = natural_to_cron(trimmed).unwrap_or_else(|| trimmed.to_string());
+        let cron = Cron::new(&expr)
             .parse()
-            .map_err(|e| anyhow::anyhow!("Invalid cron expression '{}': {}", trimmed, e))?;
+            .map_err(|e| anyhow::anyhow!("Invalid schedule '{}': {}", trimmed, e))?;
         Ok(Schedule::Cron(Box::new(cron)))
     }
 
@@ -38,8 +64,244 @@ impl Schedule {
             Schedule::Interval(duration) => {
                 Some(after + chrono::Duration::from_std(*duration).ok()?)
             }
+            Schedule::Once(at) => (*at > after).then_some(*at),
+        }
+    }
+
+    /// Run time of a one-shot schedule.
+    pub fn once_at(&self) -> Option<DateTime<Local>> {
+        match self {
+            Schedule::Once(at) => Some(*at),
+            _ => None,
+        }
+    }
+
+    /// Number of occurrences after `after` and up to `until`, at most `max`.
+    /// Always zero for one-shot schedules, which run when overdue anyway.
+    pub fn occurrences_between(
+        &self,
+        after: DateTime<Local>,
+        until: DateTime<Local>,
+        max: usize,
+    ) -> usize {
+        if matches!(self, Schedule::Once(_)) {
+            return 0;
+        }
+        let mut count = 0;
+        let mut next = self.next_after(after);
+        while let Some(time) = next
+            && time <= until
+            && count < max
+        {
+            count += 1;
+            next = self.next_after(time);
+        }
+        count
+    }
+}
+
+/// Turn a relative one-shot schedule ("in 45 minutes", "in 2h30m",
+/// "in 1 day") into an absolute "at" schedule counted from `now`. Returns
+/// `None` for other schedules.
+pub fn resolve_relative(s: &str, now: DateTime<Local>) -> Result<Option<String>> {
+    let Some(delay) = s.trim().strip_prefix("in ") else {
+        return Ok(None);
+    };
+
+    let invalid = || anyhow::anyhow!("Invalid delay '{}'", delay);
+    let lower = delay.to_lowercase();
+    let mut words = lower
+        .split_whitespace()
+        .map(|w| w.trim_end_matches(','))
+        .filter(|w| *w != "and");
+    let mut duration = Duration::ZERO;
+    while let Some(word) = words.next() {
+        // Compact form: "45m", "2h30m"
+        if let Ok(d) = crate::config::parse_duration(word) {
+            duration += d;
+            continue;
         }
+        let count: u64 = word.parse().map_err(|_| invalid())?;
+        let unit_secs = match words.next().ok_or_else(invalid)? {
+            "second" | "seconds" | "sec" | "secs" => 1,
+            "minute" | "minutes" | "min" | "mins" => 60,
+            "hour" | "hours" | "hr" | "hrs" => 3600,
+            "day" | "days" => 86400,
+            "week" | "weeks" => 7 * 86400,
+            _ => return Err(invalid()),
+        };
+        duration += Duration::from_secs(count * unit_secs);
     }
+    if duration.is_zero() {
+        return Err(invalid());
+    }
+    let at = now + chrono::Duration::from_std(duration)?;
+    Ok(Some(format!("at {}", at.format("%Y-%m-%d %H:%M:%S"))))
+}
+
+/// Local time for "2025-07-01 09:00", "2025-07-01 09:00:30", or
+/// "2025-07-01T09:00".
+fn parse_at(s: &str) -> Option<DateTime<Local>> {
+    let s = s.trim();
+    let naive = [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// Cron expression for a natural-language schedule, or `None` if `s` is not
+/// one. A missing time means midnight.
+pub fn natural_to_cron(s: &str) -> Option<String> {
+    let s = s
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (days, time) = match s.rsplit_once(" at ") {
+        Some((days, time)) => (days, Some(time)),
+        None => (s.as_str(), None),
+    };
+    let days = days
+        .strip_prefix("on ")
+        .or_else(|| days.strip_prefix("each "))
+        .unwrap_or(days);
+
+    if matches!(days, "hourly" | "every hour") {
+        return time.is_none().then(|| "0 * * * *".to_string());
+    }
+
+    let (minute, hour) = match time {
+        Some(time) => parse_time_of_day(time)?,
+        None => (0, 0),
+    };
+    let (dom, dow) = parse_days(days)?;
+    Some(format!("{} {} {} * {}", minute, hour, dom, dow))
+}
+
+/// Day-of-month and day-of-week cron fields for the day part of a
+/// natural-language schedule.
+fn parse_days(days: &str) -> Option<(String, String)> {
+    let any = || "*".to_string();
+    match days {
+        "daily" | "every day" | "day" => return Some((any(), any())),
+        "weekdays" | "every weekday" => return Some((any(), "1-5".to_string())),
+        "weekends" | "every weekend" | "every weekend day" => {
+            return Some((any(), "0,6".to_string()));
+        }
+        "weekly" | "every week" => return Some((any(), "0".to_string())),
+        "monthly" | "every month" => return Some(("1".to_string(), any())),
+        _ => {}
+    }
+
+    // "first monday of the month", "last friday of every month",
+    // "the 15th of the month", "last day of the month"
+    if let Some(rest) = days
+        .strip_suffix(" of the month")
+        .or_else(|| days.strip_suffix(" of every month"))
+        .or_else(|| days.strip_suffix(" of each month"))
+    {
+        let rest = rest.strip_prefix("the ").unwrap_or(rest);
+        return match rest.split_once(' ') {
+            Some((nth, "day")) if nth == "last" => Some(("L".to_string(), any())),
+            Some((nth, day)) => {
+                let day = weekday_number(day)?;
+                let dow = match nth {
+                    "last" => format!("{}L", day),
+                    _ => format!("{}#{}", day, ordinal(nth).filter(|n| *n <= 5)?),
+                };
+                Some((any(), dow))
+            }
+            None => Some((ordinal(rest).filter(|n| *n <= 31)?.to_string(), any())),
+        };
+    }
+
+    // "every monday", "every mon, wed and fri", "mondays"
+    let list = days.strip_prefix("every ").unwrap_or(days);
+    let mut numbers: Vec<u32> = list
+        .split([',', ' '])
+        .filter(|w| !w.is_empty() && *w != "and")
+        .map(weekday_number)
+        .collect::<Option<_>>()?;
+    if numbers.is_empty() {
+        return None;
+    }
+    numbers.sort_unstable();
+    numbers.dedup();
+    let dow = numbers
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    Some((any(), dow))
+}
+
+/// Cron day-of-week number (Sunday = 0) for a day name, abbreviation, or plural.
+fn weekday_number(day: &str) -> Option<u32> {
+    let day = day.strip_suffix('s').filter(|d| d.len() > 2).unwrap_or(day);
+    let n = match day {
+        "sun" | "sunday" => 0,
+        "mon" | "monday" => 1,
+        "tue" | "tues" | "tuesday" => 2,
+        "wed" | "wednesday" => 3,
+        "thu" | "thur" | "thurs" | "thursday" => 4,
+        "fri" | "friday" => 5,
+        "sat" | "saturday" => 6,
+        _ => return None,
+    };
+    Some(n)
+}
+
+/// Number for "first"/"1st", "second"/"2nd", ... "31st".
+fn ordinal(word: &str) -> Option<u32> {
+    let n = match word {
+        "first" => 1,
+        "second" => 2,
+        "third" => 3,
+        "fourth" => 4,
+        "fifth" => 5,
+        _ => {
+            let digits = word
+                .strip_suffix("st")
+                .or_else(|| word.strip_suffix("nd"))
+                .or_else(|| word.strip_suffix("rd"))
+                .or_else(|| word.strip_suffix("th"))?;
+            digits.parse().ok().filter(|n| *n >= 1)?
+        }
+    };
+    Some(n)
+}
+
+/// (minute, hour) for "9am", "9:30 pm", "17:00", "noon", or "midnight".
+fn parse_time_of_day(time: &str) -> Option<(u32, u32)> {
+    match time {
+        "noon" => return Some((0, 12)),
+        "midnight" => return Some((0, 0)),
+        _ => {}
+    }
+    let compact = time.replace(' ', "");
+    let (clock, meridiem) = if let Some(clock) = compact.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = compact.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (compact.as_str(), None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match meridiem {
+        Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+    (hour < 24 && minute < 60).then_some((minute, hour))
 }
 
 /// Parse an interval string like "30m", "2h", "1d", "90s".
@@ -88,6 +350,93 @@ mod tests {
         assert!(matches!(s, Schedule::Interval(_)));
     }
 
+    #[test]
+    fn test_natural_to_cron() {
+        let cases = [
+            ("every weekday at 9am", "0 9 * * 1-5"),
+            ("Every day at 18:30", "30 18 * * *"),
+            ("daily", "0 0 * * *"),
+            ("hourly", "0 * * * *"),
+            ("every weekend at noon", "0 12 * * 0,6"),
+            ("every monday and friday at 7:15 pm", "15 19 * * 1,5"),
+            ("every mon, wed, fri at 12am", "0 0 * * 1,3,5"),
+            ("first monday of the month", "0 0 * * 1#1"),
+            ("last friday of every month at 5pm", "0 17 * * 5L"),
+            ("the 15th of every month at 9am", "0 9 15 * *"),
+            ("last day of the month", "0 0 L * *"),
+            ("monthly", "0 0 1 * *"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                natural_to_cron(input).as_deref(),
+                Some(expected),
+                "{}",
+                input
+            );
+        }
+
+        assert_eq!(natural_to_cron("0 9 * * 1-5"), None);
+        assert_eq!(natural_to_cron("every weekday at 13pm"), None);
+        assert_eq!(natural_to_cron("sixth monday of the month"), None);
+        assert_eq!(natural_to_cron("every blursday"), None);
+    }
+
+    #[test]
+    fn test_parse_natural() {
+        let s = Schedule::parse("every weekday at 9am").unwrap();
+        assert!(matches!(s, Schedule::Cron(_)));
+        assert!(Schedule::parse("first monday of the month at 9am").is_ok());
+        assert!(Schedule::parse("every someday").is_err());
+    }
+
+    #[test]
+    fn test_occurrences_between() {
+        let s = Schedule::parse("every 1h").unwrap();
+        let now = Local::now();
+        let five_hours_ago = now - chrono::Duration::hours(5) - chrono::Duration::minutes(1);
+        assert_eq!(s.occurrences_between(five_hours_ago, now, 100), 5);
+        assert_eq!(s.occurrences_between(five_hours_ago, now, 2), 2);
+        assert_eq!(s.occurrences_between(now, now, 100), 0);
+    }
+
+    #[test]
+    fn test_one_shot() {
+        let s = Schedule::parse("at 2025-07-01 09:00").unwrap();
+        let at = s.once_at().unwrap();
+        assert_eq!(at.format("%Y-%m-%d %H:%M").to_string(), "2025-07-01 09:00");
+        assert_eq!(s.next_after(at - chrono::Duration::minutes(1)), Some(at));
+        assert_eq!(s.next_after(at), None);
+        assert_eq!(
+            s.occurrences_between(at - chrono::Duration::days(1), at, 10),
+            0
+        );
+        assert!(Schedule::parse("at 2025-07-01T09:00:30").is_ok());
+        assert!(Schedule::parse("at tomorrow").is_err());
+        assert!(Schedule::parse("in 45 minutes").is_err());
+    }
+
+    #[test]
+    fn test_resolve_relative() {
+        let now = Local.with_ymd_and_hms(2025, 7, 1, 9, 0, 0).unwrap();
+        let cases = [
+            ("in 45 minutes", "at 2025-07-01 09:45:00"),
+            ("in 2h30m", "at 2025-07-01 11:30:00"),
+            ("in 1 hour and 15 mins", "at 2025-07-01 10:15:00"),
+            ("in 1 day", "at 2025-07-02 09:00:00"),
+            ("in 2 weeks", "at 2025-07-15 09:00:00"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                resolve_relative(input, now).unwrap().as_deref(),
+                Some(expected),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(resolve_relative("every 1h", now).unwrap(), None);
+        assert!(resolve_relative("in a while", now).is_err());
+    }
+
     #[test]
     fn test_next_after_interval() {
         let s = Schedule::parse("every 1h").unwrap();
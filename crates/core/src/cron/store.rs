@@ -0,0 +1,899 @@
+//! Runtime cron jobs.
+//!
+//! Jobs added with `localgpt cron add`, the HTTP API, or bridge RPCs are kept
+//! in a jobs file (data_dir/cron-jobs.json) separate from config.toml, so
+//! runtime changes never rewrite the user's config. Jobs from `[[cron.jobs]]`
+//! in config.toml are read-only at runtime, but any job can be paused,
+//! resumed, or triggered to run now; that state lives in the jobs file too.
+//! One-shot jobs ("at ..." schedules) are moved to an archive in the jobs
+//! file after they run. Webhook events are queued there for the daemon too.
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Local};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::history::CronRunStatus;
+use super::parser::{Schedule, natural_to_cron, resolve_relative};
+use crate::config::{CatchUpPolicy, Config, CronJob, JobTrigger};
+
+/// Maximum archived one-shot jobs kept in the jobs file.
+const MAX_ARCHIVED: usize = 100;
+
+/// Maximum queued events kept in the jobs file while the daemon is down.
+const MAX_QUEUED_EVENTS: usize = 100;
+
+/// Where a job is defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CronJobSource {
+    /// `[[cron.jobs]]` in config.toml (read-only at runtime)
+    Config,
+    /// The runtime jobs file
+    Runtime,
+}
+
+impl CronJobSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CronJobSource::Config => "config",
+            CronJobSource::Runtime => "runtime",
+        }
+    }
+}
+
+/// A job with its source and next scheduled run.
+#[derive(Debug, Clone, Serialize)]
+pub struct CronJobInfo {
+    #[serde(flatten)]
+    pub job: CronJob,
+    pub source: CronJobSource,
+    /// Cron expression a natural-language schedule was translated to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interpreted: Option<String>,
+    /// Paused jobs skip their scheduled runs until resumed
+    pub paused: bool,
+    /// Next run time, if the job is enabled, not paused, and its schedule parses
+    pub next_run: Option<DateTime<Local>>,
+}
+
+/// One upcoming scheduled run.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpcomingRun {
+    pub job: String,
+    pub at: DateTime<Local>,
+}
+
+/// Changes to a runtime job. `None` fields are left unchanged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CronJobUpdate {
+    #[serde(default)]
+    pub schedule: Option<String>,
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub channel: Option<String>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub timeout: Option<String>,
+    #[serde(default)]
+    pub catch_up: Option<CatchUpPolicy>,
+    /// Empty string clears the jitter
+    #[serde(default)]
+    pub jitter: Option<String>,
+    /// Empty string reverts to the default model
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Replaces the tool allow list (empty list removes the restriction)
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+    /// Replaces the skill list (empty list allows all skills)
+    #[serde(default)]
+    pub skills: Option<Vec<String>>,
+    /// Empty string reverts to the default "cron-<name>" namespace
+    #[serde(default)]
+    pub memory_namespace: Option<String>,
+    /// Total attempts per run, including the first
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+    /// Delay before the first retry
+    #[serde(default)]
+    pub retry_backoff: Option<String>,
+    /// New event trigger
+    #[serde(default)]
+    pub trigger: Option<JobTrigger>,
+    /// Remove the event trigger (the job must have a schedule)
+    #[serde(default)]
+    pub remove_trigger: bool,
+}
+
+/// An event for a triggered job, waiting for the daemon to run it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CronJobEvent {
+    pub job: String,
+    /// Available to the prompt as `{{event}}`
+    pub detail: String,
+}
+
+/// A one-shot job that has run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedCronJob {
+    #[serde(flatten)]
+    pub job: CronJob,
+    pub source: CronJobSource,
+    /// When the run finished (RFC 3339)
+    pub finished_at: String,
+    /// Outcome of the run's last attempt
+    pub status: CronRunStatus,
+}
+
+/// Contents of the runtime jobs file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct JobsFile {
+    #[serde(default)]
+    jobs: Vec<CronJob>,
+    /// Names of paused jobs (config or runtime)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    paused: Vec<String>,
+    /// Jobs to run on the scheduler's next tick, consumed by the daemon
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    run_requests: Vec<String>,
+    /// One-shot jobs that have run, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    archived: Vec<ArchivedCronJob>,
+    /// Webhook events not yet picked up by the daemon, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    events: Vec<CronJobEvent>,
+}
+
+/// Add, change, and remove runtime cron jobs. Every operation reads and
+/// writes the jobs file, so the daemon's scheduler picks changes up on its
+/// next tick.
+#[derive(Clone)]
+pub struct CronJobStore {
+    path: PathBuf,
+    /// Jobs from config.toml
+    config_jobs: Vec<CronJob>,
+}
+
+impl CronJobStore {
+    pub fn new(path: impl Into<PathBuf>, config_jobs: Vec<CronJob>) -> Self {
+        Self {
+            path: path.into(),
+            config_jobs,
+        }
+    }
+
+    /// Store for the resolved jobs file and the jobs in `config`.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let paths = crate::paths::Paths::resolve()?;
+        Ok(Self::new(paths.cron_jobs_file(), config.cron.jobs.clone()))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Modification time of the jobs file, if it exists.
+    pub fn modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.path).and_then(|m| m.modified()).ok()
+    }
+
+    /// All jobs: config.toml jobs first, then runtime jobs. Config one-shot
+    /// jobs that already ran are left out.
+    pub fn jobs(&self) -> Result<Vec<CronJob>> {
+        let file = self.load()?;
+        let mut jobs: Vec<CronJob> = self.active_config_jobs(&file).cloned().collect();
+        jobs.extend(file.jobs);
+        Ok(jobs)
+    }
+
+    /// All jobs with their source and next run time.
+    pub fn list(&self) -> Result<Vec<CronJobInfo>> {
+        let file = self.load()?;
+        let now = Local::now();
+        let info = |job: CronJob, source| {
+            let paused = file.paused.contains(&job.name);
+            let next_run = if job.enabled && !paused {
+                // An overdue one-shot job runs on the next tick
+                Schedule::parse(&job.schedule)
+                    .ok()
+                    .and_then(|s| s.next_after(now).or_else(|| s.once_at()))
+            } else {
+                None
+            };
+            CronJobInfo {
+                interpreted: natural_to_cron(job.schedule.trim()),
+                job,
+                source,
+                paused,
+                next_run,
+            }
+        };
+
+        let mut jobs: Vec<CronJobInfo> = self
+            .active_config_jobs(&file)
+            .cloned()
+            .map(|job| info(job, CronJobSource::Config))
+            .collect();
+        jobs.extend(
+            file.jobs
+                .iter()
+                .cloned()
+                .map(|job| info(job, CronJobSource::Runtime)),
+        );
+        Ok(jobs)
+    }
+
+    /// The next `limit` scheduled runs, soonest first, across all enabled
+    /// and unpaused jobs or only `job`. Interval schedules count from now,
+    /// and jitter isn't included.
+    pub fn upcoming(&self, job: Option<&str>, limit: usize) -> Result<Vec<UpcomingRun>> {
+        let jobs = self.list()?;
+        if let Some(name) = job
+            && !jobs.iter().any(|info| info.job.name == name)
+        {
+            bail!("Cron job '{}' not found", name);
+        }
+
+        let mut runs = Vec::new();
+        for info in jobs {
+            if job.is_some_and(|name| name != info.job.name) {
+                continue;
+            }
+            let (Some(first), Ok(schedule)) = (info.next_run, Schedule::parse(&info.job.schedule))
+            else {
+                continue;
+            };
+            let mut next = Some(first);
+            for _ in 0..limit {
+                let Some(at) = next else {
+                    break;
+                };
+                runs.push(UpcomingRun {
+                    job: info.job.name.clone(),
+                    at,
+                });
+                next = schedule.next_after(at);
+            }
+            // Keep only the soonest runs across the jobs seen so far
+            runs.sort_by_key(|run| run.at);
+            runs.truncate(limit);
+        }
+        Ok(runs)
+    }
+
+    /// Queue a webhook event for a job with a webhook trigger. `secret` is
+    /// the caller's `X-Webhook-Secret`, checked against the trigger's.
+    pub fn push_webhook_event(
+        &self,
+        name: &str,
+        secret: Option<&str>,
+        detail: String,
+    ) -> Result<()> {
+        let _lock = self.lock()?;
+        let mut file = self.load()?;
+        let job = self.find(&file, name)?;
+        let Some(trigger) = job.trigger.as_ref().filter(|t| t.kind() == "webhook") else {
+            bail!("Cron job '{}' has no webhook trigger", name);
+        };
+        if !trigger.accepts_webhook(secret) {
+            bail!("Invalid webhook secret for cron job '{}'", name);
+        }
+        if !job.enabled {
+            bail!("Cron job '{}' is disabled", name);
+        }
+        file.events.push(CronJobEvent {
+            job: name.to_string(),
+            detail,
+        });
+        if file.events.len() > MAX_QUEUED_EVENTS {
+            let excess = file.events.len() - MAX_QUEUED_EVENTS;
+            file.events.drain(..excess);
+        }
+        self.save(&file)
+    }
+
+    /// Remove and return queued events.
+    pub fn take_events(&self) -> Result<Vec<CronJobEvent>> {
+        let _lock = self.lock()?;
+        let mut file = self.load()?;
+        if file.events.is_empty() {
+            return Ok(Vec::new());
+        }
+        let events = std::mem::take(&mut file.events);
+        self.save(&file)?;
+        Ok(events)
+    }
+
+    /// One-shot jobs that have run, oldest first.
+    pub fn archived(&self) -> Result<Vec<ArchivedCronJob>> {
+        Ok(self.load()?.archived)
+    }
+
+    /// Move a one-shot job that has run to the archive. Runtime jobs are
+    /// removed; config.toml jobs stay in the config but are no longer
+    /// scheduled.
+    pub fn archive(&self, name: &str, status: CronRunStatus) -> Result<()> {
+        let _lock = self.lock()?;
+        let mut file = self.load()?;
+        let (job, source) = match file.jobs.iter().position(|j| j.name == name) {
+            Some(index) => (file.jobs.remove(index), CronJobSource::Runtime),
+            None => (self.find(&file, name)?.clone(), CronJobSource::Config),
+        };
+        file.paused.retain(|n| n != name);
+        file.run_requests.retain(|n| n != name);
+        file.events.retain(|e| e.job != name);
+        file.archived.push(ArchivedCronJob {
+            job,
+            source,
+            finished_at: chrono::Utc::now().to_rfc3339(),
+            status,
+        });
+        if file.archived.len() > MAX_ARCHIVED {
+            let excess = file.archived.len() - MAX_ARCHIVED;
+            file.archived.drain(..excess);
+        }
+        self.save(&file)
+    }
+
+    /// Names of paused jobs.
+    pub fn paused(&self) -> Result<Vec<String>> {
+        Ok(self.load()?.paused)
+    }
+
+    /// Pause a job: its scheduled runs are skipped until it is resumed.
+    /// Works for config.toml jobs too, without editing the config.
+    pub fn pause(&self, name: &str) -> Result<()> {
+        let _lock = self.lock()?;
+        let mut file = self.load()?;
+        self.find(&file, name)?;
+        if !file.paused.iter().any(|n| n == name) {
+            file.paused.push(name.to_string());
+            self.save(&file)?;
+        }
+        Ok(())
+    }
+
+    /// Resume a paused job from its next scheduled run.
+    pub fn resume(&self, name: &str) -> Result<()> {
+        let _lock = self.lock()?;
+        let mut file = self.load()?;
+        self.find(&file, name)?;
+        if !file.paused.iter().any(|n| n == name) {
+            bail!("Cron job '{}' is not paused", name);
+        }
+        file.paused.retain(|n| n != name);
+        self.save(&file)
+    }
+
+    /// Ask the daemon to run a job on its next scheduler tick, regardless
+    /// of its schedule or pause state.
+    pub fn request_run(&self, name: &str) -> Result<()> {
+        let _lock = self.lock()?;
+        let mut file = self.load()?;
+        if !self.find(&file, name)?.enabled {
+            bail!("Cron job '{}' is disabled; enable it first", name);
+        }
+        if !file.run_requests.iter().any(|n| n == name) {
+            file.run_requests.push(name.to_string());
+            self.save(&file)?;
+        }
+        Ok(())
+    }
+
+    /// Remove and return pending run requests.
+    pub fn take_run_requests(&self) -> Result<Vec<String>> {
+        let _lock = self.lock()?;
+        let mut file = self.load()?;
+        if file.run_requests.is_empty() {
+            return Ok(Vec::new());
+        }
+        let requests = std::mem::take(&mut file.run_requests);
+        self.save(&file)?;
+        Ok(requests)
+    }
+
+    /// Add a runtime job. Names must be unique across config and runtime jobs.
+    /// Relative one-shot schedules ("in 45 minutes") are resolved to "at"
+    /// times now.
+    pub fn add(&self, mut job: CronJob) -> Result<()> {
+        if let Some(at) = resolve_relative(&job.schedule, Local::now())? {
+            job.schedule = at;
+        }
+        validate_job(&job)?;
+        let _lock = self.lock()?;
+        let mut file = self.load()?;
+        if self.config_jobs.iter().any(|j| j.name == job.name)
+            || file.jobs.iter().any(|j| j.name == job.name)
+        {
+            bail!("Cron job '{}' already exists", job.name);
+        }
+        file.jobs.push(job);
+        self.save(&file)
+    }
+
+    /// Remove a runtime job, returning it.
+    pub fn remove(&self, name: &str) -> Result<CronJob> {
+        let _lock = self.lock()?;
+        let mut file = self.load()?;
+        let index = self.runtime_index(&file, name)?;
+        let job = file.jobs.remove(index);
+        file.paused.retain(|n| *n != job.name);
+        file.run_requests.retain(|n| *n != job.name);
+        file.events.retain(|e| e.job != job.name);
+        self.save(&file)?;
+        Ok(job)
+    }
+
+    /// Apply `update` to a runtime job, returning the updated job.
+    pub fn update(&self, name: &str, update: CronJobUpdate) -> Result<CronJob> {
+        let _lock = self.lock()?;
+        let mut file = self.load()?;
+        let index = self.runtime_index(&file, name)?;
+
+        let mut job = file.jobs[index].clone();
+        if let Some(schedule) = update.schedule {
+            job.schedule = resolve_relative(&schedule, Local::now())?.unwrap_or(schedule);
+        }
+        if let Some(prompt) = update.prompt {
+            job.prompt = prompt;
+        }
+        if let Some(channel) = update.channel {
+            job.channel = Some(channel).filter(|c| !c.is_empty());
+        }
+        if let Some(enabled) = update.enabled {
+            job.enabled = enabled;
+        }
+        if let Some(timeout) = update.timeout {
+            job.timeout = timeout;
+        }
+        if let Some(catch_up) = update.catch_up {
+            job.catch_up = catch_up;
+        }
+        if let Some(jitter) = update.jitter {
+            job.jitter = Some(jitter).filter(|j| !j.is_empty());
+        }
+        if let Some(model) = update.model {
+            job.model = Some(model).filter(|m| !m.is_empty());
+        }
+        if let Some(tools) = update.tools {
+            job.tools = tools;
+        }
+        if let Some(skills) = update.skills {
+            job.skills = skills;
+        }
+        if let Some(namespace) = update.memory_namespace {
+            job.memory_namespace = Some(namespace).filter(|n| !n.is_empty());
+        }
+        if let Some(max_attempts) = update.max_attempts {
+            job.retry.max_attempts = max_attempts;
+        }
+        if let Some(backoff) = update.retry_backoff {
+            job.retry.backoff = backoff;
+        }
+        if update.remove_trigger {
+            job.trigger = None;
+        }
+        if let Some(trigger) = update.trigger {
+            job.trigger = Some(trigger);
+        }
+        validate_job(&job)?;
+
+        file.jobs[index] = job.clone();
+        self.save(&file)?;
+        Ok(job)
+    }
+
+    /// Enable or disable a runtime job.
+    pub fn set_enabled(&self, name: &str, enabled: bool) -> Result<CronJob> {
+        self.update(
+            name,
+            CronJobUpdate {
+                enabled: Some(enabled),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Config jobs, except one-shot jobs that were archived after running.
+    fn active_config_jobs<'a>(&'a self, file: &'a JobsFile) -> impl Iterator<Item = &'a CronJob> {
+        self.config_jobs.iter().filter(|job| {
+            !file.archived.iter().any(|a| {
+                a.source == CronJobSource::Config
+                    && a.job.name == job.name
+                    && a.job.schedule == job.schedule
+            })
+        })
+    }
+
+    /// Look up a config or runtime job by name.
+    fn find<'a>(&'a self, file: &'a JobsFile, name: &str) -> Result<&'a CronJob> {
+        self.active_config_jobs(file)
+            .chain(file.jobs.iter())
+            .find(|j| j.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Cron job '{}' not found", name))
+    }
+
+    fn runtime_index(&self, file: &JobsFile, name: &str) -> Result<usize> {
+        if let Some(index) = file.jobs.iter().position(|j| j.name == name) {
+            return Ok(index);
+        }
+        if self.config_jobs.iter().any(|j| j.name == name) {
+            bail!(
+                "Cron job '{}' is defined in config.toml; edit it there",
+                name
+            );
+        }
+        bail!("Cron job '{}' not found", name)
+    }
+
+    fn load(&self) -> Result<JobsFile> {
+        if !self.path.exists() {
+            return Ok(JobsFile::default());
+        }
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Writes go to a temporary file that replaces the jobs file, so a
+    /// reader never sees a partial one.
+    fn save(&self, file: &JobsFile) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(file)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Exclusive lock held across a load-modify-save, so changes from the
+    /// CLI, HTTP API, bridges, and scheduler don't overwrite each other.
+    /// Released when the returned file is dropped.
+    fn lock(&self) -> Result<File> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let lock = File::create(self.path.with_extension("json.lock"))?;
+        lock.lock_exclusive()?;
+        Ok(lock)
+    }
+}
+
+/// Upper bound on `retry.max_attempts`.
+const MAX_ATTEMPTS: u32 = 10;
+
+fn validate_job(job: &CronJob) -> Result<()> {
+    if job.name.trim().is_empty() {
+        bail!("Cron job name cannot be empty");
+    }
+    if job.prompt.trim().is_empty() {
+        bail!("Cron job '{}' has an empty prompt", job.name);
+    }
+    if !job.schedule.trim().is_empty() {
+        Schedule::parse(&job.schedule)?;
+    } else if job.trigger.is_none() {
+        bail!("Cron job '{}' needs a schedule or a trigger", job.name);
+    }
+    match &job.trigger {
+        Some(JobTrigger::File { path, pattern }) => {
+            if path.trim().is_empty() {
+                bail!("Cron job '{}': file trigger needs a directory", job.name);
+            }
+            if let Some(pattern) = pattern {
+                glob::Pattern::new(pattern)
+                    .map_err(|e| anyhow::anyhow!("Invalid file pattern '{}': {}", pattern, e))?;
+            }
+        }
+        Some(JobTrigger::Webhook { secret }) if secret.as_deref().is_none_or(str::is_empty) => {
+            bail!("Cron job '{}': webhook trigger needs a secret", job.name);
+        }
+        _ => {}
+    }
+    crate::config::parse_duration(&job.timeout)
+        .map_err(|e| anyhow::anyhow!("Invalid timeout '{}': {}", job.timeout, e))?;
+    if let Some(jitter) = &job.jitter {
+        crate::config::parse_duration(jitter)
+            .map_err(|e| anyhow::anyhow!("Invalid jitter '{}': {}", jitter, e))?;
+    }
+    if !(1..=MAX_ATTEMPTS).contains(&job.retry.max_attempts) {
+        bail!(
+            "Cron job '{}': max_attempts must be between 1 and {}",
+            job.name,
+            MAX_ATTEMPTS
+        );
+    }
+    crate::config::parse_duration(&job.retry.backoff)
+        .map_err(|e| anyhow::anyhow!("Invalid retry backoff '{}': {}", job.retry.backoff, e))?;
+    if let Some(namespace) = &job.memory_namespace
+        && !namespace
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "Invalid memory namespace '{}': use letters, digits, '-' and '_'",
+            namespace
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(name: &str, schedule: &str) -> CronJob {
+        CronJob {
+            name: name.to_string(),
+            schedule: schedule.to_string(),
+            prompt: "Check the inbox".to_string(),
+            channel: None,
+            enabled: true,
+            timeout: "10m".to_string(),
+            catch_up: CatchUpPolicy::Skip,
+            jitter: None,
+            model: None,
+            tools: Vec::new(),
+            skills: Vec::new(),
+            memory_namespace: None,
+            retry: Default::default(),
+            trigger: None,
+        }
+    }
+
+    #[test]
+    fn upcoming_runs_are_merged_and_sorted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = CronJobStore::new(
+            tmp.path().join("cron-jobs.json"),
+            vec![job("daily", "0 9 * * *")],
+        );
+        store.add(job("often", "0 */6 * * *")).unwrap();
+        store.add(job("held", "0 * * * *")).unwrap();
+        store.pause("held").unwrap();
+
+        let runs = store.upcoming(None, 6).unwrap();
+        assert_eq!(runs.len(), 6);
+        assert!(runs.windows(2).all(|w| w[0].at <= w[1].at));
+        assert!(runs.iter().any(|r| r.job == "daily"));
+        assert!(runs.iter().all(|r| r.job != "held"));
+
+        let daily = store.upcoming(Some("daily"), 3).unwrap();
+        assert_eq!(daily.len(), 3);
+        // A day apart, give or take a DST change
+        let gap = daily[1].at - daily[0].at;
+        assert!((23..=25).contains(&gap.num_hours()));
+
+        assert!(store.upcoming(Some("held"), 3).unwrap().is_empty());
+        assert!(store.upcoming(Some("missing"), 3).is_err());
+    }
+
+    #[test]
+    fn runtime_job_crud() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = CronJobStore::new(
+            tmp.path().join("cron-jobs.json"),
+            vec![job("static", "every 1h")],
+        );
+
+        store.add(job("inbox", "every 30m")).unwrap();
+        assert!(store.add(job("inbox", "every 1h")).is_err());
+        assert!(store.add(job("static", "every 1h")).is_err());
+        assert!(store.add(job("bad", "not a schedule")).is_err());
+
+        let listed = store.list().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].source, CronJobSource::Config);
+        assert_eq!(listed[1].source, CronJobSource::Runtime);
+        assert!(listed[1].next_run.is_some());
+
+        let updated = store
+            .update(
+                "inbox",
+                CronJobUpdate {
+                    schedule: Some("0 9 * * *".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(updated.schedule, "0 9 * * *");
+
+        let updated = store
+            .update(
+                "inbox",
+                CronJobUpdate {
+                    model: Some("claude-cli/opus".to_string()),
+                    tools: Some(vec!["memory_*".to_string(), "bash".to_string()]),
+                    memory_namespace: Some("analysis".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(updated.model.as_deref(), Some("claude-cli/opus"));
+        assert_eq!(updated.tools.len(), 2);
+        assert_eq!(updated.memory_namespace.as_deref(), Some("analysis"));
+        let bad_namespace = CronJobUpdate {
+            memory_namespace: Some("../escape".to_string()),
+            ..Default::default()
+        };
+        assert!(store.update("inbox", bad_namespace).is_err());
+        let retry = CronJobUpdate {
+            max_attempts: Some(3),
+            retry_backoff: Some("2m".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(store.update("inbox", retry).unwrap().retry.max_attempts, 3);
+        let too_many = CronJobUpdate {
+            max_attempts: Some(0),
+            ..Default::default()
+        };
+        assert!(store.update("inbox", too_many).is_err());
+
+        let disabled = store.set_enabled("inbox", false).unwrap();
+        assert!(!disabled.enabled);
+        assert!(store.list().unwrap()[1].next_run.is_none());
+
+        // Config jobs are read-only at runtime
+        assert!(store.remove("static").is_err());
+        assert!(store.set_enabled("static", false).is_err());
+
+        store.remove("inbox").unwrap();
+        assert_eq!(store.jobs().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn pause_resume_and_run_requests() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = CronJobStore::new(
+            tmp.path().join("cron-jobs.json"),
+            vec![job("static", "every 1h")],
+        );
+        store.add(job("inbox", "every 30m")).unwrap();
+
+        // Config jobs can be paused without editing config.toml
+        store.pause("static").unwrap();
+        store.pause("static").unwrap();
+        assert_eq!(store.paused().unwrap(), vec!["static".to_string()]);
+        let listed = store.list().unwrap();
+        assert!(listed[0].paused);
+        assert!(listed[0].next_run.is_none());
+        assert!(!listed[1].paused);
+
+        store.resume("static").unwrap();
+        assert!(store.resume("static").is_err());
+        assert!(store.pause("missing").is_err());
+
+        store.request_run("inbox").unwrap();
+        store.request_run("inbox").unwrap();
+        assert_eq!(
+            store.take_run_requests().unwrap(),
+            vec!["inbox".to_string()]
+        );
+        assert!(store.take_run_requests().unwrap().is_empty());
+
+        store.set_enabled("inbox", false).unwrap();
+        assert!(store.request_run("inbox").is_err());
+    }
+
+    #[test]
+    fn one_shot_jobs_are_archived() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = CronJobStore::new(
+            tmp.path().join("cron-jobs.json"),
+            vec![job("launch", "at 2025-07-01 09:00")],
+        );
+
+        store.add(job("reminder", "in 45 minutes")).unwrap();
+        let reminder = &store.jobs().unwrap()[1];
+        assert!(reminder.schedule.starts_with("at "));
+        // Overdue one-shot jobs still report a run time
+        assert!(store.list().unwrap()[0].next_run.is_some());
+
+        store.archive("reminder", CronRunStatus::Ok).unwrap();
+        store.archive("launch", CronRunStatus::Error).unwrap();
+        assert!(store.jobs().unwrap().is_empty());
+        assert!(store.list().unwrap().is_empty());
+
+        let archived = store.archived().unwrap();
+        assert_eq!(archived.len(), 2);
+        assert_eq!(archived[0].source, CronJobSource::Runtime);
+        assert_eq!(archived[1].source, CronJobSource::Config);
+        assert_eq!(archived[1].status, CronRunStatus::Error);
+    }
+
+    #[test]
+    fn webhook_events_are_queued() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = CronJobStore::new(tmp.path().join("cron-jobs.json"), Vec::new());
+
+        let mut hooked = job("deploy", "");
+        hooked.trigger = Some(JobTrigger::Webhook {
+            secret: Some("s3cret".to_string()),
+        });
+        store.add(hooked).unwrap();
+        store.add(job("inbox", "every 30m")).unwrap();
+        assert!(store.add(job("nothing", "")).is_err());
+        let mut open = job("open", "");
+        open.trigger = Some(JobTrigger::Webhook { secret: None });
+        assert!(store.add(open).is_err());
+
+        assert!(
+            store
+                .push_webhook_event("deploy", Some("wrong"), "{}".to_string())
+                .is_err()
+        );
+        assert!(
+            store
+                .push_webhook_event("deploy", None, "{}".to_string())
+                .is_err()
+        );
+        assert!(
+            store
+                .push_webhook_event("inbox", None, "{}".to_string())
+                .is_err()
+        );
+
+        store
+            .push_webhook_event("deploy", Some("s3cret"), "v1.2".to_string())
+            .unwrap();
+        let events = store.take_events().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].detail, "v1.2");
+        assert!(store.take_events().unwrap().is_empty());
+    }
+
+    #[test]
+    fn concurrent_webhook_events_are_not_lost() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("cron-jobs.json");
+        let store = CronJobStore::new(path.clone(), Vec::new());
+        let mut hooked = job("deploy", "");
+        hooked.trigger = Some(JobTrigger::Webhook {
+            secret: Some("s3cret".to_string()),
+        });
+        store.add(hooked).unwrap();
+
+        let pushers: Vec<_> = (0..2)
+            .map(|n| {
+                let store = CronJobStore::new(path.clone(), Vec::new());
+                std::thread::spawn(move || {
+                    for i in 0..20 {
+                        store
+                            .push_webhook_event("deploy", Some("s3cret"), format!("{}-{}", n, i))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        let taker = {
+            let store = CronJobStore::new(path.clone(), Vec::new());
+            std::thread::spawn(move || {
+                let mut taken = Vec::new();
+                for _ in 0..20 {
+                    taken.extend(store.take_events().unwrap());
+                }
+                taken
+            })
+        };
+        for pusher in pushers {
+            pusher.join().unwrap();
+        }
+        let mut details: Vec<_> = taker
+            .join()
+            .unwrap()
+            .into_iter()
+            .chain(store.take_events().unwrap())
+            .map(|event| event.detail)
+            .collect();
+        // Every event is taken exactly once
+        assert_eq!(details.len(), 40);
+        details.sort();
+        details.dedup();
+        assert_eq!(details.len(), 40);
+    }
+}
@@ -0,0 +1,156 @@
+//! Event triggers for cron jobs.
+//!
+//! File and memory triggers are polled on each scheduler tick: the first
+//! poll records a baseline, and later polls report what changed since.
+//! Webhook events arrive through the jobs file instead (see
+//! [`super::CronJobStore::push_webhook_event`]).
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::warn;
+
+use crate::config::JobTrigger;
+
+/// Poll state for one job's trigger.
+#[derive(Clone)]
+pub(super) struct TriggerWatch {
+    pub trigger: JobTrigger,
+    baseline: Option<Baseline>,
+}
+
+#[derive(Clone)]
+enum Baseline {
+    /// Files seen in the watched directory
+    Files(HashSet<PathBuf>),
+    /// Latest modification time of the memory files
+    Memory(Option<SystemTime>),
+}
+
+impl TriggerWatch {
+    pub fn new(trigger: JobTrigger) -> Self {
+        Self {
+            trigger,
+            baseline: None,
+        }
+    }
+
+    /// Events since the last poll, oldest first. The first poll only
+    /// records a baseline, so existing files don't trigger a run.
+    pub fn poll(&mut self, workspace: &Path) -> Vec<String> {
+        let current = match self.snapshot(workspace) {
+            Some(current) => current,
+            None => return Vec::new(),
+        };
+        let events = match (&self.baseline, &current) {
+            (Some(Baseline::Files(seen)), Baseline::Files(now)) => {
+                let mut new: Vec<&PathBuf> = now.difference(seen).collect();
+                new.sort();
+                new.iter().map(|p| p.display().to_string()).collect()
+            }
+            (Some(Baseline::Memory(before)), Baseline::Memory(now)) if now != before => {
+                vec!["memory files changed".to_string()]
+            }
+            _ => Vec::new(),
+        };
+        self.baseline = Some(current);
+        events
+    }
+
+    /// Take a new memory baseline, so changes a job makes to memory during
+    /// its own run don't trigger it again.
+    pub fn rebaseline(&mut self, workspace: &Path) {
+        if matches!(self.trigger, JobTrigger::Memory) {
+            self.baseline = self.snapshot(workspace);
+        }
+    }
+
+    fn snapshot(&self, workspace: &Path) -> Option<Baseline> {
+        match &self.trigger {
+            JobTrigger::File { path, pattern } => {
+                let dir = PathBuf::from(shellexpand::tilde(path).to_string());
+                Some(Baseline::Files(list_files(&dir, pattern.as_deref())))
+            }
+            JobTrigger::Memory => Some(Baseline::Memory(memory_modified(workspace))),
+            JobTrigger::Webhook { .. } => None,
+        }
+    }
+}
+
+/// Non-hidden files directly in `dir` whose names match `pattern`.
+fn list_files(dir: &Path, pattern: Option<&str>) -> HashSet<PathBuf> {
+    let pattern = pattern.and_then(|p| match glob::Pattern::new(p) {
+        Ok(pattern) => Some(pattern),
+        Err(e) => {
+            warn!("Ignoring invalid file trigger pattern '{}': {}", p, e);
+            None
+        }
+    });
+    let Ok(entries) = fs::read_dir(dir) else {
+        return HashSet::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|e| {
+            let name = e.file_name();
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && pattern.as_ref().is_none_or(|p| p.matches(&name))
+        })
+        .map(|e| e.path())
+        .collect()
+}
+
+/// Latest modification time of MEMORY.md and memory/*.md.
+fn memory_modified(workspace: &Path) -> Option<SystemTime> {
+    let mut files = vec![workspace.join("MEMORY.md")];
+    if let Ok(entries) = fs::read_dir(workspace.join("memory")) {
+        files.extend(
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "md")),
+        );
+    }
+    files
+        .iter()
+        .filter_map(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_trigger_reports_new_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("old.pdf"), "").unwrap();
+        let mut watch = TriggerWatch::new(JobTrigger::File {
+            path: tmp.path().to_string_lossy().to_string(),
+            pattern: Some("*.pdf".to_string()),
+        });
+
+        assert!(watch.poll(tmp.path()).is_empty());
+
+        fs::write(tmp.path().join("new.pdf"), "").unwrap();
+        fs::write(tmp.path().join("notes.txt"), "").unwrap();
+        fs::write(tmp.path().join(".hidden.pdf"), "").unwrap();
+        let events = watch.poll(tmp.path());
+        assert_eq!(events.len(), 1);
+        assert!(events[0].ends_with("new.pdf"));
+        assert!(watch.poll(tmp.path()).is_empty());
+    }
+
+    #[test]
+    fn memory_trigger_reports_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut watch = TriggerWatch::new(JobTrigger::Memory);
+        assert!(watch.poll(tmp.path()).is_empty());
+
+        fs::write(tmp.path().join("MEMORY.md"), "# Memory").unwrap();
+        assert_eq!(watch.poll(tmp.path()).len(), 1);
+        assert!(watch.poll(tmp.path()).is_empty());
+    }
+}
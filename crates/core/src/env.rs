@@ -18,7 +18,7 @@ pub const LOCALGPT_CACHE_DIR: &str = "LOCALGPT_CACHE_DIR";
 /// Workspace directory absolute override
 pub const LOCALGPT_WORKSPACE: &str = "LOCALGPT_WORKSPACE";
 
-/// Workspace profile override (creates `workspace-{profile}`)
+/// Profile name: suffixes every directory (`localgpt-{profile}`) for complete isolation
 pub const LOCALGPT_PROFILE: &str = "LOCALGPT_PROFILE";
 
 /// Configuration file path override (CLI arg default env)
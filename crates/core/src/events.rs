@@ -0,0 +1,95 @@
+//! In-process bus of daemon-wide events.
+//!
+//! Every event passed to [`crate::webhooks::emit`] is also published here,
+//! so the HTTP server can stream it at `/api/events`. Publishing never
+//! blocks; a subscriber that falls behind skips the oldest events.
+
+use std::sync::LazyLock;
+
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::webhooks::WebhookEvent;
+
+/// Events buffered for each subscriber before it starts missing them.
+const BUS_CAPACITY: usize = 256;
+
+static BUS: LazyLock<broadcast::Sender<DaemonEvent>> =
+    LazyLock::new(|| broadcast::channel(BUS_CAPACITY).0);
+
+/// A published event.
+#[derive(Debug, Clone)]
+pub struct DaemonEvent {
+    /// Dotted event name, e.g. `cron.finished`
+    pub name: &'static str,
+    /// `{event, timestamp, data}`, as POSTed to webhooks
+    pub payload: Value,
+}
+
+/// Publish `event` to current subscribers. Dropped if there are none.
+pub fn publish(event: &WebhookEvent) {
+    let _ = BUS.send(DaemonEvent {
+        name: event.name(),
+        payload: event.payload(),
+    });
+}
+
+/// Receive events published from now on.
+pub fn subscribe() -> broadcast::Receiver<DaemonEvent> {
+    BUS.subscribe()
+}
+
+/// Whether event `name` passes `filter`: empty matches everything, and
+/// entries are exact names, `*`, or a prefix such as `bridge.*`.
+pub fn matches_filter(filter: &[String], name: &str) -> bool {
+    filter.is_empty()
+        || filter.iter().any(|f| {
+            f == "*"
+                || f == name
+                || f.strip_suffix(".*").is_some_and(|prefix| {
+                    name.strip_prefix(prefix)
+                        .is_some_and(|rest| rest.starts_with('.'))
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn filter_matching() {
+        assert!(matches_filter(&[], "cron.finished"));
+        assert!(matches_filter(&filter(&["*"]), "bridge.health"));
+        assert!(matches_filter(&filter(&["bridge.*"]), "bridge.connected"));
+        assert!(!matches_filter(&filter(&["bridge.*"]), "bridges.connected"));
+        assert!(matches_filter(
+            &filter(&["session.created", "cron.finished"]),
+            "cron.finished"
+        ));
+        assert!(!matches_filter(
+            &filter(&["cron.finished"]),
+            "session.created"
+        ));
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_published_events() {
+        let mut rx = subscribe();
+        publish(&WebhookEvent::SessionCreated {
+            session_id: "s1".to_string(),
+            channel: "http".to_string(),
+        });
+        // Other tests may publish concurrently; find ours
+        loop {
+            let event = rx.recv().await.unwrap();
+            if event.name == "session.created" && event.payload["data"]["session_id"] == "s1" {
+                break;
+            }
+        }
+    }
+}
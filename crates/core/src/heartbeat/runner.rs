@@ -361,7 +361,7 @@ impl HeartbeatRunner {
         // Try to acquire the in-process turn gate (non-blocking, race between
         // the is_busy check above and now)
         let _gate_permit = if let Some(ref gate) = self.turn_gate {
-            match gate.try_acquire() {
+            match gate.try_acquire("heartbeat") {
                 Some(permit) => Some(permit),
                 None => {
                     info!(name: "Heartbeat", "skipping: agent turn started between check and acquire");
@@ -399,6 +399,7 @@ impl HeartbeatRunner {
         // Wrap cloned memory in Arc for sharing with spawn_agent tool
         let memory = Arc::new(self.memory.clone());
         let mut agent = Agent::new(agent_config, &self.config, Arc::clone(&memory)).await?;
+        agent.set_channel("heartbeat");
 
         // Extend agent with additional tools from factory if provided (e.g., CLI tools from daemon)
         if let Some(ref factory) = self.tool_factory {
@@ -33,6 +33,12 @@ pub enum HookEvent {
         session_id: String,
         turn_count: usize,
     },
+    /// Fired when a cron job run fails after its last retry
+    OnCronFailure {
+        job: String,
+        attempts: u32,
+        error: String,
+    },
 }
 
 impl HookEvent {
@@ -44,6 +50,7 @@ impl HookEvent {
             HookEvent::OnMessage { .. } => "on_message",
             HookEvent::OnSessionStart { .. } => "on_session_start",
             HookEvent::OnSessionEnd { .. } => "on_session_end",
+            HookEvent::OnCronFailure { .. } => "on_cron_failure",
         }
     }
 
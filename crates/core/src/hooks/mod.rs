@@ -6,6 +6,7 @@
 //! - on_message: When a user message is received
 //! - on_session_start: When a session is created
 //! - on_session_end: When a session ends
+//! - on_cron_failure: When a cron job fails after its last retry
 //!
 //! Hook definitions are JSON files in:
 //! - workspace/hooks/*.hook.json
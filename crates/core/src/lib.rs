@@ -10,11 +10,14 @@ pub mod concurrency;
 pub mod config;
 pub mod cron;
 pub mod env;
+pub mod events;
 pub mod heartbeat;
 pub mod hooks;
 pub mod mcp;
 pub mod memory;
 pub mod paths;
+pub mod readiness;
 pub mod security;
+pub mod webhooks;
 
 pub use config::Config;
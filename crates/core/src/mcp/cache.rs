@@ -0,0 +1,130 @@
+//! On-disk cache of MCP servers' tool schemas.
+//!
+//! With `mcp.lazy_connect`, tools are registered from this cache at startup
+//! so slow servers don't delay boot; the server itself is connected in the
+//! background. Entries expire after `mcp.schema_cache_ttl` and are keyed by
+//! a fingerprint of the server's launch config, so editing the config
+//! forces a fresh discovery.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::client::McpToolDef;
+use crate::config::McpServerConfig;
+
+const CACHE_FILENAME: &str = "mcp-tools.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedServer {
+    server: String,
+    fingerprint: String,
+    /// Unix timestamp of the discovery
+    cached_at: i64,
+    tools: Vec<McpToolDef>,
+}
+
+/// Cached tools of `server`, unless missing, expired, or from a different
+/// config.
+pub fn load_tools(
+    state_dir: &Path,
+    server: &McpServerConfig,
+    ttl: Duration,
+    now: i64,
+) -> Option<Vec<McpToolDef>> {
+    let fingerprint = fingerprint(server);
+    read_cache(state_dir)
+        .into_iter()
+        .find(|c| c.server == server.name)
+        .filter(|c| c.fingerprint == fingerprint)
+        .filter(|c| now.saturating_sub(c.cached_at) < ttl.as_secs() as i64)
+        .map(|c| c.tools)
+}
+
+/// Replace the cached tools of `server`.
+pub fn store_tools(
+    state_dir: &Path,
+    server: &McpServerConfig,
+    tools: &[McpToolDef],
+    now: i64,
+) -> Result<()> {
+    let mut cache = read_cache(state_dir);
+    cache.retain(|c| c.server != server.name);
+    cache.push(CachedServer {
+        server: server.name.clone(),
+        fingerprint: fingerprint(server),
+        cached_at: now,
+        tools: tools.to_vec(),
+    });
+    fs::write(cache_path(state_dir), serde_json::to_string(&cache)?)
+        .context("Failed to write MCP schema cache")
+}
+
+/// Get the full path to the schema cache.
+pub fn cache_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(CACHE_FILENAME)
+}
+
+/// A missing or corrupt cache is treated as empty.
+fn read_cache(state_dir: &Path) -> Vec<CachedServer> {
+    fs::read_to_string(cache_path(state_dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Hash of the settings that decide which server is launched.
+fn fingerprint(server: &McpServerConfig) -> String {
+    let mut env: Vec<_> = server.env.iter().collect();
+    env.sort();
+
+    let mut hasher = Sha256::new();
+    for part in [
+        server.transport.as_str(),
+        server.command.as_deref().unwrap_or(""),
+    ]
+    .into_iter()
+    .chain(server.args.iter().map(String::as_str))
+    .chain(env.iter().flat_map(|(k, v)| [k.as_str(), v.as_str()]))
+    .chain(server.url.as_deref())
+    {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(args: &str) -> McpServerConfig {
+        toml::from_str(&format!(
+            "name = \"fs\"\ncommand = \"mcp-fs\"\nargs = [\"{}\"]",
+            args
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn cached_tools_expire_and_follow_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ttl = Duration::from_secs(3600);
+        let tools = vec![McpToolDef {
+            name: "read_file".to_string(),
+            description: Some("Read a file".to_string()),
+            input_schema: None,
+        }];
+        store_tools(tmp.path(), &server("/srv"), &tools, 1000).unwrap();
+
+        let cached = load_tools(tmp.path(), &server("/srv"), ttl, 2000).unwrap();
+        assert_eq!(cached[0].name, "read_file");
+        // Expired
+        assert!(load_tools(tmp.path(), &server("/srv"), ttl, 1000 + 3600).is_none());
+        // Launched differently
+        assert!(load_tools(tmp.path(), &server("/home"), ttl, 2000).is_none());
+    }
+}
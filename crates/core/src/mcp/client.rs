@@ -1,12 +1,16 @@
 //! MCP client: handles JSON-RPC protocol lifecycle (initialize, list tools, call tool).
 
 use anyhow::Result;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::time::Duration;
 use tracing::{debug, info};
 
 use super::transport::Transport;
 
+/// How long a `ping` may take before the server is considered down.
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Information about the MCP server.
 #[derive(Debug, Deserialize)]
 pub struct ServerInfo {
@@ -17,7 +21,7 @@ pub struct ServerInfo {
 }
 
 /// An MCP tool definition returned by tools/list.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpToolDef {
     pub name: String,
     #[serde(default)]
@@ -50,11 +54,22 @@ pub struct McpClient {
 }
 
 impl McpClient {
-    /// Create a new MCP client and perform initialization handshake.
-    pub async fn connect(transport: Box<dyn Transport>, client_name: &str) -> Result<Self> {
+    /// Create a new MCP client and perform initialization handshake. With
+    /// `sampling`, the client advertises the sampling capability; requests
+    /// are served by the transport's request handler.
+    pub async fn connect(
+        transport: Box<dyn Transport>,
+        client_name: &str,
+        sampling: bool,
+    ) -> Result<Self> {
+        let capabilities = if sampling {
+            json!({ "sampling": {} })
+        } else {
+            json!({})
+        };
         let init_params = json!({
             "protocolVersion": "2024-11-05",
-            "capabilities": {},
+            "capabilities": capabilities,
             "clientInfo": {
                 "name": client_name,
                 "version": env!("CARGO_PKG_VERSION"),
@@ -113,6 +128,14 @@ impl McpClient {
         Ok(tool_result)
     }
 
+    /// Check that the server is still responding.
+    pub async fn ping(&self) -> Result<()> {
+        tokio::time::timeout(PING_TIMEOUT, self.transport.request("ping", None))
+            .await
+            .map_err(|_| anyhow::anyhow!("MCP server '{}' ping timed out", self.server_name))??;
+        Ok(())
+    }
+
     /// Shut down the client and underlying transport.
     pub async fn shutdown(&self) -> Result<()> {
         self.transport.shutdown().await
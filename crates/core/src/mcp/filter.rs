@@ -0,0 +1,208 @@
+//! Per-server restrictions on MCP tools.
+//!
+//! A server's `tools` patterns decide which discovered tools are
+//! registered; its `arg_filters` are checked against each call's arguments
+//! before it is sent. Together they let a powerful server (e.g. a
+//! filesystem server) be exposed read-only or confined to some directories.
+
+use anyhow::{Result, bail};
+use serde_json::Value;
+use std::path::{Component, Path, PathBuf};
+use tracing::warn;
+
+use crate::config::{McpArgFilter, McpServerConfig};
+
+/// Tool and argument restrictions for one server.
+#[derive(Debug, Clone)]
+pub struct ToolFilter {
+    server: String,
+    tools: Vec<glob::Pattern>,
+    args: Vec<ArgFilter>,
+}
+
+#[derive(Debug, Clone)]
+struct ArgFilter {
+    tool: glob::Pattern,
+    arg: String,
+    path_prefixes: Vec<PathBuf>,
+    values: Vec<String>,
+}
+
+impl ToolFilter {
+    /// Build the filter from a server's config. Invalid patterns are errors,
+    /// so a typo can't silently expose every tool.
+    pub fn new(server: &McpServerConfig) -> Result<Self> {
+        let pattern = |p: &str| {
+            glob::Pattern::new(p).map_err(|e| {
+                anyhow::anyhow!(
+                    "MCP server '{}': invalid tool pattern '{}': {}",
+                    server.name,
+                    p,
+                    e
+                )
+            })
+        };
+        let tools = server
+            .tools
+            .iter()
+            .map(|p| pattern(p))
+            .collect::<Result<_>>()?;
+        let args = server
+            .arg_filters
+            .iter()
+            .map(|f: &McpArgFilter| {
+                Ok(ArgFilter {
+                    tool: pattern(&f.tool)?,
+                    arg: f.arg.clone(),
+                    path_prefixes: f
+                        .path_prefixes
+                        .iter()
+                        .map(|p| PathBuf::from(shellexpand::tilde(p).to_string()))
+                        .collect(),
+                    values: f.values.clone(),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            server: server.name.clone(),
+            tools,
+            args,
+        })
+    }
+
+    /// Whether the server's tool `name` should be registered.
+    pub fn allows_tool(&self, name: &str) -> bool {
+        self.tools.is_empty() || self.tools.iter().any(|p| p.matches(name))
+    }
+
+    /// Check a call's arguments against the filters for `tool`. Missing
+    /// arguments pass; array arguments are checked element by element.
+    pub fn check_arguments(&self, tool: &str, arguments: &Value) -> Result<()> {
+        for filter in self.args.iter().filter(|f| f.tool.matches(tool)) {
+            let values = match arguments.get(&filter.arg) {
+                None | Some(Value::Null) => continue,
+                Some(Value::Array(items)) => items.iter().collect(),
+                Some(value) => vec![value],
+            };
+            for value in values {
+                let Some(value) = value.as_str() else {
+                    bail!(
+                        "Argument '{}' of MCP tool '{}' must be a string",
+                        filter.arg,
+                        tool
+                    );
+                };
+                if let Err(e) = filter.check(value) {
+                    warn!(
+                        "Blocked call to MCP tool '{}' on server '{}': {}",
+                        tool, self.server, e
+                    );
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ArgFilter {
+    fn check(&self, value: &str) -> Result<()> {
+        if !self.values.is_empty() && !self.values.iter().any(|v| v == value) {
+            bail!(
+                "'{}' is not an allowed value for argument '{}' (allowed: {})",
+                value,
+                self.arg,
+                self.values.join(", ")
+            );
+        }
+        if !self.path_prefixes.is_empty() {
+            let path = PathBuf::from(shellexpand::tilde(value).to_string());
+            let inside = is_contained(&path)
+                && self
+                    .path_prefixes
+                    .iter()
+                    .any(|prefix| path.starts_with(prefix));
+            if !inside {
+                bail!(
+                    "Path '{}' for argument '{}' is outside the allowed directories",
+                    value,
+                    self.arg
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Absolute and free of `..`, so a prefix match means the path is really
+/// inside the prefix.
+fn is_contained(path: &Path) -> bool {
+    path.is_absolute() && !path.components().any(|c| c == Component::ParentDir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn server(toml: &str) -> McpServerConfig {
+        toml::from_str(&format!("name = \"fs\"\ncommand = \"mcp-fs\"\n{}", toml)).unwrap()
+    }
+
+    #[test]
+    fn allowlist_selects_tools() {
+        let all = ToolFilter::new(&server("")).unwrap();
+        assert!(all.allows_tool("write_file"));
+
+        let read_only =
+            ToolFilter::new(&server("tools = [\"read_*\", \"list_directory\"]")).unwrap();
+        assert!(read_only.allows_tool("read_file"));
+        assert!(read_only.allows_tool("list_directory"));
+        assert!(!read_only.allows_tool("write_file"));
+
+        assert!(ToolFilter::new(&server("tools = [\"[\"]")).is_err());
+    }
+
+    #[test]
+    fn path_prefixes_confine_arguments() {
+        let filter = ToolFilter::new(&server(
+            "[[arg_filters]]\narg = \"path\"\npath_prefixes = [\"/srv/notes\"]",
+        ))
+        .unwrap();
+
+        let ok = |args: Value| filter.check_arguments("read_file", &args).is_ok();
+        assert!(ok(json!({ "path": "/srv/notes/todo.md" })));
+        assert!(ok(json!({ "paths": ["/etc/passwd"] })));
+        assert!(ok(
+            json!({ "path": ["/srv/notes/a.md", "/srv/notes/b.md"] })
+        ));
+        assert!(!ok(json!({ "path": "/srv/notes-private/x" })));
+        assert!(!ok(json!({ "path": "/srv/notes/../secrets" })));
+        assert!(!ok(json!({ "path": "notes/todo.md" })));
+        assert!(!ok(json!({ "path": ["/srv/notes/a.md", "/etc/passwd"] })));
+        assert!(!ok(json!({ "path": 42 })));
+    }
+
+    #[test]
+    fn value_filters_apply_to_matching_tools() {
+        let filter = ToolFilter::new(&server(
+            "[[arg_filters]]\ntool = \"query\"\narg = \"db\"\nvalues = [\"analytics\"]",
+        ))
+        .unwrap();
+        assert!(
+            filter
+                .check_arguments("query", &json!({ "db": "analytics" }))
+                .is_ok()
+        );
+        assert!(
+            filter
+                .check_arguments("query", &json!({ "db": "users" }))
+                .is_err()
+        );
+        assert!(
+            filter
+                .check_arguments("export", &json!({ "db": "users" }))
+                .is_ok()
+        );
+    }
+}
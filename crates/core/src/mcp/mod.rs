@@ -2,24 +2,40 @@
 //!
 //! Connects to external MCP servers via stdio or HTTP/SSE transports,
 //! discovers their tools, and exposes them as LocalGPT `Tool` instances.
+//! Discovered tools are cached so later startups can register them without
+//! waiting for the servers (see [`cache`]).
+//! Servers with a `sampling` policy may also request completions (see
+//! [`sampling`]), and HTTP servers may require OAuth login (see [`oauth`]).
+//! Connections are health-checked and reconnected when a server dies (see
+//! [`supervisor`]), and each server's tools and their arguments can be
+//! restricted (see [`filter`]).
 
+pub mod cache;
 pub mod client;
+pub mod filter;
+pub mod oauth;
+pub mod sampling;
+pub mod supervisor;
 pub mod tools;
 pub mod transport;
 
 use anyhow::Result;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{info, warn};
 
 use crate::agent::tools::Tool;
-use crate::config::McpServerConfig;
+use crate::config::{Config, McpServerConfig, SamplingPolicy, parse_duration};
 use client::McpClient;
+use filter::ToolFilter;
+use sampling::SamplingHandler;
+use supervisor::McpConnection;
 use tools::McpTool;
 use transport::{HttpSseTransport, StdioTransport};
 
 /// Manager that owns all MCP client connections.
 pub struct McpManager {
-    clients: Vec<Arc<McpClient>>,
+    connections: Vec<Arc<McpConnection>>,
 }
 
 impl McpManager {
@@ -27,31 +43,70 @@ impl McpManager {
     /// the manager plus a flat list of Tool instances.
     ///
     /// Failing servers are logged as warnings but don't prevent other servers
-    /// from connecting.
-    pub async fn connect_all(servers: &[McpServerConfig]) -> Result<(Self, Vec<Box<dyn Tool>>)> {
-        let mut clients = Vec::new();
+    /// from connecting. With `mcp.lazy_connect`, servers with fresh cached
+    /// schemas are connected in the background instead.
+    pub async fn connect_all(
+        servers: &[McpServerConfig],
+        config: &Config,
+    ) -> Result<(Self, Vec<Box<dyn Tool>>)> {
+        let mut connections = Vec::new();
         let mut all_tools: Vec<Box<dyn Tool>> = Vec::new();
+        let ttl = parse_duration(&config.mcp.schema_cache_ttl).unwrap_or_else(|e| {
+            warn!("Invalid mcp.schema_cache_ttl: {}", e);
+            Duration::ZERO
+        });
+        let now = chrono::Utc::now().timestamp();
 
         for server in servers {
-            match connect_server(server).await {
-                Ok((client, tools)) => {
+            // Without a valid filter the server could expose everything
+            let filter = match ToolFilter::new(server) {
+                Ok(filter) => Arc::new(filter),
+                Err(e) => {
+                    warn!("Skipping MCP server '{}': {}", server.name, e);
+                    continue;
+                }
+            };
+            let cached = config
+                .mcp
+                .lazy_connect
+                .then(|| cache::load_tools(&config.paths.state_dir, server, ttl, now))
+                .flatten();
+            let connected = match cached {
+                Some(tools) => {
                     info!(
-                        "MCP server '{}': {} tools discovered",
+                        "MCP server '{}': {} cached tools, connecting in background",
                         server.name,
                         tools.len()
                     );
-                    let client = Arc::new(client);
-                    // Create McpTool wrappers
-                    for tool_def in &tools {
+                    Ok((
+                        McpConnection::lazy(server, config, tools.clone()).await,
+                        tools,
+                    ))
+                }
+                None => McpConnection::connect(server, config)
+                    .await
+                    .inspect(|(_, tools)| {
+                        info!(
+                            "MCP server '{}': {} tools discovered",
+                            server.name,
+                            tools.len()
+                        );
+                    }),
+            };
+            match connected {
+                Ok((connection, tools)) => {
+                    // Create McpTool wrappers for the allowed tools
+                    for tool_def in tools.iter().filter(|t| filter.allows_tool(&t.name)) {
                         all_tools.push(Box::new(McpTool::new(
                             &server.name,
                             &tool_def.name,
                             tool_def.description.as_deref().unwrap_or(""),
                             tool_def.input_schema.clone(),
-                            client.clone(),
+                            connection.clone(),
+                            filter.clone(),
                         )));
                     }
-                    clients.push(client);
+                    connections.push(connection);
                 }
                 Err(e) => {
                     warn!("Failed to connect MCP server '{}': {}", server.name, e);
@@ -59,16 +114,16 @@ impl McpManager {
             }
         }
 
-        Ok((McpManager { clients }, all_tools))
+        Ok((McpManager { connections }, all_tools))
     }
 
     /// Gracefully shut down all MCP connections.
     pub async fn shutdown(&self) {
-        for client in &self.clients {
-            if let Err(e) = client.shutdown().await {
+        for connection in &self.connections {
+            if let Err(e) = connection.shutdown().await {
                 warn!(
                     "Error shutting down MCP client '{}': {}",
-                    client.server_name(),
+                    connection.server_name(),
                     e
                 );
             }
@@ -76,7 +131,12 @@ impl McpManager {
     }
 }
 
-async fn connect_server(config: &McpServerConfig) -> Result<(McpClient, Vec<client::McpToolDef>)> {
+/// Connect to one server without supervision and discover its tools. Used
+/// by [`McpConnection`] and by `localgpt mcp` diagnostics.
+pub async fn connect_server(
+    config: &McpServerConfig,
+    app_config: &Config,
+) -> Result<(McpClient, Vec<client::McpToolDef>)> {
     let transport: Box<dyn transport::Transport> = match config.transport.as_str() {
         "stdio" => {
             let command = config.command.as_deref().ok_or_else(|| {
@@ -94,7 +154,18 @@ async fn connect_server(config: &McpServerConfig) -> Result<(McpClient, Vec<clie
                     config.name
                 )
             })?;
-            Box::new(HttpSseTransport::new(url)?)
+            let transport = HttpSseTransport::new(url)?;
+            // Servers logged in with `localgpt mcp login` get bearer tokens
+            if config.oauth.is_some()
+                || matches!(
+                    oauth::load_tokens(&app_config.paths.data_dir, &config.name),
+                    Ok(Some(_))
+                )
+            {
+                Box::new(transport.with_oauth(app_config.paths.data_dir.clone(), &config.name))
+            } else {
+                Box::new(transport)
+            }
         }
         other => {
             anyhow::bail!(
@@ -105,7 +176,18 @@ async fn connect_server(config: &McpServerConfig) -> Result<(McpClient, Vec<clie
         }
     };
 
-    let client = McpClient::connect(transport, "localgpt").await?;
+    let sampling = config.sampling != SamplingPolicy::Deny
+        && transport
+            .set_request_handler(Arc::new(SamplingHandler::new(config, app_config)))
+            .await;
+    if config.sampling != SamplingPolicy::Deny && !sampling {
+        warn!(
+            "MCP server '{}': sampling requires the stdio transport",
+            config.name
+        );
+    }
+
+    let client = McpClient::connect(transport, "localgpt", sampling).await?;
     let tools = client.list_tools().await?;
 
     Ok((client, tools))
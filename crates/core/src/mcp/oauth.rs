@@ -0,0 +1,458 @@
+//! OAuth 2.1 for HTTP MCP servers.
+//!
+//! `localgpt mcp login <server>` runs the authorization-code flow with PKCE.
+//! Endpoints come from the server's `/.well-known/oauth-authorization-server`
+//! metadata, falling back to `/authorize`, `/token`, and `/register` on the
+//! server's origin. Without a configured `client_id`, LocalGPT registers
+//! itself dynamically.
+//!
+//! Tokens are encrypted with a key derived from the device key and stored
+//! in `mcp-tokens/<server>.enc` in the data directory. The HTTP transport
+//! sends the access token and refreshes it shortly before it expires.
+
+use anyhow::{Context, Result, bail};
+use base64::{Engine as _, engine::general_purpose};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, Nonce,
+    aead::{Aead, KeyInit},
+};
+use hmac::{Hmac, Mac};
+use rand::RngExt;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+use crate::config::McpServerConfig;
+use crate::security::read_device_key;
+
+/// Refresh access tokens this many seconds before they expire.
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// Authorization server endpoints.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthServerMetadata {
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub registration_endpoint: Option<String>,
+}
+
+/// Stored tokens for one server, with what's needed to refresh them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpTokens {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Unix timestamp (seconds) when the access token expires
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    pub token_endpoint: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+}
+
+impl McpTokens {
+    /// Whether the access token expires within the refresh margin.
+    pub fn expiring(&self, now: i64) -> bool {
+        self.expires_at
+            .is_some_and(|at| now + EXPIRY_MARGIN_SECS >= at)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+/// A login waiting for the authorization code from the redirect.
+pub struct PendingLogin {
+    /// URL to open in the browser
+    pub authorize_url: String,
+    /// Expected `state` on the redirect
+    pub state: String,
+    /// Local port the redirect goes to
+    pub redirect_port: u16,
+    server: String,
+    verifier: String,
+    redirect_uri: String,
+    token_endpoint: String,
+    client_id: String,
+    client_secret: Option<String>,
+}
+
+/// Fetch the authorization server metadata for an MCP server URL.
+pub async fn discover(client: &reqwest::Client, server_url: &str) -> Result<AuthServerMetadata> {
+    let origin = origin(server_url)?;
+    let url = format!("{}/.well-known/oauth-authorization-server", origin);
+    match client.get(&url).send().await {
+        Ok(response) if response.status().is_success() => response
+            .json()
+            .await
+            .context("Invalid OAuth authorization server metadata"),
+        _ => {
+            debug!("No OAuth metadata at {}; using default endpoints", url);
+            Ok(default_metadata(&origin))
+        }
+    }
+}
+
+/// Start a login: discover endpoints, register a client if needed, and
+/// build the authorization URL.
+pub async fn begin_login(server: &McpServerConfig) -> Result<PendingLogin> {
+    let url = server
+        .url
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("MCP server '{}' has no url", server.name))?;
+    let oauth = server.oauth.clone().unwrap_or_default();
+    let client = reqwest::Client::new();
+    let metadata = discover(&client, url).await?;
+    let redirect_uri = format!("http://localhost:{}/callback", oauth.redirect_port);
+
+    let (client_id, client_secret) = match &oauth.client_id {
+        Some(id) => (id.clone(), oauth.client_secret.clone()),
+        None => register_client(&client, &metadata, &redirect_uri).await?,
+    };
+
+    let (challenge, verifier) = pkce_pair();
+    let state = random_string(32);
+    let mut authorize_url =
+        Url::parse(&metadata.authorization_endpoint).context("Invalid authorization endpoint")?;
+    {
+        let mut query = authorize_url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("code_challenge", &challenge)
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("state", &state);
+        if !oauth.scopes.is_empty() {
+            query.append_pair("scope", &oauth.scopes.join(" "));
+        }
+    }
+
+    Ok(PendingLogin {
+        authorize_url: authorize_url.to_string(),
+        state,
+        redirect_port: oauth.redirect_port,
+        server: server.name.clone(),
+        verifier,
+        redirect_uri,
+        token_endpoint: metadata.token_endpoint,
+        client_id,
+        client_secret,
+    })
+}
+
+impl PendingLogin {
+    /// Exchange the authorization code for tokens and store them.
+    pub async fn complete(self, data_dir: &Path, code: &str) -> Result<McpTokens> {
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("client_id", self.client_id.as_str()),
+            ("code_verifier", self.verifier.as_str()),
+        ];
+        if let Some(secret) = &self.client_secret {
+            params.push(("client_secret", secret.as_str()));
+        }
+        let response = request_tokens(&self.token_endpoint, &params).await?;
+        let tokens = McpTokens {
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            expires_at: response
+                .expires_in
+                .map(|secs| chrono::Utc::now().timestamp() + secs),
+            token_endpoint: self.token_endpoint,
+            client_id: self.client_id,
+            client_secret: self.client_secret,
+        };
+        save_tokens(data_dir, &self.server, &tokens)?;
+        info!("Stored OAuth tokens for MCP server '{}'", self.server);
+        Ok(tokens)
+    }
+}
+
+/// Current access token for `server`, refreshed and re-stored if it is about
+/// to expire.
+pub async fn access_token(data_dir: &Path, server: &str) -> Result<String> {
+    let Some(mut tokens) = load_tokens(data_dir, server)? else {
+        bail!(
+            "MCP server '{}' requires login: run `localgpt mcp login {}`",
+            server,
+            server
+        );
+    };
+    if !tokens.expiring(chrono::Utc::now().timestamp()) {
+        return Ok(tokens.access_token);
+    }
+
+    let Some(refresh_token) = tokens.refresh_token.clone() else {
+        bail!(
+            "OAuth token for MCP server '{}' expired: run `localgpt mcp login {}`",
+            server,
+            server
+        );
+    };
+    debug!("Refreshing OAuth token for MCP server '{}'", server);
+    let mut params = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token.as_str()),
+        ("client_id", tokens.client_id.as_str()),
+    ];
+    if let Some(secret) = &tokens.client_secret {
+        params.push(("client_secret", secret.as_str()));
+    }
+    let response = request_tokens(&tokens.token_endpoint, &params)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to refresh OAuth token for MCP server '{}'; run `localgpt mcp login {}`",
+                server, server
+            )
+        })?;
+    tokens.access_token = response.access_token;
+    // Servers may rotate refresh tokens
+    if response.refresh_token.is_some() {
+        tokens.refresh_token = response.refresh_token;
+    }
+    tokens.expires_at = response
+        .expires_in
+        .map(|secs| chrono::Utc::now().timestamp() + secs);
+    save_tokens(data_dir, server, &tokens)?;
+    Ok(tokens.access_token)
+}
+
+async fn request_tokens(token_endpoint: &str, params: &[(&str, &str)]) -> Result<TokenResponse> {
+    let response = reqwest::Client::new()
+        .post(token_endpoint)
+        .header("Accept", "application/json")
+        .form(params)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("OAuth token request failed ({}): {}", status, body);
+    }
+    response
+        .json()
+        .await
+        .context("Invalid OAuth token response")
+}
+
+/// Dynamic client registration (RFC 7591).
+async fn register_client(
+    client: &reqwest::Client,
+    metadata: &AuthServerMetadata,
+    redirect_uri: &str,
+) -> Result<(String, Option<String>)> {
+    let Some(endpoint) = &metadata.registration_endpoint else {
+        bail!(
+            "The authorization server doesn't support client registration; \
+             set oauth.client_id for this MCP server"
+        );
+    };
+    let response = client
+        .post(endpoint)
+        .json(&json!({
+            "client_name": "LocalGPT",
+            "redirect_uris": [redirect_uri],
+            "grant_types": ["authorization_code", "refresh_token"],
+            "response_types": ["code"],
+            "token_endpoint_auth_method": "none",
+        }))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("OAuth client registration failed ({}): {}", status, body);
+    }
+
+    #[derive(Deserialize)]
+    struct Registration {
+        client_id: String,
+        #[serde(default)]
+        client_secret: Option<String>,
+    }
+    let registration: Registration = response
+        .json()
+        .await
+        .context("Invalid client registration response")?;
+    Ok((registration.client_id, registration.client_secret))
+}
+
+/// `scheme://host[:port]` of `url`.
+fn origin(url: &str) -> Result<String> {
+    let url = Url::parse(url).with_context(|| format!("Invalid MCP server url '{}'", url))?;
+    Ok(url.origin().ascii_serialization())
+}
+
+fn default_metadata(origin: &str) -> AuthServerMetadata {
+    AuthServerMetadata {
+        authorization_endpoint: format!("{}/authorize", origin),
+        token_endpoint: format!("{}/token", origin),
+        registration_endpoint: Some(format!("{}/register", origin)),
+    }
+}
+
+fn random_string(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::rng().fill(&mut bytes[..]);
+    general_purpose::URL_SAFE_NO_PAD.encode(&bytes)
+}
+
+/// PKCE `(challenge, verifier)` using S256.
+fn pkce_pair() -> (String, String) {
+    let verifier = random_string(64);
+    let challenge = general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    (challenge, verifier)
+}
+
+fn tokens_path(data_dir: &Path, server: &str) -> Result<PathBuf> {
+    if server.is_empty()
+        || !server
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "MCP server name '{}' can't be used for token storage (use letters, digits, '-', '_')",
+            server
+        );
+    }
+    Ok(data_dir.join("mcp-tokens").join(format!("{}.enc", server)))
+}
+
+/// Key = HMAC-SHA256(device key, "mcp-oauth:" + server).
+fn token_key(data_dir: &Path, server: &str) -> Result<Key> {
+    let device_key = read_device_key(data_dir)?;
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&device_key)
+        .map_err(|e| anyhow::anyhow!("HMAC init failed: {}", e))?;
+    mac.update(b"mcp-oauth:");
+    mac.update(server.as_bytes());
+    Ok(*Key::from_slice(&mac.finalize().into_bytes()))
+}
+
+/// Encrypt and store tokens as `[nonce (12 bytes)][ciphertext]`.
+pub fn save_tokens(data_dir: &Path, server: &str, tokens: &McpTokens) -> Result<()> {
+    let path = tokens_path(data_dir, server)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let cipher = ChaCha20Poly1305::new(&token_key(data_dir, server)?);
+    let mut nonce_bytes = [0u8; 12];
+    rand::rng().fill(&mut nonce_bytes);
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce_bytes),
+            serde_json::to_vec(tokens)?.as_slice(),
+        )
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+
+    let mut content = nonce_bytes.to_vec();
+    content.extend(ciphertext);
+    fs::write(&path, content)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// Load stored tokens, if any.
+pub fn load_tokens(data_dir: &Path, server: &str) -> Result<Option<McpTokens>> {
+    let path = tokens_path(data_dir, server)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read(&path)?;
+    if content.len() < 12 {
+        bail!("Invalid token file for MCP server '{}'", server);
+    }
+    let (nonce_bytes, ciphertext) = content.split_at(12);
+    let cipher = ChaCha20Poly1305::new(&token_key(data_dir, server)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|e| anyhow::anyhow!("Decryption failed: {}", e))?;
+    Ok(Some(serde_json::from_slice(&plaintext)?))
+}
+
+/// Delete stored tokens. Returns whether there were any.
+pub fn delete_tokens(data_dir: &Path, server: &str) -> Result<bool> {
+    let path = tokens_path(data_dir, server)?;
+    if !path.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(&path)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(expires_at: Option<i64>) -> McpTokens {
+        McpTokens {
+            access_token: "access".to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_at,
+            token_endpoint: "https://mcp.example.com/token".to_string(),
+            client_id: "client".to_string(),
+            client_secret: None,
+        }
+    }
+
+    #[test]
+    fn stores_tokens_encrypted() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("localgpt.device.key"), [7u8; 32]).unwrap();
+
+        assert!(load_tokens(tmp.path(), "github").unwrap().is_none());
+        save_tokens(tmp.path(), "github", &tokens(Some(100))).unwrap();
+
+        let raw = fs::read(tmp.path().join("mcp-tokens/github.enc")).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("access"));
+        let loaded = load_tokens(tmp.path(), "github").unwrap().unwrap();
+        assert_eq!(loaded.access_token, "access");
+        assert_eq!(loaded.expires_at, Some(100));
+
+        assert!(save_tokens(tmp.path(), "../escape", &tokens(None)).is_err());
+        assert!(delete_tokens(tmp.path(), "github").unwrap());
+        assert!(!delete_tokens(tmp.path(), "github").unwrap());
+    }
+
+    #[test]
+    fn refreshes_shortly_before_expiry() {
+        assert!(!tokens(None).expiring(1_000));
+        assert!(!tokens(Some(2_000)).expiring(1_000));
+        assert!(tokens(Some(1_030)).expiring(1_000));
+    }
+
+    #[test]
+    fn falls_back_to_default_endpoints() {
+        let origin = origin("https://mcp.example.com:8443/v1/mcp").unwrap();
+        assert_eq!(origin, "https://mcp.example.com:8443");
+        let metadata = default_metadata(&origin);
+        assert_eq!(
+            metadata.token_endpoint,
+            "https://mcp.example.com:8443/token"
+        );
+
+        let (challenge, verifier) = pkce_pair();
+        assert_ne!(challenge, verifier);
+        assert_eq!(challenge.len(), 43);
+    }
+}
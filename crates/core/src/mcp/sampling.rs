@@ -0,0 +1,378 @@
+//! MCP sampling: serves `sampling/createMessage` requests, letting MCP
+//! servers request completions from LocalGPT's configured providers.
+//!
+//! Each server's `sampling` policy decides whether requests are refused
+//! (`deny`, the default; the capability isn't advertised), confirmed by the
+//! registered [`SamplingApprover`] (`ask`), or served (`allow`). Every
+//! request is appended to `localgpt.mcp-sampling.jsonl` in the state
+//! directory with its token usage, so spend is attributed to the server
+//! that asked for it (`localgpt audit sampling`).
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tracing::{info, warn};
+
+use super::transport::RequestHandler;
+use crate::agent::providers::{LLMResponseContent, Message, Role, create_provider};
+use crate::config::{Config, McpServerConfig, SamplingPolicy};
+
+const SAMPLING_LOG_FILENAME: &str = "localgpt.mcp-sampling.jsonl";
+
+/// Maximum characters of the request shown when asking for approval.
+const MAX_PREVIEW_CHARS: usize = 500;
+
+/// Confirms sampling requests from servers with `sampling = "ask"`.
+pub trait SamplingApprover: Send + Sync {
+    /// Whether `server` may run a completion. `preview` is the last
+    /// message of the request, truncated.
+    fn approve(&self, server: &str, preview: &str) -> bool;
+}
+
+static APPROVER: OnceLock<Arc<dyn SamplingApprover>> = OnceLock::new();
+
+/// Register the approver for `ask` servers. Interactive interfaces call this
+/// once at startup; without one, `ask` requests are denied.
+pub fn set_sampling_approver(approver: Arc<dyn SamplingApprover>) {
+    if APPROVER.set(approver).is_err() {
+        warn!("MCP sampling approver already registered");
+    }
+}
+
+/// Serves one server's sampling requests.
+pub struct SamplingHandler {
+    server: String,
+    policy: SamplingPolicy,
+    model: String,
+    config: Config,
+}
+
+impl SamplingHandler {
+    pub fn new(server: &McpServerConfig, config: &Config) -> Self {
+        Self {
+            server: server.name.clone(),
+            policy: server.sampling,
+            model: server
+                .sampling_model
+                .clone()
+                .unwrap_or_else(|| config.agent.default_model.clone()),
+            config: config.clone(),
+        }
+    }
+
+    async fn create_message(&self, params: Value) -> Result<Value> {
+        let request: CreateMessageRequest =
+            serde_json::from_value(params).context("Invalid sampling request")?;
+        let messages = request.to_messages()?;
+
+        if !self.approved(&messages).await? {
+            self.record(SamplingOutcome::Denied, 0, 0, None);
+            bail!("Sampling request denied by LocalGPT");
+        }
+
+        info!(
+            "MCP server '{}' sampling {} message(s) with {}",
+            self.server,
+            messages.len(),
+            self.model
+        );
+        let response = match create_provider(&self.model, &self.config) {
+            Ok(provider) => provider.chat(&messages, None).await,
+            Err(e) => Err(e),
+        };
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                self.record(SamplingOutcome::Error, 0, 0, Some(e.to_string()));
+                return Err(e);
+            }
+        };
+
+        let usage = response.usage.unwrap_or_default();
+        self.record(
+            SamplingOutcome::Ok,
+            usage.input_tokens,
+            usage.output_tokens,
+            None,
+        );
+        let text = match response.content {
+            LLMResponseContent::Text(text) => text,
+            LLMResponseContent::ToolCalls { text, .. } => text.unwrap_or_default(),
+        };
+        Ok(json!({
+            "role": "assistant",
+            "content": { "type": "text", "text": text },
+            "model": self.model,
+            "stopReason": "endTurn",
+        }))
+    }
+
+    async fn approved(&self, messages: &[Message]) -> Result<bool> {
+        match self.policy {
+            SamplingPolicy::Allow => Ok(true),
+            SamplingPolicy::Deny => Ok(false),
+            SamplingPolicy::Ask => {
+                let Some(approver) = APPROVER.get().cloned() else {
+                    warn!(
+                        "Denied sampling request from MCP server '{}': no one to ask",
+                        self.server
+                    );
+                    return Ok(false);
+                };
+                let server = self.server.clone();
+                let preview: String = messages
+                    .last()
+                    .map(|m| m.content.chars().take(MAX_PREVIEW_CHARS).collect())
+                    .unwrap_or_default();
+                // Approvers may block on user input
+                let approved =
+                    tokio::task::spawn_blocking(move || approver.approve(&server, &preview));
+                Ok(approved.await?)
+            }
+        }
+    }
+
+    fn record(
+        &self,
+        outcome: SamplingOutcome,
+        input_tokens: u64,
+        output_tokens: u64,
+        error: Option<String>,
+    ) {
+        let entry = SamplingLogEntry {
+            ts: chrono::Utc::now().to_rfc3339(),
+            server: self.server.clone(),
+            model: self.model.clone(),
+            outcome,
+            input_tokens,
+            output_tokens,
+            error,
+        };
+        if let Err(e) = append_sampling_log(&self.config.paths.state_dir, &entry) {
+            warn!("Failed to record MCP sampling request: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl RequestHandler for SamplingHandler {
+    async fn handle(&self, method: &str, params: Value) -> Result<Value> {
+        match method {
+            "sampling/createMessage" => self.create_message(params).await,
+            other => bail!("Unsupported MCP request '{}'", other),
+        }
+    }
+}
+
+/// `sampling/createMessage` parameters. `maxTokens` and model preferences
+/// are ignored; the server's `sampling_model` is used.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateMessageRequest {
+    messages: Vec<SamplingMessage>,
+    #[serde(default)]
+    system_prompt: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SamplingMessage {
+    role: String,
+    content: SamplingContent,
+}
+
+#[derive(Debug, Deserialize)]
+struct SamplingContent {
+    #[serde(rename = "type")]
+    content_type: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+impl CreateMessageRequest {
+    fn to_messages(&self) -> Result<Vec<Message>> {
+        if self.messages.is_empty() {
+            bail!("Sampling request has no messages");
+        }
+        let mut messages = Vec::new();
+        if let Some(system) = self.system_prompt.as_deref().filter(|s| !s.is_empty()) {
+            messages.push(text_message(Role::System, system));
+        }
+        for message in &self.messages {
+            let role = match message.role.as_str() {
+                "user" => Role::User,
+                "assistant" => Role::Assistant,
+                other => bail!("Unsupported sampling message role '{}'", other),
+            };
+            let text = match (&message.content.content_type[..], &message.content.text) {
+                ("text", Some(text)) => text,
+                (other, _) => bail!("Unsupported sampling content type '{}'", other),
+            };
+            messages.push(text_message(role, text));
+        }
+        Ok(messages)
+    }
+}
+
+fn text_message(role: Role, content: &str) -> Message {
+    Message {
+        role,
+        content: content.to_string(),
+        tool_calls: None,
+        tool_call_id: None,
+        images: Vec::new(),
+    }
+}
+
+/// How a sampling request ended.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplingOutcome {
+    Ok,
+    Denied,
+    Error,
+}
+
+/// One sampling request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingLogEntry {
+    /// ISO 8601 timestamp of when the request finished
+    pub ts: String,
+    /// MCP server that made the request
+    pub server: String,
+    pub model: String,
+    pub outcome: SamplingOutcome,
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Sampling totals for one server.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SamplingUsage {
+    pub server: String,
+    /// Requests served, including failed ones
+    pub requests: u64,
+    pub denied: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// Append a request to the sampling log.
+pub fn append_sampling_log(state_dir: &Path, entry: &SamplingLogEntry) -> Result<()> {
+    let json = serde_json::to_string(entry).context("Failed to serialize sampling entry")?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(sampling_log_path(state_dir))
+        .context("Failed to open MCP sampling log")?;
+    writeln!(file, "{}", json).context("Failed to write MCP sampling log")?;
+    Ok(())
+}
+
+/// Read the sampling log, oldest first. Corrupted lines are skipped.
+pub fn read_sampling_log(state_dir: &Path) -> Result<Vec<SamplingLogEntry>> {
+    let path = sampling_log_path(state_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).context("Failed to read MCP sampling log")?;
+    Ok(content
+        .lines()
+        .filter(|l| !l.is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect())
+}
+
+/// Totals per server, in order of first request.
+pub fn usage_by_server(entries: &[SamplingLogEntry]) -> Vec<SamplingUsage> {
+    let mut usage: Vec<SamplingUsage> = Vec::new();
+    for entry in entries {
+        let index = match usage.iter().position(|u| u.server == entry.server) {
+            Some(index) => index,
+            None => {
+                usage.push(SamplingUsage {
+                    server: entry.server.clone(),
+                    ..Default::default()
+                });
+                usage.len() - 1
+            }
+        };
+        let totals = &mut usage[index];
+        match entry.outcome {
+            SamplingOutcome::Denied => totals.denied += 1,
+            SamplingOutcome::Ok | SamplingOutcome::Error => totals.requests += 1,
+        }
+        totals.input_tokens += entry.input_tokens;
+        totals.output_tokens += entry.output_tokens;
+    }
+    usage
+}
+
+/// Get the full path to the sampling log.
+pub fn sampling_log_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(SAMPLING_LOG_FILENAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_request_messages() {
+        let request: CreateMessageRequest = serde_json::from_value(json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Summarize" } },
+                { "role": "assistant", "content": { "type": "text", "text": "Sure" } }
+            ],
+            "systemPrompt": "Be brief",
+            "maxTokens": 100
+        }))
+        .unwrap();
+        let messages = request.to_messages().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].role, Role::System);
+        assert_eq!(messages[2].content, "Sure");
+
+        let image: CreateMessageRequest = serde_json::from_value(json!({
+            "messages": [{ "role": "user", "content": { "type": "image", "data": "" } }]
+        }))
+        .unwrap();
+        assert!(image.to_messages().is_err());
+    }
+
+    #[test]
+    fn totals_usage_per_server() {
+        let tmp = tempfile::tempdir().unwrap();
+        let entry = |server: &str, outcome, tokens| SamplingLogEntry {
+            ts: "2026-01-01T09:00:00Z".to_string(),
+            server: server.to_string(),
+            model: "claude-cli/opus".to_string(),
+            outcome,
+            input_tokens: tokens,
+            output_tokens: tokens / 2,
+            error: None,
+        };
+        for e in [
+            entry("github", SamplingOutcome::Ok, 100),
+            entry("notes", SamplingOutcome::Denied, 0),
+            entry("github", SamplingOutcome::Ok, 50),
+        ] {
+            append_sampling_log(tmp.path(), &e).unwrap();
+        }
+
+        let usage = usage_by_server(&read_sampling_log(tmp.path()).unwrap());
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].server, "github");
+        assert_eq!(usage[0].requests, 2);
+        assert_eq!(usage[0].input_tokens, 150);
+        assert_eq!(usage[1].denied, 1);
+    }
+}
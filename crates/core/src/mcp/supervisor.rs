@@ -0,0 +1,438 @@
+//! Supervised MCP connections.
+//!
+//! A [`McpConnection`] owns one server's client and restarts it when it
+//! dies. A tool call that fails on a connection that no longer answers
+//! `ping` reconnects and is retried once; a background health check pings
+//! the server every [`HEALTH_CHECK_INTERVAL`] and reconnects on failure.
+//! Reconnect attempts back off exponentially, and tools are re-discovered
+//! after each reconnect. Lazy connections start from cached tools and
+//! connect in the background (see [`super::cache`]).
+//!
+//! Connection state is recorded in `mcp-status.json` in the state directory
+//! for `localgpt mcp status` and `localgpt doctor`.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use super::client::{McpClient, McpToolDef, McpToolResult};
+use crate::config::{Config, McpServerConfig};
+
+const STATUS_FILENAME: &str = "mcp-status.json";
+
+/// How often connected servers are pinged.
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Upper bound on the delay between reconnect attempts.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(300);
+
+/// Connection state of an MCP server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionState {
+    /// Not connected yet; tools came from the schema cache
+    Idle,
+    Connected,
+    /// Down; reconnect attempts continue with backoff
+    Reconnecting,
+    /// Shut down by LocalGPT
+    Closed,
+}
+
+impl ConnectionState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectionState::Idle => "idle",
+            ConnectionState::Connected => "connected",
+            ConnectionState::Reconnecting => "reconnecting",
+            ConnectionState::Closed => "closed",
+        }
+    }
+}
+
+/// Recorded state of one server's connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerStatus {
+    pub server: String,
+    pub state: ConnectionState,
+    /// Tools discovered on the last successful connect
+    pub tools: usize,
+    /// Successful reconnects since the first connect
+    pub restarts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// RFC 3339 time of the last change
+    pub updated_at: String,
+    /// Process holding the connection
+    pub pid: u32,
+}
+
+/// One server's supervised connection.
+pub struct McpConnection {
+    server: McpServerConfig,
+    config: Config,
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    client: Option<Arc<McpClient>>,
+    tools: Vec<McpToolDef>,
+    /// Consecutive failed reconnect attempts
+    failures: u32,
+    /// Whether a connect ever succeeded; later connects are restarts
+    connected: bool,
+    restarts: u32,
+    retry_at: Option<Instant>,
+    last_error: Option<String>,
+    /// Shut down; no more reconnects
+    closed: bool,
+}
+
+impl McpConnection {
+    /// Connect to `server` and start its health check. Returns the
+    /// connection and the discovered tools.
+    pub async fn connect(
+        server: &McpServerConfig,
+        config: &Config,
+    ) -> Result<(Arc<Self>, Vec<McpToolDef>)> {
+        let (client, tools) = super::connect_server(server, config).await?;
+        store_cached_tools(server, config, &tools);
+        let connection = Arc::new(Self {
+            server: server.clone(),
+            config: config.clone(),
+            inner: Mutex::new(Inner {
+                client: Some(Arc::new(client)),
+                tools: tools.clone(),
+                failures: 0,
+                connected: true,
+                restarts: 0,
+                retry_at: None,
+                last_error: None,
+                closed: false,
+            }),
+        });
+        connection.record(&*connection.inner.lock().await, ConnectionState::Connected);
+
+        let weak = Arc::downgrade(&connection);
+        tokio::spawn(health_check(weak));
+        Ok((connection, tools))
+    }
+
+    /// Start from cached `tools` and connect in the background. Tool calls
+    /// made before the connect finishes wait for it.
+    pub async fn lazy(
+        server: &McpServerConfig,
+        config: &Config,
+        tools: Vec<McpToolDef>,
+    ) -> Arc<Self> {
+        let connection = Arc::new(Self {
+            server: server.clone(),
+            config: config.clone(),
+            inner: Mutex::new(Inner {
+                client: None,
+                tools,
+                failures: 0,
+                connected: false,
+                restarts: 0,
+                retry_at: None,
+                last_error: None,
+                closed: false,
+            }),
+        });
+        connection.record(&*connection.inner.lock().await, ConnectionState::Idle);
+
+        let warm = connection.clone();
+        tokio::spawn(async move {
+            let mut inner = warm.inner.lock().await;
+            if inner.client.is_none() && !inner.closed {
+                let _ = warm.reconnect(&mut inner).await;
+            }
+        });
+        tokio::spawn(health_check(Arc::downgrade(&connection)));
+        connection
+    }
+
+    pub fn server_name(&self) -> &str {
+        &self.server.name
+    }
+
+    /// Call a tool, reconnecting and retrying once if the server died.
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<McpToolResult> {
+        let client = self.client(name).await?;
+        match client.call_tool(name, arguments.clone()).await {
+            Ok(result) => Ok(result),
+            Err(e) if client.ping().await.is_err() => {
+                warn!(
+                    "MCP server '{}' stopped responding: {}",
+                    self.server.name, e
+                );
+                self.mark_down(&client, &e.to_string()).await;
+                let client = self.client(name).await?;
+                client.call_tool(name, arguments).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Shut down the client and stop supervising it.
+    pub async fn shutdown(&self) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        let client = inner.client.take();
+        inner.closed = true;
+        self.record(&inner, ConnectionState::Closed);
+        match client {
+            Some(client) => client.shutdown().await,
+            None => Ok(()),
+        }
+    }
+
+    /// The live client, reconnecting if it is down and the backoff has
+    /// passed. Fails if `tool` isn't provided after a reconnect.
+    async fn client(&self, tool: &str) -> Result<Arc<McpClient>> {
+        let mut inner = self.inner.lock().await;
+        if inner.closed {
+            bail!("MCP server '{}' was shut down", self.server.name);
+        }
+        if inner.client.is_none() {
+            if let Some(retry_at) = inner.retry_at
+                && retry_at > Instant::now()
+            {
+                bail!(
+                    "MCP server '{}' is down; reconnecting in {}s (last error: {})",
+                    self.server.name,
+                    retry_at.saturating_duration_since(Instant::now()).as_secs() + 1,
+                    inner.last_error.as_deref().unwrap_or("unknown")
+                );
+            }
+            self.reconnect(&mut inner).await?;
+        }
+        if !inner.tools.iter().any(|t| t.name == tool) {
+            bail!(
+                "MCP server '{}' no longer provides tool '{}'",
+                self.server.name,
+                tool
+            );
+        }
+        inner
+            .client
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("MCP server '{}' is down", self.server.name))
+    }
+
+    /// Drop `client` if it is still the current one.
+    async fn mark_down(&self, client: &Arc<McpClient>, error: &str) {
+        let mut inner = self.inner.lock().await;
+        if inner
+            .client
+            .as_ref()
+            .is_some_and(|c| Arc::ptr_eq(c, client))
+        {
+            inner.client = None;
+            inner.last_error = Some(error.to_string());
+            inner.retry_at = None;
+            self.record(&inner, ConnectionState::Reconnecting);
+            // Stop the old process; errors don't matter now
+            let _ = client.shutdown().await;
+        }
+    }
+
+    async fn reconnect(&self, inner: &mut Inner) -> Result<()> {
+        let action = if inner.connected {
+            "Reconnecting"
+        } else {
+            "Connecting"
+        };
+        info!("{} MCP server '{}'", action, self.server.name);
+        match super::connect_server(&self.server, &self.config).await {
+            Ok((client, tools)) => {
+                info!(
+                    "MCP server '{}' connected: {} tools",
+                    self.server.name,
+                    tools.len()
+                );
+                // Tools are registered once, at startup
+                if tools
+                    .iter()
+                    .any(|t| !inner.tools.iter().any(|o| o.name == t.name))
+                {
+                    info!(
+                        "MCP server '{}' offers new tools; they are available after a restart",
+                        self.server.name
+                    );
+                }
+                store_cached_tools(&self.server, &self.config, &tools);
+                inner.client = Some(Arc::new(client));
+                inner.tools = tools;
+                inner.failures = 0;
+                if inner.connected {
+                    inner.restarts += 1;
+                }
+                inner.connected = true;
+                inner.retry_at = None;
+                inner.last_error = None;
+                self.record(inner, ConnectionState::Connected);
+                Ok(())
+            }
+            Err(e) => {
+                inner.failures += 1;
+                let delay = reconnect_delay(inner.failures);
+                inner.retry_at = Some(Instant::now() + delay);
+                inner.last_error = Some(e.to_string());
+                self.record(inner, ConnectionState::Reconnecting);
+                warn!(
+                    "Failed to connect MCP server '{}' (retrying in {}s): {}",
+                    self.server.name,
+                    delay.as_secs(),
+                    e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Ping the server, or retry a reconnect that is due. Returns `false`
+    /// once the connection was shut down.
+    async fn check_health(&self) -> bool {
+        let client = {
+            let mut inner = self.inner.lock().await;
+            if inner.closed {
+                return false;
+            }
+            match inner.client.clone() {
+                Some(client) => client,
+                None => {
+                    if inner.retry_at.is_none_or(|at| at <= Instant::now()) {
+                        let _ = self.reconnect(&mut inner).await;
+                    }
+                    return true;
+                }
+            }
+        };
+        if let Err(e) = client.ping().await {
+            warn!(
+                "MCP server '{}' failed health check: {}",
+                self.server.name, e
+            );
+            self.mark_down(&client, &e.to_string()).await;
+            let mut inner = self.inner.lock().await;
+            if inner.client.is_none() && !inner.closed {
+                let _ = self.reconnect(&mut inner).await;
+            }
+        }
+        true
+    }
+
+    fn record(&self, inner: &Inner, state: ConnectionState) {
+        let status = ServerStatus {
+            server: self.server.name.clone(),
+            state,
+            tools: inner.tools.len(),
+            restarts: inner.restarts,
+            last_error: inner.last_error.clone(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+            pid: std::process::id(),
+        };
+        if let Err(e) = record_status(&self.config.paths.state_dir, status) {
+            warn!("Failed to record MCP server status: {}", e);
+        }
+    }
+}
+
+async fn health_check(connection: Weak<McpConnection>) {
+    loop {
+        tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+        // Stop once the connection's owners are gone
+        let Some(connection) = connection.upgrade() else {
+            break;
+        };
+        if !connection.check_health().await {
+            break;
+        }
+    }
+}
+
+fn store_cached_tools(server: &McpServerConfig, config: &Config, tools: &[McpToolDef]) {
+    let now = chrono::Utc::now().timestamp();
+    if let Err(e) = super::cache::store_tools(&config.paths.state_dir, server, tools, now) {
+        warn!(
+            "Failed to cache tools of MCP server '{}': {}",
+            server.name, e
+        );
+    }
+}
+
+/// Delay before reconnect attempt `failures + 1`: 1s, doubling, capped.
+fn reconnect_delay(failures: u32) -> Duration {
+    let secs = 1u64 << failures.saturating_sub(1).min(16);
+    Duration::from_secs(secs).min(MAX_RECONNECT_DELAY)
+}
+
+/// Get the full path to the MCP status file.
+pub fn status_file_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(STATUS_FILENAME)
+}
+
+/// Recorded status of each server, in order of first record.
+pub fn read_status(state_dir: &Path) -> Result<Vec<ServerStatus>> {
+    let path = status_file_path(state_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).context("Failed to read MCP status")?;
+    Ok(serde_json::from_str(&content).unwrap_or_default())
+}
+
+/// Replace the recorded status of `status.server`.
+pub fn record_status(state_dir: &Path, status: ServerStatus) -> Result<()> {
+    let mut all = read_status(state_dir)?;
+    match all.iter_mut().find(|s| s.server == status.server) {
+        Some(existing) => *existing = status,
+        None => all.push(status),
+    }
+    fs::write(
+        status_file_path(state_dir),
+        serde_json::to_string_pretty(&all)?,
+    )
+    .context("Failed to write MCP status")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnect_backoff_doubles_up_to_cap() {
+        assert_eq!(reconnect_delay(1), Duration::from_secs(1));
+        assert_eq!(reconnect_delay(2), Duration::from_secs(2));
+        assert_eq!(reconnect_delay(5), Duration::from_secs(16));
+        assert_eq!(reconnect_delay(40), MAX_RECONNECT_DELAY);
+    }
+
+    #[test]
+    fn records_latest_status_per_server() {
+        let tmp = tempfile::tempdir().unwrap();
+        let status = |server: &str, state| ServerStatus {
+            server: server.to_string(),
+            state,
+            tools: 3,
+            restarts: 0,
+            last_error: None,
+            updated_at: "2026-01-01T09:00:00Z".to_string(),
+            pid: 42,
+        };
+        record_status(tmp.path(), status("github", ConnectionState::Connected)).unwrap();
+        record_status(tmp.path(), status("notes", ConnectionState::Connected)).unwrap();
+        record_status(tmp.path(), status("github", ConnectionState::Reconnecting)).unwrap();
+
+        let all = read_status(tmp.path()).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].server, "github");
+        assert_eq!(all[0].state, ConnectionState::Reconnecting);
+    }
+}
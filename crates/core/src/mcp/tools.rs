@@ -5,7 +5,8 @@ use async_trait::async_trait;
 use serde_json::{Value, json};
 use std::sync::Arc;
 
-use super::client::McpClient;
+use super::filter::ToolFilter;
+use super::supervisor::McpConnection;
 use crate::agent::providers::ToolSchema;
 use crate::agent::tools::Tool;
 
@@ -17,7 +18,8 @@ pub struct McpTool {
     remote_name: String,
     description: String,
     parameters: Value,
-    client: Arc<McpClient>,
+    connection: Arc<McpConnection>,
+    filter: Arc<ToolFilter>,
 }
 
 impl McpTool {
@@ -26,7 +28,8 @@ impl McpTool {
         remote_name: &str,
         description: &str,
         parameters: Option<Value>,
-        client: Arc<McpClient>,
+        connection: Arc<McpConnection>,
+        filter: Arc<ToolFilter>,
     ) -> Self {
         // Sanitize server/tool names for safe tool naming
         let sanitized_server = server_name.replace(|c: char| !c.is_alphanumeric(), "_");
@@ -37,7 +40,8 @@ impl McpTool {
             remote_name: remote_name.to_string(),
             description: description.to_string(),
             parameters: parameters.unwrap_or_else(|| json!({"type": "object", "properties": {}})),
-            client,
+            connection,
+            filter,
         }
     }
 }
@@ -63,7 +67,9 @@ impl Tool for McpTool {
             serde_json::from_str(arguments)?
         };
 
-        let result = self.client.call_tool(&self.remote_name, args).await?;
+        self.filter.check_arguments(&self.remote_name, &args)?;
+
+        let result = self.connection.call_tool(&self.remote_name, args).await?;
 
         if result.is_error {
             let error_text = result
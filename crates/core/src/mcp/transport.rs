@@ -4,7 +4,9 @@ use anyhow::{Result, bail};
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
@@ -21,6 +23,19 @@ pub trait Transport: Send + Sync {
 
     /// Shut down the transport.
     async fn shutdown(&self) -> Result<()>;
+
+    /// Serve requests the server sends to the client (e.g. sampling) with
+    /// `handler`. Returns `false` if the transport can't receive them.
+    async fn set_request_handler(&self, _handler: Arc<dyn RequestHandler>) -> bool {
+        false
+    }
+}
+
+/// Handles JSON-RPC requests sent by the server to the client.
+#[async_trait]
+pub trait RequestHandler: Send + Sync {
+    /// Handle `method`; errors become JSON-RPC error responses.
+    async fn handle(&self, method: &str, params: Value) -> Result<Value>;
 }
 
 /// Stdio transport: communicates with an MCP server via stdin/stdout.
@@ -33,6 +48,7 @@ struct StdioInner {
     stdin: tokio::process::ChildStdin,
     reader: BufReader<tokio::process::ChildStdout>,
     next_id: u64,
+    handler: Option<Arc<dyn RequestHandler>>,
 }
 
 impl StdioTransport {
@@ -70,6 +86,7 @@ impl StdioTransport {
                 stdin,
                 reader: BufReader::new(stdout),
                 next_id: 1,
+                handler: None,
             }),
         })
     }
@@ -113,6 +130,30 @@ impl Transport for StdioTransport {
                 Err(_) => continue, // Skip non-JSON lines
             };
 
+            // A request from the server, made while it handles ours
+            if let (Some(method), Some(request_id)) = (
+                response.get("method").and_then(|m| m.as_str()),
+                response.get("id"),
+            ) {
+                let params = response.get("params").cloned().unwrap_or(Value::Null);
+                let reply = match inner.handler.clone() {
+                    Some(handler) => match handler.handle(method, params).await {
+                        Ok(result) => serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "id": request_id,
+                            "result": result,
+                        }),
+                        Err(e) => error_response(request_id, -32603, &e.to_string()),
+                    },
+                    None => error_response(request_id, -32601, "Method not found"),
+                };
+                let mut line = serde_json::to_string(&reply)?;
+                line.push('\n');
+                inner.stdin.write_all(line.as_bytes()).await?;
+                inner.stdin.flush().await?;
+                continue;
+            }
+
             // Check if this is a response to our request
             if response.get("id").and_then(|v| v.as_u64()) == Some(id) {
                 if let Some(error) = response.get("error") {
@@ -152,6 +193,19 @@ impl Transport for StdioTransport {
         inner.child.kill().await.ok();
         Ok(())
     }
+
+    async fn set_request_handler(&self, handler: Arc<dyn RequestHandler>) -> bool {
+        self.inner.lock().await.handler = Some(handler);
+        true
+    }
+}
+
+fn error_response(id: &Value, code: i64, message: &str) -> Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
 }
 
 /// HTTP/SSE transport: sends JSON-RPC requests via HTTP POST.
@@ -159,6 +213,8 @@ pub struct HttpSseTransport {
     client: reqwest::Client,
     url: String,
     next_id: Mutex<u64>,
+    /// Data directory and server name for OAuth tokens
+    oauth: Option<(PathBuf, String)>,
 }
 
 impl HttpSseTransport {
@@ -170,8 +226,33 @@ impl HttpSseTransport {
             client,
             url: url.to_string(),
             next_id: Mutex::new(1),
+            oauth: None,
         })
     }
+
+    /// Authenticate requests with the OAuth tokens stored for `server`.
+    pub fn with_oauth(mut self, data_dir: PathBuf, server: &str) -> Self {
+        self.oauth = Some((data_dir, server.to_string()));
+        self
+    }
+
+    async fn post(&self, body: &Value) -> Result<reqwest::Response> {
+        let mut request = self.client.post(&self.url).json(body);
+        if let Some((data_dir, server)) = &self.oauth {
+            request = request.bearer_auth(super::oauth::access_token(data_dir, server).await?);
+        }
+        let response = request.send().await?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            match &self.oauth {
+                Some((_, server)) => bail!(
+                    "MCP server rejected the OAuth token: run `localgpt mcp login {}`",
+                    server
+                ),
+                None => bail!("MCP server requires login: run `localgpt mcp login <server>`"),
+            }
+        }
+        Ok(response)
+    }
 }
 
 #[async_trait]
@@ -189,7 +270,7 @@ impl Transport for HttpSseTransport {
             "params": params.unwrap_or(Value::Object(serde_json::Map::new())),
         });
 
-        let response = self.client.post(&self.url).json(&request).send().await?;
+        let response = self.post(&request).await?;
 
         if !response.status().is_success() {
             bail!("MCP HTTP error: {}", response.status());
@@ -211,11 +292,7 @@ impl Transport for HttpSseTransport {
             "params": params.unwrap_or(Value::Object(serde_json::Map::new())),
         });
 
-        self.client
-            .post(&self.url)
-            .json(&notification)
-            .send()
-            .await?;
+        self.post(&notification).await?;
 
         Ok(())
     }
@@ -1,6 +1,7 @@
 use anyhow::{Result, anyhow};
 use rusqlite::{Connection, params};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
@@ -9,7 +10,7 @@ use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use super::embeddings::{cosine_similarity, deserialize_embedding, serialize_embedding};
-use super::search::MemoryChunk;
+use super::search::{ChunkMark, IndexedChunk, MemoryChunk};
 
 #[derive(Clone)]
 pub struct MemoryIndex {
@@ -32,6 +33,21 @@ pub struct ReindexStats {
     pub duration: Duration,
 }
 
+/// What a memory garbage collection removed (see `MemoryManager::gc`)
+#[derive(Debug, Default)]
+pub struct GcStats {
+    /// Files dropped from the index because they no longer exist
+    pub files_removed: usize,
+    /// Pins and archive marks whose chunk content is gone
+    pub marks_removed: usize,
+    /// Cached embeddings no chunk uses any more
+    pub cache_entries_removed: usize,
+    /// Full-text and vector rows left behind by deleted chunks
+    pub stale_rows_removed: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
 impl MemoryIndex {
     /// Create a new memory index with database at the specified path
     pub fn new_with_db_path(workspace: &Path, db_path: &Path) -> Result<Self> {
@@ -93,6 +109,16 @@ impl MemoryIndex {
                 PRIMARY KEY (provider, model, provider_key, hash)
             );
 
+            -- Pinned/archived chunks (LocalGPT extension), keyed by content
+            -- so marks survive reindexing
+            CREATE TABLE IF NOT EXISTS chunk_marks (
+                path TEXT NOT NULL,
+                hash TEXT NOT NULL,
+                mark TEXT NOT NULL,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (path, hash)
+            );
+
             -- Indexes
             CREATE INDEX IF NOT EXISTS idx_chunks_path ON chunks(path);
             CREATE INDEX IF NOT EXISTS idx_chunks_source ON chunks(source);
@@ -322,6 +348,175 @@ impl MemoryIndex {
         Ok(paths)
     }
 
+    /// Indexed files with their chunk counts, by path
+    pub fn indexed_file_chunks(&self) -> Result<Vec<(String, usize)>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT f.path, COUNT(c.id) FROM files f
+             LEFT JOIN chunks c ON c.path = f.path
+             GROUP BY f.path ORDER BY f.path",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+
+        let mut files = Vec::new();
+        for row in rows {
+            files.push(row?);
+        }
+        Ok(files)
+    }
+
+    /// Chunks of an indexed file, in file order, with their marks
+    pub fn file_chunks(&self, path: &str) -> Result<Vec<IndexedChunk>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT c.id, c.start_line, c.end_line, c.text, m.mark
+             FROM chunks c
+             LEFT JOIN chunk_marks m ON m.path = c.path AND m.hash = c.hash
+             WHERE c.path = ?1
+             ORDER BY c.start_line",
+        )?;
+        let rows = stmt.query_map(params![path], |row| {
+            Ok(IndexedChunk {
+                id: row.get(0)?,
+                line_start: row.get(1)?,
+                line_end: row.get(2)?,
+                content: row.get(3)?,
+                mark: row
+                    .get::<_, Option<String>>(4)?
+                    .as_deref()
+                    .and_then(ChunkMark::parse),
+            })
+        })?;
+
+        let mut chunks = Vec::new();
+        for row in rows {
+            chunks.push(row?);
+        }
+        Ok(chunks)
+    }
+
+    /// Set or clear (`None`) the mark on a chunk. Returns false if there
+    /// is no chunk with that id.
+    pub fn set_chunk_mark(&self, chunk_id: &str, mark: Option<ChunkMark>) -> Result<bool> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
+
+        let chunk: Option<(String, String)> = conn
+            .query_row(
+                "SELECT path, hash FROM chunks WHERE id = ?1",
+                params![chunk_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+        let Some((path, hash)) = chunk else {
+            return Ok(false);
+        };
+
+        match mark {
+            Some(mark) => {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)?
+                    .as_secs() as i64;
+                conn.execute(
+                    "INSERT OR REPLACE INTO chunk_marks (path, hash, mark, updated_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![&path, &hash, mark.as_str(), now],
+                )?;
+            }
+            None => {
+                conn.execute(
+                    "DELETE FROM chunk_marks WHERE path = ?1 AND hash = ?2",
+                    params![&path, &hash],
+                )?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Marks on chunks, keyed by (path, content hash)
+    pub fn chunk_marks(&self) -> Result<HashMap<(String, String), ChunkMark>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
+
+        let mut stmt = conn.prepare("SELECT path, hash, mark FROM chunk_marks")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut marks = HashMap::new();
+        for row in rows {
+            let (path, hash, mark) = row?;
+            if let Some(mark) = ChunkMark::parse(&mark) {
+                marks.insert((path, hash), mark);
+            }
+        }
+        Ok(marks)
+    }
+
+    /// Delete marks, cached embeddings, and search rows that no longer
+    /// belong to any chunk. Fills in the counts of `stats`.
+    pub fn prune_orphans(&self, stats: &mut GcStats) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
+
+        stats.marks_removed = conn.execute(
+            "DELETE FROM chunk_marks WHERE NOT EXISTS (
+                SELECT 1 FROM chunks c WHERE c.path = chunk_marks.path AND c.hash = chunk_marks.hash
+             )",
+            [],
+        )?;
+        // The cache is keyed by the same content hash as chunks
+        stats.cache_entries_removed = conn.execute(
+            "DELETE FROM embedding_cache WHERE hash NOT IN (SELECT hash FROM chunks)",
+            [],
+        )?;
+        stats.stale_rows_removed = conn
+            .execute(
+                "DELETE FROM chunks_fts WHERE id NOT IN (SELECT id FROM chunks)",
+                [],
+            )
+            .unwrap_or(0);
+        if self.has_vec_extension {
+            stats.stale_rows_removed += conn
+                .execute(
+                    "DELETE FROM chunks_vec WHERE id NOT IN (SELECT id FROM chunks)",
+                    [],
+                )
+                .unwrap_or(0);
+        }
+        Ok(())
+    }
+
+    /// Rebuild the database file to give freed pages back to the filesystem
+    pub fn vacuum(&self) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
+        conn.execute_batch("VACUUM")?;
+        Ok(())
+    }
+
     /// Insert into FTS table
     #[allow(clippy::too_many_arguments)]
     fn insert_fts(
@@ -374,6 +569,7 @@ impl MemoryIndex {
                 content: row.get(3)?,
                 score: row.get::<_, f64>(4)?.abs(), // BM25 returns negative scores
                 updated_at: row.get(5)?,
+                pinned: false,
             })
         })?;
 
@@ -415,6 +611,25 @@ impl MemoryIndex {
         Ok(count as usize)
     }
 
+    /// Run SQLite's quick integrity check. Returns the problems found,
+    /// empty if the database is sound.
+    pub fn integrity_problems(&self) -> Result<Vec<String>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
+        let mut stmt = conn.prepare("PRAGMA quick_check")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let mut problems = Vec::new();
+        for row in rows {
+            let row = row?;
+            if row != "ok" {
+                problems.push(row);
+            }
+        }
+        Ok(problems)
+    }
+
     /// Get database size in bytes
     pub fn size_bytes(&self) -> Result<u64> {
         if self.db_path.exists() {
@@ -794,6 +1009,7 @@ impl MemoryIndex {
                 content: row.get(3)?,
                 score: row.get(4)?,
                 updated_at: row.get(5)?,
+                pinned: false,
             })
         })?;
 
@@ -848,6 +1064,7 @@ impl MemoryIndex {
                         content: text,
                         score: similarity as f64,
                         updated_at,
+                        pinned: false,
                     },
                 ));
             }
@@ -941,7 +1158,7 @@ impl MemoryIndex {
     }
 }
 
-fn hash_content(content: &str) -> String {
+pub(super) fn hash_content(content: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(content.as_bytes());
     format!("{:x}", hasher.finalize())
@@ -1076,4 +1293,75 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_integrity_problems_empty_for_sound_index() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = MemoryIndex::new(temp_dir.path())?;
+        assert!(index.integrity_problems()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunk_marks_survive_reindex() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let workspace = temp_dir.path();
+        let test_file = workspace.join("notes.md");
+        fs::write(&test_file, "# Notes\n\nRemember the milk.")?;
+
+        let index = MemoryIndex::new(workspace)?;
+        index.index_file(&test_file, false)?;
+        assert_eq!(
+            index.indexed_file_chunks()?,
+            vec![("notes.md".to_string(), 1)]
+        );
+
+        let chunks = index.file_chunks("notes.md")?;
+        assert_eq!(chunks[0].mark, None);
+        assert!(index.set_chunk_mark(&chunks[0].id, Some(ChunkMark::Pinned))?);
+        assert!(!index.set_chunk_mark("no-such-chunk", Some(ChunkMark::Pinned))?);
+
+        // Chunk ids change on reindex, but the mark follows the content
+        index.index_file(&test_file, true)?;
+        let chunks = index.file_chunks("notes.md")?;
+        assert_eq!(chunks[0].mark, Some(ChunkMark::Pinned));
+        assert_eq!(index.chunk_marks()?.len(), 1);
+
+        assert!(index.set_chunk_mark(&chunks[0].id, None)?);
+        assert!(index.chunk_marks()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_orphans() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let workspace = temp_dir.path();
+        let test_file = workspace.join("notes.md");
+        fs::write(&test_file, "# Notes\n\nRemember the milk.")?;
+
+        let index = MemoryIndex::new(workspace)?;
+        index.index_file(&test_file, false)?;
+        let chunk = index.file_chunks("notes.md")?.remove(0);
+        index.set_chunk_mark(&chunk.id, Some(ChunkMark::Pinned))?;
+        index.cache_embedding("test", "m", "", &hash_content(&chunk.content), &[0.5])?;
+        index.cache_embedding("test", "m", "", "unused", &[0.5])?;
+
+        // Nothing is orphaned while the chunk exists, except the unused cache entry
+        let mut stats = GcStats::default();
+        index.prune_orphans(&mut stats)?;
+        assert_eq!(stats.marks_removed, 0);
+        assert_eq!(stats.cache_entries_removed, 1);
+
+        // Changing the content orphans the mark and the cached embedding
+        fs::write(&test_file, "# Notes\n\nRemember the eggs.")?;
+        index.index_file(&test_file, false)?;
+        let mut stats = GcStats::default();
+        index.prune_orphans(&mut stats)?;
+        assert_eq!(stats.marks_removed, 1);
+        assert_eq!(stats.cache_entries_removed, 1);
+        assert!(index.chunk_marks()?.is_empty());
+
+        Ok(())
+    }
 }
@@ -8,16 +8,18 @@ mod workspace;
 pub use embeddings::FastEmbedProvider;
 #[cfg(feature = "gguf")]
 pub use embeddings::LlamaCppProvider;
-pub use embeddings::{EmbeddingProvider, OpenAIEmbeddingProvider, hash_text};
-pub use index::{MemoryIndex, ReindexStats};
-pub use search::MemoryChunk;
+pub use embeddings::{
+    EmbeddingProvider, OpenAIEmbeddingProvider, cosine_similarity, hash_text, normalize_embedding,
+};
+pub use index::{GcStats, MemoryIndex, ReindexStats};
+pub use search::{ChunkMark, IndexedChunk, MemoryChunk};
 pub use watcher::MemoryWatcher;
 pub use workspace::{init_state_dir, init_workspace};
 
 use anyhow::Result;
 use chrono::Local;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::runtime::Handle;
@@ -53,6 +55,30 @@ pub struct FileStats {
     pub lines: usize,
 }
 
+/// A document added with [`MemoryManager::ingest`]
+#[derive(Debug)]
+pub struct IngestedFile {
+    /// Path relative to the workspace
+    pub file: String,
+    pub chunks: usize,
+}
+
+/// An indexed file and its chunks, for browsing memory
+#[derive(Debug)]
+pub struct MemoryFile {
+    /// Path as indexed: relative to the workspace, or absolute for
+    /// configured paths outside it
+    pub path: String,
+    pub content: String,
+    pub chunks: Vec<IndexedChunk>,
+}
+
+/// Score multiplier for pinned chunks in search results
+const PINNED_SCORE_BOOST: f64 = 2.0;
+
+/// Workspace directory that ingested documents are written to
+const INGEST_DIR: &str = "memory/ingested";
+
 #[derive(Debug)]
 pub struct RecentEntry {
     pub timestamp: String,
@@ -236,6 +262,11 @@ impl MemoryManager {
         self.embedding_provider.is_some()
     }
 
+    /// The configured embedding provider, if any
+    pub fn embedding_provider(&self) -> Option<Arc<dyn EmbeddingProvider>> {
+        self.embedding_provider.clone()
+    }
+
     pub fn workspace(&self) -> &PathBuf {
         &self.workspace
     }
@@ -344,9 +375,29 @@ impl MemoryManager {
         Ok(content)
     }
 
-    /// Search memory using hybrid search (FTS + semantic if available)
+    /// Search memory using hybrid search (FTS + semantic if available).
+    /// Archived chunks are left out; pinned ones skip temporal decay and
+    /// get a score boost.
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<MemoryChunk>> {
-        let mut results = self.search_raw(query, limit)?;
+        let marks = self.index.chunk_marks()?;
+        let archived = marks
+            .values()
+            .filter(|m| **m == ChunkMark::Archived)
+            .count();
+        // Fetch extra so dropping archived chunks doesn't come up short
+        let mut results = self.search_raw(query, limit + archived)?;
+
+        results.retain_mut(|chunk| {
+            let key = (chunk.file.clone(), index::hash_content(&chunk.content));
+            match marks.get(&key) {
+                Some(ChunkMark::Archived) => false,
+                Some(ChunkMark::Pinned) => {
+                    chunk.pinned = true;
+                    true
+                }
+                None => true,
+            }
+        });
 
         // Apply temporal decay if configured
         if self.config.temporal_decay_lambda > 0.0 {
@@ -354,13 +405,20 @@ impl MemoryManager {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs() as i64;
-            for chunk in &mut results {
+            for chunk in results.iter_mut().filter(|c| !c.pinned) {
                 chunk.apply_temporal_decay(self.config.temporal_decay_lambda, now);
             }
-            // Re-sort after decay
-            results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        }
+        for chunk in results.iter_mut().filter(|c| c.pinned) {
+            chunk.score *= PINNED_SCORE_BOOST;
         }
 
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(limit);
         Ok(results)
     }
 
@@ -409,8 +467,23 @@ impl MemoryManager {
         self.index.chunk_count()
     }
 
+    /// Problems SQLite's integrity check finds in the search index
+    pub fn integrity_problems(&self) -> Result<Vec<String>> {
+        self.index.integrity_problems()
+    }
+
     /// Reindex all memory files
     pub fn reindex(&self, force: bool) -> Result<ReindexStats> {
+        self.reindex_with_progress(force, |_, _, _| {})
+    }
+
+    /// Reindex all memory files, calling `progress(done, total, path)`
+    /// after each file
+    pub fn reindex_with_progress(
+        &self,
+        force: bool,
+        mut progress: impl FnMut(usize, usize, &Path),
+    ) -> Result<ReindexStats> {
         let start = std::time::Instant::now();
         let mut stats = ReindexStats {
             files_processed: 0,
@@ -425,20 +498,34 @@ impl MemoryManager {
             info!("Removed {} deleted files from index", files_removed);
         }
 
-        // Index all .md files recursively under workspace
+        // Collect first so progress has a total
+        let files = self.files_to_index();
+        for entry in &files {
+            stats.files_processed += 1;
+            if self.index.index_file(entry, force)? {
+                stats.files_updated += 1;
+            }
+            progress(stats.files_processed, files.len(), entry);
+        }
+
+        stats.chunks_indexed = self.index.chunk_count()?;
+        stats.duration = start.elapsed();
+
+        info!("Reindex complete: {:?}", stats);
+        Ok(stats)
+    }
+
+    /// Every file a reindex covers: .md files under the workspace, then
+    /// configured external paths
+    fn files_to_index(&self) -> Vec<PathBuf> {
+        // All .md files recursively under workspace
         let pattern = format!("{}/**/*.md", self.workspace.display());
-        for entry in glob::glob(&pattern)
+        let mut files: Vec<PathBuf> = glob::glob(&pattern)
             .into_iter()
             .flatten()
             .filter_map(|r| r.ok())
-        {
-            if entry.is_file() {
-                stats.files_processed += 1;
-                if self.index.index_file(&entry, force)? {
-                    stats.files_updated += 1;
-                }
-            }
-        }
+            .filter(|entry| entry.is_file())
+            .collect();
 
         // Index configured external paths (outside workspace)
         for index_path in &self.config.paths {
@@ -462,24 +549,31 @@ impl MemoryManager {
             let pattern = format!("{}/{}", base_path.display(), index_path.pattern);
             debug!("Indexing external path with pattern: {}", pattern);
 
-            for entry in glob::glob(&pattern)
-                .into_iter()
-                .flatten()
-                .filter_map(|r| r.ok())
-            {
-                if entry.is_file() {
-                    stats.files_processed += 1;
-                    if self.index.index_file(&entry, force)? {
-                        stats.files_updated += 1;
-                    }
-                }
-            }
+            files.extend(
+                glob::glob(&pattern)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|r| r.ok())
+                    .filter(|entry| entry.is_file()),
+            );
         }
 
-        stats.chunks_indexed = self.index.chunk_count()?;
-        stats.duration = start.elapsed();
+        files
+    }
 
-        info!("Reindex complete: {:?}", stats);
+    /// Drop deleted files from the index, then marks, cached embeddings,
+    /// and search rows that no chunk uses, and compact the database
+    pub fn gc(&self) -> Result<GcStats> {
+        let mut stats = GcStats {
+            bytes_before: self.index.size_bytes()?,
+            ..Default::default()
+        };
+        stats.files_removed = self.cleanup_deleted_files()?;
+        self.index.prune_orphans(&mut stats)?;
+        self.index.vacuum()?;
+        stats.bytes_after = self.index.size_bytes()?;
+
+        info!("Memory gc complete: {:?}", stats);
         Ok(stats)
     }
 
@@ -588,6 +682,32 @@ impl MemoryManager {
         })
     }
 
+    /// Indexed files with their chunk counts, by path
+    pub fn indexed_files(&self) -> Result<Vec<(String, usize)>> {
+        self.index.indexed_file_chunks()
+    }
+
+    /// An indexed file's content and chunks; `None` if `path` isn't indexed
+    pub fn indexed_file(&self, path: &str) -> Result<Option<MemoryFile>> {
+        // Only paths the index knows, so callers can't read arbitrary files
+        if !self.index.indexed_files()?.iter().any(|p| p == path) {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(self.workspace.join(path))?;
+        let chunks = self.index.file_chunks(path)?;
+        Ok(Some(MemoryFile {
+            path: path.to_string(),
+            content,
+            chunks,
+        }))
+    }
+
+    /// Pin, archive, or (with `None`) unmark a chunk. Returns false if
+    /// there is no chunk with that id.
+    pub fn set_chunk_mark(&self, chunk_id: &str, mark: Option<ChunkMark>) -> Result<bool> {
+        self.index.set_chunk_mark(chunk_id, mark)
+    }
+
     /// Get recent memory entries
     pub fn recent_entries(&self, count: usize) -> Result<Vec<RecentEntry>> {
         let mut entries = Vec::new();
@@ -631,6 +751,38 @@ impl MemoryManager {
         Ok(entries)
     }
 
+    /// Save `content` as a new Markdown file under `memory/ingested/` and
+    /// index it right away, so it is searchable without a reindex. The file
+    /// name comes from `name` (or the first line) and today's date; existing
+    /// files are never overwritten.
+    pub fn ingest(&self, name: Option<&str>, content: &str) -> Result<IngestedFile> {
+        if content.trim().is_empty() {
+            anyhow::bail!("Nothing to ingest: content is empty");
+        }
+        let dir = self.workspace.join(INGEST_DIR);
+        fs::create_dir_all(&dir)?;
+
+        let label = name.unwrap_or_else(|| content.trim().lines().next().unwrap_or(""));
+        let stem = format!("{}-{}", Local::now().format("%Y-%m-%d"), ingest_slug(label));
+        let mut path = dir.join(format!("{}.md", stem));
+        let mut n = 2;
+        while path.exists() {
+            path = dir.join(format!("{}-{}.md", stem, n));
+            n += 1;
+        }
+
+        fs::write(&path, content)?;
+        self.index.index_file(&path, true)?;
+        let chunks = self.index.file_chunk_count(&path)?;
+
+        let file = path
+            .strip_prefix(&self.workspace)
+            .map(|rel| rel.display().to_string())
+            .unwrap_or_else(|_| path.display().to_string());
+        info!("Ingested {} ({} chunks)", file, chunks);
+        Ok(IngestedFile { file, chunks })
+    }
+
     /// Start file watcher for automatic reindexing
     pub fn start_watcher(&self) -> Result<MemoryWatcher> {
         MemoryWatcher::new(
@@ -763,3 +915,67 @@ impl MemoryManager {
         self.index.embedded_chunk_count(&model)
     }
 }
+
+/// File-name-safe form of a document label: lowercase words joined by `-`,
+/// at most 48 characters.
+fn ingest_slug(label: &str) -> String {
+    let label = label.strip_suffix(".md").unwrap_or(label);
+    let mut slug = String::new();
+    for word in label
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        if slug.len() + word.len() + 1 > 48 {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_lowercase());
+    }
+    if slug.is_empty() {
+        "note".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ingest_slug_is_file_name_safe() {
+        assert_eq!(
+            ingest_slug("Meeting notes: Q3 plan.md"),
+            "meeting-notes-q3-plan"
+        );
+        assert_eq!(ingest_slug("../../etc/passwd"), "etc-passwd");
+        assert_eq!(ingest_slug("# !!"), "note");
+        assert!(ingest_slug(&"word ".repeat(30)).len() <= 48);
+    }
+
+    #[test]
+    fn users_have_separate_memory() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = Config {
+            paths: crate::paths::Paths::from_root(tmp.path()),
+            ..Default::default()
+        };
+        config.memory.embedding_provider = "none".to_string();
+        let memory_for = |user: &str| {
+            let config = config.for_user(user);
+            MemoryManager::new_with_full_config(&config.memory, Some(&config), user).unwrap()
+        };
+
+        let alice = memory_for("alice");
+        alice
+            .ingest(Some("plans"), "The launch codename is bluebird")
+            .unwrap();
+        assert!(!alice.search("bluebird", 5).unwrap().is_empty());
+
+        let bob = memory_for("bob");
+        assert!(bob.search("bluebird", 5).unwrap().is_empty());
+        assert_ne!(alice.workspace(), bob.workspace());
+    }
+}
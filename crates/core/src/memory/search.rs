@@ -23,6 +23,48 @@ pub struct MemoryChunk {
     /// Unix timestamp when the chunk was last updated (for temporal decay)
     #[serde(default)]
     pub updated_at: i64,
+
+    /// Whether the chunk is pinned (see [`ChunkMark::Pinned`])
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// A user's mark on an indexed chunk. Marks are keyed by the chunk's file
+/// and content, so they survive reindexing until the chunk's text changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkMark {
+    /// Exempt from temporal decay and ranked ahead of unpinned matches
+    Pinned,
+    /// Left out of search results
+    Archived,
+}
+
+impl ChunkMark {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChunkMark::Pinned => "pinned",
+            ChunkMark::Archived => "archived",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pinned" => Some(ChunkMark::Pinned),
+            "archived" => Some(ChunkMark::Archived),
+            _ => None,
+        }
+    }
+}
+
+/// A chunk as stored in the index, for browsing a file's chunks
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexedChunk {
+    pub id: String,
+    pub line_start: i32,
+    pub line_end: i32,
+    pub content: String,
+    pub mark: Option<ChunkMark>,
 }
 
 impl MemoryChunk {
@@ -35,6 +77,7 @@ impl MemoryChunk {
             content,
             score,
             updated_at: 0,
+            pinned: false,
         }
     }
 
@@ -18,6 +18,8 @@
 //! ```
 //!
 //! This provides complete isolation: separate config, sessions, cache, workspace per profile.
+//! Profile names are lowercased and limited to ASCII letters, digits, `-`, and `_` so a
+//! profile can never point outside the base directories.
 
 use anyhow::{Context, Result};
 #[cfg(unix)]
@@ -60,6 +62,9 @@ pub struct Paths {
     /// Runtime directory: PID file, sockets.
     /// None if no suitable runtime directory is available.
     pub runtime_dir: Option<PathBuf>,
+
+    /// Active profile name (normalized), or None for the default profile
+    pub profile: Option<String>,
 }
 
 impl Paths {
@@ -79,7 +84,14 @@ impl Paths {
             .map_err(|e| anyhow::anyhow!("Failed to determine base directories: {}", e))?;
 
         // Get profile suffix once - applies to ALL directories for complete isolation
-        let suffix = profile_suffix(&env_fn);
+        let profile = match env_fn(LOCALGPT_PROFILE) {
+            Ok(name) => normalize_profile(&name)?,
+            Err(_) => None,
+        };
+        let suffix = profile
+            .as_ref()
+            .map(|p| format!("-{}", p))
+            .unwrap_or_default();
 
         let config_dir = env_or(&env_fn, LOCALGPT_CONFIG_DIR, || {
             strategy.config_dir().join(format!("localgpt{}", suffix))
@@ -113,6 +125,7 @@ impl Paths {
             state_dir,
             cache_dir,
             runtime_dir,
+            profile,
         })
     }
 
@@ -152,6 +165,11 @@ impl Paths {
             .join("sessions")
     }
 
+    /// Workspace of an HTTP API user: data_dir/users/{user}/workspace
+    pub fn user_workspace(&self, user: &str) -> PathBuf {
+        self.data_dir.join("users").join(user).join("workspace")
+    }
+
     /// Logs directory
     pub fn logs_dir(&self) -> PathBuf {
         self.state_dir.join("logs")
@@ -175,6 +193,11 @@ impl Paths {
         self.locks_dir().join("workspace.lock")
     }
 
+    /// Bridge admin token, replaced at each daemon start
+    pub fn bridge_admin_token(&self) -> PathBuf {
+        self.locks_dir().join("bridge-admin.token")
+    }
+
     /// Telegram pairing file
     pub fn pairing_file(&self) -> PathBuf {
         self.state_dir.join("telegram_paired_user.json")
@@ -200,6 +223,33 @@ impl Paths {
         self.data_dir.join("skills")
     }
 
+    /// Bundled skills extracted from the binary: cache_dir/bundled-skills
+    pub fn bundled_skills_dir(&self) -> PathBuf {
+        self.cache_dir.join("bundled-skills")
+    }
+
+    /// Installed skill sources and versions: data_dir/skills.lock.json
+    pub fn skills_lock_file(&self) -> PathBuf {
+        self.data_dir.join("skills.lock.json")
+    }
+
+    /// Skills turned off with `localgpt skills disable`:
+    /// data_dir/skills/disabled.json (in the managed skills directory, so
+    /// the skills watcher picks up changes)
+    pub fn disabled_skills_file(&self) -> PathBuf {
+        self.managed_skills_dir().join("disabled.json")
+    }
+
+    /// Runtime cron jobs: data_dir/cron-jobs.json
+    pub fn cron_jobs_file(&self) -> PathBuf {
+        self.data_dir.join("cron-jobs.json")
+    }
+
+    /// Local skill signing key: data_dir/skill-signing.key
+    pub fn skill_signing_key(&self) -> PathBuf {
+        self.data_dir.join("skill-signing.key")
+    }
+
     /// Embedding cache directory: cache_dir/embeddings
     pub fn embedding_cache_dir(&self) -> PathBuf {
         self.cache_dir.join("embeddings")
@@ -218,6 +268,7 @@ impl Paths {
             state_dir: root.join("state"),
             cache_dir: root.join("cache"),
             runtime_dir: None,
+            profile: None,
         }
     }
 
@@ -263,6 +314,7 @@ impl Default for Paths {
                 state_dir: home.join(".local").join("state").join("localgpt"),
                 cache_dir: home.join(".cache").join("localgpt"),
                 runtime_dir: None,
+                profile: None,
             }
         })
     }
@@ -281,19 +333,30 @@ where
         .unwrap_or_else(default)
 }
 
-/// Get the profile suffix for directory names.
-/// Returns empty string for default/empty profile, "-{profile}" otherwise.
-fn profile_suffix<F>(env_fn: &F) -> String
-where
-    F: Fn(&str) -> std::result::Result<String, std::env::VarError>,
-{
-    if let Ok(profile) = env_fn(LOCALGPT_PROFILE) {
-        let trimmed = profile.trim().to_lowercase();
-        if !trimmed.is_empty() && trimmed != "default" {
-            return format!("-{}", trimmed);
-        }
+/// Normalize a profile name from `--profile` or `LOCALGPT_PROFILE`.
+///
+/// Returns None for an empty or "default" profile. Names are trimmed and
+/// lowercased; anything other than ASCII letters, digits, `-`, and `_` is
+/// rejected because the name becomes part of every directory path.
+pub fn normalize_profile(name: &str) -> Result<Option<String>> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() || name == "default" {
+        return Ok(None);
+    }
+    if name.len() > 64 {
+        anyhow::bail!("Profile name is too long (max 64 characters): {}", name);
+    }
+    if name.starts_with('-')
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!(
+            "Invalid profile name '{}': use letters, digits, '-' and '_' only",
+            name
+        );
     }
-    String::new()
+    Ok(Some(name))
 }
 
 /// Resolve workspace path with LOCALGPT_WORKSPACE override or default under data_dir.
@@ -501,6 +564,34 @@ mod tests {
         assert!(paths.workspace.ends_with("workspace"));
     }
 
+    #[test]
+    fn profile_name_is_normalized() {
+        assert_eq!(normalize_profile(" Work ").unwrap(), Some("work".into()));
+        assert_eq!(
+            normalize_profile("side_project-2").unwrap(),
+            Some("side_project-2".into())
+        );
+        assert_eq!(normalize_profile("").unwrap(), None);
+        assert_eq!(normalize_profile("DEFAULT").unwrap(), None);
+
+        let mut env: HashMap<&str, &str> = HashMap::new();
+        env.insert(LOCALGPT_PROFILE, "Personal");
+        let paths = Paths::resolve_with_env(make_env(env)).unwrap();
+        assert_eq!(paths.profile.as_deref(), Some("personal"));
+        assert!(paths.config_dir.ends_with("localgpt-personal"));
+    }
+
+    #[test]
+    fn invalid_profile_names_rejected() {
+        for name in ["../evil", "a/b", "work space", "-work", "wörk"] {
+            assert!(normalize_profile(name).is_err(), "accepted {:?}", name);
+
+            let mut env: HashMap<&str, &str> = HashMap::new();
+            env.insert(LOCALGPT_PROFILE, name);
+            assert!(Paths::resolve_with_env(make_env(env)).is_err());
+        }
+    }
+
     #[test]
     fn workspace_override_independent_of_profile() {
         let mut env: HashMap<&str, &str> = HashMap::new();
@@ -527,6 +618,15 @@ mod tests {
         assert!(paths.sessions_dir("main").ends_with("agents/main/sessions"));
         assert!(paths.logs_dir().ends_with("logs"));
         assert!(paths.managed_skills_dir().ends_with("skills"));
+        assert!(paths.skills_lock_file().ends_with("skills.lock.json"));
+        assert!(
+            paths
+                .disabled_skills_file()
+                .ends_with("skills/disabled.json")
+        );
+        assert!(paths.bundled_skills_dir().ends_with("bundled-skills"));
+        assert!(paths.skill_signing_key().ends_with("skill-signing.key"));
+        assert!(paths.cron_jobs_file().ends_with("cron-jobs.json"));
         assert!(paths.embedding_cache_dir().ends_with("embeddings"));
         assert!(paths.pairing_file().ends_with("telegram_paired_user.json"));
     }
@@ -0,0 +1,73 @@
+//! State of long-running daemon components, for the HTTP `/readyz` check.
+//!
+//! Components report as they start, tick, and fail; the server reads the
+//! latest report. A component that never reported isn't running in this
+//! process (e.g. the bridge socket under `localgpt serve`).
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+
+/// The bridge socket listener
+pub const BRIDGE: &str = "bridge";
+/// The cron scheduler loop; it reports on every tick
+pub const SCHEDULER: &str = "scheduler";
+
+static COMPONENTS: LazyLock<Mutex<HashMap<&'static str, ComponentStatus>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComponentState {
+    Running,
+    Failed(String),
+}
+
+/// A component's latest report.
+#[derive(Debug, Clone)]
+pub struct ComponentStatus {
+    pub state: ComponentState,
+    pub reported_at: Instant,
+}
+
+/// Record `component`'s current state.
+pub fn report(component: &'static str, state: ComponentState) {
+    let mut components = COMPONENTS.lock().unwrap_or_else(|e| e.into_inner());
+    components.insert(
+        component,
+        ComponentStatus {
+            state,
+            reported_at: Instant::now(),
+        },
+    );
+}
+
+/// `component`'s latest report, if it ever reported.
+pub fn status(component: &str) -> Option<ComponentStatus> {
+    let components = COMPONENTS.lock().unwrap_or_else(|e| e.into_inner());
+    components.get(component).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_replaces_state() {
+        assert!(status("test-component").is_none());
+
+        report("test-component", ComponentState::Running);
+        let first = status("test-component").unwrap();
+        assert_eq!(first.state, ComponentState::Running);
+
+        report(
+            "test-component",
+            ComponentState::Failed("bind failed".to_string()),
+        );
+        let second = status("test-component").unwrap();
+        assert_eq!(
+            second.state,
+            ComponentState::Failed("bind failed".to_string())
+        );
+        assert!(second.reported_at >= first.reported_at);
+    }
+}
@@ -0,0 +1,314 @@
+//! API keys for the HTTP server.
+//!
+//! Keys are created and revoked with `localgpt apikey` and stored at
+//! `~/.local/state/localgpt/api-keys.json`. Only a SHA-256 hash of each key
+//! is kept; the key itself is shown once, when it is created. Each key has
+//! scopes that decide which routes it may call, and optionally a user:
+//! keys of different users see separate sessions and usage totals.
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use rand::RngExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const API_KEYS_FILENAME: &str = "api-keys.json";
+
+/// Prefix of every generated key, so leaked keys are easy to spot.
+pub const API_KEY_PREFIX: &str = "lgpt_";
+
+/// Characters of the key kept in the clear, to tell keys apart in listings.
+const SHOWN_PREFIX_CHARS: usize = 12;
+
+/// Longest accepted user name.
+const MAX_USER_CHARS: usize = 32;
+
+/// What an API key is allowed to do.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiScope {
+    /// Chat, sessions, and the OpenAI- and Ollama-compatible endpoints
+    Chat,
+    /// Memory search and statistics
+    MemoryRead,
+    /// Everything, including memory writes, cron, config, and logs
+    Admin,
+}
+
+impl ApiScope {
+    pub const ALL: [ApiScope; 3] = [ApiScope::Chat, ApiScope::MemoryRead, ApiScope::Admin];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::Chat => "chat",
+            ApiScope::MemoryRead => "memory-read",
+            ApiScope::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == s)
+            .with_context(|| {
+                format!(
+                    "Unknown API scope '{}' (expected one of: chat, memory-read, admin)",
+                    s
+                )
+            })
+    }
+}
+
+/// A stored API key. The key itself is never stored, only its hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub name: String,
+    /// First characters of the key, for identifying it
+    pub prefix: String,
+    /// SHA-256 of the key, hex-encoded
+    pub hash: String,
+    pub scopes: Vec<ApiScope>,
+    /// User whose sessions the key works with. Keys without one share the
+    /// default namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    /// Whether this key may call routes that need `scope`. `admin` grants
+    /// every scope.
+    pub fn allows(&self, scope: ApiScope) -> bool {
+        self.scopes.contains(&scope) || self.scopes.contains(&ApiScope::Admin)
+    }
+
+    /// Whether `secret` is this key.
+    pub fn matches(&self, secret: &str) -> bool {
+        constant_time_eq(self.hash.as_bytes(), hash_key(secret).as_bytes())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ApiKeysFile {
+    #[serde(default)]
+    keys: Vec<ApiKey>,
+}
+
+/// The API keys in the state directory.
+#[derive(Debug, Clone)]
+pub struct ApiKeyStore {
+    path: PathBuf,
+}
+
+impl ApiKeyStore {
+    pub fn new(state_dir: &Path) -> Self {
+        Self {
+            path: state_dir.join(API_KEYS_FILENAME),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn list(&self) -> Result<Vec<ApiKey>> {
+        Ok(self.load()?.keys)
+    }
+
+    /// Create a key named `name` with `scopes`, acting as `user` if given.
+    /// Returns the stored key and the key itself, which can't be recovered
+    /// later.
+    pub fn create(
+        &self,
+        name: &str,
+        scopes: Vec<ApiScope>,
+        user: Option<&str>,
+    ) -> Result<(ApiKey, String)> {
+        if name.trim().is_empty() {
+            bail!("API key name must not be empty");
+        }
+        if scopes.is_empty() {
+            bail!("API key needs at least one scope");
+        }
+        if let Some(user) = user
+            && !valid_user_name(user)
+        {
+            bail!(
+                "Invalid user '{}': use up to {} lowercase letters, digits, '-' or '_'",
+                user,
+                MAX_USER_CHARS
+            );
+        }
+        let mut file = self.load()?;
+        if file.keys.iter().any(|k| k.name == name) {
+            bail!("An API key named '{}' already exists", name);
+        }
+
+        let mut bytes = [0u8; 24];
+        rand::rng().fill(&mut bytes);
+        let secret = format!(
+            "{}{}",
+            API_KEY_PREFIX,
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        );
+        let key = ApiKey {
+            name: name.to_string(),
+            prefix: secret.chars().take(SHOWN_PREFIX_CHARS).collect(),
+            hash: hash_key(&secret),
+            scopes,
+            user: user.map(String::from),
+            created_at: Utc::now(),
+        };
+        file.keys.push(key.clone());
+        self.save(&file)?;
+        Ok((key, secret))
+    }
+
+    /// Revoke the key named `name_or_prefix`, or whose shown prefix matches.
+    pub fn revoke(&self, name_or_prefix: &str) -> Result<ApiKey> {
+        let mut file = self.load()?;
+        let index = file
+            .keys
+            .iter()
+            .position(|k| k.name == name_or_prefix || k.prefix == name_or_prefix)
+            .with_context(|| format!("No API key named '{}'", name_or_prefix))?;
+        let key = file.keys.remove(index);
+        self.save(&file)?;
+        Ok(key)
+    }
+
+    /// Users that keys have been created for, sorted.
+    pub fn users(&self) -> Result<Vec<String>> {
+        let mut users: Vec<String> = self.list()?.into_iter().filter_map(|k| k.user).collect();
+        users.sort();
+        users.dedup();
+        Ok(users)
+    }
+
+    /// The stored key matching `secret`, if any.
+    pub fn verify(&self, secret: &str) -> Result<Option<ApiKey>> {
+        Ok(self.load()?.keys.into_iter().find(|k| k.matches(secret)))
+    }
+
+    fn load(&self) -> Result<ApiKeysFile> {
+        if !self.path.exists() {
+            return Ok(ApiKeysFile::default());
+        }
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", self.path.display()))
+    }
+
+    fn save(&self, file: &ApiKeysFile) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(file)?)?;
+
+        // Restrict permissions: hashes are not secrets, but scopes are policy
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))?;
+        }
+
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// User names end up in session directory names.
+fn valid_user_name(user: &str) -> bool {
+    !user.is_empty()
+        && user.len() <= MAX_USER_CHARS
+        && user
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+fn hash_key(secret: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(secret.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Compare secrets without leaking where they first differ.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn create_verify_revoke() {
+        let dir = TempDir::new().unwrap();
+        let store = ApiKeyStore::new(dir.path());
+
+        let (key, secret) = store.create("ci", vec![ApiScope::Chat], None).unwrap();
+        assert!(secret.starts_with(API_KEY_PREFIX));
+        assert!(!fs::read_to_string(store.path()).unwrap().contains(&secret));
+        assert!(store.create("ci", vec![ApiScope::Chat], None).is_err());
+
+        let found = store.verify(&secret).unwrap().unwrap();
+        assert_eq!(found.name, "ci");
+        assert!(found.allows(ApiScope::Chat));
+        assert!(!found.allows(ApiScope::MemoryRead));
+        assert!(store.verify("lgpt_wrong").unwrap().is_none());
+
+        store.revoke(&key.prefix).unwrap();
+        assert!(store.verify(&secret).unwrap().is_none());
+        assert!(store.revoke("ci").is_err());
+    }
+
+    #[test]
+    fn admin_grants_every_scope() {
+        let dir = TempDir::new().unwrap();
+        let store = ApiKeyStore::new(dir.path());
+        let (key, _) = store.create("ops", vec![ApiScope::Admin], None).unwrap();
+        assert!(ApiScope::ALL.iter().all(|s| key.allows(*s)));
+        assert_eq!(
+            ApiScope::parse("memory-read").unwrap(),
+            ApiScope::MemoryRead
+        );
+        assert!(ApiScope::parse("write").is_err());
+    }
+
+    #[test]
+    fn keys_for_users() {
+        let dir = TempDir::new().unwrap();
+        let store = ApiKeyStore::new(dir.path());
+        store
+            .create("alice-phone", vec![ApiScope::Chat], Some("alice"))
+            .unwrap();
+        store
+            .create("alice-laptop", vec![ApiScope::Chat], Some("alice"))
+            .unwrap();
+        store
+            .create("bob", vec![ApiScope::Chat], Some("bob"))
+            .unwrap();
+        store.create("shared", vec![ApiScope::Chat], None).unwrap();
+        assert_eq!(store.users().unwrap(), vec!["alice", "bob"]);
+
+        assert!(
+            store
+                .create("x", vec![ApiScope::Chat], Some("../etc"))
+                .is_err()
+        );
+        assert!(
+            store
+                .create("y", vec![ApiScope::Chat], Some("Alice"))
+                .is_err()
+        );
+        assert!(store.create("z", vec![ApiScope::Chat], Some("")).is_err());
+    }
+}
@@ -21,16 +21,44 @@
 //! The first entry uses `000...000` (64 zeros) as `prev_entry_sha256`.
 //! Every subsequent entry hashes the raw bytes of the previous line.
 //! A broken chain indicates the log file was tampered with.
-
-use anyhow::{Context, Result};
+//!
+//! # Rotation
+//!
+//! [`rotate_audit_log`] moves the log to `localgpt.audit.<timestamp>.jsonl`
+//! (gzipped by default) once it passes `security.audit.max_size_mb` or its
+//! first entry is older than `security.audit.max_age`. The fresh log starts
+//! with a `rotated` entry linking to the archived segment's last line, so
+//! the chain stays verifiable; only the newest `security.audit.keep`
+//! segments are kept.
+//!
+//! Appends and rotation hold an exclusive lock on `localgpt.audit.lock`,
+//! so writers in other processes can't slip an entry in between reading
+//! the last line and appending, or into a log that is being moved aside.
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use fs2::FileExt;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fs;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::{AuditLogConfig, parse_duration};
 
 const AUDIT_FILENAME: &str = "localgpt.audit.jsonl";
 
+/// Lock file held while the audit log is written or rotated.
+const LOCK_FILENAME: &str = "localgpt.audit.lock";
+
+/// Rotated segments are `localgpt.audit.<timestamp>.jsonl[.gz]`.
+const ARCHIVE_PREFIX: &str = "localgpt.audit.";
+
 /// The hash used for the first entry in the chain (no predecessor).
 const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
 
@@ -80,6 +108,37 @@ pub enum AuditAction {
     PathDenied,
     /// Previous audit entry corrupted, new chain segment started.
     ChainRecovery,
+    /// Agent write to a protected file was queued for the user's approval.
+    WriteApprovalRequested,
+    /// User approved a queued protected write.
+    WriteApproved,
+    /// User denied a queued protected write, or it expired unanswered.
+    WriteDenied,
+    /// An approved protected write was carried out.
+    WriteTokenUsed,
+    /// An unknown bridge executable asked for credentials and is waiting
+    /// for approval.
+    BridgeApprovalRequested,
+    /// User approved a bridge executable.
+    BridgeApproved,
+    /// User denied a bridge executable's request.
+    BridgeDenied,
+    /// User revoked a bridge's approvals.
+    BridgeRevoked,
+    /// User changed which RPCs a bridge may call.
+    BridgeScopesChanged,
+    /// Log was rotated; the previous entries moved to an archive segment.
+    Rotated,
+}
+
+impl FromStr for AuditAction {
+    type Err = anyhow::Error;
+
+    /// Parse the snake_case name used in the log, e.g. `write_blocked`.
+    fn from_str(s: &str) -> Result<Self> {
+        serde_json::from_value(serde_json::Value::String(s.to_string()))
+            .map_err(|_| anyhow!("Unknown audit action '{}'", s))
+    }
 }
 
 /// Append a new entry to the audit log.
@@ -113,6 +172,7 @@ pub fn append_audit_entry_with_detail(
     detail: Option<&str>,
 ) -> Result<()> {
     let path = audit_file_path(state_dir);
+    let _lock = lock_audit_log(state_dir)?;
 
     // Read the last line to compute the chain hash, with corruption recovery
     let prev_hash = if path.exists() {
@@ -163,6 +223,18 @@ pub fn append_audit_entry_with_detail(
     Ok(())
 }
 
+/// Take the exclusive audit log lock, released when the file is dropped.
+fn lock_audit_log(state_dir: &Path) -> Result<fs::File> {
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(state_dir.join(LOCK_FILENAME))
+        .context("Failed to open audit log lock")?;
+    file.lock_exclusive().context("Failed to lock audit log")?;
+    Ok(file)
+}
+
 /// Append a single line to a file.
 fn append_line(path: &Path, line: &str) -> Result<()> {
     let mut file = fs::OpenOptions::new()
@@ -230,9 +302,9 @@ pub fn verify_audit_chain(state_dir: &Path) -> Result<Vec<usize>> {
         parsed.push(serde_json::from_str(line).ok());
     }
 
-    // Check first entry
+    // Check first entry; after a rotation it links to the archived segment
     if let Some(ref first) = parsed[0] {
-        if first.prev_entry_sha256 != GENESIS_HASH {
+        if first.prev_entry_sha256 != GENESIS_HASH && first.action != AuditAction::Rotated {
             broken.push(0);
         }
     } else {
@@ -254,6 +326,267 @@ pub fn verify_audit_chain(state_dir: &Path) -> Result<Vec<usize>> {
     Ok(broken)
 }
 
+/// Filter applied when reading the audit log.
+///
+/// All fields are optional; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    /// Only entries at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries with one of these actions.
+    pub actions: Vec<AuditAction>,
+    /// Only entries whose source starts with this (`tool:` matches every tool).
+    pub source: Option<String>,
+    /// Only entries whose action, source, or detail contains this text,
+    /// ignoring case.
+    pub text: Option<String>,
+    /// Also read rotated segments, oldest first.
+    pub archives: bool,
+    /// Keep only the most recent N matching entries.
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        let since = self.since.is_none_or(|since| {
+            DateTime::parse_from_rfc3339(&entry.ts).is_ok_and(|ts| ts >= since)
+        });
+        let text = self.text.as_ref().is_none_or(|text| {
+            let text = text.to_lowercase();
+            let action = serde_json::to_string(&entry.action).unwrap_or_default();
+            action.contains(&text)
+                || entry.source.to_lowercase().contains(&text)
+                || entry
+                    .detail
+                    .as_ref()
+                    .is_some_and(|d| d.to_lowercase().contains(&text))
+        });
+        since
+            && text
+            && (self.actions.is_empty() || self.actions.contains(&entry.action))
+            && self
+                .source
+                .as_ref()
+                .is_none_or(|s| entry.source.starts_with(s.as_str()))
+    }
+}
+
+/// Read audit entries matching `query`, oldest first.
+pub fn query_audit_log(state_dir: &Path, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+    let mut files = if query.archives {
+        audit_archives(state_dir)?
+    } else {
+        Vec::new()
+    };
+    files.push(audit_file_path(state_dir));
+
+    let mut entries = Vec::new();
+    for file in files {
+        if !file.exists() {
+            continue;
+        }
+        let content = read_segment(&file)?;
+        entries.extend(
+            content
+                .lines()
+                .filter(|l| !l.is_empty())
+                .filter_map(|l| serde_json::from_str::<AuditEntry>(l).ok())
+                .filter(|e| query.matches(e)),
+        );
+    }
+
+    if let Some(limit) = query.limit
+        && entries.len() > limit
+    {
+        entries.drain(..entries.len() - limit);
+    }
+
+    Ok(entries)
+}
+
+/// When to rotate the audit log and how many old segments to keep.
+#[derive(Debug, Clone)]
+pub struct AuditRotation {
+    /// Rotate once the log is at least this large
+    pub max_bytes: Option<u64>,
+    /// Rotate once the first entry is older than this
+    pub max_age: Option<Duration>,
+    /// Gzip rotated segments
+    pub compress: bool,
+    /// Segments to keep (0 = all)
+    pub keep: usize,
+}
+
+impl AuditRotation {
+    /// Rotation settings from `[security.audit]`.
+    pub fn from_config(config: &AuditLogConfig) -> Result<Self> {
+        let max_age = if config.max_age.is_empty() {
+            None
+        } else {
+            Some(
+                parse_duration(&config.max_age)
+                    .map_err(|e| anyhow!("Invalid security.audit.max_age: {}", e))?,
+            )
+        };
+        Ok(Self {
+            max_bytes: (config.max_size_mb > 0).then_some(config.max_size_mb * 1024 * 1024),
+            max_age,
+            compress: config.compress,
+            keep: config.keep,
+        })
+    }
+
+    /// Whether the log at `path`, `len` bytes long, is due for rotation.
+    fn is_due(&self, path: &Path, len: u64) -> Result<bool> {
+        if self.max_bytes.is_some_and(|max| len >= max) {
+            return Ok(true);
+        }
+        let Some(max_age) = self.max_age else {
+            return Ok(false);
+        };
+        let mut first_line = String::new();
+        BufReader::new(fs::File::open(path).context("Failed to open audit log")?)
+            .read_line(&mut first_line)
+            .context("Failed to read audit log")?;
+        let Ok(first) = serde_json::from_str::<AuditEntry>(first_line.trim_end()) else {
+            return Ok(false);
+        };
+        let Ok(started) = DateTime::parse_from_rfc3339(&first.ts) else {
+            return Ok(false);
+        };
+        let age = Utc::now().signed_duration_since(started);
+        Ok(age.to_std().is_ok_and(|age| age >= max_age))
+    }
+}
+
+/// Move the audit log to an archive segment if it is due (or `force` is
+/// set), then prune old segments.
+///
+/// The new log starts with a `Rotated` entry whose `prev_entry_sha256` is
+/// the hash of the archived segment's last line and whose `content_sha256`
+/// covers the whole segment, so the chain continues across files. Returns
+/// the archive's path if the log was rotated.
+pub fn rotate_audit_log(
+    state_dir: &Path,
+    rotation: &AuditRotation,
+    force: bool,
+) -> Result<Option<PathBuf>> {
+    let path = audit_file_path(state_dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let _lock = lock_audit_log(state_dir)?;
+    let Ok(metadata) = fs::metadata(&path) else {
+        return Ok(None);
+    };
+    if metadata.len() == 0 || !(force || rotation.is_due(&path, metadata.len())?) {
+        return Ok(None);
+    }
+
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut archive = state_dir.join(format!("{}{}.jsonl", ARCHIVE_PREFIX, stamp));
+    let mut n = 1;
+    while archive.exists() || archive.with_extension("jsonl.gz").exists() {
+        archive = state_dir.join(format!("{}{}-{}.jsonl", ARCHIVE_PREFIX, stamp, n));
+        n += 1;
+    }
+    // Appends wait for the lock, so none can land in the archive or
+    // ahead of the `Rotated` entry
+    fs::rename(&path, &archive).context("Failed to move audit log aside")?;
+
+    let content = fs::read_to_string(&archive).context("Failed to read rotated audit log")?;
+    let lines: Vec<&str> = content.lines().filter(|l| !l.is_empty()).collect();
+    let prev_hash = lines
+        .last()
+        .map(|l| sha256_hex(l.as_bytes()))
+        .unwrap_or_else(|| GENESIS_HASH.to_string());
+
+    let archive = if rotation.compress {
+        let gz = archive.with_extension("jsonl.gz");
+        let mut encoder = GzEncoder::new(
+            fs::File::create(&gz).context("Failed to create compressed audit segment")?,
+            Compression::default(),
+        );
+        encoder.write_all(content.as_bytes())?;
+        encoder.finish()?;
+        fs::remove_file(&archive)?;
+        gz
+    } else {
+        archive
+    };
+    let name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let entry = AuditEntry {
+        ts: Utc::now().to_rfc3339(),
+        action: AuditAction::Rotated,
+        content_sha256: sha256_hex(content.as_bytes()),
+        prev_entry_sha256: prev_hash,
+        source: "audit_system".to_string(),
+        detail: Some(format!("{} entries moved to {}", lines.len(), name)),
+    };
+    let json = serde_json::to_string(&entry).context("Failed to serialize audit entry")?;
+    append_line(&path, &json)?;
+
+    prune_audit_archives(state_dir, rotation.keep)?;
+    Ok(Some(archive))
+}
+
+/// Rotated audit log segments, oldest first.
+pub fn audit_archives(state_dir: &Path) -> Result<Vec<PathBuf>> {
+    let Ok(dir) = fs::read_dir(state_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut archives: Vec<PathBuf> = dir
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(is_archive_name)
+        })
+        .collect();
+    // Timestamps in the names sort chronologically
+    archives.sort();
+    Ok(archives)
+}
+
+fn is_archive_name(name: &str) -> bool {
+    name != AUDIT_FILENAME
+        && name.starts_with(ARCHIVE_PREFIX)
+        && (name.ends_with(".jsonl") || name.ends_with(".jsonl.gz"))
+}
+
+/// Delete all but the newest `keep` segments (0 keeps everything).
+fn prune_audit_archives(state_dir: &Path, keep: usize) -> Result<()> {
+    if keep == 0 {
+        return Ok(());
+    }
+    let archives = audit_archives(state_dir)?;
+    let excess = archives.len().saturating_sub(keep);
+    for archive in &archives[..excess] {
+        fs::remove_file(archive)
+            .with_context(|| format!("Failed to remove {}", archive.display()))?;
+    }
+    Ok(())
+}
+
+/// Read a log segment, decompressing `.gz` segments.
+fn read_segment(path: &Path) -> Result<String> {
+    let context = || format!("Failed to read {}", path.display());
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        let mut content = String::new();
+        GzDecoder::new(fs::File::open(path).with_context(context)?)
+            .read_to_string(&mut content)
+            .with_context(context)?;
+        Ok(content)
+    } else {
+        fs::read_to_string(path).with_context(context)
+    }
+}
+
 /// Get the full path to the audit log file.
 pub fn audit_file_path(state_dir: &Path) -> PathBuf {
     state_dir.join(AUDIT_FILENAME)
@@ -433,4 +766,245 @@ mod tests {
         // Signed + ChainRecovery + Verified = 3 (garbage line skipped)
         assert_eq!(entries.len(), 3);
     }
+
+    #[test]
+    fn query_filters_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        append_audit_entry(tmp.path(), AuditAction::Signed, "abc", "cli").unwrap();
+        append_audit_entry_with_detail(
+            tmp.path(),
+            AuditAction::WriteBlocked,
+            "",
+            "tool:write_file",
+            Some("Agent attempted write to LocalGPT.md"),
+        )
+        .unwrap();
+        append_audit_entry(tmp.path(), AuditAction::Verified, "abc", "session_start").unwrap();
+
+        let query = |query: AuditQuery| query_audit_log(tmp.path(), &query).unwrap();
+
+        assert_eq!(query(AuditQuery::default()).len(), 3);
+        let blocked = query(AuditQuery {
+            actions: vec!["write_blocked".parse().unwrap()],
+            ..Default::default()
+        });
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0].source, "tool:write_file");
+        assert_eq!(
+            query(AuditQuery {
+                source: Some("tool:".to_string()),
+                ..Default::default()
+            })
+            .len(),
+            1
+        );
+        assert_eq!(
+            query(AuditQuery {
+                text: Some("localgpt.MD".to_string()),
+                ..Default::default()
+            })
+            .len(),
+            1
+        );
+        assert!(
+            query(AuditQuery {
+                since: Some(Utc::now() + chrono::Duration::hours(1)),
+                ..Default::default()
+            })
+            .is_empty()
+        );
+        let last = query(AuditQuery {
+            limit: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(last[0].action, AuditAction::Verified);
+
+        assert!("no_such_action".parse::<AuditAction>().is_err());
+    }
+
+    #[test]
+    fn rotation_keeps_chain_and_archives_readable() {
+        let tmp = tempfile::tempdir().unwrap();
+        for i in 0..3 {
+            append_audit_entry(tmp.path(), AuditAction::Verified, &format!("{}", i), "test")
+                .unwrap();
+        }
+        let rotation = AuditRotation {
+            max_bytes: Some(1),
+            max_age: None,
+            compress: true,
+            keep: 0,
+        };
+
+        let archive = rotate_audit_log(tmp.path(), &rotation, false)
+            .unwrap()
+            .expect("log over max_bytes should rotate");
+        assert!(archive.to_string_lossy().ends_with(".jsonl.gz"));
+        assert_eq!(audit_archives(tmp.path()).unwrap(), vec![archive.clone()]);
+
+        // The new log starts with a link to the archived segment
+        let current = read_audit_log(tmp.path()).unwrap();
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].action, AuditAction::Rotated);
+        let archived = read_segment(&archive).unwrap();
+        let last_line = archived.lines().last().unwrap();
+        assert_eq!(
+            current[0].prev_entry_sha256,
+            sha256_hex(last_line.as_bytes())
+        );
+        assert!(verify_audit_chain(tmp.path()).unwrap().is_empty());
+
+        append_audit_entry(tmp.path(), AuditAction::Signed, "abc", "cli").unwrap();
+        assert!(verify_audit_chain(tmp.path()).unwrap().is_empty());
+
+        let all = query_audit_log(
+            tmp.path(),
+            &AuditQuery {
+                archives: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let actions: Vec<_> = all.iter().map(|e| e.action.clone()).collect();
+        assert_eq!(
+            actions,
+            vec![
+                AuditAction::Verified,
+                AuditAction::Verified,
+                AuditAction::Verified,
+                AuditAction::Rotated,
+                AuditAction::Signed,
+            ]
+        );
+    }
+
+    #[test]
+    fn rotation_during_appends_keeps_chain() {
+        let tmp = tempfile::tempdir().unwrap();
+        append_audit_entry(tmp.path(), AuditAction::Verified, "0", "test").unwrap();
+        let rotation = AuditRotation {
+            max_bytes: Some(1),
+            max_age: None,
+            compress: false,
+            keep: 0,
+        };
+
+        std::thread::scope(|scope| {
+            for t in 0..4 {
+                let dir = tmp.path();
+                scope.spawn(move || {
+                    for i in 0..25 {
+                        append_audit_entry(dir, AuditAction::Verified, &format!("{t}-{i}"), "test")
+                            .unwrap();
+                    }
+                });
+            }
+            for _ in 0..10 {
+                rotate_audit_log(tmp.path(), &rotation, true).unwrap();
+            }
+        });
+
+        assert!(verify_audit_chain(tmp.path()).unwrap().is_empty());
+        let all = query_audit_log(
+            tmp.path(),
+            &AuditQuery {
+                archives: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let appended = all
+            .iter()
+            .filter(|e| e.action == AuditAction::Verified)
+            .count();
+        assert_eq!(appended, 101);
+    }
+
+    #[test]
+    fn rotation_only_when_due() {
+        let tmp = tempfile::tempdir().unwrap();
+        let rotation = AuditRotation {
+            max_bytes: Some(1024 * 1024),
+            max_age: Some(Duration::from_secs(3600)),
+            compress: false,
+            keep: 0,
+        };
+        // Nothing to rotate yet
+        assert!(
+            rotate_audit_log(tmp.path(), &rotation, true)
+                .unwrap()
+                .is_none()
+        );
+
+        append_audit_entry(tmp.path(), AuditAction::Created, "abc", "cli").unwrap();
+        assert!(
+            rotate_audit_log(tmp.path(), &rotation, false)
+                .unwrap()
+                .is_none()
+        );
+
+        // Backdate the first entry past max_age
+        let path = audit_file_path(tmp.path());
+        let mut entry: AuditEntry =
+            serde_json::from_str(fs::read_to_string(&path).unwrap().trim()).unwrap();
+        entry.ts = (Utc::now() - chrono::Duration::hours(2)).to_rfc3339();
+        fs::write(&path, serde_json::to_string(&entry).unwrap() + "\n").unwrap();
+
+        let archive = rotate_audit_log(tmp.path(), &rotation, false)
+            .unwrap()
+            .expect("old log should rotate");
+        assert!(archive.to_string_lossy().ends_with(".jsonl"));
+        assert!(read_segment(&archive).unwrap().contains("\"created\""));
+    }
+
+    #[test]
+    fn rotation_prunes_old_archives() {
+        let tmp = tempfile::tempdir().unwrap();
+        for name in [
+            "localgpt.audit.20250101T000000Z.jsonl.gz",
+            "localgpt.audit.20250201T000000Z.jsonl.gz",
+            "localgpt.audit.20250301T000000Z.jsonl",
+            "localgpt.audit.notes.txt",
+        ] {
+            fs::write(tmp.path().join(name), "").unwrap();
+        }
+        append_audit_entry(tmp.path(), AuditAction::Created, "abc", "cli").unwrap();
+        let rotation = AuditRotation {
+            max_bytes: None,
+            max_age: None,
+            compress: true,
+            keep: 2,
+        };
+
+        let archive = rotate_audit_log(tmp.path(), &rotation, true)
+            .unwrap()
+            .unwrap();
+        let archives = audit_archives(tmp.path()).unwrap();
+        assert_eq!(
+            archives,
+            vec![
+                tmp.path().join("localgpt.audit.20250301T000000Z.jsonl"),
+                archive
+            ]
+        );
+        assert!(tmp.path().join("localgpt.audit.notes.txt").exists());
+    }
+
+    #[test]
+    fn rotation_config_defaults() {
+        let rotation = AuditRotation::from_config(&AuditLogConfig::default()).unwrap();
+        assert_eq!(rotation.max_bytes, Some(10 * 1024 * 1024));
+        assert_eq!(rotation.max_age, Some(Duration::from_secs(90 * 86400)));
+        assert!(rotation.compress);
+        assert_eq!(rotation.keep, 12);
+
+        let unlimited = AuditRotation::from_config(&AuditLogConfig {
+            max_size_mb: 0,
+            max_age: String::new(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(unlimited.max_bytes, None);
+        assert_eq!(unlimited.max_age, None);
+    }
 }
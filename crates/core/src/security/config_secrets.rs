@@ -0,0 +1,125 @@
+//! Config secrets encrypted with the device key.
+//!
+//! A value like `api_token = "enc:..."` in config.toml holds a secret
+//! encrypted with ChaCha20-Poly1305 under a key derived from the device key
+//! (HMAC-SHA256(device key, "config-secret")). The base64 payload is
+//! `[nonce (12 bytes)][ciphertext]`. Only this machine's device key can
+//! decrypt it, so a config file that gets committed or synced doesn't give
+//! the secret away. `localgpt secret set` writes these values.
+
+use anyhow::{Result, anyhow, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hmac::{Hmac, Mac};
+use rand::RngExt;
+use sha2::Sha256;
+use std::path::Path;
+
+use super::signing::read_device_key;
+
+/// A config value starting with this is an encrypted secret.
+pub const ENCRYPTED_PREFIX: &str = "enc:";
+
+const NONCE_LEN: usize = 12;
+
+/// Whether `value` is an `enc:` secret.
+pub fn is_encrypted_secret(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Encrypt `secret` with the device key in `data_dir`, returning the
+/// `enc:` value to put in config.toml.
+pub fn encrypt_config_secret(data_dir: &Path, secret: &str) -> Result<String> {
+    let cipher = ChaCha20Poly1305::new(&config_secret_key(data_dir)?);
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rand::rng().fill(&mut nonce_bytes);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), secret.as_bytes())
+        .map_err(|e| anyhow!("Encryption failed: {}", e))?;
+
+    let mut payload = nonce_bytes.to_vec();
+    payload.extend(ciphertext);
+    Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(payload)))
+}
+
+/// Decrypt an `enc:` value written by [`encrypt_config_secret`].
+pub fn decrypt_config_secret(data_dir: &Path, value: &str) -> Result<String> {
+    let Some(encoded) = value.strip_prefix(ENCRYPTED_PREFIX) else {
+        bail!(
+            "Not an encrypted secret (expected '{}...')",
+            ENCRYPTED_PREFIX
+        );
+    };
+    let payload = STANDARD
+        .decode(encoded.trim())
+        .map_err(|_| anyhow!("Encrypted secret is not valid base64"))?;
+    if payload.len() <= NONCE_LEN {
+        bail!("Encrypted secret is too short");
+    }
+    let (nonce_bytes, ciphertext) = payload.split_at(NONCE_LEN);
+
+    let cipher = ChaCha20Poly1305::new(&config_secret_key(data_dir)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|_| {
+            anyhow!("Cannot decrypt secret; it was encrypted with a different device key")
+        })?;
+    String::from_utf8(plaintext).map_err(|_| anyhow!("Decrypted secret is not valid UTF-8"))
+}
+
+/// Key = HMAC-SHA256(device key, "config-secret").
+fn config_secret_key(data_dir: &Path) -> Result<Key> {
+    let device_key = read_device_key(data_dir)?;
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&device_key)
+        .map_err(|e| anyhow!("HMAC init failed: {}", e))?;
+    mac.update(b"config-secret");
+    Ok(*Key::from_slice(&mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::ensure_device_key;
+
+    #[test]
+    fn round_trips_with_the_device_key() {
+        let tmp = tempfile::tempdir().unwrap();
+        ensure_device_key(tmp.path()).unwrap();
+
+        let value = encrypt_config_secret(tmp.path(), "123456:ABC-token").unwrap();
+        assert!(is_encrypted_secret(&value));
+        assert!(!value.contains("ABC-token"));
+        assert_eq!(
+            decrypt_config_secret(tmp.path(), &value).unwrap(),
+            "123456:ABC-token"
+        );
+
+        // Fresh nonce every time
+        let again = encrypt_config_secret(tmp.path(), "123456:ABC-token").unwrap();
+        assert_ne!(value, again);
+    }
+
+    #[test]
+    fn other_device_key_cannot_decrypt() {
+        let ours = tempfile::tempdir().unwrap();
+        let theirs = tempfile::tempdir().unwrap();
+        ensure_device_key(ours.path()).unwrap();
+        ensure_device_key(theirs.path()).unwrap();
+
+        let value = encrypt_config_secret(ours.path(), "secret").unwrap();
+        let err = decrypt_config_secret(theirs.path(), &value).unwrap_err();
+        assert!(err.to_string().contains("different device key"));
+    }
+
+    #[test]
+    fn rejects_malformed_values() {
+        let tmp = tempfile::tempdir().unwrap();
+        ensure_device_key(tmp.path()).unwrap();
+
+        assert!(decrypt_config_secret(tmp.path(), "sk-plain").is_err());
+        assert!(decrypt_config_secret(tmp.path(), "enc:not base64!").is_err());
+        assert!(decrypt_config_secret(tmp.path(), "enc:AAAA").is_err());
+    }
+}
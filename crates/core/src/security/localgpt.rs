@@ -42,7 +42,9 @@
 //!    writing to `LocalGPT.md`, the manifest, the device key, and
 //!    the audit log via `write_file`/`edit_file` tools. Bash commands
 //!    get a best-effort heuristic check (true enforcement requires
-//!    OS-level sandboxing).
+//!    OS-level sandboxing). Where an interface can ask the user, a
+//!    write to a workspace protected file is queued instead and only
+//!    that exact write goes ahead once approved (`write_approval.rs`).
 //!
 //! 5. **Recency-reinforced**: The hardcoded security suffix is always
 //!    the last content in the context window, exploiting transformer
@@ -95,6 +97,8 @@
 //!
 //! ~/.local/state/localgpt/                  # State directory (XDG_STATE_HOME)
 //! ├── localgpt.audit.jsonl                  # Append-only audit log
+//! ├── localgpt.audit.<ts>.jsonl.gz          # Rotated audit log segments
+//! ├── localgpt.tools.jsonl                  # Tool usage audit log
 //! ├── agents/{agent_id}/sessions/           # Session transcripts
 //! └── logs/                                 # Application logs
 //!
@@ -106,7 +110,7 @@
 //!
 //! | Threat | Defense Layer |
 //! |--------|--------------|
-//! | Agent writes to `LocalGPT.md` via tool | Protected files deny list |
+//! | Agent writes to `LocalGPT.md` via tool | Protected files deny list, or per-write user approval |
 //! | Agent writes via `bash` | Heuristic check + OS sandbox (separate) |
 //! | Injected content in policy file | Sanitization pipeline (blocking) |
 //! | Modified policy after signing | HMAC verification |
@@ -114,6 +118,7 @@
 //! | Policy weakens hardcoded rules | Hardcoded suffix always last in context |
 //! | Policy floods context window | 4096 char limit |
 //! | Audit log tampered | Hash chain + state dir location |
+//! | Secrets in tool output sent to the model | Redaction (`redact.rs`) |
 
 // ── Policy Verification ─────────────────────────────────────────────
 
@@ -131,8 +136,40 @@ pub use super::signing::{
 // ── Audit Log ───────────────────────────────────────────────────────
 
 pub use super::audit::{
-    AuditAction, AuditEntry, append_audit_entry, append_audit_entry_with_detail, audit_file_path,
-    read_audit_log, verify_audit_chain,
+    AuditAction, AuditEntry, AuditQuery, AuditRotation, append_audit_entry,
+    append_audit_entry_with_detail, audit_archives, audit_file_path, query_audit_log,
+    read_audit_log, rotate_audit_log, verify_audit_chain,
+};
+
+// ── Tool Usage Audit ────────────────────────────────────────────────
+
+pub use super::tool_audit::{
+    MAX_AUDIT_ARGS_CHARS, ToolAuditEntry, ToolAuditQuery, ToolOutcome, append_tool_audit_entry,
+    query_tool_audit_log, read_tool_audit_log, tool_audit_file_path,
+};
+
+// ── HTTP API Keys ───────────────────────────────────────────────────
+
+pub use super::api_keys::{API_KEY_PREFIX, ApiKey, ApiKeyStore, ApiScope, constant_time_eq};
+
+// ── Encrypted Config Secrets ────────────────────────────────────────
+
+pub use super::config_secrets::{
+    ENCRYPTED_PREFIX, decrypt_config_secret, encrypt_config_secret, is_encrypted_secret,
+};
+
+// ── OS Keyring ──────────────────────────────────────────────────────
+
+pub use super::os_keyring::{
+    KEYRING_PREFIX, delete_keyring_secret, get_keyring_bytes, get_keyring_secret,
+    keyring_reference, keyring_service, set_keyring_bytes, set_keyring_secret,
+};
+
+// ── Secret Redaction ────────────────────────────────────────────────
+
+pub use super::redact::{
+    REDACTED, RedactingWriter, redact_known_secrets, redact_secrets, redact_tool_output,
+    register_secret, set_redaction_enabled,
 };
 
 // ── Protected Files ─────────────────────────────────────────────────
@@ -142,6 +179,13 @@ pub use super::protected_files::{
     is_workspace_file_protected,
 };
 
+// ── Protected Write Approval ────────────────────────────────────────
+
+pub use super::write_approval::{
+    PendingWrite, WriteApprover, WriteDecision, WriteOrigin, add_write_approver, pending_writes,
+    request_protected_write, resolve_write, with_write_origin,
+};
+
 // ── Context Window Suffix ───────────────────────────────────────────
 
 pub use super::suffix::{HARDCODED_SECURITY_SUFFIX, build_ending_security_block};
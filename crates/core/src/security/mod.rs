@@ -3,12 +3,18 @@
 //! See [`localgpt`] for the module overview, architecture diagram,
 //! and public API documentation.
 
+mod api_keys;
 mod audit;
+mod config_secrets;
 mod localgpt;
+mod os_keyring;
 mod policy;
 mod protected_files;
+mod redact;
 mod signing;
 mod suffix;
+mod tool_audit;
+mod write_approval;
 
 // The localgpt.rs facade controls the entire public API surface.
 pub use self::localgpt::*;
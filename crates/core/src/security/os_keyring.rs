@@ -0,0 +1,161 @@
+//! Secrets kept in the OS keyring.
+//!
+//! Provider API keys and other config secrets can be stored in the macOS
+//! Keychain, the Secret Service (GNOME Keyring, KWallet) on Linux, or the
+//! Windows Credential Manager instead of `config.toml`. The config then
+//! names the entry, e.g. `api_key = "keyring:openai"`, and the value is
+//! looked up when the config is loaded. Entries are created with
+//! `localgpt keyring set <name>`.
+//!
+//! Entries live under the service `localgpt` (`localgpt-<profile>` for a
+//! profile), so profiles don't share secrets. Bridge secrets registered with
+//! `localgpt bridge register --keyring` are stored as `bridge:<id>`.
+//!
+//! Builds without the `keyring` feature (e.g. mobile) fail every lookup.
+
+use anyhow::{Result, bail};
+
+use crate::env::LOCALGPT_PROFILE;
+use crate::paths::normalize_profile;
+
+/// A config value starting with this names a keyring entry.
+pub const KEYRING_PREFIX: &str = "keyring:";
+
+/// The keyring entry a config value refers to, if it is a `keyring:` value.
+pub fn keyring_reference(value: &str) -> Option<&str> {
+    value
+        .strip_prefix(KEYRING_PREFIX)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// Keyring service the current profile's entries are stored under.
+pub fn keyring_service() -> String {
+    let profile = std::env::var(LOCALGPT_PROFILE)
+        .ok()
+        .and_then(|name| normalize_profile(&name).ok().flatten());
+    match profile {
+        Some(profile) => format!("localgpt-{}", profile),
+        None => "localgpt".to_string(),
+    }
+}
+
+/// Read the secret stored as `name`.
+pub fn get_keyring_secret(name: &str) -> Result<String> {
+    let secret = get_keyring_bytes(name)?;
+    String::from_utf8(secret)
+        .map_err(|_| anyhow::anyhow!("Keyring entry '{}' is not valid UTF-8", name))
+}
+
+/// Store `secret` as `name`, replacing any existing entry.
+pub fn set_keyring_secret(name: &str, secret: &str) -> Result<()> {
+    set_keyring_bytes(name, secret.as_bytes())
+}
+
+/// Read the raw bytes stored as `name`.
+pub fn get_keyring_bytes(name: &str) -> Result<Vec<u8>> {
+    validate_entry_name(name)?;
+    imp::get(&keyring_service(), name)
+}
+
+/// Store raw bytes as `name`, replacing any existing entry.
+pub fn set_keyring_bytes(name: &str, secret: &[u8]) -> Result<()> {
+    validate_entry_name(name)?;
+    imp::set(&keyring_service(), name, secret)
+}
+
+/// Remove the entry `name`. Returns false if there was none.
+pub fn delete_keyring_secret(name: &str) -> Result<bool> {
+    validate_entry_name(name)?;
+    imp::delete(&keyring_service(), name)
+}
+
+fn validate_entry_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > 128 {
+        bail!("Keyring entry names must be 1-128 characters");
+    }
+    if name.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        bail!(
+            "Keyring entry name '{}' contains whitespace or control characters",
+            name.escape_default()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "keyring")]
+mod imp {
+    use anyhow::{Context, Result};
+    use keyring::{Entry, Error};
+
+    pub fn get(service: &str, name: &str) -> Result<Vec<u8>> {
+        match Entry::new(service, name)?.get_secret() {
+            Ok(secret) => Ok(secret),
+            Err(Error::NoEntry) => anyhow::bail!(
+                "No keyring entry '{}' (add it with `localgpt keyring set {}`)",
+                name,
+                name
+            ),
+            Err(e) => Err(e).with_context(|| format!("Failed to read keyring entry '{}'", name)),
+        }
+    }
+
+    pub fn set(service: &str, name: &str, secret: &[u8]) -> Result<()> {
+        Entry::new(service, name)?
+            .set_secret(secret)
+            .with_context(|| format!("Failed to store keyring entry '{}'", name))
+    }
+
+    pub fn delete(service: &str, name: &str) -> Result<bool> {
+        match Entry::new(service, name)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(Error::NoEntry) => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete keyring entry '{}'", name)),
+        }
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+mod imp {
+    use anyhow::{Result, bail};
+
+    const UNSUPPORTED: &str = "This build of LocalGPT has no OS keyring support";
+
+    pub fn get(_service: &str, _name: &str) -> Result<Vec<u8>> {
+        bail!(UNSUPPORTED)
+    }
+
+    pub fn set(_service: &str, _name: &str, _secret: &[u8]) -> Result<()> {
+        bail!(UNSUPPORTED)
+    }
+
+    pub fn delete(_service: &str, _name: &str) -> Result<bool> {
+        bail!(UNSUPPORTED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyring_reference_parses_prefix() {
+        assert_eq!(keyring_reference("keyring:openai"), Some("openai"));
+        assert_eq!(
+            keyring_reference("keyring: bridge:telegram "),
+            Some("bridge:telegram")
+        );
+        assert_eq!(keyring_reference("keyring:"), None);
+        assert_eq!(keyring_reference("sk-abc"), None);
+        assert_eq!(keyring_reference("${OPENAI_API_KEY}"), None);
+    }
+
+    #[test]
+    fn entry_names_are_validated() {
+        assert!(validate_entry_name("openai").is_ok());
+        assert!(validate_entry_name("bridge:telegram").is_ok());
+        assert!(validate_entry_name("").is_err());
+        assert!(validate_entry_name("two words").is_err());
+        assert!(validate_entry_name(&"x".repeat(129)).is_err());
+    }
+}
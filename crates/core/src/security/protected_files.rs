@@ -88,12 +88,13 @@ pub fn is_path_protected(path: &str, workspace: &Path, state_dir: &Path) -> bool
         }
     }
 
-    // Fallback: check if path ends with any external protected filename
+    // Fallback: check if path ends with any external protected filename,
+    // including rotated audit log segments (`localgpt.audit.<ts>.jsonl.gz`)
     let name = Path::new(path)
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or(path);
-    PROTECTED_EXTERNAL_PATHS.contains(&name)
+    PROTECTED_EXTERNAL_PATHS.contains(&name) || name.starts_with("localgpt.audit.")
 }
 
 /// Best-effort check for bash commands that might write to protected files.
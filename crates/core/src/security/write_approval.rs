@@ -0,0 +1,541 @@
+//! Approval flow for agent writes to protected workspace files.
+//!
+//! When approvers are registered, a `write_file`/`edit_file` (or strict-mode
+//! `bash`) call that touches `LocalGPT.md`, the manifest, or `IDENTITY.md`
+//! isn't rejected outright: the write is queued as a [`PendingWrite`], every
+//! registered [`WriteApprover`] is asked (terminal prompt, Telegram buttons,
+//! web UI card), and an approval issues a one-time token bound to the path
+//! and the exact content. The tool consumes the token and writes; any other
+//! content needs a new approval. Each step is recorded in the audit log.
+//!
+//! Without an approver, and always for the device key and audit log, the
+//! write stays blocked.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::sync::oneshot;
+use tracing::{debug, info, warn};
+
+use super::audit::{AuditAction, append_audit_entry_with_detail};
+use super::protected_files::is_workspace_file_protected;
+use crate::webhooks::{self, WebhookEvent};
+
+/// How long a tool call waits for an answer before reporting the write as
+/// pending.
+const APPROVAL_WAIT: Duration = Duration::from_secs(120);
+
+/// How long an unanswered request can still be approved.
+const PENDING_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// How long an approved write may take to be retried.
+const TOKEN_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Maximum characters of the new content shown to approvers.
+const MAX_PREVIEW_CHARS: usize = 1000;
+
+/// A protected write waiting for the user's decision.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingWrite {
+    pub id: String,
+    /// Tool that attempted the write
+    pub tool: String,
+    pub path: PathBuf,
+    /// SHA-256 of the content that would be written
+    pub content_sha256: String,
+    /// The write (or command) to show the user, truncated
+    pub preview: String,
+    /// Channel of the turn that made the request (`cli`, `telegram`, ...)
+    pub channel: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    pub requested_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Asks the user about protected writes.
+pub trait WriteApprover: Send + Sync {
+    /// Shown in logs
+    fn name(&self) -> &str;
+
+    /// Called from a blocking thread. Returns the user's answer, or `None`
+    /// if it will come later through [`resolve_write`] (or this approver
+    /// doesn't serve `write.channel`).
+    fn ask(&self, write: &PendingWrite) -> Option<bool>;
+}
+
+/// Outcome of [`request_protected_write`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteDecision {
+    /// The path can't be approved, or no approver is registered
+    Blocked,
+    /// A token was consumed; go ahead with the write
+    Approved,
+    Denied,
+    /// Nobody answered in time; the id can still be approved
+    Pending(String),
+}
+
+/// Which turn a tool call belongs to, for approval prompts.
+#[derive(Debug, Clone, Default)]
+pub struct WriteOrigin {
+    pub channel: String,
+    pub session_id: Option<String>,
+}
+
+tokio::task_local! {
+    static WRITE_ORIGIN: WriteOrigin;
+}
+
+/// Run `future` (a tool call) with `origin` visible to
+/// [`request_protected_write`].
+pub async fn with_write_origin<F: Future>(origin: WriteOrigin, future: F) -> F::Output {
+    WRITE_ORIGIN.scope(origin, future).await
+}
+
+static APPROVERS: RwLock<Vec<Arc<dyn WriteApprover>>> = RwLock::new(Vec::new());
+
+static APPROVALS: LazyLock<Mutex<Approvals>> = LazyLock::new(Default::default);
+
+#[derive(Default)]
+struct Approvals {
+    pending: HashMap<String, Queued>,
+    tokens: Vec<Token>,
+}
+
+struct Queued {
+    write: PendingWrite,
+    state_dir: PathBuf,
+    queued: Instant,
+    waiter: Option<oneshot::Sender<bool>>,
+}
+
+/// One-time permission for a single write
+struct Token {
+    id: String,
+    path: PathBuf,
+    content_sha256: String,
+    expires: Instant,
+}
+
+/// Register an approver. Interfaces that can reach the user (the chat
+/// prompt, the Telegram bot, the web UI) call this at startup; with none
+/// registered, protected writes are blocked.
+pub fn add_write_approver(approver: Arc<dyn WriteApprover>) {
+    debug!("Registered write approver '{}'", approver.name());
+    APPROVERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(approver);
+}
+
+fn approvers() -> Vec<Arc<dyn WriteApprover>> {
+    APPROVERS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn approvals() -> std::sync::MutexGuard<'static, Approvals> {
+    APPROVALS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Ask to write `content` to the protected file at `path`.
+///
+/// Consumes a matching token if the same write was approved earlier;
+/// otherwise queues the write, asks every approver, and waits a while for
+/// the answer. `preview` is what the user is shown.
+pub async fn request_protected_write(
+    state_dir: &Path,
+    tool: &str,
+    path: &Path,
+    content: &str,
+    preview: &str,
+) -> WriteDecision {
+    if !is_workspace_file_protected(&path.to_string_lossy()) {
+        return WriteDecision::Blocked;
+    }
+    let content_sha256 = sha256_hex(content.as_bytes());
+    if let Some(id) = take_token(path, &content_sha256) {
+        audit(
+            state_dir,
+            AuditAction::WriteTokenUsed,
+            &content_sha256,
+            &format!("tool:{}", tool),
+            &format!("#{} {}", id, path.display()),
+        );
+        return WriteDecision::Approved;
+    }
+    let approvers = approvers();
+    if approvers.is_empty() {
+        return WriteDecision::Blocked;
+    }
+
+    let origin = WRITE_ORIGIN.try_with(Clone::clone).unwrap_or_default();
+    let write = PendingWrite {
+        id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+        tool: tool.to_string(),
+        path: path.to_path_buf(),
+        content_sha256: content_sha256.clone(),
+        preview: truncate(preview, MAX_PREVIEW_CHARS),
+        channel: origin.channel,
+        session_id: origin.session_id,
+        requested_at: chrono::Utc::now(),
+    };
+    let (tx, rx) = oneshot::channel();
+    {
+        let mut approvals = approvals();
+        approvals.prune(Instant::now());
+        approvals.pending.insert(
+            write.id.clone(),
+            Queued {
+                write: write.clone(),
+                state_dir: state_dir.to_path_buf(),
+                queued: Instant::now(),
+                waiter: Some(tx),
+            },
+        );
+    }
+    info!(
+        "Protected write {} to {} waiting for approval",
+        write.id,
+        path.display()
+    );
+    audit(
+        state_dir,
+        AuditAction::WriteApprovalRequested,
+        &content_sha256,
+        &format!("tool:{}", tool),
+        &format!("#{} {} ({})", write.id, path.display(), write.channel),
+    );
+    webhooks::emit_to(
+        &[],
+        WebhookEvent::WriteApprovalRequested {
+            id: write.id.clone(),
+            tool: write.tool.clone(),
+            path: path.display().to_string(),
+            channel: write.channel.clone(),
+        },
+    );
+
+    for approver in approvers {
+        let write = write.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Some(approved) = approver.ask(&write)
+                && let Err(e) = resolve_write(&write.id, approved, approver.name())
+            {
+                debug!("Approver '{}' answered late: {}", approver.name(), e);
+            }
+        });
+    }
+
+    match tokio::time::timeout(APPROVAL_WAIT, rx).await {
+        Ok(Ok(true)) => match take_token(path, &content_sha256) {
+            Some(id) => {
+                audit(
+                    state_dir,
+                    AuditAction::WriteTokenUsed,
+                    &content_sha256,
+                    &format!("tool:{}", tool),
+                    &format!("#{} {}", id, path.display()),
+                );
+                WriteDecision::Approved
+            }
+            // Another call with the same content got there first
+            None => WriteDecision::Pending(write.id),
+        },
+        Ok(Ok(false)) => WriteDecision::Denied,
+        // Expired, or the wait was abandoned
+        Ok(Err(_)) => WriteDecision::Denied,
+        Err(_) => WriteDecision::Pending(write.id),
+    }
+}
+
+/// Answer the pending write `id`; `by` names the interface that answered.
+/// Approving issues a one-time token for the write.
+pub fn resolve_write(id: &str, approved: bool, by: &str) -> Result<PendingWrite> {
+    let queued = {
+        let mut approvals = approvals();
+        approvals.prune(Instant::now());
+        let Some(queued) = approvals.pending.remove(id) else {
+            bail!("No pending write with id '{}'", id);
+        };
+        if approved {
+            approvals.tokens.push(Token {
+                id: id.to_string(),
+                path: queued.write.path.clone(),
+                content_sha256: queued.write.content_sha256.clone(),
+                expires: Instant::now() + TOKEN_TTL,
+            });
+        }
+        queued
+    };
+
+    let write = queued.write;
+    info!(
+        "Protected write {} to {} {} via {}",
+        id,
+        write.path.display(),
+        if approved { "approved" } else { "denied" },
+        by
+    );
+    audit(
+        &queued.state_dir,
+        if approved {
+            AuditAction::WriteApproved
+        } else {
+            AuditAction::WriteDenied
+        },
+        &write.content_sha256,
+        by,
+        &format!("#{} {}", id, write.path.display()),
+    );
+    webhooks::emit_to(
+        &[],
+        WebhookEvent::WriteApprovalResolved {
+            id: id.to_string(),
+            approved,
+            by: by.to_string(),
+        },
+    );
+    if let Some(waiter) = queued.waiter {
+        let _ = waiter.send(approved);
+    }
+    Ok(write)
+}
+
+/// Writes waiting for an answer, oldest first.
+pub fn pending_writes() -> Vec<PendingWrite> {
+    let mut approvals = approvals();
+    approvals.prune(Instant::now());
+    let mut pending: Vec<PendingWrite> = approvals
+        .pending
+        .values()
+        .map(|queued| queued.write.clone())
+        .collect();
+    pending.sort_by_key(|write| write.requested_at);
+    pending
+}
+
+fn take_token(path: &Path, content_sha256: &str) -> Option<String> {
+    let mut approvals = approvals();
+    approvals.prune(Instant::now());
+    let index = approvals
+        .tokens
+        .iter()
+        .position(|token| token.path == path && token.content_sha256 == content_sha256)?;
+    Some(approvals.tokens.remove(index).id)
+}
+
+impl Approvals {
+    /// Drop expired tokens and requests; expired requests count as denied.
+    fn prune(&mut self, now: Instant) {
+        self.tokens.retain(|token| token.expires > now);
+        let expired: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, queued)| now.duration_since(queued.queued) > PENDING_TTL)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in expired {
+            if let Some(queued) = self.pending.remove(&id) {
+                audit(
+                    &queued.state_dir,
+                    AuditAction::WriteDenied,
+                    &queued.write.content_sha256,
+                    "expired",
+                    &format!("#{} {}", id, queued.write.path.display()),
+                );
+            }
+        }
+    }
+}
+
+fn audit(state_dir: &Path, action: AuditAction, content_sha256: &str, source: &str, detail: &str) {
+    if let Err(e) =
+        append_audit_entry_with_detail(state_dir, action, content_sha256, source, Some(detail))
+    {
+        warn!("Failed to record write approval in audit log: {}", e);
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Approves writes whose preview contains "yes", defers the rest
+    struct TestApprover {
+        asked: AtomicUsize,
+    }
+
+    impl WriteApprover for TestApprover {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        fn ask(&self, write: &PendingWrite) -> Option<bool> {
+            self.asked.fetch_add(1, Ordering::SeqCst);
+            if write.preview.contains("yes") {
+                Some(true)
+            } else if write.preview.contains("no") {
+                Some(false)
+            } else {
+                None
+            }
+        }
+    }
+
+    fn register() -> Arc<TestApprover> {
+        static APPROVER: LazyLock<Arc<TestApprover>> = LazyLock::new(|| {
+            let approver = Arc::new(TestApprover {
+                asked: AtomicUsize::new(0),
+            });
+            add_write_approver(approver.clone());
+            approver
+        });
+        APPROVER.clone()
+    }
+
+    fn audit_log(state_dir: &Path) -> String {
+        std::fs::read_to_string(state_dir.join("localgpt.audit.jsonl")).unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_unprotected_and_external_paths_are_blocked() {
+        register();
+        let tmp = tempfile::tempdir().unwrap();
+        for name in ["notes.md", "localgpt.device.key", "localgpt.audit.jsonl"] {
+            let decision = request_protected_write(
+                tmp.path(),
+                "write_file",
+                &tmp.path().join(name),
+                "x",
+                "yes",
+            )
+            .await;
+            assert_eq!(decision, WriteDecision::Blocked, "{}", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_approved_write_consumes_token_once() {
+        let approver = register();
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("LocalGPT.md");
+
+        let asked = approver.asked.load(Ordering::SeqCst);
+        let decision = request_protected_write(tmp.path(), "write_file", &path, "A", "yes").await;
+        assert_eq!(decision, WriteDecision::Approved);
+        assert!(approver.asked.load(Ordering::SeqCst) > asked);
+        assert!(take_token(&path, &sha256_hex(b"A")).is_none());
+
+        let log = audit_log(tmp.path());
+        assert!(log.contains("write_approval_requested"));
+        assert!(log.contains("write_approved"));
+        assert!(log.contains("write_token_used"));
+    }
+
+    #[tokio::test]
+    async fn test_denied_write() {
+        register();
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("IDENTITY.md");
+
+        let decision = request_protected_write(tmp.path(), "edit_file", &path, "B", "no").await;
+        assert_eq!(decision, WriteDecision::Denied);
+        assert!(audit_log(tmp.path()).contains("write_denied"));
+    }
+
+    #[tokio::test]
+    async fn test_late_approval_issues_token_for_same_content() {
+        register();
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("LocalGPT.md");
+
+        let request = tokio::spawn({
+            let state_dir = tmp.path().to_path_buf();
+            let path = path.clone();
+            async move {
+                with_write_origin(
+                    WriteOrigin {
+                        channel: "telegram".to_string(),
+                        session_id: Some("s1".to_string()),
+                    },
+                    request_protected_write(&state_dir, "write_file", &path, "C", "later"),
+                )
+                .await
+            }
+        });
+
+        let pending = loop {
+            if let Some(write) = pending_writes().into_iter().find(|w| w.path == path) {
+                break write;
+            }
+            tokio::task::yield_now().await;
+        };
+        assert_eq!(pending.channel, "telegram");
+        assert_eq!(pending.session_id.as_deref(), Some("s1"));
+
+        resolve_write(&pending.id, true, "http").unwrap();
+        assert_eq!(request.await.unwrap(), WriteDecision::Approved);
+        assert!(resolve_write(&pending.id, true, "http").is_err());
+
+        // The token was used; a different write asks again
+        assert!(take_token(&path, &sha256_hex(b"C")).is_none());
+    }
+
+    #[test]
+    fn test_expired_requests_are_denied() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut approvals = Approvals::default();
+        let now = Instant::now();
+        approvals.pending.insert(
+            "old".to_string(),
+            Queued {
+                write: PendingWrite {
+                    id: "old".to_string(),
+                    tool: "write_file".to_string(),
+                    path: tmp.path().join("LocalGPT.md"),
+                    content_sha256: String::new(),
+                    preview: String::new(),
+                    channel: "cli".to_string(),
+                    session_id: None,
+                    requested_at: chrono::Utc::now(),
+                },
+                state_dir: tmp.path().to_path_buf(),
+                queued: now,
+                waiter: None,
+            },
+        );
+        approvals.tokens.push(Token {
+            id: "t".to_string(),
+            path: tmp.path().join("LocalGPT.md"),
+            content_sha256: String::new(),
+            expires: now + TOKEN_TTL,
+        });
+
+        approvals.prune(now + PENDING_TTL + Duration::from_secs(1));
+        assert!(approvals.pending.is_empty());
+        assert!(approvals.tokens.is_empty());
+        assert!(audit_log(tmp.path()).contains("\"expired\""));
+    }
+
+    #[test]
+    fn test_truncate_preview() {
+        assert_eq!(truncate("abc", 5), "abc");
+        assert_eq!(truncate("abcdef", 3), "abc…");
+    }
+}
@@ -0,0 +1,298 @@
+//! Outbound webhooks for daemon events.
+//!
+//! Each `[[webhooks]]` entry in the config receives the events it
+//! subscribes to as a JSON POST:
+//!
+//! ```json
+//! {
+//!   "event": "cron.finished",
+//!   "timestamp": "2026-01-05T09:00:12Z",
+//!   "data": { "job": "standup", "status": "ok", "duration_ms": 8412, ... }
+//! }
+//! ```
+//!
+//! Requests carry `X-LocalGPT-Event`, a unique `X-LocalGPT-Delivery` id,
+//! and, when the webhook has a `secret`, `X-LocalGPT-Signature:
+//! sha256=<hex>` — an HMAC-SHA256 of the body. Failed deliveries (network
+//! errors or non-2xx responses) are retried with exponential backoff.
+//! Delivery runs in the background and never blocks the event source.
+//! Every event is also published on the [`crate::events`] bus.
+
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sha2::Sha256;
+use tracing::{debug, warn};
+
+use crate::config::{Config, WebhookConfig, parse_duration};
+use crate::events;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Backoff before the second attempt; doubled for each later one.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Events delivered to webhooks.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum WebhookEvent {
+    /// A chat session was started
+    SessionCreated { session_id: String, channel: String },
+    /// A chat session ended (expired, deleted, or closed)
+    SessionCompleted {
+        session_id: String,
+        channel: String,
+        message_count: usize,
+    },
+    /// A cron job run finished, successfully or not
+    CronFinished {
+        job: String,
+        status: String,
+        duration_ms: u64,
+        attempts: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// A bridge process connected to the daemon
+    BridgeConnected {
+        connection_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pid: Option<i32>,
+    },
+    /// A bridge process disconnected
+    BridgeDisconnected {
+        connection_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        bridge_id: Option<String>,
+    },
+    /// A bridge's health changed (`healthy`, `degraded`, or `unhealthy`)
+    BridgeHealth {
+        bridge_id: String,
+        connection_id: String,
+        health: String,
+        idle_secs: u64,
+    },
+    /// A connected bridge stopped responding
+    BridgeUnhealthy {
+        bridge_id: String,
+        connection_id: String,
+        idle_secs: u64,
+    },
+    /// An unknown bridge executable asked for credentials
+    BridgeApprovalRequested {
+        bridge_id: String,
+        fingerprint: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exe: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pid: Option<i32>,
+    },
+    /// A configured spending or usage limit was reached
+    BudgetExceeded {
+        budget: String,
+        limit: String,
+        detail: String,
+    },
+    /// A `localgpt watch` turn finished after files changed
+    WatchFinished {
+        files: Vec<String>,
+        status: String,
+        duration_ms: u64,
+        response: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// An agent write to a protected file is waiting for the user
+    WriteApprovalRequested {
+        id: String,
+        tool: String,
+        path: String,
+        channel: String,
+    },
+    /// A protected write was approved or denied
+    WriteApprovalResolved {
+        id: String,
+        approved: bool,
+        by: String,
+    },
+}
+
+impl WebhookEvent {
+    /// Event name, as used in a webhook's `events` filter.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookEvent::SessionCreated { .. } => "session.created",
+            WebhookEvent::SessionCompleted { .. } => "session.completed",
+            WebhookEvent::CronFinished { .. } => "cron.finished",
+            WebhookEvent::BridgeConnected { .. } => "bridge.connected",
+            WebhookEvent::BridgeDisconnected { .. } => "bridge.disconnected",
+            WebhookEvent::BridgeHealth { .. } => "bridge.health",
+            WebhookEvent::BridgeUnhealthy { .. } => "bridge.unhealthy",
+            WebhookEvent::BridgeApprovalRequested { .. } => "bridge.approval_requested",
+            WebhookEvent::BudgetExceeded { .. } => "budget.exceeded",
+            WebhookEvent::WatchFinished { .. } => "watch.finished",
+            WebhookEvent::WriteApprovalRequested { .. } => "approval.requested",
+            WebhookEvent::WriteApprovalResolved { .. } => "approval.resolved",
+        }
+    }
+
+    /// `{event, timestamp, data}`, the body POSTed to webhooks.
+    pub fn payload(&self) -> Value {
+        json!({
+            "event": self.name(),
+            "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "data": self,
+        })
+    }
+}
+
+/// Whether `webhook` subscribes to `event`.
+fn subscribes(webhook: &WebhookConfig, event: &str) -> bool {
+    events::matches_filter(&webhook.events, event)
+}
+
+/// `sha256=<hex>` HMAC of `body` under `secret`.
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+/// Publish `event` and deliver it to every subscribed webhook in the
+/// background. Delivery does nothing without webhooks or outside a Tokio
+/// runtime.
+pub fn emit(config: &Config, event: WebhookEvent) {
+    emit_to(&config.webhooks, event);
+}
+
+/// [`emit`] for code that holds the webhook list rather than the config.
+pub fn emit_to(webhooks: &[WebhookConfig], event: WebhookEvent) {
+    events::publish(&event);
+
+    let targets: Vec<WebhookConfig> = webhooks
+        .iter()
+        .filter(|w| subscribes(w, event.name()))
+        .cloned()
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        debug!("No runtime to deliver webhook event {}", event.name());
+        return;
+    };
+
+    let body = match serde_json::to_vec(&event.payload()) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to serialize webhook event {}: {}", event.name(), e);
+            return;
+        }
+    };
+    let name = event.name();
+    for webhook in targets {
+        let body = body.clone();
+        handle.spawn(async move {
+            if let Err(e) = deliver(&webhook, name, &body).await {
+                warn!("Webhook {} for {} failed: {}", webhook.url, name, e);
+            }
+        });
+    }
+}
+
+/// POST `body` to the webhook, retrying until it succeeds or runs out of
+/// attempts.
+async fn deliver(webhook: &WebhookConfig, event: &str, body: &[u8]) -> Result<()> {
+    let timeout = parse_duration(&webhook.timeout).unwrap_or(Duration::from_secs(10));
+    let client = reqwest::Client::builder().timeout(timeout).build()?;
+    let delivery = uuid::Uuid::new_v4().to_string();
+    let signature = webhook.secret.as_deref().map(|s| sign_payload(s, body));
+
+    let attempts = webhook.max_attempts.max(1);
+    let mut backoff = INITIAL_BACKOFF;
+    let mut last_error = String::new();
+    for attempt in 1..=attempts {
+        let mut request = client
+            .post(&webhook.url)
+            .header("content-type", "application/json")
+            .header("x-localgpt-event", event)
+            .header("x-localgpt-delivery", &delivery)
+            .body(body.to_vec());
+        if let Some(signature) = &signature {
+            request = request.header("x-localgpt-signature", signature);
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!(
+                    "Delivered {} to {} (attempt {})",
+                    event, webhook.url, attempt
+                );
+                return Ok(());
+            }
+            Ok(response) => last_error = format!("HTTP {}", response.status()),
+            Err(e) => last_error = e.to_string(),
+        }
+        if attempt < attempts {
+            debug!(
+                "Webhook {} attempt {} failed ({}); retrying in {:?}",
+                webhook.url, attempt, last_error, backoff
+            );
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+    bail!("{} after {} attempt(s)", last_error, attempts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(events: &[&str]) -> WebhookConfig {
+        WebhookConfig {
+            url: "http://localhost:8123/hook".to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            secret: None,
+            max_attempts: 3,
+            timeout: "10s".to_string(),
+        }
+    }
+
+    #[test]
+    fn filters_events() {
+        assert!(subscribes(&webhook(&[]), "cron.finished"));
+        assert!(subscribes(&webhook(&["*"]), "budget.exceeded"));
+        assert!(subscribes(&webhook(&["bridge.*"]), "bridge.unhealthy"));
+        assert!(subscribes(&webhook(&["cron.finished"]), "cron.finished"));
+        assert!(!subscribes(
+            &webhook(&["cron.finished"]),
+            "bridge.unhealthy"
+        ));
+    }
+
+    #[test]
+    fn payload_and_signature() {
+        let event = WebhookEvent::CronFinished {
+            job: "standup".to_string(),
+            status: "ok".to_string(),
+            duration_ms: 1200,
+            attempts: 1,
+            error: None,
+        };
+        let payload = event.payload();
+        assert_eq!(payload["event"], "cron.finished");
+        assert_eq!(payload["data"]["job"], "standup");
+        assert!(payload["data"].get("error").is_none());
+
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -87,9 +87,10 @@ impl LocalGPTClient {
                 .map_err(|e| MobileError::Init(e.to_string()))?,
         );
 
-        let agent = rt
+        let mut agent = rt
             .block_on(Agent::new(agent_config, &config, Arc::clone(&memory)))
             .map_err(|e| MobileError::Init(e.to_string()))?;
+        agent.set_channel("mobile");
 
         let handle = AgentHandle::new(agent);
 
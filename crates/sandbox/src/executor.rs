@@ -1,21 +1,32 @@
 use anyhow::Result;
 use std::time::Duration;
 
-use crate::policy::SandboxPolicy;
+use crate::policy::{NetworkPolicy, SandboxPolicy};
+use crate::proxy;
 
 /// Run a shell command inside the sandbox.
 ///
 /// This is the parent-side function. It:
-/// 1. Serializes the policy to JSON
-/// 2. Re-execs the current binary with argv[0]="localgpt-sandbox"
-/// 3. Passes policy + command as arguments
-/// 4. Collects output and enforces timeout
+/// 1. Starts the filtering proxy if the network policy is an allowlist
+/// 2. Serializes the policy to JSON
+/// 3. Re-execs the current binary with argv[0]="localgpt-sandbox"
+/// 4. Passes policy + command as arguments
+/// 5. Collects output and enforces timeout
 pub async fn run_sandboxed(
     command: &str,
     policy: &SandboxPolicy,
     timeout_ms: u64,
 ) -> Result<(String, i32)> {
-    let policy_json = serde_json::to_string(policy)?;
+    // Runs until the command finishes; elsewhere an allowlist denies everything
+    let proxy = match &policy.network {
+        NetworkPolicy::Allowlist(hosts) if cfg!(any(target_os = "linux", target_os = "macos")) => {
+            Some(proxy::start(hosts.clone()).await?)
+        }
+        _ => None,
+    };
+    let mut policy = policy.clone();
+    policy.proxy = proxy.as_ref().map(|p| p.endpoint.clone());
+    let policy_json = serde_json::to_string(&policy)?;
 
     // Get path to current executable for re-exec
     let exe_path = std::env::current_exe()?;
@@ -26,19 +37,26 @@ pub async fn run_sandboxed(
     // argv[2] = shell command to execute
     let timeout_duration = Duration::from_millis(timeout_ms);
 
-    let output = tokio::time::timeout(
-        timeout_duration,
-        tokio::process::Command::new(&exe_path)
-            .arg0("localgpt-sandbox")
-            .arg(&policy_json)
-            .arg(command)
-            .current_dir(&policy.workspace_path)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .output(),
-    )
-    .await
-    .map_err(|_| anyhow::anyhow!("Sandboxed command timed out after {}ms", timeout_ms))??;
+    let mut child = tokio::process::Command::new(&exe_path);
+    child
+        .arg0("localgpt-sandbox")
+        .arg(&policy_json)
+        .arg(command)
+        .current_dir(&policy.workspace_path)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(proxy) = &proxy {
+        let url = proxy.url();
+        for var in proxy::PROXY_ENV_VARS {
+            child.env(var, &url);
+        }
+        child.env_remove("NO_PROXY").env_remove("no_proxy");
+    }
+
+    let output = tokio::time::timeout(timeout_duration, child.output())
+        .await
+        .map_err(|_| anyhow::anyhow!("Sandboxed command timed out after {}ms", timeout_ms))??;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
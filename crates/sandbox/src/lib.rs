@@ -6,7 +6,11 @@
 //!
 //! Platform enforcement:
 //! - Linux: Landlock LSM (filesystem) + seccomp-bpf (network syscall deny)
+//!   + a network namespace for allowlisted network access
 //! - macOS: Seatbelt SBPL profiles via sandbox-exec
+//!
+//! Only the command is restricted; the agent's own provider traffic never
+//! goes through the sandbox.
 
 #[cfg(unix)]
 pub mod child;
@@ -17,9 +21,12 @@ pub mod linux;
 #[cfg(target_os = "macos")]
 pub mod macos;
 pub mod policy;
+pub mod proxy;
 
 #[cfg(unix)]
 pub use child::sandbox_child_main;
 pub use detect::{SandboxCapabilities, detect_capabilities};
 pub use executor::run_sandboxed;
-pub use policy::{NetworkPolicy, SandboxLevel, SandboxMode, SandboxPolicy, build_policy};
+pub use policy::{
+    NetworkPolicy, ProxyEndpoint, SandboxLevel, SandboxMode, SandboxPolicy, build_policy,
+};
@@ -1,24 +1,70 @@
-use crate::policy::{NetworkPolicy, SandboxPolicy};
+use std::io;
+use std::net::{Ipv4Addr, Shutdown, TcpListener, TcpStream};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+use crate::policy::{NetworkPolicy, ProxyEndpoint, SandboxPolicy};
+use crate::proxy::RELAY_PORT;
 use nix::libc;
 
-/// Apply Linux sandbox enforcement: rlimits → NO_NEW_PRIVS → Landlock → seccomp.
+/// Network-related syscalls denied when the network is off
+const NETWORK_SYSCALLS: [i64; 12] = [
+    libc::SYS_socket,
+    libc::SYS_connect,
+    libc::SYS_accept,
+    libc::SYS_accept4,
+    libc::SYS_bind,
+    libc::SYS_listen,
+    libc::SYS_sendto,
+    libc::SYS_sendmsg,
+    libc::SYS_sendmmsg,
+    libc::SYS_recvfrom,
+    libc::SYS_recvmsg,
+    libc::SYS_recvmmsg,
+];
+
+/// Apply Linux sandbox enforcement: rlimits → NO_NEW_PRIVS → network
+/// namespace → Landlock → seccomp.
 ///
-/// Order matters: seccomp must be last because it blocks syscalls that
+/// Order matters: the namespace needs `/proc/self` writes that Landlock
+/// forbids, and seccomp must be last because it blocks syscalls that
 /// Landlock setup requires.
 pub fn apply_sandbox(policy: &SandboxPolicy) -> Result<(), String> {
     // 1. Set PR_SET_NO_NEW_PRIVS (required for both Landlock and seccomp)
     set_no_new_privs()?;
 
-    // 2. Apply Landlock filesystem rules
+    // 2. An allowlist gets a network namespace that can only reach the
+    //    parent's proxy; if that fails, no network at all
+    let deny_network = match (&policy.network, &policy.proxy) {
+        (NetworkPolicy::Deny, _) => true,
+        (NetworkPolicy::Allow, _) => false,
+        (NetworkPolicy::Allowlist(_), Some(ProxyEndpoint::Unix(socket))) => {
+            match isolate_network(socket) {
+                Ok(()) => false,
+                Err(e) => {
+                    eprintln!(
+                        "localgpt-sandbox: network allowlist not applied, denying network: {}",
+                        e
+                    );
+                    true
+                }
+            }
+        }
+        (NetworkPolicy::Allowlist(_), _) => true,
+    };
+
+    // 3. Apply Landlock filesystem rules
     if let Err(e) = apply_landlock(policy) {
         // Landlock may not be available — log and continue
         eprintln!("localgpt-sandbox: landlock not applied: {}", e);
     }
 
-    // 3. Apply seccomp network deny filter (must be last)
-    if policy.network == NetworkPolicy::Deny
-        && let Err(e) = apply_seccomp_network_deny()
-    {
+    // 4. Apply seccomp filter (must be last). Outside `allow`, unix sockets
+    //    are refused too: neither the namespace nor Landlock stops them
+    //    from reaching the daemon's sockets. The relay was forked before
+    //    this, so it can still reach the proxy.
+    let deny_unix_sockets = !matches!(policy.network, NetworkPolicy::Allow);
+    if let Err(e) = apply_seccomp(deny_network, deny_unix_sockets) {
         eprintln!("localgpt-sandbox: seccomp not applied: {}", e);
     }
 
@@ -111,32 +157,155 @@ fn apply_landlock(policy: &SandboxPolicy) -> Result<(), String> {
     Ok(())
 }
 
-/// Apply seccomp-bpf filter that denies network-related syscalls with EPERM.
-fn apply_seccomp_network_deny() -> Result<(), String> {
-    use seccompiler::{BpfProgram, SeccompAction, SeccompFilter, SeccompRule, TargetArch};
+/// Move into new user and network namespaces, which have nothing but
+/// loopback, and leave a relay on `127.0.0.1:RELAY_PORT` that forwards to
+/// the parent's filtering proxy at `proxy_socket`.
+fn isolate_network(proxy_socket: &Path) -> Result<(), String> {
+    use nix::sched::{CloneFlags, unshare};
+
+    // SAFETY: getuid/getgid cannot fail
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    unshare(CloneFlags::CLONE_NEWUSER | CloneFlags::CLONE_NEWNET)
+        .map_err(|e| format!("unshare: {}", e))?;
+
+    // Map our own ids so files keep their owners
+    let write = |file: &str, contents: String| {
+        std::fs::write(file, contents).map_err(|e| format!("{}: {}", file, e))
+    };
+    write("/proc/self/setgroups", "deny".to_string())?;
+    write("/proc/self/uid_map", format!("{} {} 1", uid, uid))?;
+    write("/proc/self/gid_map", format!("{} {} 1", gid, gid))?;
+
+    bring_up_loopback()?;
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, RELAY_PORT))
+        .map_err(|e| format!("relay bind: {}", e))?;
+    spawn_relay(listener, proxy_socket)
+}
+
+/// A new network namespace's loopback starts out down
+fn bring_up_loopback() -> Result<(), String> {
+    // SAFETY: plain socket/ioctl calls on a zeroed ifreq we own
+    unsafe {
+        let fd = libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0);
+        if fd < 0 {
+            return Err(format!("socket: {}", io::Error::last_os_error()));
+        }
+        let mut ifr: libc::ifreq = std::mem::zeroed();
+        for (dst, src) in ifr.ifr_name.iter_mut().zip(b"lo\0") {
+            *dst = *src as libc::c_char;
+        }
+        let mut ret = libc::ioctl(fd, libc::SIOCGIFFLAGS as _, &mut ifr as *mut libc::ifreq);
+        if ret == 0 {
+            ifr.ifr_ifru.ifru_flags |= (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short;
+            ret = libc::ioctl(fd, libc::SIOCSIFFLAGS as _, &ifr as *const libc::ifreq);
+        }
+        let err = io::Error::last_os_error();
+        libc::close(fd);
+        if ret != 0 {
+            return Err(format!("loopback up: {}", err));
+        }
+    }
+    Ok(())
+}
+
+/// Fork a process that relays connections on `listener` to the proxy
+/// socket. It is killed when the command exits.
+fn spawn_relay(listener: TcpListener, proxy_socket: &Path) -> Result<(), String> {
+    use nix::unistd::{ForkResult, fork, getppid};
+
+    let parent = std::process::id() as libc::pid_t;
+    // SAFETY: the sandbox child is single-threaded, so fork is sound
+    match unsafe { fork() }.map_err(|e| format!("fork: {}", e))? {
+        // The listener stays open in the relay only
+        ForkResult::Parent { .. } => Ok(()),
+        ForkResult::Child => {
+            // SAFETY: prctl with integer arguments
+            unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) };
+            if getppid().as_raw() != parent {
+                std::process::exit(0);
+            }
+            detach_stdio();
+            for client in listener.incoming().flatten() {
+                if let Ok(upstream) = UnixStream::connect(proxy_socket) {
+                    relay(client, upstream);
+                }
+            }
+            std::process::exit(0);
+        }
+    }
+}
+
+/// Keep the relay from holding the command's output pipes open
+fn detach_stdio() {
+    use std::os::fd::AsRawFd;
+
+    if let Ok(null) = std::fs::File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/null")
+    {
+        for fd in 0..3 {
+            // SAFETY: dup2 onto the standard descriptors
+            unsafe { libc::dup2(null.as_raw_fd(), fd) };
+        }
+    }
+}
+
+/// Copy both ways between a sandboxed client and the proxy
+fn relay(client: TcpStream, upstream: UnixStream) {
+    let (Ok(mut client_rx), Ok(mut upstream_rx)) = (client.try_clone(), upstream.try_clone())
+    else {
+        return;
+    };
+    let (mut client_tx, mut upstream_tx) = (client, upstream);
+    // Thread spawn can fail under RLIMIT_NPROC; the connection just drops
+    let _ = std::thread::Builder::new().spawn(move || {
+        let _ = io::copy(&mut client_rx, &mut upstream_tx);
+        let _ = upstream_tx.shutdown(Shutdown::Write);
+    });
+    let _ = std::thread::Builder::new().spawn(move || {
+        let _ = io::copy(&mut upstream_rx, &mut client_tx);
+        let _ = client_tx.shutdown(Shutdown::Write);
+    });
+}
+
+/// Syscalls the seccomp filter denies: always `ptrace`, plus networking
+/// when the network is off.
+fn denied_syscalls(deny_network: bool) -> Vec<i64> {
+    let mut syscalls = vec![libc::SYS_ptrace];
+    if deny_network {
+        syscalls.extend(NETWORK_SYSCALLS);
+    }
+    syscalls
+}
+
+/// Apply seccomp-bpf filter that denies `ptrace` and, with `deny_network`,
+/// network-related syscalls with EPERM. With `deny_unix_sockets`,
+/// `socket(AF_UNIX, ...)` is denied as well.
+fn apply_seccomp(deny_network: bool, deny_unix_sockets: bool) -> Result<(), String> {
+    use seccompiler::{
+        BpfProgram, SeccompAction, SeccompCmpArgLen, SeccompCmpOp, SeccompCondition, SeccompFilter,
+        SeccompRule, TargetArch,
+    };
     use std::collections::BTreeMap;
 
-    // Network-related syscalls to deny
-    let denied_syscalls: Vec<i64> = vec![
-        libc::SYS_socket,
-        libc::SYS_connect,
-        libc::SYS_accept,
-        libc::SYS_accept4,
-        libc::SYS_bind,
-        libc::SYS_listen,
-        libc::SYS_sendto,
-        libc::SYS_sendmsg,
-        libc::SYS_sendmmsg,
-        libc::SYS_recvfrom,
-        libc::SYS_recvmsg,
-        libc::SYS_recvmmsg,
-        libc::SYS_ptrace,
-    ];
+    let denied_syscalls = denied_syscalls(deny_network);
 
     let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
     for syscall in denied_syscalls {
         rules.insert(syscall, vec![SeccompRule::new(vec![]).unwrap()]);
     }
+    if deny_unix_sockets && !rules.contains_key(&libc::SYS_socket) {
+        let is_unix = SeccompCondition::new(
+            0,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Eq,
+            libc::AF_UNIX as u64,
+        )
+        .map_err(|e| format!("seccomp condition: {}", e))?;
+        let rule = SeccompRule::new(vec![is_unix]).map_err(|e| format!("seccomp rule: {}", e))?;
+        rules.insert(libc::SYS_socket, vec![rule]);
+    }
 
     let target_arch: TargetArch = std::env::consts::ARCH
         .try_into()
@@ -190,4 +359,49 @@ mod tests {
             assert!(syscall > 0, "Invalid syscall number: {}", syscall);
         }
     }
+
+    #[test]
+    fn test_denied_syscalls_follow_network_policy() {
+        let deny = denied_syscalls(true);
+        assert!(deny.contains(&libc::SYS_socket));
+        assert!(deny.contains(&libc::SYS_connect));
+        assert!(deny.contains(&libc::SYS_ptrace));
+
+        // With network allowed (or namespaced), ptrace is still blocked
+        assert_eq!(denied_syscalls(false), vec![libc::SYS_ptrace]);
+    }
+
+    #[test]
+    fn test_unix_sockets_denied_with_namespaced_network() {
+        use nix::sys::wait::{WaitStatus, waitpid};
+        use nix::unistd::{ForkResult, fork};
+        use std::os::unix::net::UnixListener;
+
+        // A socket outside any workspace, like the daemon's
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.sock");
+        let _listener = UnixListener::bind(&path).unwrap();
+        assert!(UnixStream::connect(&path).is_ok());
+
+        // The filter can't be undone, so apply it in a child
+        // SAFETY: the child only makes syscalls and exits
+        match unsafe { fork() }.unwrap() {
+            ForkResult::Child => {
+                let code = match apply_seccomp(false, true) {
+                    Err(_) => 3,
+                    Ok(()) if UnixStream::connect(&path).is_ok() => 1,
+                    // IP sockets still work, for the relay
+                    Ok(()) if TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).is_err() => 2,
+                    Ok(()) => 0,
+                };
+                // SAFETY: exit without running the parent's destructors
+                unsafe { libc::_exit(code) }
+            }
+            ForkResult::Parent { child } => match waitpid(child, None).unwrap() {
+                WaitStatus::Exited(_, 3) => eprintln!("seccomp unavailable, skipping"),
+                WaitStatus::Exited(_, code) => assert_eq!(code, 0, "child exit code"),
+                status => panic!("unexpected child status: {:?}", status),
+            },
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use crate::policy::{NetworkPolicy, SandboxPolicy};
+use crate::policy::{NetworkPolicy, ProxyEndpoint, SandboxPolicy};
 
 /// Apply macOS sandbox enforcement using Seatbelt SBPL profiles.
 ///
@@ -50,8 +50,9 @@ pub fn exec_sandboxed(command: &str) -> ! {
 /// system frameworks, and paths that are impractical to enumerate), then deny
 /// the user's home directory to prevent reading repos, personal files, etc.
 /// Re-allow workspace and configured paths within home. Restrict writes to
-/// workspace + /tmp. Deny network. SBPL evaluates rules in order — last match
-/// wins for deny/allow conflicts.
+/// workspace + /tmp. Network follows `policy.network`; an allowlist may only
+/// reach the parent's filtering proxy on localhost. SBPL evaluates rules in
+/// order — last match wins for deny/allow conflicts.
 pub fn generate_sbpl_profile(policy: &SandboxPolicy) -> String {
     let mut rules = vec![
         "(version 1)".to_string(),
@@ -108,18 +109,25 @@ pub fn generate_sbpl_profile(policy: &SandboxPolicy) -> String {
         ));
     }
 
-    // Network policy — deny all network by default
+    // Network policy
     match &policy.network {
         NetworkPolicy::Deny => {
             rules.push("(deny network*)".to_string());
         }
-        NetworkPolicy::AllowProxy(socket_path) => {
-            let escaped = escape_sbpl_path(&socket_path.to_string_lossy());
+        NetworkPolicy::Allow => {
+            rules.push("(allow system-socket)".to_string());
+            rules.push("(allow network*)".to_string());
+        }
+        NetworkPolicy::Allowlist(_) => {
             rules.push("(deny network*)".to_string());
-            rules.push(format!(
-                "(allow network-outbound (local file \"{}\"))",
-                escaped
-            ));
+            // Without a proxy to go through, nothing is reachable
+            if let Some(ProxyEndpoint::Tcp(port)) = &policy.proxy {
+                rules.push("(allow system-socket)".to_string());
+                rules.push(format!(
+                    "(allow network-outbound (remote tcp \"localhost:{}\"))",
+                    port
+                ));
+            }
         }
     }
 
@@ -158,6 +166,7 @@ mod tests {
                 PathBuf::from("/Users/test/.aws"),
             ],
             network: NetworkPolicy::Deny,
+            proxy: None,
             timeout_secs: 120,
             max_output_bytes: 1_048_576,
             max_file_size_bytes: 52_428_800,
@@ -206,6 +215,31 @@ mod tests {
         assert!(profile.contains("(deny network*)"));
     }
 
+    #[test]
+    fn test_generate_sbpl_profile_allowlist_reaches_proxy_only() {
+        let mut policy = test_policy();
+        policy.network = NetworkPolicy::Allowlist(vec!["api.github.com".to_string()]);
+        let profile = generate_sbpl_profile(&policy);
+        // No proxy running: no network at all
+        assert!(profile.contains("(deny network*)"));
+        assert!(!profile.contains("network-outbound"));
+
+        policy.proxy = Some(ProxyEndpoint::Tcp(49152));
+        let profile = generate_sbpl_profile(&policy);
+        assert!(profile.contains("(deny network*)"));
+        assert!(profile.contains("(allow network-outbound (remote tcp \"localhost:49152\"))"));
+        assert!(!profile.contains("(allow network*)"));
+    }
+
+    #[test]
+    fn test_generate_sbpl_profile_allow_network() {
+        let mut policy = test_policy();
+        policy.network = NetworkPolicy::Allow;
+        let profile = generate_sbpl_profile(&policy);
+        assert!(profile.contains("(allow network*)"));
+        assert!(!profile.contains("(deny network*)"));
+    }
+
     #[test]
     fn test_generate_sbpl_profile_denies_credentials() {
         let policy = test_policy();
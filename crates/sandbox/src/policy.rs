@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-use localgpt_core::config::SandboxConfig;
+use localgpt_core::config::{SandboxConfig, SandboxNetwork};
 
 /// High-level sandbox mode (user-facing setting).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -34,8 +34,22 @@ pub enum SandboxLevel {
 pub enum NetworkPolicy {
     /// No network connectivity at all.
     Deny,
-    /// Allow through a proxy socket (future).
-    AllowProxy(PathBuf),
+    /// Unrestricted network.
+    Allow,
+    /// HTTP(S) to these hosts only, through a filtering proxy run by the
+    /// parent (see [`crate::proxy`]).
+    Allowlist(Vec<String>),
+}
+
+/// Where the parent's filtering proxy listens for an allowlisted command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProxyEndpoint {
+    /// Unix socket, reached through a relay inside the child's network
+    /// namespace (Linux).
+    Unix(PathBuf),
+    /// TCP port on localhost (macOS).
+    Tcp(u16),
 }
 
 /// Serializable sandbox policy passed to the re-exec'd child process.
@@ -56,6 +70,10 @@ pub struct SandboxPolicy {
     /// Network access policy.
     pub network: NetworkPolicy,
 
+    /// The proxy an allowlisted command goes through; set by `run_sandboxed`.
+    #[serde(default)]
+    pub proxy: Option<ProxyEndpoint>,
+
     /// Kill command after this many seconds.
     pub timeout_secs: u64,
 
@@ -144,16 +162,13 @@ pub fn build_policy(
         SandboxMode::WorkspaceWrite
     };
 
-    let network = match mode {
-        SandboxMode::FullAccess => NetworkPolicy::Deny, // still deny by default; full-access only relaxes FS
-        _ => {
-            if config.network.policy == "proxy" {
-                // Future: proxy support
-                NetworkPolicy::Deny
-            } else {
-                NetworkPolicy::Deny
-            }
-        }
+    // Full access only relaxes the filesystem; the network setting still applies
+    let network = match config.network.policy {
+        SandboxNetwork::Deny => NetworkPolicy::Deny,
+        SandboxNetwork::Allow => NetworkPolicy::Allow,
+        // Nothing to allow: same as deny, without running a proxy
+        SandboxNetwork::Allowlist if config.network.allow.is_empty() => NetworkPolicy::Deny,
+        SandboxNetwork::Allowlist => NetworkPolicy::Allowlist(config.network.allow.clone()),
     };
 
     let mut read_only = default_read_only_paths();
@@ -180,6 +195,7 @@ pub fn build_policy(
         extra_write_paths: extra_write,
         deny_paths,
         network,
+        proxy: None,
         timeout_secs: config.timeout_secs,
         max_output_bytes: config.max_output_bytes,
         max_file_size_bytes: config.max_file_size_bytes,
@@ -252,6 +268,29 @@ mod tests {
         assert_eq!(deserialized.timeout_secs, policy.timeout_secs);
     }
 
+    #[test]
+    fn test_build_policy_network_settings() {
+        let workspace = PathBuf::from("/tmp/test");
+        let with_network = |toml: &str| {
+            let config: SandboxConfig = toml::from_str(toml).unwrap();
+            build_policy(&config, &workspace, SandboxLevel::Standard).network
+        };
+
+        assert_eq!(with_network(""), NetworkPolicy::Deny);
+        assert_eq!(with_network("network = \"allow\""), NetworkPolicy::Allow);
+        // The old placeholder value still parses, as deny
+        assert_eq!(
+            with_network("[network]\npolicy = \"proxy\""),
+            NetworkPolicy::Deny
+        );
+        assert_eq!(
+            with_network("[network]\npolicy = \"allowlist\"\nallow = [\"*.pypi.org\"]"),
+            NetworkPolicy::Allowlist(vec!["*.pypi.org".to_string()])
+        );
+        // An empty allowlist allows nothing
+        assert_eq!(with_network("network = \"allowlist\""), NetworkPolicy::Deny);
+    }
+
     #[test]
     fn test_deny_paths_include_credentials() {
         let config = SandboxConfig::default();
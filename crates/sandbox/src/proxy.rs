@@ -0,0 +1,412 @@
+//! Filtering HTTP proxy for `sandbox.network = "allowlist"`.
+//!
+//! The parent runs one for each allowlisted command and points the child's
+//! `HTTP_PROXY`/`HTTPS_PROXY` at it. The child can reach nothing else: on
+//! Linux it runs in a network namespace with only loopback and a relay to
+//! this proxy's Unix socket; on macOS Seatbelt only allows connecting to the
+//! proxy's localhost port. `CONNECT` tunnels and plain HTTP requests to
+//! allowlisted hosts are let through; DNS is resolved here, not in the
+//! sandbox.
+
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+
+use crate::policy::ProxyEndpoint;
+
+/// Port the Linux relay listens on inside the command's network namespace
+pub const RELAY_PORT: u16 = 3128;
+
+/// Environment variables that point the child's tools at the proxy
+pub const PROXY_ENV_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+
+/// Largest request head read before giving up on a client
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// A running proxy; it stops when dropped
+pub struct ProxyHandle {
+    pub endpoint: ProxyEndpoint,
+    task: JoinHandle<()>,
+}
+
+impl ProxyHandle {
+    /// Proxy URL as seen from inside the sandbox
+    pub fn url(&self) -> String {
+        let port = match self.endpoint {
+            ProxyEndpoint::Unix(_) => RELAY_PORT,
+            ProxyEndpoint::Tcp(port) => port,
+        };
+        format!("http://127.0.0.1:{}", port)
+    }
+}
+
+impl Drop for ProxyHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+        if let ProxyEndpoint::Unix(path) = &self.endpoint {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Start a proxy that only forwards requests to hosts matching `allow`.
+pub async fn start(allow: Vec<String>) -> Result<ProxyHandle> {
+    let allow = Arc::new(allow);
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static NEXT_SOCKET: AtomicU64 = AtomicU64::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "localgpt-proxy-{}-{}.sock",
+            std::process::id(),
+            NEXT_SOCKET.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, Arc::clone(&allow)));
+            }
+        });
+        Ok(ProxyHandle {
+            endpoint: ProxyEndpoint::Unix(path),
+            task,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await?;
+        let port = listener.local_addr()?.port();
+
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, Arc::clone(&allow)));
+            }
+        });
+        Ok(ProxyHandle {
+            endpoint: ProxyEndpoint::Tcp(port),
+            task,
+        })
+    }
+}
+
+/// Whether `host:port` matches an allowlist entry. Entries are a host
+/// (`api.github.com`) or a wildcard for its subdomains (`*.github.com`),
+/// optionally limited to one port (`example.com:8443`).
+pub fn host_allowed(allow: &[String], host: &str, port: u16) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allow.iter().any(|entry| {
+        let (pattern, entry_port) = match entry.rsplit_once(':') {
+            Some((pattern, p)) if !pattern.contains(':') => match p.parse::<u16>() {
+                Ok(p) => (pattern, Some(p)),
+                Err(_) => return false,
+            },
+            _ => (entry.as_str(), None),
+        };
+        if entry_port.is_some_and(|p| p != port) {
+            return false;
+        }
+        let pattern = pattern.trim_end_matches('.').to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host.ends_with(&format!(".{}", domain)),
+            None => host == pattern,
+        }
+    })
+}
+
+/// A parsed proxy request
+#[derive(Debug, PartialEq)]
+struct ProxyRequest {
+    host: String,
+    port: u16,
+    /// `CONNECT` tunnel rather than a forwarded HTTP request
+    tunnel: bool,
+    /// What to send upstream first: the rewritten request head, or nothing
+    /// for a tunnel
+    forward: Vec<u8>,
+}
+
+async fn serve<S>(mut client: S, allow: Arc<Vec<String>>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    if let Err(e) = proxy(&mut client, &allow).await {
+        tracing::debug!("Sandbox proxy: {}", e);
+    }
+}
+
+async fn proxy<S>(client: &mut S, allow: &[String]) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (head, rest) = read_head(client).await?;
+    let request = match parse_request(&head) {
+        Ok(request) => request,
+        Err(e) => {
+            respond(client, "400 Bad Request", &format!("{}\n", e)).await?;
+            return Ok(());
+        }
+    };
+
+    if !host_allowed(allow, &request.host, request.port) {
+        tracing::info!(
+            "Sandbox proxy denied {}:{} (not in sandbox.network.allow)",
+            request.host,
+            request.port
+        );
+        let body = format!(
+            "Access to {}:{} denied by the sandbox.network allowlist\n",
+            request.host, request.port
+        );
+        respond(client, "403 Forbidden", &body).await?;
+        return Ok(());
+    }
+
+    let mut upstream = match TcpStream::connect((request.host.as_str(), request.port)).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            let body = format!("Cannot reach {}:{}: {}\n", request.host, request.port, e);
+            respond(client, "502 Bad Gateway", &body).await?;
+            return Ok(());
+        }
+    };
+
+    if request.tunnel {
+        client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+    } else {
+        upstream.write_all(&request.forward).await?;
+    }
+    upstream.write_all(&rest).await?;
+    tokio::io::copy_bidirectional(client, &mut upstream).await?;
+    Ok(())
+}
+
+/// Read up to the blank line ending the request head; returns the head and
+/// any bytes read past it
+async fn read_head<S>(client: &mut S) -> Result<(Vec<u8>, Vec<u8>)>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            return Ok((buf, rest));
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            bail!("request head over {} bytes", MAX_HEAD_BYTES);
+        }
+        let n = client.read(&mut chunk).await?;
+        if n == 0 {
+            bail!("client closed before the request head ended");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+fn parse_request(head: &[u8]) -> Result<ProxyRequest> {
+    let head = std::str::from_utf8(head)?;
+    let (request_line, headers) = head.split_once("\r\n").unwrap_or((head, ""));
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        bail!("malformed request line");
+    };
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_host_port(target, 443)?;
+        return Ok(ProxyRequest {
+            host,
+            port,
+            tunnel: true,
+            forward: Vec::new(),
+        });
+    }
+
+    let Some(url) = target
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("http://"))
+        .map(|_| &target[7..])
+    else {
+        bail!("only CONNECT and absolute http:// requests are proxied");
+    };
+    let (authority, path) = match url.find('/') {
+        Some(i) => (&url[..i], &url[i..]),
+        None => (url, "/"),
+    };
+    let (host, port) = split_host_port(authority, 80)?;
+
+    // Forward in origin form, without the proxy's own headers
+    let mut forward = format!("{} {} {}\r\n", method, path, version);
+    for line in headers.split("\r\n").filter(|l| !l.is_empty()) {
+        let name = line.split(':').next().unwrap_or("");
+        if !name.trim().to_ascii_lowercase().starts_with("proxy-") {
+            forward.push_str(line);
+            forward.push_str("\r\n");
+        }
+    }
+    forward.push_str("\r\n");
+
+    Ok(ProxyRequest {
+        host,
+        port,
+        tunnel: false,
+        forward: forward.into_bytes(),
+    })
+}
+
+/// Split `host[:port]`, including bracketed IPv6 addresses
+fn split_host_port(authority: &str, default_port: u16) -> Result<(String, u16)> {
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let Some((host, after)) = rest.split_once(']') else {
+            bail!("malformed address: {}", authority);
+        };
+        (host, after.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        bail!("missing host");
+    }
+    let port = match port {
+        Some(port) => port.parse()?,
+        None => default_port,
+    };
+    Ok((host.to_string(), port))
+}
+
+async fn respond<S>(client: &mut S, status: &str, body: &str) -> Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    client.write_all(response.as_bytes()).await?;
+    client.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allow(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn test_host_allowed() {
+        let list = allow(&["api.github.com", "*.pypi.org", "example.com:8443"]);
+
+        assert!(host_allowed(&list, "api.github.com", 443));
+        assert!(host_allowed(&list, "API.GitHub.com.", 80));
+        assert!(!host_allowed(&list, "github.com", 443));
+        assert!(!host_allowed(&list, "evil-api.github.com", 443));
+
+        // Wildcards cover subdomains, not the domain itself
+        assert!(host_allowed(&list, "files.pypi.org", 443));
+        assert!(!host_allowed(&list, "pypi.org", 443));
+        assert!(!host_allowed(&list, "notpypi.org", 443));
+
+        assert!(host_allowed(&list, "example.com", 8443));
+        assert!(!host_allowed(&list, "example.com", 443));
+    }
+
+    #[test]
+    fn test_parse_connect() {
+        let request = parse_request(b"CONNECT api.github.com:443 HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(request.host, "api.github.com");
+        assert_eq!(request.port, 443);
+        assert!(request.tunnel);
+
+        let request = parse_request(b"CONNECT [::1]:8443 HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!((request.host.as_str(), request.port), ("::1", 8443));
+    }
+
+    #[test]
+    fn test_parse_absolute_request() {
+        let head = b"GET http://example.com/simple/pkg?x=1 HTTP/1.1\r\nHost: example.com\r\nProxy-Connection: keep-alive\r\n\r\n";
+        let request = parse_request(head).unwrap();
+        assert_eq!((request.host.as_str(), request.port), ("example.com", 80));
+        assert!(!request.tunnel);
+        assert_eq!(
+            String::from_utf8(request.forward).unwrap(),
+            "GET /simple/pkg?x=1 HTTP/1.1\r\nHost: example.com\r\n\r\n"
+        );
+
+        assert!(parse_request(b"GET /relative HTTP/1.1\r\n\r\n").is_err());
+        assert!(parse_request(b"GET https://example.com/ HTTP/1.1\r\n\r\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_denied_host_gets_403() {
+        let (mut client, server) = tokio::io::duplex(4096);
+        tokio::spawn(serve(server, Arc::new(allow(&["api.github.com"]))));
+
+        client
+            .write_all(b"CONNECT evil.example:443 HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 403"));
+        assert!(response.contains("evil.example:443 denied"));
+    }
+
+    #[tokio::test]
+    async fn test_allowed_request_is_forwarded() {
+        let upstream = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap();
+        let port = upstream.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = upstream.accept().await.unwrap();
+            let (head, _) = read_head(&mut stream).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                .await
+                .unwrap();
+            String::from_utf8(head).unwrap()
+        });
+
+        let (mut client, proxy_side) = tokio::io::duplex(4096);
+        tokio::spawn(serve(proxy_side, Arc::new(allow(&["127.0.0.1"]))));
+        let request = format!(
+            "GET http://127.0.0.1:{}/x HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n",
+            port
+        );
+        client.write_all(request.as_bytes()).await.unwrap();
+
+        let head = server.await.unwrap();
+        assert!(head.starts_with("GET /x HTTP/1.1\r\n"));
+        let mut response = [0u8; 64];
+        let n = client.read(&mut response).await.unwrap();
+        assert!(response[..n].starts_with(b"HTTP/1.1 200 OK"));
+    }
+}
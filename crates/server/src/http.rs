@@ -5,44 +5,64 @@
 
 use anyhow::Result;
 use axum::{
-    Router,
-    extract::{
-        Path, Query, Request, State,
-        ws::{Message as WsMessage, WebSocket, WebSocketUpgrade},
-    },
-    http::{StatusCode, header},
+    Extension, Router,
+    extract::{Path, Query, Request, State},
+    http::{HeaderMap, StatusCode, header},
     middleware::{self, Next},
     response::{
         IntoResponse, Json, Response,
-        sse::{Event, Sse},
+        sse::{Event, KeepAlive, Sse},
     },
-    routing::{delete, get, post},
+    routing::{delete, get, patch, post},
 };
-use futures::{SinkExt, StreamExt};
+use futures::StreamExt;
 use rust_embed::RustEmbed;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tower_http::limit::RequestBodyLimitLayer;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use localgpt_core::agent::{Agent, AgentConfig, StreamEvent, extract_tool_detail};
-use localgpt_core::concurrency::{TurnGate, WorkspaceLock};
-use localgpt_core::config::Config;
+use localgpt_core::agent::tool_filters::InterfaceToolPolicy;
+use localgpt_core::agent::{
+    Agent, AgentConfig, StreamEvent, Usage, extract_tool_detail, model_catalog,
+};
+use localgpt_core::concurrency::{
+    CancellationToken, DropGuard, Shutdown, TurnGate, TurnGateStats, TurnPermit, WorkspaceLock,
+    WorkspaceLockGuard, is_cancelled,
+};
+use localgpt_core::config::{Config, CorsConfig, CronJob, ServerConfig};
+use localgpt_core::cron::{CronJobStore, CronJobUpdate};
 use localgpt_core::heartbeat::{HeartbeatStatus, get_last_heartbeat_event};
 use localgpt_core::memory::MemoryManager;
+use localgpt_core::readiness::{self, ComponentState};
+use localgpt_core::security::{ApiKey, ApiKeyStore, ApiScope, constant_time_eq};
+use localgpt_core::webhooks::WebhookEvent;
+
+use crate::model_queue::{Admission, ModelQueue, QueueProgress};
+use crate::rate_limiter::{ClientKey, RateDecision};
+use crate::websocket;
 
 /// Embedded UI assets
 #[derive(RustEmbed)]
 #[folder = "ui/"]
 struct UiAssets;
 
+/// Web (wasm) build of the egui UI, e.g. the output of
+/// `trunk build --release --public-url /ui/`
+#[cfg(feature = "egui-web")]
+#[derive(RustEmbed)]
+#[folder = "$LOCALGPT_WEB_UI_DIR"]
+struct EguiWebAssets;
+
 /// Session timeout (30 minutes of inactivity)
 const SESSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
 
@@ -52,17 +72,26 @@ const MAX_SESSIONS: usize = 100;
 /// Agent ID for HTTP sessions
 const HTTP_AGENT_ID: &str = "http";
 
+/// How long `/readyz` reuses a default-provider probe
+const PROVIDER_PROBE_TTL: Duration = Duration::from_secs(30);
+
+/// A scheduler silent for this long is stuck (the daemon ticks it every 30s)
+const SCHEDULER_STALE_AFTER: Duration = Duration::from_secs(120);
+
 pub struct Server {
     config: Config,
     turn_gate: TurnGate,
     bridge_manager: crate::security::BridgeManager,
+    shutdown: Shutdown,
 }
 
 pub(crate) struct SessionEntry {
-    agent: Agent,
-    last_accessed: Instant,
+    pub(crate) agent: Agent,
+    /// Agent ID of the user the session belongs to
+    pub(crate) owner: String,
+    pub(crate) last_accessed: Instant,
     /// Whether session has unsaved changes
-    dirty: bool,
+    pub(crate) dirty: bool,
 }
 
 pub(crate) struct AppState {
@@ -70,22 +99,346 @@ pub(crate) struct AppState {
     pub(crate) sessions: Mutex<HashMap<String, SessionEntry>>,
     /// Shared MemoryManager to avoid reinitializing embedding provider
     pub(crate) memory: MemoryManager,
+    /// Memory of each API key user, created on first use
+    user_memory: std::sync::Mutex<HashMap<String, MemoryManager>>,
     /// In-process turn gate shared with heartbeat runner
-    turn_gate: TurnGate,
+    pub(crate) turn_gate: TurnGate,
     /// Cross-process workspace lock
-    workspace_lock: WorkspaceLock,
+    pub(crate) workspace_lock: WorkspaceLock,
     /// Per-IP rate limiter
     rate_limiter: Arc<crate::rate_limiter::RateLimiter>,
+    /// Concurrent generations per model or provider
+    pub(crate) model_queue: ModelQueue,
     /// Bridge manager for tracking active connections
     pub(crate) bridge_manager: crate::security::BridgeManager,
+    /// Models discovered for `/v1/models`, with the time of discovery
+    pub(crate) model_cache: Mutex<Option<(Instant, Vec<crate::openai_compat::ModelInfo>)>>,
+    /// Token usage per user since the server started
+    usage: std::sync::Mutex<HashMap<Option<String>, UserUsage>>,
+    /// When the server started, for the reported uptime
+    started_at: chrono::DateTime<chrono::Utc>,
+    /// Last default-provider check for `/readyz`, with when it ran
+    provider_probe: Mutex<Option<(Instant, ReadyCheck)>>,
+    /// Session turns in progress, for `POST /api/sessions/{id}/cancel`
+    running_turns: std::sync::Mutex<HashMap<String, TurnHandle>>,
+    next_turn_id: AtomicU64,
+    /// Sessions taken out of `sessions` for a turn, with their owner's agent ID
+    checked_out: std::sync::Mutex<HashMap<String, String>>,
+}
+
+struct TurnHandle {
+    id: u64,
+    /// Agent ID of the session's owner
+    owner: String,
+    cancel: CancellationToken,
+}
+
+impl AppState {
+    fn new(
+        config: Config,
+        memory: MemoryManager,
+        turn_gate: TurnGate,
+        workspace_lock: WorkspaceLock,
+        bridge_manager: crate::security::BridgeManager,
+    ) -> Result<Self> {
+        let rate_limiter = crate::rate_limiter::create_rate_limiter(&config.server.rate_limit);
+        let model_queue = ModelQueue::new(&config.server.model_concurrency)?;
+        Ok(Self {
+            config,
+            sessions: Mutex::new(HashMap::new()),
+            memory,
+            user_memory: std::sync::Mutex::new(HashMap::new()),
+            turn_gate,
+            workspace_lock,
+            rate_limiter,
+            model_queue,
+            bridge_manager,
+            model_cache: Mutex::new(None),
+            usage: std::sync::Mutex::new(HashMap::new()),
+            started_at: chrono::Utc::now(),
+            provider_probe: Mutex::new(None),
+            running_turns: std::sync::Mutex::new(HashMap::new()),
+            next_turn_id: AtomicU64::new(0),
+            checked_out: std::sync::Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Config for the caller's agents. A user's has their own workspace.
+    pub(crate) fn caller_config(&self, caller: &Caller) -> Cow<'_, Config> {
+        match &caller.user {
+            Some(user) => Cow::Owned(self.config.for_user(user)),
+            None => Cow::Borrowed(&self.config),
+        }
+    }
+
+    /// The caller's memory: the shared workspace's, or a user's own
+    /// workspace with its own search index, so users can't search each
+    /// other's memory.
+    pub(crate) fn caller_memory(&self, caller: &Caller) -> Result<MemoryManager> {
+        let Some(user) = &caller.user else {
+            return Ok(self.memory.clone());
+        };
+        let mut memories = self.user_memory.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(memory) = memories.get(user) {
+            return Ok(memory.clone());
+        }
+        let config = self.config.for_user(user);
+        let memory =
+            MemoryManager::new_with_full_config(&config.memory, Some(&config), &caller.agent_id())?;
+        memories.insert(user.clone(), memory.clone());
+        Ok(memory)
+    }
+
+    /// A new agent for the caller, with their config and memory. It can't
+    /// use `schedule_task`: scheduled tasks run as cron jobs in the shared
+    /// workspace under the cron tool policy, which would sidestep both the
+    /// cron scope and the caller's own workspace.
+    pub(crate) async fn caller_agent(
+        &self,
+        caller: &Caller,
+        agent_config: AgentConfig,
+    ) -> Result<Agent> {
+        let memory = Arc::new(self.caller_memory(caller)?);
+        let mut agent = Agent::new(agent_config, &self.caller_config(caller), memory).await?;
+        agent.restrict_tools(InterfaceToolPolicy {
+            allow: Vec::new(),
+            deny: vec!["schedule_task".to_string()],
+        });
+        Ok(agent)
+    }
+
+    /// Add a request's token usage to the caller's totals.
+    pub(crate) fn record_usage(&self, caller: &Caller, usage: &Usage) {
+        if usage.total() == 0 {
+            return;
+        }
+        let mut totals = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        let totals = totals.entry(caller.user.clone()).or_default();
+        totals.input_tokens += usage.input_tokens;
+        totals.output_tokens += usage.output_tokens;
+        totals.requests += 1;
+    }
+
+    /// Give `agent` a fresh cancellation token for a turn on `session_id`.
+    /// The turn can be cancelled through the API until the returned guard is
+    /// dropped; dropping it (e.g. when the client disconnects) cancels it.
+    pub(crate) fn begin_turn(
+        self: &Arc<Self>,
+        session_id: &str,
+        caller: &Caller,
+        agent: &mut Agent,
+    ) -> RunningTurn {
+        let cancel = CancellationToken::new();
+        agent.set_cancel_token(cancel.clone());
+        let id = self.next_turn_id.fetch_add(1, Ordering::Relaxed);
+        self.running_turns_lock().insert(
+            session_id.to_string(),
+            TurnHandle {
+                id,
+                owner: caller.agent_id(),
+                cancel: cancel.clone(),
+            },
+        );
+        RunningTurn {
+            state: Arc::clone(self),
+            session_id: session_id.to_string(),
+            id,
+            _cancel: cancel.drop_guard(),
+        }
+    }
+
+    /// Cancel the turn running on `caller`'s session `session_id`. Returns
+    /// whether there was one.
+    fn cancel_turn(&self, session_id: &str, caller: &Caller) -> bool {
+        let turns = self.running_turns_lock();
+        match turns.get(session_id) {
+            Some(turn) if turn.owner == caller.agent_id() => {
+                turn.cancel.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn running_turns_lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, TurnHandle>> {
+        self.running_turns.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Take `caller`'s session `session_id` out of the map for a turn, so the
+    /// map isn't locked while the turn waits on its client. Until it is put
+    /// back with [`AppState::check_in_session`], other requests for the
+    /// session get 409.
+    pub(crate) async fn check_out_session(
+        &self,
+        session_id: &str,
+        caller: &Caller,
+    ) -> Result<SessionEntry, AppError> {
+        let mut sessions = self.sessions.lock().await;
+        match sessions.remove(session_id) {
+            Some(entry) if entry.owned_by(caller) => {
+                self.checked_out_lock()
+                    .insert(session_id.to_string(), entry.owner.clone());
+                Ok(entry)
+            }
+            Some(entry) => {
+                sessions.insert(session_id.to_string(), entry);
+                Err(AppError(
+                    StatusCode::NOT_FOUND,
+                    "Session not found".to_string(),
+                ))
+            }
+            None => {
+                self.session_available(session_id, caller)?;
+                Err(AppError(
+                    StatusCode::NOT_FOUND,
+                    "Session not found".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Put back a session taken with [`AppState::check_out_session`].
+    pub(crate) async fn check_in_session(&self, session_id: &str, entry: SessionEntry) {
+        let mut sessions = self.sessions.lock().await;
+        self.checked_out_lock().remove(session_id);
+        sessions.insert(session_id.to_string(), entry);
+    }
+
+    /// Fails if `session_id` is checked out for a turn: 409 for its owner,
+    /// 404 for anyone else.
+    fn session_available(&self, session_id: &str, caller: &Caller) -> Result<(), AppError> {
+        match self.checked_out_lock().get(session_id) {
+            Some(owner) if *owner == caller.agent_id() => Err(AppError(
+                StatusCode::CONFLICT,
+                "Session is busy with another turn".to_string(),
+            )),
+            Some(_) => Err(AppError(
+                StatusCode::NOT_FOUND,
+                "Session not found".to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn checked_out_lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, String>> {
+        self.checked_out.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A session turn registered with [`AppState::begin_turn`]
+pub(crate) struct RunningTurn {
+    state: Arc<AppState>,
+    session_id: String,
+    id: u64,
+    _cancel: DropGuard,
+}
+
+impl Drop for RunningTurn {
+    fn drop(&mut self) {
+        let mut turns = self.state.running_turns_lock();
+        // A later turn on the session may already have replaced this one
+        if turns.get(&self.session_id).is_some_and(|t| t.id == self.id) {
+            turns.remove(&self.session_id);
+        }
+    }
+}
+
+/// An agent created for a single OpenAI- or Ollama-compatible request.
+/// Dropping the response mid-turn (the client disconnected) drops the
+/// agent's turn with it, cancelling the provider request and any running
+/// tool; the tokens spent so far are still recorded.
+pub(crate) struct RequestTurn {
+    pub(crate) agent: Agent,
+    state: Arc<AppState>,
+    caller: Caller,
+    /// API name for the log line, e.g. "OpenAI API"
+    api: &'static str,
+    finished: bool,
+}
+
+impl RequestTurn {
+    pub(crate) fn new(
+        state: Arc<AppState>,
+        caller: Caller,
+        agent: Agent,
+        api: &'static str,
+    ) -> Self {
+        Self {
+            agent,
+            state,
+            caller,
+            api,
+            finished: false,
+        }
+    }
+
+    /// Mark the response as fully produced.
+    pub(crate) fn finish(&mut self) {
+        self.finished = true;
+    }
+}
+
+impl Drop for RequestTurn {
+    fn drop(&mut self) {
+        if !self.finished {
+            info!("{}: client disconnected, turn cancelled", self.api);
+        }
+        self.state.record_usage(&self.caller, self.agent.usage());
+    }
+}
+
+/// Tokens spent between `before` and `now` snapshots of an agent's usage.
+pub(crate) fn usage_since(now: &Usage, before: &Usage) -> Usage {
+    Usage {
+        input_tokens: now.input_tokens.saturating_sub(before.input_tokens),
+        output_tokens: now.output_tokens.saturating_sub(before.output_tokens),
+    }
+}
+
+/// Who made a request. Users of API keys created with `--user` get their
+/// own session namespace and memory workspace; everyone else shares the
+/// default ones.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Caller {
+    pub(crate) user: Option<String>,
+    /// Whether the caller may see every user's usage
+    admin: bool,
+}
+
+impl Caller {
+    /// Agent ID the caller's sessions are saved under.
+    pub(crate) fn agent_id(&self) -> String {
+        agent_id_for(self.user.as_deref())
+    }
+}
+
+fn agent_id_for(user: Option<&str>) -> String {
+    match user {
+        Some(user) => format!("{}-{}", HTTP_AGENT_ID, user),
+        None => HTTP_AGENT_ID.to_string(),
+    }
+}
+
+impl SessionEntry {
+    pub(crate) fn owned_by(&self, caller: &Caller) -> bool {
+        self.owner == caller.agent_id()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+struct UserUsage {
+    input_tokens: u64,
+    output_tokens: u64,
+    requests: u64,
 }
 
 impl Server {
     pub fn new(config: &Config) -> Result<Self> {
         Ok(Self {
             config: config.clone(),
-            turn_gate: TurnGate::new(),
+            turn_gate: TurnGate::from_config(&config.server.turns)?,
             bridge_manager: crate::security::BridgeManager::new(),
+            shutdown: Shutdown::new(Duration::ZERO),
         })
     }
 
@@ -96,6 +449,7 @@ impl Server {
             config: config.clone(),
             turn_gate,
             bridge_manager: crate::security::BridgeManager::new(),
+            shutdown: Shutdown::new(Duration::ZERO),
         })
     }
 
@@ -109,26 +463,33 @@ impl Server {
             config: config.clone(),
             turn_gate,
             bridge_manager,
+            shutdown: Shutdown::new(Duration::ZERO),
         })
     }
 
+    /// Stop serving when `shutdown` is triggered: listeners close, sessions
+    /// are saved once their in-flight turns finish, and `run` returns after
+    /// open connections close.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
     pub async fn run(&self) -> Result<()> {
         // Create shared MemoryManager once to avoid reinitializing embedding provider
         let memory =
             MemoryManager::new_with_full_config(&self.config.memory, Some(&self.config), "main")?;
 
-        let workspace_lock = WorkspaceLock::new()?;
-        let rate_limiter = crate::rate_limiter::create_rate_limiter(&self.config.server.rate_limit);
-
-        let state = Arc::new(AppState {
-            config: self.config.clone(),
-            sessions: Mutex::new(HashMap::new()),
+        let state = Arc::new(AppState::new(
+            self.config.clone(),
             memory,
-            turn_gate: self.turn_gate.clone(),
-            workspace_lock,
-            rate_limiter,
-            bridge_manager: self.bridge_manager.clone(),
-        });
+            self.turn_gate.clone(),
+            WorkspaceLock::new()?,
+            self.bridge_manager.clone(),
+        )?);
+
+        // Queued protected writes can be answered from the web UI
+        localgpt_core::security::add_write_approver(Arc::new(WebWriteApprover));
 
         // Load persisted sessions on startup
         if let Err(e) = load_persisted_sessions(&state).await {
@@ -155,16 +516,16 @@ impl Server {
             }
         });
 
-        let cors = CorsLayer::new()
-            .allow_origin(Any)
-            .allow_methods(Any)
-            .allow_headers(Any);
+        let cors = cors_layer(&self.config.server.cors)?;
 
         // Public routes (no auth required)
         let public_routes = Router::new()
             .route("/", get(serve_ui_index))
+            .route("/ui", get(serve_ui_app))
             .route("/ui/{*path}", get(serve_ui_file))
             .route("/health", get(health_check))
+            .route("/healthz", get(healthz))
+            .route("/readyz", get(readyz))
             .route("/api/auth/status", get(auth_status));
 
         // OpenAI-compatible API routes (auth required if token configured)
@@ -196,19 +557,57 @@ impl Server {
             .route("/api/sessions/{session_id}/compact", post(compact_session))
             .route("/api/sessions/{session_id}/clear", post(clear_session))
             .route("/api/sessions/{session_id}/model", post(set_session_model))
-            .route("/api/chat", post(chat))
+            .route(
+                "/api/sessions/{session_id}/transcript",
+                get(get_session_transcript),
+            )
+            .route("/api/sessions/{session_id}/resume", post(resume_session))
+            .route(
+                "/api/sessions/{session_id}/cancel",
+                post(cancel_session_turn),
+            )
+            .route("/api/sessions/{session_id}/export", get(export_session))
+            .route("/api/chat", post(chat_or_ollama))
+            .route("/api/generate", post(crate::ollama_compat::generate))
+            .route("/api/tags", get(crate::ollama_compat::tags))
+            .route("/api/version", get(crate::ollama_compat::version))
             .route("/api/chat/stream", post(chat_stream))
-            .route("/api/ws", get(websocket_handler))
+            .route("/api/ws", get(websocket::ws_handler))
+            .route("/api/events", get(events_stream))
             .route("/api/memory/search", get(memory_search))
             .route("/api/memory/stats", get(memory_stats))
+            .route("/api/memory/files", get(memory_files))
+            .route("/api/memory/files/{*path}", get(memory_file))
+            .route("/api/memory/chunks/{chunk_id}", patch(mark_memory_chunk))
             .route("/api/memory/reindex", post(memory_reindex))
+            .route("/api/memory/ingest", post(memory_ingest))
             .route("/api/status", get(status))
             .route("/api/config", get(get_config))
             .route("/api/heartbeat/status", get(heartbeat_status))
+            .route("/api/cron/jobs", get(list_cron_jobs))
+            .route("/api/cron/jobs", post(add_cron_job))
+            .route("/api/cron/jobs/{name}", get(get_cron_job))
+            .route("/api/cron/jobs/{name}", patch(update_cron_job))
+            .route("/api/cron/jobs/{name}", delete(remove_cron_job))
+            .route("/api/cron/jobs/{name}/pause", post(pause_cron_job))
+            .route("/api/cron/jobs/{name}/resume", post(resume_cron_job))
+            .route("/api/cron/jobs/{name}/enable", post(enable_cron_job))
+            .route("/api/cron/jobs/{name}/disable", post(disable_cron_job))
+            .route("/api/cron/jobs/{name}/run", post(run_cron_job))
+            .route("/api/cron/history", get(get_cron_history))
             .route("/api/bridges", get(list_bridges))
             .route("/api/saved-sessions", get(list_saved_sessions))
             .route("/api/saved-sessions/{session_id}", get(get_saved_session))
+            .route(
+                "/api/saved-sessions/{session_id}",
+                patch(rename_saved_session),
+            )
             .route("/api/logs/daemon", get(get_daemon_logs))
+            .route("/api/audit/tools", get(get_tool_audit))
+            .route("/api/usage", get(get_usage))
+            .route("/api/admin/status", get(admin_status))
+            .route("/api/approvals", get(list_write_approvals))
+            .route("/api/approvals/{id}", post(answer_write_approval))
             .layer(middleware::from_fn_with_state(
                 state.clone(),
                 rate_limit_middleware,
@@ -218,29 +617,180 @@ impl Server {
                 auth_middleware,
             ));
 
+        // Cron webhooks come from other services, which authenticate with
+        // the job's X-Webhook-Secret instead of an API token
+        let hook_routes = Router::new()
+            .route("/api/cron/hooks/{name}", post(cron_webhook))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                rate_limit_middleware,
+            ));
+
         let app = public_routes
             .merge(api_routes)
+            .merge(hook_routes)
             .merge(openai_routes)
             .layer(RequestBodyLimitLayer::new(
                 self.config.server.max_request_body,
             ))
             .layer(cors)
-            .with_state(state);
+            .with_state(state.clone());
+
+        if !self.config.server.tcp && self.config.server.unix_socket.is_none() {
+            anyhow::bail!("server.tcp = false needs server.unix_socket to listen on");
+        }
+
+        let tcp = async {
+            if !self.config.server.tcp {
+                return Ok(());
+            }
+            let addr: SocketAddr =
+                format!("{}:{}", self.config.server.bind, self.config.server.port).parse()?;
+
+            info!("Starting HTTP server on http://{}", addr);
+
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            axum::serve(
+                listener,
+                app.clone()
+                    .into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(self.shutdown.wait())
+            .await?;
+            Ok::<_, anyhow::Error>(())
+        };
+        let unix = serve_unix_socket(&self.config.server, app.clone(), &self.shutdown);
+        let serve = async { tokio::try_join!(tcp, unix).map(|_| ()) };
+        tokio::pin!(serve);
+
+        tokio::select! {
+            served = &mut serve => served,
+            () = self.shutdown.wait() => {
+                // Each turn holds the sessions lock, so this saves as soon as
+                // in-flight turns finish, without waiting for idle
+                // connections (websockets) to close
+                save_dirty_sessions(&state).await;
+                info!("HTTP server stopped accepting connections; sessions saved");
+                let served = serve.await;
+                // Anything changed while the last connections closed
+                save_dirty_sessions(&state).await;
+                served
+            }
+        }
+    }
+}
+
+/// Marks requests that arrived over `server.unix_socket`.
+#[derive(Debug, Clone, Copy)]
+struct UnixSocketConnection;
 
-        let addr: SocketAddr =
-            format!("{}:{}", self.config.server.bind, self.config.server.port).parse()?;
+/// Serve `app` on `server.unix_socket`, if set. The socket is created
+/// owner-only, so filesystem permissions decide who may connect.
+#[cfg(unix)]
+async fn serve_unix_socket(config: &ServerConfig, app: Router, shutdown: &Shutdown) -> Result<()> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    let Some(path) = config.unix_socket_path() else {
+        return Ok(());
+    };
+    // A socket left behind by a previous run would make bind fail
+    match std::fs::symlink_metadata(&path) {
+        Ok(meta) if meta.file_type().is_socket() => std::fs::remove_file(&path)?,
+        Ok(_) => anyhow::bail!("{} exists and is not a socket", path.display()),
+        Err(_) => {}
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
 
-        info!("Starting HTTP server on http://{}", addr);
+    let listener = tokio::net::UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    info!("Starting HTTP server on unix:{}", path.display());
 
-        let listener = tokio::net::TcpListener::bind(addr).await?;
-        axum::serve(listener, app).await?;
+    axum::serve(listener, app.layer(Extension(UnixSocketConnection)))
+        .with_graceful_shutdown(shutdown.wait())
+        .await?;
+    std::fs::remove_file(&path).ok();
+    Ok(())
+}
 
-        Ok(())
+#[cfg(not(unix))]
+async fn serve_unix_socket(
+    config: &ServerConfig,
+    _app: Router,
+    _shutdown: &Shutdown,
+) -> Result<()> {
+    if config.unix_socket.is_some() {
+        anyhow::bail!("server.unix_socket is only supported on Unix");
     }
+    Ok(())
 }
 
 // Error response type
-struct AppError(StatusCode, String);
+#[derive(Debug)]
+pub(crate) struct AppError(pub(crate) StatusCode, pub(crate) String);
+
+/// Build the CORS layer from `[server.cors]`.
+fn cors_layer(config: &CorsConfig) -> Result<CorsLayer> {
+    let any = |values: &[String]| values.iter().any(|v| v == "*");
+    if config.allow_credentials
+        && (any(&config.allowed_origins)
+            || any(&config.allowed_methods)
+            || any(&config.allowed_headers))
+    {
+        anyhow::bail!(
+            "server.cors.allow_credentials requires explicit allowed_origins, allowed_methods, \
+             and allowed_headers (no \"*\")"
+        );
+    }
+
+    let mut cors = CorsLayer::new().allow_credentials(config.allow_credentials);
+    cors = if any(&config.allowed_origins) {
+        cors.allow_origin(Any)
+    } else {
+        let origins = config
+            .allowed_origins
+            .iter()
+            .map(|o| {
+                header::HeaderValue::from_str(o)
+                    .map_err(|_| anyhow::anyhow!("Invalid CORS origin: {}", o))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        cors.allow_origin(AllowOrigin::list(origins))
+    };
+    cors = if any(&config.allowed_methods) {
+        cors.allow_methods(Any)
+    } else {
+        let methods = config
+            .allowed_methods
+            .iter()
+            .map(|m| {
+                axum::http::Method::from_bytes(m.to_uppercase().as_bytes())
+                    .map_err(|_| anyhow::anyhow!("Invalid CORS method: {}", m))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        cors.allow_methods(methods)
+    };
+    cors = if any(&config.allowed_headers) {
+        cors.allow_headers(Any)
+    } else {
+        let headers = config
+            .allowed_headers
+            .iter()
+            .map(|h| {
+                header::HeaderName::from_bytes(h.to_lowercase().as_bytes())
+                    .map_err(|_| anyhow::anyhow!("Invalid CORS header: {}", h))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        cors.allow_headers(headers)
+    };
+    if let Some(max_age) = &config.max_age {
+        let max_age = localgpt_core::config::parse_duration(max_age)
+            .map_err(|e| anyhow::anyhow!("Invalid server.cors.max_age: {}", e))?;
+        cors = cors.max_age(max_age);
+    }
+    Ok(cors)
+}
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
@@ -248,67 +798,215 @@ impl IntoResponse for AppError {
     }
 }
 
-// Auth middleware for API routes
+/// Scope an API key needs to call `path`. Anything not known to be safe
+/// for chat or memory-read keys needs `admin`.
+fn required_scope(method: &axum::http::Method, path: &str) -> ApiScope {
+    const CHAT_PREFIXES: &[&str] = &[
+        "/v1/",
+        "/api/chat",
+        "/api/generate",
+        "/api/tags",
+        "/api/version",
+        "/api/ws",
+        "/api/sessions",
+        "/api/saved-sessions",
+        "/api/usage",
+    ];
+    if CHAT_PREFIXES.iter().any(|p| path.starts_with(p)) {
+        return ApiScope::Chat;
+    }
+    if *method == axum::http::Method::GET
+        && (path == "/api/memory/search"
+            || path == "/api/memory/stats"
+            || path.starts_with("/api/memory/files"))
+    {
+        return ApiScope::MemoryRead;
+    }
+    ApiScope::Admin
+}
+
+fn api_key_store(state: &AppState) -> ApiKeyStore {
+    ApiKeyStore::new(&state.config.paths.state_dir)
+}
+
+/// Whether requests must authenticate: a token is configured or API keys
+/// have been created.
+fn auth_required(state: &AppState) -> bool {
+    keys_require_auth(state, &api_key_store(state).list())
+}
+
+/// [`auth_required`] for keys already loaded. Unreadable keys require auth.
+fn keys_require_auth(state: &AppState, keys: &Result<Vec<ApiKey>>) -> bool {
+    state.config.server.auth_token.is_some()
+        || keys.as_ref().map(|keys| !keys.is_empty()).unwrap_or(true)
+}
+
+/// Name of the API key a request authenticated with, for per-key limits.
+#[derive(Clone)]
+struct AuthenticatedKey(String);
+
+// Auth middleware for API routes. The configured `auth_token` has every
+// scope; API keys only those they were created with.
 async fn auth_middleware(
     State(state): State<Arc<AppState>>,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    // If no token configured, pass through (backward compat)
-    let Some(expected) = &state.config.server.auth_token else {
+    // If no token or keys configured, pass through (backward compat).
+    // With `unix_socket_trust`, reaching the owner-only unix socket is
+    // proof enough, too, unless the client sends its own credentials.
+    // The keys file is read once and used for both checks.
+    let keys = api_key_store(&state).list();
+    let trusted_socket = state.config.server.unix_socket_trust
+        && request.extensions().get::<UnixSocketConnection>().is_some()
+        && !request.headers().contains_key(header::AUTHORIZATION);
+    if !keys_require_auth(&state, &keys) || trusted_socket {
+        request.extensions_mut().insert(Caller {
+            user: None,
+            admin: true,
+        });
         return Ok(next.run(request).await);
-    };
+    }
 
-    let auth_header = request
+    let token = request
         .headers()
         .get("authorization")
-        .and_then(|v| v.to_str().ok());
+        .and_then(|v| v.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+    let Some(token) = token else {
+        debug!("Auth failed: missing or invalid Authorization header");
+        return Err(StatusCode::UNAUTHORIZED);
+    };
 
-    match auth_header {
-        Some(h) if h.starts_with("Bearer ") => {
-            let token = &h[7..];
-            if token == expected {
-                Ok(next.run(request).await)
-            } else {
-                debug!("Auth failed: invalid token");
-                Err(StatusCode::UNAUTHORIZED)
-            }
-        }
-        _ => {
-            debug!("Auth failed: missing or invalid Authorization header");
-            Err(StatusCode::UNAUTHORIZED)
+    if let Some(expected) = state.config.server.auth_token.as_deref()
+        && constant_time_eq(token.as_bytes(), expected.as_bytes())
+    {
+        request
+            .extensions_mut()
+            .insert(AuthenticatedKey("server.auth_token".to_string()));
+        request.extensions_mut().insert(Caller {
+            user: None,
+            admin: true,
+        });
+        return Ok(next.run(request).await);
+    }
+
+    let keys = match keys {
+        Ok(keys) => keys,
+        Err(e) => {
+            warn!("Failed to read API keys: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
+    };
+    let Some(key) = keys.into_iter().find(|k| k.matches(token)) else {
+        debug!("Auth failed: invalid token");
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+    let scope = required_scope(request.method(), request.uri().path());
+    if !key.allows(scope) {
+        debug!(
+            "Auth failed: API key '{}' lacks scope '{}' for {}",
+            key.name,
+            scope.as_str(),
+            request.uri().path()
+        );
+        return Err(StatusCode::FORBIDDEN);
     }
+    request.extensions_mut().insert(Caller {
+        admin: key.allows(ApiScope::Admin),
+        user: key.user,
+    });
+    request.extensions_mut().insert(AuthenticatedKey(key.name));
+    Ok(next.run(request).await)
+}
+
+/// Routes whose requests count against the concurrent chat request limit.
+fn is_chat_route(path: &str) -> bool {
+    path == "/v1/chat/completions" || path.starts_with("/api/chat") || path == "/api/generate"
+}
+
+fn rate_limit_headers(decision: &RateDecision) -> [(&'static str, String); 3] {
+    [
+        ("ratelimit-limit", decision.limit.to_string()),
+        ("ratelimit-remaining", decision.remaining.to_string()),
+        ("ratelimit-reset", decision.reset_secs.to_string()),
+    ]
 }
 
-// Rate limit middleware for API routes
+// Rate limit middleware for API routes. Runs after auth, so requests made
+// with an API key are limited per key and the rest per IP.
 async fn rate_limit_middleware(
     State(state): State<Arc<AppState>>,
     request: Request,
     next: Next,
 ) -> Result<Response, Response> {
-    let ip = request
-        .extensions()
-        .get::<axum::extract::ConnectInfo<SocketAddr>>()
-        .map(|ci| ci.0.ip())
-        .unwrap_or_else(|| std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST));
+    let client = match request.extensions().get::<AuthenticatedKey>() {
+        Some(key) => ClientKey::ApiKey(key.0.clone()),
+        None => ClientKey::Ip(
+            request
+                .extensions()
+                .get::<axum::extract::ConnectInfo<SocketAddr>>()
+                .map(|ci| ci.0.ip())
+                .unwrap_or_else(|| std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST)),
+        ),
+    };
 
-    if !state.rate_limiter.check(ip).await {
+    let decision = state.rate_limiter.check(&client).await;
+    if !decision.allowed {
+        debug!("Rate limited: {:?}", client);
         return Err((
             StatusCode::TOO_MANY_REQUESTS,
-            [(header::RETRY_AFTER, "60")],
+            rate_limit_headers(&decision),
+            [(header::RETRY_AFTER, decision.reset_secs.max(1).to_string())],
             "Rate limit exceeded",
         )
             .into_response());
     }
 
-    Ok(next.run(request).await)
+    // Hold a concurrency slot until a chat response, streamed or not, has
+    // been fully sent
+    let guard = if is_chat_route(request.uri().path()) {
+        match state.rate_limiter.try_acquire_stream(&client) {
+            Some(guard) => Some(guard),
+            None => {
+                debug!("Too many concurrent chat requests: {:?}", client);
+                return Err((
+                    StatusCode::TOO_MANY_REQUESTS,
+                    rate_limit_headers(&decision),
+                    [(header::RETRY_AFTER, "5".to_string())],
+                    format!(
+                        "Too many concurrent chat requests (limit {})",
+                        state.rate_limiter.max_streams()
+                    ),
+                )
+                    .into_response());
+            }
+        }
+    } else {
+        None
+    };
+
+    let response = next.run(request).await;
+    let (mut parts, body) = response.into_parts();
+    for (name, value) in rate_limit_headers(&decision) {
+        if let Ok(value) = header::HeaderValue::from_str(&value) {
+            parts.headers.insert(name, value);
+        }
+    }
+    let body = match guard {
+        Some(guard) => axum::body::Body::from_stream(body.into_data_stream().map(move |chunk| {
+            let _held = &guard;
+            chunk
+        })),
+        None => body,
+    };
+    Ok(Response::from_parts(parts, body))
 }
 
 // Auth status endpoint (public, tells client if auth is required)
 async fn auth_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     Json(json!({
-        "auth_required": state.config.server.auth_token.is_some()
+        "auth_required": auth_required(&state)
     }))
 }
 
@@ -321,6 +1019,7 @@ async fn cleanup_expired_sessions(state: &Arc<AppState>) {
         let expired = entry.last_accessed.elapsed() > SESSION_TIMEOUT;
         if expired {
             debug!("Expiring session: {}", id);
+            emit_session_completed(&state.config, id, entry);
         }
         !expired
     });
@@ -331,31 +1030,65 @@ async fn cleanup_expired_sessions(state: &Arc<AppState>) {
     }
 }
 
+fn emit_session_completed(config: &Config, session_id: &str, entry: &SessionEntry) {
+    localgpt_core::webhooks::emit(
+        config,
+        WebhookEvent::SessionCompleted {
+            session_id: session_id.to_string(),
+            channel: HTTP_AGENT_ID.to_string(),
+            message_count: entry.agent.session_status().message_count,
+        },
+    );
+}
+
 // Load persisted sessions from disk
 async fn load_persisted_sessions(state: &Arc<AppState>) -> Result<(), anyhow::Error> {
     use localgpt_core::agent::list_sessions_for_agent;
-    use std::sync::Arc as StdArc;
 
-    let sessions_list = list_sessions_for_agent(HTTP_AGENT_ID)?;
+    // The default namespace, then each API key user's
+    let users = api_key_store(state).users().unwrap_or_default();
+    let mut owners = vec![Caller::default()];
+    owners.extend(users.into_iter().map(|user| Caller {
+        user: Some(user),
+        admin: false,
+    }));
+
+    let mut sessions_list = Vec::new();
+    for owner in owners {
+        for session_info in list_sessions_for_agent(&owner.agent_id())? {
+            sessions_list.push((owner.clone(), session_info));
+        }
+    }
     let mut loaded = 0;
 
-    for session_info in sessions_list.into_iter().take(MAX_SESSIONS) {
+    for (caller, session_info) in sessions_list.into_iter().take(MAX_SESSIONS) {
         let agent_config = AgentConfig {
             model: state.config.agent.default_model.clone(),
             context_window: state.config.agent.context_window,
             reserve_tokens: state.config.agent.reserve_tokens,
         };
 
-        let memory = StdArc::new(state.memory.clone());
-        let mut agent = Agent::new(agent_config, &state.config, memory).await?;
+        let owner = caller.agent_id();
+        let mut agent = state.caller_agent(&caller, agent_config).await?;
+        agent.set_channel(HTTP_AGENT_ID);
 
         // Try to resume the session
-        if agent.resume_session(&session_info.id).await.is_ok() {
+        if agent
+            .resume_session_for_agent(&session_info.id, &owner)
+            .await
+            .is_ok()
+        {
             let mut sessions = state.sessions.lock().await;
+            // Another user may have saved a session under the same id
+            let key = match sessions.get(&session_info.id) {
+                Some(entry) if entry.owner != owner => conversation_key(&caller, &session_info.id),
+                _ => session_info.id.clone(),
+            };
             sessions.insert(
-                session_info.id.clone(),
+                key,
                 SessionEntry {
                     agent,
+                    owner,
                     last_accessed: Instant::now(),
                     dirty: false,
                 },
@@ -378,7 +1111,7 @@ async fn save_dirty_sessions(state: &Arc<AppState>) {
 
     for (id, entry) in sessions.iter_mut() {
         if entry.dirty {
-            if let Err(e) = entry.agent.save_session_for_agent(HTTP_AGENT_ID).await {
+            if let Err(e) = entry.agent.save_session_for_agent(&entry.owner).await {
                 debug!("Failed to save session {}: {}", id, e);
             } else {
                 entry.dirty = false;
@@ -393,22 +1126,39 @@ async fn save_dirty_sessions(state: &Arc<AppState>) {
 }
 
 // Get or create a session
-async fn get_or_create_session(
+pub(crate) async fn get_or_create_session(
     state: &Arc<AppState>,
     session_id: Option<String>,
+    caller: &Caller,
 ) -> Result<String, AppError> {
+    if let Some(ref id) = session_id
+        && !valid_session_id(id)
+    {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            "Invalid session id".to_string(),
+        ));
+    }
     let mut sessions = state.sessions.lock().await;
 
     // If session_id provided, try to use existing session
     if let Some(ref id) = session_id
-        && sessions.contains_key(id)
+        && let Some(entry) = sessions.get_mut(id)
     {
-        // Update last accessed time
-        if let Some(entry) = sessions.get_mut(id) {
-            entry.last_accessed = Instant::now();
+        // Another user's session id can't be reused
+        if !entry.owned_by(caller) {
+            return Err(AppError(
+                StatusCode::NOT_FOUND,
+                "Session not found".to_string(),
+            ));
         }
+        // Update last accessed time
+        entry.last_accessed = Instant::now();
         return Ok(id.clone());
     }
+    if let Some(ref id) = session_id {
+        state.session_available(id, caller)?;
+    }
 
     // Check session limit
     if sessions.len() >= MAX_SESSIONS {
@@ -438,10 +1188,11 @@ async fn get_or_create_session(
         reserve_tokens: state.config.agent.reserve_tokens,
     };
 
-    let memory = std::sync::Arc::new(state.memory.clone());
-    let mut agent = Agent::new(agent_config, &state.config, memory)
+    let mut agent = state
+        .caller_agent(caller, agent_config)
         .await
         .map_err(|e| AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    agent.set_channel(HTTP_AGENT_ID);
 
     agent
         .new_session()
@@ -452,12 +1203,20 @@ async fn get_or_create_session(
         new_id.clone(),
         SessionEntry {
             agent,
+            owner: caller.agent_id(),
             last_accessed: Instant::now(),
             dirty: true, // New sessions should be saved
         },
     );
 
     info!("Created new session: {}", new_id);
+    localgpt_core::webhooks::emit(
+        &state.config,
+        WebhookEvent::SessionCreated {
+            session_id: new_id.clone(),
+            channel: HTTP_AGENT_ID.to_string(),
+        },
+    );
     Ok(new_id)
 }
 
@@ -466,33 +1225,187 @@ async fn health_check() -> &'static str {
     "OK"
 }
 
+// Liveness probe: the process is up and serving requests
+async fn healthz() -> Json<serde_json::Value> {
+    Json(json!({"status": "ok"}))
+}
+
+/// One dependency check reported by `/readyz`.
+#[derive(Debug, Clone, Serialize)]
+struct ReadyCheck {
+    name: &'static str,
+    /// "ok", "fail", or "skip" for components this process doesn't run
+    status: &'static str,
+    detail: String,
+}
+
+impl ReadyCheck {
+    fn new(name: &'static str, result: Result<String>) -> Self {
+        match result {
+            Ok(detail) => Self {
+                name,
+                status: "ok",
+                detail,
+            },
+            Err(e) => Self {
+                name,
+                status: "fail",
+                detail: format!("{:#}", e),
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ReadyResponse {
+    ready: bool,
+    checks: Vec<ReadyCheck>,
+}
+
+// Readiness probe: 200 when no dependency check fails, 503 otherwise
+async fn readyz(State(state): State<Arc<AppState>>) -> Response {
+    let memory = ReadyCheck::new(
+        "memory",
+        state
+            .memory
+            .chunk_count()
+            .map(|chunks| format!("{} chunks indexed", chunks)),
+    );
+    let checks = vec![
+        memory,
+        provider_check(&state).await,
+        component_check(readiness::BRIDGE, None),
+        component_check(readiness::SCHEDULER, Some(SCHEDULER_STALE_AFTER)),
+    ];
+
+    let ready = checks.iter().all(|check| check.status != "fail");
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(ReadyResponse { ready, checks })).into_response()
+}
+
+/// Whether the default model's provider answers, cached for
+/// [`PROVIDER_PROBE_TTL`] so frequent probes don't hit the provider.
+async fn provider_check(state: &AppState) -> ReadyCheck {
+    let mut cached = state.provider_probe.lock().await;
+    if let Some((at, check)) = cached.as_ref()
+        && at.elapsed() < PROVIDER_PROBE_TTL
+    {
+        return check.clone();
+    }
+    let model = &state.config.agent.default_model;
+    let check = ReadyCheck::new(
+        "provider",
+        model_catalog::probe_model(&state.config, model).await,
+    );
+    *cached = Some((Instant::now(), check.clone()));
+    check
+}
+
+/// A daemon component's latest report; `stale_after` fails one that has
+/// stopped reporting.
+fn component_check(name: &'static str, stale_after: Option<Duration>) -> ReadyCheck {
+    let Some(status) = readiness::status(name) else {
+        return ReadyCheck {
+            name,
+            status: "skip",
+            detail: "not running in this process".to_string(),
+        };
+    };
+    let result = match status.state {
+        ComponentState::Failed(e) => Err(anyhow::anyhow!(e)),
+        ComponentState::Running => match stale_after {
+            Some(limit) if status.reported_at.elapsed() > limit => Err(anyhow::anyhow!(
+                "no report for {}s",
+                status.reported_at.elapsed().as_secs()
+            )),
+            _ => Ok("running".to_string()),
+        },
+    };
+    ReadyCheck::new(name, result)
+}
+
 // Serve UI index.html at root
-async fn serve_ui_index() -> Response {
-    serve_ui_asset("index.html")
+async fn serve_ui_index(headers: HeaderMap) -> Response {
+    match UiAssets::get("index.html") {
+        Some(content) => embedded_response("index.html", content, "no-cache", &headers),
+        None => (StatusCode::NOT_FOUND, "Not found").into_response(),
+    }
+}
+
+// Serve the UI app at /ui: the egui web build if embedded, else index.html
+async fn serve_ui_app(headers: HeaderMap) -> Response {
+    serve_ui_asset("index.html", &headers)
 }
 
 // Serve UI static files
-async fn serve_ui_file(Path(path): Path<String>) -> Response {
-    serve_ui_asset(&path)
+async fn serve_ui_file(Path(path): Path<String>, headers: HeaderMap) -> Response {
+    serve_ui_asset(&path, &headers)
 }
 
 // Helper to serve embedded UI assets
-fn serve_ui_asset(path: &str) -> Response {
+fn serve_ui_asset(path: &str, headers: &HeaderMap) -> Response {
+    #[cfg(feature = "egui-web")]
+    if let Some(content) = EguiWebAssets::get(path) {
+        // Trunk content-hashes every file name except the page itself
+        let cache_control = if path.ends_with(".html") {
+            "no-cache"
+        } else {
+            "public, max-age=31536000, immutable"
+        };
+        return embedded_response(path, content, cache_control, headers);
+    }
+
     match UiAssets::get(path) {
-        Some(content) => {
-            let mime = match path.rsplit('.').next() {
-                Some("js") => "application/javascript".to_string(),
-                Some("wasm") => "application/wasm".to_string(),
-                _ => mime_guess::from_path(path)
-                    .first_or_octet_stream()
-                    .to_string(),
-            };
-            ([(header::CONTENT_TYPE, mime)], content.data.to_vec()).into_response()
-        }
+        Some(content) => embedded_response(path, content, "no-cache", headers),
         None => (StatusCode::NOT_FOUND, "Not found").into_response(),
     }
 }
 
+/// Respond with an embedded file, or `304` if the client's copy is current.
+fn embedded_response(
+    path: &str,
+    content: rust_embed::EmbeddedFile,
+    cache_control: &'static str,
+    headers: &HeaderMap,
+) -> Response {
+    let hash: String = content
+        .metadata
+        .sha256_hash()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let etag = format!("\"{}\"", hash);
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, cache_control.to_string()),
+    ];
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|tags| tags.split(',').any(|tag| tag.trim() == etag))
+    {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    let mime = match path.rsplit('.').next() {
+        Some("js") => "application/javascript".to_string(),
+        Some("wasm") => "application/wasm".to_string(),
+        _ => mime_guess::from_path(path)
+            .first_or_octet_stream()
+            .to_string(),
+    };
+    (
+        [(header::CONTENT_TYPE, mime)],
+        cache_headers,
+        content.data.to_vec(),
+    )
+        .into_response()
+}
+
 // Status endpoint
 #[derive(Serialize)]
 struct StatusResponse {
@@ -521,12 +1434,85 @@ async fn list_bridges(
     Json(state.bridge_manager.get_active_bridges().await)
 }
 
-// Session management endpoints
-#[derive(Deserialize)]
-struct CreateSessionRequest {
-    session_id: Option<String>,
-}
-
+// Admin status endpoint - one document for `localgpt status` and the UI dashboard
+#[derive(Serialize)]
+struct AdminStatusResponse {
+    version: String,
+    pid: u32,
+    started_at: chrono::DateTime<chrono::Utc>,
+    uptime_secs: u64,
+    model: String,
+    /// Providers with a config section
+    providers: Vec<&'static str>,
+    active_sessions: usize,
+    mcp_servers: Vec<localgpt_core::mcp::supervisor::ServerStatus>,
+    bridges: Vec<crate::security::bridge::BridgeStatus>,
+    scheduler: SchedulerStatus,
+    /// `None` if the memory index could not be read
+    memory: Option<StatsResponse>,
+    /// Turn slots and queue wait times
+    turns: TurnGateStats,
+}
+
+#[derive(Serialize)]
+struct SchedulerStatus {
+    heartbeat: HeartbeatStatusResponse,
+    cron_jobs: usize,
+    cron_enabled: usize,
+    cron_paused: usize,
+    /// Earliest upcoming cron run
+    next_run: Option<chrono::DateTime<chrono::Local>>,
+}
+
+async fn admin_status(State(state): State<Arc<AppState>>) -> Json<AdminStatusResponse> {
+    let active_sessions = state.sessions.lock().await.len();
+
+    let mcp_servers = localgpt_core::mcp::supervisor::read_status(&state.config.paths.state_dir)
+        .unwrap_or_else(|e| {
+            warn!("Failed to read MCP status: {}", e);
+            Vec::new()
+        });
+
+    let jobs = CronJobStore::from_config(&state.config)
+        .and_then(|store| store.list())
+        .unwrap_or_else(|e| {
+            warn!("Failed to list cron jobs: {}", e);
+            Vec::new()
+        });
+    let scheduler = SchedulerStatus {
+        heartbeat: heartbeat_status_inner(&state.config),
+        cron_jobs: jobs.len(),
+        cron_enabled: jobs.iter().filter(|info| info.job.enabled).count(),
+        cron_paused: jobs.iter().filter(|info| info.paused).count(),
+        next_run: jobs.iter().filter_map(|info| info.next_run).min(),
+    };
+
+    let memory = memory_stats_inner(&state.memory)
+        .inspect_err(|e| warn!("Failed to read memory stats: {}", e))
+        .ok();
+
+    Json(AdminStatusResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        pid: std::process::id(),
+        started_at: state.started_at,
+        uptime_secs: (chrono::Utc::now() - state.started_at).num_seconds().max(0) as u64,
+        model: state.config.agent.default_model.clone(),
+        providers: state.config.providers.configured(),
+        active_sessions,
+        mcp_servers,
+        bridges: state.bridge_manager.get_active_bridges().await,
+        scheduler,
+        memory,
+        turns: state.turn_gate.stats(),
+    })
+}
+
+// Session management endpoints
+#[derive(Deserialize)]
+struct CreateSessionRequest {
+    session_id: Option<String>,
+}
+
 #[derive(Serialize)]
 struct SessionResponse {
     session_id: String,
@@ -535,9 +1521,10 @@ struct SessionResponse {
 
 async fn create_session(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Json(request): Json<CreateSessionRequest>,
 ) -> Response {
-    match get_or_create_session(&state, request.session_id).await {
+    match get_or_create_session(&state, request.session_id, &caller).await {
         Ok(session_id) => Json(SessionResponse {
             session_id,
             model: state.config.agent.default_model.clone(),
@@ -547,10 +1534,16 @@ async fn create_session(
     }
 }
 
+/// An HTTP session: active in memory, saved on disk, or both. Active
+/// sessions are listed under their HTTP id; saved ones under their file id.
 #[derive(Serialize)]
 struct SessionInfo {
     session_id: String,
-    idle_seconds: u64,
+    title: Option<String>,
+    created_at: Option<String>,
+    message_count: usize,
+    active: bool,
+    idle_seconds: Option<u64>,
 }
 
 #[derive(Serialize)]
@@ -558,30 +1551,111 @@ struct ListSessionsResponse {
     sessions: Vec<SessionInfo>,
 }
 
-async fn list_sessions(State(state): State<Arc<AppState>>) -> Json<ListSessionsResponse> {
+async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+) -> Response {
+    use localgpt_core::agent::list_sessions_for_agent;
+
+    let saved = match list_sessions_for_agent(&caller.agent_id()) {
+        Ok(saved) => saved,
+        Err(e) => {
+            return AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+    };
     let sessions = state.sessions.lock().await;
 
-    let session_list: Vec<SessionInfo> = sessions
+    let sessions: Vec<(&String, &SessionEntry)> = sessions
+        .iter()
+        .filter(|(_, entry)| entry.owned_by(&caller))
+        .collect();
+
+    let mut session_list: Vec<SessionInfo> = sessions
         .iter()
-        .map(|(id, entry)| SessionInfo {
-            session_id: id.clone(),
-            idle_seconds: entry.last_accessed.elapsed().as_secs(),
+        .map(|(id, entry)| {
+            let status = entry.agent.session_status();
+            let file = saved.iter().find(|s| s.id == status.id);
+            SessionInfo {
+                session_id: id.to_string(),
+                title: file.and_then(|s| s.title.clone()),
+                created_at: file.map(|s| s.created_at.format("%Y-%m-%dT%H:%M:%S").to_string()),
+                message_count: status.message_count,
+                active: true,
+                idle_seconds: Some(entry.last_accessed.elapsed().as_secs()),
+            }
         })
         .collect();
+    let active_ids: Vec<String> = sessions
+        .iter()
+        .map(|(_, entry)| entry.agent.session_status().id)
+        .collect();
+    session_list.extend(
+        saved
+            .into_iter()
+            .filter(|s| !active_ids.contains(&s.id))
+            .map(|s| SessionInfo {
+                session_id: s.id,
+                title: s.title,
+                created_at: Some(s.created_at.format("%Y-%m-%dT%H:%M:%S").to_string()),
+                message_count: s.message_count,
+                active: false,
+                idle_seconds: None,
+            }),
+    );
 
     Json(ListSessionsResponse {
         sessions: session_list,
     })
+    .into_response()
 }
 
-// Delete a session
+/// Session ids end up in file names, so only accept the characters our
+/// own ids use.
+fn valid_session_id(session_id: &str) -> bool {
+    !session_id.is_empty()
+        && session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// Delete a session, both from memory and from disk
 async fn delete_session(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Path(session_id): Path<String>,
 ) -> Response {
+    use localgpt_core::agent::delete_session_for_agent;
+
+    if !valid_session_id(&session_id) {
+        return AppError(StatusCode::BAD_REQUEST, "Invalid session id".to_string()).into_response();
+    }
     let mut sessions = state.sessions.lock().await;
 
-    if sessions.remove(&session_id).is_some() {
+    // The id may name an active session or the file of one
+    let mut file_id = session_id.clone();
+    let mut found = false;
+    if sessions
+        .get(&session_id)
+        .is_some_and(|entry| entry.owned_by(&caller))
+        && let Some(entry) = sessions.remove(&session_id)
+    {
+        file_id = entry.agent.session_status().id;
+        emit_session_completed(&state.config, &session_id, &entry);
+        found = true;
+    }
+    let before = sessions.len();
+    sessions
+        .retain(|_, entry| !entry.owned_by(&caller) || entry.agent.session_status().id != file_id);
+    found |= sessions.len() != before;
+
+    match delete_session_for_agent(&caller.agent_id(), &file_id) {
+        Ok(deleted) => found |= deleted,
+        Err(e) => {
+            return AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+    }
+
+    if found {
         info!("Deleted session: {}", session_id);
         Json(json!({"deleted": true, "session_id": session_id})).into_response()
     } else {
@@ -589,6 +1663,264 @@ async fn delete_session(
     }
 }
 
+// Full transcript of an active or saved session
+async fn get_session_transcript(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Path(session_id): Path<String>,
+) -> Response {
+    if !valid_session_id(&session_id) {
+        return AppError(StatusCode::BAD_REQUEST, "Invalid session id".to_string()).into_response();
+    }
+
+    // Flush an active session so the file has its latest messages
+    let file_id = {
+        let mut sessions = state.sessions.lock().await;
+        match sessions
+            .get_mut(&session_id)
+            .filter(|entry| entry.owned_by(&caller))
+        {
+            Some(entry) => {
+                entry.last_accessed = Instant::now();
+                if let Err(e) = entry.agent.save_session_for_agent(&entry.owner).await {
+                    return AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+                        .into_response();
+                }
+                entry.dirty = false;
+                entry.agent.session_status().id
+            }
+            None => session_id,
+        }
+    };
+
+    saved_session_detail(&caller.agent_id(), &file_id)
+}
+
+// Load a saved session back into memory so it can be chatted with
+async fn resume_session(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Path(session_id): Path<String>,
+) -> Response {
+    let session_id = match resume_saved_session(&state, &session_id, &caller).await {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    let sessions = state.sessions.lock().await;
+    match sessions.get(&session_id) {
+        Some(entry) => Json(SessionResponse {
+            model: entry.agent.model().to_string(),
+            session_id,
+        })
+        .into_response(),
+        None => AppError(StatusCode::NOT_FOUND, "Session not found".to_string()).into_response(),
+    }
+}
+
+/// Make the saved session `session_id` active, loading it from disk unless
+/// it already is. Returns the id it is active under.
+pub(crate) async fn resume_saved_session(
+    state: &Arc<AppState>,
+    session_id: &str,
+    caller: &Caller,
+) -> Result<String, AppError> {
+    if !valid_session_id(session_id) {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            "Invalid session id".to_string(),
+        ));
+    }
+    let mut sessions = state.sessions.lock().await;
+
+    // Already active, under this id or as the session saved in this file
+    if let Some((id, entry)) = sessions.iter_mut().find(|(id, entry)| {
+        entry.owned_by(caller)
+            && (**id == session_id || entry.agent.session_status().id == session_id)
+    }) {
+        entry.last_accessed = Instant::now();
+        return Ok(id.clone());
+    }
+    if sessions.contains_key(session_id) {
+        // Active under this id, but for another user
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            "Session not found".to_string(),
+        ));
+    }
+    state.session_available(session_id, caller)?;
+
+    let agent_config = AgentConfig {
+        model: state.config.agent.default_model.clone(),
+        context_window: state.config.agent.context_window,
+        reserve_tokens: state.config.agent.reserve_tokens,
+    };
+    let mut agent = state
+        .caller_agent(caller, agent_config)
+        .await
+        .map_err(|e| AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    agent.set_channel(HTTP_AGENT_ID);
+    agent
+        .resume_session_for_agent(session_id, &caller.agent_id())
+        .await
+        .map_err(|e| AppError(StatusCode::NOT_FOUND, e.to_string()))?;
+
+    if sessions.len() >= MAX_SESSIONS
+        && let Some(oldest_id) = sessions
+            .iter()
+            .min_by_key(|(_, e)| e.last_accessed)
+            .map(|(id, _)| id.clone())
+    {
+        sessions.remove(&oldest_id);
+        info!("Removed oldest session {} to make room", oldest_id);
+    }
+
+    sessions.insert(
+        session_id.to_string(),
+        SessionEntry {
+            agent,
+            owner: caller.agent_id(),
+            last_accessed: Instant::now(),
+            dirty: false,
+        },
+    );
+    info!("Resumed session: {}", session_id);
+    Ok(session_id.to_string())
+}
+
+/// Key of `caller`'s conversation `conversation_id` in the session map.
+/// Conversation ids are chosen by clients, so each caller has their own:
+/// the key can't clash with another caller's or with a plain session id.
+fn conversation_key(caller: &Caller, conversation_id: &str) -> String {
+    format!("{}:{}", caller.agent_id(), conversation_id)
+}
+
+/// Make `caller`'s conversation `conversation_id` active: the active
+/// session, the one saved under that id, or a new session saved under it.
+/// Returns its key in the session map.
+pub(crate) async fn conversation_session(
+    state: &Arc<AppState>,
+    conversation_id: &str,
+    caller: &Caller,
+) -> Result<String, AppError> {
+    if !valid_session_id(conversation_id) {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            "Invalid conversation id".to_string(),
+        ));
+    }
+    let key = conversation_key(caller, conversation_id);
+    let mut sessions = state.sessions.lock().await;
+
+    // Active under its key, or loaded at startup under its saved id
+    if let Some((id, entry)) = sessions.iter_mut().find(|(id, entry)| {
+        entry.owned_by(caller)
+            && (**id == key || entry.agent.session_status().id == conversation_id)
+    }) {
+        entry.last_accessed = Instant::now();
+        return Ok(id.clone());
+    }
+    state.session_available(&key, caller)?;
+
+    let agent_config = AgentConfig {
+        model: state.config.agent.default_model.clone(),
+        context_window: state.config.agent.context_window,
+        reserve_tokens: state.config.agent.reserve_tokens,
+    };
+    let mut agent = state
+        .caller_agent(caller, agent_config)
+        .await
+        .map_err(|e| AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    agent.set_channel(HTTP_AGENT_ID);
+
+    let owner = caller.agent_id();
+    let created = agent
+        .resume_session_for_agent(conversation_id, &owner)
+        .await
+        .is_err();
+    if created {
+        agent
+            .new_session_with_id(conversation_id)
+            .await
+            .map_err(|e| AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    if sessions.len() >= MAX_SESSIONS
+        && let Some(oldest_id) = sessions
+            .iter()
+            .min_by_key(|(_, e)| e.last_accessed)
+            .map(|(id, _)| id.clone())
+    {
+        sessions.remove(&oldest_id);
+        info!("Removed oldest session {} to make room", oldest_id);
+    }
+
+    sessions.insert(
+        key.clone(),
+        SessionEntry {
+            agent,
+            owner,
+            last_accessed: Instant::now(),
+            dirty: created,
+        },
+    );
+    if created {
+        info!("Created conversation: {}", key);
+        localgpt_core::webhooks::emit(
+            &state.config,
+            WebhookEvent::SessionCreated {
+                session_id: key.clone(),
+                channel: HTTP_AGENT_ID.to_string(),
+            },
+        );
+    } else {
+        info!("Resumed conversation: {}", key);
+    }
+    Ok(key)
+}
+
+// Export an active or saved session as Markdown
+async fn export_session(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Path(session_id): Path<String>,
+) -> Response {
+    use localgpt_core::agent::Session;
+
+    if !valid_session_id(&session_id) {
+        return AppError(StatusCode::BAD_REQUEST, "Invalid session id".to_string()).into_response();
+    }
+
+    let markdown = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&session_id)
+            .filter(|entry| entry.owned_by(&caller))
+            .map(|entry| entry.agent.export_markdown())
+    };
+    let markdown = match markdown {
+        Some(markdown) => markdown,
+        None => match Session::load_for_agent(&session_id, &caller.agent_id()) {
+            Ok(session) => session.export_markdown(None),
+            Err(e) => return AppError(StatusCode::NOT_FOUND, e.to_string()).into_response(),
+        },
+    };
+
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/markdown; charset=utf-8".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"session-{}.md\"", session_id),
+            ),
+        ],
+        markdown,
+    )
+        .into_response()
+}
+
 // Get session status
 #[derive(Serialize)]
 struct SessionStatusResponse {
@@ -606,11 +1938,15 @@ struct SessionStatusResponse {
 
 async fn get_session_status(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Path(session_id): Path<String>,
 ) -> Response {
     let sessions = state.sessions.lock().await;
 
-    match sessions.get(&session_id) {
+    match sessions
+        .get(&session_id)
+        .filter(|entry| entry.owned_by(&caller))
+    {
         Some(entry) => {
             let status = entry.agent.session_status();
             Json(SessionStatusResponse {
@@ -649,11 +1985,15 @@ struct SessionMessagesResponse {
 
 async fn get_session_messages(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Path(session_id): Path<String>,
 ) -> Response {
     let mut sessions = state.sessions.lock().await;
 
-    match sessions.get_mut(&session_id) {
+    match sessions
+        .get_mut(&session_id)
+        .filter(|entry| entry.owned_by(&caller))
+    {
         Some(entry) => {
             entry.last_accessed = Instant::now();
 
@@ -709,11 +2049,15 @@ async fn get_session_messages(
 // Compact session history
 async fn compact_session(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Path(session_id): Path<String>,
 ) -> Response {
     let mut sessions = state.sessions.lock().await;
 
-    match sessions.get_mut(&session_id) {
+    match sessions
+        .get_mut(&session_id)
+        .filter(|entry| entry.owned_by(&caller))
+    {
         Some(entry) => {
             entry.last_accessed = Instant::now();
 
@@ -733,14 +2077,37 @@ async fn compact_session(
     }
 }
 
+// Cancel the turn running on a session
+async fn cancel_session_turn(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Path(session_id): Path<String>,
+) -> Response {
+    // Doesn't take the sessions lock: the running turn holds it
+    if state.cancel_turn(&session_id, &caller) {
+        info!("Cancelled turn on session {}", session_id);
+        Json(json!({"cancelled": true, "session_id": session_id})).into_response()
+    } else {
+        AppError(
+            StatusCode::NOT_FOUND,
+            "No turn running on this session".to_string(),
+        )
+        .into_response()
+    }
+}
+
 // Clear session history
 async fn clear_session(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Path(session_id): Path<String>,
 ) -> Response {
     let mut sessions = state.sessions.lock().await;
 
-    match sessions.get_mut(&session_id) {
+    match sessions
+        .get_mut(&session_id)
+        .filter(|entry| entry.owned_by(&caller))
+    {
         Some(entry) => {
             entry.last_accessed = Instant::now();
             entry.agent.clear_session();
@@ -758,12 +2125,16 @@ struct SetModelRequest {
 
 async fn set_session_model(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Path(session_id): Path<String>,
     Json(request): Json<SetModelRequest>,
 ) -> Response {
     let mut sessions = state.sessions.lock().await;
 
-    match sessions.get_mut(&session_id) {
+    match sessions
+        .get_mut(&session_id)
+        .filter(|entry| entry.owned_by(&caller))
+    {
         Some(entry) => {
             entry.last_accessed = Instant::now();
 
@@ -780,6 +2151,26 @@ async fn set_session_model(
     }
 }
 
+/// `/api/chat` also serves Ollama clients; their requests carry a
+/// `messages` list instead of a `message`.
+async fn chat_or_ollama(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Json(body): Json<serde_json::Value>,
+) -> Response {
+    if body.get("messages").is_some() {
+        match serde_json::from_value(body) {
+            Ok(request) => crate::ollama_compat::chat(state, caller, request).await,
+            Err(e) => AppError(StatusCode::UNPROCESSABLE_ENTITY, e.to_string()).into_response(),
+        }
+    } else {
+        match serde_json::from_value(body) {
+            Ok(request) => chat(State(state), Extension(caller), Json(request)).await,
+            Err(e) => AppError(StatusCode::UNPROCESSABLE_ENTITY, e.to_string()).into_response(),
+        }
+    }
+}
+
 // Chat endpoint
 #[derive(Deserialize)]
 struct ChatRequest {
@@ -796,36 +2187,31 @@ struct ChatResponse {
     model: String,
 }
 
-async fn chat(State(state): State<Arc<AppState>>, Json(request): Json<ChatRequest>) -> Response {
+async fn chat(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Json(request): Json<ChatRequest>,
+) -> Response {
     // Get or create session
-    let session_id = match get_or_create_session(&state, request.session_id).await {
+    let session_id = match get_or_create_session(&state, request.session_id, &caller).await {
         Ok(id) => id,
         Err(e) => return e.into_response(),
     };
 
-    // Acquire in-process turn gate (waits for other turns to finish)
-    let _gate_permit = state.turn_gate.acquire().await;
-
-    // Acquire cross-process workspace lock (blocking, so use spawn_blocking)
-    let ws_lock_path = state.workspace_lock.clone();
-    let ws_guard = match tokio::task::spawn_blocking(move || ws_lock_path.acquire()).await {
-        Ok(Ok(guard)) => guard,
-        Ok(Err(e)) => {
-            return AppError(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to acquire workspace lock: {}", e),
-            )
-            .into_response();
-        }
+    // Wait for a free slot on the model before taking the turn gate
+    let model = turn_model(&state, &session_id, request.model.as_deref()).await;
+    let (_model_permit, queue) = match state.model_queue.acquire(&model).await {
+        Ok(admitted) => admitted,
         Err(e) => {
-            return AppError(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Lock task error: {}", e),
-            )
-            .into_response();
+            return AppError(StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response();
         }
     };
 
+    let locks = match lock_turn(&state).await {
+        Ok(locks) => locks,
+        Err(e) => return e.into_response(),
+    };
+
     // Get agent from session
     let mut sessions = state.sessions.lock().await;
     let entry = match sessions.get_mut(&session_id) {
@@ -845,12 +2231,21 @@ async fn chat(State(state): State<Arc<AppState>>, Json(request): Json<ChatReques
         return AppError(StatusCode::BAD_REQUEST, format!("Invalid model: {}", e)).into_response();
     }
 
+    let before = entry.agent.session_status();
+    let usage_before = entry.agent.usage().clone();
+    let turn = state.begin_turn(&session_id, &caller, &mut entry.agent);
     let result = entry.agent.chat(&request.message).await;
+    drop(turn);
+    state.record_usage(&caller, &usage_since(entry.agent.usage(), &usage_before));
 
     // Release workspace lock explicitly before returning
-    drop(ws_guard);
+    drop(locks);
 
-    match result {
+    let response = match result {
+        Err(e) if is_cancelled(&e) => {
+            entry.agent.rewind_session(&before);
+            AppError(StatusCode::CONFLICT, e.to_string()).into_response()
+        }
         Ok(response) => {
             entry.dirty = true;
             Json(ChatResponse {
@@ -861,29 +2256,115 @@ async fn chat(State(state): State<Arc<AppState>>, Json(request): Json<ChatReques
             .into_response()
         }
         Err(e) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    (queue.headers(), response).into_response()
+}
+
+/// Model a session's next turn runs with: `requested` if given, otherwise
+/// the session's current model.
+pub(crate) async fn turn_model(
+    state: &AppState,
+    session_id: &str,
+    requested: Option<&str>,
+) -> String {
+    if let Some(model) = requested {
+        return model.to_string();
     }
+    let sessions = state.sessions.lock().await;
+    sessions
+        .get(session_id)
+        .map(|entry| entry.agent.model().to_string())
+        .unwrap_or_else(|| state.config.agent.default_model.clone())
+}
+
+/// Locks held for the length of a session turn.
+pub(crate) struct TurnLocks {
+    _gate: TurnPermit,
+    _workspace: WorkspaceLockGuard,
+}
+
+/// Take the in-process turn gate (waiting in line for a slot), then the
+/// cross-process workspace lock.
+pub(crate) async fn lock_turn(state: &AppState) -> Result<TurnLocks, AppError> {
+    let gate = state
+        .turn_gate
+        .acquire("http")
+        .await
+        .map_err(|e| AppError(StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+
+    // The workspace lock blocks, so take it off the async runtime
+    let ws_lock_path = state.workspace_lock.clone();
+    let workspace = match tokio::task::spawn_blocking(move || ws_lock_path.acquire()).await {
+        Ok(Ok(guard)) => guard,
+        Ok(Err(e)) => {
+            return Err(AppError(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to acquire workspace lock: {}", e),
+            ));
+        }
+        Err(e) => {
+            return Err(AppError(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Lock task error: {}", e),
+            ));
+        }
+    };
+    Ok(TurnLocks {
+        _gate: gate,
+        _workspace: workspace,
+    })
 }
 
 // Streaming chat endpoint (SSE) with tool support
 async fn chat_stream(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Json(request): Json<ChatRequest>,
 ) -> Response {
     // Get or create session first (outside the stream)
-    let session_id = match get_or_create_session(&state, request.session_id).await {
+    let session_id = match get_or_create_session(&state, request.session_id, &caller).await {
         Ok(id) => id,
         Err(e) => return e.into_response(),
     };
 
     let state_clone = state.clone();
     let message = request.message.clone();
+    let model = turn_model(&state, &session_id, None).await;
 
     let stream = async_stream::stream! {
         // Send session_id first
         yield Ok::<Event, Infallible>(Event::default().data(json!({"type": "session", "session_id": session_id}).to_string()));
 
+        // Wait for a free slot on the model, reporting the queue position
+        // as SSE comments
+        let _model_permit = match state_clone.model_queue.admit(&model) {
+            Admission::Ready(permit) => permit,
+            Admission::Queued(ticket) => {
+                let mut progress = std::pin::pin!(ticket.progress());
+                loop {
+                    match progress.next().await {
+                        Some(QueueProgress::Position(position)) => {
+                            yield Ok(Event::default().comment(format!("queued position={}", position)));
+                        }
+                        Some(QueueProgress::Done(Ok(permit))) => break permit,
+                        Some(QueueProgress::Done(Err(e))) => {
+                            yield Ok(Event::default().data(json!({"error": e.to_string()}).to_string()));
+                            return;
+                        }
+                        None => return,
+                    }
+                }
+            }
+        };
+
         // Acquire in-process turn gate
-        let _gate_permit = state_clone.turn_gate.acquire().await;
+        let _gate_permit = match state_clone.turn_gate.acquire("http").await {
+            Ok(permit) => permit,
+            Err(e) => {
+                yield Ok(Event::default().data(json!({"error": e.to_string()}).to_string()));
+                return;
+            }
+        };
 
         // Acquire cross-process workspace lock
         let ws_lock = state_clone.workspace_lock.clone();
@@ -910,14 +2391,16 @@ async fn chat_stream(
 
         entry.last_accessed = Instant::now();
         entry.dirty = true;
+        let before = entry.agent.session_status();
+        let usage_before = entry.agent.usage().clone();
+        let _turn = state_clone.begin_turn(&session_id, &caller, &mut entry.agent);
 
         // Use streaming with tools
         match entry.agent.chat_stream_with_tools(&message, Vec::new()).await {
             Ok(event_stream) => {
-                use futures::StreamExt;
-
                 // Pin the stream to iterate over it
                 let mut pinned_stream = std::pin::pin!(event_stream);
+                let mut tool_started: HashMap<String, Instant> = HashMap::new();
 
                 while let Some(event) = pinned_stream.next().await {
                     match event {
@@ -927,16 +2410,27 @@ async fn chat_stream(
                         }
                         Ok(StreamEvent::ToolCallStart { name, id, arguments }) => {
                             let detail = extract_tool_detail(&name, &arguments);
-                            let data = json!({"type": "tool_start", "name": name, "id": id, "detail": detail});
+                            tool_started.insert(id.clone(), Instant::now());
+                            let data = json!({
+                                "type": "tool_start",
+                                "name": name,
+                                "id": id,
+                                "detail": detail,
+                                "arguments": arguments.chars().take(2000).collect::<String>()
+                            });
                             yield Ok(Event::default().data(data.to_string()));
                         }
                         Ok(StreamEvent::ToolCallEnd { name, id, output, warnings }) => {
+                            let duration_ms = tool_started
+                                .remove(&id)
+                                .map(|start| start.elapsed().as_millis() as u64);
                             let data = json!({
                                 "type": "tool_end",
                                 "name": name,
                                 "id": id,
                                 "output": output.chars().take(500).collect::<String>(),
-                                "warnings": warnings
+                                "warnings": warnings,
+                                "duration_ms": duration_ms
                             });
                             yield Ok(Event::default().data(data.to_string()));
                         }
@@ -955,6 +2449,10 @@ async fn chat_stream(
                 yield Ok(Event::default().data(json!({"error": e.to_string()}).to_string()));
             }
         }
+        if entry.agent.is_cancelled() {
+            entry.agent.rewind_session(&before);
+        }
+        state_clone.record_usage(&caller, &usage_since(entry.agent.usage(), &usage_before));
 
         yield Ok(Event::default().data("[DONE]"));
     };
@@ -962,11 +2460,55 @@ async fn chat_stream(
     Sse::new(stream).into_response()
 }
 
-// Memory search endpoint
 #[derive(Deserialize)]
-struct SearchQuery {
-    q: String,
-    limit: Option<usize>,
+struct EventsQuery {
+    /// Comma-separated event names or prefixes (`bridge.*`)
+    types: Option<String>,
+}
+
+// Daemon-wide event stream (SSE)
+async fn events_stream(Query(query): Query<EventsQuery>) -> Response {
+    use tokio::sync::broadcast::error::RecvError;
+
+    let filter: Vec<String> = query
+        .types
+        .iter()
+        .flat_map(|types| types.split(','))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+        .collect();
+    let mut events = localgpt_core::events::subscribe();
+
+    let stream = async_stream::stream! {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    if localgpt_core::events::matches_filter(&filter, event.name) {
+                        yield Ok::<Event, Infallible>(
+                            Event::default().event(event.name).data(event.payload.to_string()),
+                        );
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    let data = json!({"skipped": skipped});
+                    yield Ok(Event::default().event("lagged").data(data.to_string()));
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+// Memory search endpoint
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    limit: Option<usize>,
 }
 
 #[derive(Serialize)]
@@ -976,6 +2518,7 @@ struct SearchResult {
     line_end: i32,
     content: String,
     score: f64,
+    pinned: bool,
 }
 
 #[derive(Serialize)]
@@ -986,14 +2529,26 @@ struct SearchResponse {
 
 async fn memory_search(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Query(query): Query<SearchQuery>,
 ) -> Response {
-    match memory_search_inner(&state.memory, &query.q, query.limit) {
+    let memory = match caller_memory(&state, &caller) {
+        Ok(memory) => memory,
+        Err(e) => return e.into_response(),
+    };
+    match memory_search_inner(&memory, &query.q, query.limit) {
         Ok(response) => Json(response).into_response(),
         Err(e) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
+/// The caller's memory, for the `/api/memory` endpoints.
+fn caller_memory(state: &AppState, caller: &Caller) -> Result<MemoryManager, AppError> {
+    state
+        .caller_memory(caller)
+        .map_err(|e| AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
 fn memory_search_inner(
     memory: &MemoryManager,
     query: &str,
@@ -1010,6 +2565,7 @@ fn memory_search_inner(
             line_end: r.line_end,
             content: r.content,
             score: r.score,
+            pinned: r.pinned,
         })
         .collect();
 
@@ -1028,8 +2584,15 @@ struct StatsResponse {
     index_size_kb: u64,
 }
 
-async fn memory_stats(State(state): State<Arc<AppState>>) -> Response {
-    match memory_stats_inner(&state.memory) {
+async fn memory_stats(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+) -> Response {
+    let memory = match caller_memory(&state, &caller) {
+        Ok(memory) => memory,
+        Err(e) => return e.into_response(),
+    };
+    match memory_stats_inner(&memory) {
         Ok(response) => Json(response).into_response(),
         Err(e) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
@@ -1046,6 +2609,79 @@ fn memory_stats_inner(memory: &MemoryManager) -> Result<StatsResponse, anyhow::E
     })
 }
 
+// Memory explorer endpoints: indexed files, their chunks, and chunk marks
+#[derive(Serialize)]
+struct MemoryFileInfo {
+    path: String,
+    chunks: usize,
+}
+
+async fn memory_files(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+) -> Response {
+    let memory = match caller_memory(&state, &caller) {
+        Ok(memory) => memory,
+        Err(e) => return e.into_response(),
+    };
+    match memory.indexed_files() {
+        Ok(files) => {
+            let files: Vec<MemoryFileInfo> = files
+                .into_iter()
+                .map(|(path, chunks)| MemoryFileInfo { path, chunks })
+                .collect();
+            Json(json!({ "files": files })).into_response()
+        }
+        Err(e) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn memory_file(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Path(path): Path<String>,
+) -> Response {
+    let memory = match caller_memory(&state, &caller) {
+        Ok(memory) => memory,
+        Err(e) => return e.into_response(),
+    };
+    match memory.indexed_file(&path) {
+        Ok(Some(file)) => Json(json!({
+            "path": file.path,
+            "content": file.content,
+            "chunks": file.chunks,
+        }))
+        .into_response(),
+        Ok(None) => {
+            AppError(StatusCode::NOT_FOUND, "File is not indexed".to_string()).into_response()
+        }
+        Err(e) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct ChunkMarkRequest {
+    /// "pinned", "archived", or null to clear
+    mark: Option<localgpt_core::memory::ChunkMark>,
+}
+
+async fn mark_memory_chunk(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Path(chunk_id): Path<String>,
+    Json(request): Json<ChunkMarkRequest>,
+) -> Response {
+    let memory = match caller_memory(&state, &caller) {
+        Ok(memory) => memory,
+        Err(e) => return e.into_response(),
+    };
+    match memory.set_chunk_mark(&chunk_id, request.mark) {
+        Ok(true) => Json(json!({ "chunk_id": chunk_id, "mark": request.mark })).into_response(),
+        Ok(false) => AppError(StatusCode::NOT_FOUND, "Chunk not found".to_string()).into_response(),
+        Err(e) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
 // Memory reindex endpoint
 #[derive(Deserialize)]
 struct ReindexRequest {
@@ -1063,10 +2699,14 @@ struct ReindexResponse {
 
 async fn memory_reindex(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Json(request): Json<ReindexRequest>,
 ) -> Response {
     // Run reindex in blocking task since it uses sqlite
-    let memory = state.memory.clone();
+    let memory = match caller_memory(&state, &caller) {
+        Ok(memory) => memory,
+        Err(e) => return e.into_response(),
+    };
     let force = request.force;
 
     match tokio::task::spawn_blocking(move || memory_reindex_inner(&memory, force)).await {
@@ -1094,6 +2734,78 @@ fn memory_reindex_inner(
     })
 }
 
+// Memory ingest endpoint - add a document to memory. Takes either JSON
+// (`{"text": ..., "name": ...}`) or a raw text/Markdown file body with an
+// optional `?name=` query parameter.
+#[derive(Deserialize)]
+struct IngestRequest {
+    text: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IngestQuery {
+    name: Option<String>,
+}
+
+#[derive(Serialize)]
+struct IngestResponse {
+    file: String,
+    chunks: usize,
+}
+
+async fn memory_ingest(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Query(query): Query<IngestQuery>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let (text, name) = if is_json {
+        match serde_json::from_slice::<IngestRequest>(&body) {
+            Ok(request) => (request.text, request.name.or(query.name)),
+            Err(e) => return AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        }
+    } else {
+        match String::from_utf8(body.to_vec()) {
+            Ok(text) => (text, query.name),
+            Err(_) => {
+                return AppError(
+                    StatusCode::BAD_REQUEST,
+                    "Body must be UTF-8 text".to_string(),
+                )
+                .into_response();
+            }
+        }
+    };
+    if text.trim().is_empty() {
+        return AppError(StatusCode::BAD_REQUEST, "Nothing to ingest".to_string()).into_response();
+    }
+
+    // Indexing uses sqlite, so run it off the async runtime
+    let memory = match caller_memory(&state, &caller) {
+        Ok(memory) => memory,
+        Err(e) => return e.into_response(),
+    };
+    match tokio::task::spawn_blocking(move || memory.ingest(name.as_deref(), &text)).await {
+        Ok(Ok(ingested)) => Json(IngestResponse {
+            file: ingested.file,
+            chunks: ingested.chunks,
+        })
+        .into_response(),
+        Ok(Err(e)) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        Err(e) => AppError(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task error: {}", e),
+        )
+        .into_response(),
+    }
+}
+
 // Config endpoint - show current configuration (safe subset)
 #[derive(Serialize)]
 struct ConfigResponse {
@@ -1173,6 +2885,10 @@ struct HeartbeatEventInfo {
 }
 
 async fn heartbeat_status(State(state): State<Arc<AppState>>) -> Json<HeartbeatStatusResponse> {
+    Json(heartbeat_status_inner(&state.config))
+}
+
+fn heartbeat_status_inner(config: &Config) -> HeartbeatStatusResponse {
     let last_event = get_last_heartbeat_event().map(|event| {
         let now_ms = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1199,17 +2915,271 @@ async fn heartbeat_status(State(state): State<Arc<AppState>>) -> Json<HeartbeatS
         }
     });
 
-    Json(HeartbeatStatusResponse {
-        enabled: state.config.heartbeat.enabled,
-        interval: state.config.heartbeat.interval.clone(),
+    HeartbeatStatusResponse {
+        enabled: config.heartbeat.enabled,
+        interval: config.heartbeat.interval.clone(),
         last_event,
-    })
+    }
+}
+
+// Cron job endpoints - runtime jobs are persisted to the cron jobs file and
+// picked up by the daemon's scheduler on its next tick
+fn cron_store(state: &AppState) -> Result<CronJobStore, AppError> {
+    CronJobStore::from_config(&state.config)
+        .map_err(|e| AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn list_cron_jobs(State(state): State<Arc<AppState>>) -> Response {
+    match cron_store(&state).and_then(|store| {
+        store
+            .list()
+            .and_then(|jobs| store.archived().map(|archived| (jobs, archived)))
+            .map_err(|e| AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+    }) {
+        Ok((jobs, archived)) => Json(json!({ "jobs": jobs, "archived": archived })).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Runs included with a single job's details.
+const CRON_JOB_RECENT_RUNS: usize = 20;
+
+async fn get_cron_job(State(state): State<Arc<AppState>>, Path(name): Path<String>) -> Response {
+    let store = match cron_store(&state) {
+        Ok(store) => store,
+        Err(e) => return e.into_response(),
+    };
+    let job = match store.list() {
+        Ok(jobs) => jobs.into_iter().find(|info| info.job.name == name),
+        Err(e) => {
+            return AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+    };
+    let Some(job) = job else {
+        return AppError(StatusCode::NOT_FOUND, format!("No cron job '{}'", name)).into_response();
+    };
+    match localgpt_core::cron::history::read_cron_history(
+        &state.config.paths.state_dir,
+        Some(&name),
+        Some(CRON_JOB_RECENT_RUNS),
+    ) {
+        Ok(runs) => Json(json!({ "job": job, "runs": runs })).into_response(),
+        Err(e) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn add_cron_job(State(state): State<Arc<AppState>>, Json(job): Json<CronJob>) -> Response {
+    let store = match cron_store(&state) {
+        Ok(store) => store,
+        Err(e) => return e.into_response(),
+    };
+    let name = job.name.clone();
+    match store.add(job) {
+        Ok(()) => {
+            info!("Added cron job: {}", name);
+            (
+                StatusCode::CREATED,
+                Json(json!({ "added": true, "name": name })),
+            )
+                .into_response()
+        }
+        Err(e) => AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+async fn update_cron_job(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(update): Json<CronJobUpdate>,
+) -> Response {
+    let store = match cron_store(&state) {
+        Ok(store) => store,
+        Err(e) => return e.into_response(),
+    };
+    match store.update(&name, update) {
+        Ok(job) => {
+            info!("Updated cron job: {}", name);
+            Json(job).into_response()
+        }
+        Err(e) => AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+async fn remove_cron_job(State(state): State<Arc<AppState>>, Path(name): Path<String>) -> Response {
+    let store = match cron_store(&state) {
+        Ok(store) => store,
+        Err(e) => return e.into_response(),
+    };
+    match store.remove(&name) {
+        Ok(_) => {
+            info!("Removed cron job: {}", name);
+            Json(json!({ "deleted": true, "name": name })).into_response()
+        }
+        Err(e) => AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+async fn pause_cron_job(State(state): State<Arc<AppState>>, Path(name): Path<String>) -> Response {
+    let store = match cron_store(&state) {
+        Ok(store) => store,
+        Err(e) => return e.into_response(),
+    };
+    match store.pause(&name) {
+        Ok(()) => {
+            info!("Paused cron job: {}", name);
+            Json(json!({ "paused": true, "name": name })).into_response()
+        }
+        Err(e) => AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+async fn resume_cron_job(State(state): State<Arc<AppState>>, Path(name): Path<String>) -> Response {
+    let store = match cron_store(&state) {
+        Ok(store) => store,
+        Err(e) => return e.into_response(),
+    };
+    match store.resume(&name) {
+        Ok(()) => {
+            info!("Resumed cron job: {}", name);
+            Json(json!({ "paused": false, "name": name })).into_response()
+        }
+        Err(e) => AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+async fn enable_cron_job(State(state): State<Arc<AppState>>, Path(name): Path<String>) -> Response {
+    set_cron_job_enabled(&state, name, true)
+}
+
+async fn disable_cron_job(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Response {
+    set_cron_job_enabled(&state, name, false)
+}
+
+fn set_cron_job_enabled(state: &AppState, name: String, enabled: bool) -> Response {
+    let store = match cron_store(state) {
+        Ok(store) => store,
+        Err(e) => return e.into_response(),
+    };
+    match store.set_enabled(&name, enabled) {
+        Ok(_) => {
+            info!(
+                "{} cron job: {}",
+                if enabled { "Enabled" } else { "Disabled" },
+                name
+            );
+            Json(json!({ "enabled": enabled, "name": name })).into_response()
+        }
+        Err(e) => AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+async fn run_cron_job(State(state): State<Arc<AppState>>, Path(name): Path<String>) -> Response {
+    let store = match cron_store(&state) {
+        Ok(store) => store,
+        Err(e) => return e.into_response(),
+    };
+    match store.request_run(&name) {
+        Ok(()) => {
+            info!("Requested cron job run: {}", name);
+            (
+                StatusCode::ACCEPTED,
+                Json(json!({ "queued": true, "name": name })),
+            )
+                .into_response()
+        }
+        Err(e) => AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+/// Maximum characters of a webhook body passed to the job as `{{event}}`.
+const MAX_WEBHOOK_EVENT_CHARS: usize = 10_000;
+
+/// Queue a run of a job with a webhook trigger. The request body becomes the
+/// job's `{{event}}`. Mounted outside `auth_middleware`: `X-Webhook-Secret`
+/// must match the trigger's secret, and is the only authentication.
+async fn cron_webhook(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    let store = match cron_store(&state) {
+        Ok(store) => store,
+        Err(e) => return e.into_response(),
+    };
+    let trigger = match store.jobs() {
+        Ok(jobs) => jobs
+            .into_iter()
+            .find(|j| j.name == name)
+            .and_then(|j| j.trigger)
+            .filter(|t| t.kind() == "webhook"),
+        Err(e) => {
+            return AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+    };
+    let Some(trigger) = trigger else {
+        return AppError(
+            StatusCode::NOT_FOUND,
+            format!("No cron job '{}' with a webhook trigger", name),
+        )
+        .into_response();
+    };
+    let secret = headers
+        .get("x-webhook-secret")
+        .and_then(|v| v.to_str().ok());
+    if !trigger.accepts_webhook(secret) {
+        return AppError(StatusCode::FORBIDDEN, "Invalid webhook secret".to_string())
+            .into_response();
+    }
+
+    let detail: String = body.chars().take(MAX_WEBHOOK_EVENT_CHARS).collect();
+    match store.push_webhook_event(&name, secret, detail) {
+        Ok(()) => {
+            info!("Queued webhook event for cron job: {}", name);
+            (
+                StatusCode::ACCEPTED,
+                Json(json!({ "queued": true, "name": name })),
+            )
+                .into_response()
+        }
+        Err(e) => AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct CronHistoryQuery {
+    job: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct CronHistoryResponse {
+    runs: Vec<localgpt_core::cron::history::CronRunEntry>,
+}
+
+async fn get_cron_history(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CronHistoryQuery>,
+) -> Response {
+    let limit = query.limit.unwrap_or(50).min(1000);
+    match localgpt_core::cron::history::read_cron_history(
+        &state.config.paths.state_dir,
+        query.job.as_deref(),
+        Some(limit),
+    ) {
+        Ok(runs) => Json(CronHistoryResponse { runs }).into_response(),
+        Err(e) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
 }
 
 // Saved sessions endpoint - list sessions from file store
 #[derive(Serialize)]
 struct SavedSessionInfo {
     id: String,
+    title: Option<String>,
     message_count: usize,
     created_at: String,
 }
@@ -1219,15 +3189,16 @@ struct SavedSessionsResponse {
     sessions: Vec<SavedSessionInfo>,
 }
 
-async fn list_saved_sessions(State(_state): State<Arc<AppState>>) -> Response {
+async fn list_saved_sessions(Extension(caller): Extension<Caller>) -> Response {
     use localgpt_core::agent::list_sessions_for_agent;
 
-    match list_sessions_for_agent(HTTP_AGENT_ID) {
+    match list_sessions_for_agent(&caller.agent_id()) {
         Ok(sessions) => {
             let session_list: Vec<SavedSessionInfo> = sessions
                 .into_iter()
                 .map(|s| SavedSessionInfo {
                     id: s.id,
+                    title: s.title,
                     message_count: s.message_count,
                     created_at: s.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
                 })
@@ -1242,6 +3213,72 @@ async fn list_saved_sessions(State(_state): State<Arc<AppState>>) -> Response {
     }
 }
 
+/// Longest title accepted by the rename endpoint, in characters.
+const MAX_SESSION_TITLE_CHARS: usize = 200;
+
+#[derive(Deserialize)]
+struct RenameSessionRequest {
+    /// New title; empty or null clears it
+    title: Option<String>,
+}
+
+// Rename an active or saved session
+async fn rename_saved_session(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Path(session_id): Path<String>,
+    Json(request): Json<RenameSessionRequest>,
+) -> Response {
+    use localgpt_core::agent::rename_session_for_agent;
+
+    if !valid_session_id(&session_id) {
+        return AppError(StatusCode::BAD_REQUEST, "Invalid session id".to_string()).into_response();
+    }
+    let title = request
+        .title
+        .map(|t| t.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|t| !t.is_empty());
+    if title
+        .as_ref()
+        .is_some_and(|t| t.chars().count() > MAX_SESSION_TITLE_CHARS)
+    {
+        return AppError(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Title is longer than {} characters",
+                MAX_SESSION_TITLE_CHARS
+            ),
+        )
+        .into_response();
+    }
+
+    // An active session would overwrite the file on its next save, so
+    // rename it in memory and save it now
+    let mut sessions = state.sessions.lock().await;
+    if let Some(entry) = sessions.iter_mut().find_map(|(id, entry)| {
+        (entry.owned_by(&caller)
+            && (*id == session_id || entry.agent.session_status().id == session_id))
+            .then_some(entry)
+    }) {
+        entry.last_accessed = Instant::now();
+        entry.agent.set_session_title(title.clone());
+        if let Err(e) = entry.agent.save_session_for_agent(&entry.owner).await {
+            return AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+        entry.dirty = false;
+        return Json(json!({"session_id": session_id, "title": title})).into_response();
+    }
+    drop(sessions);
+
+    match rename_session_for_agent(&caller.agent_id(), &session_id, title.as_deref()) {
+        Ok(true) => Json(json!({"session_id": session_id, "title": title})).into_response(),
+        Ok(false) => {
+            AppError(StatusCode::NOT_FOUND, "Session not found".to_string()).into_response()
+        }
+        Err(e) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
 // Get saved session detail - read and parse JSONL session file
 #[derive(Serialize)]
 struct SavedSessionMessage {
@@ -1259,12 +3296,23 @@ struct SavedSessionDetail {
     messages: Vec<SavedSessionMessage>,
 }
 
-async fn get_saved_session(Path(session_id): Path<String>) -> Response {
+async fn get_saved_session(
+    Extension(caller): Extension<Caller>,
+    Path(session_id): Path<String>,
+) -> Response {
+    saved_session_detail(&caller.agent_id(), &session_id)
+}
+
+/// Parse the saved session `session_id` of `agent_id`.
+fn saved_session_detail(agent_id: &str, session_id: &str) -> Response {
     use localgpt_core::agent::get_sessions_dir_for_agent;
     use std::fs::File;
     use std::io::{BufRead, BufReader};
 
-    let sessions_dir = match get_sessions_dir_for_agent(HTTP_AGENT_ID) {
+    if !valid_session_id(session_id) {
+        return AppError(StatusCode::BAD_REQUEST, "Invalid session id".to_string()).into_response();
+    }
+    let sessions_dir = match get_sessions_dir_for_agent(agent_id) {
         Ok(dir) => dir,
         Err(e) => {
             return AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
@@ -1353,7 +3401,7 @@ async fn get_saved_session(Path(session_id): Path<String>) -> Response {
     }
 
     Json(SavedSessionDetail {
-        session_id,
+        session_id: session_id.to_string(),
         created_at,
         messages,
     })
@@ -1435,228 +3483,207 @@ async fn get_daemon_logs(Query(query): Query<LogsQuery>) -> Response {
     .into_response()
 }
 
-// WebSocket handler
-async fn websocket_handler(
-    ws: WebSocketUpgrade,
+// Tool usage audit endpoint - query the tool audit log
+#[derive(Serialize)]
+struct ToolAuditResponse {
+    entries: Vec<localgpt_core::security::ToolAuditEntry>,
+}
+
+async fn get_tool_audit(
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(|socket| handle_websocket(socket, state))
+    Query(mut query): Query<localgpt_core::security::ToolAuditQuery>,
+) -> Response {
+    query.limit = Some(query.limit.unwrap_or(200).min(1000));
+
+    match localgpt_core::security::query_tool_audit_log(&state.config.paths.state_dir, &query) {
+        Ok(entries) => Json(ToolAuditResponse { entries }).into_response(),
+        Err(e) => AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Leaves protected writes queued for the web UI, which learns of them from
+/// `approval.requested` events and answers through `/api/approvals`.
+struct WebWriteApprover;
+
+impl localgpt_core::security::WriteApprover for WebWriteApprover {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    fn ask(&self, _write: &localgpt_core::security::PendingWrite) -> Option<bool> {
+        None
+    }
+}
+
+// Protected writes waiting for the user's approval
+async fn list_write_approvals() -> Json<Vec<localgpt_core::security::PendingWrite>> {
+    Json(localgpt_core::security::pending_writes())
 }
 
-/// WebSocket message types
 #[derive(Deserialize)]
-#[serde(tag = "type")]
-enum WsIncoming {
-    /// Start or resume a session
-    #[serde(rename = "session")]
-    Session { session_id: Option<String> },
-    /// Chat message (uses tool loop, returns complete response)
-    /// For streaming, use the SSE endpoint at /api/chat/stream
-    #[serde(rename = "chat")]
-    Chat { message: String },
-    /// Ping for keepalive
-    #[serde(rename = "ping")]
-    Ping,
+struct WriteApprovalAnswer {
+    approved: bool,
+}
+
+async fn answer_write_approval(
+    Path(id): Path<String>,
+    Json(answer): Json<WriteApprovalAnswer>,
+) -> Response {
+    match localgpt_core::security::resolve_write(&id, answer.approved, "http") {
+        Ok(write) => Json(json!({
+            "id": write.id,
+            "approved": answer.approved,
+            "path": write.path,
+        }))
+        .into_response(),
+        Err(e) => AppError(StatusCode::NOT_FOUND, e.to_string()).into_response(),
+    }
 }
 
 #[derive(Serialize)]
-#[serde(tag = "type")]
-#[allow(dead_code)] // ToolStart/ToolEnd reserved for streaming with tools
-enum WsOutgoing {
-    /// Connection established
-    #[serde(rename = "connected")]
-    Connected { session_id: String },
-    /// Text content chunk
-    #[serde(rename = "content")]
-    Content { delta: String },
-    /// Tool call started
-    #[serde(rename = "tool_start")]
-    ToolStart { name: String, id: String },
-    /// Tool call completed
-    #[serde(rename = "tool_end")]
-    ToolEnd {
-        name: String,
-        id: String,
-        output: String,
-    },
-    /// Message complete
-    #[serde(rename = "done")]
-    Done,
-    /// Pong response
-    #[serde(rename = "pong")]
-    Pong,
-    /// Error
-    #[serde(rename = "error")]
-    Error { message: String },
-}
-
-async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
-    let (mut sender, mut receiver) = socket.split();
-
-    debug!("WebSocket client connected");
-
-    // Track current session for this connection
-    let mut current_session_id: Option<String> = None;
-
-    // Process incoming messages
-    while let Some(msg) = receiver.next().await {
-        match msg {
-            Ok(WsMessage::Text(text)) => {
-                // Parse incoming message
-                match serde_json::from_str::<WsIncoming>(&text) {
-                    Ok(WsIncoming::Session { session_id }) => {
-                        // Create or resume session
-                        match get_or_create_session(&state, session_id).await {
-                            Ok(id) => {
-                                current_session_id = Some(id.clone());
-                                let connected = WsOutgoing::Connected { session_id: id };
-                                if let Ok(json) = serde_json::to_string(&connected) {
-                                    let _ = sender.send(WsMessage::Text(json.into())).await;
-                                }
-                            }
-                            Err(e) => {
-                                let error = WsOutgoing::Error {
-                                    message: format!("Failed to create session: {}", e.1),
-                                };
-                                if let Ok(json) = serde_json::to_string(&error) {
-                                    let _ = sender.send(WsMessage::Text(json.into())).await;
-                                }
-                            }
-                        }
-                    }
-                    Ok(WsIncoming::Chat { message }) => {
-                        // Ensure we have a session
-                        let session_id = match &current_session_id {
-                            Some(id) => id.clone(),
-                            None => {
-                                // Auto-create session if none exists
-                                match get_or_create_session(&state, None).await {
-                                    Ok(id) => {
-                                        current_session_id = Some(id.clone());
-                                        // Notify client of new session
-                                        let connected = WsOutgoing::Connected {
-                                            session_id: id.clone(),
-                                        };
-                                        if let Ok(json) = serde_json::to_string(&connected) {
-                                            let _ = sender.send(WsMessage::Text(json.into())).await;
-                                        }
-                                        id
-                                    }
-                                    Err(e) => {
-                                        let error = WsOutgoing::Error {
-                                            message: format!("Failed to create session: {}", e.1),
-                                        };
-                                        if let Ok(json) = serde_json::to_string(&error) {
-                                            let _ = sender.send(WsMessage::Text(json.into())).await;
-                                        }
-                                        continue;
-                                    }
-                                }
-                            }
-                        };
-
-                        debug!("WebSocket chat [{}]: {}", session_id, message);
-
-                        // Acquire in-process turn gate
-                        let _gate_permit = state.turn_gate.acquire().await;
-
-                        // Acquire cross-process workspace lock
-                        let ws_lock = state.workspace_lock.clone();
-                        let _ws_guard =
-                            match tokio::task::spawn_blocking(move || ws_lock.acquire()).await {
-                                Ok(Ok(guard)) => guard,
-                                Ok(Err(e)) => {
-                                    let error = WsOutgoing::Error {
-                                        message: format!("Workspace lock error: {}", e),
-                                    };
-                                    if let Ok(json) = serde_json::to_string(&error) {
-                                        let _ = sender.send(WsMessage::Text(json.into())).await;
-                                    }
-                                    continue;
-                                }
-                                Err(e) => {
-                                    let error = WsOutgoing::Error {
-                                        message: format!("Lock task error: {}", e),
-                                    };
-                                    if let Ok(json) = serde_json::to_string(&error) {
-                                        let _ = sender.send(WsMessage::Text(json.into())).await;
-                                    }
-                                    continue;
-                                }
-                            };
-
-                        // Process chat
-                        let mut sessions = state.sessions.lock().await;
-                        let entry = match sessions.get_mut(&session_id) {
-                            Some(e) => e,
-                            None => {
-                                let error = WsOutgoing::Error {
-                                    message: "Session not found".to_string(),
-                                };
-                                if let Ok(json) = serde_json::to_string(&error) {
-                                    let _ = sender.send(WsMessage::Text(json.into())).await;
-                                }
-                                current_session_id = None;
-                                continue;
-                            }
-                        };
-
-                        entry.last_accessed = Instant::now();
-
-                        match entry.agent.chat(&message).await {
-                            Ok(response) => {
-                                // Send response as content
-                                let content = WsOutgoing::Content { delta: response };
-                                if let Ok(json) = serde_json::to_string(&content) {
-                                    let _ = sender.send(WsMessage::Text(json.into())).await;
-                                }
-
-                                // Send done
-                                let done = WsOutgoing::Done;
-                                if let Ok(json) = serde_json::to_string(&done) {
-                                    let _ = sender.send(WsMessage::Text(json.into())).await;
-                                }
-                            }
-                            Err(e) => {
-                                let error = WsOutgoing::Error {
-                                    message: e.to_string(),
-                                };
-                                if let Ok(json) = serde_json::to_string(&error) {
-                                    let _ = sender.send(WsMessage::Text(json.into())).await;
-                                }
-                            }
-                        }
-                    }
-                    Ok(WsIncoming::Ping) => {
-                        let pong = WsOutgoing::Pong;
-                        if let Ok(json) = serde_json::to_string(&pong) {
-                            let _ = sender.send(WsMessage::Text(json.into())).await;
-                        }
-                    }
-                    Err(e) => {
-                        let error = WsOutgoing::Error {
-                            message: format!("Invalid message format: {}", e),
-                        };
-                        if let Ok(json) = serde_json::to_string(&error) {
-                            let _ = sender.send(WsMessage::Text(json.into())).await;
-                        }
-                    }
-                }
-            }
-            Ok(WsMessage::Ping(data)) => {
-                let _ = sender.send(WsMessage::Pong(data)).await;
-            }
-            Ok(WsMessage::Close(_)) => {
-                debug!("WebSocket client disconnected");
-                break;
-            }
-            Err(e) => {
-                debug!("WebSocket error: {}", e);
-                break;
-            }
-            _ => {}
+struct UsageResponse {
+    users: Vec<UsageEntry>,
+}
+
+#[derive(Serialize)]
+struct UsageEntry {
+    /// `null` for the default namespace
+    user: Option<String>,
+    #[serde(flatten)]
+    usage: UserUsage,
+}
+
+// Token usage per user since the server started. Admins see every user.
+async fn get_usage(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+) -> Json<UsageResponse> {
+    let totals = state.usage.lock().unwrap_or_else(|e| e.into_inner());
+    let mut users: Vec<UsageEntry> = totals
+        .iter()
+        .filter(|(user, _)| caller.admin || **user == caller.user)
+        .map(|(user, usage)| UsageEntry {
+            user: user.clone(),
+            usage: usage.clone(),
+        })
+        .collect();
+    users.sort_by(|a, b| a.user.cmp(&b.user));
+    Json(UsageResponse { users })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use localgpt_core::config::OllamaConfig;
+    use localgpt_core::paths::Paths;
+
+    fn test_state(root: &std::path::Path) -> Arc<AppState> {
+        let mut config = Config {
+            paths: Paths::from_root(root),
+            ..Default::default()
+        };
+        config.memory.embedding_provider = "none".to_string();
+        config.agent.default_model = "ollama/llama3".to_string();
+        config.providers.ollama = Some(OllamaConfig {
+            endpoint: "http://127.0.0.1:9".to_string(),
+            model: "llama3".to_string(),
+        });
+        let memory =
+            MemoryManager::new_with_full_config(&config.memory, Some(&config), "main").unwrap();
+        let workspace_lock = WorkspaceLock::at(root.join("workspace.lock")).unwrap();
+        let turn_gate = TurnGate::from_config(&config.server.turns).unwrap();
+        Arc::new(
+            AppState::new(
+                config,
+                memory,
+                turn_gate,
+                workspace_lock,
+                crate::security::BridgeManager::new(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn user(name: &str) -> Caller {
+        Caller {
+            user: Some(name.to_string()),
+            admin: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn conversations_are_saved_and_resumed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = test_state(tmp.path());
+        let alice = user("alice");
+
+        let key = conversation_session(&state, "notes-bot", &alice)
+            .await
+            .unwrap();
+        {
+            let mut sessions = state.sessions.lock().await;
+            let entry = sessions.get_mut(&key).unwrap();
+            assert_eq!(entry.agent.session_status().id, "notes-bot");
+            entry.agent.add_user_message("I prefer metric units.");
         }
+
+        // Another user gets their own conversation under the same id
+        let bob_key = conversation_session(&state, "notes-bot", &user("bob"))
+            .await
+            .unwrap();
+        assert_ne!(key, bob_key);
+
+        // Saved under the caller's sessions, then expired from memory
+        save_dirty_sessions(&state).await;
+        let file = state
+            .config
+            .paths
+            .sessions_dir(&alice.agent_id())
+            .join("notes-bot.jsonl");
+        assert!(file.exists());
+        state.sessions.lock().await.clear();
+
+        let resumed = conversation_session(&state, "notes-bot", &alice)
+            .await
+            .unwrap();
+        assert_eq!(resumed, key);
+        let sessions = state.sessions.lock().await;
+        let entry = sessions.get(&resumed).unwrap();
+        assert!(entry.agent.export_markdown().contains("metric units"));
+        assert!(
+            !sessions[&bob_key]
+                .agent
+                .export_markdown()
+                .contains("metric units")
+        );
     }
 
-    debug!("WebSocket connection closed");
+    #[tokio::test]
+    async fn http_agents_cannot_schedule_tasks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = test_state(tmp.path());
+        let agent_config = AgentConfig {
+            model: state.config.agent.default_model.clone(),
+            context_window: state.config.agent.context_window,
+            reserve_tokens: state.config.agent.reserve_tokens,
+        };
+        for caller in [Caller::default(), user("alice")] {
+            let agent = state
+                .caller_agent(&caller, agent_config.clone())
+                .await
+                .unwrap();
+            assert!(!agent.tool_names().contains(&"schedule_task"));
+            assert!(agent.tool_names().contains(&"memory_search"));
+        }
+    }
+
+    #[tokio::test]
+    async fn conversation_ids_are_checked() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = test_state(tmp.path());
+        let err = conversation_session(&state, "../escape", &Caller::default())
+            .await
+            .unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    }
 }
@@ -1,6 +1,10 @@
 #[cfg(not(target_arch = "wasm32"))]
 mod http;
 #[cfg(not(target_arch = "wasm32"))]
+mod model_queue;
+#[cfg(not(target_arch = "wasm32"))]
+mod ollama_compat;
+#[cfg(not(target_arch = "wasm32"))]
 mod openai_compat;
 #[cfg(not(target_arch = "wasm32"))]
 mod rate_limiter;
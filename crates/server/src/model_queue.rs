@@ -0,0 +1,238 @@
+//! Per-model concurrency limits. Generations over a model's (or its
+//! provider's) limit wait in a first-come, first-served queue until a slot
+//! frees up or the queue timeout passes.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use futures::Stream;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use localgpt_core::config::{ModelConcurrencyConfig, parse_duration};
+
+/// How often a queued request's position is checked for changes
+const POSITION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Slots and waiters shared by everything under one limit key.
+struct Group {
+    key: String,
+    semaphore: Arc<Semaphore>,
+    /// Tickets waiting for a slot, oldest first
+    waiting: std::sync::Mutex<VecDeque<u64>>,
+    next_ticket: AtomicU64,
+}
+
+impl Group {
+    fn leave(&self, ticket: u64) {
+        let mut waiting = self.waiting.lock().unwrap_or_else(|e| e.into_inner());
+        waiting.retain(|t| *t != ticket);
+    }
+}
+
+/// Limits generations per model or provider.
+pub struct ModelQueue {
+    limits: HashMap<String, usize>,
+    default_limit: usize,
+    timeout: Duration,
+    groups: std::sync::Mutex<HashMap<String, Arc<Group>>>,
+}
+
+impl ModelQueue {
+    pub fn new(config: &ModelConcurrencyConfig) -> Result<Self> {
+        let timeout = parse_duration(&config.queue_timeout)
+            .map_err(|e| anyhow!("Invalid server.model_concurrency.queue_timeout: {}", e))?;
+        Ok(Self {
+            limits: config.limits.clone(),
+            default_limit: config.default_limit,
+            timeout,
+            groups: std::sync::Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The limit key and size for `model`: the model itself if listed, then
+    /// its provider, then the default. `None` if unlimited.
+    fn limit_for(&self, model: &str) -> Option<(String, usize)> {
+        let provider = model.split_once('/').map(|(provider, _)| provider);
+        let (key, limit) = if let Some(limit) = self.limits.get(model) {
+            (model, *limit)
+        } else if let Some(provider) = provider
+            && let Some(limit) = self.limits.get(provider)
+        {
+            (provider, *limit)
+        } else {
+            (model, self.default_limit)
+        };
+        (limit > 0).then(|| (key.to_string(), limit))
+    }
+
+    /// Ask for a slot for a generation with `model`. Hold the returned
+    /// permit until the generation finishes.
+    pub fn admit(&self, model: &str) -> Admission {
+        let Some((key, limit)) = self.limit_for(model) else {
+            return Admission::Ready(ModelPermit { _permit: None });
+        };
+        let group = {
+            let mut groups = self.groups.lock().unwrap_or_else(|e| e.into_inner());
+            groups
+                .entry(key.clone())
+                .or_insert_with(|| {
+                    Arc::new(Group {
+                        key,
+                        semaphore: Arc::new(Semaphore::new(limit)),
+                        waiting: std::sync::Mutex::new(VecDeque::new()),
+                        next_ticket: AtomicU64::new(0),
+                    })
+                })
+                .clone()
+        };
+
+        // Slots are only free when nobody is queued, so this can't jump the line
+        if let Ok(permit) = group.semaphore.clone().try_acquire_owned() {
+            return Admission::Ready(ModelPermit {
+                _permit: Some(permit),
+            });
+        }
+        let ticket = group.next_ticket.fetch_add(1, Ordering::Relaxed);
+        group
+            .waiting
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_back(ticket);
+        Admission::Queued(QueueTicket {
+            group,
+            ticket,
+            timeout: self.timeout,
+            queued_at: Instant::now(),
+        })
+    }
+
+    /// Wait for a slot, for responses that report the wait in headers.
+    pub async fn acquire(&self, model: &str) -> Result<(ModelPermit, QueueReport)> {
+        match self.admit(model) {
+            Admission::Ready(permit) => Ok((permit, QueueReport::default())),
+            Admission::Queued(ticket) => {
+                let position = ticket.position();
+                let permit = ticket.wait().await?;
+                let report = QueueReport {
+                    position,
+                    waited: ticket.waited(),
+                };
+                Ok((permit, report))
+            }
+        }
+    }
+}
+
+/// How long a request queued, for the `x-queue-*` response headers.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QueueReport {
+    /// Position on arrival; 0 if a slot was free
+    pub position: usize,
+    pub waited: Duration,
+}
+
+impl QueueReport {
+    pub fn headers(&self) -> [(&'static str, String); 2] {
+        [
+            ("x-queue-position", self.position.to_string()),
+            ("x-queue-wait-ms", self.waited.as_millis().to_string()),
+        ]
+    }
+}
+
+/// Result of [`ModelQueue::admit`].
+pub enum Admission {
+    Ready(ModelPermit),
+    Queued(QueueTicket),
+}
+
+/// A generation slot, released on drop.
+pub struct ModelPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+/// Progress of a [`QueueTicket`] through its queue.
+pub enum QueueProgress {
+    /// 1-based position in the queue, sent whenever it changes
+    Position(usize),
+    /// Always the last item: the slot, or why none came
+    Done(Result<ModelPermit>),
+}
+
+/// A place in a model's queue. Dropping it gives up the place.
+pub struct QueueTicket {
+    group: Arc<Group>,
+    ticket: u64,
+    timeout: Duration,
+    queued_at: Instant,
+}
+
+impl QueueTicket {
+    /// 1-based position among the requests waiting for this limit.
+    pub fn position(&self) -> usize {
+        let waiting = self.group.waiting.lock().unwrap_or_else(|e| e.into_inner());
+        waiting
+            .iter()
+            .position(|t| *t == self.ticket)
+            .map_or(0, |i| i + 1)
+    }
+
+    /// Time spent in the queue so far.
+    pub fn waited(&self) -> Duration {
+        self.queued_at.elapsed()
+    }
+
+    /// Wait for a slot, failing once the queue timeout has passed.
+    pub async fn wait(&self) -> Result<ModelPermit> {
+        let remaining = self.timeout.saturating_sub(self.waited());
+        let acquired =
+            tokio::time::timeout(remaining, self.group.semaphore.clone().acquire_owned()).await;
+        self.group.leave(self.ticket);
+        match acquired {
+            Ok(Ok(permit)) => Ok(ModelPermit {
+                _permit: Some(permit),
+            }),
+            Ok(Err(_)) => Err(anyhow!("Queue for '{}' was closed", self.group.key)),
+            Err(_) => Err(anyhow!(
+                "Timed out after {}s waiting for a free '{}' slot",
+                self.timeout.as_secs(),
+                self.group.key
+            )),
+        }
+    }
+
+    /// Wait for a slot, reporting the queue position as it changes, for
+    /// responses that stream while the request waits.
+    pub fn progress(self) -> impl Stream<Item = QueueProgress> {
+        async_stream::stream! {
+            let wait = self.wait();
+            let mut wait = std::pin::pin!(wait);
+            let mut check = tokio::time::interval(POSITION_CHECK_INTERVAL);
+            let mut reported = 0;
+            loop {
+                tokio::select! {
+                    result = &mut wait => {
+                        yield QueueProgress::Done(result);
+                        break;
+                    }
+                    _ = check.tick() => {
+                        let position = self.position();
+                        if position != reported {
+                            reported = position;
+                            yield QueueProgress::Position(position);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        self.group.leave(self.ticket);
+    }
+}
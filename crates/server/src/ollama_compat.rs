@@ -0,0 +1,476 @@
+//! Ollama-compatible HTTP API
+//!
+//! Provides `/api/chat`, `/api/generate`, `/api/tags`, and `/api/version` in
+//! the Ollama wire format, so apps with built-in Ollama support can use
+//! LocalGPT as their Ollama server. Requests run through a stateless agent
+//! with LocalGPT's tools and memory, like the OpenAI-compatible API.
+//!
+//! `/api/chat` is shared with LocalGPT's own chat endpoint; requests with a
+//! `messages` list are Ollama requests.
+
+use axum::{
+    body::Body,
+    http::{StatusCode, header},
+    response::{IntoResponse, Json, Response},
+};
+use futures::stream::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info, warn};
+
+use localgpt_core::agent::{
+    Agent, AgentConfig, ImageAttachment, LLMResponseContent, Message, Role, StreamEvent, ToolCall,
+    ToolSchema, model_catalog,
+};
+
+use crate::http::{AppState, Caller, RequestTurn};
+use crate::model_queue::ModelPermit;
+
+/// Ollama version reported by `/api/version`; clients check it for
+/// feature support.
+const OLLAMA_VERSION: &str = "0.6.0";
+
+// ============================================================================
+// Request Types (Ollama Wire Format)
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaChatRequest {
+    pub model: String,
+    #[serde(default)]
+    pub messages: Vec<OllamaMessage>,
+    #[serde(default = "default_stream")]
+    pub stream: bool,
+    pub tools: Option<Vec<OllamaToolDef>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaGenerateRequest {
+    pub model: String,
+    #[serde(default)]
+    pub prompt: String,
+    pub system: Option<String>,
+    #[serde(default)]
+    pub images: Vec<String>,
+    #[serde(default = "default_stream")]
+    pub stream: bool,
+}
+
+/// Ollama streams unless told otherwise.
+fn default_stream() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OllamaMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: String,
+    /// Base64-encoded images
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<OllamaToolCall>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OllamaToolCall {
+    pub function: OllamaFunctionCall,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OllamaFunctionCall {
+    pub name: String,
+    /// Arguments as a JSON object (not a string, unlike OpenAI)
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaToolDef {
+    pub function: OllamaFunctionDef,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaFunctionDef {
+    pub name: String,
+    pub description: Option<String>,
+    pub parameters: Option<Value>,
+}
+
+/// Which endpoint a reply is shaped for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Endpoint {
+    /// `/api/chat`: text in `message.content`
+    Chat,
+    /// `/api/generate`: text in `response`
+    Generate,
+}
+
+// ============================================================================
+// Conversion
+// ============================================================================
+
+/// Convert Ollama messages to LocalGPT messages. Ollama tool calls carry no
+/// ids, so ids are assigned here and matched to the `tool` messages that
+/// follow in order.
+fn convert_messages(messages: &[OllamaMessage]) -> Vec<Message> {
+    let mut converted = Vec::new();
+    let mut pending_ids = std::collections::VecDeque::new();
+    let mut next_id = 0;
+
+    for msg in messages {
+        let role = match msg.role.as_str() {
+            "system" => Role::System,
+            "assistant" => Role::Assistant,
+            "tool" => Role::Tool,
+            _ => Role::User,
+        };
+        let tool_calls = (!msg.tool_calls.is_empty()).then(|| {
+            msg.tool_calls
+                .iter()
+                .map(|tc| {
+                    next_id += 1;
+                    let id = format!("call_{}", next_id);
+                    pending_ids.push_back(id.clone());
+                    ToolCall {
+                        id,
+                        name: tc.function.name.clone(),
+                        arguments: tc.function.arguments.to_string(),
+                    }
+                })
+                .collect()
+        });
+        let tool_call_id = if role == Role::Tool {
+            pending_ids.pop_front()
+        } else {
+            None
+        };
+
+        converted.push(Message {
+            role,
+            content: msg.content.clone(),
+            tool_calls,
+            tool_call_id,
+            images: msg.images.iter().map(|data| image(data)).collect(),
+        });
+    }
+    converted
+}
+
+/// Ollama images are bare base64; the type is sniffed from the data.
+fn image(data: &str) -> ImageAttachment {
+    let media_type = if data.starts_with("iVBOR") {
+        "image/png"
+    } else if data.starts_with("R0lG") {
+        "image/gif"
+    } else if data.starts_with("UklGR") {
+        "image/webp"
+    } else {
+        "image/jpeg"
+    };
+    ImageAttachment {
+        data: data.to_string(),
+        media_type: media_type.to_string(),
+    }
+}
+
+fn convert_tools(tools: &[OllamaToolDef]) -> Vec<ToolSchema> {
+    tools
+        .iter()
+        .map(|t| ToolSchema {
+            name: t.function.name.clone(),
+            description: t.function.description.clone().unwrap_or_default(),
+            parameters: t.function.parameters.clone().unwrap_or(json!({})),
+        })
+        .collect()
+}
+
+fn to_ollama_tool_call(name: String, arguments: &str) -> OllamaToolCall {
+    OllamaToolCall {
+        function: OllamaFunctionCall {
+            name,
+            arguments: serde_json::from_str(arguments).unwrap_or(json!({})),
+        },
+    }
+}
+
+/// Clients may add Ollama's default `:latest` tag to the names we list.
+fn model_name(model: &str) -> &str {
+    model.strip_suffix(":latest").unwrap_or(model)
+}
+
+/// One reply object, streamed as a line or returned whole.
+fn reply(
+    endpoint: Endpoint,
+    model: &str,
+    content: &str,
+    tool_calls: Vec<OllamaToolCall>,
+) -> serde_json::Map<String, Value> {
+    let mut object = serde_json::Map::new();
+    object.insert("model".into(), json!(model));
+    object.insert("created_at".into(), json!(chrono::Utc::now().to_rfc3339()));
+    match endpoint {
+        Endpoint::Chat => {
+            let message = OllamaMessage {
+                role: "assistant".to_string(),
+                content: content.to_string(),
+                tool_calls,
+                ..Default::default()
+            };
+            object.insert("message".into(), json!(message));
+        }
+        Endpoint::Generate => {
+            object.insert("response".into(), json!(content));
+        }
+    }
+    object.insert("done".into(), json!(false));
+    object
+}
+
+/// Mark `object` as the final reply, with timing and token counts.
+fn finish(
+    mut object: serde_json::Map<String, Value>,
+    agent: &Agent,
+    started: Instant,
+) -> serde_json::Map<String, Value> {
+    let usage = agent.usage();
+    object.insert("done".into(), json!(true));
+    object.insert("done_reason".into(), json!("stop"));
+    object.insert(
+        "total_duration".into(),
+        json!(started.elapsed().as_nanos() as u64),
+    );
+    object.insert("prompt_eval_count".into(), json!(usage.input_tokens));
+    object.insert("eval_count".into(), json!(usage.output_tokens));
+    object
+}
+
+fn ndjson_line(object: &serde_json::Map<String, Value>) -> String {
+    let mut line = serde_json::to_string(object).unwrap_or_default();
+    line.push('\n');
+    line
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Handle POST /api/chat with an Ollama request
+pub async fn chat(state: Arc<AppState>, caller: Caller, req: OllamaChatRequest) -> Response {
+    let messages = convert_messages(&req.messages);
+    let tools = req.tools.as_ref().map(|t| convert_tools(t));
+    run(
+        state,
+        caller,
+        req.model,
+        messages,
+        tools,
+        req.stream,
+        Endpoint::Chat,
+    )
+    .await
+}
+
+/// Handle POST /api/generate
+pub async fn generate(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    axum::Extension(caller): axum::Extension<Caller>,
+    Json(req): Json<OllamaGenerateRequest>,
+) -> Response {
+    let mut messages = Vec::new();
+    if let Some(system) = req.system.filter(|s| !s.is_empty()) {
+        messages.push(OllamaMessage {
+            role: "system".to_string(),
+            content: system,
+            ..Default::default()
+        });
+    }
+    messages.push(OllamaMessage {
+        role: "user".to_string(),
+        content: req.prompt,
+        images: req.images,
+        ..Default::default()
+    });
+    let messages = convert_messages(&messages);
+    run(
+        state,
+        caller,
+        req.model,
+        messages,
+        None,
+        req.stream,
+        Endpoint::Generate,
+    )
+    .await
+}
+
+/// Handle GET /api/tags
+pub async fn tags(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let modified_at = chrono::Utc::now().to_rfc3339();
+    let models: Vec<Value> = model_catalog::discover_models(&state.config)
+        .await
+        .into_iter()
+        .map(|m| {
+            json!({
+                "name": m.id,
+                "model": m.id,
+                "modified_at": modified_at,
+                "size": 0,
+                "digest": "",
+                "details": { "family": m.owned_by, "format": "localgpt" },
+            })
+        })
+        .collect();
+    Json(json!({ "models": models }))
+}
+
+/// Handle GET /api/version
+pub async fn version() -> impl IntoResponse {
+    Json(json!({ "version": OLLAMA_VERSION }))
+}
+
+async fn run(
+    state: Arc<AppState>,
+    caller: Caller,
+    model: String,
+    messages: Vec<Message>,
+    tools: Option<Vec<ToolSchema>>,
+    stream: bool,
+    endpoint: Endpoint,
+) -> Response {
+    let model = model_name(&model).to_string();
+    let agent_config = AgentConfig {
+        model: model.clone(),
+        context_window: state.config.agent.context_window,
+        reserve_tokens: state.config.agent.reserve_tokens,
+    };
+    let mut agent = match state.caller_agent(&caller, agent_config).await {
+        Ok(agent) => agent,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": format!("Failed to create agent: {}", e) })),
+            )
+                .into_response();
+        }
+    };
+    agent.set_channel("http");
+    info!(
+        "Ollama API: {} request for model {}",
+        if stream { "streaming" } else { "non-streaming" },
+        model
+    );
+
+    // NDJSON has no room for progress lines, so both forms report the
+    // queue wait in headers
+    let (model_permit, queue) = match state.model_queue.acquire(&model).await {
+        Ok(admitted) => admitted,
+        Err(e) => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response();
+        }
+    };
+
+    let mut turn = RequestTurn::new(state, caller, agent, "Ollama API");
+    if stream {
+        let body = Body::from_stream(ndjson_stream(
+            turn,
+            model_permit,
+            model,
+            messages,
+            tools,
+            endpoint,
+        ));
+        return (
+            queue.headers(),
+            [(header::CONTENT_TYPE, "application/x-ndjson")],
+            body,
+        )
+            .into_response();
+    }
+
+    let started = Instant::now();
+    let result = turn
+        .agent
+        .chat_with_messages(&messages, tools.as_deref())
+        .await;
+    turn.finish();
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response();
+        }
+    };
+    let (content, tool_calls) = match response.content {
+        LLMResponseContent::Text(text) => (text, Vec::new()),
+        LLMResponseContent::ToolCalls { calls, text } => (
+            text.unwrap_or_default(),
+            calls
+                .into_iter()
+                .map(|c| to_ollama_tool_call(c.name, &c.arguments))
+                .collect(),
+        ),
+    };
+    let object = reply(endpoint, &model, &content, tool_calls);
+    (queue.headers(), Json(finish(object, &turn.agent, started))).into_response()
+}
+
+/// Stream NDJSON replies from a turn that lives as long as the stream,
+/// holding the model slot until the last line.
+fn ndjson_stream(
+    mut turn: RequestTurn,
+    model_permit: ModelPermit,
+    model: String,
+    messages: Vec<Message>,
+    tools: Option<Vec<ToolSchema>>,
+    endpoint: Endpoint,
+) -> impl Stream<Item = Result<String, Infallible>> {
+    async_stream::stream! {
+        let _model_permit = model_permit;
+        let started = Instant::now();
+        // Client tools are returned as tool_calls; agent tools run here
+        let passthrough = tools.is_some();
+        let mut tool_calls = Vec::new();
+
+        // The stream borrows the agent until the end of this block
+        {
+            let events = turn.agent.chat_stream_with_messages(&messages, tools);
+            let mut events = std::pin::pin!(events);
+            while let Some(event) = events.next().await {
+                match event {
+                    Ok(StreamEvent::Content(text)) => {
+                        yield Ok(ndjson_line(&reply(endpoint, &model, &text, Vec::new())));
+                    }
+                    Ok(StreamEvent::ToolCallStart { name, arguments, .. }) if passthrough => {
+                        tool_calls.push(to_ollama_tool_call(name, &arguments));
+                    }
+                    Ok(StreamEvent::ToolCallStart { .. } | StreamEvent::ToolCallEnd { .. }) => {}
+                    Ok(StreamEvent::Done) => break,
+                    Err(e) => {
+                        warn!("Ollama API stream error: {}", e);
+                        yield Ok(format!("{}\n", json!({ "error": e.to_string() })));
+                        turn.finish();
+                        return;
+                    }
+                }
+            }
+        }
+
+        let last = reply(endpoint, &model, "", tool_calls);
+        yield Ok(ndjson_line(&finish(last, &turn.agent, started)));
+        turn.finish();
+    }
+}
@@ -3,11 +3,16 @@
 //! Provides `/v1/chat/completions` and `/v1/models` endpoints that match
 //! the OpenAI wire format, enabling integration with tools like Cursor,
 //! Continue, Open WebUI, LibreChat, and the Python `openai` library.
+//!
+//! Requests without `tools` run the full agent loop server-side with
+//! LocalGPT's own tools. Requests with `tools` are passed through: the
+//! model's tool calls are returned for the client to execute.
 
 use anyhow::Result;
 use axum::{
+    Extension,
     extract::State,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{
         IntoResponse, Json, Response,
         sse::{Event, Sse},
@@ -18,17 +23,33 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::convert::Infallible;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use localgpt_core::agent::json_output::JsonFormat;
+use localgpt_core::agent::model_catalog;
+use localgpt_core::agent::tools::fetch_image;
+use localgpt_core::agent::tools::web_search::DomainFilter;
 use localgpt_core::agent::{
-    Agent, AgentConfig, LLMResponse, LLMResponseContent, Message, Role, StreamEvent, ToolCall,
-    ToolSchema,
+    Agent, AgentConfig, ImageAttachment, LLMResponse, LLMResponseContent, Message, Role,
+    StreamEvent, ToolCall, ToolSchema,
 };
 use localgpt_core::config::Config;
 
-use crate::http::AppState;
+use crate::http::{
+    AppError, AppState, Caller, RequestTurn, conversation_session, lock_turn, usage_since,
+};
+use crate::model_queue::{Admission, QueueProgress};
+
+/// How long discovered models are served before providers are asked again.
+const MODEL_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Times the model is asked to fix a reply that doesn't match `response_format`
+const MAX_JSON_REPAIRS: usize = 2;
+
+/// Header naming the daemon session a request continues
+const CONVERSATION_ID_HEADER: &str = "x-conversation-id";
 
 // ============================================================================
 // Request/Response Types (OpenAI Wire Format)
@@ -41,18 +62,63 @@ pub struct ChatCompletionRequest {
     pub messages: Vec<OaiMessage>,
     #[serde(default)]
     pub stream: bool,
+    pub stream_options: Option<StreamOptions>,
     pub max_tokens: Option<usize>,
     pub temperature: Option<f64>,
     pub tools: Option<Vec<OaiToolDef>>,
     /// Map of tool_choice options: "auto", "none", or {"type": "function", "function": {"name": "..."}}
     pub tool_choice: Option<Value>,
+    /// `{"type": "json_object"}` or `{"type": "json_schema", ...}` for JSON replies
+    pub response_format: Option<ResponseFormat>,
+    /// Daemon session to continue instead of running statelessly (also
+    /// accepted as the `X-Conversation-Id` header)
+    pub conversation_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    Text,
+    JsonObject,
+    JsonSchema { json_schema: OaiJsonSchema },
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(dead_code)]
+pub struct OaiJsonSchema {
+    pub name: String,
+    pub description: Option<String>,
+    pub schema: Option<Value>,
+    pub strict: Option<bool>,
+}
+
+impl ResponseFormat {
+    fn json_format(&self) -> Option<JsonFormat> {
+        match self {
+            ResponseFormat::Text => None,
+            ResponseFormat::JsonObject => Some(JsonFormat::Object),
+            ResponseFormat::JsonSchema { json_schema } => Some(
+                json_schema
+                    .schema
+                    .clone()
+                    .map_or(JsonFormat::Object, JsonFormat::Schema),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct StreamOptions {
+    /// Send a final chunk with the request's token usage
+    #[serde(default)]
+    pub include_usage: bool,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 #[allow(dead_code)]
 pub struct OaiMessage {
     pub role: String,
-    pub content: Option<String>,
+    pub content: Option<OaiContent>,
     pub tool_calls: Option<Vec<OaiToolCallResponse>>,
     pub tool_call_id: Option<String>,
     /// For assistant messages with tool calls, the content might be null or string
@@ -60,6 +126,36 @@ pub struct OaiMessage {
     pub name: Option<String>,
 }
 
+/// Message content: a plain string, or an array of text and image parts
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum OaiContent {
+    Text(String),
+    Parts(Vec<OaiContentPart>),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OaiContentPart {
+    Text {
+        text: String,
+    },
+    ImageUrl {
+        image_url: OaiImageUrl,
+    },
+    /// Audio, files, and other part types LocalGPT doesn't handle
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(dead_code)]
+pub struct OaiImageUrl {
+    /// A `data:` URL, or an http(s) URL on an allowed domain
+    pub url: String,
+    pub detail: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OaiToolCallResponse {
     pub id: String,
@@ -131,6 +227,9 @@ pub struct ChatCompletionChunk {
     pub created: u64,
     pub model: String,
     pub choices: Vec<ChunkChoice>,
+    /// Only on the final chunk, with `stream_options.include_usage`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<OaiUsage>,
 }
 
 #[derive(Debug, Serialize)]
@@ -176,7 +275,7 @@ pub struct ModelsResponse {
     pub data: Vec<ModelInfo>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ModelInfo {
     pub id: String,
     pub object: &'static str,
@@ -188,8 +287,8 @@ pub struct ModelInfo {
 // Message Conversion
 // ============================================================================
 
-/// Convert OpenAI messages to LocalGPT Message format
-fn convert_messages(oai_messages: &[OaiMessage]) -> Result<Vec<Message>> {
+/// Convert OpenAI messages to LocalGPT Message format, loading any images
+async fn convert_messages(oai_messages: &[OaiMessage], config: &Config) -> Result<Vec<Message>> {
     let mut messages = Vec::new();
 
     for msg in oai_messages {
@@ -217,18 +316,141 @@ fn convert_messages(oai_messages: &[OaiMessage]) -> Result<Vec<Message>> {
                 .collect()
         });
 
+        let (content, images) = match &msg.content {
+            None => (String::new(), Vec::new()),
+            Some(OaiContent::Text(text)) => (text.clone(), Vec::new()),
+            Some(OaiContent::Parts(parts)) => {
+                let mut texts = Vec::new();
+                let mut images = Vec::new();
+                for part in parts {
+                    match part {
+                        OaiContentPart::Text { text } => texts.push(text.as_str()),
+                        OaiContentPart::ImageUrl { image_url } => {
+                            images.push(load_image(&image_url.url, config).await?)
+                        }
+                        OaiContentPart::Unsupported => {
+                            debug!("Skipping unsupported content part")
+                        }
+                    }
+                }
+                (texts.join("\n"), images)
+            }
+        };
+
         messages.push(Message {
             role,
-            content: msg.content.clone().unwrap_or_default(),
+            content,
             tool_calls,
             tool_call_id: msg.tool_call_id.clone(),
-            images: Vec::new(),
+            images,
         });
     }
 
     Ok(messages)
 }
 
+/// Decode a `data:` image URL, or download an http(s) one from a domain in
+/// `server.image_url_domains`.
+async fn load_image(url: &str, config: &Config) -> Result<ImageAttachment> {
+    if url.starts_with("data:") {
+        return ImageAttachment::from_data_url(url);
+    }
+    let domains = &config.server.image_url_domains;
+    if domains.is_empty() || !DomainFilter::new(domains, &[]).permits(url) {
+        anyhow::bail!(
+            "Image URL not allowed (send a data: URL or add its domain to server.image_url_domains): {}",
+            url
+        );
+    }
+    fetch_image(url, config.server.max_request_body)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch image {}: {}", url, e))
+}
+
+fn text_message(role: Role, content: String) -> Message {
+    Message {
+        role,
+        content,
+        tool_calls: None,
+        tool_call_id: None,
+        images: Vec::new(),
+    }
+}
+
+fn llm_error(e: anyhow::Error) -> (StatusCode, String) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("LLM error: {}", e),
+    )
+}
+
+/// Run a completion whose reply must match `format`: the model is told the
+/// format up front, and asked to fix a non-matching reply up to
+/// [`MAX_JSON_REPAIRS`] times before the request fails with 422.
+async fn complete_json(
+    agent: &mut Agent,
+    messages: &[Message],
+    tools: Option<&[ToolSchema]>,
+    format: &JsonFormat,
+) -> Result<LLMResponse, (StatusCode, String)> {
+    let mut messages = messages.to_vec();
+    let after_system = messages
+        .iter()
+        .take_while(|m| m.role == Role::System)
+        .count();
+    messages.insert(
+        after_system,
+        text_message(Role::System, format.instruction()),
+    );
+
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let response = agent
+            .chat_with_messages(&messages, tools)
+            .await
+            .map_err(llm_error)?;
+        let text = match response.content {
+            LLMResponseContent::Text(text) => text,
+            // Client tool calls go back to the client before any JSON reply
+            calls @ LLMResponseContent::ToolCalls { .. } => {
+                return Ok(LLMResponse {
+                    content: calls,
+                    usage: response.usage,
+                });
+            }
+        };
+        match format.parse_reply(&text) {
+            Ok(value) => {
+                return Ok(LLMResponse {
+                    content: LLMResponseContent::Text(value.to_string()),
+                    usage: response.usage,
+                });
+            }
+            Err(e) if attempts <= MAX_JSON_REPAIRS => {
+                debug!("OpenAI API: rejected JSON reply ({}), asking for a fix", e);
+                messages.push(text_message(Role::Assistant, text));
+                messages.push(text_message(
+                    Role::User,
+                    format!(
+                        "That reply was rejected: {}. Reply again with only the corrected JSON.",
+                        e
+                    ),
+                ));
+            }
+            Err(e) => {
+                return Err((
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    format!(
+                        "Model did not produce valid JSON for response_format after {} attempts: {}",
+                        attempts, e
+                    ),
+                ));
+            }
+        }
+    }
+}
+
 /// Convert OpenAI tool definitions to LocalGPT ToolSchema
 fn convert_tools(oai_tools: &[OaiToolDef]) -> Vec<ToolSchema> {
     oai_tools
@@ -261,28 +483,158 @@ fn generate_completion_id() -> String {
 /// Handle POST /v1/chat/completions
 pub async fn chat_completions(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    headers: HeaderMap,
     Json(req): Json<ChatCompletionRequest>,
 ) -> Result<Response, (StatusCode, String)> {
+    let conversation_id = req.conversation_id.clone().or_else(|| {
+        headers
+            .get(CONVERSATION_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    });
+    if let Some(conversation_id) = conversation_id {
+        return chat_completions_conversation(state, caller, req, conversation_id).await;
+    }
+
     if req.stream {
-        return chat_completions_stream(state, req)
+        return chat_completions_stream(state, caller, req)
             .await
             .map(|r| r.into_response());
     }
 
-    chat_completions_non_stream(state, req)
+    chat_completions_non_stream(state, caller, req)
         .await
         .map(|r| r.into_response())
 }
 
+/// Chat completion in a persistent daemon session. Only the last message is
+/// sent; the session keeps the earlier history, with memory and compaction.
+async fn chat_completions_conversation(
+    state: Arc<AppState>,
+    caller: Caller,
+    req: ChatCompletionRequest,
+    conversation_id: String,
+) -> Result<Response, (StatusCode, String)> {
+    if req.tools.is_some() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "tools can't be used with conversation_id; conversations run LocalGPT's own tools"
+                .to_string(),
+        ));
+    }
+    if req
+        .response_format
+        .as_ref()
+        .and_then(ResponseFormat::json_format)
+        .is_some()
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "response_format can't be used with conversation_id".to_string(),
+        ));
+    }
+    let last = req
+        .messages
+        .last()
+        .filter(|m| m.role.eq_ignore_ascii_case("user"))
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                "conversation_id requests must end with a user message".to_string(),
+            )
+        })?;
+    let message = convert_messages(std::slice::from_ref(last), &state.config)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid messages: {}", e)))?
+        .remove(0);
+
+    let session_id = conversation_session(&state, &conversation_id, &caller)
+        .await
+        .map_err(|AppError(status, message)| (status, message))?;
+    let conversation_header = [(CONVERSATION_ID_HEADER, conversation_id)];
+    info!(
+        "OpenAI API: request in conversation {} for model {}",
+        session_id, req.model
+    );
+
+    if req.stream {
+        let include_usage = req.stream_options.is_some_and(|o| o.include_usage);
+        let event_stream = create_conversation_sse_stream(
+            state,
+            caller,
+            session_id,
+            message,
+            include_usage,
+            generate_completion_id(),
+            unix_timestamp(),
+            req.model,
+        );
+        let sse = Sse::new(event_stream).keep_alive(
+            axum::response::sse::KeepAlive::new()
+                .interval(Duration::from_secs(15))
+                .text(""),
+        );
+        return Ok((conversation_header, sse).into_response());
+    }
+
+    let (_model_permit, queue) = state
+        .model_queue
+        .acquire(&req.model)
+        .await
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+    let locks = lock_turn(&state)
+        .await
+        .map_err(|AppError(status, message)| (status, message))?;
+
+    let mut sessions = state.sessions.lock().await;
+    let entry = sessions
+        .get_mut(&session_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Session not found".to_string()))?;
+    entry.last_accessed = Instant::now();
+    entry
+        .agent
+        .set_model(&req.model)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid model: {}", e)))?;
+
+    let usage_before = entry.agent.usage().clone();
+    let turn = state.begin_turn(&session_id, &caller, &mut entry.agent);
+    let result = entry
+        .agent
+        .chat_with_images(&message.content, message.images)
+        .await;
+    drop(turn);
+    let usage = usage_since(entry.agent.usage(), &usage_before);
+    state.record_usage(&caller, &usage);
+    if result.is_ok() {
+        entry.dirty = true;
+    }
+    drop(sessions);
+    drop(locks);
+
+    let response = LLMResponse {
+        content: LLMResponseContent::Text(result.map_err(llm_error)?),
+        usage: Some(usage),
+    };
+    let completion = to_completion_response(response, &req.model);
+    Ok((queue.headers(), conversation_header, Json(completion)).into_response())
+}
+
 /// Non-streaming chat completion
 async fn chat_completions_non_stream(
     state: Arc<AppState>,
+    caller: Caller,
     req: ChatCompletionRequest,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let messages = convert_messages(&req.messages)
+    let messages = convert_messages(&req.messages, &state.config)
+        .await
         .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid messages: {}", e)))?;
 
     let tools = req.tools.as_ref().map(|t| convert_tools(t));
+    let json_format = req
+        .response_format
+        .as_ref()
+        .and_then(ResponseFormat::json_format);
 
     // Create a fresh agent for this request
     let agent_config = AgentConfig {
@@ -291,8 +643,8 @@ async fn chat_completions_non_stream(
         reserve_tokens: state.config.agent.reserve_tokens,
     };
 
-    let memory = Arc::new(state.memory.clone());
-    let mut agent = Agent::new(agent_config, &state.config, memory)
+    let mut agent = state
+        .caller_agent(&caller, agent_config)
         .await
         .map_err(|e| {
             (
@@ -300,39 +652,58 @@ async fn chat_completions_non_stream(
                 format!("Failed to create agent: {}", e),
             )
         })?;
+    agent.set_channel("http");
 
     info!("OpenAI API: non-streaming request for model {}", req.model);
 
-    // Call the provider
-    let response = agent
-        .chat_with_messages(&messages, tools.as_deref())
+    let (_model_permit, queue) = state
+        .model_queue
+        .acquire(&req.model)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("LLM error: {}", e),
-            )
-        })?;
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+    let mut turn = RequestTurn::new(state.clone(), caller, agent, "OpenAI API");
 
-    // Convert response
-    let completion = to_completion_response(response, &req.model);
+    // Call the provider
+    let result = match &json_format {
+        Some(format) => complete_json(&mut turn.agent, &messages, tools.as_deref(), format).await,
+        None => turn
+            .agent
+            .chat_with_messages(&messages, tools.as_deref())
+            .await
+            .map_err(llm_error),
+    };
+    turn.finish();
+    let response = result?;
+
+    // Convert response, counting every LLM call of the tool loop
+    let mut completion = to_completion_response(response, &req.model);
+    let usage = turn.agent.usage();
+    if usage.total() > 0 {
+        completion.usage = Some(OaiUsage {
+            prompt_tokens: usage.input_tokens,
+            completion_tokens: usage.output_tokens,
+            total_tokens: usage.total(),
+        });
+    }
 
-    Ok(Json(completion))
+    Ok((queue.headers(), Json(completion)))
 }
 
 /// Streaming chat completion (SSE)
 async fn chat_completions_stream(
     state: Arc<AppState>,
+    caller: Caller,
     req: ChatCompletionRequest,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    // Extract the last user message for streaming
-    let last_message = req
-        .messages
-        .iter()
-        .rev()
-        .find(|m| m.role == "user")
-        .and_then(|m| m.content.clone())
-        .unwrap_or_default();
+    let messages = convert_messages(&req.messages, &state.config)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid messages: {}", e)))?;
+    let tools = req.tools.as_ref().map(|t| convert_tools(t));
+    let include_usage = req.stream_options.is_some_and(|o| o.include_usage);
+    let json_format = req
+        .response_format
+        .as_ref()
+        .and_then(ResponseFormat::json_format);
 
     let model = req.model.clone();
     let completion_id = generate_completion_id();
@@ -345,17 +716,18 @@ async fn chat_completions_stream(
         reserve_tokens: state.config.agent.reserve_tokens,
     };
 
-    let memory = Arc::new(state.memory.clone());
-
     info!("OpenAI API: streaming request for model {}", model);
 
     // The agent must live for the duration of the stream, so we create the stream
     // in an async_stream that owns both the agent and the inner event stream.
     let event_stream = create_sse_stream_owned(
         agent_config,
-        state.config.clone(),
-        memory,
-        last_message,
+        state,
+        caller,
+        messages,
+        tools,
+        json_format,
+        include_usage,
         completion_id,
         created,
         model,
@@ -363,42 +735,65 @@ async fn chat_completions_stream(
 
     Ok(Sse::new(event_stream).keep_alive(
         axum::response::sse::KeepAlive::new()
-            .interval(std::time::Duration::from_secs(15))
+            .interval(Duration::from_secs(15))
             .text(""),
     ))
 }
 
 /// Create an SSE stream that owns its agent and handles the full lifecycle.
+#[allow(clippy::too_many_arguments)]
 fn create_sse_stream_owned(
     agent_config: AgentConfig,
-    config: Config,
-    memory: Arc<localgpt_core::memory::MemoryManager>,
-    message: String,
+    state: Arc<AppState>,
+    caller: Caller,
+    messages: Vec<Message>,
+    tools: Option<Vec<ToolSchema>>,
+    json_format: Option<JsonFormat>,
+    include_usage: bool,
     completion_id: String,
     created: u64,
     model: String,
 ) -> impl Stream<Item = Result<Event, Infallible>> {
     async_stream::try_stream! {
-        // Create agent inside the stream so it lives for the stream's duration
-        let mut agent = match Agent::new(agent_config, &config, memory).await {
-            Ok(a) => a,
-            Err(e) => {
-                warn!("Failed to create agent for streaming: {}", e);
-                yield Event::default().data("[DONE]");
-                return;
+        // Wait for a free slot on the model, reporting the queue position
+        // as SSE comments
+        let _model_permit = match state.model_queue.admit(&model) {
+            Admission::Ready(permit) => permit,
+            Admission::Queued(ticket) => {
+                let mut progress = std::pin::pin!(ticket.progress());
+                loop {
+                    match progress.next().await {
+                        Some(QueueProgress::Position(position)) => {
+                            yield Event::default().comment(format!("queued position={}", position));
+                        }
+                        Some(QueueProgress::Done(Ok(permit))) => break permit,
+                        Some(QueueProgress::Done(Err(e))) => {
+                            warn!("OpenAI API: {}", e);
+                            let error = json!({"error": {"message": e.to_string(), "type": "queue_timeout"}});
+                            yield Event::default().data(error.to_string());
+                            yield Event::default().data("[DONE]");
+                            return;
+                        }
+                        None => return,
+                    }
+                }
             }
         };
 
-        let event_stream = match agent.chat_stream_with_tools(&message, Vec::new()).await {
-            Ok(s) => s,
+        // Create agent inside the stream so it lives for the stream's duration
+        let mut agent = match state.caller_agent(&caller, agent_config).await {
+            Ok(a) => a,
             Err(e) => {
-                warn!("Failed to start stream: {}", e);
+                warn!("Failed to create agent for streaming: {}", e);
                 yield Event::default().data("[DONE]");
                 return;
             }
         };
+        agent.set_channel("http");
+        let mut turn = RequestTurn::new(state.clone(), caller, agent, "OpenAI API");
 
-        let mut stream = std::pin::pin!(event_stream);
+        // Client tools are returned as tool_calls; agent tools run here
+        let passthrough = tools.is_some();
 
         // Send initial chunk with role
         let initial = ChatCompletionChunk {
@@ -415,14 +810,31 @@ fn create_sse_stream_owned(
                 },
                 finish_reason: None,
             }],
+            usage: None,
         };
         yield Event::default().json_data(initial).unwrap();
 
-        let mut tool_call_index: usize = 0;
-
-        while let Some(event) = stream.next().await {
-            match event {
-                Ok(StreamEvent::Content(text)) => {
+        if let Some(format) = &json_format {
+            // JSON replies are validated whole, so they arrive as one chunk
+            match complete_json(&mut turn.agent, &messages, tools.as_deref(), format).await {
+                Ok(response) => {
+                    let mut completion = to_completion_response(response, &model);
+                    let choice = completion.choices.remove(0);
+                    let tool_calls = choice.message.tool_calls.map(|calls| {
+                        calls
+                            .into_iter()
+                            .enumerate()
+                            .map(|(index, call)| OaiToolCallChunk {
+                                index,
+                                id: Some(call.id),
+                                tool_type: call.tool_type,
+                                function: Some(OaiFunctionCallChunk {
+                                    name: Some(call.function.name),
+                                    arguments: Some(call.function.arguments),
+                                }),
+                            })
+                            .collect()
+                    });
                     let chunk = ChatCompletionChunk {
                         id: completion_id.clone(),
                         object: "chat.completion.chunk",
@@ -432,107 +844,318 @@ fn create_sse_stream_owned(
                             index: 0,
                             delta: ChunkDelta {
                                 role: None,
-                                content: Some(text),
-                                tool_calls: None,
+                                content: choice.message.content,
+                                tool_calls,
                             },
-                            finish_reason: None,
+                            finish_reason: choice.finish_reason,
                         }],
+                        usage: None,
                     };
                     yield Event::default().json_data(chunk).unwrap();
                 }
-                Ok(StreamEvent::ToolCallStart { name, id, arguments: _ }) => {
-                    let chunk = ChatCompletionChunk {
-                        id: completion_id.clone(),
-                        object: "chat.completion.chunk",
-                        created,
-                        model: model.clone(),
-                        choices: vec![ChunkChoice {
-                            index: 0,
-                            delta: ChunkDelta {
-                                role: None,
-                                content: None,
-                                tool_calls: Some(vec![OaiToolCallChunk {
-                                    index: tool_call_index,
-                                    id: Some(id),
-                                    tool_type: Some("function".to_string()),
-                                    function: Some(OaiFunctionCallChunk {
-                                        name: Some(name),
-                                        arguments: None,
-                                    }),
-                                }]),
-                            },
-                            finish_reason: None,
-                        }],
-                    };
-                    yield Event::default().json_data(chunk).unwrap();
-                    tool_call_index += 1;
+                Err((_, message)) => {
+                    warn!("OpenAI API: {}", message);
+                    let error = json!({"error": {"message": message, "type": "invalid_response_format"}});
+                    yield Event::default().data(error.to_string());
                 }
-                Ok(StreamEvent::ToolCallEnd { .. }) => {
-                    // Tool call finished - the output will be processed internally
-                    // We don't need to send anything special for the end
+            }
+        } else {
+            // The stream borrows the agent until the end of this block
+            let event_stream = turn.agent.chat_stream_with_messages(&messages, tools);
+            let mut stream = std::pin::pin!(event_stream);
+            let mut tool_call_index: usize = 0;
+
+            while let Some(event) = stream.next().await {
+                match event {
+                    Ok(StreamEvent::Content(text)) => {
+                        let chunk = ChatCompletionChunk {
+                            id: completion_id.clone(),
+                            object: "chat.completion.chunk",
+                            created,
+                            model: model.clone(),
+                            choices: vec![ChunkChoice {
+                                index: 0,
+                                delta: ChunkDelta {
+                                    role: None,
+                                    content: Some(text),
+                                    tool_calls: None,
+                                },
+                                finish_reason: None,
+                            }],
+                            usage: None,
+                        };
+                        yield Event::default().json_data(chunk).unwrap();
+                    }
+                    Ok(StreamEvent::ToolCallStart { name, id, .. }) if !passthrough => {
+                        debug!("OpenAI API: executing tool {} ({})", name, id);
+                    }
+                    Ok(StreamEvent::ToolCallStart { name, id, arguments }) => {
+                        let chunk = ChatCompletionChunk {
+                            id: completion_id.clone(),
+                            object: "chat.completion.chunk",
+                            created,
+                            model: model.clone(),
+                            choices: vec![ChunkChoice {
+                                index: 0,
+                                delta: ChunkDelta {
+                                    role: None,
+                                    content: None,
+                                    tool_calls: Some(vec![OaiToolCallChunk {
+                                        index: tool_call_index,
+                                        id: Some(id),
+                                        tool_type: Some("function".to_string()),
+                                        function: Some(OaiFunctionCallChunk {
+                                            name: Some(name),
+                                            arguments: Some(arguments),
+                                        }),
+                                    }]),
+                                },
+                                finish_reason: None,
+                            }],
+                            usage: None,
+                        };
+                        yield Event::default().json_data(chunk).unwrap();
+                        tool_call_index += 1;
+                    }
+                    Ok(StreamEvent::ToolCallEnd { .. }) => {
+                        // Tool call finished - the output will be processed internally
+                        // We don't need to send anything special for the end
+                    }
+                    Ok(StreamEvent::Done) => {
+                        // Send final chunk with finish_reason
+                        let finish_reason = if tool_call_index > 0 {
+                            "tool_calls"
+                        } else {
+                            "stop"
+                        };
+                        let finish_chunk = ChatCompletionChunk {
+                            id: completion_id.clone(),
+                            object: "chat.completion.chunk",
+                            created,
+                            model: model.clone(),
+                            choices: vec![ChunkChoice {
+                                index: 0,
+                                delta: ChunkDelta::default(),
+                                finish_reason: Some(finish_reason.to_string()),
+                            }],
+                            usage: None,
+                        };
+                        yield Event::default().json_data(finish_chunk).unwrap();
+                        break;
+                    }
+                    Err(e) => {
+                        warn!("Stream error: {}", e);
+                        break;
+                    }
                 }
-                Ok(StreamEvent::Done) => {
-                    // Send final chunk with finish_reason
-                    let finish_chunk = ChatCompletionChunk {
-                        id: completion_id.clone(),
-                        object: "chat.completion.chunk",
-                        created,
-                        model: model.clone(),
-                        choices: vec![ChunkChoice {
-                            index: 0,
-                            delta: ChunkDelta::default(),
-                            finish_reason: Some("stop".to_string()),
-                        }],
-                    };
-                    yield Event::default().json_data(finish_chunk).unwrap();
-                    break;
+            }
+        }
+
+        if include_usage {
+            let usage = turn.agent.usage();
+            let usage_chunk = ChatCompletionChunk {
+                id: completion_id.clone(),
+                object: "chat.completion.chunk",
+                created,
+                model: model.clone(),
+                choices: Vec::new(),
+                usage: Some(OaiUsage {
+                    prompt_tokens: usage.input_tokens,
+                    completion_tokens: usage.output_tokens,
+                    total_tokens: usage.total(),
+                }),
+            };
+            yield Event::default().json_data(usage_chunk).unwrap();
+        }
+
+        // Send [DONE] marker
+        yield Event::default().data("[DONE]");
+        turn.finish();
+    }
+}
+
+/// A chunk of a streamed completion with a single choice.
+fn completion_chunk(
+    completion_id: &str,
+    created: u64,
+    model: &str,
+    delta: ChunkDelta,
+    finish_reason: Option<&str>,
+) -> ChatCompletionChunk {
+    ChatCompletionChunk {
+        id: completion_id.to_string(),
+        object: "chat.completion.chunk",
+        created,
+        model: model.to_string(),
+        choices: vec![ChunkChoice {
+            index: 0,
+            delta,
+            finish_reason: finish_reason.map(str::to_string),
+        }],
+        usage: None,
+    }
+}
+
+/// Create an SSE stream for a turn in a persistent daemon session. The
+/// session's own tools run server-side; only content is streamed.
+#[allow(clippy::too_many_arguments)]
+fn create_conversation_sse_stream(
+    state: Arc<AppState>,
+    caller: Caller,
+    session_id: String,
+    message: Message,
+    include_usage: bool,
+    completion_id: String,
+    created: u64,
+    model: String,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    async_stream::try_stream! {
+        // Wait for a free slot on the model, reporting the queue position
+        // as SSE comments
+        let _model_permit = match state.model_queue.admit(&model) {
+            Admission::Ready(permit) => permit,
+            Admission::Queued(ticket) => {
+                let mut progress = std::pin::pin!(ticket.progress());
+                loop {
+                    match progress.next().await {
+                        Some(QueueProgress::Position(position)) => {
+                            yield Event::default().comment(format!("queued position={}", position));
+                        }
+                        Some(QueueProgress::Done(Ok(permit))) => break permit,
+                        Some(QueueProgress::Done(Err(e))) => {
+                            warn!("OpenAI API: {}", e);
+                            let error = json!({"error": {"message": e.to_string(), "type": "queue_timeout"}});
+                            yield Event::default().data(error.to_string());
+                            yield Event::default().data("[DONE]");
+                            return;
+                        }
+                        None => return,
+                    }
                 }
-                Err(e) => {
-                    warn!("Stream error: {}", e);
-                    break;
+            }
+        };
+
+        let locks = match lock_turn(&state).await {
+            Ok(locks) => locks,
+            Err(AppError(_, message)) => {
+                let error = json!({"error": {"message": message, "type": "server_error"}});
+                yield Event::default().data(error.to_string());
+                yield Event::default().data("[DONE]");
+                return;
+            }
+        };
+        let mut sessions = state.sessions.lock().await;
+        let Some(entry) = sessions.get_mut(&session_id) else {
+            let error = json!({"error": {"message": "Session not found", "type": "invalid_request_error"}});
+            yield Event::default().data(error.to_string());
+            yield Event::default().data("[DONE]");
+            return;
+        };
+        entry.last_accessed = Instant::now();
+        if let Err(e) = entry.agent.set_model(&model) {
+            let error = json!({"error": {"message": format!("Invalid model: {}", e), "type": "invalid_request_error"}});
+            yield Event::default().data(error.to_string());
+            yield Event::default().data("[DONE]");
+            return;
+        }
+        entry.dirty = true;
+        let usage_before = entry.agent.usage().clone();
+        let _turn = state.begin_turn(&session_id, &caller, &mut entry.agent);
+
+        let role = ChunkDelta {
+            role: Some("assistant".to_string()),
+            ..Default::default()
+        };
+        yield Event::default().json_data(completion_chunk(&completion_id, created, &model, role, None)).unwrap();
+
+        match entry.agent.chat_stream_with_tools(&message.content, message.images).await {
+            Ok(event_stream) => {
+                let mut stream = std::pin::pin!(event_stream);
+                while let Some(event) = stream.next().await {
+                    match event {
+                        Ok(StreamEvent::Content(text)) => {
+                            let delta = ChunkDelta {
+                                content: Some(text),
+                                ..Default::default()
+                            };
+                            yield Event::default().json_data(completion_chunk(&completion_id, created, &model, delta, None)).unwrap();
+                        }
+                        Ok(StreamEvent::ToolCallStart { name, id, .. }) => {
+                            debug!("OpenAI API: executing tool {} ({})", name, id);
+                        }
+                        Ok(StreamEvent::ToolCallEnd { .. }) => {}
+                        Ok(StreamEvent::Done) => {
+                            let chunk = completion_chunk(&completion_id, created, &model, ChunkDelta::default(), Some("stop"));
+                            yield Event::default().json_data(chunk).unwrap();
+                            break;
+                        }
+                        Err(e) => {
+                            warn!("Stream error: {}", e);
+                            let error = json!({"error": {"message": e.to_string(), "type": "server_error"}});
+                            yield Event::default().data(error.to_string());
+                            break;
+                        }
+                    }
                 }
             }
+            Err(e) => {
+                warn!("Stream error: {}", e);
+                let error = json!({"error": {"message": e.to_string(), "type": "server_error"}});
+                yield Event::default().data(error.to_string());
+            }
+        }
+
+        let usage = usage_since(entry.agent.usage(), &usage_before);
+        state.record_usage(&caller, &usage);
+        drop(sessions);
+        drop(locks);
+
+        if include_usage {
+            let usage_chunk = ChatCompletionChunk {
+                id: completion_id.clone(),
+                object: "chat.completion.chunk",
+                created,
+                model: model.clone(),
+                choices: Vec::new(),
+                usage: Some(OaiUsage {
+                    prompt_tokens: usage.input_tokens,
+                    completion_tokens: usage.output_tokens,
+                    total_tokens: usage.total(),
+                }),
+            };
+            yield Event::default().json_data(usage_chunk).unwrap();
         }
 
-        // Send [DONE] marker
         yield Event::default().data("[DONE]");
     }
 }
 
 /// Handle GET /v1/models
+///
+/// Lists configured models plus those discovered from the providers (see
+/// `model_catalog`). Discovery is cached for [`MODEL_CACHE_TTL`].
 pub async fn list_models(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let mut models = Vec::new();
-
-    // Add the default model
-    let default_model = &state.config.agent.default_model;
-    models.push(ModelInfo {
-        id: default_model.clone(),
-        object: "model",
-        created: 0,
-        owned_by: "localgpt".to_string(),
-    });
-
-    // Add fallback models
-    for model in &state.config.agent.fallback_models {
-        models.push(ModelInfo {
-            id: model.clone(),
-            object: "model",
-            created: 0,
-            owned_by: "localgpt".to_string(),
-        });
-    }
-
-    // Add configured provider models
-    if let Some(ollama) = &state.config.providers.ollama {
-        models.push(ModelInfo {
-            id: format!("ollama/{}", ollama.model),
-            object: "model",
-            created: 0,
-            owned_by: "ollama".to_string(),
-        });
-    }
+    let mut cache = state.model_cache.lock().await;
+    let models = match cache.as_ref() {
+        Some((at, models)) if at.elapsed() < MODEL_CACHE_TTL => models.clone(),
+        _ => {
+            let models: Vec<ModelInfo> = model_catalog::discover_models(&state.config)
+                .await
+                .into_iter()
+                .map(|m| ModelInfo {
+                    id: m.id,
+                    object: "model",
+                    created: 0,
+                    owned_by: m.owned_by,
+                })
+                .collect();
+            info!("OpenAI API: discovered {} models", models.len());
+            *cache = Some((Instant::now(), models.clone()));
+            models
+        }
+    };
 
     Ok(Json(ModelsResponse {
         object: "list",
@@ -1,4 +1,5 @@
-//! Token bucket rate limiter per IP address.
+//! Token bucket rate limiter per API key or IP address, with a cap on
+//! concurrent chat requests per client.
 
 use std::collections::HashMap;
 use std::net::IpAddr;
@@ -8,57 +9,148 @@ use tokio::sync::Mutex;
 
 use localgpt_core::config::RateLimitConfig;
 
-/// Token bucket state for a single IP.
+/// Who a request is counted against: the API key it authenticated with,
+/// or its IP address if it used none.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClientKey {
+    Ip(IpAddr),
+    ApiKey(String),
+}
+
+/// Token bucket state for a single client.
 struct Bucket {
     tokens: f64,
     last_refill: Instant,
 }
 
-/// Per-IP token bucket rate limiter.
-pub struct RateLimiter {
-    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+/// Steady rate and burst capacity of one kind of bucket.
+#[derive(Clone, Copy)]
+struct Limit {
+    per_minute: u32,
     rate: f64,       // tokens per second
     max_tokens: f64, // burst capacity
+}
+
+impl Limit {
+    fn new(per_minute: u32, burst: u32) -> Self {
+        let rate = per_minute as f64 / 60.0;
+        Self {
+            per_minute,
+            rate,
+            max_tokens: rate + burst as f64,
+        }
+    }
+}
+
+/// Outcome of a rate limit check, for the `RateLimit-*` response headers.
+#[derive(Debug, Clone, Copy)]
+pub struct RateDecision {
+    pub allowed: bool,
+    /// Requests allowed per minute
+    pub limit: u32,
+    /// Requests left before being limited
+    pub remaining: u32,
+    /// Seconds until the bucket is full again (or, when limited, until the
+    /// next request is allowed)
+    pub reset_secs: u64,
+}
+
+/// Per-client token bucket rate limiter.
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<ClientKey, Bucket>>,
+    ip_limit: Limit,
+    key_limit: Limit,
+    max_streams: u32,
+    streams: std::sync::Mutex<HashMap<ClientKey, u32>>,
     enabled: bool,
 }
 
 impl RateLimiter {
     pub fn new(config: &RateLimitConfig) -> Self {
-        let rate = config.requests_per_minute as f64 / 60.0;
-        let max_tokens = rate + config.burst as f64;
+        let key_per_minute = config
+            .key_requests_per_minute
+            .unwrap_or(config.requests_per_minute);
         Self {
             buckets: Mutex::new(HashMap::new()),
-            rate,
-            max_tokens,
+            ip_limit: Limit::new(config.requests_per_minute, config.burst),
+            key_limit: Limit::new(key_per_minute, config.burst),
+            max_streams: config.max_concurrent_streams,
+            streams: std::sync::Mutex::new(HashMap::new()),
             enabled: config.enabled,
         }
     }
 
-    /// Try to consume one token for the given IP. Returns true if allowed.
-    pub async fn check(&self, ip: IpAddr) -> bool {
+    /// Try to consume one token for the given client.
+    pub async fn check(&self, client: &ClientKey) -> RateDecision {
+        let limit = match client {
+            ClientKey::Ip(_) => self.ip_limit,
+            ClientKey::ApiKey(_) => self.key_limit,
+        };
         if !self.enabled {
-            return true;
+            return RateDecision {
+                allowed: true,
+                limit: limit.per_minute,
+                remaining: limit.max_tokens as u32,
+                reset_secs: 0,
+            };
         }
 
         let mut buckets = self.buckets.lock().await;
         let now = Instant::now();
 
-        let bucket = buckets.entry(ip).or_insert_with(|| Bucket {
-            tokens: self.max_tokens,
+        let bucket = buckets.entry(client.clone()).or_insert_with(|| Bucket {
+            tokens: limit.max_tokens,
             last_refill: now,
         });
 
         // Refill tokens based on elapsed time
         let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
-        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.max_tokens);
+        bucket.tokens = (bucket.tokens + elapsed * limit.rate).min(limit.max_tokens);
         bucket.last_refill = now;
 
-        if bucket.tokens >= 1.0 {
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
             bucket.tokens -= 1.0;
-            true
+        }
+        let missing = if allowed {
+            limit.max_tokens - bucket.tokens
+        } else {
+            1.0 - bucket.tokens
+        };
+        let reset_secs = if limit.rate > 0.0 {
+            (missing / limit.rate).ceil() as u64
         } else {
-            false
+            60
+        };
+
+        RateDecision {
+            allowed,
+            limit: limit.per_minute,
+            remaining: bucket.tokens.floor() as u32,
+            reset_secs,
+        }
+    }
+
+    /// Claim one of the client's concurrent chat request slots. The slot is
+    /// held until the returned guard is dropped; `None` if all are in use.
+    pub fn try_acquire_stream(self: &Arc<Self>, client: &ClientKey) -> Option<StreamGuard> {
+        if self.enabled && self.max_streams > 0 {
+            let mut streams = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+            let count = streams.entry(client.clone()).or_insert(0);
+            if *count >= self.max_streams {
+                return None;
+            }
+            *count += 1;
         }
+        Some(StreamGuard {
+            limiter: self.clone(),
+            client: client.clone(),
+        })
+    }
+
+    /// Concurrent chat requests allowed per client (0 = unlimited).
+    pub fn max_streams(&self) -> u32 {
+        self.max_streams
     }
 
     /// Remove buckets that haven't been used in 5 minutes.
@@ -69,6 +161,31 @@ impl RateLimiter {
     }
 }
 
+/// A claimed concurrent request slot, released on drop.
+pub struct StreamGuard {
+    limiter: Arc<RateLimiter>,
+    client: ClientKey,
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        if !self.limiter.enabled || self.limiter.max_streams == 0 {
+            return;
+        }
+        let mut streams = self
+            .limiter
+            .streams
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = streams.get_mut(&self.client) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                streams.remove(&self.client);
+            }
+        }
+    }
+}
+
 /// Create a shared rate limiter and spawn a background cleanup task.
 pub fn create_rate_limiter(config: &RateLimitConfig) -> Arc<RateLimiter> {
     let limiter = Arc::new(RateLimiter::new(config));
@@ -5,28 +5,52 @@ use chacha20poly1305::{
 };
 use chrono::{DateTime, Utc};
 use hmac::{Hmac, Mac};
-use localgpt_bridge::peer_identity::{PeerIdentity, get_peer_identity};
-use localgpt_bridge::{BridgeError, BridgeServer, BridgeService};
+use localgpt_bridge::peer_identity::{PeerIdentity, get_peer_identity, peer_executable};
+use localgpt_bridge::{
+    BridgeConnection, BridgeError, BridgeServer, BridgeService, BridgeStatusReport, CronJobChanges,
+    CronJobSpec, CronJobStatus, SkillCommand,
+};
 use rand::RngExt;
 use serde::Serialize;
 use sha2::Sha256;
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tarpc::context;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use localgpt_core::agent::{Agent, AgentConfig};
-use localgpt_core::config::Config;
+use localgpt_core::agent::{
+    load_skills, parse_skill_command, skill_invocation_prompt, skill_usage, slash_command_skills,
+};
+use localgpt_core::concurrency::{ActorPool, ActorPoolConfig, CancellationToken};
+use localgpt_core::config::{Config, CronJob};
+use localgpt_core::cron::{CronJobStore, CronJobUpdate};
 use localgpt_core::memory::MemoryManager;
 use localgpt_core::paths::Paths;
-use localgpt_core::security::read_device_key;
+use localgpt_core::readiness::{self, ComponentState};
+use localgpt_core::security::{
+    AuditAction, constant_time_eq, get_keyring_bytes, read_device_key, set_keyring_bytes,
+};
+use localgpt_core::webhooks::WebhookEvent;
+
+use super::bridge_approval::{
+    BridgeApprovals, BridgeApprover, BridgePeer, PendingBridge, audit_bridge,
+    resolve_bridge_request,
+};
+use super::bridge_scopes::{BridgeScope, BridgeScopes, join_scopes};
 
 /// Agent ID used for bridge CLI sessions.
 const BRIDGE_CLI_AGENT_ID: &str = "bridge-cli";
 
+/// How long `get_credentials` waits for the daemon's approval prompt to be
+/// answered; bridges give up on an RPC after 10 seconds.
+const APPROVAL_PROMPT_WAIT: Duration = Duration::from_secs(8);
+
 /// Health status of a bridge connection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -39,6 +63,16 @@ pub enum HealthStatus {
     Unhealthy,
 }
 
+impl HealthStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HealthStatus::Healthy => "healthy",
+            HealthStatus::Degraded => "degraded",
+            HealthStatus::Unhealthy => "unhealthy",
+        }
+    }
+}
+
 /// Status and health info for a connected bridge.
 #[derive(Debug, Clone, Serialize)]
 pub struct BridgeStatus {
@@ -78,16 +112,30 @@ impl Default for HealthCheckConfig {
     }
 }
 
-/// Shared agent session for bridge CLI connections.
-struct AgentSession {
-    agent: Agent,
+/// `bridge.health` event for a bridge's current health.
+fn health_event(status: &BridgeStatus, idle: Duration) -> WebhookEvent {
+    WebhookEvent::BridgeHealth {
+        bridge_id: status.bridge_id.clone().unwrap_or_default(),
+        connection_id: status.connection_id.clone(),
+        health: status.health.as_str().to_string(),
+        idle_secs: idle.as_secs(),
+    }
 }
 
 /// Optional agent support for handling chat/memory RPCs.
 struct AgentSupport {
     config: Config,
     memory: Arc<MemoryManager>,
-    sessions: tokio::sync::Mutex<HashMap<String, AgentSession>>,
+    /// Bridge CLI sessions, each served by one of the pool's actors
+    sessions: ActorPool,
+    /// Cancellation token of the chat running on each session, for `cancel_chat`
+    running_chats: std::sync::Mutex<HashMap<String, CancellationToken>>,
+}
+
+impl AgentSupport {
+    fn running_chats_lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CancellationToken>> {
+        self.running_chats.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
 
 /// Manages bridge processes and their credentials.
@@ -101,6 +149,8 @@ pub struct BridgeManager {
     agent_support: Option<Arc<AgentSupport>>,
     // Health check configuration
     health_config: HealthCheckConfig,
+    // Asks the user about unknown bridge executables
+    approver: Option<Arc<dyn BridgeApprover>>,
 }
 
 impl BridgeManager {
@@ -110,22 +160,35 @@ impl BridgeManager {
             active_bridges: Arc::new(RwLock::new(HashMap::new())),
             agent_support: None,
             health_config: HealthCheckConfig::default(),
+            approver: None,
         }
     }
 
     /// Create a BridgeManager with agent support for handling chat/memory RPCs.
     /// This is used by the daemon when serving bridge CLI connections.
-    pub fn new_with_agent_support(config: Config, memory: MemoryManager) -> Self {
-        Self {
+    pub fn new_with_agent_support(config: Config, memory: MemoryManager) -> Result<Self> {
+        let memory = Arc::new(memory);
+        let sessions = ActorPool::with_memory(
+            config.clone(),
+            BRIDGE_CLI_AGENT_ID,
+            Arc::clone(&memory),
+            ActorPoolConfig {
+                channel: Some("bridge".to_string()),
+                ..Default::default()
+            },
+        )?;
+        Ok(Self {
             credentials: Arc::new(RwLock::new(HashMap::new())),
             active_bridges: Arc::new(RwLock::new(HashMap::new())),
             agent_support: Some(Arc::new(AgentSupport {
                 config,
-                memory: Arc::new(memory),
-                sessions: tokio::sync::Mutex::new(HashMap::new()),
+                memory,
+                sessions,
+                running_chats: std::sync::Mutex::new(HashMap::new()),
             })),
             health_config: HealthCheckConfig::default(),
-        }
+            approver: None,
+        })
     }
 
     /// Create with custom health check configuration
@@ -135,9 +198,16 @@ impl BridgeManager {
             active_bridges: Arc::new(RwLock::new(HashMap::new())),
             agent_support: None,
             health_config: config,
+            approver: None,
         }
     }
 
+    /// Ask `approver` when an unknown bridge executable requests credentials,
+    /// in addition to waiting for `localgpt bridge approve`.
+    pub fn set_approver(&mut self, approver: Arc<dyn BridgeApprover>) {
+        self.approver = Some(approver);
+    }
+
     /// Start the background health check task
     pub fn start_health_checker(&self) -> tokio::task::JoinHandle<()> {
         let manager = self.clone();
@@ -178,6 +248,17 @@ impl BridgeManager {
                 status.consecutive_failures = 0;
             }
 
+            if status.health != previous_health {
+                self.emit_event(health_event(status, elapsed));
+            }
+            if status.health == HealthStatus::Unhealthy && previous_health != HealthStatus::Unhealthy {
+                self.emit_event(WebhookEvent::BridgeUnhealthy {
+                    bridge_id: status.bridge_id.clone().unwrap_or_default(),
+                    connection_id: status.connection_id.clone(),
+                    idle_secs: elapsed.as_secs(),
+                });
+            }
+
             // Log warnings on state changes or continued unhealthy state
             if config.log_warnings {
                 if status.health != previous_health {
@@ -222,6 +303,15 @@ impl BridgeManager {
         }
     }
 
+    /// Publish a bridge event, also delivering it to webhooks when the
+    /// daemon config is known.
+    fn emit_event(&self, event: WebhookEvent) {
+        match &self.agent_support {
+            Some(support) => localgpt_core::webhooks::emit(&support.config, event),
+            None => localgpt_core::webhooks::emit_to(&[], event),
+        }
+    }
+
     /// Return status of all active bridge connections.
     pub async fn get_active_bridges(&self) -> Vec<BridgeStatus> {
         self.active_bridges.read().await.values().cloned().collect()
@@ -242,22 +332,36 @@ impl BridgeManager {
             .write()
             .await
             .insert(id.to_string(), status);
+        self.emit_event(WebhookEvent::BridgeConnected {
+            connection_id: id.to_string(),
+            pid: identity.pid,
+        });
     }
 
     async fn update_active(&self, id: &str, bridge_id: Option<String>) {
         let mut active = self.active_bridges.write().await;
         if let Some(status) = active.get_mut(id) {
+            let previous_health = status.health;
             status.last_active = Utc::now();
             status.health = HealthStatus::Healthy;
             status.consecutive_failures = 0;
             if bridge_id.is_some() {
                 status.bridge_id = bridge_id;
             }
+            if previous_health != HealthStatus::Healthy {
+                self.emit_event(health_event(status, Duration::ZERO));
+            }
         }
     }
 
     async fn remove_connection(&self, id: &str) {
-        self.active_bridges.write().await.remove(id);
+        let removed = self.active_bridges.write().await.remove(id);
+        if let Some(status) = removed {
+            self.emit_event(WebhookEvent::BridgeDisconnected {
+                connection_id: status.connection_id,
+                bridge_id: status.bridge_id,
+            });
+        }
     }
 
     /// Register a new bridge secret.
@@ -266,120 +370,181 @@ impl BridgeManager {
         validate_bridge_id(bridge_id)?;
 
         let paths = Paths::resolve()?;
-        let bridges_dir = paths.data_dir.join("bridges");
-        std::fs::create_dir_all(&bridges_dir)?;
-
-        // 1. Get Master Key
-        let master_key = read_device_key(&paths.data_dir)?;
-
-        // 2. Derive Bridge Key = HMAC-SHA256(MasterKey, "bridge-key:" + bridge_id)
-        let bridge_key = derive_bridge_key(&master_key, bridge_id)?;
-
-        // 3. Encrypt Secret
-        let cipher = ChaCha20Poly1305::new(&bridge_key);
-
-        // Generate nonce manually to avoid rand_core version mismatch
-        let mut nonce_bytes = [0u8; 12];
-        rand::rng().fill(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
+        write_bridge_secret(&paths.data_dir, bridge_id, secret)?;
 
-        let ciphertext = cipher
-            .encrypt(nonce, secret)
-            .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
-
-        // 4. Save to file: [Nonce (12 bytes)][Ciphertext]
-        let mut file_content = nonce_bytes.to_vec();
-        file_content.extend(ciphertext);
+        // Update Cache
+        let mut creds = self.credentials.write().await;
+        creds.insert(bridge_id.to_string(), secret.to_vec());
 
-        let file_path = bridges_dir.join(format!("{}.enc", bridge_id));
-        std::fs::write(&file_path, file_content)?;
+        info!("Registered credentials for bridge: {}", bridge_id);
+        Ok(())
+    }
 
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o600))?;
-        }
+    /// Register a new bridge secret in the OS keyring instead of an
+    /// encrypted file, and update the cache.
+    pub async fn register_bridge_in_keyring(&self, bridge_id: &str, secret: &[u8]) -> Result<()> {
+        let paths = Paths::resolve()?;
+        write_bridge_secret_to_keyring(&paths.data_dir, bridge_id, secret)?;
 
-        // 5. Update Cache
         let mut creds = self.credentials.write().await;
         creds.insert(bridge_id.to_string(), secret.to_vec());
 
-        info!("Registered credentials for bridge: {}", bridge_id);
+        info!("Registered keyring credentials for bridge: {}", bridge_id);
         Ok(())
     }
 
-    /// Retrieve credentials if the identity is authorized.
+    /// Retrieve credentials if the identity is authorized: the peer's
+    /// executable must have been approved for `bridge_id`.
     /// Loads from disk if not in cache.
     pub async fn get_credentials_for(
         &self,
         bridge_id: &str,
         identity: &PeerIdentity,
+        exe: Option<&Path>,
     ) -> Result<Vec<u8>, BridgeError> {
         if let Err(e) = validate_bridge_id(bridge_id) {
             error!("Invalid bridge ID: {}", e);
             return Err(BridgeError::AuthFailed("Invalid bridge ID".to_string()));
         }
 
-        // Verify identity (Basic check for now)
-        // TODO: Implement stricter checks based on OS user or code signature
         info!(
-            "Checking access for bridge: {} from {:?}",
-            bridge_id, identity
+            "Checking access for bridge: {} from {:?} ({})",
+            bridge_id,
+            identity,
+            exe.map(|p| p.display().to_string())
+                .unwrap_or_else(|| "unknown executable".to_string())
         );
 
         // Check cache first
-        {
-            let creds = self.credentials.read().await;
-            if let Some(secret) = creds.get(bridge_id) {
-                return Ok(secret.clone());
-            }
-        }
+        let cached = self.credentials.read().await.get(bridge_id).cloned();
+        let secret = match cached {
+            Some(secret) => secret,
+            // Load from disk
+            None => match self.load_credentials_from_disk(bridge_id).await {
+                Ok(secret) => {
+                    // Cache it
+                    let mut creds = self.credentials.write().await;
+                    creds.insert(bridge_id.to_string(), secret.clone());
+                    secret
+                }
+                Err(e) => {
+                    error!("Failed to load credentials for {}: {}", bridge_id, e);
+                    return Err(BridgeError::NotRegistered);
+                }
+            },
+        };
 
-        // Load from disk
-        match self.load_credentials_from_disk(bridge_id).await {
-            Ok(secret) => {
-                // Cache it
-                let mut creds = self.credentials.write().await;
-                creds.insert(bridge_id.to_string(), secret.clone());
-                Ok(secret)
-            }
-            Err(e) => {
-                error!("Failed to load credentials for {}: {}", bridge_id, e);
-                Err(BridgeError::NotRegistered)
-            }
-        }
+        let peer = bridge_peer(bridge_id, identity, exe).await?;
+        self.check_approval(&peer, identity).await?;
+        Ok(secret)
     }
 
-    async fn load_credentials_from_disk(&self, bridge_id: &str) -> Result<Vec<u8>> {
-        let paths = Paths::resolve()?;
-        let file_path = paths
-            .data_dir
-            .join("bridges")
-            .join(format!("{}.enc", bridge_id));
-
-        if !file_path.exists() {
-            anyhow::bail!("Credential file not found");
+    /// Authenticate a connection as `bridge_id`, without credentials: the
+    /// peer's executable must have been approved for it.
+    pub async fn authenticate(
+        &self,
+        bridge_id: &str,
+        identity: &PeerIdentity,
+        exe: Option<&Path>,
+    ) -> Result<(), BridgeError> {
+        if let Err(e) = validate_bridge_id(bridge_id) {
+            error!("Invalid bridge ID: {}", e);
+            return Err(BridgeError::AuthFailed("Invalid bridge ID".to_string()));
         }
+        let peer = bridge_peer(bridge_id, identity, exe).await?;
+        self.check_approval(&peer, identity).await
+    }
 
-        let file_content = std::fs::read(&file_path)?;
-        if file_content.len() < 12 {
-            anyhow::bail!("Invalid credential file format (too short)");
+    /// Succeeds if `peer` is approved. Otherwise records it as pending and,
+    /// the first time, asks the approver.
+    async fn check_approval(
+        &self,
+        peer: &BridgePeer,
+        identity: &PeerIdentity,
+    ) -> Result<(), BridgeError> {
+        if !peer.is_known() {
+            return Err(BridgeError::AuthFailed(format!(
+                "Can't read the executable of bridge '{}' from {}, so it can't be approved",
+                peer.bridge_id,
+                peer.exe_display()
+            )));
+        }
+        let paths = Paths::resolve().map_err(|e| BridgeError::Internal(e.to_string()))?;
+        let mut approvals = BridgeApprovals::load(&paths.data_dir)
+            .map_err(|e| BridgeError::Internal(format!("Failed to read approvals: {}", e)))?;
+        if approvals.is_approved(peer) {
+            return Ok(());
         }
 
-        let (nonce_bytes, ciphertext) = file_content.split_at(12);
-        let nonce = Nonce::from_slice(nonce_bytes);
+        if let Some(request) = approvals.request(peer, identity.pid) {
+            approvals
+                .save(&paths.data_dir)
+                .map_err(|e| BridgeError::Internal(format!("Failed to save approvals: {}", e)))?;
+            warn!(
+                "Bridge '{}' from {} (fingerprint {}) is waiting for approval: run `localgpt bridge approve {}`",
+                peer.bridge_id,
+                peer.exe_display(),
+                peer.fingerprint,
+                peer.bridge_id
+            );
+            audit_bridge(&paths, AuditAction::BridgeApprovalRequested, "bridge", peer);
+            self.emit_event(WebhookEvent::BridgeApprovalRequested {
+                bridge_id: peer.bridge_id.clone(),
+                fingerprint: peer.fingerprint.clone(),
+                exe: peer.exe.as_ref().map(|p| p.display().to_string()),
+                pid: identity.pid,
+            });
 
-        // Derive Key
-        let master_key = read_device_key(&paths.data_dir)?;
-        let bridge_key = derive_bridge_key(&master_key, bridge_id)?;
+            if let Some(approver) = self.approver.clone()
+                && self.ask_approver(approver, paths, request).await
+            {
+                return Ok(());
+            }
+        }
+
+        Err(BridgeError::AuthFailed(format!(
+            "Bridge '{}' from {} is waiting for approval; run `localgpt bridge approve {}`",
+            peer.bridge_id,
+            peer.exe_display(),
+            peer.bridge_id
+        )))
+    }
 
-        // Decrypt
-        let cipher = ChaCha20Poly1305::new(&bridge_key);
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| anyhow::anyhow!("Decryption failed: {}", e))?;
+    /// Ask `approver` about `request`, recording the answer. An answer
+    /// that comes after the bridge has given up still counts for its next
+    /// connection.
+    async fn ask_approver(
+        &self,
+        approver: Arc<dyn BridgeApprover>,
+        paths: Paths,
+        request: PendingBridge,
+    ) -> bool {
+        let answer = tokio::task::spawn_blocking(move || {
+            let approved = approver.ask(&request);
+            let peer = &request.peer;
+            match resolve_bridge_request(
+                &paths,
+                &peer.bridge_id,
+                Some(&peer.fingerprint),
+                approved,
+                "daemon",
+            ) {
+                Ok(_) => approved,
+                Err(e) => {
+                    warn!("Failed to record bridge approval: {}", e);
+                    false
+                }
+            }
+        });
+        matches!(
+            tokio::time::timeout(APPROVAL_PROMPT_WAIT, answer).await,
+            Ok(Ok(true))
+        )
+    }
 
-        Ok(plaintext)
+    async fn load_credentials_from_disk(&self, bridge_id: &str) -> Result<Vec<u8>> {
+        let paths = Paths::resolve()?;
+        read_bridge_secret(&paths.data_dir, bridge_id)
     }
 
     /// Start the bridge server listening on the given socket path.
@@ -387,7 +552,16 @@ impl BridgeManager {
         let listener = BridgeServer::bind(socket_path)?;
         let manager = self.clone();
 
+        // Connections presenting this token (the `localgpt` CLI) may call
+        // every RPC
+        let admin_token = Arc::new(create_admin_token(&Paths::resolve()?.bridge_admin_token())?);
+        let cli_exe = std::env::current_exe().and_then(std::fs::canonicalize).ok();
+        if cli_exe.is_none() {
+            warn!("Can't find the daemon's executable; refusing admin connections");
+        }
+
         info!("BridgeManager listening on {}", socket_path);
+        readiness::report(readiness::BRIDGE, ComponentState::Running);
 
         loop {
             let conn = match listener.accept().await {
@@ -437,10 +611,16 @@ impl BridgeManager {
             let connection_id = Uuid::new_v4().to_string();
             manager.add_connection(&connection_id, &identity).await;
 
+            let exe = peer_executable(&identity);
             let handler = ConnectionHandler {
                 manager: manager.clone(),
                 identity,
+                exe,
+                admin_token: admin_token.clone(),
+                cli_exe: cli_exe.clone(),
+                admin: Arc::new(AtomicBool::new(false)),
                 connection_id: connection_id.clone(),
+                authenticated: Arc::new(RwLock::new(None)),
             };
 
             let connection_manager = manager.clone();
@@ -460,6 +640,193 @@ impl Default for BridgeManager {
     }
 }
 
+/// Write a new random admin token to `path`, readable only by the user,
+/// replacing the previous daemon's.
+fn create_admin_token(path: &Path) -> Result<String> {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill(&mut bytes);
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let _ = std::fs::remove_file(path);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(token.as_bytes())?;
+    Ok(token)
+}
+
+/// Whether the peer's `exe` is the daemon's own binary, `cli_exe`, which
+/// the `localgpt` CLI runs too. Linux reports a binary replaced by an
+/// upgrade as "<path> (deleted)", which still counts.
+fn is_cli_executable(exe: Option<&Path>, cli_exe: Option<&Path>) -> bool {
+    let path = |p: &Path| {
+        let p = p.to_string_lossy();
+        p.strip_suffix(" (deleted)").unwrap_or(&p).to_string()
+    };
+    match (exe, cli_exe) {
+        (Some(exe), Some(cli_exe)) => path(exe) == path(cli_exe),
+        _ => false,
+    }
+}
+
+/// The peer `identity`, running `exe`, asking for `bridge_id`. Reading the
+/// executable to fingerprint it happens off the async runtime.
+async fn bridge_peer(
+    bridge_id: &str,
+    identity: &PeerIdentity,
+    exe: Option<&Path>,
+) -> Result<BridgePeer, BridgeError> {
+    let bridge_id = bridge_id.to_string();
+    let exe = exe.map(Path::to_path_buf);
+    let pid = identity.pid;
+    tokio::task::spawn_blocking(move || BridgePeer::for_process(&bridge_id, exe.as_deref(), pid))
+        .await
+        .map_err(|e| BridgeError::Internal(e.to_string()))
+}
+
+/// IDs of the registered bridges, whether their secret is in an encrypted
+/// file or the OS keyring.
+pub fn registered_bridge_ids(data_dir: &Path) -> Vec<String> {
+    let mut ids = bridge_ids_with_suffix(data_dir, ".enc");
+    ids.extend(bridge_ids_with_suffix(data_dir, KEYRING_MARKER_SUFFIX));
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// IDs of the bridges whose secret is encrypted with the device key.
+pub fn encrypted_bridge_ids(data_dir: &Path) -> Vec<String> {
+    bridge_ids_with_suffix(data_dir, ".enc")
+}
+
+/// Marks a bridge whose secret is kept in the OS keyring as `bridge:<id>`;
+/// the keyring can't list its entries, so this is how we know it exists.
+const KEYRING_MARKER_SUFFIX: &str = ".keyring";
+
+fn bridge_ids_with_suffix(data_dir: &Path, suffix: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(data_dir.join("bridges")) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.strip_suffix(suffix).map(str::to_string)
+        })
+        .filter(|id| validate_bridge_id(id).is_ok())
+        .collect();
+    ids.sort();
+    ids
+}
+
+fn keyring_marker(bridges_dir: &Path, bridge_id: &str) -> std::path::PathBuf {
+    bridges_dir.join(format!("{}{}", bridge_id, KEYRING_MARKER_SUFFIX))
+}
+
+fn keyring_entry_name(bridge_id: &str) -> String {
+    format!("bridge:{}", bridge_id)
+}
+
+/// Store a bridge secret in the OS keyring, replacing an encrypted file
+/// saved by [`write_bridge_secret`].
+pub fn write_bridge_secret_to_keyring(
+    data_dir: &Path,
+    bridge_id: &str,
+    secret: &[u8],
+) -> Result<()> {
+    validate_bridge_id(bridge_id)?;
+    let bridges_dir = data_dir.join("bridges");
+    std::fs::create_dir_all(&bridges_dir)?;
+
+    set_keyring_bytes(&keyring_entry_name(bridge_id), secret)?;
+    std::fs::write(keyring_marker(&bridges_dir, bridge_id), "")?;
+    let _ = std::fs::remove_file(bridges_dir.join(format!("{}.enc", bridge_id)));
+    Ok(())
+}
+
+/// Encrypt a bridge secret with the device key and save it to disk.
+pub fn write_bridge_secret(data_dir: &Path, bridge_id: &str, secret: &[u8]) -> Result<()> {
+    validate_bridge_id(bridge_id)?;
+    let bridges_dir = data_dir.join("bridges");
+    std::fs::create_dir_all(&bridges_dir)?;
+
+    // 1. Get Master Key
+    let master_key = read_device_key(data_dir)?;
+
+    // 2. Derive Bridge Key = HMAC-SHA256(MasterKey, "bridge-key:" + bridge_id)
+    let bridge_key = derive_bridge_key(&master_key, bridge_id)?;
+
+    // 3. Encrypt Secret
+    let cipher = ChaCha20Poly1305::new(&bridge_key);
+
+    // Generate nonce manually to avoid rand_core version mismatch
+    let mut nonce_bytes = [0u8; 12];
+    rand::rng().fill(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, secret)
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+
+    // 4. Save to file: [Nonce (12 bytes)][Ciphertext]
+    let mut file_content = nonce_bytes.to_vec();
+    file_content.extend(ciphertext);
+
+    let file_path = bridges_dir.join(format!("{}.enc", bridge_id));
+    std::fs::write(&file_path, file_content)?;
+    let _ = std::fs::remove_file(keyring_marker(&bridges_dir, bridge_id));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(())
+}
+
+/// Load a bridge secret saved by [`write_bridge_secret`] (decrypting it) or
+/// [`write_bridge_secret_to_keyring`].
+pub fn read_bridge_secret(data_dir: &Path, bridge_id: &str) -> Result<Vec<u8>> {
+    validate_bridge_id(bridge_id)?;
+    let bridges_dir = data_dir.join("bridges");
+    if keyring_marker(&bridges_dir, bridge_id).exists() {
+        return get_keyring_bytes(&keyring_entry_name(bridge_id));
+    }
+    let file_path = bridges_dir.join(format!("{}.enc", bridge_id));
+
+    if !file_path.exists() {
+        anyhow::bail!("Credential file not found");
+    }
+
+    let file_content = std::fs::read(&file_path)?;
+    if file_content.len() < 12 {
+        anyhow::bail!("Invalid credential file format (too short)");
+    }
+
+    let (nonce_bytes, ciphertext) = file_content.split_at(12);
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    // Derive Key
+    let master_key = read_device_key(data_dir)?;
+    let bridge_key = derive_bridge_key(&master_key, bridge_id)?;
+
+    // Decrypt
+    let cipher = ChaCha20Poly1305::new(&bridge_key);
+    let plaintext = cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|e| anyhow::anyhow!("Decryption failed: {}", e))?;
+
+    Ok(plaintext)
+}
+
 fn derive_bridge_key(master_key: &[u8; 32], bridge_id: &str) -> Result<Key> {
     type HmacSha256 = Hmac<Sha256>;
     // Disambiguate Mac vs KeyInit
@@ -479,7 +846,17 @@ fn derive_bridge_key(master_key: &[u8; 32], bridge_id: &str) -> Result<Key> {
 struct ConnectionHandler {
     manager: BridgeManager,
     identity: PeerIdentity,
+    /// The peer's executable, which bridge approvals are bound to
+    exe: Option<PathBuf>,
+    /// The daemon's admin token, see `authenticate_admin`
+    admin_token: Arc<String>,
+    /// The daemon's own executable, the only one the admin token counts from
+    cli_exe: Option<PathBuf>,
+    /// The connection presented the admin token and may call every RPC
+    admin: Arc<AtomicBool>,
     connection_id: String,
+    /// Bridge ID the connection authenticated as, whose scopes apply
+    authenticated: Arc<RwLock<Option<String>>>,
 }
 
 impl BridgeService for ConnectionHandler {
@@ -501,9 +878,15 @@ impl BridgeService for ConnectionHandler {
         self.manager
             .update_active(&self.connection_id, Some(bridge_id.clone()))
             .await;
-        self.manager
-            .get_credentials_for(&bridge_id, &self.identity)
-            .await
+        if !self.is_admin() {
+            check_scope(&bridge_id, BridgeScope::Credentials)?;
+        }
+        let secret = self
+            .manager
+            .get_credentials_for(&bridge_id, &self.identity, self.exe.as_deref())
+            .await?;
+        *self.authenticated.write().await = Some(bridge_id);
+        Ok(secret)
     }
 
     async fn chat(
@@ -513,50 +896,47 @@ impl BridgeService for ConnectionHandler {
         message: String,
     ) -> Result<String, BridgeError> {
         self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Chat).await?;
         let support = self
             .manager
             .agent_support
             .as_ref()
             .ok_or_else(|| BridgeError::NotSupported("Agent support not available".into()))?;
 
-        let mut sessions = support.sessions.lock().await;
-
-        // Create session if it doesn't exist, using entry API to avoid unwrap
-        if let std::collections::hash_map::Entry::Vacant(entry) = sessions.entry(session_id.clone())
-        {
-            let agent_config = AgentConfig {
-                model: support.config.agent.default_model.clone(),
-                context_window: support.config.agent.context_window,
-                reserve_tokens: support.config.agent.reserve_tokens,
-            };
-            let mut agent = Agent::new(agent_config, &support.config, Arc::clone(&support.memory))
-                .await
-                .map_err(|e| BridgeError::Internal(format!("Failed to create agent: {}", e)))?;
-            agent
-                .new_session()
-                .await
-                .map_err(|e| BridgeError::Internal(format!("Failed to init session: {}", e)))?;
-            entry.insert(AgentSession { agent });
-        }
-
-        let session = sessions
-            .get_mut(&session_id)
-            .ok_or_else(|| BridgeError::Internal("Session unexpectedly missing".into()))?;
-        let response = session
-            .agent
-            .chat(&message)
-            .await
-            .map_err(|e| BridgeError::Internal(format!("Chat error: {}", e)))?;
-
-        if let Err(e) = session
-            .agent
-            .save_session_for_agent(BRIDGE_CLI_AGENT_ID)
-            .await
-        {
-            warn!("Failed to save bridge-cli session: {}", e);
-        }
+        // `/skill args` invokes a skill (see `list_skills`)
+        let message = if message.starts_with('/') {
+            let skills = load_skills(
+                &support.config.workspace_path(),
+                &support.config.skills.trust_policy(),
+            )
+            .unwrap_or_default();
+            let invocation = parse_skill_command(&message, &skills).and_then(|inv| {
+                skills
+                    .iter()
+                    .find(|s| s.name == inv.skill_name)
+                    .map(|skill| (skill, inv.args))
+            });
+            match invocation {
+                Some((skill, args)) => skill_invocation_prompt(skill, &args)
+                    .map_err(|e| BridgeError::Internal(e.to_string()))?,
+                None => message,
+            }
+        } else {
+            message
+        };
 
-        Ok(response)
+        // The pool resumes or creates the session and saves it after the turn
+        let cancel = CancellationToken::new();
+        support
+            .running_chats_lock()
+            .insert(session_id.clone(), cancel.clone());
+        let result = support
+            .sessions
+            .session(&session_id)
+            .chat_with_cancel(&message, cancel)
+            .await;
+        support.running_chats_lock().remove(&session_id);
+        result.map_err(|e| BridgeError::Internal(format!("Chat error: {}", e)))
     }
 
     async fn new_session(
@@ -565,34 +945,26 @@ impl BridgeService for ConnectionHandler {
         session_id: String,
     ) -> Result<String, BridgeError> {
         self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Chat).await?;
         let support = self
             .manager
             .agent_support
             .as_ref()
             .ok_or_else(|| BridgeError::NotSupported("Agent support not available".into()))?;
 
-        let mut sessions = support.sessions.lock().await;
-
-        let agent_config = AgentConfig {
-            model: support.config.agent.default_model.clone(),
-            context_window: support.config.agent.context_window,
-            reserve_tokens: support.config.agent.reserve_tokens,
-        };
-        let mut agent = Agent::new(agent_config, &support.config, Arc::clone(&support.memory))
-            .await
-            .map_err(|e| BridgeError::Internal(format!("Failed to create agent: {}", e)))?;
-        agent
+        let session = support.sessions.session(&session_id);
+        session
             .new_session()
             .await
             .map_err(|e| BridgeError::Internal(format!("Failed to init session: {}", e)))?;
-
-        let model = agent.model().to_string();
-        let chunks = agent.memory_chunk_count();
-        sessions.insert(session_id, AgentSession { agent });
+        let status = session
+            .status()
+            .await
+            .map_err(|e| BridgeError::Internal(format!("Failed to get status: {}", e)))?;
 
         Ok(format!(
             "New session created. Model: {} | Memory: {} chunks",
-            model, chunks
+            status.model, status.memory_chunks
         ))
     }
 
@@ -602,28 +974,26 @@ impl BridgeService for ConnectionHandler {
         session_id: String,
     ) -> Result<String, BridgeError> {
         self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Chat).await?;
         let support = self
             .manager
             .agent_support
             .as_ref()
             .ok_or_else(|| BridgeError::NotSupported("Agent support not available".into()))?;
 
-        let sessions = support.sessions.lock().await;
-        let session = sessions
-            .get(&session_id)
-            .ok_or_else(|| BridgeError::Internal("No active session".into()))?;
-
-        let status = session.agent.session_status();
+        let status = support
+            .sessions
+            .session(&session_id)
+            .status()
+            .await
+            .map_err(|e| BridgeError::Internal(format!("Failed to get status: {}", e)))?;
         let mut output = String::new();
-        output.push_str(&format!("Session ID: {}\n", status.id));
-        output.push_str(&format!("Model: {}\n", session.agent.model()));
+        output.push_str(&format!("Session ID: {}\n", status.session_id));
+        output.push_str(&format!("Model: {}\n", status.model));
         output.push_str(&format!("Messages: {}\n", status.message_count));
         output.push_str(&format!("Context tokens: ~{}\n", status.token_count));
         output.push_str(&format!("Compactions: {}\n", status.compaction_count));
-        output.push_str(&format!(
-            "Memory chunks: {}",
-            session.agent.memory_chunk_count()
-        ));
+        output.push_str(&format!("Memory chunks: {}", status.memory_chunks));
 
         if status.api_input_tokens > 0 || status.api_output_tokens > 0 {
             output.push_str(&format!(
@@ -642,20 +1012,18 @@ impl BridgeService for ConnectionHandler {
         model: String,
     ) -> Result<String, BridgeError> {
         self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Chat).await?;
         let support = self
             .manager
             .agent_support
             .as_ref()
             .ok_or_else(|| BridgeError::NotSupported("Agent support not available".into()))?;
 
-        let mut sessions = support.sessions.lock().await;
-        let session = sessions
-            .get_mut(&session_id)
-            .ok_or_else(|| BridgeError::Internal("No active session".into()))?;
-
-        session
-            .agent
+        support
+            .sessions
+            .session(&session_id)
             .set_model(&model)
+            .await
             .map_err(|e| BridgeError::Internal(format!("Failed to set model: {}", e)))?;
 
         Ok(format!("Switched to model: {}", model))
@@ -667,20 +1035,17 @@ impl BridgeService for ConnectionHandler {
         session_id: String,
     ) -> Result<String, BridgeError> {
         self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Chat).await?;
         let support = self
             .manager
             .agent_support
             .as_ref()
             .ok_or_else(|| BridgeError::NotSupported("Agent support not available".into()))?;
 
-        let mut sessions = support.sessions.lock().await;
-        let session = sessions
-            .get_mut(&session_id)
-            .ok_or_else(|| BridgeError::Internal("No active session".into()))?;
-
-        let (before, after) = session
-            .agent
-            .compact_session()
+        let (before, after) = support
+            .sessions
+            .session(&session_id)
+            .compact()
             .await
             .map_err(|e| BridgeError::Internal(format!("Failed to compact: {}", e)))?;
 
@@ -696,18 +1061,14 @@ impl BridgeService for ConnectionHandler {
         session_id: String,
     ) -> Result<String, BridgeError> {
         self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Chat).await?;
         let support = self
             .manager
             .agent_support
             .as_ref()
             .ok_or_else(|| BridgeError::NotSupported("Agent support not available".into()))?;
 
-        let mut sessions = support.sessions.lock().await;
-        let session = sessions
-            .get_mut(&session_id)
-            .ok_or_else(|| BridgeError::Internal("No active session".into()))?;
-
-        session.agent.clear_session();
+        support.sessions.session(&session_id).clear_session().await;
         Ok("Session cleared.".into())
     }
 
@@ -718,6 +1079,7 @@ impl BridgeService for ConnectionHandler {
         limit: u32,
     ) -> Result<String, BridgeError> {
         self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Memory).await?;
         let support = self
             .manager
             .agent_support
@@ -761,6 +1123,7 @@ impl BridgeService for ConnectionHandler {
 
     async fn memory_stats(self, _: context::Context) -> Result<String, BridgeError> {
         self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Memory).await?;
         let support = self
             .manager
             .agent_support
@@ -789,9 +1152,314 @@ impl BridgeService for ConnectionHandler {
 
         Ok(output)
     }
+
+    async fn list_skills(self, _: context::Context) -> Result<Vec<SkillCommand>, BridgeError> {
+        self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Chat).await?;
+        let support = self
+            .manager
+            .agent_support
+            .as_ref()
+            .ok_or_else(|| BridgeError::NotSupported("Agent support not available".into()))?;
+
+        let skills = load_skills(
+            &support.config.workspace_path(),
+            &support.config.skills.trust_policy(),
+        )
+        .map_err(|e| BridgeError::Internal(format!("Failed to load skills: {}", e)))?;
+
+        Ok(slash_command_skills(&skills)
+            .into_iter()
+            .map(|skill| SkillCommand {
+                name: skill.command_name.clone(),
+                description: skill.description.clone(),
+                usage: skill_usage(skill),
+            })
+            .collect())
+    }
+
+    async fn list_cron_jobs(self, _: context::Context) -> Result<Vec<CronJobStatus>, BridgeError> {
+        let store = self.cron_store().await?;
+        let jobs = store
+            .list()
+            .map_err(|e| BridgeError::Internal(format!("Failed to list cron jobs: {}", e)))?;
+
+        Ok(jobs
+            .into_iter()
+            .map(|info| CronJobStatus {
+                job: cron_job_spec(info.job),
+                source: info.source.as_str().to_string(),
+                next_run: info.next_run.map(|t| t.to_rfc3339()),
+                paused: info.paused,
+            })
+            .collect())
+    }
+
+    async fn add_cron_job(self, _: context::Context, job: CronJobSpec) -> Result<(), BridgeError> {
+        let store = self.cron_store().await?;
+        store
+            .add(CronJob {
+                name: job.name,
+                schedule: job.schedule,
+                prompt: job.prompt,
+                channel: job.channel,
+                enabled: job.enabled,
+                timeout: job.timeout,
+                catch_up: Default::default(),
+                jitter: None,
+                model: None,
+                tools: Vec::new(),
+                skills: Vec::new(),
+                memory_namespace: None,
+                retry: Default::default(),
+                trigger: None,
+            })
+            .map_err(|e| BridgeError::Internal(e.to_string()))
+    }
+
+    async fn update_cron_job(
+        self,
+        _: context::Context,
+        name: String,
+        changes: CronJobChanges,
+    ) -> Result<CronJobSpec, BridgeError> {
+        let store = self.cron_store().await?;
+        let update = CronJobUpdate {
+            schedule: changes.schedule,
+            prompt: changes.prompt,
+            channel: changes.channel,
+            enabled: changes.enabled,
+            timeout: changes.timeout,
+            catch_up: None,
+            jitter: None,
+            ..Default::default()
+        };
+        store
+            .update(&name, update)
+            .map(cron_job_spec)
+            .map_err(|e| BridgeError::Internal(e.to_string()))
+    }
+
+    async fn remove_cron_job(self, _: context::Context, name: String) -> Result<(), BridgeError> {
+        let store = self.cron_store().await?;
+        store
+            .remove(&name)
+            .map(|_| ())
+            .map_err(|e| BridgeError::Internal(e.to_string()))
+    }
+
+    async fn pause_cron_job(self, _: context::Context, name: String) -> Result<(), BridgeError> {
+        let store = self.cron_store().await?;
+        store
+            .pause(&name)
+            .map_err(|e| BridgeError::Internal(e.to_string()))
+    }
+
+    async fn resume_cron_job(self, _: context::Context, name: String) -> Result<(), BridgeError> {
+        let store = self.cron_store().await?;
+        store
+            .resume(&name)
+            .map_err(|e| BridgeError::Internal(e.to_string()))
+    }
+
+    async fn run_cron_job(self, _: context::Context, name: String) -> Result<(), BridgeError> {
+        let store = self.cron_store().await?;
+        store
+            .request_run(&name)
+            .map_err(|e| BridgeError::Internal(e.to_string()))
+    }
+
+    async fn bridge_status(self, _: context::Context) -> Result<BridgeStatusReport, BridgeError> {
+        self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Status).await?;
+        let paths = Paths::resolve().map_err(|e| BridgeError::Internal(e.to_string()))?;
+        let connections = self
+            .manager
+            .get_active_bridges()
+            .await
+            .into_iter()
+            .filter(|status| status.connection_id != self.connection_id)
+            .map(|status| BridgeConnection {
+                connection_id: status.connection_id,
+                bridge_id: status.bridge_id,
+                connected_at: status.connected_at.to_rfc3339(),
+                last_active: status.last_active.to_rfc3339(),
+                pid: status.pid,
+                uid: status.uid,
+                health: status.health.as_str().to_string(),
+                consecutive_failures: status.consecutive_failures,
+            })
+            .collect();
+        Ok(BridgeStatusReport {
+            connections,
+            registered: registered_bridge_ids(&paths.data_dir),
+        })
+    }
+
+    async fn cancel_chat(
+        self,
+        _: context::Context,
+        session_id: String,
+    ) -> Result<bool, BridgeError> {
+        self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Chat).await?;
+        let support = self
+            .manager
+            .agent_support
+            .as_ref()
+            .ok_or_else(|| BridgeError::NotSupported("Agent support not available".into()))?;
+
+        // Goes around the session's actor, which is busy with the chat
+        let token = support.running_chats_lock().get(&session_id).cloned();
+        Ok(match token {
+            Some(token) => {
+                info!("Bridge: cancelling chat on session {}", session_id);
+                token.cancel();
+                true
+            }
+            None => false,
+        })
+    }
+
+    async fn authenticate(
+        self,
+        _: context::Context,
+        bridge_id: String,
+    ) -> Result<Vec<String>, BridgeError> {
+        self.manager
+            .update_active(&self.connection_id, Some(bridge_id.clone()))
+            .await;
+        self.manager
+            .authenticate(&bridge_id, &self.identity, self.exe.as_deref())
+            .await?;
+        let scopes = load_scopes()?.scopes_for(&bridge_id);
+        info!(
+            "Connection {} authenticated as bridge '{}' ({})",
+            self.connection_id,
+            bridge_id,
+            join_scopes(&scopes)
+        );
+        *self.authenticated.write().await = Some(bridge_id);
+        Ok(scopes.iter().map(|s| s.as_str().to_string()).collect())
+    }
+
+    async fn authenticate_admin(
+        self,
+        _: context::Context,
+        token: String,
+    ) -> Result<(), BridgeError> {
+        self.manager.update_active(&self.connection_id, None).await;
+        if !constant_time_eq(token.as_bytes(), self.admin_token.as_bytes()) {
+            warn!(
+                "Connection {} presented a wrong admin token",
+                self.connection_id
+            );
+            return Err(BridgeError::AuthFailed("Invalid admin token".to_string()));
+        }
+        // Any process running as the user can read the token file, so a
+        // bridge presenting it still doesn't get admin
+        if !is_cli_executable(self.exe.as_deref(), self.cli_exe.as_deref()) {
+            warn!(
+                "Connection {} presented the admin token from {}",
+                self.connection_id,
+                self.exe
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| "an unknown executable".to_string())
+            );
+            return Err(BridgeError::AuthFailed(
+                "The admin token is only accepted from the localgpt CLI".to_string(),
+            ));
+        }
+        info!("Connection {} authenticated as admin", self.connection_id);
+        self.admin.store(true, Ordering::Relaxed);
+        Ok(())
+    }
 }
 
-fn validate_bridge_id(id: &str) -> Result<()> {
+impl ConnectionHandler {
+    fn is_admin(&self) -> bool {
+        self.admin.load(Ordering::Relaxed)
+    }
+
+    /// Fail unless the bridge this connection authenticated as has `scope`.
+    async fn require_scope(&self, scope: BridgeScope) -> Result<(), BridgeError> {
+        if self.is_admin() {
+            return Ok(());
+        }
+        let authenticated = self.authenticated.read().await.clone();
+        match authenticated {
+            Some(bridge_id) => check_scope(&bridge_id, scope),
+            None => {
+                warn!(
+                    "Refused a {} RPC from unauthenticated connection {} ({})",
+                    scope,
+                    self.connection_id,
+                    self.exe
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|| "unknown executable".to_string())
+                );
+                Err(BridgeError::AuthFailed(format!(
+                    "'{}' RPCs need an authenticated bridge; call authenticate or get_credentials first",
+                    scope
+                )))
+            }
+        }
+    }
+
+    /// Runtime cron job store; changes are picked up by the daemon's scheduler.
+    async fn cron_store(&self) -> Result<CronJobStore, BridgeError> {
+        self.manager.update_active(&self.connection_id, None).await;
+        self.require_scope(BridgeScope::Cron).await?;
+        let support = self
+            .manager
+            .agent_support
+            .as_ref()
+            .ok_or_else(|| BridgeError::NotSupported("Agent support not available".into()))?;
+
+        CronJobStore::from_config(&support.config)
+            .map_err(|e| BridgeError::Internal(format!("Failed to open cron jobs: {}", e)))
+    }
+}
+
+fn load_scopes() -> Result<BridgeScopes, BridgeError> {
+    let paths = Paths::resolve().map_err(|e| BridgeError::Internal(e.to_string()))?;
+    BridgeScopes::load(&paths.data_dir)
+        .map_err(|e| BridgeError::Internal(format!("Failed to read bridge scopes: {}", e)))
+}
+
+/// Fail unless `bridge_id` has `scope`, naming the command that grants it.
+fn check_scope(bridge_id: &str, scope: BridgeScope) -> Result<(), BridgeError> {
+    let scopes = load_scopes()?;
+    if scopes.allows(bridge_id, scope) {
+        return Ok(());
+    }
+    warn!("Refused a {} RPC from bridge '{}'", scope, bridge_id);
+    let mut granted = scopes.scopes_for(bridge_id);
+    granted.push(scope);
+    let granted: Vec<&str> = granted.iter().map(BridgeScope::as_str).collect();
+    Err(BridgeError::AuthFailed(format!(
+        "Bridge '{}' doesn't have the '{}' scope; grant it with `localgpt bridge scopes {} {}`",
+        bridge_id,
+        scope,
+        bridge_id,
+        granted.join(" ")
+    )))
+}
+
+fn cron_job_spec(job: CronJob) -> CronJobSpec {
+    CronJobSpec {
+        name: job.name,
+        schedule: job.schedule,
+        prompt: job.prompt,
+        channel: job.channel,
+        enabled: job.enabled,
+        timeout: job.timeout,
+    }
+}
+
+pub(crate) fn validate_bridge_id(id: &str) -> Result<()> {
     if id.is_empty() {
         anyhow::bail!("Bridge ID cannot be empty");
     }
@@ -823,6 +1491,61 @@ mod tests {
         assert_eq!(serde_json::to_string(&unhealthy).unwrap(), "\"unhealthy\"");
     }
 
+    #[test]
+    fn test_admin_token_is_replaced_at_each_start() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("locks").join("bridge-admin.token");
+
+        let first = create_admin_token(&path).unwrap();
+        assert_eq!(first.len(), 64);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), first);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let second = create_admin_token(&path).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), second);
+    }
+
+    #[test]
+    fn test_admin_only_from_cli_executable() {
+        let cli = Path::new("/usr/local/bin/localgpt");
+        assert!(is_cli_executable(Some(cli), Some(cli)));
+        assert!(is_cli_executable(
+            Some(Path::new("/usr/local/bin/localgpt (deleted)")),
+            Some(cli)
+        ));
+        assert!(!is_cli_executable(
+            Some(Path::new("/usr/local/bin/localgpt-bridge-telegram")),
+            Some(cli)
+        ));
+        assert!(!is_cli_executable(None, Some(cli)));
+        assert!(!is_cli_executable(Some(cli), None));
+    }
+
+    #[test]
+    fn test_registered_bridge_ids_include_keyring_markers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bridges = tmp.path().join("bridges");
+        std::fs::create_dir_all(&bridges).unwrap();
+        std::fs::write(bridges.join("telegram.enc"), b"x").unwrap();
+        std::fs::write(bridges.join("discord.keyring"), b"").unwrap();
+        std::fs::write(bridges.join("notes.txt"), b"").unwrap();
+
+        assert_eq!(
+            registered_bridge_ids(tmp.path()),
+            vec!["discord".to_string(), "telegram".to_string()]
+        );
+        assert_eq!(
+            encrypted_bridge_ids(tmp.path()),
+            vec!["telegram".to_string()]
+        );
+    }
+
     #[test]
     fn test_health_check_config_default() {
         let config = HealthCheckConfig::default();
@@ -869,7 +1592,9 @@ mod tests {
         }
 
         // Update active should reset health
-        manager.update_active("test-conn", Some("telegram".to_string())).await;
+        manager
+            .update_active("test-conn", Some("telegram".to_string()))
+            .await;
 
         let bridges = manager.get_active_bridges().await;
         assert_eq!(bridges[0].health, HealthStatus::Healthy);
@@ -0,0 +1,430 @@
+//! Approval of bridge processes before they get credentials.
+//!
+//! Any process running as the same user can connect to the bridge socket,
+//! so knowing a bridge ID isn't enough to get its credentials. The first
+//! time a bridge ID is asked for by an executable the daemon hasn't seen,
+//! the request is recorded as pending in `bridges/approvals.json` and
+//! refused until the user approves it with `localgpt bridge approve <id>`,
+//! or answers the prompt of a daemon running in a terminal.
+//!
+//! An approval covers one bridge ID from one executable, identified by a
+//! fingerprint of the executable's contents. The same bridge ID from
+//! another binary, or from a rebuilt one, needs its own approval. Where the
+//! executable can't be read, the fingerprint is `unknown`, which is never
+//! approved.
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use localgpt_core::paths::Paths;
+use localgpt_core::security::{AuditAction, append_audit_entry_with_detail};
+
+use super::bridge::validate_bridge_id;
+
+const APPROVALS_FILE: &str = "approvals.json";
+
+/// Fingerprint of a peer whose executable couldn't be read.
+pub const UNKNOWN_FINGERPRINT: &str = "unknown";
+
+/// A bridge ID asked for by a particular executable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgePeer {
+    pub bridge_id: String,
+    /// The connecting process's executable, if the OS reports it
+    pub exe: Option<PathBuf>,
+    /// First 16 hex digits of the SHA-256 of the executable's contents
+    pub fingerprint: String,
+}
+
+impl BridgePeer {
+    /// A peer running the file at `exe`.
+    pub fn new(bridge_id: &str, exe: Option<&Path>) -> Self {
+        Self::with_image(bridge_id, exe, exe)
+    }
+
+    /// The process `pid`, whose executable the OS reports as `exe`. On
+    /// Linux the fingerprint is read through `/proc/<pid>/exe`, the file
+    /// the process is actually running, even if `exe` has been replaced
+    /// or deleted since it started.
+    pub fn for_process(bridge_id: &str, exe: Option<&Path>, pid: Option<i32>) -> Self {
+        #[cfg(target_os = "linux")]
+        if let Some(pid) = pid {
+            let image = PathBuf::from(format!("/proc/{}/exe", pid));
+            return Self::with_image(bridge_id, exe, exe.map(|_| image.as_path()));
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = pid;
+        Self::new(bridge_id, exe)
+    }
+
+    fn with_image(bridge_id: &str, exe: Option<&Path>, image: Option<&Path>) -> Self {
+        let fingerprint = image
+            .and_then(file_fingerprint)
+            .unwrap_or_else(|| UNKNOWN_FINGERPRINT.to_string());
+        Self {
+            bridge_id: bridge_id.to_string(),
+            exe: exe.map(Path::to_path_buf),
+            fingerprint,
+        }
+    }
+
+    /// Whether the executable could be read; approvals need it.
+    pub fn is_known(&self) -> bool {
+        self.fingerprint != UNKNOWN_FINGERPRINT
+    }
+
+    /// The executable, for messages
+    pub fn exe_display(&self) -> String {
+        match &self.exe {
+            Some(exe) => exe.display().to_string(),
+            None => "an unknown executable".to_string(),
+        }
+    }
+}
+
+/// A peer allowed to fetch its bridge's credentials.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovedBridge {
+    #[serde(flatten)]
+    pub peer: BridgePeer,
+    pub approved_at: DateTime<Utc>,
+}
+
+/// A peer that asked for credentials and is waiting for the user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingBridge {
+    #[serde(flatten)]
+    pub peer: BridgePeer,
+    pub pid: Option<i32>,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// Asks the user whether a bridge may have its credentials.
+pub trait BridgeApprover: Send + Sync {
+    /// Called from a blocking thread.
+    fn ask(&self, request: &PendingBridge) -> bool;
+}
+
+/// Approved and pending bridge peers, stored in `bridges/approvals.json`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BridgeApprovals {
+    #[serde(default)]
+    pub approved: Vec<ApprovedBridge>,
+    #[serde(default)]
+    pub pending: Vec<PendingBridge>,
+}
+
+impl BridgeApprovals {
+    fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join("bridges").join(APPROVALS_FILE)
+    }
+
+    /// Load the approvals under `data_dir`; none if the file doesn't exist.
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        let path = Self::path(data_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        let path = Self::path(data_dir);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    pub fn is_approved(&self, peer: &BridgePeer) -> bool {
+        peer.is_known() && self.approved.iter().any(|a| same_peer(&a.peer, peer))
+    }
+
+    /// Record a request from `peer`. Returns it if it's new, or `None` if
+    /// the same peer was already waiting or its executable is unknown.
+    pub fn request(&mut self, peer: &BridgePeer, pid: Option<i32>) -> Option<PendingBridge> {
+        if !peer.is_known() || self.pending.iter().any(|p| same_peer(&p.peer, peer)) {
+            return None;
+        }
+        let request = PendingBridge {
+            peer: peer.clone(),
+            pid,
+            requested_at: Utc::now(),
+        };
+        self.pending.push(request.clone());
+        Some(request)
+    }
+
+    /// Approve `peer` directly, whether or not it asked. A peer with an
+    /// unknown executable is never approved.
+    pub fn approve_peer(&mut self, peer: &BridgePeer) {
+        self.pending.retain(|p| !same_peer(&p.peer, peer));
+        if peer.is_known() && !self.is_approved(peer) {
+            self.approved.push(ApprovedBridge {
+                peer: peer.clone(),
+                approved_at: Utc::now(),
+            });
+        }
+    }
+
+    /// Approve (or deny) the pending requests for `bridge_id`, only the one
+    /// with `fingerprint` if given. Returns the peers decided on.
+    pub fn resolve(
+        &mut self,
+        bridge_id: &str,
+        fingerprint: Option<&str>,
+        approved: bool,
+    ) -> Vec<BridgePeer> {
+        let (matching, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|p| {
+                p.peer.bridge_id == bridge_id
+                    && fingerprint.is_none_or(|fp| p.peer.fingerprint == fp)
+            });
+        self.pending = rest;
+        let peers: Vec<BridgePeer> = matching.into_iter().map(|p| p.peer).collect();
+        if approved {
+            for peer in &peers {
+                self.approve_peer(peer);
+            }
+        }
+        peers
+    }
+
+    /// Remove every approval and pending request for `bridge_id`.
+    pub fn revoke(&mut self, bridge_id: &str) -> Vec<BridgePeer> {
+        let mut removed: Vec<BridgePeer> = self
+            .approved
+            .iter()
+            .filter(|a| a.peer.bridge_id == bridge_id)
+            .map(|a| a.peer.clone())
+            .collect();
+        removed.extend(
+            self.pending
+                .iter()
+                .filter(|p| p.peer.bridge_id == bridge_id)
+                .map(|p| p.peer.clone()),
+        );
+        self.approved.retain(|a| a.peer.bridge_id != bridge_id);
+        self.pending.retain(|p| p.peer.bridge_id != bridge_id);
+        removed
+    }
+}
+
+/// First 16 hex digits of the SHA-256 of the file at `path`.
+fn file_fingerprint(path: &Path) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(format!("{:x}", hasher.finalize())[..16].to_string())
+}
+
+fn same_peer(a: &BridgePeer, b: &BridgePeer) -> bool {
+    a.bridge_id == b.bridge_id && a.fingerprint == b.fingerprint
+}
+
+/// Approve or deny the pending requests for `bridge_id` (see
+/// [`BridgeApprovals::resolve`]), recording each decision in the audit log.
+/// Fails if nothing matching was waiting.
+pub fn resolve_bridge_request(
+    paths: &Paths,
+    bridge_id: &str,
+    fingerprint: Option<&str>,
+    approved: bool,
+    by: &str,
+) -> Result<Vec<BridgePeer>> {
+    let mut approvals = BridgeApprovals::load(&paths.data_dir)?;
+    let peers = approvals.resolve(bridge_id, fingerprint, approved);
+    if peers.is_empty() {
+        bail!(
+            "No request from bridge '{}' is waiting for approval. Start the bridge; its first connection asks for approval.",
+            bridge_id
+        );
+    }
+    approvals.save(&paths.data_dir)?;
+
+    let action = if approved {
+        AuditAction::BridgeApproved
+    } else {
+        AuditAction::BridgeDenied
+    };
+    for peer in &peers {
+        audit_bridge(paths, action.clone(), by, peer);
+    }
+    Ok(peers)
+}
+
+/// Approve `exe` for `bridge_id` ahead of its first connection.
+pub fn approve_bridge_exe(
+    paths: &Paths,
+    bridge_id: &str,
+    exe: &Path,
+    by: &str,
+) -> Result<BridgePeer> {
+    validate_bridge_id(bridge_id)?;
+    let peer = BridgePeer::new(bridge_id, Some(exe));
+    if !peer.is_known() {
+        bail!("Can't read {} to fingerprint it", exe.display());
+    }
+    let mut approvals = BridgeApprovals::load(&paths.data_dir)?;
+    approvals.approve_peer(&peer);
+    approvals.save(&paths.data_dir)?;
+    audit_bridge(paths, AuditAction::BridgeApproved, by, &peer);
+    Ok(peer)
+}
+
+/// Remove every approval and pending request for `bridge_id`, recording
+/// the revocation in the audit log. Returns the peers removed.
+pub fn revoke_bridge(paths: &Paths, bridge_id: &str, by: &str) -> Result<Vec<BridgePeer>> {
+    let mut approvals = BridgeApprovals::load(&paths.data_dir)?;
+    let removed = approvals.revoke(bridge_id);
+    if !removed.is_empty() {
+        approvals.save(&paths.data_dir)?;
+        for peer in &removed {
+            audit_bridge(paths, AuditAction::BridgeRevoked, by, peer);
+        }
+    }
+    Ok(removed)
+}
+
+/// Record a bridge approval step in the security audit log (best effort).
+pub(crate) fn audit_bridge(paths: &Paths, action: AuditAction, by: &str, peer: &BridgePeer) {
+    let detail = format!(
+        "bridge '{}' from {} (fingerprint {})",
+        peer.bridge_id,
+        peer.exe_display(),
+        peer.fingerprint
+    );
+    if let Err(e) = append_audit_entry_with_detail(&paths.state_dir, action, "", by, Some(&detail))
+    {
+        warn!("Failed to record bridge approval in audit log: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// An executable named `name` in `dir` with `contents`.
+    fn exe(dir: &TempDir, name: &str, contents: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn peer(bridge_id: &str, exe: &Path) -> BridgePeer {
+        BridgePeer::new(bridge_id, Some(exe))
+    }
+
+    #[test]
+    fn fingerprint_depends_on_the_executable_contents() {
+        let dir = TempDir::new().unwrap();
+        let telegram = exe(&dir, "localgpt-bridge-telegram", "telegram v1");
+        let copy = exe(&dir, "copy", "telegram v1");
+        let rebuilt = exe(&dir, "rebuilt", "telegram v2");
+
+        let a = peer("telegram", &telegram);
+        assert_eq!(a.fingerprint.len(), 16);
+        assert!(a.is_known());
+        assert_eq!(a.fingerprint, peer("telegram", &copy).fingerprint);
+        assert_ne!(a.fingerprint, peer("telegram", &rebuilt).fingerprint);
+
+        assert_eq!(
+            BridgePeer::new("telegram", None).fingerprint,
+            UNKNOWN_FINGERPRINT
+        );
+        assert_eq!(
+            peer("telegram", &dir.path().join("missing")).fingerprint,
+            UNKNOWN_FINGERPRINT
+        );
+    }
+
+    #[test]
+    fn requests_wait_until_approved() {
+        let dir = TempDir::new().unwrap();
+        let mut approvals = BridgeApprovals::default();
+        let telegram = peer("telegram", &exe(&dir, "telegram", "telegram"));
+
+        assert!(!approvals.is_approved(&telegram));
+        assert!(approvals.request(&telegram, Some(42)).is_some());
+        assert!(approvals.request(&telegram, Some(43)).is_none());
+        assert!(!approvals.is_approved(&telegram));
+
+        let decided = approvals.resolve("telegram", None, true);
+        assert_eq!(decided, vec![telegram.clone()]);
+        assert!(approvals.is_approved(&telegram));
+        assert!(approvals.pending.is_empty());
+
+        // Another binary asking for the same bridge isn't covered
+        let other = peer("telegram", &exe(&dir, "evil", "evil"));
+        assert!(!approvals.is_approved(&other));
+    }
+
+    #[test]
+    fn unknown_executables_are_never_approved() {
+        let mut approvals = BridgeApprovals::default();
+        let unknown = BridgePeer::new("telegram", None);
+
+        assert!(approvals.request(&unknown, Some(42)).is_none());
+        assert!(approvals.pending.is_empty());
+        approvals.approve_peer(&unknown);
+        assert!(approvals.approved.is_empty());
+
+        // Not even with an approval written by an older version
+        approvals.approved.push(ApprovedBridge {
+            peer: unknown.clone(),
+            approved_at: Utc::now(),
+        });
+        assert!(!approvals.is_approved(&unknown));
+    }
+
+    #[test]
+    fn deny_and_revoke() {
+        let dir = TempDir::new().unwrap();
+        let mut approvals = BridgeApprovals::default();
+        let a = peer("discord", &exe(&dir, "a", "a"));
+        let b = peer("discord", &exe(&dir, "b", "b"));
+        approvals.request(&a, None);
+        approvals.request(&b, None);
+
+        let denied = approvals.resolve("discord", Some(&b.fingerprint), false);
+        assert_eq!(denied, vec![b.clone()]);
+        assert!(!approvals.is_approved(&b));
+        assert_eq!(approvals.pending.len(), 1);
+
+        approvals.resolve("discord", None, true);
+        assert!(approvals.is_approved(&a));
+        assert_eq!(approvals.revoke("discord"), vec![a.clone()]);
+        assert!(!approvals.is_approved(&a));
+    }
+
+    #[test]
+    fn approvals_round_trip_through_the_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let telegram = peer("telegram", &exe(&tmp, "telegram", "telegram"));
+
+        let mut approvals = BridgeApprovals::load(tmp.path()).unwrap();
+        approvals.approve_peer(&telegram);
+        approvals.save(tmp.path()).unwrap();
+
+        let loaded = BridgeApprovals::load(tmp.path()).unwrap();
+        assert!(loaded.is_approved(&telegram));
+        // Not mistaken for a registered bridge
+        assert!(super::super::registered_bridge_ids(tmp.path()).is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+//! Which bridge RPCs each bridge may call.
+//!
+//! Every bridge talks to the same socket and service, so without scopes a
+//! compromised Telegram bridge, which only needs its bot token, could also
+//! chat with the agent, search memory, and add cron jobs. Each bridge ID
+//! has a set of scopes, kept in `bridges/scopes.json` and changed with
+//! `localgpt bridge scopes <id>`. A connection gets the scopes of the
+//! bridge ID it authenticated as (with `get_credentials` or `authenticate`),
+//! which requires its executable to be approved for that ID.
+//!
+//! `get_version` and `ping` need no scope. A connection that presents the
+//! daemon's admin token with `authenticate_admin` (the `localgpt` CLI) may
+//! call everything; the token is new at each daemon start and kept in a
+//! file only the user can read. Any process of that user can read it, so
+//! admin also requires the peer to run the daemon's own executable. Scopes
+//! don't stop a compromised bridge from running `localgpt` itself.
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::warn;
+
+use localgpt_core::paths::Paths;
+use localgpt_core::security::{AuditAction, append_audit_entry_with_detail};
+
+use super::bridge::validate_bridge_id;
+
+const SCOPES_FILE: &str = "scopes.json";
+
+/// Bridge ID the `localgpt-bridge-cli` client authenticates as.
+pub const BRIDGE_CLI_ID: &str = "bridge-cli";
+
+/// A group of bridge RPCs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BridgeScope {
+    /// `get_credentials` for the bridge's own ID
+    Credentials,
+    /// Agent sessions: `chat`, `cancel_chat`, session commands, `list_skills`
+    Chat,
+    /// `memory_search` and `memory_stats`
+    Memory,
+    /// Listing, changing, and running cron jobs
+    Cron,
+    /// `bridge_status`
+    Status,
+    /// Every RPC
+    Admin,
+}
+
+impl BridgeScope {
+    pub const ALL: [BridgeScope; 6] = [
+        BridgeScope::Credentials,
+        BridgeScope::Chat,
+        BridgeScope::Memory,
+        BridgeScope::Cron,
+        BridgeScope::Status,
+        BridgeScope::Admin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BridgeScope::Credentials => "credentials",
+            BridgeScope::Chat => "chat",
+            BridgeScope::Memory => "memory",
+            BridgeScope::Cron => "cron",
+            BridgeScope::Status => "status",
+            BridgeScope::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for BridgeScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BridgeScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match Self::ALL.iter().find(|scope| scope.as_str() == s) {
+            Some(scope) => Ok(*scope),
+            None => bail!(
+                "Unknown bridge scope '{}' (expected one of: {})",
+                s,
+                join_scopes(&Self::ALL)
+            ),
+        }
+    }
+}
+
+/// Scopes for a bridge ID with none configured: chat and memory for the
+/// CLI bridge, only its credentials for any other bridge.
+pub fn default_scopes(bridge_id: &str) -> Vec<BridgeScope> {
+    if bridge_id == BRIDGE_CLI_ID {
+        vec![BridgeScope::Chat, BridgeScope::Memory]
+    } else {
+        vec![BridgeScope::Credentials]
+    }
+}
+
+/// Comma-separated scope names, for messages.
+pub fn join_scopes(scopes: &[BridgeScope]) -> String {
+    scopes
+        .iter()
+        .map(BridgeScope::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Scopes set for each bridge ID, stored in `bridges/scopes.json`. IDs
+/// missing from the file have [`default_scopes`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BridgeScopes {
+    #[serde(flatten)]
+    pub bridges: BTreeMap<String, Vec<BridgeScope>>,
+}
+
+impl BridgeScopes {
+    fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join("bridges").join(SCOPES_FILE)
+    }
+
+    /// Load the scopes under `data_dir`; all defaults if the file doesn't
+    /// exist.
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        let path = Self::path(data_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        let path = Self::path(data_dir);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// The scopes `bridge_id` has, configured or default.
+    pub fn scopes_for(&self, bridge_id: &str) -> Vec<BridgeScope> {
+        self.bridges
+            .get(bridge_id)
+            .cloned()
+            .unwrap_or_else(|| default_scopes(bridge_id))
+    }
+
+    /// Whether `bridge_id` may call the RPCs in `scope`.
+    pub fn allows(&self, bridge_id: &str, scope: BridgeScope) -> bool {
+        let scopes = self.scopes_for(bridge_id);
+        scopes.contains(&scope) || scopes.contains(&BridgeScope::Admin)
+    }
+
+    /// Replace the scopes of `bridge_id`; `None` goes back to the defaults.
+    pub fn set(&mut self, bridge_id: &str, scopes: Option<Vec<BridgeScope>>) {
+        match scopes {
+            Some(mut scopes) => {
+                scopes.sort();
+                scopes.dedup();
+                self.bridges.insert(bridge_id.to_string(), scopes);
+            }
+            None => {
+                self.bridges.remove(bridge_id);
+            }
+        }
+    }
+}
+
+/// Set (or, with `None`, reset) the scopes of `bridge_id`, recording the
+/// change in the audit log. Returns the scopes it now has. Connections
+/// already authenticated as `bridge_id` see the change on their next RPC.
+pub fn set_bridge_scopes(
+    paths: &Paths,
+    bridge_id: &str,
+    scopes: Option<Vec<BridgeScope>>,
+    by: &str,
+) -> Result<Vec<BridgeScope>> {
+    validate_bridge_id(bridge_id)?;
+    let mut all = BridgeScopes::load(&paths.data_dir)?;
+    all.set(bridge_id, scopes);
+    all.save(&paths.data_dir)?;
+
+    let scopes = all.scopes_for(bridge_id);
+    let detail = format!("bridge '{}' scopes: {}", bridge_id, join_scopes(&scopes));
+    if let Err(e) = append_audit_entry_with_detail(
+        &paths.state_dir,
+        AuditAction::BridgeScopesChanged,
+        "",
+        by,
+        Some(&detail),
+    ) {
+        warn!("Failed to record bridge scope change in audit log: {}", e);
+    }
+    Ok(scopes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_depend_on_the_bridge() {
+        let scopes = BridgeScopes::default();
+        assert!(scopes.allows("telegram", BridgeScope::Credentials));
+        assert!(!scopes.allows("telegram", BridgeScope::Chat));
+        assert!(scopes.allows(BRIDGE_CLI_ID, BridgeScope::Chat));
+        assert!(scopes.allows(BRIDGE_CLI_ID, BridgeScope::Memory));
+        assert!(!scopes.allows(BRIDGE_CLI_ID, BridgeScope::Credentials));
+        assert!(!scopes.allows(BRIDGE_CLI_ID, BridgeScope::Cron));
+    }
+
+    #[test]
+    fn admin_allows_everything() {
+        let mut scopes = BridgeScopes::default();
+        scopes.set("ops", Some(vec![BridgeScope::Admin]));
+        for scope in BridgeScope::ALL {
+            assert!(scopes.allows("ops", scope), "{}", scope);
+        }
+    }
+
+    #[test]
+    fn set_and_reset() {
+        let mut scopes = BridgeScopes::default();
+        scopes.set(
+            "discord",
+            Some(vec![
+                BridgeScope::Memory,
+                BridgeScope::Credentials,
+                BridgeScope::Memory,
+            ]),
+        );
+        assert_eq!(
+            scopes.scopes_for("discord"),
+            vec![BridgeScope::Credentials, BridgeScope::Memory]
+        );
+        scopes.set("discord", None);
+        assert_eq!(scopes.scopes_for("discord"), default_scopes("discord"));
+    }
+
+    #[test]
+    fn scopes_round_trip_through_the_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut scopes = BridgeScopes::load(tmp.path()).unwrap();
+        scopes.set("telegram", Some(vec![BridgeScope::Status]));
+        scopes.save(tmp.path()).unwrap();
+
+        let content = std::fs::read_to_string(tmp.path().join("bridges/scopes.json")).unwrap();
+        assert!(content.contains("\"telegram\""));
+        assert!(content.contains("\"status\""));
+
+        let loaded = BridgeScopes::load(tmp.path()).unwrap();
+        assert_eq!(loaded.scopes_for("telegram"), vec![BridgeScope::Status]);
+        assert!(super::super::registered_bridge_ids(tmp.path()).is_empty());
+    }
+
+    #[test]
+    fn scope_names_parse() {
+        for scope in BridgeScope::ALL {
+            assert_eq!(scope.as_str().parse::<BridgeScope>().unwrap(), scope);
+        }
+        assert!("everything".parse::<BridgeScope>().is_err());
+    }
+}
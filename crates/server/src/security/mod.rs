@@ -1,5 +1,17 @@
 //! Security features for the server (bridge management).
 
 pub mod bridge;
+pub mod bridge_approval;
+pub mod bridge_scopes;
 
-pub use bridge::{BridgeManager, BridgeStatus};
+pub use bridge::{
+    BridgeManager, BridgeStatus, encrypted_bridge_ids, read_bridge_secret, registered_bridge_ids,
+    write_bridge_secret, write_bridge_secret_to_keyring,
+};
+pub use bridge_approval::{
+    ApprovedBridge, BridgeApprovals, BridgeApprover, BridgePeer, PendingBridge,
+    approve_bridge_exe, resolve_bridge_request, revoke_bridge,
+};
+pub use bridge_scopes::{
+    BRIDGE_CLI_ID, BridgeScope, BridgeScopes, default_scopes, join_scopes, set_bridge_scopes,
+};
@@ -10,14 +10,20 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 use teloxide::prelude::*;
-use teloxide::types::{MessageId, ParseMode};
+use teloxide::types::{
+    BotCommand, InlineKeyboardButton, InlineKeyboardMarkup, MessageId, ParseMode, UpdateKind,
+};
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 
-use localgpt_core::agent::{Agent, AgentConfig, StreamEvent, extract_tool_detail, tools::Tool};
-use localgpt_core::concurrency::TurnGate;
+use localgpt_core::agent::{
+    Agent, AgentConfig, Skill, StreamEvent, extract_tool_detail, load_skills, parse_skill_command,
+    skill_invocation_prompt, slash_command_skills, tools::Tool,
+};
+use localgpt_core::concurrency::{CancellationToken, TurnGate, is_cancelled};
 use localgpt_core::config::Config;
 use localgpt_core::memory::MemoryManager;
+use localgpt_core::security::{PendingWrite, WriteApprover, add_write_approver, resolve_write};
 
 /// Agent ID for Telegram sessions
 const TELEGRAM_AGENT_ID: &str = "telegram";
@@ -28,6 +34,9 @@ const MAX_MESSAGE_LENGTH: usize = 4096;
 /// Debounce interval for message edits (seconds)
 const EDIT_DEBOUNCE_SECS: u64 = 2;
 
+/// Maximum number of commands Telegram accepts in the bot menu
+const MAX_BOT_COMMANDS: usize = 100;
+
 /// Factory function type for creating additional tools for the Telegram agent.
 /// This allows the caller (e.g., CLI daemon) to inject dangerous tools like bash, file I/O.
 pub type ToolFactory = Box<dyn Fn(&Config) -> Result<Vec<Box<dyn Tool>>> + Send + Sync>;
@@ -49,6 +58,10 @@ struct BotState {
     sessions: Mutex<HashMap<i64, SessionEntry>>,
     memory: MemoryManager,
     turn_gate: TurnGate,
+    /// Cancellation token of the turn running in each chat, for `/stop`
+    running_turns: std::sync::Mutex<HashMap<i64, CancellationToken>>,
+    /// Chat of each session, so protected-write approvals go to the right chat
+    session_chats: std::sync::Mutex<HashMap<String, i64>>,
     paired_user: Mutex<Option<PairedUser>>,
     pending_pairing_code: Mutex<Option<String>>,
     tool_factory: Option<ToolFactory>,
@@ -123,26 +136,30 @@ pub async fn run_telegram_bot(
         sessions: Mutex::new(HashMap::new()),
         memory,
         turn_gate,
+        running_turns: std::sync::Mutex::new(HashMap::new()),
+        session_chats: std::sync::Mutex::new(HashMap::new()),
         paired_user: Mutex::new(paired_user),
         pending_pairing_code: Mutex::new(None),
         tool_factory,
     });
 
     // Register bot commands so Telegram clients show the "/" menu
-    let commands: Vec<teloxide::types::BotCommand> = localgpt_core::commands::COMMANDS
-        .iter()
-        .filter(|c| c.supports(localgpt_core::commands::Interface::Telegram))
-        .map(|c| teloxide::types::BotCommand::new(c.name, c.description))
-        .collect();
-    if let Err(e) = bot.set_my_commands(commands).await {
-        warn!("Failed to set bot commands: {}", e);
-    }
+    register_bot_commands(&bot, config).await;
+
+    add_write_approver(Arc::new(TelegramWriteApprover {
+        bot: bot.clone(),
+        state: state.clone(),
+        runtime: tokio::runtime::Handle::current(),
+    }));
 
     info!("Starting Telegram bot...");
 
-    let handler = Update::filter_message().endpoint(handle_message);
+    let handler = dptree::entry()
+        .branch(Update::filter_message().endpoint(handle_message))
+        .branch(Update::filter_callback_query().endpoint(handle_callback));
 
     Dispatcher::builder(bot, handler)
+        .distribution_function(distribution_key)
         .default_handler(|_upd| async {})
         .dependencies(dptree::deps![state])
         .enable_ctrlc_handler()
@@ -153,6 +170,220 @@ pub async fn run_telegram_bot(
     Ok(())
 }
 
+/// Updates from a chat are handled one at a time, except `/stop` and
+/// approval buttons, which get their own lane so they aren't stuck behind the
+/// turn they should stop or unblock.
+fn distribution_key(update: &Update) -> Option<(ChatId, bool)> {
+    let chat = update.chat()?;
+    let priority = match &update.kind {
+        UpdateKind::Message(msg) => msg.text().is_some_and(|t| t.trim() == "/stop"),
+        UpdateKind::CallbackQuery(_) => true,
+        _ => false,
+    };
+    Some((chat.id, priority))
+}
+
+/// Callback data prefix of the approval buttons
+const WRITE_CALLBACK_PREFIX: &str = "write:";
+
+/// Asks the paired user, with Approve/Deny buttons, before an agent turn from
+/// Telegram writes to a protected file. The answer arrives as a callback
+/// query.
+struct TelegramWriteApprover {
+    bot: Bot,
+    state: Arc<BotState>,
+    runtime: tokio::runtime::Handle,
+}
+
+impl WriteApprover for TelegramWriteApprover {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    fn ask(&self, write: &PendingWrite) -> Option<bool> {
+        if write.channel != TELEGRAM_AGENT_ID {
+            return None;
+        }
+        let chat_id = {
+            let chats = self
+                .state
+                .session_chats
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            *chats.get(write.session_id.as_deref()?)?
+        };
+
+        let text = format!(
+            "{} wants to change protected file {}:\n\n{}",
+            write.tool,
+            write.path.display(),
+            write.preview
+        );
+        let buttons = InlineKeyboardMarkup::new([[
+            InlineKeyboardButton::callback(
+                "Approve",
+                format!("{}approve:{}", WRITE_CALLBACK_PREFIX, write.id),
+            ),
+            InlineKeyboardButton::callback(
+                "Deny",
+                format!("{}deny:{}", WRITE_CALLBACK_PREFIX, write.id),
+            ),
+        ]]);
+        let bot = self.bot.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = bot
+                .send_message(ChatId(chat_id), text)
+                .reply_markup(buttons)
+                .await
+            {
+                warn!("Failed to send write approval request: {}", e);
+            }
+        });
+        None
+    }
+}
+
+/// Handle a press of an approval button.
+async fn handle_callback(
+    bot: Bot,
+    query: CallbackQuery,
+    state: Arc<BotState>,
+) -> ResponseResult<()> {
+    let Some((action, id)) = query
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(WRITE_CALLBACK_PREFIX))
+        .and_then(|rest| rest.split_once(':'))
+    else {
+        return Ok(());
+    };
+
+    let authorized = state
+        .paired_user
+        .lock()
+        .await
+        .as_ref()
+        .is_some_and(|pu| pu.user_id == query.from.id.0);
+    if !authorized {
+        bot.answer_callback_query(query.id.clone())
+            .text("Not authorized.")
+            .await?;
+        return Ok(());
+    }
+
+    let approved = action == "approve";
+    let text = match resolve_write(id, approved, "telegram") {
+        Ok(write) => format!(
+            "{} {} to {}",
+            if approved { "Approved" } else { "Denied" },
+            write.tool,
+            write.path.display()
+        ),
+        Err(_) => "This request was already answered or has expired.".to_string(),
+    };
+    bot.answer_callback_query(query.id.clone()).await?;
+    if let Some(message) = query.regular_message() {
+        let _ = bot
+            .edit_message_text(message.chat.id, message.id, text)
+            .await;
+    }
+    Ok(())
+}
+
+/// Telegram command names allow only lowercase letters, digits and
+/// underscores, up to 32 characters.
+fn telegram_command_name(name: &str) -> Option<String> {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c == '-' {
+                '_'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '_')
+        .take(32)
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Core Telegram commands followed by user-invocable skills.
+fn bot_commands(skills: &[Skill]) -> Vec<BotCommand> {
+    let mut commands: Vec<BotCommand> = localgpt_core::commands::COMMANDS
+        .iter()
+        .filter(|c| c.supports(localgpt_core::commands::Interface::Telegram))
+        .map(|c| BotCommand::new(c.name, c.description))
+        .collect();
+
+    for skill in slash_command_skills(skills) {
+        if commands.len() >= MAX_BOT_COMMANDS {
+            warn!(
+                "Telegram command menu full, not registering remaining skills from {}",
+                skill.name
+            );
+            break;
+        }
+        let Some(name) = telegram_command_name(&skill.command_name) else {
+            continue;
+        };
+        if commands.iter().any(|c| c.command == name) {
+            continue;
+        }
+        let description = if skill.description.is_empty() {
+            format!("Run the {} skill", skill.name)
+        } else {
+            truncate_str(&skill.description, 256).to_string()
+        };
+        commands.push(BotCommand::new(name, description));
+    }
+
+    commands
+}
+
+/// Skills to offer in the command menu.
+fn command_skills(config: &Config) -> Vec<Skill> {
+    load_skills(&config.workspace_path(), &config.skills.trust_policy()).unwrap_or_else(|e| {
+        warn!("Failed to load skills for bot commands: {}", e);
+        Vec::new()
+    })
+}
+
+/// Register core commands and the currently loaded skills with Telegram.
+async fn register_bot_commands(bot: &Bot, config: &Config) {
+    if let Err(e) = bot
+        .set_my_commands(bot_commands(&command_skills(config)))
+        .await
+    {
+        warn!("Failed to set bot commands: {}", e);
+    }
+}
+
+/// Names of the commands Telegram has registered for the bot, and of those
+/// it should have for the current skills.
+pub async fn bot_command_names(token: &str, config: &Config) -> Result<(Vec<String>, Vec<String>)> {
+    let registered = Bot::new(token)
+        .get_my_commands()
+        .await?
+        .into_iter()
+        .map(|c| c.command)
+        .collect();
+    let expected = bot_commands(&command_skills(config))
+        .into_iter()
+        .map(|c| c.command)
+        .collect();
+    Ok((registered, expected))
+}
+
+/// Replace the bot's command menu with the core commands and current
+/// skills. Returns how many commands were registered.
+pub async fn reregister_bot_commands(token: &str, config: &Config) -> Result<usize> {
+    let commands = bot_commands(&command_skills(config));
+    let count = commands.len();
+    Bot::new(token).set_my_commands(commands).await?;
+    Ok(count)
+}
+
 async fn handle_message(bot: Bot, msg: Message, state: Arc<BotState>) -> ResponseResult<()> {
     let text = match msg.text() {
         Some(t) => t.to_string(),
@@ -286,6 +517,22 @@ async fn handle_command(
             );
             bot.send_message(chat_id, &help).await?;
         }
+        "/stop" => {
+            let token = state
+                .running_turns
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&chat_id.0)
+                .cloned();
+            let reply = match token {
+                Some(token) => {
+                    token.cancel();
+                    "Stopping..."
+                }
+                None => "Nothing is running.",
+            };
+            bot.send_message(chat_id, reply).await?;
+        }
         "/new" => {
             let mut sessions = state.sessions.lock().await;
             sessions.remove(&chat_id.0);
@@ -434,7 +681,10 @@ async fn handle_command(
         }
         "/skills" => {
             let workspace_path = state.config.workspace_path();
-            match localgpt_core::agent::load_skills(&workspace_path) {
+            match localgpt_core::agent::load_skills(
+                &workspace_path,
+                &state.config.skills.trust_policy(),
+            ) {
                 Ok(skills) => {
                     if skills.is_empty() {
                         bot.send_message(chat_id, "No skills installed.").await?;
@@ -464,11 +714,33 @@ async fn handle_command(
             .await?;
         }
         _ => {
-            bot.send_message(
-                chat_id,
-                "Unknown command. Use /help for available commands.",
+            // Skill commands: `/skill_name args`
+            let skills = load_skills(
+                &state.config.workspace_path(),
+                &state.config.skills.trust_policy(),
             )
-            .await?;
+            .unwrap_or_default();
+            let invocation = parse_skill_command(text, &skills).and_then(|inv| {
+                skills
+                    .iter()
+                    .find(|s| s.name == inv.skill_name)
+                    .map(|skill| (skill, inv.args))
+            });
+            match invocation {
+                Some((skill, args)) => match skill_invocation_prompt(skill, &args) {
+                    Ok(prompt) => return handle_chat(bot, chat_id, state, &prompt).await,
+                    Err(e) => {
+                        bot.send_message(chat_id, e.to_string()).await?;
+                    }
+                },
+                None => {
+                    bot.send_message(
+                        chat_id,
+                        "Unknown command. Use /help for available commands.",
+                    )
+                    .await?;
+                }
+            }
         }
     }
 
@@ -663,7 +935,15 @@ async fn handle_chat(
     let msg_id = thinking_msg.id;
 
     // Acquire turn gate
-    let _gate_permit = state.turn_gate.acquire().await;
+    let _gate_permit = match state.turn_gate.acquire("telegram").await {
+        Ok(permit) => permit,
+        Err(err) => {
+            let _ = bot
+                .edit_message_text(chat_id, msg_id, err.to_string())
+                .await;
+            return Ok(());
+        }
+    };
 
     // Get or create agent session, then stream response
     let mut sessions = state.sessions.lock().await;
@@ -678,6 +958,8 @@ async fn handle_chat(
         let memory = std::sync::Arc::new(state.memory.clone());
         match Agent::new(agent_config, &state.config, memory).await {
             Ok(mut agent) => {
+                agent.set_channel(TELEGRAM_AGENT_ID);
+
                 // Extend agent with additional tools from factory if provided (e.g., CLI tools from daemon)
                 if let Some(ref factory) = state.tool_factory {
                     match factory(&state.config) {
@@ -698,6 +980,9 @@ async fn handle_chat(
                     return Ok(());
                 }
 
+                // Refresh the "/" menu with the skills this session loaded
+                register_bot_commands(bot, &state.config).await;
+
                 // Send welcome message on first run
                 let is_brand_new = agent.is_brand_new();
                 if is_brand_new {
@@ -726,6 +1011,20 @@ async fn handle_chat(
     let entry = sessions.get_mut(&chat_id.0).unwrap();
     entry.last_accessed = Instant::now();
 
+    let before = entry.agent.session_status();
+    let cancel = CancellationToken::new();
+    entry.agent.set_cancel_token(cancel.clone());
+    state
+        .running_turns
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(chat_id.0, cancel);
+    state
+        .session_chats
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(before.id.clone(), chat_id.0);
+
     // Use streaming with tools
     let response = match entry.agent.chat_stream_with_tools(text, Vec::new()).await {
         Ok(event_stream) => {
@@ -777,6 +1076,10 @@ async fn handle_chat(
                         }
                     }
                     Ok(StreamEvent::Done) => break,
+                    Err(e) if is_cancelled(&e) => {
+                        full_response.push_str("\n\n(stopped)");
+                        break;
+                    }
                     Err(e) => {
                         error!("Stream error: {}", e);
                         full_response.push_str(&format!("\n\nError: {}", e));
@@ -794,6 +1097,16 @@ async fn handle_chat(
         Err(e) => format!("Error: {}", e),
     };
 
+    state
+        .running_turns
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&chat_id.0);
+    // A stopped turn is dropped from the session
+    if entry.agent.is_cancelled() {
+        entry.agent.rewind_session(&before);
+    }
+
     // Save session before releasing lock
     if let Err(e) = entry.agent.save_session_for_agent(TELEGRAM_AGENT_ID).await {
         debug!("Failed to save telegram session: {}", e);
@@ -1,60 +1,611 @@
-//! WebSocket support for real-time communication
-//! TODO: Implement WebSocket endpoints for streaming responses
+//! WebSocket chat at `/api/ws`.
+//!
+//! Every frame is a JSON text message tagged by `type`.
+//!
+//! Client → server:
+//! - `attach` `{session_id?}` — attach to an active or saved session, or
+//!   start a new one when `session_id` is omitted (`session` is an alias)
+//! - `chat` `{message}` — run a turn, starting a session first if needed
+//! - `cancel` — stop the running turn and drop it from the session; so does
+//!   `POST /api/sessions/{id}/cancel` from another connection
+//! - `approval` `{id, approved}` — answer an `approval_request`
+//! - `ping`
+//!
+//! Server → client:
+//! - `attached` `{session_id, model, message_count}`
+//! - `queued` `{position}` — the model is at its concurrency limit; sent as
+//!   the turn's place in the queue changes
+//! - `content` `{delta}`
+//! - `tool_start` `{id, name, detail, arguments}`, `tool_end`
+//!   `{id, name, output, warnings, duration_ms}`
+//! - `approval_request` `{id, name, detail, arguments}` — a tool listed in
+//!   `tools.require_approval` is about to run; the turn waits for `approval`,
+//!   and no answer within five minutes counts as a denial
+//! - `usage` `{input_tokens, output_tokens}` — tokens spent on the turn
+//! - `done`, `cancelled` `{reason}`, or `error` `{code, message}` end a turn
+//! - `pong`
 
-#![allow(dead_code)]
+use std::collections::HashMap;
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use axum::Extension;
+use axum::extract::State;
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use tracing::{debug, info};
+use futures::stream::{SplitSink, SplitStream, Stream};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use localgpt_core::agent::{StreamEvent, extract_tool_detail};
+use localgpt_core::concurrency::is_cancelled;
+
+use crate::http::{
+    AppError, AppState, Caller, SessionEntry, get_or_create_session, lock_turn,
+    resume_saved_session, turn_model, usage_since,
+};
+use crate::model_queue::{Admission, ModelPermit, QueueProgress};
+
+/// Characters of tool output included in `tool_end` frames
+const TOOL_OUTPUT_PREVIEW_CHARS: usize = 500;
+/// Characters of tool arguments included in `tool_start` and
+/// `approval_request` frames
+const TOOL_ARGUMENTS_PREVIEW_CHARS: usize = 2000;
+/// How long a turn waits for the client to answer an `approval_request`
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Frames sent by the client
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientFrame {
+    #[serde(alias = "session")]
+    Attach {
+        session_id: Option<String>,
+    },
+    Chat {
+        message: String,
+    },
+    Cancel,
+    Approval {
+        id: String,
+        approved: bool,
+    },
+    Ping,
+}
+
+/// Frames sent by the server
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerFrame {
+    Attached {
+        session_id: String,
+        model: String,
+        message_count: usize,
+    },
+    Queued {
+        position: usize,
+    },
+    Content {
+        delta: String,
+    },
+    ToolStart {
+        id: String,
+        name: String,
+        detail: Option<String>,
+        arguments: String,
+    },
+    ToolEnd {
+        id: String,
+        name: String,
+        output: String,
+        warnings: Vec<String>,
+        duration_ms: Option<u64>,
+    },
+    ApprovalRequest {
+        id: String,
+        name: String,
+        detail: Option<String>,
+        arguments: String,
+    },
+    Usage {
+        input_tokens: u64,
+        output_tokens: u64,
+    },
+    Done,
+    Cancelled {
+        reason: String,
+    },
+    Error {
+        code: &'static str,
+        message: String,
+    },
+    Pong,
+}
+
+impl ServerFrame {
+    fn error(code: &'static str, message: impl Into<String>) -> Self {
+        ServerFrame::Error {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// How a turn ended
+enum TurnEnd {
+    Completed,
+    /// The agent failed; an `error` frame was sent
+    Failed,
+    Cancelled(String),
+    Disconnected,
+}
+
+/// The client's answer to an `approval_request`
+enum Approval {
+    Approved,
+    Denied,
+    TimedOut,
+    Cancelled,
+    Disconnected,
+}
 
 /// WebSocket upgrade handler
-pub async fn ws_handler(ws: WebSocketUpgrade) -> impl IntoResponse {
-    ws.on_upgrade(handle_socket)
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+) -> impl IntoResponse {
+    ws.on_upgrade(|socket| handle_socket(socket, state, caller))
+}
+
+async fn handle_socket(socket: WebSocket, state: Arc<AppState>, caller: Caller) {
+    let (sender, mut receiver) = socket.split();
+    let mut conn = Connection {
+        state,
+        caller,
+        sender,
+        session_id: None,
+    };
+    debug!("WebSocket client connected");
+
+    while let Some(frame) = conn.next_frame(&mut receiver).await {
+        let open = match frame {
+            ClientFrame::Attach { session_id } => conn.attach(session_id).await,
+            ClientFrame::Chat { message } => conn.chat(&message, &mut receiver).await,
+            ClientFrame::Cancel => {
+                conn.send(ServerFrame::error("no_turn", "No turn is running"))
+                    .await
+            }
+            ClientFrame::Approval { .. } => {
+                conn.send(ServerFrame::error(
+                    "no_pending_approval",
+                    "No tool call is waiting for approval",
+                ))
+                .await
+            }
+            ClientFrame::Ping => conn.send(ServerFrame::Pong).await,
+        };
+        if !open {
+            break;
+        }
+    }
+
+    debug!("WebSocket connection closed");
 }
 
-/// Handle WebSocket connection
-async fn handle_socket(mut socket: WebSocket) {
-    info!("WebSocket connection established");
+/// One client connection and the session it is attached to
+struct Connection {
+    state: Arc<AppState>,
+    caller: Caller,
+    sender: SplitSink<WebSocket, Message>,
+    session_id: Option<String>,
+}
 
-    while let Some(msg) = socket.recv().await {
-        let msg = match msg {
-            Ok(msg) => msg,
+impl Connection {
+    /// Send a frame. Returns false once the socket is closed.
+    async fn send(&mut self, frame: ServerFrame) -> bool {
+        let json = match serde_json::to_string(&frame) {
+            Ok(json) => json,
             Err(e) => {
-                debug!("WebSocket error: {}", e);
-                break;
+                debug!("Failed to serialize WebSocket frame: {}", e);
+                return true;
             }
         };
+        match self.sender.send(Message::Text(json.into())).await {
+            Ok(()) => true,
+            Err(e) => {
+                debug!("Failed to send: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Next frame from the client, answering pings and reporting malformed
+    /// frames along the way. `None` once the client disconnects.
+    async fn next_frame(&mut self, receiver: &mut SplitStream<WebSocket>) -> Option<ClientFrame> {
+        loop {
+            let text = match receiver.next().await? {
+                Ok(Message::Text(text)) => text,
+                Ok(Message::Ping(data)) => {
+                    if self.sender.send(Message::Pong(data)).await.is_err() {
+                        return None;
+                    }
+                    continue;
+                }
+                Ok(Message::Close(_)) => {
+                    debug!("WebSocket client disconnected");
+                    return None;
+                }
+                Ok(_) => continue,
+                Err(e) => {
+                    debug!("WebSocket error: {}", e);
+                    return None;
+                }
+            };
 
-        match msg {
-            Message::Text(text) => {
-                debug!("Received text: {}", text);
+            match serde_json::from_str(&text) {
+                Ok(frame) => return Some(frame),
+                Err(e) => {
+                    let error =
+                        ServerFrame::error("invalid_frame", format!("Invalid frame: {}", e));
+                    if !self.send(error).await {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
 
-                // Echo for now
-                // TODO: Implement proper message handling
-                if let Err(e) = socket
-                    .send(Message::Text(format!("Echo: {}", text).into()))
+    /// Attach to `session_id` (resuming it from disk if needed), or to a new
+    /// session.
+    async fn attach(&mut self, session_id: Option<String>) -> bool {
+        let result = match session_id {
+            Some(id) => resume_saved_session(&self.state, &id, &self.caller).await,
+            None => get_or_create_session(&self.state, None, &self.caller).await,
+        };
+        let session_id = match result {
+            Ok(id) => id,
+            Err(AppError(status, message)) => {
+                let code = match status {
+                    StatusCode::NOT_FOUND => "session_not_found",
+                    StatusCode::BAD_REQUEST => "invalid_session_id",
+                    _ => "session_error",
+                };
+                return self.send(ServerFrame::error(code, message)).await;
+            }
+        };
+
+        let state = self.state.clone();
+        let sessions = state.sessions.lock().await;
+        let Some(entry) = sessions.get(&session_id) else {
+            drop(sessions);
+            return self
+                .send(ServerFrame::error("session_not_found", "Session not found"))
+                .await;
+        };
+        let attached = ServerFrame::Attached {
+            session_id: session_id.clone(),
+            model: entry.agent.model().to_string(),
+            message_count: entry.agent.session_status().message_count,
+        };
+        drop(sessions);
+
+        self.session_id = Some(session_id);
+        self.send(attached).await
+    }
+
+    /// Run one chat turn in the attached session.
+    async fn chat(&mut self, message: &str, receiver: &mut SplitStream<WebSocket>) -> bool {
+        if self.session_id.is_none() && !self.attach(None).await {
+            return false;
+        }
+        let Some(session_id) = self.session_id.clone() else {
+            // Attaching failed and the client was told why
+            return true;
+        };
+
+        debug!("WebSocket chat [{}]: {}", session_id, message);
+
+        let state = self.state.clone();
+
+        let model = turn_model(&state, &session_id, None).await;
+        let _model_permit = match self.await_slot(&model, receiver).await {
+            Ok(permit) => permit,
+            Err(TurnEnd::Cancelled(reason)) => {
+                return self.send(ServerFrame::Cancelled { reason }).await;
+            }
+            Err(TurnEnd::Disconnected) => return false,
+            Err(_) => return true,
+        };
+
+        let _locks = match lock_turn(&state).await {
+            Ok(locks) => locks,
+            Err(AppError(status, message)) => {
+                let code = if status == StatusCode::SERVICE_UNAVAILABLE {
+                    "busy"
+                } else {
+                    "workspace_locked"
+                };
+                return self.send(ServerFrame::error(code, message)).await;
+            }
+        };
+
+        // Hold the session outside the map, so other sessions aren't held up
+        // while this turn streams or waits for an approval
+        let mut entry = match state.check_out_session(&session_id, &self.caller).await {
+            Ok(entry) => entry,
+            Err(AppError(StatusCode::CONFLICT, message)) => {
+                return self.send(ServerFrame::error("session_busy", message)).await;
+            }
+            Err(_) => {
+                self.session_id = None;
+                return self
+                    .send(ServerFrame::error(
+                        "session_not_found",
+                        "Session expired or was deleted; attach again",
+                    ))
+                    .await;
+            }
+        };
+        let open = self
+            .run_chat(&session_id, &mut entry, message, receiver)
+            .await;
+        state.check_in_session(&session_id, entry).await;
+        open
+    }
+
+    /// Run a turn on a checked-out session and report how it ended.
+    async fn run_chat(
+        &mut self,
+        session_id: &str,
+        entry: &mut SessionEntry,
+        message: &str,
+        receiver: &mut SplitStream<WebSocket>,
+    ) -> bool {
+        let state = self.state.clone();
+        entry.last_accessed = Instant::now();
+        entry.dirty = true;
+
+        let before = entry.agent.session_status();
+        let usage_before = entry.agent.usage().clone();
+        let approval_tools = entry.agent.approval_required_tools().to_vec();
+        let turn = state.begin_turn(session_id, &self.caller, &mut entry.agent);
+
+        let end = match entry
+            .agent
+            .chat_stream_with_tools(message, Vec::new())
+            .await
+        {
+            Ok(stream) => self.run_turn(stream, &approval_tools, receiver).await,
+            Err(e) => {
+                if !self
+                    .send(ServerFrame::error("agent_error", e.to_string()))
                     .await
                 {
-                    debug!("Failed to send: {}", e);
-                    break;
+                    return false;
                 }
+                TurnEnd::Failed
             }
-            Message::Binary(data) => {
-                debug!("Received binary: {} bytes", data.len());
+        };
+        drop(turn);
+
+        // Tokens are spent whether or not the turn completes
+        let usage = usage_since(entry.agent.usage(), &usage_before);
+        state.record_usage(&self.caller, &usage);
+
+        match end {
+            TurnEnd::Completed => {
+                let usage = ServerFrame::Usage {
+                    input_tokens: usage.input_tokens,
+                    output_tokens: usage.output_tokens,
+                };
+                self.send(usage).await && self.send(ServerFrame::Done).await
             }
-            Message::Ping(data) => {
-                if let Err(e) = socket.send(Message::Pong(data)).await {
-                    debug!("Failed to send pong: {}", e);
-                    break;
+            TurnEnd::Failed => true,
+            TurnEnd::Cancelled(reason) => {
+                entry.agent.rewind_session(&before);
+                self.send(ServerFrame::Cancelled { reason }).await
+            }
+            TurnEnd::Disconnected => {
+                entry.agent.rewind_session(&before);
+                false
+            }
+        }
+    }
+
+    /// Wait for a free slot on `model`, sending `queued` frames while the
+    /// turn waits. The client may cancel in the meantime.
+    async fn await_slot(
+        &mut self,
+        model: &str,
+        receiver: &mut SplitStream<WebSocket>,
+    ) -> Result<ModelPermit, TurnEnd> {
+        let ticket = match self.state.model_queue.admit(model) {
+            Admission::Ready(permit) => return Ok(permit),
+            Admission::Queued(ticket) => ticket,
+        };
+        let mut progress = pin!(ticket.progress());
+
+        loop {
+            tokio::select! {
+                update = progress.next() => {
+                    let frame = match update {
+                        Some(QueueProgress::Position(position)) => ServerFrame::Queued { position },
+                        Some(QueueProgress::Done(Ok(permit))) => return Ok(permit),
+                        Some(QueueProgress::Done(Err(e))) => {
+                            self.send(ServerFrame::error("queue_timeout", e.to_string())).await;
+                            return Err(TurnEnd::Failed);
+                        }
+                        None => return Err(TurnEnd::Failed),
+                    };
+                    if !self.send(frame).await {
+                        return Err(TurnEnd::Disconnected);
+                    }
+                }
+                frame = self.next_frame(receiver) => {
+                    let reply = match frame {
+                        None => return Err(TurnEnd::Disconnected),
+                        Some(ClientFrame::Cancel) => {
+                            return Err(TurnEnd::Cancelled("cancelled by client".to_string()));
+                        }
+                        Some(ClientFrame::Ping) => ServerFrame::Pong,
+                        Some(_) => ServerFrame::error(
+                            "turn_in_progress",
+                            "A turn is queued; wait for it to start or send cancel",
+                        ),
+                    };
+                    if !self.send(reply).await {
+                        return Err(TurnEnd::Disconnected);
+                    }
                 }
             }
-            Message::Pong(_) => {}
-            Message::Close(_) => {
-                info!("WebSocket closed by client");
-                break;
+        }
+    }
+
+    /// Forward the turn's events to the client while listening for `cancel`.
+    /// Tools that need approval don't run until the client approves them.
+    async fn run_turn(
+        &mut self,
+        stream: impl Stream<Item = anyhow::Result<StreamEvent>>,
+        approval_tools: &[String],
+        receiver: &mut SplitStream<WebSocket>,
+    ) -> TurnEnd {
+        let mut stream = pin!(stream);
+        let mut started: HashMap<String, Instant> = HashMap::new();
+
+        loop {
+            tokio::select! {
+                event = stream.next() => {
+                    let frame = match event {
+                        None => return TurnEnd::Completed,
+                        Some(Ok(StreamEvent::Content(delta))) => ServerFrame::Content { delta },
+                        Some(Ok(StreamEvent::ToolCallStart { name, id, arguments })) => {
+                            let detail = extract_tool_detail(&name, &arguments);
+                            let arguments: String =
+                                arguments.chars().take(TOOL_ARGUMENTS_PREVIEW_CHARS).collect();
+                            if approval_tools.contains(&name) {
+                                let request = ServerFrame::ApprovalRequest {
+                                    id: id.clone(),
+                                    name: name.clone(),
+                                    detail: detail.clone(),
+                                    arguments: arguments.clone(),
+                                };
+                                if !self.send(request).await {
+                                    return TurnEnd::Disconnected;
+                                }
+                                match self.await_approval(&id, receiver).await {
+                                    Approval::Approved => {}
+                                    Approval::Denied => {
+                                        return TurnEnd::Cancelled(format!("{} was not approved", name));
+                                    }
+                                    Approval::TimedOut => {
+                                        return TurnEnd::Cancelled(format!(
+                                            "{} was not approved within {} seconds",
+                                            name,
+                                            APPROVAL_TIMEOUT.as_secs()
+                                        ));
+                                    }
+                                    Approval::Cancelled => {
+                                        return TurnEnd::Cancelled("cancelled by client".to_string());
+                                    }
+                                    Approval::Disconnected => return TurnEnd::Disconnected,
+                                }
+                            }
+                            started.insert(id.clone(), Instant::now());
+                            ServerFrame::ToolStart { id, name, detail, arguments }
+                        }
+                        Some(Ok(StreamEvent::ToolCallEnd { name, id, output, warnings })) => {
+                            let duration_ms = started
+                                .remove(&id)
+                                .map(|start| start.elapsed().as_millis() as u64);
+                            ServerFrame::ToolEnd {
+                                id,
+                                name,
+                                output: output.chars().take(TOOL_OUTPUT_PREVIEW_CHARS).collect(),
+                                warnings,
+                                duration_ms,
+                            }
+                        }
+                        // `done` is sent after the usage, once the stream ends
+                        Some(Ok(StreamEvent::Done)) => continue,
+                        Some(Err(e)) if is_cancelled(&e) => {
+                            return TurnEnd::Cancelled("cancelled".to_string());
+                        }
+                        Some(Err(e)) => {
+                            self.send(ServerFrame::error("agent_error", e.to_string())).await;
+                            return TurnEnd::Failed;
+                        }
+                    };
+                    if !self.send(frame).await {
+                        return TurnEnd::Disconnected;
+                    }
+                }
+                frame = self.next_frame(receiver) => {
+                    let reply = match frame {
+                        None => return TurnEnd::Disconnected,
+                        Some(ClientFrame::Cancel) => {
+                            return TurnEnd::Cancelled("cancelled by client".to_string());
+                        }
+                        Some(ClientFrame::Ping) => ServerFrame::Pong,
+                        Some(ClientFrame::Approval { .. }) => ServerFrame::error(
+                            "no_pending_approval",
+                            "No tool call is waiting for approval",
+                        ),
+                        Some(_) => ServerFrame::error(
+                            "turn_in_progress",
+                            "A turn is running; wait for it to end or send cancel",
+                        ),
+                    };
+                    if !self.send(reply).await {
+                        return TurnEnd::Disconnected;
+                    }
+                }
             }
         }
     }
 
-    info!("WebSocket connection closed");
+    /// Wait for the client to approve or deny tool call `id`, for up to
+    /// [`APPROVAL_TIMEOUT`].
+    async fn await_approval(
+        &mut self,
+        id: &str,
+        receiver: &mut SplitStream<WebSocket>,
+    ) -> Approval {
+        let answer = async {
+            loop {
+                let reply = match self.next_frame(receiver).await {
+                    None => return Approval::Disconnected,
+                    Some(ClientFrame::Approval {
+                        id: answered,
+                        approved,
+                    }) if answered == id => {
+                        return if approved {
+                            Approval::Approved
+                        } else {
+                            Approval::Denied
+                        };
+                    }
+                    Some(ClientFrame::Approval { .. }) => ServerFrame::error(
+                        "no_pending_approval",
+                        "No tool call with that id is waiting for approval",
+                    ),
+                    Some(ClientFrame::Cancel) => return Approval::Cancelled,
+                    Some(ClientFrame::Ping) => ServerFrame::Pong,
+                    Some(_) => ServerFrame::error(
+                        "approval_pending",
+                        "Answer the approval request or send cancel first",
+                    ),
+                };
+                if !self.send(reply).await {
+                    return Approval::Disconnected;
+                }
+            }
+        };
+        tokio::time::timeout(APPROVAL_TIMEOUT, answer)
+            .await
+            .unwrap_or(Approval::TimedOut)
+    }
 }
@@ -10,6 +10,7 @@ document.addEventListener('DOMContentLoaded', () => {
     setupEventListeners();
     showEmptyState();
     loadStatus();
+    loadWriteApprovals();
     startStatusPolling();
 });
 
@@ -59,6 +60,14 @@ function setupEventListeners() {
     document.getElementById('sessions-toggle').onclick = toggleSessionsPanel;
     document.getElementById('sessions-close').onclick = toggleSessionsPanel;
     document.getElementById('session-back').onclick = showSessionsList;
+
+    // Memory panel
+    document.getElementById('memory-toggle').onclick = toggleMemoryPanel;
+    document.getElementById('memory-close').onclick = toggleMemoryPanel;
+    document.getElementById('memory-search-form').onsubmit = (e) => {
+        e.preventDefault();
+        searchMemory();
+    };
 }
 
 function showEmptyState() {
@@ -176,23 +185,28 @@ async function loadSessionMessages(sessionId) {
             if (msg.role === 'user') {
                 appendMessage('user', msg.content || '');
             } else if (msg.role === 'assistant') {
-                const div = appendMessage('assistant', msg.content || '');
-
-                // Render tool calls if present
-                if (msg.tool_calls && msg.tool_calls.length > 0) {
-                    for (const tc of msg.tool_calls) {
-                        const toolDiv = document.createElement('div');
-                        toolDiv.className = 'message tool';
-                        toolDiv.innerHTML = `<span class="tool-name">[${tc.name}]</span>`;
-                        div.after(toolDiv);
-                    }
+                if (msg.content) {
+                    appendMessage('assistant', msg.content);
+                }
+
+                // Tool calls get cards that their results fill in
+                for (const tc of msg.tool_calls || []) {
+                    const card = createToolCard(tc);
+                    card.classList.replace('running', 'pending');
+                    card.querySelector('.tool-state').textContent = 'no result';
+                    document.getElementById('messages').appendChild(card);
                 }
             } else if (msg.role === 'toolResult') {
-                const toolDiv = document.createElement('div');
-                toolDiv.className = 'message tool';
-                const output = msg.content ? msg.content.slice(0, 300) : 'Done';
-                toolDiv.innerHTML = `<span class="tool-name">[result]</span><div class="tool-output">${escapeHtml(output)}</div>`;
-                document.getElementById('messages').appendChild(toolDiv);
+                const card = msg.tool_call_id && document.getElementById(`tool-${msg.tool_call_id}`);
+                if (card) {
+                    finishToolCard(card, { output: msg.content });
+                } else {
+                    const toolDiv = document.createElement('div');
+                    toolDiv.className = 'message tool';
+                    const output = msg.content ? msg.content.slice(0, 300) : 'Done';
+                    toolDiv.innerHTML = `<span class="tool-name">[result]</span><div class="tool-output">${escapeHtml(output)}</div>`;
+                    document.getElementById('messages').appendChild(toolDiv);
+                }
             }
         }
 
@@ -220,97 +234,283 @@ async function sendMessage() {
     }
 
     appendMessage('user', message);
-    const assistantDiv = appendMessage('assistant', '');
-    assistantDiv.classList.add('loading');
+    const turn = startTurn();
 
     const sendBtn = document.getElementById('send');
     sendBtn.disabled = true;
     isStreaming = true;
 
     try {
-        const res = await fetch(`${API}/chat/stream`, {
-            method: 'POST',
-            headers: { 'Content-Type': 'application/json' },
-            body: JSON.stringify({ message, session_id: sessionId })
-        });
-
-        if (!res.ok) {
-            throw new Error(`HTTP ${res.status}: ${res.statusText}`);
+        let ws = null;
+        try {
+            ws = await openSocket();
+        } catch (err) {
+            console.warn('WebSocket unavailable, streaming over SSE:', err);
         }
+        if (ws) {
+            await chatOverSocket(ws, message, turn);
+        } else {
+            await chatOverSse(message, turn);
+        }
+    } catch (err) {
+        handleEvent({ type: 'error', message: err.message }, turn);
+    } finally {
+        endTurn(turn);
+        sendBtn.disabled = false;
+        isStreaming = false;
+        scrollToBottom();
+    }
+}
 
-        const reader = res.body.getReader();
-        const decoder = new TextDecoder();
-        let buffer = '';
+// A reply being streamed: the bubble receiving text, and its markdown so far.
+// Tool calls split the reply, so text after one goes into a new bubble.
+function startTurn() {
+    const div = appendMessage('assistant', '');
+    div.classList.add('loading');
+    return { div, text: '', renderPending: false, finish: null };
+}
 
-        while (true) {
-            const { done, value } = await reader.read();
-            if (done) break;
+function endTurn(turn) {
+    turn.div.classList.remove('loading');
+    if (turn.text) {
+        renderTurn(turn, true);
+    } else if (!turn.div.classList.contains('error')) {
+        // Nothing was said, e.g. after the last tool call
+        turn.div.remove();
+    }
+}
 
-            buffer += decoder.decode(value, { stream: true });
-            const lines = buffer.split('\n');
-            buffer = lines.pop() || '';
+// Re-render the streamed markdown at most once per frame
+function renderTurn(turn, now = false) {
+    const render = () => {
+        turn.renderPending = false;
+        turn.div.innerHTML = renderMarkdown(turn.text);
+        scrollToBottom();
+    };
+    if (now) {
+        render();
+    } else if (!turn.renderPending) {
+        turn.renderPending = true;
+        requestAnimationFrame(render);
+    }
+}
 
-            for (const line of lines) {
-                if (!line.startsWith('data: ')) continue;
-                const data = line.slice(6);
-                if (data === '[DONE]') continue;
+// The websocket, once open, stays open across turns
+let socket = null;
+let socketSessionId = null;
+let pendingAttach = null;
+let currentTurn = null;
 
-                try {
-                    const event = JSON.parse(data);
-                    handleEvent(event, assistantDiv);
-                } catch (e) {
-                    // Ignore parse errors for partial data
-                }
+function openSocket() {
+    if (socket && socket.readyState === WebSocket.OPEN) {
+        return Promise.resolve(socket);
+    }
+    return new Promise((resolve, reject) => {
+        const scheme = location.protocol === 'https:' ? 'wss' : 'ws';
+        const ws = new WebSocket(`${scheme}://${location.host}${API}/ws`);
+        ws.onopen = () => {
+            socket = ws;
+            socketSessionId = null;
+            resolve(ws);
+        };
+        ws.onerror = () => reject(new Error('WebSocket connection failed'));
+        ws.onclose = () => {
+            if (socket !== ws) return;
+            socket = null;
+            socketSessionId = null;
+            if (pendingAttach) {
+                pendingAttach.reject(new Error('Connection closed'));
+                pendingAttach = null;
+            }
+            if (currentTurn) {
+                handleEvent({ type: 'error', message: 'Connection closed' }, currentTurn);
+                currentTurn.finish();
+            }
+        };
+        ws.onmessage = (msg) => {
+            try {
+                handleSocketFrame(JSON.parse(msg.data));
+            } catch (e) {
+                console.error('Bad websocket frame:', e);
+            }
+        };
+    });
+}
+
+// Attach the socket to the selected session, or a new one if none is
+function attachSocket(ws) {
+    if (socketSessionId && socketSessionId === sessionId) {
+        return Promise.resolve();
+    }
+    return new Promise((resolve, reject) => {
+        pendingAttach = { resolve, reject };
+        ws.send(JSON.stringify({ type: 'attach', session_id: sessionId || undefined }));
+    });
+}
+
+async function chatOverSocket(ws, message, turn) {
+    await attachSocket(ws);
+    await new Promise((resolve) => {
+        turn.finish = resolve;
+        currentTurn = turn;
+        ws.send(JSON.stringify({ type: 'chat', message }));
+    });
+    currentTurn = null;
+}
+
+function handleSocketFrame(frame) {
+    switch (frame.type) {
+        case 'attached':
+            socketSessionId = frame.session_id;
+            sessionId = frame.session_id;
+            updateSessionSelect(sessionId);
+            if (pendingAttach) {
+                pendingAttach.resolve();
+                pendingAttach = null;
+            }
+            break;
+
+        case 'error':
+            if (pendingAttach) {
+                pendingAttach.reject(new Error(frame.message));
+                pendingAttach = null;
+            } else if (currentTurn) {
+                handleEvent(frame, currentTurn);
+                currentTurn.finish();
+            }
+            break;
+
+        case 'done':
+        case 'cancelled':
+            if (currentTurn) {
+                handleEvent(frame, currentTurn);
+                currentTurn.finish();
+            }
+            break;
+
+        default:
+            if (currentTurn) {
+                handleEvent(frame, currentTurn);
+            }
+    }
+}
+
+async function chatOverSse(message, turn) {
+    const res = await fetch(`${API}/chat/stream`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ message, session_id: sessionId })
+    });
+
+    if (!res.ok) {
+        throw new Error(`HTTP ${res.status}: ${res.statusText}`);
+    }
+
+    const reader = res.body.getReader();
+    const decoder = new TextDecoder();
+    let buffer = '';
+
+    while (true) {
+        const { done, value } = await reader.read();
+        if (done) break;
+
+        buffer += decoder.decode(value, { stream: true });
+        const lines = buffer.split('\n');
+        buffer = lines.pop() || '';
+
+        for (const line of lines) {
+            if (!line.startsWith('data: ')) continue;
+            const data = line.slice(6);
+            if (data === '[DONE]') continue;
+
+            try {
+                const event = JSON.parse(data);
+                handleEvent(event, turn);
+            } catch (e) {
+                // Ignore parse errors for partial data
             }
         }
-    } catch (err) {
-        assistantDiv.classList.remove('loading');
-        assistantDiv.classList.add('error');
-        assistantDiv.textContent = `Error: ${err.message}`;
-    } finally {
-        assistantDiv.classList.remove('loading');
-        sendBtn.disabled = false;
-        isStreaming = false;
-        scrollToBottom();
     }
 }
 
-function handleEvent(event, assistantDiv) {
+// Handle a streamed event; the websocket and SSE streams share these types
+function handleEvent(event, turn) {
     switch (event.type) {
         case 'session':
             sessionId = event.session_id;
             updateSessionSelect(sessionId);
             break;
 
+        case 'queued':
+            if (!turn.text) {
+                turn.div.textContent = `Waiting for the model (position ${event.position})...`;
+            }
+            break;
+
         case 'content':
-            assistantDiv.textContent += event.delta;
+            turn.div.classList.remove('loading');
+            turn.text += event.delta;
+            renderTurn(turn);
+            break;
+
+        case 'approval_request': {
+            const card = placeToolCard(createToolCard(event), turn);
+            card.classList.replace('running', 'awaiting');
+            card.open = true;
+            card.querySelector('.tool-state').textContent = 'needs approval';
+            const prompt = document.createElement('div');
+            prompt.className = 'tool-approval';
+            prompt.innerHTML = `
+                <span>Run this tool?</span>
+                <button class="approve">Approve</button>
+                <button class="deny">Deny</button>
+            `;
+            const answer = (approved) => {
+                socket.send(JSON.stringify({ type: 'approval', id: event.id, approved }));
+                card.classList.replace('awaiting', approved ? 'running' : 'denied');
+                card.querySelector('.tool-state').textContent = approved ? 'running' : 'denied';
+                prompt.remove();
+            };
+            prompt.querySelector('.approve').onclick = () => answer(true);
+            prompt.querySelector('.deny').onclick = () => answer(false);
+            card.appendChild(prompt);
+            prompt.querySelector('.approve').focus();
             scrollToBottom();
             break;
+        }
 
         case 'tool_start':
-            const toolStartDiv = document.createElement('div');
-            toolStartDiv.className = 'message tool';
-            toolStartDiv.id = `tool-${event.id}`;
-            const toolLabel = event.detail
-                ? `[${event.name}: ${escapeHtml(event.detail)}]`
-                : `[${event.name}]`;
-            toolStartDiv.innerHTML = `<span class="tool-name">${toolLabel}</span> Running...`;
-            assistantDiv.after(toolStartDiv);
+            // An approved call already has its card
+            if (!document.getElementById(`tool-${event.id}`)) {
+                placeToolCard(createToolCard(event), turn);
+            }
             scrollToBottom();
             break;
 
-        case 'tool_end':
-            const toolEl = document.getElementById(`tool-${event.id}`);
-            if (toolEl) {
-                const output = event.output ? event.output.slice(0, 300) : 'Done';
-                toolEl.innerHTML = `<span class="tool-name">[${event.name}]</span><div class="tool-output">${escapeHtml(output)}</div>`;
+        case 'tool_end': {
+            const card = document.getElementById(`tool-${event.id}`);
+            if (card) {
+                finishToolCard(card, event);
             }
             scrollToBottom();
             break;
+        }
+
+        case 'cancelled':
+            // A tool left waiting for approval won't run now
+            for (const card of document.querySelectorAll('.tool-block.awaiting')) {
+                card.classList.replace('awaiting', 'denied');
+                card.querySelector('.tool-state').textContent = 'cancelled';
+                card.querySelector('.tool-approval')?.remove();
+            }
+            appendSystemMessage(`Cancelled: ${event.reason}`);
+            break;
 
         case 'error':
-            assistantDiv.classList.add('error');
-            assistantDiv.textContent = `Error: ${event.message}`;
+            turn.div.classList.remove('loading');
+            turn.div.classList.add('error');
+            turn.text = '';
+            turn.div.textContent = `Error: ${event.message}`;
             break;
 
         case 'done':
@@ -318,6 +518,68 @@ function handleEvent(event, assistantDiv) {
     }
 }
 
+// Tool calls show as expandable cards: name and detail in the summary,
+// arguments and output inside, and the duration once the call finishes
+function createToolCard({ id, name, detail, arguments: args }) {
+    const card = document.createElement('details');
+    card.className = 'tool-block running';
+    if (id) {
+        card.id = `tool-${id}`;
+    }
+    card.innerHTML = `
+        <summary>
+            <span class="tool-name">${escapeHtml(name)}</span>
+            ${detail ? `<span class="tool-detail">${escapeHtml(detail)}</span>` : ''}
+            <span class="tool-state">running</span>
+        </summary>
+        <div class="tool-section">Arguments</div>
+        <pre class="tool-arguments">${escapeHtml(formatToolArguments(args))}</pre>
+        <div class="tool-section">Output</div>
+        <pre class="tool-output"></pre>
+    `;
+    return card;
+}
+
+function finishToolCard(card, { output, warnings, duration_ms }) {
+    card.classList.remove('running', 'awaiting', 'pending');
+    card.classList.add('done');
+    const warned = warnings && warnings.length > 0;
+    const took = duration_ms != null ? ` in ${formatDuration(duration_ms)}` : '';
+    card.querySelector('.tool-state').textContent = (warned ? 'done with warnings' : 'done') + took;
+    const warningText = warned ? `\n\n${warnings.join('\n')}` : '';
+    card.querySelector('.tool-output').textContent = (output || 'No output') + warningText;
+}
+
+// Put a new tool card after the text streamed so far; text that comes
+// after the tool call goes into a new bubble below it
+function placeToolCard(card, turn) {
+    if (turn.text) {
+        endTurn(turn);
+        turn.div.after(card);
+        turn.div = appendMessage('assistant', '');
+        turn.div.classList.add('loading');
+        turn.text = '';
+    } else {
+        turn.div.before(card);
+    }
+    return card;
+}
+
+function formatToolArguments(args) {
+    if (!args) {
+        return '{}';
+    }
+    try {
+        return JSON.stringify(JSON.parse(args), null, 2);
+    } catch {
+        return args;
+    }
+}
+
+function formatDuration(ms) {
+    return ms < 1000 ? `${ms} ms` : `${(ms / 1000).toFixed(1)} s`;
+}
+
 function updateSessionSelect(newSessionId) {
     const select = document.getElementById('session-select');
 
@@ -348,7 +610,12 @@ function updateSessionSelect(newSessionId) {
 function appendMessage(role, content) {
     const div = document.createElement('div');
     div.className = `message ${role}`;
-    div.textContent = content;
+    if (role === 'assistant') {
+        div.classList.add('markdown');
+        div.innerHTML = renderMarkdown(content);
+    } else {
+        div.textContent = content;
+    }
     document.getElementById('messages').appendChild(div);
     scrollToBottom();
     return div;
@@ -365,6 +632,10 @@ function escapeHtml(text) {
     return div.innerHTML;
 }
 
+function escapeAttr(text) {
+    return escapeHtml(text).replace(/"/g, '&quot;');
+}
+
 // Slash command handling
 function handleSlashCommand(input) {
     const parts = input.split(/\s+/);
@@ -452,18 +723,96 @@ function toggleStatusPanel() {
 function startStatusPolling() {
     // Poll status every 30 seconds
     statusPollInterval = setInterval(loadStatus, 30000);
+    subscribeToEvents();
+}
+
+function subscribeToEvents() {
+    // Refresh right away when sessions, cron jobs, bridges, or approvals change
+    if (!window.EventSource) return;
+    const events = new EventSource(`${API}/events?types=session.*,cron.finished,bridge.*,approval.*`);
+    for (const name of ['session.created', 'session.completed']) {
+        events.addEventListener(name, () => {
+            loadSessions();
+            loadStatus();
+        });
+    }
+    for (const name of ['cron.finished', 'bridge.connected', 'bridge.disconnected', 'bridge.health']) {
+        events.addEventListener(name, loadStatus);
+    }
+    for (const name of ['approval.requested', 'approval.resolved']) {
+        events.addEventListener(name, loadWriteApprovals);
+    }
+}
+
+// Agent writes to protected files (LocalGPT.md, IDENTITY.md) wait here for
+// the user; answering needs an admin key
+async function loadWriteApprovals() {
+    try {
+        const res = await fetch(`${API}/approvals`);
+        if (!res.ok) return;
+        renderWriteApprovals(await res.json());
+    } catch (err) {
+        console.error('Failed to load approvals:', err);
+    }
+}
+
+function renderWriteApprovals(writes) {
+    const container = document.getElementById('write-approvals');
+    container.innerHTML = '';
+    for (const write of writes) {
+        const card = document.createElement('div');
+        card.className = 'write-approval';
+        card.innerHTML = `
+            <div class="write-approval-title">
+                <strong>${escapeHtml(write.tool)}</strong> wants to change
+                <code>${escapeHtml(write.path)}</code> (${escapeHtml(write.channel)})
+            </div>
+            <pre>${escapeHtml(write.preview)}</pre>
+            <div class="tool-approval">
+                <span>Allow this write?</span>
+                <button class="approve">Approve</button>
+                <button class="deny">Deny</button>
+            </div>
+        `;
+        const answer = async (approved) => {
+            card.remove();
+            try {
+                const res = await fetch(`${API}/approvals/${encodeURIComponent(write.id)}`, {
+                    method: 'POST',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify({ approved })
+                });
+                if (!res.ok) {
+                    appendSystemMessage(`Approval failed: ${await res.text() || res.statusText}`);
+                }
+            } catch (err) {
+                appendSystemMessage(`Approval failed: ${err.message}`);
+            }
+            loadWriteApprovals();
+        };
+        card.querySelector('.approve').onclick = () => answer(true);
+        card.querySelector('.deny').onclick = () => answer(false);
+        container.appendChild(card);
+    }
 }
 
 async function loadStatus() {
     try {
-        // Fetch both status and heartbeat in parallel
-        const [statusRes, heartbeatRes] = await Promise.all([
+        // Admin status includes the heartbeat; keys without admin scope get 403
+        const [statusRes, adminRes] = await Promise.all([
             fetch(`${API}/status`),
-            fetch(`${API}/heartbeat/status`)
+            fetch(`${API}/admin/status`)
         ]);
 
         const status = await statusRes.json();
-        const heartbeat = await heartbeatRes.json();
+        let heartbeat;
+        if (adminRes.ok) {
+            const admin = await adminRes.json();
+            heartbeat = admin.scheduler.heartbeat;
+            updateDaemonSection(admin);
+        } else {
+            heartbeat = await (await fetch(`${API}/heartbeat/status`)).json();
+        }
 
         updateStatusPanel(status, heartbeat);
         
@@ -531,6 +880,30 @@ function updateStatusPanel(status, heartbeat) {
     }
 }
 
+function updateDaemonSection(admin) {
+    document.getElementById('daemon-section').style.display = 'block';
+    document.getElementById('daemon-uptime').textContent =
+        formatAge(admin.uptime_secs).replace(' ago', '');
+    document.getElementById('daemon-providers').textContent =
+        admin.providers.length ? admin.providers.join(', ') : 'none';
+
+    const connected = admin.mcp_servers.filter(s => s.state === 'connected').length;
+    document.getElementById('daemon-mcp').textContent =
+        `${connected}/${admin.mcp_servers.length} connected`;
+
+    const healthy = admin.bridges.filter(b => b.health === 'healthy').length;
+    document.getElementById('daemon-bridges').textContent =
+        `${healthy}/${admin.bridges.length} healthy`;
+
+    const cron = admin.scheduler;
+    document.getElementById('daemon-cron').textContent =
+        `${cron.cron_enabled} enabled, ${cron.cron_paused} paused`;
+
+    document.getElementById('daemon-memory').textContent = admin.memory
+        ? `${admin.memory.total_files} files, ${admin.memory.total_chunks} chunks`
+        : 'unavailable';
+}
+
 function formatHeartbeatStatus(status) {
     const labels = {
         'ok': 'OK',
@@ -616,16 +989,83 @@ async function loadSavedSessions() {
 
         listEl.innerHTML = data.sessions.map(s => `
             <div class="session-item" onclick="viewSession('${s.id}')">
-                <div class="session-item-id">${s.id.slice(0, 16)}...</div>
+                <div class="session-item-title">${s.title ? escapeHtml(s.title) : `<span class="session-item-id">${s.id.slice(0, 16)}...</span>`}</div>
                 <div class="session-item-meta">${s.created_at} \u2022 ${s.message_count} messages</div>
+                <div class="session-item-actions">
+                    <button onclick="event.stopPropagation(); resumeSavedSession('${s.id}')">Resume</button>
+                    <button onclick="event.stopPropagation(); renameSavedSession('${s.id}')">Rename</button>
+                    <button onclick="event.stopPropagation(); deleteSavedSession('${s.id}')">Delete</button>
+                </div>
             </div>
         `).join('');
+        savedSessionTitles = Object.fromEntries(data.sessions.map(s => [s.id, s.title || '']));
     } catch (err) {
         console.error('Failed to load saved sessions:', err);
         document.getElementById('sessions-list').innerHTML = `<div class="session-item error">Error: ${err.message}</div>`;
     }
 }
 
+// Titles of the listed saved sessions, to prefill the rename prompt
+let savedSessionTitles = {};
+
+async function resumeSavedSession(id) {
+    try {
+        const res = await fetch(`${API}/sessions/${id}/resume`, { method: 'POST' });
+        if (!res.ok) {
+            throw new Error(await res.text() || res.statusText);
+        }
+        const data = await res.json();
+        sessionId = data.session_id;
+        updateSessionSelect(sessionId);
+        await loadSessionMessages(sessionId);
+        toggleSessionsPanel();
+    } catch (err) {
+        console.error('Failed to resume session:', err);
+        appendSystemMessage(`Failed to resume session: ${err.message}`);
+    }
+}
+
+async function renameSavedSession(id) {
+    const title = prompt('Session title (leave empty to clear):', savedSessionTitles[id] || '');
+    if (title === null) {
+        return;
+    }
+    try {
+        const res = await fetch(`${API}/saved-sessions/${id}`, {
+            method: 'PATCH',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({ title })
+        });
+        if (!res.ok) {
+            throw new Error(await res.text() || res.statusText);
+        }
+        await loadSavedSessions();
+    } catch (err) {
+        console.error('Failed to rename session:', err);
+        alert(`Failed to rename session: ${err.message}`);
+    }
+}
+
+async function deleteSavedSession(id) {
+    if (!confirm('Delete this session? This cannot be undone.')) {
+        return;
+    }
+    try {
+        const res = await fetch(`${API}/sessions/${id}`, { method: 'DELETE' });
+        if (!res.ok) {
+            throw new Error(await res.text() || res.statusText);
+        }
+        if (sessionId === id) {
+            newSession();
+        }
+        await loadSavedSessions();
+        loadSessions();
+    } catch (err) {
+        console.error('Failed to delete session:', err);
+        alert(`Failed to delete session: ${err.message}`);
+    }
+}
+
 async function viewSession(sessionId) {
     try {
         const res = await fetch(`${API}/saved-sessions/${sessionId}`);
@@ -649,10 +1089,10 @@ function renderSessionMessage(msg) {
     const roleClass = msg.role === 'user' ? 'user' :
                       msg.role === 'toolResult' ? 'tool' : 'assistant';
 
-    let html = `<div class="message ${roleClass}">`;
+    let html = `<div class="message ${roleClass}${roleClass === 'assistant' ? ' markdown' : ''}">`;
 
     if (msg.content) {
-        html += escapeHtml(msg.content);
+        html += roleClass === 'assistant' ? renderMarkdown(msg.content) : escapeHtml(msg.content);
     }
 
     // Render tool calls
@@ -693,3 +1133,146 @@ function showSessionsList() {
     listEl.style.display = 'block';
     viewerEl.classList.add('hidden');
 }
+
+// Memory explorer: indexed files, their chunks, search, and chunk marks
+function toggleMemoryPanel() {
+    const panel = document.getElementById('memory-panel');
+    panel.classList.toggle('hidden');
+    if (!panel.classList.contains('hidden')) {
+        loadMemoryFiles();
+    }
+}
+
+async function loadMemoryFiles() {
+    const body = document.getElementById('memory-body');
+    try {
+        const res = await fetch(`${API}/memory/files`);
+        if (!res.ok) {
+            throw new Error(await res.text() || res.statusText);
+        }
+        const data = await res.json();
+        if (data.files.length === 0) {
+            body.innerHTML = '<div class="session-item"><em>Nothing indexed yet</em></div>';
+            return;
+        }
+        body.innerHTML = data.files.map(f => `
+            <div class="session-item" data-path="${escapeAttr(f.path)}">
+                <div class="session-item-title">${escapeHtml(f.path)}</div>
+                <div class="session-item-meta">${f.chunks} chunk${f.chunks === 1 ? '' : 's'}</div>
+            </div>
+        `).join('');
+        for (const item of body.querySelectorAll('[data-path]')) {
+            item.onclick = () => openMemoryFile(item.dataset.path);
+        }
+    } catch (err) {
+        console.error('Failed to load memory files:', err);
+        body.innerHTML = `<div class="session-item error">Error: ${escapeHtml(err.message)}</div>`;
+    }
+}
+
+async function openMemoryFile(path, focusLine) {
+    const body = document.getElementById('memory-body');
+    try {
+        const res = await fetch(`${API}/memory/files/${encodeURIComponent(path)}`);
+        if (!res.ok) {
+            throw new Error(await res.text() || res.statusText);
+        }
+        const data = await res.json();
+        body.innerHTML = `
+            <button class="memory-back">&larr; Files</button>
+            <div class="memory-file-path">${escapeHtml(data.path)}</div>
+            ${data.chunks.map(renderMemoryChunk).join('') || '<div class="session-item"><em>No chunks</em></div>'}
+        `;
+        body.querySelector('.memory-back').onclick = loadMemoryFiles;
+        bindChunkActions(body, () => openMemoryFile(path));
+
+        if (focusLine) {
+            const chunk = data.chunks.find(c => c.line_start <= focusLine && focusLine <= c.line_end);
+            const el = chunk && body.querySelector(`[data-chunk="${chunk.id}"]`);
+            if (el) {
+                el.classList.add('focused');
+                el.scrollIntoView({ block: 'center' });
+            }
+        }
+    } catch (err) {
+        console.error('Failed to open memory file:', err);
+        body.innerHTML = `<div class="session-item error">Error: ${escapeHtml(err.message)}</div>`;
+    }
+}
+
+function renderMemoryChunk(chunk) {
+    const mark = chunk.mark ? `<span class="memory-mark ${chunk.mark}">${chunk.mark}</span>` : '';
+    const actions = [
+        chunk.mark !== 'pinned' ? `<button data-mark="pinned">Pin</button>` : '',
+        chunk.mark !== 'archived' ? `<button data-mark="archived">Archive</button>` : '',
+        chunk.mark ? `<button data-mark="">Unmark</button>` : '',
+    ].join('');
+    return `
+        <div class="memory-chunk ${chunk.mark || ''}" data-chunk="${chunk.id}">
+            <div class="memory-chunk-header">
+                <span>Lines ${chunk.line_start}&ndash;${chunk.line_end}</span>
+                ${mark}
+                <span class="memory-chunk-actions">${actions}</span>
+            </div>
+            <pre>${escapeHtml(chunk.content)}</pre>
+        </div>
+    `;
+}
+
+function bindChunkActions(container, refresh) {
+    for (const button of container.querySelectorAll('[data-mark]')) {
+        button.onclick = async () => {
+            const id = button.closest('[data-chunk]').dataset.chunk;
+            try {
+                const res = await fetch(`${API}/memory/chunks/${id}`, {
+                    method: 'PATCH',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify({ mark: button.dataset.mark || null })
+                });
+                if (!res.ok) {
+                    throw new Error(await res.text() || res.statusText);
+                }
+                refresh();
+            } catch (err) {
+                console.error('Failed to mark chunk:', err);
+                alert(`Failed to update chunk: ${err.message}`);
+            }
+        };
+    }
+}
+
+async function searchMemory() {
+    const query = document.getElementById('memory-query').value.trim();
+    if (!query) {
+        loadMemoryFiles();
+        return;
+    }
+    const body = document.getElementById('memory-body');
+    try {
+        const res = await fetch(`${API}/memory/search?q=${encodeURIComponent(query)}&limit=20`);
+        if (!res.ok) {
+            throw new Error(await res.text() || res.statusText);
+        }
+        const data = await res.json();
+        if (data.results.length === 0) {
+            body.innerHTML = '<div class="session-item"><em>No matches</em></div>';
+            return;
+        }
+        body.innerHTML = data.results.map(r => `
+            <div class="memory-chunk ${r.pinned ? 'pinned' : ''}" data-path="${escapeAttr(r.file)}" data-line="${r.line_start}">
+                <div class="memory-chunk-header">
+                    <span>${escapeHtml(r.file)}:${r.line_start}&ndash;${r.line_end}</span>
+                    ${r.pinned ? '<span class="memory-mark pinned">pinned</span>' : ''}
+                    <span class="memory-score">${r.score.toFixed(3)}</span>
+                </div>
+                <pre>${escapeHtml(r.content)}</pre>
+            </div>
+        `).join('');
+        for (const item of body.querySelectorAll('[data-path]')) {
+            item.onclick = () => openMemoryFile(item.dataset.path, Number(item.dataset.line));
+        }
+    } catch (err) {
+        console.error('Memory search failed:', err);
+        body.innerHTML = `<div class="session-item error">Error: ${escapeHtml(err.message)}</div>`;
+    }
+}
@@ -0,0 +1,160 @@
+// Minimal markdown renderer for agent replies.
+//
+// Everything is escaped before any markup is added, so model output can't
+// inject HTML. Handles what replies commonly use: fenced code, headings,
+// lists, block quotes, tables, rules, and inline code, emphasis, and links.
+// An unclosed code fence renders as code so partially streamed replies
+// don't flicker between layouts.
+
+function renderMarkdown(text) {
+    const lines = text.replace(/\r\n?/g, '\n').split('\n');
+    const out = [];
+    let i = 0;
+
+    while (i < lines.length) {
+        const line = lines[i];
+
+        // Fenced code block
+        const fence = line.match(/^\s*(```|~~~)\s*([\w+-]*)/);
+        if (fence) {
+            const body = [];
+            i++;
+            while (i < lines.length && !lines[i].trim().startsWith(fence[1])) {
+                body.push(lines[i]);
+                i++;
+            }
+            i++; // closing fence
+            const lang = fence[2] ? ` data-lang="${escapeMarkdownHtml(fence[2])}"` : '';
+            out.push(`<pre class="md-code"${lang}><code>${escapeMarkdownHtml(body.join('\n'))}</code></pre>`);
+            continue;
+        }
+
+        if (!line.trim()) {
+            i++;
+            continue;
+        }
+
+        const heading = line.match(/^(#{1,6})\s+(.*?)\s*#*\s*$/);
+        if (heading) {
+            const level = heading[1].length;
+            out.push(`<h${level}>${renderInline(heading[2])}</h${level}>`);
+            i++;
+            continue;
+        }
+
+        if (/^\s*([-*_])(\s*\1){2,}\s*$/.test(line)) {
+            out.push('<hr>');
+            i++;
+            continue;
+        }
+
+        // Table: header row, then a |---|---| separator
+        if (line.includes('|') && i + 1 < lines.length && isTableSeparator(lines[i + 1])) {
+            const header = splitTableRow(line);
+            const rows = [];
+            i += 2;
+            while (i < lines.length && lines[i].includes('|') && lines[i].trim()) {
+                rows.push(splitTableRow(lines[i]));
+                i++;
+            }
+            const head = header.map(cell => `<th>${renderInline(cell)}</th>`).join('');
+            const body = rows
+                .map(row => `<tr>${header.map((_, c) => `<td>${renderInline(row[c] || '')}</td>`).join('')}</tr>`)
+                .join('');
+            out.push(`<table><thead><tr>${head}</tr></thead><tbody>${body}</tbody></table>`);
+            continue;
+        }
+
+        if (/^\s*>/.test(line)) {
+            const quoted = [];
+            while (i < lines.length && /^\s*>/.test(lines[i])) {
+                quoted.push(lines[i].replace(/^\s*>\s?/, ''));
+                i++;
+            }
+            out.push(`<blockquote>${renderMarkdown(quoted.join('\n'))}</blockquote>`);
+            continue;
+        }
+
+        const listItem = /^\s*([-*+]|\d+[.)])\s+/;
+        if (listItem.test(line)) {
+            const ordered = /^\s*\d/.test(line);
+            const items = [];
+            while (i < lines.length && (listItem.test(lines[i]) || (/^\s{2,}\S/.test(lines[i]) && items.length))) {
+                if (listItem.test(lines[i])) {
+                    items.push(lines[i].replace(listItem, ''));
+                } else {
+                    items[items.length - 1] += ' ' + lines[i].trim();
+                }
+                i++;
+            }
+            const tag = ordered ? 'ol' : 'ul';
+            out.push(`<${tag}>${items.map(item => `<li>${renderInline(item)}</li>`).join('')}</${tag}>`);
+            continue;
+        }
+
+        // Paragraph: runs until a blank line or another block starts
+        const para = [];
+        while (i < lines.length && lines[i].trim() && !startsBlock(lines, i)) {
+            para.push(lines[i]);
+            i++;
+        }
+        if (para.length === 0) {
+            para.push(lines[i]);
+            i++;
+        }
+        out.push(`<p>${para.map(renderInline).join('<br>')}</p>`);
+    }
+
+    return out.join('');
+}
+
+function startsBlock(lines, i) {
+    const line = lines[i];
+    return /^\s*(```|~~~)/.test(line)
+        || /^#{1,6}\s/.test(line)
+        || /^\s*>/.test(line)
+        || /^\s*([-*+]|\d+[.)])\s+/.test(line)
+        || (line.includes('|') && i + 1 < lines.length && isTableSeparator(lines[i + 1]));
+}
+
+function isTableSeparator(line) {
+    return /^\s*\|?\s*:?-{3,}:?\s*(\|\s*:?-{3,}:?\s*)*\|?\s*$/.test(line);
+}
+
+function splitTableRow(line) {
+    return line.trim().replace(/^\|/, '').replace(/\|$/, '').split('|').map(cell => cell.trim());
+}
+
+function renderInline(text) {
+    // Pull out code spans first so their contents stay literal
+    const codes = [];
+    let html = escapeMarkdownHtml(text).replace(/`([^`]+)`/g, (_, code) => {
+        codes.push(code);
+        return `\u0000${codes.length - 1}\u0000`;
+    });
+
+    html = html
+        .replace(/\[([^\]]+)\]\(([^)\s]+)\)/g, (match, label, url) =>
+            isSafeUrl(url) ? `<a href="${url}" target="_blank" rel="noopener noreferrer">${label}</a>` : match)
+        .replace(/(^|[\s(])(https?:\/\/[^\s<)]*[^\s<).,;:!?])/g,
+            '$1<a href="$2" target="_blank" rel="noopener noreferrer">$2</a>')
+        .replace(/\*\*([^*]+)\*\*/g, '<strong>$1</strong>')
+        .replace(/__([^_]+)__/g, '<strong>$1</strong>')
+        .replace(/(^|[^*])\*([^*\s][^*]*)\*/g, '$1<em>$2</em>')
+        .replace(/(^|[^\w])_([^_\s][^_]*)_(?!\w)/g, '$1<em>$2</em>')
+        .replace(/~~([^~]+)~~/g, '<del>$1</del>');
+
+    return html.replace(/\u0000(\d+)\u0000/g, (_, n) => `<code>${codes[n]}</code>`);
+}
+
+function isSafeUrl(url) {
+    return /^(https?:|mailto:|\/|#)/i.test(url.replace(/&amp;/g, '&'));
+}
+
+function escapeMarkdownHtml(text) {
+    return text
+        .replace(/&/g, '&amp;')
+        .replace(/</g, '&lt;')
+        .replace(/>/g, '&gt;')
+        .replace(/"/g, '&quot;');
+}